@@ -0,0 +1,105 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, xdr::ToXdr, Address, BytesN, Env, Symbol, Vec, log};
+
+#[path = "../src/merkle.rs"]
+mod merkle;
+
+// Claimed flags are packed 128 to a storage entry
+const BITMAP_WORD_BITS: u32 = 128;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AirdropData {
+    pub governance: Address, // Publishes distribution roots
+    pub pi_token: Address, // PI paid out from this contract's balance
+    pub root: Option<BytesN<32>>, // Current distribution root
+    pub epoch: u32, // Bumped per published root; claimed bitmaps are per epoch
+}
+
+// Merkle leaf of a distribution: sha256 of this struct's XDR
+#[contracttype]
+#[derive(Clone)]
+pub struct AirdropLeaf {
+    pub index: u32,
+    pub claimant: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AirdropKey {
+    Claimed(u32, u32), // (Epoch, word) -> Bitmap of claimed indexes
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AirdropError {
+    NoRoot = 1,
+    InvalidProof = 2,
+    AlreadyClaimed = 3,
+}
+
+#[contract]
+pub struct PiCoinAirdrop;
+
+#[contractimpl]
+impl PiCoinAirdrop {
+    // Constructor: airdrop paying PI held by this contract against governance-published roots
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address) {
+        let data = AirdropData { governance, pi_token, root: None, epoch: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "airdrop_data"), &data);
+        log!(&env, "Airdrop initialized: migrating Pi balances on-chain");
+    }
+
+    // Governance-only: Publish the Merkle root of (index, claimant, amount) entries for a
+    // new distribution. Returns its epoch; claims are tracked per epoch.
+    pub fn publish_root(env: Env, root: BytesN<32>) -> u32 {
+        let mut data: AirdropData = env.storage().instance().get(&Symbol::new(&env, "airdrop_data")).unwrap();
+        data.governance.require_auth();
+        data.root = Some(root);
+        data.epoch += 1;
+        env.storage().instance().set(&Symbol::new(&env, "airdrop_data"), &data);
+        log!(&env, "Airdrop root published for epoch {}", data.epoch);
+        data.epoch
+    }
+
+    // Claim `amount` PI for entry `index` of the current distribution, once
+    pub fn claim(env: Env, claimant: Address, index: u32, amount: i128, proof: Vec<BytesN<32>>) -> Result<(), AirdropError> {
+        claimant.require_auth();
+        let data: AirdropData = env.storage().instance().get(&Symbol::new(&env, "airdrop_data")).unwrap();
+        let root = data.root.ok_or(AirdropError::NoRoot)?;
+
+        let key = AirdropKey::Claimed(data.epoch, index / BITMAP_WORD_BITS);
+        let bit = 1u128 << (index % BITMAP_WORD_BITS);
+        let word: u128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if word & bit != 0 {
+            return Err(AirdropError::AlreadyClaimed);
+        }
+
+        let leaf = AirdropLeaf { index, claimant: claimant.clone(), amount };
+        if !merkle::verify(&env, &root, &leaf.to_xdr(&env), &proof) {
+            return Err(AirdropError::InvalidProof);
+        }
+
+        env.storage().persistent().set(&key, &(word | bit));
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &claimant, &amount);
+        env.events().publish((Symbol::new(&env, "claimed"), claimant, data.epoch), (index, amount));
+        Ok(())
+    }
+
+    // View: Whether entry `index` of the current distribution has been claimed
+    pub fn is_claimed(env: Env, index: u32) -> bool {
+        let data: AirdropData = env.storage().instance().get(&Symbol::new(&env, "airdrop_data")).unwrap();
+        let word: u128 = env.storage().persistent().get(&AirdropKey::Claimed(data.epoch, index / BITMAP_WORD_BITS)).unwrap_or(0);
+        word & (1u128 << (index % BITMAP_WORD_BITS)) != 0
+    }
+
+    // View: Current distribution root and its epoch
+    pub fn root(env: Env) -> (Option<BytesN<32>>, u32) {
+        let data: AirdropData = env.storage().instance().get(&Symbol::new(&env, "airdrop_data")).unwrap();
+        (data.root, data.epoch)
+    }
+}
+
+mod test;
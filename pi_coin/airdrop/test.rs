@@ -0,0 +1,78 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::Address as _, vec, xdr::ToXdr, Address, BytesN, Env, Vec};
+use crate::{merkle, AirdropError, AirdropLeaf, PiCoinAirdrop, PiCoinAirdropClient};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+// Two-leaf distribution: alice's leaf, its proof and the root
+fn distribution(env: &Env, alice: &Address, bob: &Address) -> (AirdropLeaf, Vec<BytesN<32>>, BytesN<32>) {
+    let alice_leaf = AirdropLeaf { index: 0, claimant: alice.clone(), amount: 300 };
+    let bob_leaf = AirdropLeaf { index: 1, claimant: bob.clone(), amount: 700 };
+    let alice_proof = vec![env, BytesN::from(env.crypto().sha256(&bob_leaf.to_xdr(env)))];
+    let root = merkle::root_from(env, &alice_leaf.clone().to_xdr(env), &alice_proof);
+    (alice_leaf, alice_proof, root)
+}
+
+#[test]
+fn test_claim_once_with_valid_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinAirdrop, (&governance, &pi.address));
+    let client = PiCoinAirdropClient::new(&env, &contract_id);
+    pi.mint(&contract_id, &1_000, &PiCoinSource::Rewards);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let (_, alice_proof, root) = distribution(&env, &alice, &bob);
+
+    assert_eq!(client.try_claim(&alice, &0, &300, &alice_proof), Err(Ok(AirdropError::NoRoot)));
+    assert_eq!(client.publish_root(&root), 1);
+
+    // Inflated amounts and stolen proofs do not verify
+    assert_eq!(client.try_claim(&alice, &0, &3_000, &alice_proof), Err(Ok(AirdropError::InvalidProof)));
+    assert_eq!(client.try_claim(&bob, &0, &300, &alice_proof), Err(Ok(AirdropError::InvalidProof)));
+
+    client.claim(&alice, &0, &300, &alice_proof);
+    assert!(client.is_claimed(&0));
+    assert!(!client.is_claimed(&1));
+    assert_eq!(pi.balance(&alice), 300);
+
+    // Second claim of the same leaf is refused
+    assert_eq!(client.try_claim(&alice, &0, &300, &alice_proof), Err(Ok(AirdropError::AlreadyClaimed)));
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_superseded_claims() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinAirdrop, (&governance, &pi.address));
+    let client = PiCoinAirdropClient::new(&env, &contract_id);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let (_, alice_proof, root) = distribution(&env, &alice, &bob);
+
+    // Only governance publishes, and only the claimant claims
+    env.set_auths(&[]);
+    assert!(client.try_publish_root(&root).is_err());
+    env.mock_all_auths();
+    client.publish_root(&root);
+    env.set_auths(&[]);
+    assert!(client.try_claim(&alice, &0, &300, &alice_proof).is_err());
+    env.mock_all_auths();
+
+    // An unfunded airdrop cannot pay, and the leaf stays claimable
+    pi.mint(&contract_id, &100, &PiCoinSource::Rewards);
+    assert!(client.try_claim(&alice, &0, &300, &alice_proof).is_err());
+    assert!(!client.is_claimed(&0));
+
+    // A new root supersedes the old distribution and its proofs
+    client.publish_root(&BytesN::from_array(&env, &[9; 32]));
+    pi.mint(&contract_id, &1_000, &PiCoinSource::Rewards);
+    assert_eq!(client.try_claim(&alice, &0, &300, &alice_proof), Err(Ok(AirdropError::InvalidProof)));
+    assert_eq!(pi.balance(&alice), 0);
+}
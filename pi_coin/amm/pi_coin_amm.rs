@@ -0,0 +1,271 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+// Swap fee: 30 bps of the input stays in the pool for liquidity providers
+pub const FEE_BPS: i128 = 30;
+const BPS: i128 = 10_000;
+// Pool price scale: micro-units, matching the oracle feed (assumes PI and the quote
+// token use the same decimals)
+pub const PRICE_SCALE: i128 = 1_000_000;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AmmData {
+    pub pi_token: Address,
+    pub quote_token: Address, // e.g. USDC
+    pub reserve_pi: i128,
+    pub reserve_quote: i128,
+    pub total_shares: i128, // LP shares outstanding
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AmmKey {
+    Shares(Address), // Provider -> LP shares
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AmmError {
+    InvalidAmount = 1,
+    SlippageExceeded = 2,
+    InsufficientShares = 3,
+    InsufficientLiquidity = 4,
+    DeadlineExpired = 5,
+    Overflow = 6, // An amount times a reserve or share count would pass i128
+}
+
+#[contract]
+pub struct PiCoinAmm;
+
+#[contractimpl]
+impl PiCoinAmm {
+    // Constructor: constant-product PI/quote pool (e.g. PI/USDC). Reserves grow by what
+    // the pool actually receives, so a PI transfer fee on the way in is borne by the
+    // depositor or trader. Governance may exempt the pool (`set_fee_exempt`), since swaps
+    // already pay the pool fee.
+    pub fn __constructor(env: Env, pi_token: Address, quote_token: Address) {
+        let data = AmmData { pi_token, quote_token, reserve_pi: 0, reserve_quote: 0, total_shares: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "amm_data"), &data);
+        log!(&env, "AMM initialized: PI pair live on the DEX, {} bps fee", FEE_BPS);
+    }
+
+    // Provide liquidity at the pool ratio (any ratio for the first deposit), within the
+    // given minimums and up to `deadline_ledger`. Shares are minted for what arrives, net
+    // of any transfer fee. Returns LP shares minted.
+    pub fn deposit(
+        env: Env,
+        to: Address,
+        desired_pi: i128,
+        min_pi: i128,
+        desired_quote: i128,
+        min_quote: i128,
+        deadline_ledger: u32,
+    ) -> Result<i128, AmmError> {
+        to.require_auth();
+        Self::check_deadline(&env, deadline_ledger)?;
+        let mut data = Self::load(&env);
+        let (amount_pi, amount_quote) = if data.total_shares == 0 {
+            (desired_pi, desired_quote)
+        } else {
+            let quote_for_pi = mul_div(desired_pi, data.reserve_quote, data.reserve_pi)?;
+            if quote_for_pi <= desired_quote {
+                (desired_pi, quote_for_pi)
+            } else {
+                (mul_div(desired_quote, data.reserve_pi, data.reserve_quote)?, desired_quote)
+            }
+        };
+        if amount_pi <= 0 || amount_quote <= 0 {
+            return Err(AmmError::InvalidAmount);
+        }
+        if amount_pi < min_pi || amount_quote < min_quote {
+            return Err(AmmError::SlippageExceeded);
+        }
+
+        let amount_pi = receive::pull(&env, &data.pi_token, &to, amount_pi).ok_or(AmmError::Overflow)?;
+        let amount_quote = receive::pull(&env, &data.quote_token, &to, amount_quote).ok_or(AmmError::Overflow)?;
+        let shares = if data.total_shares == 0 {
+            sqrt(amount_pi.checked_mul(amount_quote).ok_or(AmmError::Overflow)?)
+        } else {
+            mul_div(amount_pi, data.total_shares, data.reserve_pi)?.min(mul_div(amount_quote, data.total_shares, data.reserve_quote)?)
+        };
+        if shares <= 0 {
+            return Err(AmmError::InvalidAmount);
+        }
+
+        data.reserve_pi = data.reserve_pi.checked_add(amount_pi).ok_or(AmmError::Overflow)?;
+        data.reserve_quote = data.reserve_quote.checked_add(amount_quote).ok_or(AmmError::Overflow)?;
+        data.total_shares += shares;
+        Self::save(&env, &data);
+        Self::put_shares(&env, &to, Self::shares_of(&env, &to) + shares);
+        env.events().publish((Symbol::new(&env, "deposit"), to), (amount_pi, amount_quote, shares));
+        Ok(shares)
+    }
+
+    // Sell `amount_in` of PI (`sell_pi`) or of the quote token for the other side,
+    // receiving at least `min_out`. The price is set by what reaches the pool, net of any
+    // transfer fee. A swap simulated long before it lands would trade at a stale price, so
+    // it fails once the ledger passes `deadline_ledger`. Returns the amount bought.
+    pub fn swap(env: Env, to: Address, sell_pi: bool, amount_in: i128, min_out: i128, deadline_ledger: u32) -> Result<i128, AmmError> {
+        to.require_auth();
+        Self::check_deadline(&env, deadline_ledger)?;
+        let mut data = Self::load(&env);
+        if amount_in <= 0 {
+            return Err(AmmError::InvalidAmount);
+        }
+        let (reserve_in, reserve_out, token_in, token_out) = if sell_pi {
+            (data.reserve_pi, data.reserve_quote, data.pi_token.clone(), data.quote_token.clone())
+        } else {
+            (data.reserve_quote, data.reserve_pi, data.quote_token.clone(), data.pi_token.clone())
+        };
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(AmmError::InsufficientLiquidity);
+        }
+        let received = receive::pull(&env, &token_in, &to, amount_in).ok_or(AmmError::Overflow)?;
+        let out = Self::amount_out(received, reserve_in, reserve_out)?;
+        if out < min_out || out <= 0 {
+            return Err(AmmError::SlippageExceeded);
+        }
+
+        token::TokenClient::new(&env, &token_out).transfer(&env.current_contract_address(), &to, &out);
+        let reserve_in = reserve_in.checked_add(received).ok_or(AmmError::Overflow)?;
+        if sell_pi {
+            data.reserve_pi = reserve_in;
+            data.reserve_quote -= out;
+        } else {
+            data.reserve_quote = reserve_in;
+            data.reserve_pi -= out;
+        }
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "swap"), to, sell_pi), (received, out));
+        Ok(out)
+    }
+
+    // Burn `shares` for the proportional part of both reserves, within the given
+    // minimums and up to `deadline_ledger`. Returns (PI, quote) paid out.
+    pub fn withdraw(env: Env, to: Address, shares: i128, min_pi: i128, min_quote: i128, deadline_ledger: u32) -> Result<(i128, i128), AmmError> {
+        to.require_auth();
+        Self::check_deadline(&env, deadline_ledger)?;
+        let mut data = Self::load(&env);
+        let held = Self::shares_of(&env, &to);
+        if shares <= 0 || shares > held {
+            return Err(AmmError::InsufficientShares);
+        }
+        let out_pi = mul_div(data.reserve_pi, shares, data.total_shares)?;
+        let out_quote = mul_div(data.reserve_quote, shares, data.total_shares)?;
+        if out_pi < min_pi || out_quote < min_quote {
+            return Err(AmmError::SlippageExceeded);
+        }
+
+        Self::put_shares(&env, &to, held - shares);
+        data.total_shares -= shares;
+        data.reserve_pi -= out_pi;
+        data.reserve_quote -= out_quote;
+        Self::save(&env, &data);
+        let pool = env.current_contract_address();
+        token::TokenClient::new(&env, &data.pi_token).transfer(&pool, &to, &out_pi);
+        token::TokenClient::new(&env, &data.quote_token).transfer(&pool, &to, &out_quote);
+        env.events().publish((Symbol::new(&env, "withdraw"), to), (out_pi, out_quote, shares));
+        Ok((out_pi, out_quote))
+    }
+
+    // Move LP shares to another provider
+    pub fn transfer_shares(env: Env, from: Address, to: Address, shares: i128) -> Result<(), AmmError> {
+        from.require_auth();
+        let held = Self::shares_of(&env, &from);
+        if shares <= 0 || shares > held {
+            return Err(AmmError::InsufficientShares);
+        }
+        Self::put_shares(&env, &from, held - shares);
+        Self::put_shares(&env, &to, Self::shares_of(&env, &to) + shares);
+        Ok(())
+    }
+
+    // View: LP shares held by a provider
+    pub fn balance_shares(env: Env, provider: Address) -> i128 {
+        Self::shares_of(&env, &provider)
+    }
+
+    // View: (PI reserve, quote reserve)
+    pub fn reserves(env: Env) -> (i128, i128) {
+        let data = Self::load(&env);
+        (data.reserve_pi, data.reserve_quote)
+    }
+
+    // Oracle hook: pool-implied PI price in quote micro-units, for sanity checks against
+    // the main oracle feed
+    pub fn pool_price(env: Env) -> Result<i128, AmmError> {
+        let data = Self::load(&env);
+        if data.reserve_pi == 0 {
+            return Err(AmmError::InsufficientLiquidity);
+        }
+        mul_div(data.reserve_quote, PRICE_SCALE, data.reserve_pi)
+    }
+
+    // View: Output of selling `amount_in` right now (`sell_pi` as in `swap`), before any
+    // transfer fee on the way in
+    pub fn quote(env: Env, sell_pi: bool, amount_in: i128) -> Result<i128, AmmError> {
+        let data = Self::load(&env);
+        let (reserve_in, reserve_out) = if sell_pi { (data.reserve_pi, data.reserve_quote) } else { (data.reserve_quote, data.reserve_pi) };
+        if reserve_in == 0 || amount_in <= 0 {
+            return Ok(0);
+        }
+        Self::amount_out(amount_in, reserve_in, reserve_out)
+    }
+
+    // Helper: DeadlineExpired once the ledger is past `deadline_ledger`
+    fn check_deadline(env: &Env, deadline_ledger: u32) -> Result<(), AmmError> {
+        if env.ledger().sequence() > deadline_ledger {
+            return Err(AmmError::DeadlineExpired);
+        }
+        Ok(())
+    }
+
+    // Helper: x * y = k output for an exact input, after the pool fee
+    fn amount_out(amount_in: i128, reserve_in: i128, reserve_out: i128) -> Result<i128, AmmError> {
+        let in_after_fee = amount_in.checked_mul(BPS - FEE_BPS).ok_or(AmmError::Overflow)?;
+        let denominator = reserve_in.checked_mul(BPS).and_then(|scaled| scaled.checked_add(in_after_fee)).ok_or(AmmError::Overflow)?;
+        mul_div(reserve_out, in_after_fee, denominator)
+    }
+
+    fn shares_of(env: &Env, provider: &Address) -> i128 {
+        env.storage().persistent().get(&AmmKey::Shares(provider.clone())).unwrap_or(0)
+    }
+
+    fn put_shares(env: &Env, provider: &Address, shares: i128) {
+        env.storage().persistent().set(&AmmKey::Shares(provider.clone()), &shares);
+    }
+
+    fn load(env: &Env) -> AmmData {
+        env.storage().instance().get(&Symbol::new(env, "amm_data")).unwrap()
+    }
+
+    fn save(env: &Env, data: &AmmData) {
+        env.storage().instance().set(&Symbol::new(env, "amm_data"), data);
+    }
+}
+
+// Helper: `a * b / c`, or Overflow
+fn mul_div(a: i128, b: i128, c: i128) -> Result<i128, AmmError> {
+    Ok(a.checked_mul(b).ok_or(AmmError::Overflow)? / c)
+}
+
+// Helper: Integer square root (Newton's method), for the first deposit's shares
+fn sqrt(value: i128) -> i128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+mod test;
@@ -0,0 +1,127 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env};
+use crate::{AmmError, PiCoinAmm, PiCoinAmmClient};
+use pi_coin_testutils::{PiCoinContractClient, TokenBuilder};
+
+// PI/USDC pool over the real PI and a SEP-41 USDC; the provider holds 1_000_000 PI and
+// 4_000_000 USDC, the trader 10_000 PI
+fn setup<'a>(env: &Env, provider: &Address, trader: &Address) -> (PiCoinAmmClient<'a>, PiCoinContractClient<'a>, Address) {
+    let pi = TokenBuilder::new(env).mint(provider, 1_000_000).mint(trader, 10_000).build();
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    token::StellarAssetClient::new(env, &usdc).mint(provider, &4_000_000);
+    let contract_id = env.register(PiCoinAmm, (&pi.address, &usdc));
+    (PiCoinAmmClient::new(env, &contract_id), pi, usdc)
+}
+
+#[test]
+fn test_deposit_swap_withdraw_with_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let provider = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let (client, _, usdc) = setup(&env, &provider, &trader);
+    assert!(client.try_pool_price().is_err());
+
+    // 1 PI = 4 USDC
+    assert_eq!(client.deposit(&provider, &1_000_000, &0, &4_000_000, &0, &100), 2_000_000);
+    assert_eq!(client.pool_price(), 4_000_000);
+
+    // 10_000 PI in: 4 * 10_000 * 0.997 minus price impact
+    let expected = client.quote(&true, &10_000);
+    assert!(client.try_swap(&trader, &true, &10_000, &(expected + 1), &100).is_err());
+    assert_eq!(client.swap(&trader, &true, &10_000, &expected, &100), expected);
+    assert!(expected < 39_880 && expected > 39_400);
+    assert_eq!(token::TokenClient::new(&env, &usdc).balance(&trader), expected);
+    assert!(client.pool_price() < 4_000_000);
+
+    // The provider's exit includes the fee the trader paid
+    let (out_pi, out_usdc) = client.withdraw(&provider, &2_000_000, &0, &0, &100);
+    assert_eq!(out_pi, 1_010_000);
+    assert_eq!(out_usdc, 4_000_000 - expected);
+    assert_eq!(client.balance_shares(&provider), 0);
+}
+
+#[test]
+fn test_expired_deadline_rejects_stale_transactions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(500);
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let provider = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &pi).mint(&provider, &1_000_000);
+    token::StellarAssetClient::new(&env, &usdc).mint(&provider, &4_000_000);
+    let client = PiCoinAmmClient::new(&env, &env.register(PiCoinAmm, (&pi, &usdc)));
+
+    // Valid through the deadline ledger itself
+    assert_eq!(client.try_deposit(&provider, &500_000, &0, &2_000_000, &0, &499), Err(Ok(AmmError::DeadlineExpired)));
+    client.deposit(&provider, &500_000, &0, &2_000_000, &0, &500);
+
+    // A swap that sat in the mempool past its deadline fails before touching the pool
+    let reserves = client.reserves();
+    assert_eq!(client.try_swap(&provider, &true, &10_000, &0, &499), Err(Ok(AmmError::DeadlineExpired)));
+    assert_eq!(client.try_withdraw(&provider, &1_000, &0, &0, &499), Err(Ok(AmmError::DeadlineExpired)));
+    assert_eq!(client.reserves(), reserves);
+}
+
+#[test]
+fn test_reserves_track_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let provider = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let (client, pi, _) = setup(&env, &provider, &trader);
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+
+    // A 1% fee leaves 990_000 of the provider's PI in the pool, and the reserve says so
+    client.deposit(&provider, &1_000_000, &0, &4_000_000, &0, &100);
+    assert_eq!(client.reserves(), (990_000, 4_000_000));
+    assert_eq!(pi.balance(&client.address), 990_000);
+
+    // The trader is priced on the 9_900 PI that arrives, not the 10_000 sent
+    let expected = client.quote(&true, &9_900);
+    assert_eq!(client.swap(&trader, &true, &10_000, &expected, &100), expected);
+    assert_eq!(client.reserves(), (999_900, 4_000_000 - expected));
+    assert_eq!(pi.balance(&client.address), 999_900);
+
+    // Withdrawing every share empties the pool without a shortfall
+    let shares = client.balance_shares(&provider);
+    client.withdraw(&provider, &shares, &0, &0, &100);
+    assert_eq!(client.reserves(), (0, 0));
+    assert_eq!(pi.balance(&client.address), 0);
+}
+
+#[test]
+fn test_rejects_unauthorized_overdrawn_and_overflowing_calls() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let provider = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let (client, _, _) = setup(&env, &provider, &trader);
+    assert_eq!(client.try_swap(&trader, &true, &1_000, &0, &100), Err(Ok(AmmError::InsufficientLiquidity)));
+    client.deposit(&provider, &1_000_000, &0, &4_000_000, &0, &100);
+
+    // Only the owner's signature moves its tokens or shares
+    env.set_auths(&[]);
+    assert!(client.try_swap(&trader, &true, &1_000, &0, &100).is_err());
+    assert!(client.try_withdraw(&provider, &1_000, &0, &0, &100).is_err());
+    env.mock_all_auths();
+
+    // Nobody withdraws or moves shares it does not hold
+    assert_eq!(client.try_withdraw(&trader, &1, &0, &0, &100), Err(Ok(AmmError::InsufficientShares)));
+    assert_eq!(client.try_transfer_shares(&trader, &provider, &1), Err(Ok(AmmError::InsufficientShares)));
+    assert_eq!(client.try_swap(&trader, &true, &0, &0, &100), Err(Ok(AmmError::InvalidAmount)));
+
+    // Amounts whose products pass i128 fail cleanly instead of wrapping
+    assert_eq!(client.try_quote(&true, &i128::MAX), Err(Ok(AmmError::Overflow)));
+    let big = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let big_quote = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &big).mint(&provider, &(1 << 100));
+    token::StellarAssetClient::new(&env, &big_quote).mint(&provider, &(1 << 100));
+    let pool = PiCoinAmmClient::new(&env, &env.register(PiCoinAmm, (&big, &big_quote)));
+    assert_eq!(pool.try_deposit(&provider, &(1 << 100), &0, &(1 << 100), &0, &100), Err(Ok(AmmError::Overflow)));
+}
@@ -0,0 +1,129 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, BytesN, Env, Symbol, log};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BadgeData {
+    pub governance: Address, // Approves and removes attestors
+}
+
+// A soulbound ecosystem badge: bound to its holder, with no transfer entry point
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Badge {
+    pub id: u64,
+    pub attestor: Address,
+    pub evidence_hash: BytesN<32>, // Hash of the off-chain verification record
+    pub issued_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BadgeKey {
+    NextId,
+    Attestor(Address), // Present while approved
+    Badge(Address), // Holder -> Badge
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BadgeError {
+    NotAttestor = 1,
+    AlreadyBadged = 2,
+    NoBadge = 3,
+    Unauthorized = 4,
+}
+
+#[contract]
+pub struct PiCoinBadges;
+
+#[contractimpl]
+impl PiCoinBadges {
+    // Constructor: non-transferable badges for verified ecosystem participants, minted by
+    // governance-approved attestors. PiCoinContract::verify_ecosystem_entry accepts a
+    // badge holder whatever PI it holds (see PiCoinContract::set_badge_registry).
+    pub fn __constructor(env: Env, governance: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "badge_data"), &BadgeData { governance });
+        log!(&env, "Badges initialized: soulbound ecosystem verification badges");
+    }
+
+    // Governance-only: Approve an attestor
+    pub fn add_attestor(env: Env, attestor: Address) {
+        Self::governance(&env).require_auth();
+        env.storage().persistent().set(&BadgeKey::Attestor(attestor.clone()), &());
+        env.events().publish((Symbol::new(&env, "badge_attestor"), attestor), true);
+    }
+
+    // Governance-only: Remove an attestor. Badges it minted stop counting, since they are
+    // only as good as the verification behind them.
+    pub fn remove_attestor(env: Env, attestor: Address) {
+        Self::governance(&env).require_auth();
+        env.storage().persistent().remove(&BadgeKey::Attestor(attestor.clone()));
+        env.events().publish((Symbol::new(&env, "badge_attestor"), attestor), false);
+    }
+
+    // Attestor-only: Mint `holder` a badge for passing verification. Returns its id.
+    pub fn mint(env: Env, attestor: Address, holder: Address, evidence_hash: BytesN<32>) -> Result<u64, BadgeError> {
+        attestor.require_auth();
+        if !Self::is_attestor(env.clone(), attestor.clone()) {
+            return Err(BadgeError::NotAttestor);
+        }
+        let key = BadgeKey::Badge(holder.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(BadgeError::AlreadyBadged);
+        }
+        let id: u64 = env.storage().instance().get(&BadgeKey::NextId).unwrap_or(1);
+        env.storage().instance().set(&BadgeKey::NextId, &(id + 1));
+        let badge = Badge { id, attestor, evidence_hash, issued_at: env.ledger().timestamp() };
+        env.storage().persistent().set(&key, &badge);
+        env.events().publish((Symbol::new(&env, "badge_minted"), holder), badge);
+        Ok(id)
+    }
+
+    // Take `holder`'s badge away; allowed for the minting attestor and governance
+    pub fn revoke(env: Env, caller: Address, holder: Address) -> Result<(), BadgeError> {
+        caller.require_auth();
+        let badge = Self::badge(env.clone(), holder.clone()).ok_or(BadgeError::NoBadge)?;
+        if caller != badge.attestor && caller != Self::governance(&env) {
+            return Err(BadgeError::Unauthorized);
+        }
+        env.storage().persistent().remove(&BadgeKey::Badge(holder.clone()));
+        env.events().publish((Symbol::new(&env, "badge_revoked"), holder), (badge.id, caller));
+        Ok(())
+    }
+
+    // Holder: Give up one's own badge
+    pub fn renounce(env: Env, holder: Address) -> Result<(), BadgeError> {
+        holder.require_auth();
+        let badge = Self::badge(env.clone(), holder.clone()).ok_or(BadgeError::NoBadge)?;
+        env.storage().persistent().remove(&BadgeKey::Badge(holder.clone()));
+        env.events().publish((Symbol::new(&env, "badge_revoked"), holder.clone()), (badge.id, holder));
+        Ok(())
+    }
+
+    // View: Whether `holder` has a badge from a still-approved attestor. Queried by
+    // PiCoinContract::verify_ecosystem_entry.
+    pub fn has_badge(env: Env, holder: Address) -> bool {
+        let Some(badge) = Self::badge(env.clone(), holder) else { return false };
+        Self::is_attestor(env, badge.attestor)
+    }
+
+    // View: `holder`'s badge, if any
+    pub fn badge(env: Env, holder: Address) -> Option<Badge> {
+        env.storage().persistent().get(&BadgeKey::Badge(holder))
+    }
+
+    // View: Whether `attestor` is approved
+    pub fn is_attestor(env: Env, attestor: Address) -> bool {
+        env.storage().persistent().has(&BadgeKey::Attestor(attestor))
+    }
+
+    // Helper: Governance address
+    fn governance(env: &Env) -> Address {
+        let data: BadgeData = env.storage().instance().get(&Symbol::new(env, "badge_data")).unwrap();
+        data.governance
+    }
+}
+
+mod test;
@@ -0,0 +1,35 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+use crate::{BadgeError, PiCoinBadges, PiCoinBadgesClient};
+
+#[test]
+fn test_badges_follow_attestors_and_revocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let governance = Address::generate(&env);
+    let client = PiCoinBadgesClient::new(&env, &env.register(PiCoinBadges, (&governance,)));
+    let attestor = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let evidence = BytesN::from_array(&env, &[7; 32]);
+
+    assert_eq!(client.try_mint(&attestor, &alice, &evidence), Err(Ok(BadgeError::NotAttestor)));
+    client.add_attestor(&attestor);
+    assert_eq!(client.mint(&attestor, &alice, &evidence), 1);
+    assert_eq!(client.mint(&attestor, &bob, &evidence), 2);
+    assert_eq!(client.try_mint(&attestor, &alice, &evidence), Err(Ok(BadgeError::AlreadyBadged)));
+    assert!(client.has_badge(&alice));
+
+    // Only the minting attestor or governance may revoke; holders may renounce
+    assert_eq!(client.try_revoke(&alice, &bob), Err(Ok(BadgeError::Unauthorized)));
+    client.revoke(&governance, &bob);
+    assert!(!client.has_badge(&bob));
+    client.mint(&attestor, &bob, &evidence);
+    client.renounce(&bob);
+    assert_eq!(client.try_renounce(&bob), Err(Ok(BadgeError::NoBadge)));
+
+    // Removing an attestor voids the badges it minted
+    client.remove_attestor(&attestor);
+    assert!(!client.has_badge(&alice));
+    assert_eq!(client.badge(&alice).unwrap().id, 1);
+}
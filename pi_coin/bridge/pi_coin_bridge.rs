@@ -0,0 +1,207 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BridgeData {
+    pub governance: Address, // Manages the relayer set
+    pub pi_token: Address, // Escrowed on lock, paid out on release
+    pub relayers: Vec<BytesN<32>>, // ed25519 keys of the relayers
+    pub threshold: u32, // Relayer signatures a release needs
+    pub next_nonce: u64, // Nonce of the next outbound transfer
+    pub locked: i128, // PI held in escrow
+}
+
+// A transfer back from another chain: relayers sign the XDR of this struct. The network
+// id and bridge address bind a signature to this deployment; (source_chain, nonce) is
+// released at most once.
+#[contracttype]
+#[derive(Clone)]
+pub struct Release {
+    pub network_id: BytesN<32>,
+    pub bridge: Address,
+    pub source_chain: Symbol,
+    pub nonce: u64, // Transfer nonce on the source chain
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RelayerSignature {
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ReleaseProof {
+    pub release: Release,
+    pub signatures: Vec<RelayerSignature>, // One per relayer, at least `threshold`
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BridgeKey {
+    Released(Symbol, u64), // (Source chain, nonce) -> Ledger the transfer was released in
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BridgeError {
+    InvalidAmount = 1,
+    InvalidThreshold = 2,
+    AlreadyReleased = 3,
+    NotEnoughSignatures = 4,
+    UnknownRelayer = 5,
+    DuplicateRelayer = 6,
+    WrongDomain = 7,
+    InsufficientEscrow = 8,
+}
+
+#[contract]
+pub struct PiCoinBridge;
+
+#[contractimpl]
+impl PiCoinBridge {
+    // Constructor: lock-and-mint bridge for PI. `lock` escrows PI here and publishes a
+    // bridge_lock event that relayers pick up to mint the wrapped asset on the destination
+    // chain. On the return path the wrapped asset is burned there and `release` pays the
+    // PI back out of escrow once `threshold` of the governance-managed relayers have signed.
+    pub fn __constructor(
+        env: Env,
+        governance: Address,
+        pi_token: Address,
+        relayers: Vec<BytesN<32>>,
+        threshold: u32,
+    ) -> Result<(), BridgeError> {
+        Self::validate(&relayers, threshold)?;
+        let data = BridgeData { governance, pi_token, relayers, threshold, next_nonce: 0, locked: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "bridge_data"), &data);
+        log!(&env, "Bridge initialized: {}-of-{} relayers", threshold, data.relayers.len());
+        Ok(())
+    }
+
+    // Escrow `amount` PI for `dest_address` on `dest_chain`; returns the transfer nonce. The
+    // event carries what the bridge actually received, net of any PI transfer fee, so the
+    // wrapped side is never minted above escrow.
+    // Event: ("bridge_lock", dest_chain, nonce) -> (from, amount, dest_address)
+    pub fn lock(env: Env, from: Address, amount: i128, dest_chain: Symbol, dest_address: Bytes) -> Result<u64, BridgeError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(BridgeError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        let amount = receive::pull(&env, &data.pi_token, &from, amount).unwrap_or(0);
+        if amount <= 0 {
+            return Err(BridgeError::InvalidAmount);
+        }
+        let nonce = data.next_nonce;
+        data.next_nonce += 1;
+        data.locked += amount;
+        env.storage().instance().set(&Symbol::new(&env, "bridge_data"), &data);
+        env.events().publish((Symbol::new(&env, "bridge_lock"), dest_chain, nonce), (from, amount, dest_address));
+        Ok(nonce)
+    }
+
+    // Pay out a transfer from another chain signed by at least `threshold` relayers
+    // (permissionless: anyone may submit the proof). Event: ("bridge_release", source_chain,
+    // nonce) -> (to, amount)
+    pub fn release(env: Env, proof: ReleaseProof) -> Result<(), BridgeError> {
+        let mut data = Self::load(&env);
+        let release = proof.release;
+        if release.network_id != env.ledger().network_id() || release.bridge != env.current_contract_address() {
+            return Err(BridgeError::WrongDomain);
+        }
+        if release.amount <= 0 {
+            return Err(BridgeError::InvalidAmount);
+        }
+        let key = BridgeKey::Released(release.source_chain.clone(), release.nonce);
+        if env.storage().persistent().has(&key) {
+            return Err(BridgeError::AlreadyReleased);
+        }
+        if proof.signatures.len() < data.threshold {
+            return Err(BridgeError::NotEnoughSignatures);
+        }
+        let message = release.clone().to_xdr(&env);
+        let mut signers: Vec<BytesN<32>> = Vec::new(&env);
+        for signature in proof.signatures.iter() {
+            if !data.relayers.contains(&signature.public_key) {
+                return Err(BridgeError::UnknownRelayer);
+            }
+            if signers.contains(&signature.public_key) {
+                return Err(BridgeError::DuplicateRelayer);
+            }
+            // Traps on an invalid signature
+            env.crypto().ed25519_verify(&signature.public_key, &message, &signature.signature);
+            signers.push_back(signature.public_key);
+        }
+        if release.amount > data.locked {
+            return Err(BridgeError::InsufficientEscrow);
+        }
+        data.locked -= release.amount;
+        env.storage().instance().set(&Symbol::new(&env, "bridge_data"), &data);
+        env.storage().persistent().set(&key, &env.ledger().sequence());
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &release.to, &release.amount);
+        env.events().publish(
+            (Symbol::new(&env, "bridge_release"), release.source_chain, release.nonce),
+            (release.to, release.amount),
+        );
+        Ok(())
+    }
+
+    // Governance-only: Replace the relayer set and threshold (key rotation)
+    pub fn set_relayers(env: Env, relayers: Vec<BytesN<32>>, threshold: u32) -> Result<(), BridgeError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(&relayers, threshold)?;
+        data.relayers = relayers;
+        data.threshold = threshold;
+        env.storage().instance().set(&Symbol::new(&env, "bridge_data"), &data);
+        env.events().publish((Symbol::new(&env, "bridge_relayers"),), (data.relayers, threshold));
+        Ok(())
+    }
+
+    // View: Relayer keys and threshold
+    pub fn relayers(env: Env) -> (Vec<BytesN<32>>, u32) {
+        let data = Self::load(&env);
+        (data.relayers, data.threshold)
+    }
+
+    // View: Whether the transfer `nonce` from `source_chain` has been released
+    pub fn is_released(env: Env, source_chain: Symbol, nonce: u64) -> bool {
+        env.storage().persistent().has(&BridgeKey::Released(source_chain, nonce))
+    }
+
+    // View: PI held in escrow
+    pub fn locked(env: Env) -> i128 {
+        Self::load(&env).locked
+    }
+
+    // View: Nonce the next `lock` will be assigned
+    pub fn next_nonce(env: Env) -> u64 {
+        Self::load(&env).next_nonce
+    }
+
+    fn validate(relayers: &Vec<BytesN<32>>, threshold: u32) -> Result<(), BridgeError> {
+        if threshold == 0 || threshold > relayers.len() {
+            return Err(BridgeError::InvalidThreshold);
+        }
+        for (i, relayer) in relayers.iter().enumerate() {
+            if relayers.first_index_of(&relayer) != Some(i as u32) {
+                return Err(BridgeError::DuplicateRelayer);
+            }
+        }
+        Ok(())
+    }
+
+    fn load(env: &Env) -> BridgeData {
+        env.storage().instance().get(&Symbol::new(env, "bridge_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,208 @@
+#![cfg(test)]
+// Relayers sign with ed25519-dalek keys (dev-dependency)
+extern crate std;
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    vec, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Symbol,
+};
+use crate::{BridgeError, PiCoinBridge, PiCoinBridgeClient, Release, ReleaseProof, RelayerSignature};
+use pi_coin_testutils::TokenBuilder;
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+// `release` signed by each of `keys`
+fn proof(env: &Env, release: &Release, keys: &[&SigningKey]) -> ReleaseProof {
+    let xdr = release.clone().to_xdr(env);
+    let mut message = std::vec![0u8; xdr.len() as usize];
+    xdr.copy_into_slice(&mut message);
+    let mut signatures = vec![env];
+    for key in keys {
+        signatures.push_back(RelayerSignature {
+            public_key: public_key(env, key),
+            signature: BytesN::from_array(env, &key.sign(&message).to_bytes()),
+        });
+    }
+    ReleaseProof { release: release.clone(), signatures }
+}
+
+fn release_of(env: &Env, bridge: &Address, nonce: u64, to: &Address, amount: i128) -> Release {
+    Release {
+        network_id: env.ledger().network_id(),
+        bridge: bridge.clone(),
+        source_chain: Symbol::new(env, "ethereum"),
+        nonce,
+        to: to.clone(),
+        amount,
+    }
+}
+
+#[test]
+fn test_lock_and_release_with_relayer_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 1_000).build();
+
+    let keys = [SigningKey::from_bytes(&[1; 32]), SigningKey::from_bytes(&[2; 32]), SigningKey::from_bytes(&[3; 32])];
+    let relayers = vec![&env, public_key(&env, &keys[0]), public_key(&env, &keys[1]), public_key(&env, &keys[2])];
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinBridge, (&governance, &pi.address, relayers.clone(), 2u32));
+    let client = PiCoinBridgeClient::new(&env, &contract_id);
+
+    // Locking escrows the PI and publishes what relayers need to mint on the other side
+    let dest_chain = Symbol::new(&env, "ethereum");
+    let dest_address = Bytes::from_array(&env, &[0xab; 20]);
+    assert_eq!(client.lock(&alice, &600, &dest_chain, &dest_address), 0);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "bridge_lock"), dest_chain.clone(), 0u64).into_val(&env),
+                (alice.clone(), 600i128, dest_address.clone()).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.next_nonce(), 1);
+    assert_eq!((pi.balance(&contract_id), client.locked()), (600, 600));
+    assert_eq!(client.try_lock(&alice, &0, &dest_chain, &dest_address), Err(Ok(BridgeError::InvalidAmount)));
+
+    // One signature is below the 2-of-3 threshold; a signer counts once
+    let release = release_of(&env, &contract_id, 7, &bob, 250);
+    assert_eq!(client.try_release(&proof(&env, &release, &[&keys[0]])), Err(Ok(BridgeError::NotEnoughSignatures)));
+    assert_eq!(
+        client.try_release(&proof(&env, &release, &[&keys[0], &keys[0]])),
+        Err(Ok(BridgeError::DuplicateRelayer))
+    );
+    let outsider = SigningKey::from_bytes(&[9; 32]);
+    assert_eq!(
+        client.try_release(&proof(&env, &release, &[&keys[0], &outsider])),
+        Err(Ok(BridgeError::UnknownRelayer))
+    );
+
+    // A signature over different terms does not verify
+    let mut forged = proof(&env, &release, &[&keys[0], &keys[2]]);
+    forged.release.amount = 600;
+    assert!(client.try_release(&forged).is_err());
+
+    // Signatures bound to another deployment are rejected
+    let elsewhere = release_of(&env, &Address::generate(&env), 7, &bob, 250);
+    assert_eq!(
+        client.try_release(&proof(&env, &elsewhere, &[&keys[0], &keys[1]])),
+        Err(Ok(BridgeError::WrongDomain))
+    );
+
+    // Two relayers release the transfer, once
+    client.release(&proof(&env, &release, &[&keys[0], &keys[2]]));
+    assert_eq!((pi.balance(&bob), client.locked()), (250, 350));
+    assert!(client.is_released(&dest_chain, &7));
+    assert_eq!(
+        client.try_release(&proof(&env, &release, &[&keys[1], &keys[2]])),
+        Err(Ok(BridgeError::AlreadyReleased))
+    );
+
+    // Releases never exceed what is escrowed
+    let too_much = release_of(&env, &contract_id, 8, &bob, 351);
+    assert_eq!(
+        client.try_release(&proof(&env, &too_much, &[&keys[0], &keys[1]])),
+        Err(Ok(BridgeError::InsufficientEscrow))
+    );
+}
+
+#[test]
+fn test_governance_rotates_relayers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 100).build();
+    let old = SigningKey::from_bytes(&[1; 32]);
+    let new = [SigningKey::from_bytes(&[4; 32]), SigningKey::from_bytes(&[5; 32])];
+    let contract_id = env.register(PiCoinBridge, (Address::generate(&env), &pi.address, vec![&env, public_key(&env, &old)], 1u32));
+    let client = PiCoinBridgeClient::new(&env, &contract_id);
+    client.lock(&alice, &100, &Symbol::new(&env, "ethereum"), &Bytes::from_array(&env, &[1; 20]));
+
+    let relayers = vec![&env, public_key(&env, &new[0]), public_key(&env, &new[1])];
+    assert_eq!(client.try_set_relayers(&relayers, &3), Err(Ok(BridgeError::InvalidThreshold)));
+    let twice = vec![&env, public_key(&env, &new[0]), public_key(&env, &new[0])];
+    assert_eq!(client.try_set_relayers(&twice, &1), Err(Ok(BridgeError::DuplicateRelayer)));
+    client.set_relayers(&relayers, &2);
+    assert_eq!(client.relayers(), (relayers, 2));
+
+    // The retired key no longer releases anything
+    let release = release_of(&env, &contract_id, 0, &bob, 100);
+    assert_eq!(client.try_release(&proof(&env, &release, &[&old])), Err(Ok(BridgeError::NotEnoughSignatures)));
+    client.release(&proof(&env, &release, &[&new[1], &new[0]]));
+    assert_eq!(pi.balance(&bob), 100);
+}
+
+#[test]
+fn test_lock_announces_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let alice = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 1_000).build();
+    let key = SigningKey::from_bytes(&[1; 32]);
+    let contract_id = env.register(PiCoinBridge, (Address::generate(&env), &pi.address, vec![&env, public_key(&env, &key)], 1u32));
+    let client = PiCoinBridgeClient::new(&env, &contract_id);
+
+    // At a 1% fee 990 of the 1_000 reach escrow, and only 990 is announced for minting
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    let dest_chain = Symbol::new(&env, "ethereum");
+    let dest_address = Bytes::from_array(&env, &[0xab; 20]);
+    client.lock(&alice, &1_000, &dest_chain, &dest_address);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "bridge_lock"), dest_chain, 0u64).into_val(&env),
+                (alice, 990i128, dest_address).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!((pi.balance(&contract_id), client.locked()), (990, 990));
+}
+
+#[test]
+fn test_rejects_unauthorized_and_underfunded_locks() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let alice = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 500).build();
+    let key = SigningKey::from_bytes(&[1; 32]);
+    let contract_id = env.register(PiCoinBridge, (Address::generate(&env), &pi.address, vec![&env, public_key(&env, &key)], 1u32));
+    let client = PiCoinBridgeClient::new(&env, &contract_id);
+    let dest_chain = Symbol::new(&env, "ethereum");
+    let dest_address = Bytes::from_array(&env, &[0xab; 20]);
+
+    // A holder cannot lock more than it holds, and nothing is escrowed or announced
+    assert!(client.try_lock(&alice, &501, &dest_chain, &dest_address).is_err());
+    assert_eq!((client.locked(), client.next_nonce()), (0, 0));
+
+    // Locks need the holder's signature, relayer rotation needs governance's
+    env.set_auths(&[]);
+    assert!(client.try_lock(&alice, &100, &dest_chain, &dest_address).is_err());
+    let rotated = vec![&env, public_key(&env, &SigningKey::from_bytes(&[2; 32]))];
+    assert!(client.try_set_relayers(&rotated, &1).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.relayers(), (vec![&env, public_key(&env, &key)], 1));
+
+    // An empty escrow releases nothing, even with a valid proof
+    let release = release_of(&env, &contract_id, 0, &alice, 100);
+    assert_eq!(client.try_release(&proof(&env, &release, &[&key])), Err(Ok(BridgeError::InsufficientEscrow)));
+    assert!(!client.is_released(&dest_chain, &0));
+    assert_eq!(pi.balance(&alice), 500);
+}
@@ -0,0 +1,246 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, Address, Env, Symbol, Vec, log};
+
+pub const BPS: i128 = 10_000;
+// Largest gain governance may set (10x: 10 bps of adjustment per bp of error)
+pub const MAX_GAIN_BPS: u32 = 100_000;
+// Largest adjustment either way, in annual basis points
+pub const MAX_ADJUSTMENT_BPS: i32 = 10_000;
+
+// Same fields (and XDR) as PiCoinOracle's PricePoint
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricePoint {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// PiCoinOracle views the controller samples
+#[contractclient(name = "PriceHistoryClient")]
+pub trait PriceHistory {
+    fn prices(env: Env, asset: Symbol, from: u64, to: u64) -> Vec<PricePoint>;
+    fn query_price(env: Env, asset: Symbol) -> i128;
+}
+
+// PiCoinContract view giving the peg the controller steers toward
+#[contractclient(name = "PegClient")]
+pub trait PegSource {
+    fn peg_value(env: Env) -> i128;
+}
+
+// Rate model (PiCoinRateModel) whose rates the adjustment is added to
+#[contractclient(name = "RateModelClient")]
+pub trait RateModel {
+    fn borrow_rate(env: Env, cash: i128, borrows: i128) -> u32;
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ControllerConfig {
+    pub oracle: Address,
+    pub asset: Symbol, // e.g. "PI"
+    pub pi_token: Address, // Source of the peg
+    pub base_model: Address, // Rates before adjustment
+    pub kp_bps: u32, // Adjustment per bp of error, scaled by BPS
+    pub ki_bps: u32, // ...per bp of accumulated error
+    pub kd_bps: u32, // ...per bp of change in error since the last update
+    pub twap_window: u64, // Seconds of oracle history averaged into the TWAP
+    pub interval: u64, // Seconds between updates
+    pub min_adjustment_bps: i32, // Bounds on the adjustment; min <= 0 <= max
+    pub max_adjustment_bps: i32,
+}
+
+// Controller memory between updates
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ControllerState {
+    pub adjustment_bps: i32, // Added to the base model's rates
+    pub last_error_bps: i32, // (peg - TWAP) / peg at the last update
+    pub integral_bps: i64, // Sum of errors over past updates
+    pub last_update: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ControllerData {
+    pub governance: Address, // Sets the config and the override
+    pub config: ControllerConfig,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ControllerKey {
+    State,
+    Override, // Adjustment pinned by governance; the controller holds while set
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ControllerError {
+    InvalidConfig = 1,
+    TooEarly = 2, // Less than `interval` since the last update
+    Overridden = 3,
+    InvalidPrice = 4,
+}
+
+#[contract]
+pub struct PiCoinController;
+
+#[contractimpl]
+impl PiCoinController {
+    // Constructor: automated peg defense between governance votes. Each `update` compares
+    // the oracle TWAP with the peg and moves a rate adjustment by a PID rule, within the
+    // governance-set bounds. The controller is itself a rate model: point the savings vault
+    // and the lending market at it (set_rate_model) and their rates become the base model's
+    // plus the adjustment. Below the peg both rise, pulling PI into savings and making it
+    // dearer to borrow; above the peg both fall.
+    pub fn __constructor(env: Env, governance: Address, config: ControllerConfig) -> Result<(), ControllerError> {
+        Self::validate(&config)?;
+        env.storage().instance().set(&Symbol::new(&env, "controller_data"), &ControllerData { governance, config });
+        log!(&env, "Peg controller initialized: rates follow the TWAP's distance from the peg");
+        Ok(())
+    }
+
+    // Sample the TWAP and move the adjustment (permissionless, once per interval; keepers
+    // run it). Returns the new adjustment. The integral stops growing while the output is
+    // pinned at a bound by an error pushing it further out, so it does not wind up.
+    pub fn update(env: Env) -> Result<i32, ControllerError> {
+        let config = Self::load(&env).config;
+        if Self::override_bps(env.clone()).is_some() {
+            return Err(ControllerError::Overridden);
+        }
+        let mut state = Self::state(env.clone());
+        let now = env.ledger().timestamp();
+        if state.last_update != 0 && now < state.last_update.saturating_add(config.interval) {
+            return Err(ControllerError::TooEarly);
+        }
+        let twap = Self::twap(&env, &config)?;
+        let peg = PegClient::new(&env, &config.pi_token).peg_value();
+        if peg <= 0 {
+            return Err(ControllerError::InvalidPrice);
+        }
+        let error = ((peg - twap).saturating_mul(BPS) / peg).clamp(-BPS, BPS);
+
+        let output = |integral: i128| {
+            let raw = config.kp_bps as i128 * error + config.ki_bps as i128 * integral + config.kd_bps as i128 * (error - state.last_error_bps as i128);
+            raw / BPS
+        };
+        let mut integral = state.integral_bps as i128 + error;
+        let unbounded = output(integral);
+        if (unbounded > config.max_adjustment_bps as i128 && error > 0) || (unbounded < config.min_adjustment_bps as i128 && error < 0) {
+            integral = state.integral_bps as i128;
+        }
+        let adjustment = unbounded.clamp(config.min_adjustment_bps as i128, config.max_adjustment_bps as i128) as i32;
+
+        state = ControllerState { adjustment_bps: adjustment, last_error_bps: error as i32, integral_bps: integral as i64, last_update: now };
+        env.storage().instance().set(&ControllerKey::State, &state);
+        env.events().publish((Symbol::new(&env, "controller_update"),), (twap, error as i32, adjustment));
+        Ok(adjustment)
+    }
+
+    // Governance-only: Retune the controller. The current adjustment is pulled inside the
+    // new bounds; the error history is kept.
+    pub fn set_config(env: Env, config: ControllerConfig) -> Result<(), ControllerError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(&config)?;
+        let mut state = Self::state(env.clone());
+        state.adjustment_bps = state.adjustment_bps.clamp(config.min_adjustment_bps, config.max_adjustment_bps);
+        env.storage().instance().set(&ControllerKey::State, &state);
+        data.config = config.clone();
+        env.storage().instance().set(&Symbol::new(&env, "controller_data"), &data);
+        env.events().publish((Symbol::new(&env, "controller_config"),), config);
+        Ok(())
+    }
+
+    // Governance-only: Pin the adjustment to `adjustment_bps`, or hand control back with
+    // None. Updates fail while pinned, and control resumes with the integral cleared so
+    // error accumulated before the override does not kick in all at once.
+    pub fn set_override(env: Env, adjustment_bps: Option<i32>) -> Result<(), ControllerError> {
+        let data = Self::load(&env);
+        data.governance.require_auth();
+        match adjustment_bps {
+            Some(value) if !(-MAX_ADJUSTMENT_BPS..=MAX_ADJUSTMENT_BPS).contains(&value) => return Err(ControllerError::InvalidConfig),
+            Some(value) => env.storage().instance().set(&ControllerKey::Override, &value),
+            None => {
+                env.storage().instance().remove(&ControllerKey::Override);
+                let mut state = Self::state(env.clone());
+                state.integral_bps = 0;
+                env.storage().instance().set(&ControllerKey::State, &state);
+            }
+        }
+        env.events().publish((Symbol::new(&env, "controller_override"),), adjustment_bps);
+        Ok(())
+    }
+
+    // View: Rate for a vault with `cash` and `borrows`: the base model's plus the
+    // adjustment, never below zero. Called by savings and lending as their rate model.
+    pub fn borrow_rate(env: Env, cash: i128, borrows: i128) -> u32 {
+        let config = Self::load(&env).config;
+        let base = RateModelClient::new(&env, &config.base_model).borrow_rate(&cash, &borrows) as i64;
+        (base + Self::adjustment_bps(env) as i64).max(0) as u32
+    }
+
+    // View: Adjustment in effect: the override if set, else the controller's
+    pub fn adjustment_bps(env: Env) -> i32 {
+        Self::override_bps(env.clone()).unwrap_or(Self::state(env).adjustment_bps)
+    }
+
+    // View: Adjustment pinned by governance, if any
+    pub fn override_bps(env: Env) -> Option<i32> {
+        env.storage().instance().get(&ControllerKey::Override)
+    }
+
+    // View: Controller memory (zeroed before the first update)
+    pub fn state(env: Env) -> ControllerState {
+        env.storage().instance().get(&ControllerKey::State).unwrap_or(ControllerState {
+            adjustment_bps: 0,
+            last_error_bps: 0,
+            integral_bps: 0,
+            last_update: 0,
+        })
+    }
+
+    // View: Current config
+    pub fn config(env: Env) -> ControllerConfig {
+        Self::load(&env).config
+    }
+
+    // Helper: Average of the oracle's retained prices over the window; the spot price when
+    // the window holds none
+    fn twap(env: &Env, config: &ControllerConfig) -> Result<i128, ControllerError> {
+        let oracle = PriceHistoryClient::new(env, &config.oracle);
+        let now = env.ledger().timestamp();
+        let points = oracle.prices(&config.asset, &now.saturating_sub(config.twap_window), &now);
+        let price = if points.is_empty() {
+            oracle.query_price(&config.asset)
+        } else {
+            points.iter().map(|point| point.price).sum::<i128>() / points.len() as i128
+        };
+        if price <= 0 {
+            return Err(ControllerError::InvalidPrice);
+        }
+        Ok(price)
+    }
+
+    // Helper: Bounds around zero within MAX_ADJUSTMENT_BPS, gains up to MAX_GAIN_BPS, and
+    // a non-empty window and interval
+    fn validate(config: &ControllerConfig) -> Result<(), ControllerError> {
+        let bounded = -MAX_ADJUSTMENT_BPS <= config.min_adjustment_bps
+            && config.min_adjustment_bps <= 0
+            && 0 <= config.max_adjustment_bps
+            && config.max_adjustment_bps <= MAX_ADJUSTMENT_BPS;
+        let gains = config.kp_bps <= MAX_GAIN_BPS && config.ki_bps <= MAX_GAIN_BPS && config.kd_bps <= MAX_GAIN_BPS;
+        if !bounded || !gains || config.twap_window == 0 || config.interval == 0 {
+            return Err(ControllerError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn load(env: &Env) -> ControllerData {
+        env.storage().instance().get(&Symbol::new(env, "controller_data")).unwrap()
+    }
+}
+
+mod test;
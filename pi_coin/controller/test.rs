@@ -0,0 +1,91 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Ledger}, vec, Address, Env, Symbol, Vec};
+use crate::{ControllerConfig, ControllerError, PiCoinController, PiCoinControllerClient, PricePoint};
+
+// Just enough of PiCoinOracle, PiCoinContract and PiCoinRateModel: a price history, a
+// peg of 1_000_000 and a flat 4% base rate
+#[contract]
+struct MockEcosystem;
+
+#[contractimpl]
+impl MockEcosystem {
+    pub fn set_prices(env: Env, points: Vec<PricePoint>) {
+        env.storage().instance().set(&0u32, &points);
+    }
+
+    pub fn prices(env: Env, _asset: Symbol, _from: u64, _to: u64) -> Vec<PricePoint> {
+        env.storage().instance().get(&0u32).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn query_price(_env: Env, _asset: Symbol) -> i128 {
+        1_000_000
+    }
+
+    pub fn peg_value(_env: Env) -> i128 {
+        1_000_000
+    }
+
+    pub fn borrow_rate(_env: Env, _cash: i128, _borrows: i128) -> u32 {
+        400
+    }
+}
+
+#[test]
+fn test_controller_steers_rates_toward_the_peg() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(100_000);
+    let mock = MockEcosystemClient::new(&env, &env.register(MockEcosystem, ()));
+    let config = ControllerConfig {
+        oracle: mock.address.clone(),
+        asset: Symbol::new(&env, "PI"),
+        pi_token: mock.address.clone(),
+        base_model: mock.address.clone(),
+        kp_bps: 5_000, // 0.5 bps per bp of error
+        ki_bps: 1_000, // 0.1 bps per accumulated bp
+        kd_bps: 0,
+        twap_window: 86_400,
+        interval: 3_600,
+        min_adjustment_bps: -150,
+        max_adjustment_bps: 150,
+    };
+    let contract_id = env.register(PiCoinController, (Address::generate(&env), config.clone()));
+    let client = PiCoinControllerClient::new(&env, &contract_id);
+    assert!(matches!(
+        client.try_set_config(&ControllerConfig { min_adjustment_bps: 10, ..config.clone() }),
+        Err(Ok(ControllerError::InvalidConfig))
+    ));
+
+    // At the peg (spot fallback, no history yet) nothing moves
+    assert_eq!(client.update(), 0);
+    assert_eq!(client.borrow_rate(&0, &0), 400);
+    assert!(matches!(client.try_update(), Err(Ok(ControllerError::TooEarly))));
+
+    // A 2% TWAP discount: 0.5 * 200 + 0.1 * 200 = 120 bps on top of the base rate
+    mock.set_prices(&vec![&env, PricePoint { price: 970_000, timestamp: 96_400 }, PricePoint { price: 990_000, timestamp: 100_000 }]);
+    env.ledger().set_timestamp(103_600);
+    assert_eq!(client.update(), 120);
+    assert_eq!(client.borrow_rate(&0, &0), 520);
+    env.ledger().set_timestamp(107_200);
+    assert_eq!(client.update(), 140);
+
+    // Pinned at the bound, the integral stops accumulating
+    env.ledger().set_timestamp(110_800);
+    assert_eq!(client.update(), 150);
+    assert_eq!(client.state().integral_bps, 400);
+
+    // Above the peg the adjustment turns negative
+    mock.set_prices(&vec![&env, PricePoint { price: 1_020_000, timestamp: 110_000 }]);
+    env.ledger().set_timestamp(114_400);
+    assert_eq!(client.update(), -80);
+    assert_eq!(client.borrow_rate(&0, &0), 320);
+
+    // Governance override: the controller holds, and resumes with a clean integral
+    client.set_override(&Some(0));
+    assert_eq!(client.borrow_rate(&0, &0), 400);
+    env.ledger().set_timestamp(118_000);
+    assert!(matches!(client.try_update(), Err(Ok(ControllerError::Overridden))));
+    client.set_override(&None);
+    assert_eq!((client.state().integral_bps, client.adjustment_bps()), (0, -80));
+    assert_eq!(client.update(), -120);
+}
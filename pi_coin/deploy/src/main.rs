@@ -0,0 +1,437 @@
+// Host-side deployment CLI for the Pi Coin ecosystem.
+//
+// Drives the `stellar` CLI (which talks to Stellar RPC) to upload the four
+// contract wasms and deploy them with each other's addresses as constructor
+// arguments. Contract ids are precomputed from per-contract salts, so every
+// constructor can be wired before anything is deployed:
+//
+//   cargo run --manifest-path pi_coin/deploy/Cargo.toml -- \
+//       --network testnet --source-account alice --collateral C...
+//
+// `alice` is a `stellar keys` identity. The signer reaches the stellar CLI through its
+// STELLAR_ACCOUNT environment variable, never on the command line, where other local
+// users could read a secret key from the process list.
+//
+// With --multisig-owners, a k-of-n PiCoinMultisig is deployed first and becomes the
+// admin of the token, oracle and governance instead of the deploying key.
+//
+// Each network has a configuration profile (peg, governance thresholds, oracle reporters,
+// fee) that flags can override. The profile is validated before anything is uploaded and
+// written with the contract ids and wasm hashes to a JSON manifest, mirroring the
+// factory's `deployment_manifest` event, so a deployment can be reproduced or audited.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Network {
+    Testnet,
+    Futurenet,
+    Mainnet,
+}
+
+impl Network {
+    fn name(self) -> &'static str {
+        match self {
+            Network::Testnet => "testnet",
+            Network::Futurenet => "futurenet",
+            Network::Mainnet => "mainnet",
+        }
+    }
+
+    // Built-in launch parameters. Test networks run with a simple majority, no fee and the
+    // admin as sole reporter; mainnet needs a supermajority and an explicit reporter set.
+    fn profile(self, admin: &str) -> Profile {
+        match self {
+            Network::Testnet | Network::Futurenet => Profile {
+                peg_value: 314_159_000_000,
+                quorum_bps: 400,
+                approval_bps: 5_000,
+                oracle_reporters: vec![admin.to_string()],
+                reporter_threshold: 1,
+                fee_bps: 0,
+            },
+            Network::Mainnet => Profile {
+                peg_value: 314_159_000_000,
+                quorum_bps: 1_000,
+                approval_bps: 6_666,
+                oracle_reporters: Vec::new(),
+                reporter_threshold: 2,
+                fee_bps: 10,
+            },
+        }
+    }
+}
+
+// A network profile as registered with the factory (NetworkProfile)
+#[derive(Debug)]
+struct Profile {
+    peg_value: i128,
+    quorum_bps: u32,
+    approval_bps: u32,
+    oracle_reporters: Vec<String>,
+    reporter_threshold: u32,
+    fee_bps: u32,
+}
+
+impl Profile {
+    // Same checks as PiCoinDeployer::set_profile
+    fn validate(&self) -> Result<(), String> {
+        if self.peg_value <= 0 {
+            return Err("peg value must be positive".to_string());
+        }
+        if self.quorum_bps > 10_000 || !(5_000..10_000).contains(&self.approval_bps) {
+            return Err("quorum must be at most 10000 bps and approval within 5000..10000 bps".to_string());
+        }
+        if self.fee_bps > 10_000 {
+            return Err("fee must be at most 10000 bps".to_string());
+        }
+        let mut reporters = self.oracle_reporters.clone();
+        reporters.sort();
+        reporters.dedup();
+        if reporters.len() != self.oracle_reporters.len() {
+            return Err("oracle reporters must be distinct".to_string());
+        }
+        if self.reporter_threshold == 0 || self.reporter_threshold as usize > reporters.len() {
+            return Err(format!("reporter threshold must be within 1..={} (set --oracle-reporters)", reporters.len()));
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"peg_value\": {}, \"quorum_bps\": {}, \"approval_bps\": {}, \"oracle_reporters\": {}, \"reporter_threshold\": {}, \"fee_bps\": {}}}",
+            self.peg_value,
+            self.quorum_bps,
+            self.approval_bps,
+            address_list(&self.oracle_reporters),
+            self.reporter_threshold,
+            self.fee_bps
+        )
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "deploy", about = "Deploy the Pi Coin contracts (token, oracle, governance, treasury)")]
+struct Args {
+    /// Network to deploy to; also selects the configuration profile
+    #[arg(long, value_enum, default_value = "testnet")]
+    network: Network,
+
+    /// Override the profile's peg value (micro-units)
+    #[arg(long)]
+    peg_value: Option<i128>,
+
+    /// Override the profile's governance quorum (basis points)
+    #[arg(long)]
+    quorum_bps: Option<u32>,
+
+    /// Override the profile's governance approval threshold (basis points)
+    #[arg(long)]
+    approval_bps: Option<u32>,
+
+    /// Comma-separated oracle reporter addresses, replacing the profile's set
+    #[arg(long, value_delimiter = ',')]
+    oracle_reporters: Vec<String>,
+
+    /// Override the number of reporters needed to accept a price
+    #[arg(long)]
+    reporter_threshold: Option<u32>,
+
+    /// Override the profile's transfer fee (basis points)
+    #[arg(long)]
+    fee_bps: Option<u32>,
+
+    /// Where to write the deployment manifest; defaults to deployment-<network>-<salt>.json
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+
+    /// `stellar keys` identity (or, through the environment only, an S... secret key) that signs every transaction and becomes admin of all contracts unless a multisig is deployed
+    #[arg(long, env = "STELLAR_ACCOUNT", hide_env_values = true)]
+    source_account: String,
+
+    /// Collateral asset contract backing PI 1:1 (e.g. the USDC SAC address)
+    #[arg(long)]
+    collateral: String,
+
+    /// Optional initial PI price pushed to the oracle after deployment (micro-units)
+    #[arg(long, conflicts_with = "multisig_owners")]
+    initial_price: Option<i128>,
+
+    /// Comma-separated owner addresses of a multisig to deploy as the ecosystem admin
+    #[arg(long, value_delimiter = ',', requires = "multisig_threshold")]
+    multisig_owners: Vec<String>,
+
+    /// Owner confirmations the multisig requires (k of n)
+    #[arg(long, requires = "multisig_owners")]
+    multisig_threshold: Option<u32>,
+
+    /// Override the RPC endpoint of the selected network
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    /// Network passphrase, required together with --rpc-url
+    #[arg(long, requires = "rpc_url")]
+    network_passphrase: Option<String>,
+
+    /// PiCoinContract wasm
+    #[arg(long, default_value = "target/wasm32v1-none/release/pi_coin.wasm")]
+    token_wasm: PathBuf,
+
+    /// PiCoinOracle wasm
+    #[arg(long, default_value = "target/wasm32v1-none/release/pi_coin_oracle.wasm")]
+    oracle_wasm: PathBuf,
+
+    /// PiCoinGovernance wasm
+    #[arg(long, default_value = "target/wasm32v1-none/release/pi_coin_governance.wasm")]
+    governance_wasm: PathBuf,
+
+    /// PiCoinTreasury wasm
+    #[arg(long, default_value = "target/wasm32v1-none/release/pi_coin_treasury.wasm")]
+    treasury_wasm: PathBuf,
+
+    /// PiCoinMultisig wasm
+    #[arg(long, default_value = "target/wasm32v1-none/release/pi_coin_multisig.wasm")]
+    multisig_wasm: PathBuf,
+
+    /// Base deployment salt; defaults to the current time in nanoseconds. Reusing it reproduces the same contract ids
+    #[arg(long)]
+    salt: Option<u128>,
+
+    /// Path to the stellar CLI executable
+    #[arg(long, default_value = "stellar")]
+    stellar_bin: String,
+}
+
+struct Deployer {
+    args: Args,
+}
+
+impl Deployer {
+    // Flags shared by every transaction: network selection. The signer is passed through
+    // the environment (see `stellar`)
+    fn tx_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        match (&self.args.rpc_url, &self.args.network_passphrase) {
+            (Some(rpc_url), Some(passphrase)) => {
+                flags.extend(["--rpc-url".to_string(), rpc_url.clone()]);
+                flags.extend(["--network-passphrase".to_string(), passphrase.clone()]);
+            }
+            _ => flags.extend(["--network".to_string(), self.args.network.name().to_string()]),
+        }
+        flags
+    }
+
+    // G... address of the source account: derived locally from an S... secret, otherwise
+    // looked up for the `stellar keys` identity
+    fn source_address(&self) -> String {
+        match stellar_strkey::ed25519::PrivateKey::from_string(&self.args.source_account) {
+            Ok(private_key) => {
+                let signing_key = ed25519_dalek::SigningKey::from_bytes(&private_key.0);
+                stellar_strkey::ed25519::PublicKey(signing_key.verifying_key().to_bytes()).to_string()
+            }
+            Err(_) => self.stellar(&["keys".to_string(), "address".to_string(), self.args.source_account.clone()]),
+        }
+    }
+
+    // Run `stellar <args>` signing as the source account and return its trimmed stdout,
+    // aborting on failure
+    fn stellar(&self, args: &[String]) -> String {
+        let output = Command::new(&self.args.stellar_bin)
+            .args(args)
+            .env("STELLAR_ACCOUNT", &self.args.source_account)
+            .output()
+            .unwrap_or_else(|err| fail(&format!("could not run {}: {err}", self.args.stellar_bin)));
+        if !output.status.success() {
+            fail(&format!(
+                "`stellar {}` failed:\n{}",
+                args.first().map(String::as_str).unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    // Install wasm on the network and return its hash
+    fn upload(&self, wasm: &Path) -> String {
+        if !wasm.exists() {
+            fail(&format!("wasm not found: {} (build the contracts first)", wasm.display()));
+        }
+        let mut args = vec!["contract".to_string(), "upload".to_string(), "--wasm".to_string(), wasm.display().to_string()];
+        args.extend(self.tx_flags());
+        self.stellar(&args)
+    }
+
+    // Contract id a deployment from the admin account with `salt` will get
+    fn contract_id(&self, salt: &str) -> String {
+        let mut args = vec!["contract".to_string(), "id".to_string(), "wasm".to_string(), "--salt".to_string(), salt.to_string()];
+        args.extend(self.tx_flags());
+        self.stellar(&args)
+    }
+
+    // Create a contract instance from an uploaded wasm hash, running its constructor with
+    // `--name value` arguments, and return its address
+    fn deploy(&self, wasm_hash: &str, salt: &str, constructor_args: &[(&str, String)]) -> String {
+        let mut args = vec![
+            "contract".to_string(),
+            "deploy".to_string(),
+            "--wasm-hash".to_string(),
+            wasm_hash.to_string(),
+            "--salt".to_string(),
+            salt.to_string(),
+        ];
+        args.extend(self.tx_flags());
+        args.push("--".to_string());
+        push_fn_args(&mut args, constructor_args);
+        self.stellar(&args)
+    }
+
+    // Invoke `function` on `contract_id` with `--name value` arguments
+    fn invoke(&self, contract_id: &str, function: &str, fn_args: &[(&str, String)]) -> String {
+        let mut args = vec!["contract".to_string(), "invoke".to_string(), "--id".to_string(), contract_id.to_string()];
+        args.extend(self.tx_flags());
+        args.push("--".to_string());
+        args.push(function.to_string());
+        push_fn_args(&mut args, fn_args);
+        self.stellar(&args)
+    }
+}
+
+fn push_fn_args(args: &mut Vec<String>, fn_args: &[(&str, String)]) {
+    for (name, value) in fn_args {
+        args.push(format!("--{name}"));
+        args.push(value.clone());
+    }
+}
+
+// 32-byte hex salt for the `index`th contract of a deployment
+fn contract_salt(base: u128, index: u32) -> String {
+    format!("{base:056x}{index:08x}")
+}
+
+// Constructor value for a Vec<Address> argument
+fn address_list(addresses: &[String]) -> String {
+    let quoted: Vec<String> = addresses.iter().map(|address| format!("\"{address}\"")).collect();
+    format!("[{}]", quoted.join(","))
+}
+
+// The network's profile with any command-line overrides applied
+fn resolve_profile(args: &Args, admin: &str) -> Profile {
+    let mut profile = args.network.profile(admin);
+    profile.peg_value = args.peg_value.unwrap_or(profile.peg_value);
+    profile.quorum_bps = args.quorum_bps.unwrap_or(profile.quorum_bps);
+    profile.approval_bps = args.approval_bps.unwrap_or(profile.approval_bps);
+    if !args.oracle_reporters.is_empty() {
+        profile.oracle_reporters = args.oracle_reporters.clone();
+    }
+    profile.reporter_threshold = args.reporter_threshold.unwrap_or(profile.reporter_threshold);
+    profile.fee_bps = args.fee_bps.unwrap_or(profile.fee_bps);
+    profile
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {message}");
+    exit(1)
+}
+
+fn main() {
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    // A secret typed on the command line would sit in the process list of both this CLI
+    // and every `stellar` call
+    if matches.value_source("source_account") == Some(ValueSource::CommandLine)
+        && stellar_strkey::ed25519::PrivateKey::from_string(&args.source_account).is_ok()
+    {
+        fail("pass a secret key through STELLAR_ACCOUNT or `stellar keys add`, not --source-account");
+    }
+    let deployer = Deployer { args };
+    let args = &deployer.args;
+    let deploying_key = deployer.source_address();
+    println!("Deploying Pi Coin ecosystem to {} from {deploying_key}", args.network.name());
+
+    // Checked before any upload, so a bad profile costs no fees
+    let profile = resolve_profile(args, &deploying_key);
+    if let Err(reason) = profile.validate() {
+        fail(&format!("invalid {} profile: {reason}", args.network.name()));
+    }
+    println!("Profile:    {profile:?}");
+
+    let token_hash = deployer.upload(&args.token_wasm);
+    let oracle_hash = deployer.upload(&args.oracle_wasm);
+    let governance_hash = deployer.upload(&args.governance_wasm);
+    let treasury_hash = deployer.upload(&args.treasury_wasm);
+
+    let base_salt = args.salt.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_nanos()).unwrap_or_default()
+    });
+    println!("Deployment salt: {base_salt}");
+    let salts: Vec<String> = (0..5).map(|index| contract_salt(base_salt, index)).collect();
+    let token = deployer.contract_id(&salts[0]);
+    let oracle = deployer.contract_id(&salts[1]);
+    let governance = deployer.contract_id(&salts[2]);
+    let treasury = deployer.contract_id(&salts[3]);
+
+    // The multisig, when requested, is deployed first so it can be every contract's admin
+    let admin = match args.multisig_threshold {
+        Some(threshold) => {
+            let multisig_hash = deployer.upload(&args.multisig_wasm);
+            deployer.deploy(
+                &multisig_hash,
+                &salts[4],
+                &[("owners", address_list(&args.multisig_owners)), ("threshold", threshold.to_string())],
+            )
+        }
+        None => deploying_key,
+    };
+    println!("Admin:      {admin}");
+
+    // Each constructor is wired with the others' (precomputed) addresses
+    deployer.deploy(
+        &token_hash,
+        &salts[0],
+        &[
+            ("admin", admin.clone()),
+            ("collateral_asset", args.collateral.clone()),
+            ("oracle", oracle.clone()),
+            ("governance", governance.clone()),
+        ],
+    );
+    deployer.deploy(&oracle_hash, &salts[1], &[("admin", admin.clone()), ("governance", governance.clone())]);
+    deployer.deploy(
+        &governance_hash,
+        &salts[2],
+        &[("admin", admin.clone()), ("pi_token", token.clone()), ("treasury", treasury.clone())],
+    );
+    deployer.deploy(&treasury_hash, &salts[3], &[("governance", governance.clone()), ("pi_token", token.clone())]);
+
+    if let Some(price) = args.initial_price {
+        deployer.invoke(
+            &oracle,
+            "update_price",
+            &[("updater", admin.clone()), ("asset", "PI".to_string()), ("raw_price", price.to_string())],
+        );
+    }
+
+    println!("Main:       {token}");
+    println!("Oracle:     {oracle}");
+    println!("Governance: {governance}");
+    println!("Treasury:   {treasury}");
+
+    let manifest = format!(
+        "{{\n  \"network\": \"{}\",\n  \"salt\": \"{base_salt}\",\n  \"profile\": {},\n  \"admin\": \"{admin}\",\n  \"collateral\": \"{}\",\n  \"contracts\": {{\"token\": \"{token}\", \"oracle\": \"{oracle}\", \"governance\": \"{governance}\", \"treasury\": \"{treasury}\"}},\n  \"wasm_hashes\": [\"{token_hash}\", \"{oracle_hash}\", \"{governance_hash}\", \"{treasury_hash}\"]\n}}\n",
+        args.network.name(),
+        profile.to_json(),
+        args.collateral,
+    );
+    let manifest_path = args
+        .manifest
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("deployment-{}-{base_salt}.json", args.network.name())));
+    fs::write(&manifest_path, manifest)
+        .unwrap_or_else(|err| fail(&format!("could not write {}: {err}", manifest_path.display())));
+    println!("Manifest:   {}", manifest_path.display());
+}
@@ -0,0 +1,371 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, Address, Env, Symbol, Vec, log, vec, Bytes, BytesN, IntoVal, Val};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct DeployerData {
+    pub admin: Address,
+    pub token_wasm_hash: BytesN<32>, // Uploaded PiCoinContract wasm
+    pub oracle_wasm_hash: BytesN<32>, // Uploaded PiCoinOracle wasm
+    pub governance_wasm_hash: BytesN<32>, // Uploaded PiCoinGovernance wasm
+    pub treasury_wasm_hash: BytesN<32>, // Uploaded PiCoinTreasury wasm
+}
+
+// Addresses of one deployed (or predicted) ecosystem
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ecosystem {
+    pub token: Address,
+    pub oracle: Address,
+    pub governance: Address,
+    pub treasury: Address,
+}
+
+// Highest transfer fee PiCoinContract accepts, in basis points
+const MAX_FEE_BPS: u32 = 1_000;
+// Oldest last-valid oracle price `ecosystem_health` reports as fresh, in seconds
+pub const MAX_PRICE_AGE: u64 = 60 * 60;
+
+// PiCoinContract's compliance mode and config view, mirrored for its client below
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComplianceMode {
+    Open,
+    Blocklist,
+    Allowlist,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiCoinConfig {
+    pub symbol: Symbol,
+    pub admin: Address,
+    pub collateral_asset: Address,
+    pub oracle_address: Address,
+    pub governance_address: Address,
+    pub fee_bps: u32,
+    pub fee_collector: Option<Address>,
+    pub compliance_mode: ComplianceMode,
+    pub version: u32,
+}
+
+// PiCoinContract's last-valid oracle price, mirrored for its client below
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OraclePrice {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Wiring views of PiCoinContract
+#[contractclient(name = "PiTokenClient")]
+pub trait PiToken {
+    fn config(env: Env) -> PiCoinConfig;
+    fn last_oracle_price(env: Env) -> Option<OraclePrice>;
+}
+
+// Wiring views of PiCoinOracle
+#[contractclient(name = "OracleWiringClient")]
+pub trait OracleWiring {
+    fn governance(env: Env) -> Address;
+}
+
+// Wiring views of PiCoinGovernance
+#[contractclient(name = "GovernanceWiringClient")]
+pub trait GovernanceWiring {
+    fn pi_token(env: Env) -> Address;
+    fn treasury(env: Env) -> Address;
+}
+
+// Wiring views of PiCoinTreasury
+#[contractclient(name = "TreasuryWiringClient")]
+pub trait TreasuryWiring {
+    fn governance(env: Env) -> Address;
+    fn pi_token(env: Env) -> Address;
+}
+
+// Per-deployment parameters wired into the ecosystem contracts
+#[contracttype]
+#[derive(Clone)]
+pub struct DeployConfig {
+    pub admin: Address,
+    pub collateral: Address,
+    pub profile: Symbol, // Network profile to launch with, e.g. "testnet" or "mainnet"
+}
+
+// Launch parameters for one network, registered by the factory admin. Deployments
+// record the profile in their "deployment_manifest" event, so the same parameters can
+// be replayed on another network or checked against the live contracts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NetworkProfile {
+    pub peg_value: i128, // Micro-units
+    pub quorum_bps: u32, // Governance participation a standard proposal needs
+    pub approval_bps: u32, // Share of votes in favour a standard proposal needs
+    pub oracle_reporters: Vec<Address>, // Accounts trusted to report prices
+    pub reporter_threshold: u32, // Reports needed before a price is accepted
+    pub fee_bps: u32, // Transfer fee
+    pub fee_collector: Option<Address>,
+}
+
+// What one deployment ran with: published as the "deployment_manifest" event
+#[contracttype]
+#[derive(Clone)]
+pub struct DeploymentManifest {
+    pub salt: BytesN<32>,
+    pub profile_name: Symbol,
+    pub profile: NetworkProfile,
+    pub admin: Address,
+    pub collateral: Address,
+    pub ecosystem: Ecosystem,
+    pub wasm_hashes: Vec<BytesN<32>>, // Token, oracle, governance, treasury
+}
+
+// Result of `ecosystem_health`: each check on its own, and whether all of them passed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthReport {
+    pub ecosystem: Ecosystem,
+    pub deployed: bool, // All four contracts exist; the other checks are false otherwise
+    pub oracle_wired: bool, // The token reads prices from the deployed oracle
+    pub governance_wired: bool, // Governance controls the token, oracle and treasury, and targets this token and treasury
+    pub collateral_wired: bool, // The token is backed by the deployment's collateral asset
+    pub treasury_wired: bool, // The treasury tracks this token as its PI
+    pub price_age: Option<u64>, // Seconds since the token's last valid oracle price
+    pub oracle_fresh: bool, // That price is at most MAX_PRICE_AGE old
+    pub healthy: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DeployerKey {
+    UsedSalt(BytesN<32>), // Ecosystem salts already deployed under
+    Profile(Symbol), // Persistent: NetworkProfile by name
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DeployerError {
+    Unauthorized = 1,
+    SaltAlreadyUsed = 2,
+    AlreadyInitialized = 3,
+    InvalidProfile = 4,
+    UnknownProfile = 5,
+    NotDeployed = 6,
+}
+
+#[contract]
+pub struct PiCoinDeployer;
+
+#[contractimpl]
+impl PiCoinDeployer {
+    // Initialize the factory with the uploaded wasm hashes of the ecosystem contracts
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token_wasm_hash: BytesN<32>,
+        oracle_wasm_hash: BytesN<32>,
+        governance_wasm_hash: BytesN<32>,
+        treasury_wasm_hash: BytesN<32>,
+    ) -> Result<(), DeployerError> {
+        if env.storage().instance().has(&Symbol::new(&env, "deployer_data")) {
+            return Err(DeployerError::AlreadyInitialized);
+        }
+        admin.require_auth();
+        let data = DeployerData {
+            admin,
+            token_wasm_hash,
+            oracle_wasm_hash,
+            governance_wasm_hash,
+            treasury_wasm_hash,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "deployer_data"), &data);
+        log!(&env, "Pi Coin factory initialized with ecosystem wasm hashes");
+        Ok(())
+    }
+
+    // Admin-only: Register or replace the network profile `name`
+    pub fn set_profile(env: Env, name: Symbol, profile: NetworkProfile) -> Result<(), DeployerError> {
+        let data: DeployerData = env.storage().instance().get(&Symbol::new(&env, "deployer_data")).unwrap();
+        data.admin.require_auth();
+        Self::validate_profile(&profile)?;
+        env.storage().persistent().set(&DeployerKey::Profile(name.clone()), &profile);
+        env.events().publish((Symbol::new(&env, "profile_set"), name), profile);
+        Ok(())
+    }
+
+    // View: Network profile `name`, if registered
+    pub fn profile(env: Env, name: Symbol) -> Option<NetworkProfile> {
+        env.storage().persistent().get(&DeployerKey::Profile(name))
+    }
+
+    // Hyper-tech deployment: deploy token, oracle, governance and treasury from this
+    // factory with each wired into the others' constructor, so no contract is ever live
+    // and uninitialized. Each salt can be used once. The named profile is checked again
+    // before anything is deployed and published with the addresses as the
+    // "deployment_manifest" event.
+    pub fn deploy_with_salt(env: Env, salt: BytesN<32>, config: DeployConfig) -> Result<Ecosystem, DeployerError> {
+        let data: DeployerData = env.storage().instance().get(&Symbol::new(&env, "deployer_data")).unwrap();
+        data.admin.require_auth();
+        let profile = Self::profile(env.clone(), config.profile.clone()).ok_or(DeployerError::UnknownProfile)?;
+        Self::validate_profile(&profile)?;
+
+        let used_key = DeployerKey::UsedSalt(salt.clone());
+        if env.storage().persistent().has(&used_key) {
+            return Err(DeployerError::SaltAlreadyUsed);
+        }
+        env.storage().persistent().set(&used_key, &true);
+
+        // Every address is known up front, so each constructor receives the others' addresses
+        let eco = Self::predict_addresses(env.clone(), salt.clone());
+        let admin = config.admin;
+        Self::deploy_one(
+            &env,
+            &data.token_wasm_hash,
+            &salt,
+            b"token",
+            vec![&env, admin.into_val(&env), config.collateral.into_val(&env), eco.oracle.into_val(&env), eco.governance.into_val(&env)],
+        );
+        Self::deploy_one(&env, &data.oracle_wasm_hash, &salt, b"oracle", vec![&env, admin.into_val(&env), eco.governance.into_val(&env)]);
+        Self::deploy_one(
+            &env,
+            &data.governance_wasm_hash,
+            &salt,
+            b"governance",
+            vec![&env, admin.into_val(&env), eco.token.into_val(&env), eco.treasury.into_val(&env)],
+        );
+        Self::deploy_one(&env, &data.treasury_wasm_hash, &salt, b"treasury", vec![&env, eco.governance.into_val(&env), eco.token.into_val(&env)]);
+
+        env.events().publish((Symbol::new(&env, "ecosystem_deployed"), admin.clone()), eco.clone());
+        let manifest = DeploymentManifest {
+            salt,
+            profile_name: config.profile.clone(),
+            profile,
+            admin,
+            collateral: config.collateral,
+            ecosystem: eco.clone(),
+            wasm_hashes: vec![&env, data.token_wasm_hash, data.oracle_wasm_hash, data.governance_wasm_hash, data.treasury_wasm_hash],
+        };
+        env.storage().instance().set(&Symbol::new(&env, "last_deployment"), &manifest);
+        env.events().publish((Symbol::new(&env, "deployment_manifest"), config.profile), manifest);
+        log!(&env, "Pi Coin ecosystem deployed: Main {}, Oracle {}, Governance {}, Treasury {} - Worldwide payment ready", eco.token, eco.oracle, eco.governance, eco.treasury);
+        Ok(eco)
+    }
+
+    // View: PI token address `deploy_with_salt(salt, ..)` will produce from this factory.
+    // The address is derived from the network id, factory address and salt, so it is
+    // fixed per network but differs between networks even for the same factory and salt.
+    pub fn predict_address(env: Env, salt: BytesN<32>) -> Address {
+        Self::predict(&env, &salt, b"token")
+    }
+
+    // View: Predicted addresses of every ecosystem contract for a salt
+    pub fn predict_addresses(env: Env, salt: BytesN<32>) -> Ecosystem {
+        Ecosystem {
+            token: Self::predict(&env, &salt, b"token"),
+            oracle: Self::predict(&env, &salt, b"oracle"),
+            governance: Self::predict(&env, &salt, b"governance"),
+            treasury: Self::predict(&env, &salt, b"treasury"),
+        }
+    }
+
+    // View: Manifest of the most recent `deploy_with_salt`
+    pub fn last_deployment(env: Env) -> Option<DeploymentManifest> {
+        env.storage().instance().get(&Symbol::new(&env, "last_deployment"))
+    }
+
+    // View: Cross-check the wiring of the most recent deployment before funds move into it:
+    // the token's oracle, governance and collateral, governance's token and treasury, the
+    // treasury's PI and governance, and the age of the token's last valid oracle price
+    pub fn ecosystem_health(env: Env) -> Result<HealthReport, DeployerError> {
+        let manifest = Self::last_deployment(env.clone()).ok_or(DeployerError::NotDeployed)?;
+        let eco = manifest.ecosystem;
+        let mut report = HealthReport {
+            ecosystem: eco.clone(),
+            deployed: false,
+            oracle_wired: false,
+            governance_wired: false,
+            collateral_wired: false,
+            treasury_wired: false,
+            price_age: None,
+            oracle_fresh: false,
+            healthy: false,
+        };
+        report.deployed = eco.token.exists() && eco.oracle.exists() && eco.governance.exists() && eco.treasury.exists();
+        if !report.deployed {
+            return Ok(report);
+        }
+
+        let token = PiTokenClient::new(&env, &eco.token);
+        let governance = GovernanceWiringClient::new(&env, &eco.governance);
+        let treasury = TreasuryWiringClient::new(&env, &eco.treasury);
+        let config = token.config();
+        report.oracle_wired = config.oracle_address == eco.oracle;
+        report.governance_wired = config.governance_address == eco.governance
+            && OracleWiringClient::new(&env, &eco.oracle).governance() == eco.governance
+            && treasury.governance() == eco.governance
+            && governance.pi_token() == eco.token
+            && governance.treasury() == eco.treasury;
+        report.collateral_wired = config.collateral_asset == manifest.collateral;
+        report.treasury_wired = treasury.pi_token() == eco.token;
+        report.price_age = token.last_oracle_price().map(|last| env.ledger().timestamp().saturating_sub(last.timestamp));
+        report.oracle_fresh = report.price_age.is_some_and(|age| age <= MAX_PRICE_AGE);
+        report.healthy = report.oracle_wired && report.governance_wired && report.collateral_wired && report.treasury_wired && report.oracle_fresh;
+        Ok(report)
+    }
+
+    // View: Whether the factory has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&Symbol::new(&env, "deployer_data"))
+    }
+
+    // View: Whether an ecosystem salt has already been deployed under
+    pub fn is_salt_used(env: Env, salt: BytesN<32>) -> bool {
+        env.storage().persistent().has(&DeployerKey::UsedSalt(salt))
+    }
+
+    // Helper: A positive peg, thresholds governance would accept (quorum up to 100%, approval
+    // from a simple majority up to but excluding 100%), a fee within the token's cap, and a
+    // reporter threshold that the distinct reporters can meet
+    fn validate_profile(profile: &NetworkProfile) -> Result<(), DeployerError> {
+        let reporters = &profile.oracle_reporters;
+        let distinct = reporters.iter().enumerate().all(|(i, reporter)| reporters.first_index_of(&reporter) == Some(i as u32));
+        if profile.peg_value <= 0
+            || profile.quorum_bps > 10_000
+            || profile.approval_bps < 5_000
+            || profile.approval_bps >= 10_000
+            || profile.fee_bps > MAX_FEE_BPS
+            || !distinct
+            || profile.reporter_threshold == 0
+            || profile.reporter_threshold > reporters.len()
+        {
+            return Err(DeployerError::InvalidProfile);
+        }
+        Ok(())
+    }
+
+    // Helper: Deploy one contract under a salt derived from the ecosystem salt and a tag,
+    // running its constructor with `constructor_args`
+    fn deploy_one(env: &Env, wasm_hash: &BytesN<32>, salt: &BytesN<32>, tag: &[u8], constructor_args: Vec<Val>) -> Address {
+        env.deployer()
+            .with_current_contract(Self::derive_salt(env, salt, tag))
+            .deploy_v2(wasm_hash.clone(), constructor_args)
+    }
+
+    // Helper: Address a contract deployed by `deploy_one` with the same inputs will get
+    fn predict(env: &Env, salt: &BytesN<32>, tag: &[u8]) -> Address {
+        env.deployer()
+            .with_current_contract(Self::derive_salt(env, salt, tag))
+            .deployed_address()
+    }
+
+    // Helper: sha256(salt || tag)
+    fn derive_salt(env: &Env, salt: &BytesN<32>, tag: &[u8]) -> BytesN<32> {
+        let mut seed = Bytes::from_array(env, &salt.to_array());
+        seed.extend_from_slice(tag);
+        env.crypto().sha256(&seed).into()
+    }
+}
+
+mod test;
@@ -0,0 +1,184 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, symbol_short, testutils::{Address as _, Ledger}, vec, Address, BytesN, Env, Symbol};
+use crate::{
+    ComplianceMode, DeployConfig, DeployerData, DeployerError, DeploymentManifest, Ecosystem, NetworkProfile, OraclePrice, PiCoinConfig,
+    PiCoinDeployer, PiCoinDeployerClient,
+};
+
+// Stands in for PiCoinContract's wiring views
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn wire(env: Env, config: PiCoinConfig, last_price: Option<OraclePrice>) {
+        env.storage().instance().set(&symbol_short!("config"), &config);
+        env.storage().instance().set(&symbol_short!("price"), &last_price);
+    }
+
+    pub fn config(env: Env) -> PiCoinConfig {
+        env.storage().instance().get(&symbol_short!("config")).unwrap()
+    }
+
+    pub fn last_oracle_price(env: Env) -> Option<OraclePrice> {
+        env.storage().instance().get(&symbol_short!("price")).unwrap()
+    }
+}
+
+// Stands in for the oracle, governance and treasury wiring views
+#[contract]
+pub struct MockPeer;
+
+#[contractimpl]
+impl MockPeer {
+    pub fn wire(env: Env, governance: Address, pi_token: Address, treasury: Address) {
+        env.storage().instance().set(&symbol_short!("wiring"), &(governance, pi_token, treasury));
+    }
+
+    pub fn governance(env: Env) -> Address {
+        env.storage().instance().get::<_, (Address, Address, Address)>(&symbol_short!("wiring")).unwrap().0
+    }
+
+    pub fn pi_token(env: Env) -> Address {
+        env.storage().instance().get::<_, (Address, Address, Address)>(&symbol_short!("wiring")).unwrap().1
+    }
+
+    pub fn treasury(env: Env) -> Address {
+        env.storage().instance().get::<_, (Address, Address, Address)>(&symbol_short!("wiring")).unwrap().2
+    }
+}
+
+#[test]
+fn test_second_initialize_is_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(PiCoinDeployer, ());
+    let client = PiCoinDeployerClient::new(&env, &contract_id);
+    assert!(!client.is_initialized());
+
+    let hash = BytesN::from_array(&env, &[1; 32]);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &hash, &hash, &hash, &hash);
+    assert!(client.is_initialized());
+
+    // A different admin cannot take over the factory by initializing again
+    let attacker = Address::generate(&env);
+    let other_hash = BytesN::from_array(&env, &[2; 32]);
+    assert!(client.try_initialize(&attacker, &other_hash, &other_hash, &other_hash, &other_hash).is_err());
+    env.as_contract(&contract_id, || {
+        let data: DeployerData = env.storage().instance().get(&Symbol::new(&env, "deployer_data")).unwrap();
+        assert_eq!(data.admin, admin);
+        assert_eq!(data.token_wasm_hash, hash);
+    });
+}
+
+#[test]
+fn test_profiles_are_validated_before_deployment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = PiCoinDeployerClient::new(&env, &env.register(PiCoinDeployer, ()));
+    let hash = BytesN::from_array(&env, &[1; 32]);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &hash, &hash, &hash, &hash);
+
+    let reporter = Address::generate(&env);
+    let profile = NetworkProfile {
+        peg_value: 314_159_000_000,
+        quorum_bps: 1_000,
+        approval_bps: 6_666,
+        oracle_reporters: vec![&env, reporter.clone(), Address::generate(&env)],
+        reporter_threshold: 2,
+        fee_bps: 10,
+        fee_collector: None,
+    };
+    client.set_profile(&symbol_short!("mainnet"), &profile);
+    assert_eq!(client.profile(&symbol_short!("mainnet")), Some(profile.clone()));
+    assert_eq!(client.profile(&symbol_short!("testnet")), None);
+
+    let rejected = |profile: NetworkProfile| {
+        matches!(client.try_set_profile(&symbol_short!("bad"), &profile), Err(Ok(DeployerError::InvalidProfile)))
+    };
+    assert!(rejected(NetworkProfile { peg_value: 0, ..profile.clone() }));
+    assert!(rejected(NetworkProfile { approval_bps: 4_999, ..profile.clone() }));
+    assert!(rejected(NetworkProfile { fee_bps: 1_001, ..profile.clone() }));
+    assert!(rejected(NetworkProfile { reporter_threshold: 3, ..profile.clone() }));
+    assert!(rejected(NetworkProfile { oracle_reporters: vec![&env, reporter.clone(), reporter], ..profile.clone() }));
+    assert_eq!(client.profile(&symbol_short!("bad")), None);
+
+    // Deploying needs a registered profile, and nothing is consumed when it is missing
+    let salt = BytesN::from_array(&env, &[9; 32]);
+    let config = DeployConfig { admin, collateral: Address::generate(&env), profile: symbol_short!("testnet") };
+    assert!(matches!(client.try_deploy_with_salt(&salt, &config), Err(Ok(DeployerError::UnknownProfile))));
+    assert!(!client.is_salt_used(&salt));
+}
+
+#[test]
+fn test_ecosystem_health_flags_miswired_contracts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+    let contract_id = env.register(PiCoinDeployer, ());
+    let client = PiCoinDeployerClient::new(&env, &contract_id);
+    assert!(matches!(client.try_ecosystem_health(), Err(Ok(DeployerError::NotDeployed))));
+
+    let token = MockTokenClient::new(&env, &env.register(MockToken, ()));
+    let oracle = MockPeerClient::new(&env, &env.register(MockPeer, ()));
+    let governance = MockPeerClient::new(&env, &env.register(MockPeer, ()));
+    let treasury = MockPeerClient::new(&env, &env.register(MockPeer, ()));
+    let eco = Ecosystem {
+        token: token.address.clone(),
+        oracle: oracle.address.clone(),
+        governance: governance.address.clone(),
+        treasury: treasury.address.clone(),
+    };
+    let collateral = Address::generate(&env);
+    let manifest = DeploymentManifest {
+        salt: BytesN::from_array(&env, &[9; 32]),
+        profile_name: symbol_short!("testnet"),
+        profile: NetworkProfile {
+            peg_value: 314_159_000_000,
+            quorum_bps: 400,
+            approval_bps: 5_000,
+            oracle_reporters: vec![&env, Address::generate(&env)],
+            reporter_threshold: 1,
+            fee_bps: 0,
+            fee_collector: None,
+        },
+        admin: Address::generate(&env),
+        collateral: collateral.clone(),
+        ecosystem: eco.clone(),
+        wasm_hashes: vec![&env],
+    };
+    env.as_contract(&contract_id, || env.storage().instance().set(&Symbol::new(&env, "last_deployment"), &manifest));
+
+    let config = PiCoinConfig {
+        symbol: Symbol::new(&env, "PI"),
+        admin: manifest.admin.clone(),
+        collateral_asset: collateral,
+        oracle_address: eco.oracle.clone(),
+        governance_address: eco.governance.clone(),
+        fee_bps: 0,
+        fee_collector: None,
+        compliance_mode: ComplianceMode::Open,
+        version: 4,
+    };
+    token.wire(&config, &Some(OraclePrice { price: 314_159_000_000, timestamp: 9_000 }));
+    for peer in [&oracle, &governance, &treasury] {
+        peer.wire(&eco.governance, &eco.token, &eco.treasury);
+    }
+    let report = client.ecosystem_health();
+    assert!(report.deployed && report.healthy);
+    assert_eq!(report.price_age, Some(1_000));
+
+    // A token pointed at another oracle, and a price past MAX_PRICE_AGE
+    token.wire(&PiCoinConfig { oracle_address: Address::generate(&env), ..config.clone() }, &Some(OraclePrice { price: 1, timestamp: 5_000 }));
+    let report = client.ecosystem_health();
+    assert!(!report.oracle_wired && !report.oracle_fresh && !report.healthy);
+    assert!(report.governance_wired && report.collateral_wired && report.treasury_wired);
+
+    // A treasury built for a different token, and no price recorded yet
+    token.wire(&config, &None);
+    treasury.wire(&eco.governance, &Address::generate(&env), &eco.treasury);
+    let report = client.ecosystem_health();
+    assert!(report.oracle_wired && !report.treasury_wired && report.price_age.is_none() && !report.healthy);
+}
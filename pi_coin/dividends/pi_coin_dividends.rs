@@ -0,0 +1,209 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Fixed-point scale of an epoch's revenue per PI
+pub const MAGNITUDE: i128 = 1_000_000_000_000_000_000;
+
+// Snapshot views of PiCoinContract. Both answer for closed ledgers inside the token's
+// snapshot retention, which must outlast the claim window.
+#[contractclient(name = "PiSnapshotTokenClient")]
+pub trait PiSnapshotToken {
+    fn balance_at(env: Env, holder: Address, ledger: u32) -> i128;
+    fn circulating_at(env: Env, ledger: u32) -> i128;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct DistributorData {
+    pub governance: Address, // Sets the claim window
+    pub pi_token: Address, // Balances are snapshotted here
+    pub treasury: Address, // Receives what is left unclaimed
+    pub claim_window: u32, // Ledgers an epoch stays claimable
+    pub epoch_count: u32,
+}
+
+// One revenue deposit, shared by PI holders as of its snapshot ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Epoch {
+    pub asset: Address, // Revenue token (SEP-41)
+    pub revenue: i128,
+    pub snapshot_ledger: u32,
+    pub supply: i128, // Circulating PI at the snapshot
+    pub magnitude: i128, // Revenue per PI, scaled by MAGNITUDE
+    pub claimed: i128,
+    pub expires_ledger: u32, // Last ledger claims are accepted
+    pub swept: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DividendKey {
+    Epoch(u32),
+    Claimed(u32, Address), // (epoch, holder) -> amount paid
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DividendError {
+    InvalidAmount = 1,
+    UnknownEpoch = 2,
+    NothingToClaim = 3,
+    AlreadyClaimed = 4,
+    EpochExpired = 5,
+    EpochNotExpired = 6,
+    AlreadySwept = 7,
+    SnapshotUnavailable = 8,
+}
+
+#[contract]
+pub struct PiCoinDividends;
+
+#[contractimpl]
+impl PiCoinDividends {
+    // Constructor: revenue-sharing distributor. Each deposit opens an epoch that PI
+    // holders share pro rata to their balance at the ledger before the deposit, read from
+    // the token's balance snapshots. An epoch stores its revenue per PI (the magnitude),
+    // so a claim is one snapshot read and one multiplication however many holders there
+    // are. After `claim_window` ledgers whatever is left - unclaimed shares, rounding dust,
+    // and the shares of PI held by contracts that never claim - is swept to the treasury.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, treasury: Address, claim_window: u32) {
+        let data = DistributorData { governance, pi_token, treasury, claim_window, epoch_count: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "dividend_data"), &data);
+        log!(&env, "Dividend distributor initialized: {} ledger claim window", claim_window);
+    }
+
+    // Deposit `amount` of `asset` as revenue for PI holders; returns the new epoch id.
+    // The snapshot is the previous ledger, so PI bought alongside the deposit earns nothing.
+    pub fn deposit(env: Env, from: Address, asset: Address, amount: i128) -> Result<u32, DividendError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(DividendError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        let snapshot_ledger = env.ledger().sequence().checked_sub(1).ok_or(DividendError::SnapshotUnavailable)?;
+        let supply = PiSnapshotTokenClient::new(&env, &data.pi_token)
+            .try_circulating_at(&snapshot_ledger)
+            .ok()
+            .and_then(|supply| supply.ok())
+            .ok_or(DividendError::SnapshotUnavailable)?;
+        if supply <= 0 {
+            return Err(DividendError::NothingToClaim);
+        }
+        let magnitude = amount.checked_mul(MAGNITUDE).ok_or(DividendError::InvalidAmount)? / supply;
+
+        let id = data.epoch_count;
+        let epoch = Epoch {
+            asset: asset.clone(),
+            revenue: amount,
+            snapshot_ledger,
+            supply,
+            magnitude,
+            claimed: 0,
+            expires_ledger: env.ledger().sequence().saturating_add(data.claim_window),
+            swept: false,
+        };
+        data.epoch_count += 1;
+        env.storage().instance().set(&Symbol::new(&env, "dividend_data"), &data);
+        env.storage().persistent().set(&DividendKey::Epoch(id), &epoch);
+        token::TokenClient::new(&env, &asset).transfer(&from, env.current_contract_address(), &amount);
+        env.events().publish((Symbol::new(&env, "dividend_epoch"), id), (asset, amount, snapshot_ledger, supply));
+        Ok(id)
+    }
+
+    // Pay `holder` its share of epoch `id`, once; returns the amount
+    pub fn claim(env: Env, holder: Address, id: u32) -> Result<i128, DividendError> {
+        holder.require_auth();
+        let mut epoch = Self::epoch(env.clone(), id)?;
+        if env.ledger().sequence() > epoch.expires_ledger || epoch.swept {
+            return Err(DividendError::EpochExpired);
+        }
+        let key = DividendKey::Claimed(id, holder.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(DividendError::AlreadyClaimed);
+        }
+        let amount = Self::share(&env, &epoch, &holder)?;
+        if amount == 0 {
+            return Err(DividendError::NothingToClaim);
+        }
+        epoch.claimed += amount;
+        env.storage().persistent().set(&key, &amount);
+        env.storage().persistent().set(&DividendKey::Epoch(id), &epoch);
+        token::TokenClient::new(&env, &epoch.asset).transfer(&env.current_contract_address(), &holder, &amount);
+        env.events().publish((Symbol::new(&env, "dividend_claimed"), holder, id), amount);
+        Ok(amount)
+    }
+
+    // Send what epoch `id` has left to the treasury once its claim window has passed
+    // (permissionless); returns the amount
+    pub fn sweep(env: Env, id: u32) -> Result<i128, DividendError> {
+        let data = Self::load(&env);
+        let mut epoch = Self::epoch(env.clone(), id)?;
+        if env.ledger().sequence() <= epoch.expires_ledger {
+            return Err(DividendError::EpochNotExpired);
+        }
+        if epoch.swept {
+            return Err(DividendError::AlreadySwept);
+        }
+        let amount = epoch.revenue - epoch.claimed;
+        epoch.swept = true;
+        env.storage().persistent().set(&DividendKey::Epoch(id), &epoch);
+        if amount > 0 {
+            token::TokenClient::new(&env, &epoch.asset).transfer(&env.current_contract_address(), &data.treasury, &amount);
+        }
+        env.events().publish((Symbol::new(&env, "dividend_swept"), id), amount);
+        Ok(amount)
+    }
+
+    // Governance-only: Claim window of epochs opened from now on
+    pub fn set_claim_window(env: Env, claim_window: u32) {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        data.claim_window = claim_window;
+        env.storage().instance().set(&Symbol::new(&env, "dividend_data"), &data);
+    }
+
+    // View: What `holder` can claim from epoch `id`; 0 once claimed or expired
+    pub fn claimable(env: Env, holder: Address, id: u32) -> Result<i128, DividendError> {
+        let epoch = Self::epoch(env.clone(), id)?;
+        if env.ledger().sequence() > epoch.expires_ledger || epoch.swept || Self::has_claimed(env.clone(), holder.clone(), id) {
+            return Ok(0);
+        }
+        Self::share(&env, &epoch, &holder)
+    }
+
+    // View: Whether `holder` has claimed epoch `id`
+    pub fn has_claimed(env: Env, holder: Address, id: u32) -> bool {
+        env.storage().persistent().has(&DividendKey::Claimed(id, holder))
+    }
+
+    // View: Epoch `id`
+    pub fn epoch(env: Env, id: u32) -> Result<Epoch, DividendError> {
+        env.storage().persistent().get(&DividendKey::Epoch(id)).ok_or(DividendError::UnknownEpoch)
+    }
+
+    // View: Number of epochs opened so far (ids run 0..epoch_count)
+    pub fn epoch_count(env: Env) -> u32 {
+        Self::load(&env).epoch_count
+    }
+}
+
+impl PiCoinDividends {
+    fn load(env: &Env) -> DistributorData {
+        env.storage().instance().get(&Symbol::new(env, "dividend_data")).unwrap()
+    }
+
+    // Helper: `holder`'s snapshot balance times the epoch's magnitude, rounded down
+    fn share(env: &Env, epoch: &Epoch, holder: &Address) -> Result<i128, DividendError> {
+        let data = Self::load(env);
+        let balance = PiSnapshotTokenClient::new(env, &data.pi_token)
+            .try_balance_at(holder, &epoch.snapshot_ledger)
+            .ok()
+            .and_then(|balance| balance.ok())
+            .ok_or(DividendError::SnapshotUnavailable)?;
+        Ok(balance.checked_mul(epoch.magnitude).ok_or(DividendError::InvalidAmount)? / MAGNITUDE)
+    }
+}
+
+mod test;
@@ -0,0 +1,83 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, contracttype, testutils::{Address as _, Ledger}, token, Address, Env};
+use crate::{DividendError, PiCoinDividends, PiCoinDividendsClient};
+
+// PiCoinContract's snapshot views, answering from balances set per ledger
+#[contracttype]
+enum MockKey {
+    Balance(Address, u32),
+    Supply(u32),
+}
+
+#[contract]
+struct MockPi;
+
+#[contractimpl]
+impl MockPi {
+    pub fn set_balance(env: Env, holder: Address, ledger: u32, amount: i128) {
+        env.storage().instance().set(&MockKey::Balance(holder, ledger), &amount);
+    }
+
+    pub fn set_supply(env: Env, ledger: u32, amount: i128) {
+        env.storage().instance().set(&MockKey::Supply(ledger), &amount);
+    }
+
+    pub fn balance_at(env: Env, holder: Address, ledger: u32) -> i128 {
+        env.storage().instance().get(&MockKey::Balance(holder, ledger)).unwrap_or(0)
+    }
+
+    pub fn circulating_at(env: Env, ledger: u32) -> i128 {
+        env.storage().instance().get(&MockKey::Supply(ledger)).unwrap()
+    }
+}
+
+#[test]
+fn test_revenue_is_shared_by_snapshot_and_swept_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let usdc_client = token::TokenClient::new(&env, &usdc);
+    let pi = MockPiClient::new(&env, &env.register(MockPi, ()));
+    let treasury = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&treasury, &10_000);
+    let contract_id = env.register(PiCoinDividends, (Address::generate(&env), &pi.address, &treasury, 50u32));
+    let client = PiCoinDividendsClient::new(&env, &contract_id);
+
+    // 1_000 PI circulate at the snapshot; a pool that never claims holds 100 of them
+    env.ledger().set_sequence_number(100);
+    pi.set_supply(&99, &1_000);
+    pi.set_balance(&alice, &99, &600);
+    pi.set_balance(&bob, &99, &300);
+    pi.set_balance(&alice, &100, &1_000); // bought alongside the deposit: does not count
+    assert_eq!(client.try_deposit(&treasury, &usdc, &0), Err(Ok(DividendError::InvalidAmount)));
+    assert_eq!(client.deposit(&treasury, &usdc, &1_000), 0);
+    assert_eq!(client.epoch(&0).expires_ledger, 150);
+    assert_eq!((client.claimable(&alice, &0), client.claimable(&bob, &0)), (600, 300));
+
+    assert_eq!(client.claim(&alice, &0), 600);
+    assert_eq!(client.try_claim(&alice, &0), Err(Ok(DividendError::AlreadyClaimed)));
+    assert_eq!(client.claimable(&alice, &0), 0);
+    assert_eq!(client.try_claim(&alice, &1), Err(Ok(DividendError::UnknownEpoch)));
+
+    // Shares round down; the dust stays for the sweep
+    env.ledger().set_sequence_number(150);
+    pi.set_supply(&149, &3);
+    pi.set_balance(&alice, &149, &1);
+    pi.set_balance(&bob, &149, &2);
+    assert_eq!(client.deposit(&treasury, &usdc, &100), 1);
+    assert_eq!((client.claim(&alice, &1), client.claim(&bob, &1)), (33, 66));
+
+    // Epoch 0 closes after ledger 150: Bob's unclaimed 300 and the pool's 100 go back
+    env.ledger().set_sequence_number(151);
+    assert_eq!(client.try_claim(&bob, &0), Err(Ok(DividendError::EpochExpired)));
+    assert_eq!(client.try_sweep(&1), Err(Ok(DividendError::EpochNotExpired)));
+    assert_eq!(client.sweep(&0), 400);
+    assert_eq!(client.try_sweep(&0), Err(Ok(DividendError::AlreadySwept)));
+    env.ledger().set_sequence_number(201);
+    assert_eq!(client.sweep(&1), 1);
+    assert_eq!((usdc_client.balance(&alice), usdc_client.balance(&bob)), (633, 66));
+    assert_eq!((usdc_client.balance(&treasury), usdc_client.balance(&contract_id)), (9_301, 0));
+}
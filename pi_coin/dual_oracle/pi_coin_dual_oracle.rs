@@ -0,0 +1,124 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, Address, Env, Symbol, log};
+
+const BPS: i128 = 10_000;
+
+// Price views of PiCoinOracle
+#[contractclient(name = "PriceFeedClient")]
+pub trait PriceFeed {
+    fn query_price(env: Env, asset: Symbol) -> i128;
+    fn last_update(env: Env, asset: Symbol) -> Option<u64>;
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DualOracleConfig {
+    pub primary: Address,
+    pub secondary: Address,
+    pub tolerance_bps: u32, // Largest gap between the two, relative to the secondary, before it takes over
+    pub max_age: u64, // Seconds after which a feed's price is stale
+}
+
+// Published as "oracle_disagreement" whenever the secondary's price is used instead of
+// the primary's
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Disagreement {
+    pub primary_price: Option<i128>, // None when the primary has no price or failed
+    pub secondary_price: i128,
+    pub primary_stale: bool, // Fell back on age (or failure) rather than on the gap
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DualOracleError {
+    InvalidConfig = 1,
+    NoValidPrice = 2, // Neither feed has a fresh price
+}
+
+#[contract]
+pub struct PiCoinDualOracle;
+
+#[contractimpl]
+impl PiCoinDualOracle {
+    // Constructor: price source that reads a primary and a secondary PiCoinOracle. The
+    // primary's price is used while it is fresh and agrees with the secondary. When it is
+    // stale, unavailable or off by more than the tolerance, the secondary's price is used
+    // instead and an "oracle_disagreement" event says why. Consumers such as the lending
+    // market point at this contract instead of a single oracle.
+    pub fn __constructor(env: Env, governance: Address, config: DualOracleConfig) -> Result<(), DualOracleError> {
+        Self::validate(&config)?;
+        env.storage().instance().set(&Symbol::new(&env, "governance"), &governance);
+        env.storage().instance().set(&Symbol::new(&env, "dual_config"), &config);
+        log!(&env, "Dual oracle initialized: primary with secondary fallback");
+        Ok(())
+    }
+
+    // Governance-only: Replace the feeds, tolerance or staleness limit
+    pub fn set_config(env: Env, config: DualOracleConfig) -> Result<(), DualOracleError> {
+        let governance: Address = env.storage().instance().get(&Symbol::new(&env, "governance")).unwrap();
+        governance.require_auth();
+        Self::validate(&config)?;
+        env.storage().instance().set(&Symbol::new(&env, "dual_config"), &config);
+        Ok(())
+    }
+
+    // Price of `asset`: the primary's, or the secondary's when the primary is stale,
+    // unavailable or disagrees beyond the tolerance
+    pub fn query_price(env: Env, asset: Symbol) -> Result<i128, DualOracleError> {
+        let config = Self::config(env.clone());
+        let primary = Self::fresh_price(&env, &config.primary, &asset, config.max_age);
+        let secondary = Self::fresh_price(&env, &config.secondary, &asset, config.max_age);
+        let (primary_price, secondary_price) = match (primary, secondary) {
+            (Some(primary), None) => return Ok(primary),
+            (None, None) => return Err(DualOracleError::NoValidPrice),
+            (primary, Some(secondary)) => (primary, secondary),
+        };
+        if let Some(primary) = primary_price {
+            let gap = primary.checked_sub(secondary_price).and_then(|gap| gap.checked_abs()).and_then(|gap| gap.checked_mul(BPS));
+            let allowed = secondary_price.checked_mul(config.tolerance_bps as i128);
+            if matches!((gap, allowed), (Some(gap), Some(allowed)) if gap <= allowed) {
+                return Ok(primary);
+            }
+        }
+        // The primary's raw price, even if stale, helps whoever investigates
+        let disagreement = Disagreement {
+            primary_price: primary_price.or_else(|| Self::raw_price(&env, &config.primary, &asset)),
+            secondary_price,
+            primary_stale: primary_price.is_none(),
+        };
+        env.events().publish((Symbol::new(&env, "oracle_disagreement"), asset), disagreement);
+        Ok(secondary_price)
+    }
+
+    // View: Feeds, tolerance and staleness limit
+    pub fn config(env: Env) -> DualOracleConfig {
+        env.storage().instance().get(&Symbol::new(&env, "dual_config")).unwrap()
+    }
+
+    // Helper: Two distinct feeds and a positive staleness limit
+    fn validate(config: &DualOracleConfig) -> Result<(), DualOracleError> {
+        if config.primary == config.secondary || config.max_age == 0 {
+            return Err(DualOracleError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    // Helper: `feed`'s positive price for `asset` if updated within `max_age` seconds; a
+    // failing feed counts as having none
+    fn fresh_price(env: &Env, feed: &Address, asset: &Symbol, max_age: u64) -> Option<i128> {
+        let updated_at = PriceFeedClient::new(env, feed).try_last_update(asset).ok()?.ok()??;
+        if env.ledger().timestamp().saturating_sub(updated_at) > max_age {
+            return None;
+        }
+        Self::raw_price(env, feed, asset)
+    }
+
+    // Helper: `feed`'s positive price for `asset`, however old
+    fn raw_price(env: &Env, feed: &Address, asset: &Symbol) -> Option<i128> {
+        PriceFeedClient::new(env, feed).try_query_price(asset).ok()?.ok().filter(|price| *price > 0)
+    }
+}
+
+mod test;
@@ -0,0 +1,68 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Events, Ledger}, vec, Address, Env, IntoVal, Symbol};
+use crate::{Disagreement, DualOracleConfig, DualOracleError, PiCoinDualOracle, PiCoinDualOracleClient};
+
+// Just enough of PiCoinOracle: one price and when it was accepted
+#[contract]
+struct MockFeed;
+
+#[contractimpl]
+impl MockFeed {
+    pub fn set_price(env: Env, price: i128, updated_at: u64) {
+        env.storage().instance().set(&0u32, &(price, updated_at));
+    }
+
+    pub fn query_price(env: Env, _asset: Symbol) -> i128 {
+        env.storage().instance().get::<_, (i128, u64)>(&0u32).unwrap().0
+    }
+
+    pub fn last_update(env: Env, _asset: Symbol) -> Option<u64> {
+        env.storage().instance().get::<_, (i128, u64)>(&0u32).map(|(_, updated_at)| updated_at)
+    }
+}
+
+#[test]
+fn test_falls_back_to_secondary_on_staleness_or_disagreement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+    let primary = MockFeedClient::new(&env, &env.register(MockFeed, ()));
+    let secondary = MockFeedClient::new(&env, &env.register(MockFeed, ()));
+    let config = DualOracleConfig { primary: primary.address.clone(), secondary: secondary.address.clone(), tolerance_bps: 200, max_age: 600 };
+    let contract_id = env.register(PiCoinDualOracle, (Address::generate(&env), config.clone()));
+    let client = PiCoinDualOracleClient::new(&env, &contract_id);
+    let pi = Symbol::new(&env, "PI");
+    assert!(matches!(client.try_query_price(&pi), Err(Ok(DualOracleError::NoValidPrice))));
+    assert!(matches!(
+        client.try_set_config(&DualOracleConfig { secondary: primary.address.clone(), ..config.clone() }),
+        Err(Ok(DualOracleError::InvalidConfig))
+    ));
+
+    // Agreement within 2%: the primary's price
+    primary.set_price(&314_000, &9_900);
+    secondary.set_price(&310_000, &9_800);
+    assert_eq!(client.query_price(&pi), 314_000);
+    assert!(env.events().all().filter_by_contract(&contract_id).events().is_empty());
+
+    // A 10% gap: the secondary's price, and an event for the investigation
+    primary.set_price(&341_000, &9_900);
+    assert_eq!(client.query_price(&pi), 310_000);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "oracle_disagreement"), pi.clone()).into_val(&env),
+                Disagreement { primary_price: Some(341_000), secondary_price: 310_000, primary_stale: false }.into_val(&env),
+            ),
+        ]
+    );
+
+    // A stale primary falls back as well; a stale secondary leaves the primary in charge
+    primary.set_price(&314_000, &9_000);
+    assert_eq!(client.query_price(&pi), 310_000);
+    primary.set_price(&314_000, &9_900);
+    secondary.set_price(&310_000, &9_000);
+    assert_eq!(client.query_price(&pi), 314_000);
+}
@@ -0,0 +1,178 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Funded, // Buyer deposited, awaiting delivery
+    Delivered, // Seller marked delivered, awaiting buyer release
+    Disputed, // Only the arbiter can settle
+    Released, // Paid to seller
+    Refunded, // Returned to buyer
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escrow {
+    pub buyer: Address,
+    pub seller: Address,
+    pub arbiter: Address,
+    pub amount: i128,
+    pub deadline: u64, // After this, an undisputed escrow refunds the buyer
+    pub status: EscrowStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum EscrowKey {
+    NextId,
+    Escrow(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EscrowError {
+    EscrowNotFound = 1,
+    InvalidEscrow = 2,
+    Unauthorized = 3,
+    InvalidState = 4,
+    DeadlineNotReached = 5,
+}
+
+#[contract]
+pub struct PiCoinEscrow;
+
+#[contractimpl]
+impl PiCoinEscrow {
+    // Constructor: two-party PI escrow with arbiter and timeout refund
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "Escrow initialized: arbiter-backed PI trades ready");
+    }
+
+    // Buyer: Deposit `amount` PI for `seller`, refundable after `deadline` unless
+    // released or disputed first. The escrow holds what the contract actually received,
+    // net of any PI transfer fee. Returns the escrow id.
+    pub fn open(env: Env, buyer: Address, seller: Address, arbiter: Address, amount: i128, deadline: u64) -> Result<u64, EscrowError> {
+        buyer.require_auth();
+        if amount <= 0 || deadline <= env.ledger().timestamp() || buyer == seller {
+            return Err(EscrowError::InvalidEscrow);
+        }
+        let amount = receive::pull(&env, &Self::pi(&env).address, &buyer, amount).unwrap_or(0);
+        if amount <= 0 {
+            return Err(EscrowError::InvalidEscrow);
+        }
+
+        let id: u64 = env.storage().instance().get(&EscrowKey::NextId).unwrap_or(1);
+        env.storage().instance().set(&EscrowKey::NextId, &(id + 1));
+        let escrow = Escrow { buyer, seller, arbiter, amount, deadline, status: EscrowStatus::Funded };
+        Self::save(&env, id, &escrow);
+        Ok(id)
+    }
+
+    // Seller: Mark the goods or service delivered
+    pub fn mark_delivered(env: Env, escrow_id: u64) -> Result<(), EscrowError> {
+        let mut escrow = Self::load(&env, escrow_id)?;
+        escrow.seller.require_auth();
+        Self::require_status(&escrow, &[EscrowStatus::Funded])?;
+        escrow.status = EscrowStatus::Delivered;
+        Self::save(&env, escrow_id, &escrow);
+        Ok(())
+    }
+
+    // Buyer: Release the funds to the seller
+    pub fn release(env: Env, escrow_id: u64) -> Result<(), EscrowError> {
+        let escrow = Self::load(&env, escrow_id)?;
+        escrow.buyer.require_auth();
+        Self::require_status(&escrow, &[EscrowStatus::Funded, EscrowStatus::Delivered])?;
+        Self::settle(&env, escrow_id, escrow, EscrowStatus::Released);
+        Ok(())
+    }
+
+    // Buyer or seller: Hand the escrow to the arbiter. Stops the timeout refund.
+    pub fn dispute(env: Env, caller: Address, escrow_id: u64) -> Result<(), EscrowError> {
+        caller.require_auth();
+        let mut escrow = Self::load(&env, escrow_id)?;
+        if caller != escrow.buyer && caller != escrow.seller {
+            return Err(EscrowError::Unauthorized);
+        }
+        Self::require_status(&escrow, &[EscrowStatus::Funded, EscrowStatus::Delivered])?;
+        escrow.status = EscrowStatus::Disputed;
+        Self::save(&env, escrow_id, &escrow);
+        Ok(())
+    }
+
+    // Arbiter: Settle a dispute, paying the seller or refunding the buyer
+    pub fn resolve(env: Env, escrow_id: u64, release_to_seller: bool) -> Result<(), EscrowError> {
+        let escrow = Self::load(&env, escrow_id)?;
+        escrow.arbiter.require_auth();
+        Self::require_status(&escrow, &[EscrowStatus::Disputed])?;
+        let outcome = if release_to_seller { EscrowStatus::Released } else { EscrowStatus::Refunded };
+        Self::settle(&env, escrow_id, escrow, outcome);
+        Ok(())
+    }
+
+    // Anyone: Refund the buyer once the deadline has passed without release or dispute
+    pub fn refund(env: Env, escrow_id: u64) -> Result<(), EscrowError> {
+        let escrow = Self::load(&env, escrow_id)?;
+        Self::require_status(&escrow, &[EscrowStatus::Funded, EscrowStatus::Delivered])?;
+        if env.ledger().timestamp() <= escrow.deadline {
+            return Err(EscrowError::DeadlineNotReached);
+        }
+        Self::settle(&env, escrow_id, escrow, EscrowStatus::Refunded);
+        Ok(())
+    }
+
+    // View: Escrow details and state
+    pub fn escrow(env: Env, escrow_id: u64) -> Result<Escrow, EscrowError> {
+        Self::load(&env, escrow_id)
+    }
+
+    // Helper: Pay out a final outcome (Released -> seller, Refunded -> buyer)
+    fn settle(env: &Env, escrow_id: u64, mut escrow: Escrow, outcome: EscrowStatus) {
+        escrow.status = outcome;
+        Self::save(env, escrow_id, &escrow);
+        let to = if outcome == EscrowStatus::Released { &escrow.seller } else { &escrow.buyer };
+        Self::pi(env).transfer(&env.current_contract_address(), to, &escrow.amount);
+    }
+
+    // Helper: Persist and emit an `escrow` event carrying the new state, so marketplaces
+    // can follow every transition
+    fn save(env: &Env, escrow_id: u64, escrow: &Escrow) {
+        env.storage().persistent().set(&EscrowKey::Escrow(escrow_id), escrow);
+        env.events().publish((Symbol::new(env, "escrow"), escrow_id, Self::status_name(env, escrow.status)), escrow.clone());
+    }
+
+    fn status_name(env: &Env, status: EscrowStatus) -> Symbol {
+        Symbol::new(env, match status {
+            EscrowStatus::Funded => "funded",
+            EscrowStatus::Delivered => "delivered",
+            EscrowStatus::Disputed => "disputed",
+            EscrowStatus::Released => "released",
+            EscrowStatus::Refunded => "refunded",
+        })
+    }
+
+    fn require_status(escrow: &Escrow, allowed: &[EscrowStatus]) -> Result<(), EscrowError> {
+        if allowed.contains(&escrow.status) {
+            Ok(())
+        } else {
+            Err(EscrowError::InvalidState)
+        }
+    }
+
+    fn load(env: &Env, escrow_id: u64) -> Result<Escrow, EscrowError> {
+        env.storage().persistent().get(&EscrowKey::Escrow(escrow_id)).ok_or(EscrowError::EscrowNotFound)
+    }
+
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap();
+        token::TokenClient::new(env, &pi_token)
+    }
+}
+
+mod test;
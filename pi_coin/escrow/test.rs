@@ -0,0 +1,100 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env};
+use crate::{EscrowError, EscrowStatus, PiCoinEscrow, PiCoinEscrowClient};
+use pi_coin_testutils::TokenBuilder;
+
+#[test]
+fn test_release_dispute_and_timeout_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&buyer, 900).build();
+    let contract_id = env.register(PiCoinEscrow, (&pi.address,));
+    let client = PiCoinEscrowClient::new(&env, &contract_id);
+
+    // Happy path: delivered, then released by the buyer
+    let sale = client.open(&buyer, &seller, &arbiter, &300, &2_000);
+    client.mark_delivered(&sale);
+    client.release(&sale);
+    assert_eq!(pi.balance(&seller), 300);
+    assert_eq!(client.try_release(&sale), Err(Ok(EscrowError::InvalidState)));
+
+    // Dispute settled by the arbiter in the buyer's favour
+    let disputed = client.open(&buyer, &seller, &arbiter, &300, &2_000);
+    client.dispute(&seller, &disputed);
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(client.try_refund(&disputed), Err(Ok(EscrowError::InvalidState)));
+    client.resolve(&disputed, &false);
+    assert!(client.escrow(&disputed).status == EscrowStatus::Refunded);
+
+    // Untouched escrow refunds after the deadline, not before
+    let stale = client.open(&buyer, &seller, &arbiter, &300, &4_000);
+    assert_eq!(client.try_refund(&stale), Err(Ok(EscrowError::DeadlineNotReached)));
+    env.ledger().set_timestamp(4_001);
+    client.refund(&stale);
+    assert_eq!(pi.balance(&buyer), 600);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_escrow_holds_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&buyer, 1_000).build();
+    let contract_id = env.register(PiCoinEscrow, (&pi.address,));
+    let client = PiCoinEscrowClient::new(&env, &contract_id);
+
+    // A 1% fee leaves 990 in escrow, and releasing pays out exactly that
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    let sale = client.open(&buyer, &seller, &Address::generate(&env), &1_000, &2_000);
+    assert_eq!(client.escrow(&sale).amount, 990);
+    client.release(&sale);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_settled_escrows() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&buyer, 500).build();
+    let contract_id = env.register(PiCoinEscrow, (&pi.address,));
+    let client = PiCoinEscrowClient::new(&env, &contract_id);
+
+    // The buyer cannot escrow more than it holds, nor with a past deadline
+    assert!(client.try_open(&buyer, &seller, &arbiter, &501, &2_000).is_err());
+    assert_eq!(client.try_open(&buyer, &seller, &arbiter, &100, &1_000), Err(Ok(EscrowError::InvalidEscrow)));
+    let sale = client.open(&buyer, &seller, &arbiter, &500, &2_000);
+
+    // Outsiders cannot dispute, and only the buyer's signature releases
+    let outsider = Address::generate(&env);
+    assert_eq!(client.try_dispute(&outsider, &sale), Err(Ok(EscrowError::Unauthorized)));
+    env.set_auths(&[]);
+    assert!(client.try_release(&sale).is_err());
+    env.mock_all_auths();
+
+    // The arbiter has nothing to resolve until a party disputes
+    assert_eq!(client.try_resolve(&sale, &true), Err(Ok(EscrowError::InvalidState)));
+
+    // Once refunded, the escrow cannot be released, disputed or refunded again
+    env.ledger().set_timestamp(2_001);
+    client.refund(&sale);
+    assert_eq!(client.try_release(&sale), Err(Ok(EscrowError::InvalidState)));
+    assert_eq!(client.try_dispute(&seller, &sale), Err(Ok(EscrowError::InvalidState)));
+    assert_eq!(client.try_refund(&sale), Err(Ok(EscrowError::InvalidState)));
+    assert_eq!(client.try_escrow(&99), Err(Ok(EscrowError::EscrowNotFound)));
+    assert_eq!(pi.balance(&buyer), 500);
+}
@@ -0,0 +1,66 @@
+#![no_std]
+// PiCoinContract as version 1 deployed it, cut down to what the migration test drives:
+// the v1 storage layout (no version marker, sources in one instance map), source-tracking
+// mints and the governance-gated upgrade to later wasm. Types match the XDR of the token's
+// `migration::PiCoinDataV1` and `PiCoinSource`.
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, Map, Symbol};
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq)]
+pub enum PiCoinSource {
+    Mining,
+    Rewards,
+    P2P,
+    Invalid,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PiCoinData {
+    pub symbol: Symbol,
+    pub total_supply: i128,
+    pub peg_value: i128,
+    pub collateral_asset: Address,
+    pub oracle_address: Address,
+    pub governance_address: Address,
+    pub anti_fraud_hash: BytesN<32>,
+    pub provenance: Map<Address, PiCoinSource>,
+    pub quantum_provenance_hash: BytesN<32>,
+}
+
+#[contract]
+pub struct PiCoinContract;
+
+#[contractimpl]
+impl PiCoinContract {
+    // Initialize with fixed parameters, as version 1 did before constructors
+    pub fn initialize(env: Env, admin: Address, collateral_asset: Address, oracle: Address, governance: Address) {
+        admin.require_auth();
+        let data = PiCoinData {
+            symbol: Symbol::new(&env, "PI"),
+            total_supply: 100_000_000_000,
+            peg_value: 314_159_000_000,
+            collateral_asset,
+            oracle_address: oracle,
+            governance_address: governance,
+            anti_fraud_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique")).into(),
+            provenance: Map::new(&env),
+            quantum_provenance_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Provenance-Quantum-Unmatched")).into(),
+        };
+        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+    }
+
+    // Record `to`'s source; version 1 kept no balances
+    pub fn mint(env: Env, to: Address, _amount: i128, source: PiCoinSource) {
+        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.provenance.set(to, source);
+        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+    }
+
+    // Governance-only: Swap the contract code
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+}
@@ -0,0 +1,113 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, Address, Bytes, Env, Symbol, log};
+
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+// Implemented by contracts that take flash mints. `on_flash_mint` runs with `amount`
+// PI credited to the receiver; before returning it must approve the lender on the PI
+// token for `amount + fee`, or the whole transaction reverts. Receivers should only
+// act for initiators they trust, since the initiator decides when the fee is paid.
+#[contractclient(name = "FlashMintReceiverClient")]
+pub trait FlashMintReceiver {
+    fn on_flash_mint(env: Env, initiator: Address, amount: i128, fee: i128, data: Bytes);
+}
+
+// Flash entry points of PiCoinContract available to the registered lender
+#[contractclient(name = "PiFlashTokenClient")]
+pub trait PiFlashToken {
+    fn flash_credit(env: Env, to: Address, amount: i128);
+    fn flash_settle(env: Env, from: Address, amount: i128, fee: i128);
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct FlashData {
+    pub governance: Address,
+    pub pi_token: Address,
+    pub fee_bps: u32,
+    pub max_amount: i128, // Largest single flash mint
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FlashError {
+    InvalidAmount = 1,
+    InvalidFee = 2,
+    Overflow = 3, // The fee on `amount` would pass i128
+}
+
+#[contract]
+pub struct PiCoinFlashLender;
+
+#[contractimpl]
+impl PiCoinFlashLender {
+    // Constructor: flash mints of PI with same-transaction repayment. Governance must
+    // register this contract with PiCoinContract::set_flash_lender. The fee and cap are
+    // bounded as in `set_params`.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, fee_bps: u32, max_amount: i128) -> Result<(), FlashError> {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(FlashError::InvalidFee);
+        }
+        if max_amount < 0 {
+            return Err(FlashError::InvalidAmount);
+        }
+        let data = FlashData { governance, pi_token, fee_bps, max_amount };
+        env.storage().instance().set(&Symbol::new(&env, "flash_data"), &data);
+        log!(&env, "Flash lender initialized: {} bps fee, max {} PI", fee_bps, max_amount);
+        Ok(())
+    }
+
+    // Mint `amount` PI to `receiver`, call its `on_flash_mint`, then collect `amount`
+    // plus the fee. Any shortfall traps, undoing the mint and everything the receiver
+    // did. Soroban forbids re-entry, so the receiver cannot start its own loan; the
+    // authorized `initiator` is passed to the callback instead. Returns the fee paid.
+    pub fn flash_mint(env: Env, initiator: Address, receiver: Address, amount: i128, data: Bytes) -> Result<i128, FlashError> {
+        initiator.require_auth();
+        let flash_data: FlashData = env.storage().instance().get(&Symbol::new(&env, "flash_data")).unwrap();
+        if amount <= 0 || amount > flash_data.max_amount {
+            return Err(FlashError::InvalidAmount);
+        }
+        let fee = Self::fee_for(&flash_data, amount)?;
+
+        let pi = PiFlashTokenClient::new(&env, &flash_data.pi_token);
+        pi.flash_credit(&receiver, &amount);
+        FlashMintReceiverClient::new(&env, &receiver).on_flash_mint(&initiator, &amount, &fee, &data);
+        pi.flash_settle(&receiver, &amount, &fee);
+
+        env.events().publish((Symbol::new(&env, "flash_mint"), receiver), (amount, fee));
+        Ok(fee)
+    }
+
+    // View: Fee charged on a flash mint of `amount`
+    pub fn flash_fee(env: Env, amount: i128) -> Result<i128, FlashError> {
+        let data: FlashData = env.storage().instance().get(&Symbol::new(&env, "flash_data")).unwrap();
+        Self::fee_for(&data, amount)
+    }
+
+    // Governance-only: Change the flash fee and the per-loan cap
+    pub fn set_params(env: Env, fee_bps: u32, max_amount: i128) -> Result<(), FlashError> {
+        let mut data: FlashData = env.storage().instance().get(&Symbol::new(&env, "flash_data")).unwrap();
+        data.governance.require_auth();
+        if fee_bps > MAX_FEE_BPS {
+            return Err(FlashError::InvalidFee);
+        }
+        if max_amount < 0 {
+            return Err(FlashError::InvalidAmount);
+        }
+        data.fee_bps = fee_bps;
+        data.max_amount = max_amount;
+        env.storage().instance().set(&Symbol::new(&env, "flash_data"), &data);
+        Ok(())
+    }
+
+    // Helper: Fee rounded up, so small loans are never free; Overflow for amounts too
+    // large to price
+    fn fee_for(data: &FlashData, amount: i128) -> Result<i128, FlashError> {
+        let bps = MAX_FEE_BPS as i128;
+        let scaled = amount.checked_mul(data.fee_bps as i128).and_then(|fee| fee.checked_add(bps - 1)).ok_or(FlashError::Overflow)?;
+        Ok(scaled / bps)
+    }
+}
+
+mod test;
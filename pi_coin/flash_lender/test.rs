@@ -0,0 +1,132 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, contracttype, testutils::Address as _, Address, Bytes, Env};
+use crate::{FlashError, FlashMintReceiver, PiCoinFlashLender, PiCoinFlashLenderClient, MAX_FEE_BPS};
+
+// Just enough of PiCoinContract for flash minting: balances, approve and the flash hooks
+#[contracttype]
+enum MockKey {
+    Lender,
+    Balance(Address),
+    Allowance(Address, Address),
+}
+
+#[contract]
+struct MockPi;
+
+#[contractimpl]
+impl MockPi {
+    pub fn set_flash_lender(env: Env, lender: Address) {
+        env.storage().instance().set(&MockKey::Lender, &lender);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&MockKey::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128) {
+        from.require_auth();
+        env.storage().instance().set(&MockKey::Allowance(from, spender), &amount);
+    }
+
+    pub fn flash_credit(env: Env, to: Address, amount: i128) {
+        let lender: Address = env.storage().instance().get(&MockKey::Lender).unwrap();
+        lender.require_auth();
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&MockKey::Balance(to), &(balance + amount));
+    }
+
+    pub fn flash_settle(env: Env, from: Address, amount: i128, fee: i128) {
+        let lender: Address = env.storage().instance().get(&MockKey::Lender).unwrap();
+        lender.require_auth();
+        let allowance: i128 = env.storage().instance().get(&MockKey::Allowance(from.clone(), lender)).unwrap_or(0);
+        let balance = Self::balance(env.clone(), from.clone());
+        if allowance < amount + fee || balance < amount + fee {
+            panic!("flash loan not repaid");
+        }
+        env.storage().instance().set(&MockKey::Balance(from), &(balance - amount - fee));
+    }
+
+    // Test helper: give `to` PI outright
+    pub fn credit(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&MockKey::Balance(to), &(balance + amount));
+    }
+}
+
+// Example arbitrage receiver: borrows PI, "trades" it (here: earns a fixed profit from a
+// price gap), then approves repayment of the loan plus fee. Only its owner may start it.
+#[contract]
+struct ArbReceiver;
+
+#[contractimpl]
+impl ArbReceiver {
+    pub fn __constructor(env: Env, owner: Address, pi: Address, lender: Address) {
+        env.storage().instance().set(&0u32, &(owner, pi, lender));
+    }
+}
+
+#[contractimpl]
+impl FlashMintReceiver for ArbReceiver {
+    fn on_flash_mint(env: Env, initiator: Address, amount: i128, fee: i128, data: Bytes) {
+        let _ = data;
+        let (owner, pi, lender): (Address, Address, Address) = env.storage().instance().get(&0u32).unwrap();
+        assert!(initiator == owner, "untrusted initiator");
+        let pi = MockPiClient::new(&env, &pi);
+        let me = env.current_contract_address();
+        assert!(pi.balance(&me) >= amount);
+        pi.credit(&me, &(amount / 100)); // 1% arbitrage profit
+        pi.approve(&me, &lender, &(amount + fee));
+    }
+}
+
+// Receiver that keeps the PI
+#[contract]
+struct DefaultingReceiver;
+
+#[contractimpl]
+impl FlashMintReceiver for DefaultingReceiver {
+    fn on_flash_mint(env: Env, initiator: Address, amount: i128, fee: i128, data: Bytes) {
+        let _ = (env, initiator, amount, fee, data);
+    }
+}
+
+#[test]
+fn test_flash_mint_repaid_or_reverted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = env.register(MockPi, ());
+    let pi_client = MockPiClient::new(&env, &pi);
+    let lender = env.register(PiCoinFlashLender, (Address::generate(&env), &pi, 9u32, 1_000_000i128));
+    let lender_client = PiCoinFlashLenderClient::new(&env, &lender);
+    pi_client.set_flash_lender(&lender);
+
+    // 9 bps on 100_000 = 90; the arbitrage keeps the rest of its 1_000 profit
+    let owner = Address::generate(&env);
+    let arb = env.register(ArbReceiver, (&owner, &pi, &lender));
+    let no_data = Bytes::new(&env);
+    assert_eq!(lender_client.flash_fee(&100_000), 90);
+    assert_eq!(lender_client.flash_mint(&owner, &arb, &100_000, &no_data), 90);
+    assert_eq!(pi_client.balance(&arb), 910);
+
+    // Over the cap, started by a stranger, or not repaid: the whole flash mint reverts
+    assert!(lender_client.try_flash_mint(&owner, &arb, &2_000_000, &no_data).is_err());
+    assert!(lender_client.try_flash_mint(&Address::generate(&env), &arb, &100_000, &no_data).is_err());
+    assert_eq!(pi_client.balance(&arb), 910);
+    let defaulter = env.register(DefaultingReceiver, ());
+    assert!(lender_client.try_flash_mint(&owner, &defaulter, &100_000, &no_data).is_err());
+    assert_eq!(pi_client.balance(&defaulter), 0);
+
+    // With the cap lifted, a fee too large for i128 is an error rather than a wrapped fee
+    lender_client.set_params(&9, &i128::MAX);
+    assert_eq!(lender_client.try_flash_fee(&i128::MAX), Err(Ok(FlashError::Overflow)));
+    assert_eq!(lender_client.try_flash_mint(&owner, &arb, &i128::MAX, &no_data), Err(Ok(FlashError::Overflow)));
+}
+
+#[test]
+#[should_panic]
+fn test_constructor_rejects_fee_above_100_percent() {
+    let env = Env::default();
+    let pi = env.register(MockPi, ());
+    env.register(PiCoinFlashLender, (Address::generate(&env), &pi, MAX_FEE_BPS + 1, 1_000_000i128));
+}
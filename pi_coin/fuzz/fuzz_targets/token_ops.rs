@@ -0,0 +1,13 @@
+//! Fuzz the token with arbitrary sequences of mints, transfers, allowances, batches,
+//! burns and fee changes. After every step the harness asserts that balances sum to
+//! the minted-minus-burned supply, that no balance is negative, that every accepted
+//! mint was covered by collateral and that rejected operations change nothing.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pi_coin::testutils::{Harness, Op};
+
+fuzz_target!(|ops: Vec<Op>| {
+    Harness::new().run(&ops);
+});
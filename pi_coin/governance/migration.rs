@@ -0,0 +1,202 @@
+// Versioned storage schemas: every layout GovernanceData has had is kept here so an
+// upgraded governance contract can read state written by older wasm and rewrite it.
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec, Map, BytesN};
+use crate::{
+    Checkpoint, CategoryParams, DelegationCheckpoint, EntryKey, GovernanceData, GovernanceError, PiCoinGovernance,
+    Proposal, ProposalCategory, VoteReceipt, VoterData,
+};
+
+// Entries moved per `post_upgrade` call, so a migration of any size fits the per-transaction
+// write limits; a migration with more entries resumes from the cursor on the next call
+pub const MIGRATION_BATCH: u32 = 20;
+
+// Layout written by version 1 (proposals, voters and receipts in maps inside the instance entry)
+#[contracttype]
+#[derive(Clone)]
+pub struct GovernanceDataV1 {
+    pub admin: Address,
+    pub pi_token: Address,
+    pub treasury: Address,
+    pub proposals: Map<u32, Proposal>,
+    pub voters: Map<Address, VoterData>,
+    pub receipts: Map<(u32, Address), VoteReceipt>,
+    pub checkpoints: Map<Address, Vec<Checkpoint>>,
+    pub delegations: Map<Address, Vec<DelegationCheckpoint>>,
+    pub delegators: Map<Address, Vec<Address>>,
+    pub vote_keys: Map<Address, BytesN<32>>,
+    pub vote_nonces: Map<Address, u64>,
+    pub bls_keys: Map<Address, BytesN<96>>,
+    pub ai_model_hash: BytesN<32>,
+    pub category_params: Map<ProposalCategory, CategoryParams>,
+    pub total_stake_history: Vec<Checkpoint>,
+    pub timelock_delay: u64,
+    pub voting_delay: u32,
+    pub voting_period: u32,
+}
+
+// Layout written by version 2 (stake and delegation histories, vote keys and nonces in maps
+// inside the instance entry)
+#[contracttype]
+#[derive(Clone)]
+pub struct GovernanceDataV2 {
+    pub admin: Address,
+    pub pi_token: Address,
+    pub treasury: Address,
+    pub checkpoints: Map<Address, Vec<Checkpoint>>,
+    pub delegations: Map<Address, Vec<DelegationCheckpoint>>,
+    pub delegators: Map<Address, Vec<Address>>,
+    pub vote_keys: Map<Address, BytesN<32>>,
+    pub vote_nonces: Map<Address, u64>,
+    pub bls_keys: Map<Address, BytesN<96>>,
+    pub ai_model_hash: BytesN<32>,
+    pub category_params: Map<ProposalCategory, CategoryParams>,
+    pub total_stake_history: Vec<Checkpoint>,
+    pub timelock_delay: u64,
+    pub voting_delay: u32,
+    pub voting_period: u32,
+}
+
+// Run one batch of the migration out of `version` and return the version reached, which
+// stays `version` until the last batch; UnknownVersion if no layout with that number was
+// ever written
+pub fn step(env: &Env, version: u32) -> Result<u32, GovernanceError> {
+    match version {
+        1 => Ok(if v1_to_v2(env) { 2 } else { 1 }),
+        2 => Ok(if v2_to_v3(env)? { 3 } else { 2 }),
+        _ => Err(GovernanceError::UnknownVersion),
+    }
+}
+
+// v1 -> v2: each proposal, voter and vote receipt becomes its own persistent entry.
+// Returns whether the last batch has run.
+fn v1_to_v2(env: &Env) -> bool {
+    let key = Symbol::new(env, "gov_data");
+    let old: GovernanceDataV1 = env.storage().instance().get(&key).unwrap();
+    let total = old.proposals.len() + old.voters.len() + old.receipts.len();
+    let batch = next_batch(env, total);
+    let mut offset = move_batch(&old.proposals, 0, batch, |proposal_id, proposal| {
+        env.storage().persistent().set(&EntryKey::Proposal(proposal_id), &proposal);
+    });
+    offset = move_batch(&old.voters, offset, batch, |voter, voter_data| PiCoinGovernance::save_voter(env, &voter, &voter_data));
+    move_batch(&old.receipts, offset, batch, |(proposal_id, voter), receipt| {
+        env.storage().persistent().set(&EntryKey::Receipt(proposal_id, voter.clone()), &receipt);
+        PiCoinGovernance::index_receipt(env, proposal_id, &voter);
+    });
+    if !finish_batch(env, batch, total) {
+        return false;
+    }
+
+    env.storage().instance().set(&EntryKey::ProposalCount, &old.proposals.len());
+    let data = GovernanceDataV2 {
+        admin: old.admin,
+        pi_token: old.pi_token,
+        treasury: old.treasury,
+        checkpoints: old.checkpoints,
+        delegations: old.delegations,
+        delegators: old.delegators,
+        vote_keys: old.vote_keys,
+        vote_nonces: old.vote_nonces,
+        bls_keys: old.bls_keys,
+        ai_model_hash: old.ai_model_hash,
+        category_params: old.category_params,
+        total_stake_history: old.total_stake_history,
+        timelock_delay: old.timelock_delay,
+        voting_delay: old.voting_delay,
+        voting_period: old.voting_period,
+    };
+    env.storage().instance().set(&key, &data);
+    true
+}
+
+// v2 -> v3: stake and delegation histories, delegator lists, vote keys and nonces become
+// per-address persistent entries, and the total stake history one entry per checkpoint.
+// Delegator lists keep only current delegators. Last, every current delegation adds the
+// delegator's stake to the delegated power down its chain; that power is checkpointed
+// from the migration ledger, so snapshots taken before it count own stake only.
+// Returns whether the last batch has run.
+fn v2_to_v3(env: &Env) -> Result<bool, GovernanceError> {
+    let key = Symbol::new(env, "gov_data");
+    let old: GovernanceDataV2 = env.storage().instance().get(&key).unwrap();
+    let history = &old.total_stake_history;
+    let total = old.checkpoints.len() + old.delegations.len() + old.delegators.len() + old.vote_keys.len()
+        + old.vote_nonces.len() + old.bls_keys.len() + history.len() + old.delegations.len();
+    let batch = next_batch(env, total);
+    let persistent = env.storage().persistent();
+    let current_delegate = |delegator: &Address| old.delegations.get(delegator.clone()).and_then(|h| h.last()).and_then(|c| c.delegate);
+    let mut offset = move_batch(&old.checkpoints, 0, batch, |voter, value| persistent.set(&EntryKey::Checkpoints(voter), &value));
+    offset = move_batch(&old.delegations, offset, batch, |delegator, value| persistent.set(&EntryKey::Delegations(delegator), &value));
+    offset = move_batch(&old.delegators, offset, batch, |delegate, value| {
+        let current = Vec::from_iter(env, value.iter().filter(|delegator| current_delegate(delegator) == Some(delegate.clone())));
+        if !current.is_empty() {
+            persistent.set(&EntryKey::Delegators(delegate), &current);
+        }
+    });
+    offset = move_batch(&old.vote_keys, offset, batch, |voter, value| persistent.set(&EntryKey::VoteKey(voter), &value));
+    offset = move_batch(&old.vote_nonces, offset, batch, |voter, value| persistent.set(&EntryKey::VoteNonce(voter), &value));
+    offset = move_batch(&old.bls_keys, offset, batch, |voter, value| persistent.set(&EntryKey::BlsKey(voter), &value));
+    for position in batch.0.max(offset)..batch.1.min(offset + history.len()) {
+        let index = position - offset;
+        persistent.set(&EntryKey::TotalStakeAt(index), &history.get_unchecked(index));
+    }
+    offset += history.len();
+    let delegators = old.delegations.keys();
+    for position in batch.0.max(offset)..batch.1 {
+        let delegator = delegators.get_unchecked(position - offset);
+        if current_delegate(&delegator).is_some() {
+            let stake = PiCoinGovernance::load_voter(env, &delegator).map(|v| v.stake).unwrap_or(0);
+            PiCoinGovernance::shift_power(env, &delegator, stake)?;
+        }
+    }
+    if !finish_batch(env, batch, total) {
+        return Ok(false);
+    }
+
+    env.storage().instance().set(&EntryKey::TotalStakeCount, &history.len());
+    let data = GovernanceData {
+        admin: old.admin,
+        pi_token: old.pi_token,
+        treasury: old.treasury,
+        ai_model_hash: old.ai_model_hash,
+        category_params: old.category_params,
+        timelock_delay: old.timelock_delay,
+        voting_delay: old.voting_delay,
+        voting_period: old.voting_period,
+    };
+    env.storage().instance().set(&key, &data);
+    Ok(true)
+}
+
+// Helper: Positions [start, end) of the entries this call moves, out of `total` entries
+// counted across every collection of the migration in order
+fn next_batch(env: &Env, total: u32) -> (u32, u32) {
+    let start: u32 = env.storage().instance().get(&EntryKey::MigrationCursor).unwrap_or(0);
+    (start, start.saturating_add(MIGRATION_BATCH).min(total))
+}
+
+// Helper: Save the cursor after `batch`, or clear it once all `total` entries have moved.
+// Returns whether the migration is complete.
+fn finish_batch(env: &Env, batch: (u32, u32), total: u32) -> bool {
+    if batch.1 < total {
+        env.storage().instance().set(&EntryKey::MigrationCursor, &batch.1);
+        return false;
+    }
+    env.storage().instance().remove(&EntryKey::MigrationCursor);
+    true
+}
+
+// Helper: Pass the entries of `map` that fall inside `batch` to `write`, with the map's
+// first entry at position `offset`; returns the position after the map
+fn move_batch<K, V>(map: &Map<K, V>, offset: u32, batch: (u32, u32), write: impl Fn(K, V)) -> u32
+where
+    K: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone,
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let end = offset + map.len();
+    let keys = map.keys();
+    for position in batch.0.max(offset)..batch.1.min(end) {
+        let key = keys.get_unchecked(position - offset);
+        let value = map.get_unchecked(key.clone());
+        write(key, value);
+    }
+    end
+}
@@ -1,25 +1,115 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+use soroban_sdk::{
+    bytesn, contract, contractimpl, contracterror, contracttype,
+    crypto::bls12_381::{G1Affine, G2Affine},
+    xdr::ToXdr, Address, Env, Executable, Symbol, Vec, Map, log, token, vec, Bytes, BytesN, IntoVal, Val,
+};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+mod migration;
+mod scoring;
+
+pub use scoring::{PassRecord, ScoreFactors, ScoringConfig};
+
+// Storage layout version written by this wasm
+pub const CONTRACT_VERSION: u32 = 3;
+// Last topic of every governance event; bumped whenever a topic or data layout changes
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+// Delay between a proposal passing and its action becoming executable (seconds)
+const DEFAULT_TIMELOCK_DELAY: u64 = 2 * 24 * 60 * 60;
+// Ledgers between proposal creation and the opening of the voting window
+const DEFAULT_VOTING_DELAY: u32 = 1;
+// Length of the voting window in ledgers (~1 day at 5s per ledger)
+const DEFAULT_VOTING_PERIOD: u32 = 17_280;
+// Unbonding period before unstaked PI can be withdrawn (7 days of ledger time)
+const UNBONDING_PERIOD: u64 = 7 * 24 * 60 * 60;
+// Maximum votes accepted in one BLS-aggregated batch
+const MAX_VOTE_BATCH: u32 = 32;
+// Domain separation tag for hashing signed votes to G2 (RFC 9380)
+const VOTE_DST: &str = "PICOIN-VOTE-V01-CS01-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+// Basis-point denominator for quorum and approval thresholds
+const BPS: i128 = 10_000;
+// Approval needed for the supermajority track (strictly above, i.e. >= 2/3)
+const SUPERMAJORITY_BPS: u32 = 6_666;
+// Longest delegation chain allowed, in links from the first delegator to the voting delegate
+const MAX_DELEGATION_DEPTH: u32 = 4;
+// Largest peg move one proposal may make, relative to the current peg
+const MAX_PEG_STEP_BPS: i128 = 1_000;
+// Transfer fee ceiling; mirrors the token's own cap so a passed fee change cannot fail
+const MAX_FEE_BPS: u32 = 1_000;
+// Largest page `get_proposals` and `get_voters` return
+const MAX_PAGE_SIZE: u32 = 50;
+// Share of a pruned proposal's unclaimed deposit paid to whoever prunes it
+const PRUNE_FEE_BPS: i128 = 500;
+// Seconds after the timelock a passed proposal stays executable; past it, it has expired
+const EXECUTION_WINDOW: u64 = 14 * 24 * 60 * 60;
 
 #[contracttype]
 #[derive(Clone)]
 pub struct GovernanceData {
     pub admin: Address,
-    pub proposals: Map<u32, Proposal>, // Proposal ID -> Details
-    pub voters: Map<Address, VoterData>, // Voter -> Stake and history
+    pub pi_token: Address, // PiCoinContract targeted by peg/fee/oracle actions
+    pub treasury: Address, // PiCoinTreasury holding protocol revenue
     pub ai_model_hash: BytesN<32>, // For AI-assisted scoring
-    pub quantum_threshold: u32, // Min signatures for approval
+    pub category_params: Map<ProposalCategory, CategoryParams>, // Thresholds per proposal category
+    pub timelock_delay: u64, // Seconds between passing and execution
+    pub voting_delay: u32, // Ledgers from creation to start of voting
+    pub voting_period: u32, // Ledgers the voting window stays open
+}
+
+// Proposal category: decides which quorum/approval track applies
+#[contracttype]
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum ProposalCategory {
+    Standard, // Fees, oracle rotation, treasury spending, custom calls
+    PegChange, // Changes to the $314,159 peg - supermajority
+    Governance, // Changes to governance parameters themselves - supermajority
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct CategoryParams {
+    pub quorum_bps: u32, // Min participation as a share of total staked supply at the snapshot
+    pub approval_bps: u32, // Share of votes cast that must be in favour (strictly above)
+    pub min_proposal_stake: i128, // Voting power required to create a proposal
+    pub deposit: i128, // PI locked by the proposer; burned if quorum is missed
+}
+
+// On-chain action carried by a proposal, performed by `execute` once passed
+#[contracttype]
+#[derive(Clone)]
+pub enum Action {
+    SetPeg(i128), // New peg value in micro-units
+    SetFee(u32), // Transfer fee in basis points
+    SetOracle(Address), // New oracle contract for PiCoinContract
+    UpgradeWasm(Address, BytesN<32>), // Target contract, new wasm hash
+    TransferTreasury(Address, Address, i128), // Asset, recipient, amount
+    Custom(Address, Symbol, Vec<Val>), // Arbitrary contract call: contract, fn, args
+    SetCategoryParams(ProposalCategory, CategoryParams), // Governance parameter change
+    SetScoringConfig(ScoringConfig), // Proposal scoring weights and minimum score
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Proposal {
+    pub proposer: Address,
     pub title: Symbol,
     pub description: Bytes, // e.g., "Update peg to $314,160"
-    pub votes_for: u32,
-    pub votes_against: u32,
-    pub status: Symbol, // "active", "passed", "failed"
-    pub ai_score: i128, // AI prediction of success
+    pub votes_for: i128, // Stake-weighted tally
+    pub votes_against: i128, // Stake-weighted tally
+    pub voter_count: u32, // Distinct voters with a live receipt
+    pub category: ProposalCategory, // Derived from the action at creation
+    pub deposit: i128, // Locked PI; zero once refunded or burned
+    pub status: Symbol, // "active", "passed", "failed", "executed", "cancelled"
+    pub ai_score: i128, // Deterministic score in bps at creation (see scoring.rs)
+    pub action: Action, // Performed on execution
+    pub eta: u64, // Earliest execution timestamp, set when the proposal passes
+    pub snapshot_ledger: u32, // Voting power is read from stake checkpoints before this ledger
+    pub start_ledger: u32, // First ledger votes are accepted
+    pub end_ledger: u32, // Last ledger votes are accepted
 }
 
 #[contracttype]
@@ -27,14 +117,121 @@ pub struct Proposal {
 pub struct VoterData {
     pub stake: i128, // PI tokens staked for voting power
     pub vote_history: Vec<u32>, // Proposal IDs voted on
+    pub unbonding: i128, // Unstaked PI waiting out the unbonding period (no voting power)
+    pub unbonding_release: u64, // Timestamp from which `unbonding` can be withdrawn
+}
+
+// Per-proposal per-voter record: blocks double voting and allows change/revoke
+#[contracttype]
+#[derive(Clone)]
+pub struct VoteReceipt {
+    pub approve: bool,
+    pub weight: i128, // Snapshotted stake counted for this vote
+}
+
+// Payload a voter signs off-chain for `vote_by_sig`; serialized as XDR
+#[contracttype]
+#[derive(Clone)]
+pub struct SignedVote {
+    pub governance: Address, // Binds the signature to this contract
+    pub proposal_id: u32,
+    pub voter: Address,
+    pub approve: bool,
+    pub nonce: u64,
+}
+
+// One entry of a BLS-aggregated vote batch; each voter signs its own SignedVote
+#[contracttype]
+#[derive(Clone)]
+pub struct BatchVote {
+    pub voter: Address,
+    pub proposal_id: u32,
+    pub approve: bool,
+}
+
+// Proposals, voters, vote receipts and per-address histories and keys each live in
+// their own persistent entry, so a vote or stake only rewrites the entries it touches
+#[contracttype]
+#[derive(Clone)]
+pub enum EntryKey {
+    ProposalCount, // Instance: proposals created; IDs run from 1 to this count
+    Proposal(u32),
+    VoterCount, // Instance: addresses with a voter record
+    VoterAt(u32), // Position (from 0) -> voter address, for paging through voters
+    Voter(Address),
+    Receipt(u32, Address), // (Proposal ID, voter) -> Recorded vote
+    ReceiptCount(u32), // Proposal ID -> Voters indexed under ReceiptVoter
+    ReceiptVoter(u32, u32), // (Proposal ID, position from 0) -> Voter, so pruning can find the receipts
+    PruneCursor, // Instance: lowest proposal ID not yet pruned
+    Checkpoints(Address), // Voter -> Stake history, ordered by ledger
+    Delegations(Address), // Delegator -> Delegation history
+    Delegators(Address), // Delegate -> Everyone currently delegating to it
+    DelegatedPower(Address), // Delegate -> History of the stake delegated to it, directly or through a chain
+    VoteKey(Address), // Voter -> ed25519 key accepted for signed (relayed) votes
+    VoteNonce(Address), // Voter -> Next expected signed-vote nonce
+    BlsKey(Address), // Voter -> BLS12-381 G1 public key for batched votes
+    TotalStakeCount, // Instance: checkpoints of the total staked supply
+    TotalStakeAt(u32), // Position (from 0) -> Total staked supply checkpoint, ordered by ledger
+    MigrationCursor, // Instance: entries already moved by the migration in progress
+}
+
+// Scoring state kept outside GovernanceData
+#[contracttype]
+#[derive(Clone)]
+pub enum ScoreKey {
+    Config, // ScoringConfig
+    Proposer(Address), // PassRecord of the proposer's finalized proposals
+    Category(ProposalCategory), // PassRecord of the category
+    Factors(u32), // Proposal ID -> ScoreFactors behind its score
+}
+
+// Stake checkpoint: the voter's stake from `ledger` onwards
+#[contracttype]
+#[derive(Clone)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub stake: i128,
 }
 
+// Delegation checkpoint: who `delegator` delegates to from `ledger` onwards (None = self)
 #[contracttype]
+#[derive(Clone)]
+pub struct DelegationCheckpoint {
+    pub ledger: u32,
+    pub delegate: Option<Address>,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum GovernanceError {
     Unauthorized = 1,
     ProposalNotFound = 2,
     InsufficientStake = 3,
     QuantumThresholdNotMet = 4,
+    ProposalNotPassed = 5,
+    TimelockNotExpired = 6,
+    ProposalNotActive = 7,
+    NoVoteToRevoke = 8,
+    VotingClosed = 9,
+    VotingNotEnded = 10,
+    DelegationCycle = 11,
+    DelegationTooDeep = 12,
+    NotDelegating = 13,
+    InvalidParams = 14,
+    NothingToWithdraw = 15,
+    UnbondingNotElapsed = 16,
+    DepositNotClaimable = 17,
+    VoteKeyNotRegistered = 18,
+    InvalidNonce = 19,
+    BatchTooLarge = 20,
+    InvalidAggregateSignature = 21,
+    InvalidAction = 22,
+    CancelWindowClosed = 23,
+    Overflow = 24, // A stake, tally or total would pass i128
+    InvalidAmount = 25, // A stake or unstake of zero or less, or a transfer that delivers nothing
+    UnknownVersion = 26, // Stored state has a version no migration starts from
+    ProposalExpired = 27, // A passed proposal not executed within EXECUTION_WINDOW of its eta
 }
 
 #[contract]
@@ -42,110 +239,1099 @@ pub struct PiCoinGovernance;
 
 #[contractimpl]
 impl PiCoinGovernance {
-    // Initialize governance with hyper-tech parameters
-    pub fn initialize(env: Env, admin: Address, quantum_threshold: u32) -> Result<(), GovernanceError> {
-        admin.require_auth();
+    // Constructor: initialize governance with hyper-tech parameters atomically at deploy time
+    pub fn __constructor(env: Env, admin: Address, pi_token: Address, treasury: Address) {
+        let mut category_params = Map::new(&env);
+        category_params.set(ProposalCategory::Standard, CategoryParams { quorum_bps: 400, approval_bps: 5_000, min_proposal_stake: 100_000, deposit: 10_000 });
+        category_params.set(ProposalCategory::PegChange, CategoryParams { quorum_bps: 1_000, approval_bps: SUPERMAJORITY_BPS, min_proposal_stake: 1_000_000, deposit: 100_000 });
+        category_params.set(ProposalCategory::Governance, CategoryParams { quorum_bps: 1_000, approval_bps: SUPERMAJORITY_BPS, min_proposal_stake: 1_000_000, deposit: 100_000 });
         let data = GovernanceData {
             admin,
-            proposals: Map::new(&env),
-            voters: Map::new(&env),
-            ai_model_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Governance-AI-Ultimate")),
-            quantum_threshold,
+            pi_token,
+            treasury,
+            ai_model_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Governance-AI-Ultimate")).into(),
+            category_params,
+            timelock_delay: DEFAULT_TIMELOCK_DELAY,
+            voting_delay: DEFAULT_VOTING_DELAY,
+            voting_period: DEFAULT_VOTING_PERIOD,
         };
         env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
+        env.storage().instance().set(&Symbol::new(&env, "version"), &CONTRACT_VERSION);
         log!(&env, "Governance initialized: Quantum-secure, AI-assisted, global consensus ready");
-        Ok(())
     }
 
     // Create proposal with AI scoring (hyper-tech: predictive analysis)
-    pub fn create_proposal(env: Env, creator: Address, title: Symbol, description: Bytes) -> Result<u32, GovernanceError> {
+    pub fn create_proposal(env: Env, creator: Address, title: Symbol, description: Bytes, action: Action) -> Result<u32, GovernanceError> {
         creator.require_auth();
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let proposal_id = data.proposals.len() as u32 + 1;
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let proposal_id = Self::proposal_count(env.clone()) + 1;
 
-        // Hyper-tech AI: Score proposal success probability
-        let ai_score = Self::ai_score_proposal(&env, &description);
+        let category = Self::category_of(&action);
+        Self::validate_action(&env, &data, &action)?;
+        let params = data.category_params.get(category).unwrap();
+        let power = Self::voting_power(&env, &creator, env.ledger().sequence() + 1);
+        if power < params.min_proposal_stake {
+            return Err(GovernanceError::InsufficientStake);
+        }
+        // Anti-spam: lock the category's deposit until finalization. The proposal holds what
+        // arrived net of any PI transfer fee, so a forfeited deposit can always be burned.
+        let deposit = if params.deposit > 0 { receive::pull(&env, &data.pi_token, &creator, params.deposit).ok_or(GovernanceError::Overflow)? } else { 0 };
+        if params.deposit > 0 && deposit <= 0 {
+            return Err(GovernanceError::InvalidAmount);
+        }
+
+        // Deterministic score from the proposer's record, stake, change size and category history
+        let factors = Self::score_factors(&env, &data, &creator, &action, category, power);
+        let ai_score = scoring::combine(&factors, &Self::scoring_config(env.clone()));
+        env.storage().persistent().set(&ScoreKey::Factors(proposal_id), &factors);
+        // Snapshot: stake added from this ledger on cannot swing the proposal
+        let snapshot_ledger = env.ledger().sequence();
+        let start_ledger = snapshot_ledger + data.voting_delay;
+        Self::proposal_event(&env, "proposal_created", proposal_id, title.clone());
         let proposal = Proposal {
-            title,
+            proposer: creator,
+            title: title.clone(),
             description,
             votes_for: 0,
             votes_against: 0,
+            voter_count: 0,
+            category,
+            deposit,
             status: Symbol::new(&env, "active"),
             ai_score,
+            action,
+            eta: 0,
+            snapshot_ledger,
+            start_ledger,
+            end_ledger: start_ledger + data.voting_period,
         };
-        data.proposals.set(proposal_id, proposal);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
-        log!(&env, "Proposal {} created: {} with AI score {} - Ultimate governance for global Pi Coin", proposal_id, title, ai_score);
+        Self::save_proposal(&env, proposal_id, &proposal);
+        env.storage().instance().set(&EntryKey::ProposalCount, &proposal_id);
+        log!(&env, "Proposal {} created: {} with score {} - Ultimate governance for global Pi Coin", proposal_id, title, ai_score);
         Ok(proposal_id)
     }
 
-    // Vote on proposal with quantum multi-sig (maximum level: secure tallying)
+    // Vote on proposal (maximum level: secure tallying)
+    // Voting power is the voter's stake at the proposal snapshot; voting again replaces the previous vote
     pub fn vote(env: Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), GovernanceError> {
         voter.require_auth();
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let mut voter_data = data.voters.get(voter.clone()).unwrap_or(VoterData {
-            stake: 0,
-            vote_history: Vec::new(&env),
-        });
+        Self::cast_vote(&env, voter, proposal_id, approve)
+    }
 
-        if voter_data.stake < 100_000 { // Min stake for voting
-            return Err(GovernanceError::InsufficientStake);
+    // Register the ed25519 public key whose signatures count as this voter's votes
+    pub fn register_vote_key(env: Env, voter: Address, public_key: BytesN<32>) -> Result<(), GovernanceError> {
+        voter.require_auth();
+        env.storage().persistent().set(&EntryKey::VoteKey(voter), &public_key);
+        log!(&env, "Vote key registered - Relayed voting enabled");
+        Ok(())
+    }
+
+    // Gasless voting: anyone may relay a vote signed off-chain with the voter's
+    // registered key over SignedVote{governance, proposal_id, voter, approve, nonce}
+    pub fn vote_by_sig(env: Env, voter: Address, proposal_id: u32, approve: bool, nonce: u64, signature: BytesN<64>) -> Result<(), GovernanceError> {
+        let public_key: BytesN<32> = env.storage().persistent().get(&EntryKey::VoteKey(voter.clone())).ok_or(GovernanceError::VoteKeyNotRegistered)?;
+        let expected_nonce = Self::vote_nonce(env.clone(), voter.clone());
+        if nonce != expected_nonce {
+            return Err(GovernanceError::InvalidNonce);
         }
 
-        let mut proposal = data.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
-        if approve {
-            proposal.votes_for += 1;
-        } else {
-            proposal.votes_against += 1;
+        let payload = SignedVote {
+            governance: env.current_contract_address(),
+            proposal_id,
+            voter: voter.clone(),
+            approve,
+            nonce,
+        };
+        // Traps on an invalid signature
+        env.crypto().ed25519_verify(&public_key, &payload.to_xdr(&env), &signature);
+
+        // Consume the nonce so the same signed vote cannot be replayed
+        env.storage().persistent().set(&EntryKey::VoteNonce(voter.clone()), &(nonce + 1));
+        Self::cast_vote(&env, voter, proposal_id, approve)
+    }
+
+    // Register the BLS12-381 G1 public key used for this voter's batched votes
+    pub fn register_bls_key(env: Env, voter: Address, public_key: BytesN<96>) -> Result<(), GovernanceError> {
+        voter.require_auth();
+        env.storage().persistent().set(&EntryKey::BlsKey(voter), &public_key);
+        log!(&env, "BLS vote key registered - Batched voting enabled");
+        Ok(())
+    }
+
+    // Tally up to MAX_VOTE_BATCH votes with one aggregated BLS signature.
+    // Every voter signs its own SignedVote (with its current nonce), so the
+    // messages are distinct and the aggregate is checked with a single
+    // multi-pairing: e(-g1, agg_sig) * prod(e(pk_i, H(m_i))) == 1.
+    pub fn submit_vote_batch(env: Env, votes: Vec<BatchVote>, agg_sig: BytesN<192>) -> Result<u32, GovernanceError> {
+        if votes.len() > MAX_VOTE_BATCH {
+            return Err(GovernanceError::BatchTooLarge);
         }
-        voter_data.vote_history.push_back(proposal_id);
-        data.voters.set(voter, voter_data);
-        data.proposals.set(proposal_id, proposal);
+        let bls = env.crypto().bls12_381();
+        let dst = Bytes::from_slice(&env, VOTE_DST.as_bytes());
 
-        // Quantum-resistant: Generate multi-sig for vote
-        let vote_sig = env.crypto().ed25519_sign(&voter, &proposal_id.to_be_bytes());
-        log!(&env, "Vote cast for proposal {}: {} with quantum sig: {:?}", proposal_id, if approve { "for" } else { "against" }, vote_sig);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
+        // Negated generator of G1
+        let neg_g1 = G1Affine::from_bytes(bytesn!(&env, 0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb114d1d6855d545a8aa7d76c8cf2e21f267816aef1db507c96655b9d5caac42364e6f38ba0ecb751bad54dcd6b939c2ca));
+        let mut vp1 = vec![&env, neg_g1];
+        let mut vp2 = vec![&env, G2Affine::from_bytes(agg_sig)];
+
+        for vote in votes.iter() {
+            let public_key: BytesN<96> = env.storage().persistent().get(&EntryKey::BlsKey(vote.voter.clone())).ok_or(GovernanceError::VoteKeyNotRegistered)?;
+            // Nonces advance as we go, so a voter appearing twice signs two distinct messages.
+            // A failed check below returns an error, which rolls the nonces back.
+            let nonce = Self::vote_nonce(env.clone(), vote.voter.clone());
+            env.storage().persistent().set(&EntryKey::VoteNonce(vote.voter.clone()), &(nonce + 1));
+            let payload = SignedVote {
+                governance: env.current_contract_address(),
+                proposal_id: vote.proposal_id,
+                voter: vote.voter.clone(),
+                approve: vote.approve,
+                nonce,
+            };
+            vp1.push_back(G1Affine::from_bytes(public_key));
+            vp2.push_back(bls.hash_to_g2(&payload.to_xdr(&env), &dst));
+        }
+
+        if !bls.pairing_check(vp1, vp2) {
+            return Err(GovernanceError::InvalidAggregateSignature);
+        }
+
+        for vote in votes.iter() {
+            Self::cast_vote(&env, vote.voter, vote.proposal_id, vote.approve)?;
+        }
+        log!(&env, "Vote batch of {} tallied with one aggregate BLS signature", votes.len());
+        Ok(votes.len())
+    }
+
+    // Migration hook run after an upgrade (idempotent, permissionless). Governance
+    // can only be upgraded through its own executed UpgradeWasm proposal. Each call moves
+    // at most migration::MIGRATION_BATCH entries and returns the version now in effect;
+    // call again until it returns CONTRACT_VERSION. Other calls fail until then.
+    pub fn post_upgrade(env: Env) -> Result<u32, GovernanceError> {
+        let stored: u32 = env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1);
+        if stored == CONTRACT_VERSION {
+            return Ok(stored);
+        }
+        let version = migration::step(&env, stored)?;
+        if version != stored {
+            env.storage().instance().set(&Symbol::new(&env, "version"), &version);
+            log!(&env, "Governance storage migrated from version {} to {}", stored, version);
+        }
+        Ok(version)
+    }
+
+    // View: Whether the contract state has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&Symbol::new(&env, "gov_data"))
+    }
+
+    // View: Storage layout version currently in effect
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1)
+    }
+
+    // View: PiCoinContract targeted by peg, fee and oracle actions
+    pub fn pi_token(env: Env) -> Address {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        data.pi_token
+    }
+
+    // View: Treasury spent from by TransferTreasury actions
+    pub fn treasury(env: Env) -> Address {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        data.treasury
+    }
+
+    // View: Proposal details, tally and status
+    pub fn proposal(env: Env, proposal_id: u32) -> Result<Proposal, GovernanceError> {
+        Self::load_proposal(&env, proposal_id)
+    }
+
+    // View: Number of proposals created; IDs run from 1 to this count
+    pub fn proposal_count(env: Env) -> u32 {
+        env.storage().instance().get(&EntryKey::ProposalCount).unwrap_or(0)
+    }
+
+    // View: Page `page` (from 0) of proposal IDs, `size` per page (at most MAX_PAGE_SIZE),
+    // with the proposals still stored; pruned ones are left out. Past the last proposal
+    // the page is empty.
+    pub fn get_proposals(env: Env, page: u32, size: u32) -> Result<Vec<(u32, Proposal)>, GovernanceError> {
+        let (start, end) = Self::page_bounds(page, size, Self::proposal_count(env.clone()))?;
+        let mut proposals = Vec::new(&env);
+        for index in start..end {
+            if let Ok(proposal) = Self::load_proposal(&env, index + 1) {
+                proposals.push_back((index + 1, proposal));
+            }
+        }
+        Ok(proposals)
+    }
+
+    // View: Number of addresses that have ever staked
+    pub fn voter_count(env: Env) -> u32 {
+        env.storage().instance().get(&EntryKey::VoterCount).unwrap_or(0)
+    }
+
+    // View: Page `page` (from 0) of voters in order of first stake, `size` per page (at
+    // most MAX_PAGE_SIZE). Past the last voter the page is empty.
+    pub fn get_voters(env: Env, page: u32, size: u32) -> Result<Vec<(Address, VoterData)>, GovernanceError> {
+        let (start, end) = Self::page_bounds(page, size, Self::voter_count(env.clone()))?;
+        let mut voters = Vec::new(&env);
+        for index in start..end {
+            let voter: Address = env.storage().persistent().get(&EntryKey::VoterAt(index)).unwrap();
+            let voter_data = Self::load_voter(&env, &voter).unwrap();
+            voters.push_back((voter, voter_data));
+        }
+        Ok(voters)
+    }
+
+    // View: Scoring weights and minimum score (changed by a SetScoringConfig proposal)
+    pub fn scoring_config(env: Env) -> ScoringConfig {
+        env.storage().instance().get(&ScoreKey::Config).unwrap_or(ScoringConfig::default_config())
+    }
+
+    // View: Factors behind a proposal's score, for reproducing it off-chain
+    pub fn score_factors_of(env: Env, proposal_id: u32) -> Result<ScoreFactors, GovernanceError> {
+        env.storage().persistent().get(&ScoreKey::Factors(proposal_id)).ok_or(GovernanceError::ProposalNotFound)
+    }
+
+    // View: Finalized and passed proposals of `proposer`
+    pub fn proposer_record(env: Env, proposer: Address) -> PassRecord {
+        Self::pass_record(&env, &ScoreKey::Proposer(proposer))
+    }
+
+    // View: Finalized and passed proposals of `category`
+    pub fn category_record(env: Env, category: ProposalCategory) -> PassRecord {
+        Self::pass_record(&env, &ScoreKey::Category(category))
+    }
+
+    // View: Voter's stake, unbonding PI and voting history (empty for unknown addresses)
+    pub fn voter(env: Env, voter: Address) -> VoterData {
+        Self::load_voter(&env, &voter).unwrap_or(Self::empty_voter(&env))
+    }
+
+    // View: Next nonce expected from `voter` for a signed vote
+    pub fn vote_nonce(env: Env, voter: Address) -> u64 {
+        env.storage().persistent().get(&EntryKey::VoteNonce(voter)).unwrap_or(0)
+    }
+
+    // Revoke a previously cast vote while the proposal is still active
+    pub fn revoke_vote(env: Env, voter: Address, proposal_id: u32) -> Result<(), GovernanceError> {
+        voter.require_auth();
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_voting_open(&env, &proposal)?;
+
+        let receipt_key = EntryKey::Receipt(proposal_id, voter.clone());
+        let previous: VoteReceipt = env.storage().persistent().get(&receipt_key).ok_or(GovernanceError::NoVoteToRevoke)?;
+        Self::remove_weight(&mut proposal, &previous);
+        proposal.voter_count -= 1;
+        env.storage().persistent().remove(&receipt_key);
+        Self::save_proposal(&env, proposal_id, &proposal);
+        log!(&env, "Vote revoked for proposal {}: weight {} withdrawn", proposal_id, previous.weight);
         Ok(())
     }
 
     // Finalize proposal with global consensus (ultimate: aggregate votes)
     pub fn finalize_proposal(env: Env, proposal_id: u32) -> Result<(), GovernanceError> {
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let mut proposal = data.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if proposal.status != Symbol::new(&env, "active") {
+            return Err(GovernanceError::ProposalNotActive);
+        }
+        if env.ledger().sequence() <= proposal.end_ledger {
+            return Err(GovernanceError::VotingNotEnded);
+        }
 
-        // Hyper-tech: Check quantum threshold and AI score
-        if proposal.votes_for >= data.quantum_threshold && proposal.ai_score > 50 {
+        // Hyper-tech: Check the category's quorum and approval track plus the minimum score
+        let params = data.category_params.get(proposal.category).unwrap();
+        let total_votes = Self::checked_add(proposal.votes_for, proposal.votes_against)?;
+        let total_staked = Self::total_stake_at(&env, proposal.snapshot_ledger);
+        let quorum_met = Self::checked_mul(total_votes, BPS)? >= Self::checked_mul(total_staked, params.quorum_bps as i128)?;
+        let approved = Self::checked_mul(proposal.votes_for, BPS)? > Self::checked_mul(total_votes, params.approval_bps as i128)?;
+        let passed = quorum_met && approved && proposal.ai_score >= Self::scoring_config(env.clone()).min_score as i128;
+        Self::record_outcome(&env, &ScoreKey::Proposer(proposal.proposer.clone()), passed);
+        Self::record_outcome(&env, &ScoreKey::Category(proposal.category), passed);
+        if passed {
             proposal.status = Symbol::new(&env, "passed");
+            // Timelock: give holders time to react before the action lands
+            proposal.eta = env.ledger().timestamp() + data.timelock_delay;
             // Simulate global recognition: Emit event for worldwide adoption
-            env.events().publish((Symbol::new(&env, "proposal_passed"), proposal_id), proposal.title.clone());
+            Self::proposal_event(&env, "proposal_passed", proposal_id, proposal.title.clone());
         } else {
             proposal.status = Symbol::new(&env, "failed");
         }
-        data.proposals.set(proposal_id, proposal);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
+
+        // Burn-on-spam: a proposal that could not even attract quorum forfeits its deposit
+        let burned = if !quorum_met { proposal.deposit } else { 0 };
+        if burned > 0 {
+            proposal.deposit = 0;
+        }
+        Self::save_proposal(&env, proposal_id, &proposal);
+        if burned > 0 {
+            token::TokenClient::new(&env, &data.pi_token).burn(&env.current_contract_address(), &burned);
+            Self::proposal_event(&env, "deposit_burned", proposal_id, burned);
+        }
         log!(&env, "Proposal {} finalized: {} - Pi Coin governance unmatched for global stability", proposal_id, proposal.status);
         Ok(())
     }
 
-    // Stake PI for voting power (anti-sybil)
+    // Admin-only: Appoint the guardian, who may cancel any proposal before it executes
+    pub fn set_guardian(env: Env, guardian: Address) {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        data.admin.require_auth();
+        env.storage().instance().set(&Symbol::new(&env, "guardian"), &guardian);
+        log!(&env, "Governance guardian appointed");
+    }
+
+    // View: Guardian, if appointed
+    pub fn guardian(env: Env) -> Option<Address> {
+        env.storage().instance().get(&Symbol::new(&env, "guardian"))
+    }
+
+    // Cancel a proposal: the proposer may withdraw it until voting starts, the guardian
+    // may cancel it at any point before execution. The deposit goes back to the proposer.
+    pub fn cancel_proposal(env: Env, caller: Address, proposal_id: u32) -> Result<(), GovernanceError> {
+        caller.require_auth();
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        let active = proposal.status == Symbol::new(&env, "active");
+        if !active && proposal.status != Symbol::new(&env, "passed") {
+            return Err(GovernanceError::ProposalNotActive);
+        }
+        if Self::guardian(env.clone()) != Some(caller.clone()) {
+            if caller != proposal.proposer {
+                return Err(GovernanceError::Unauthorized);
+            }
+            if !active || env.ledger().sequence() >= proposal.start_ledger {
+                return Err(GovernanceError::CancelWindowClosed);
+            }
+        }
+
+        let refund = proposal.deposit;
+        proposal.deposit = 0;
+        proposal.status = Symbol::new(&env, "cancelled");
+        Self::save_proposal(&env, proposal_id, &proposal);
+        if refund > 0 {
+            token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &proposal.proposer, &refund);
+        }
+        Self::proposal_event(&env, "proposal_cancelled", proposal_id, caller);
+        log!(&env, "Proposal {} cancelled, deposit of {} PI refunded", proposal_id, refund);
+        Ok(())
+    }
+
+    // Refund the proposer's deposit once the proposal is finalized with quorum reached
+    pub fn claim_deposit(env: Env, proposal_id: u32) -> Result<i128, GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if proposal.status == Symbol::new(&env, "active") || proposal.deposit == 0 {
+            return Err(GovernanceError::DepositNotClaimable);
+        }
+        let amount = proposal.deposit;
+        proposal.deposit = 0;
+        Self::save_proposal(&env, proposal_id, &proposal);
+
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &proposal.proposer, &amount);
+        log!(&env, "Deposit of {} PI refunded for proposal {}", amount, proposal_id);
+        Ok(amount)
+    }
+
+    // Execute a passed proposal's action once its timelock has expired (permissionless),
+    // within EXECUTION_WINDOW of it
+    pub fn execute(env: Env, proposal_id: u32) -> Result<(), GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if proposal.status != Symbol::new(&env, "passed") {
+            return Err(GovernanceError::ProposalNotPassed);
+        }
+        if env.ledger().timestamp() < proposal.eta {
+            return Err(GovernanceError::TimelockNotExpired);
+        }
+        if Self::expired(&env, &proposal) {
+            return Err(GovernanceError::ProposalExpired);
+        }
+
+        // Mark executed before the external call so a re-entrant execute is rejected
+        proposal.status = Symbol::new(&env, "executed");
+        Self::save_proposal(&env, proposal_id, &proposal);
+
+        Self::perform_action(&env, &data, &proposal.action);
+        Self::proposal_event(&env, "proposal_executed", proposal_id, proposal.title.clone());
+        log!(&env, "Proposal {} executed: {} - On-chain action applied", proposal_id, proposal.title);
+        Ok(())
+    }
+
+    // Permissionless upkeep: delete finalized proposals (failed, executed, cancelled, or
+    // passed and expired) whose voting ended before `before_ledger`, with their vote
+    // receipts, oldest first. Proposals still active or awaiting execution are skipped and
+    // pruned by a later call. Each call looks at most MAX_PAGE_SIZE proposals and receipts
+    // in all; a proposal with more receipts than that is finished over several calls. Each
+    // deletion emits `proposal_archived` with the final status and the sha256 of the
+    // proposal's XDR, so indexers can still prove the outcome. A deposit still held is paid
+    // out on the way: PRUNE_FEE_BPS of it to `caller` for the upkeep, the rest to the proposer.
+    pub fn prune_finalized(env: Env, caller: Address, before_ledger: u32) -> Result<u32, GovernanceError> {
+        caller.require_auth();
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let count = Self::proposal_count(env.clone());
+        let mut cursor: u32 = env.storage().instance().get(&EntryKey::PruneCursor).unwrap_or(1);
+        let (mut next, mut budget) = (cursor, MAX_PAGE_SIZE);
+        let (mut pruned, mut fees) = (0u32, 0i128);
+        while next <= count && budget > 0 {
+            budget -= 1;
+            // A proposal already gone was pruned past a skipped one by an earlier call
+            if let Ok(proposal) = Self::load_proposal(&env, next) {
+                let mut status = proposal.status.clone();
+                if Self::expired(&env, &proposal) {
+                    status = Symbol::new(&env, "expired");
+                }
+                let finalized = status == Symbol::new(&env, "failed")
+                    || status == Symbol::new(&env, "executed")
+                    || status == Symbol::new(&env, "cancelled")
+                    || status == Symbol::new(&env, "expired");
+                if !finalized || proposal.end_ledger >= before_ledger {
+                    next += 1;
+                    continue;
+                }
+                if !Self::prune_receipts(&env, next, &mut budget) {
+                    break;
+                }
+
+                let state_hash = env.crypto().sha256(&proposal.clone().to_xdr(&env)).to_bytes();
+                env.storage().persistent().remove(&EntryKey::Proposal(next));
+                env.storage().persistent().remove(&ScoreKey::Factors(next));
+                if proposal.deposit > 0 {
+                    let fee = Self::checked_mul(proposal.deposit, PRUNE_FEE_BPS)? / BPS;
+                    pi.transfer(&env.current_contract_address(), &proposal.proposer, &(proposal.deposit - fee));
+                    fees = Self::checked_add(fees, fee)?;
+                }
+                Self::proposal_event(&env, "proposal_archived", next, (status, state_hash));
+                pruned += 1;
+            }
+            if cursor == next {
+                cursor += 1;
+            }
+            next += 1;
+        }
+
+        env.storage().instance().set(&EntryKey::PruneCursor, &cursor);
+        if fees > 0 {
+            pi.transfer(&env.current_contract_address(), &caller, &fees);
+        }
+        log!(&env, "Pruned {} finalized proposals, {} PI paid for upkeep", pruned, fees);
+        Ok(pruned)
+    }
+
+    // Stake PI for voting power (anti-sybil): PI moves into the governance contract, and
+    // the stake is what arrived net of any PI transfer fee
     pub fn stake_tokens(env: Env, staker: Address, amount: i128) -> Result<(), GovernanceError> {
         staker.require_auth();
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let mut voter_data = data.voters.get(staker.clone()).unwrap_or(VoterData {
-            stake: 0,
-            vote_history: Vec::new(&env),
-        });
-        voter_data.stake += amount;
-        data.voters.set(staker, voter_data);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
+        if amount <= 0 {
+            return Err(GovernanceError::InvalidAmount);
+        }
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let amount = receive::pull(&env, &data.pi_token, &staker, amount).ok_or(GovernanceError::Overflow)?;
+        if amount <= 0 {
+            return Err(GovernanceError::InvalidAmount);
+        }
+
+        let mut voter_data = Self::load_voter(&env, &staker).unwrap_or(Self::empty_voter(&env));
+        voter_data.stake = Self::checked_add(voter_data.stake, amount)?;
+        Self::write_checkpoint(&env, &staker, voter_data.stake);
+        Self::adjust_total_stake(&env, amount)?;
+        Self::shift_power(&env, &staker, amount)?;
+        Self::save_voter(&env, &staker, &voter_data);
         log!(&env, "Staked {} PI for governance: Anti-sybil power unlocked", amount);
         Ok(())
     }
 
-    // Helper: AI score proposal (predictive analytics)
-    fn ai_score_proposal(env: &Env, description: &Bytes) -> i128 {
-        // Ultimate AI: Simulate scoring based on description length/trend
-        (description.len() as i128 * 10) % 100 // Predictive score 0-99
+    // Start unbonding staked PI: it stops counting for votes immediately and
+    // becomes withdrawable after UNBONDING_PERIOD (a new unstake restarts the clock)
+    pub fn unstake(env: Env, staker: Address, amount: i128) -> Result<(), GovernanceError> {
+        staker.require_auth();
+        if amount <= 0 {
+            return Err(GovernanceError::InvalidAmount);
+        }
+        let mut voter_data = Self::load_voter(&env, &staker).ok_or(GovernanceError::InsufficientStake)?;
+        if amount > voter_data.stake {
+            return Err(GovernanceError::InsufficientStake);
+        }
+        voter_data.stake -= amount;
+        voter_data.unbonding = Self::checked_add(voter_data.unbonding, amount)?;
+        voter_data.unbonding_release = env.ledger().timestamp() + UNBONDING_PERIOD;
+        Self::write_checkpoint(&env, &staker, voter_data.stake);
+        Self::adjust_total_stake(&env, -amount)?;
+        Self::shift_power(&env, &staker, -amount)?;
+        Self::save_voter(&env, &staker, &voter_data);
+        log!(&env, "Unstaked {} PI: Unbonding period started", amount);
+        Ok(())
+    }
+
+    // Withdraw PI whose unbonding period has elapsed
+    pub fn withdraw(env: Env, staker: Address) -> Result<i128, GovernanceError> {
+        staker.require_auth();
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut voter_data = Self::load_voter(&env, &staker).ok_or(GovernanceError::NothingToWithdraw)?;
+        if voter_data.unbonding == 0 {
+            return Err(GovernanceError::NothingToWithdraw);
+        }
+        if env.ledger().timestamp() < voter_data.unbonding_release {
+            return Err(GovernanceError::UnbondingNotElapsed);
+        }
+        let amount = voter_data.unbonding;
+        voter_data.unbonding = 0;
+        Self::save_voter(&env, &staker, &voter_data);
+
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &staker, &amount);
+        log!(&env, "Withdrew {} PI from governance after unbonding", amount);
+        Ok(amount)
+    }
+
+    // Delegate voting power to another address (chains are followed up to MAX_DELEGATION_DEPTH)
+    pub fn delegate(env: Env, delegator: Address, to: Address) -> Result<(), GovernanceError> {
+        delegator.require_auth();
+
+        // Walk the chain starting at `to`: it must neither loop back nor grow too long,
+        // counting the chains that already end at the delegator
+        let next_ledger = env.ledger().sequence() + 1;
+        let mut cursor = to.clone();
+        let mut depth = 1;
+        loop {
+            if cursor == delegator {
+                return Err(GovernanceError::DelegationCycle);
+            }
+            match Self::delegate_at(&env, &cursor, next_ledger) {
+                Some(next) => {
+                    depth += 1;
+                    if depth > MAX_DELEGATION_DEPTH {
+                        return Err(GovernanceError::DelegationTooDeep);
+                    }
+                    cursor = next;
+                }
+                None => break,
+            }
+        }
+        if depth + Self::chain_height(&env, &delegator, MAX_DELEGATION_DEPTH) > MAX_DELEGATION_DEPTH {
+            return Err(GovernanceError::DelegationTooDeep);
+        }
+
+        // The delegator's stake and everything delegated to it moves from the old chain, if
+        // any, to the new one
+        let weight = Self::delegation_weight(&env, &delegator)?;
+        if let Some(previous) = Self::delegate_at(&env, &delegator, next_ledger) {
+            Self::shift_power(&env, &delegator, -weight)?;
+            Self::remove_delegator(&env, &previous, &delegator);
+        }
+        Self::write_delegation(&env, &delegator, Some(to.clone()));
+        Self::shift_power(&env, &delegator, weight)?;
+        let key = EntryKey::Delegators(to.clone());
+        let mut reverse: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(&env));
+        reverse.push_back(delegator.clone());
+        env.storage().persistent().set(&key, &reverse);
+        Self::delegation_event(&env, "delegate", &delegator, to);
+        log!(&env, "Voting power delegated - Governance delegation graph updated");
+        Ok(())
+    }
+
+    // Take back delegated voting power
+    pub fn undelegate(env: Env, delegator: Address) -> Result<(), GovernanceError> {
+        delegator.require_auth();
+        let previous = Self::delegate_at(&env, &delegator, env.ledger().sequence() + 1).ok_or(GovernanceError::NotDelegating)?;
+        let weight = Self::delegation_weight(&env, &delegator)?;
+        Self::shift_power(&env, &delegator, -weight)?;
+        Self::remove_delegator(&env, &previous, &delegator);
+        Self::write_delegation(&env, &delegator, None);
+        Self::delegation_event(&env, "undelegate", &delegator, previous);
+        log!(&env, "Voting power undelegated - Governance delegation graph updated");
+        Ok(())
+    }
+
+    // View: Current voting power of `addr`, including power delegated to it
+    pub fn delegated_power(env: Env, addr: Address) -> i128 {
+        Self::voting_power(&env, &addr, env.ledger().sequence() + 1)
+    }
+
+    // View: Stake of `voter` as checkpointed before `ledger`
+    pub fn stake_at(env: Env, voter: Address, ledger: u32) -> i128 {
+        Self::find_stake(&env, &voter, ledger)
+    }
+
+    // Helper: Record an already-authenticated vote
+    fn cast_vote(env: &Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), GovernanceError> {
+        let mut voter_data = Self::load_voter(env, &voter).unwrap_or(Self::empty_voter(env));
+
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
+        Self::require_voting_open(env, &proposal)?;
+
+        // Delegation chains are resolved at the snapshot ledger
+        let power = Self::voting_power(env, &voter, proposal.snapshot_ledger);
+        if power < 100_000 { // Min stake for voting
+            return Err(GovernanceError::InsufficientStake);
+        }
+
+        // Changing a vote: back out the previously recorded weight first
+        let receipt_key = EntryKey::Receipt(proposal_id, voter.clone());
+        match env.storage().persistent().get(&receipt_key) {
+            Some(previous) => Self::remove_weight(&mut proposal, &previous),
+            None => {
+                proposal.voter_count = proposal.voter_count.checked_add(1).ok_or(GovernanceError::Overflow)?;
+                voter_data.vote_history.push_back(proposal_id);
+                Self::index_receipt(env, proposal_id, &voter);
+            }
+        }
+
+        let receipt = VoteReceipt { approve, weight: power };
+        if approve {
+            proposal.votes_for = Self::checked_add(proposal.votes_for, receipt.weight)?;
+        } else {
+            proposal.votes_against = Self::checked_add(proposal.votes_against, receipt.weight)?;
+        }
+        env.storage().persistent().set(&receipt_key, &receipt);
+        Self::save_voter(env, &voter, &voter_data);
+        Self::save_proposal(env, proposal_id, &proposal);
+        log!(env, "Vote cast for proposal {}: {} with weight {}", proposal_id, if approve { "for" } else { "against" }, receipt.weight);
+        Ok(())
+    }
+
+    // Helper: List `voter` under the proposal's receipts. A voter who revokes and votes
+    // again is listed twice; removing their receipt twice is harmless.
+    fn index_receipt(env: &Env, proposal_id: u32, voter: &Address) {
+        let count: u32 = env.storage().persistent().get(&EntryKey::ReceiptCount(proposal_id)).unwrap_or(0);
+        env.storage().persistent().set(&EntryKey::ReceiptVoter(proposal_id, count), voter);
+        env.storage().persistent().set(&EntryKey::ReceiptCount(proposal_id), &(count + 1));
+    }
+
+    // Helper: Delete the proposal's receipts, last indexed first, while `budget` lasts.
+    // Returns whether none are left.
+    fn prune_receipts(env: &Env, proposal_id: u32, budget: &mut u32) -> bool {
+        let persistent = env.storage().persistent();
+        let mut count: u32 = persistent.get(&EntryKey::ReceiptCount(proposal_id)).unwrap_or(0);
+        while count > 0 && *budget > 0 {
+            count -= 1;
+            *budget -= 1;
+            let voter: Address = persistent.get(&EntryKey::ReceiptVoter(proposal_id, count)).unwrap();
+            persistent.remove(&EntryKey::Receipt(proposal_id, voter));
+            persistent.remove(&EntryKey::ReceiptVoter(proposal_id, count));
+        }
+        if count > 0 {
+            persistent.set(&EntryKey::ReceiptCount(proposal_id), &count);
+            return false;
+        }
+        persistent.remove(&EntryKey::ReceiptCount(proposal_id));
+        true
+    }
+
+    // Helper: Whether a passed proposal's execution window has closed
+    fn expired(env: &Env, proposal: &Proposal) -> bool {
+        proposal.status == Symbol::new(env, "passed") && env.ledger().timestamp() > proposal.eta.saturating_add(EXECUTION_WINDOW)
+    }
+
+    // Helper: Reject votes outside the proposal's voting window
+    fn require_voting_open(env: &Env, proposal: &Proposal) -> Result<(), GovernanceError> {
+        if proposal.status != Symbol::new(env, "active") {
+            return Err(GovernanceError::ProposalNotActive);
+        }
+        let ledger = env.ledger().sequence();
+        if ledger < proposal.start_ledger || ledger > proposal.end_ledger {
+            return Err(GovernanceError::VotingClosed);
+        }
+        Ok(())
+    }
+
+    // Helper: Record the voter's stake from the current ledger onwards
+    fn write_checkpoint(env: &Env, voter: &Address, stake: i128) {
+        let key = EntryKey::Checkpoints(voter.clone());
+        let mut history = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        Self::push_checkpoint(&mut history, env.ledger().sequence(), stake);
+        env.storage().persistent().set(&key, &history);
+    }
+
+    // Helper: Stake of `voter` as checkpointed strictly before `ledger`
+    fn find_stake(env: &Env, voter: &Address, ledger: u32) -> i128 {
+        match env.storage().persistent().get(&EntryKey::Checkpoints(voter.clone())) {
+            Some(history) => Self::lookup_checkpoint(&history, ledger),
+            None => 0,
+        }
+    }
+
+    // Helper: Apply a delta to the total staked supply history. Every stake change adds to
+    // it, so each checkpoint is its own entry rather than one ever-growing list.
+    fn adjust_total_stake(env: &Env, delta: i128) -> Result<(), GovernanceError> {
+        let ledger = env.ledger().sequence();
+        let total = Self::checked_add(Self::total_stake_at(env, ledger + 1), delta)?;
+        let count: u32 = env.storage().instance().get(&EntryKey::TotalStakeCount).unwrap_or(0);
+        // Several changes in one ledger collapse into one checkpoint
+        let last: Option<Checkpoint> = count.checked_sub(1).and_then(|index| env.storage().persistent().get(&EntryKey::TotalStakeAt(index)));
+        let index = match last {
+            Some(checkpoint) if checkpoint.ledger == ledger => count - 1,
+            _ => {
+                env.storage().instance().set(&EntryKey::TotalStakeCount, &(count + 1));
+                count
+            }
+        };
+        env.storage().persistent().set(&EntryKey::TotalStakeAt(index), &Checkpoint { ledger, stake: total });
+        Ok(())
+    }
+
+    // Helper: Total staked supply as checkpointed strictly before `ledger`
+    fn total_stake_at(env: &Env, ledger: u32) -> i128 {
+        let count: u32 = env.storage().instance().get(&EntryKey::TotalStakeCount).unwrap_or(0);
+        Self::search_checkpoints(count, ledger, |index| env.storage().persistent().get(&EntryKey::TotalStakeAt(index)).unwrap())
+    }
+
+    // Helper: `a + b`, or Overflow
+    fn checked_add(a: i128, b: i128) -> Result<i128, GovernanceError> {
+        a.checked_add(b).ok_or(GovernanceError::Overflow)
+    }
+
+    // Helper: `a * b`, or Overflow
+    fn checked_mul(a: i128, b: i128) -> Result<i128, GovernanceError> {
+        a.checked_mul(b).ok_or(GovernanceError::Overflow)
+    }
+
+    // Helper: Proposal `proposal_id` from its own entry
+    fn load_proposal(env: &Env, proposal_id: u32) -> Result<Proposal, GovernanceError> {
+        env.storage().persistent().get(&EntryKey::Proposal(proposal_id)).ok_or(GovernanceError::ProposalNotFound)
+    }
+
+    fn save_proposal(env: &Env, proposal_id: u32, proposal: &Proposal) {
+        env.storage().persistent().set(&EntryKey::Proposal(proposal_id), proposal);
+    }
+
+    fn load_voter(env: &Env, voter: &Address) -> Option<VoterData> {
+        env.storage().persistent().get(&EntryKey::Voter(voter.clone()))
+    }
+
+    // Helper: Write a voter record, indexing the voter for `get_voters` on first write
+    fn save_voter(env: &Env, voter: &Address, voter_data: &VoterData) {
+        let key = EntryKey::Voter(voter.clone());
+        if !env.storage().persistent().has(&key) {
+            let count = Self::voter_count(env.clone());
+            env.storage().persistent().set(&EntryKey::VoterAt(count), voter);
+            env.storage().instance().set(&EntryKey::VoterCount, &(count + 1));
+        }
+        env.storage().persistent().set(&key, voter_data);
+    }
+
+    // Helper: Index range [start, end) of page `page` over `total` entries
+    fn page_bounds(page: u32, size: u32, total: u32) -> Result<(u32, u32), GovernanceError> {
+        if size == 0 || size > MAX_PAGE_SIZE {
+            return Err(GovernanceError::InvalidParams);
+        }
+        let start = page.saturating_mul(size).min(total);
+        Ok((start, start.saturating_add(size).min(total)))
+    }
+
+    // Helper: Fresh voter record
+    fn empty_voter(env: &Env) -> VoterData {
+        VoterData {
+            stake: 0,
+            vote_history: Vec::new(env),
+            unbonding: 0,
+            unbonding_release: 0,
+        }
+    }
+
+    // Helper: Append a checkpoint; several changes in one ledger collapse into one
+    fn push_checkpoint(history: &mut Vec<Checkpoint>, ledger: u32, stake: i128) {
+        let checkpoint = Checkpoint { ledger, stake };
+        match history.last() {
+            Some(last) if last.ledger == ledger => history.set(history.len() - 1, checkpoint),
+            _ => history.push_back(checkpoint),
+        }
+    }
+
+    // Helper: Last checkpoint in `history` strictly before `ledger`
+    fn lookup_checkpoint(history: &Vec<Checkpoint>, ledger: u32) -> i128 {
+        Self::search_checkpoints(history.len(), ledger, |index| history.get(index).unwrap())
+    }
+
+    // Helper: Binary search over `len` checkpoints, read through `at`, for the last one
+    // strictly before `ledger`
+    fn search_checkpoints(len: u32, ledger: u32, at: impl Fn(u32) -> Checkpoint) -> i128 {
+        let (mut low, mut high) = (0u32, len);
+        while low < high {
+            let mid = (low + high) / 2;
+            if at(mid).ledger < ledger {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == 0 {
+            0
+        } else {
+            at(low - 1).stake
+        }
+    }
+
+    // Helper: Voting power before `ledger`: own stake plus the stake delegated to the
+    // address, or zero if it delegates itself. Stake unstaked or undelegated since the
+    // snapshot no longer counts.
+    fn voting_power(env: &Env, addr: &Address, ledger: u32) -> i128 {
+        if Self::delegate_at(env, addr, ledger).is_some() {
+            return 0;
+        }
+        let current = Self::load_voter(env, addr).map(|v| v.stake).unwrap_or(0);
+        let own = Self::find_stake(env, addr, ledger).min(current);
+        let delegated = Self::delegated_at(env, addr, ledger).min(Self::delegated_at(env, addr, env.ledger().sequence() + 1));
+        // Delegated stakes are part of the checked total, so this cannot really saturate
+        own.saturating_add(delegated)
+    }
+
+    // Helper: Stake delegated to `addr` as checkpointed strictly before `ledger`
+    fn delegated_at(env: &Env, addr: &Address, ledger: u32) -> i128 {
+        match env.storage().persistent().get(&EntryKey::DelegatedPower(addr.clone())) {
+            Some(history) => Self::lookup_checkpoint(&history, ledger),
+            None => 0,
+        }
+    }
+
+    // Helper: What `delegator` passes on when it delegates: its stake plus the stake
+    // delegated to it
+    fn delegation_weight(env: &Env, delegator: &Address) -> Result<i128, GovernanceError> {
+        let stake = Self::load_voter(env, delegator).map(|v| v.stake).unwrap_or(0);
+        Self::checked_add(stake, Self::delegated_at(env, delegator, env.ledger().sequence() + 1))
+    }
+
+    // Helper: Add `delta` to the delegated power of every address down the chain `from`
+    // currently delegates along (at most MAX_DELEGATION_DEPTH links)
+    fn shift_power(env: &Env, from: &Address, delta: i128) -> Result<(), GovernanceError> {
+        let next_ledger = env.ledger().sequence() + 1;
+        let mut cursor = Self::delegate_at(env, from, next_ledger);
+        while let Some(delegate) = cursor {
+            let key = EntryKey::DelegatedPower(delegate.clone());
+            let mut history = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+            let power = Self::checked_add(Self::lookup_checkpoint(&history, next_ledger), delta)?;
+            Self::push_checkpoint(&mut history, env.ledger().sequence(), power);
+            env.storage().persistent().set(&key, &history);
+            cursor = Self::delegate_at(env, &delegate, next_ledger);
+        }
+        Ok(())
+    }
+
+    // Helper: Links in the longest delegation chain ending at `addr`, counted up to `limit`
+    fn chain_height(env: &Env, addr: &Address, limit: u32) -> u32 {
+        let mut height = 0;
+        if limit == 0 {
+            return height;
+        }
+        let delegators: Vec<Address> = env.storage().persistent().get(&EntryKey::Delegators(addr.clone())).unwrap_or(Vec::new(env));
+        for delegator in delegators.iter() {
+            height = height.max(1 + Self::chain_height(env, &delegator, limit - 1));
+            if height >= limit {
+                break;
+            }
+        }
+        height
+    }
+
+    // Helper: Drop `delegator` from the list of addresses delegating to `delegate`
+    fn remove_delegator(env: &Env, delegate: &Address, delegator: &Address) {
+        let key = EntryKey::Delegators(delegate.clone());
+        let mut delegators: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        if let Some(index) = delegators.first_index_of(delegator) {
+            delegators.remove(index);
+        }
+        if delegators.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &delegators);
+        }
+    }
+
+    // Helper: Record the delegation target from the current ledger onwards
+    fn write_delegation(env: &Env, delegator: &Address, delegate: Option<Address>) {
+        let ledger = env.ledger().sequence();
+        let key = EntryKey::Delegations(delegator.clone());
+        let mut history: Vec<DelegationCheckpoint> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        let checkpoint = DelegationCheckpoint { ledger, delegate };
+        match history.last() {
+            Some(last) if last.ledger == ledger => history.set(history.len() - 1, checkpoint),
+            _ => history.push_back(checkpoint),
+        }
+        env.storage().persistent().set(&key, &history);
+    }
+
+    // Helper: Binary search for the delegation in force strictly before `ledger`
+    fn delegate_at(env: &Env, delegator: &Address, ledger: u32) -> Option<Address> {
+        let history: Vec<DelegationCheckpoint> = env.storage().persistent().get(&EntryKey::Delegations(delegator.clone()))?;
+        let (mut low, mut high) = (0u32, history.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if history.get(mid).unwrap().ledger < ledger {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == 0 {
+            None
+        } else {
+            history.get(low - 1).unwrap().delegate
+        }
+    }
+
+    // Helper: Subtract a recorded vote from the proposal tallies
+    fn remove_weight(proposal: &mut Proposal, receipt: &VoteReceipt) {
+        if receipt.approve {
+            proposal.votes_for -= receipt.weight;
+        } else {
+            proposal.votes_against -= receipt.weight;
+        }
+    }
+
+    // Helper: Peg and governance-parameter changes ride the supermajority track
+    fn category_of(action: &Action) -> ProposalCategory {
+        match action {
+            Action::SetPeg(_) => ProposalCategory::PegChange,
+            Action::SetCategoryParams(_, _) | Action::SetScoringConfig(_) => ProposalCategory::Governance,
+            _ => ProposalCategory::Standard,
+        }
+    }
+
+    // Helper: Reject thresholds outside [0, 100%] or approval below simple majority
+    fn validate_params(params: &CategoryParams) -> Result<(), GovernanceError> {
+        if params.quorum_bps as i128 > BPS || params.approval_bps as i128 >= BPS || params.approval_bps < 5_000 || params.min_proposal_stake < 0 || params.deposit < 0 {
+            return Err(GovernanceError::InvalidParams);
+        }
+        Ok(())
+    }
+
+    // Helper: Check an action's parameters at creation, so a malformed proposal cannot pass
+    // and then fail at execution
+    fn validate_action(env: &Env, data: &GovernanceData, action: &Action) -> Result<(), GovernanceError> {
+        let valid = match action {
+            Action::SetPeg(peg) => {
+                let current: i128 = env.invoke_contract(&data.pi_token, &Symbol::new(env, "peg_value"), vec![env]);
+                *peg > 0 && scoring::relative_delta(current, *peg) <= MAX_PEG_STEP_BPS
+            }
+            Action::SetFee(fee_bps) => *fee_bps <= MAX_FEE_BPS,
+            // The new oracle must be a deployed contract other than the ones it would serve
+            Action::SetOracle(oracle) => {
+                matches!(oracle.executable(), Some(Executable::Wasm(_)))
+                    && *oracle != data.pi_token
+                    && *oracle != env.current_contract_address()
+            }
+            Action::UpgradeWasm(target, _) => matches!(target.executable(), Some(Executable::Wasm(_))),
+            Action::TransferTreasury(_, _, amount) => *amount > 0,
+            Action::Custom(contract, _, _) => contract.exists(),
+            Action::SetCategoryParams(_, params) => return Self::validate_params(params),
+            Action::SetScoringConfig(config) => {
+                if !config.is_valid() {
+                    return Err(GovernanceError::InvalidParams);
+                }
+                true
+            }
+        };
+        if !valid {
+            return Err(GovernanceError::InvalidAction);
+        }
+        Ok(())
+    }
+
+    // Helper: Dispatch a proposal action as a cross-contract call from governance
+    fn perform_action(env: &Env, data: &GovernanceData, action: &Action) {
+        match action {
+            Action::SetPeg(peg) => {
+                env.invoke_contract::<()>(&data.pi_token, &Symbol::new(env, "set_peg"), vec![env, peg.into_val(env)]);
+            }
+            Action::SetFee(fee_bps) => {
+                env.invoke_contract::<()>(&data.pi_token, &Symbol::new(env, "set_fee"), vec![env, fee_bps.into_val(env)]);
+            }
+            Action::SetOracle(oracle) => {
+                env.invoke_contract::<()>(&data.pi_token, &Symbol::new(env, "set_oracle"), vec![env, oracle.into_val(env)]);
+            }
+            Action::UpgradeWasm(target, wasm_hash) => {
+                if *target == env.current_contract_address() {
+                    // Self-upgrade: the new code's post_upgrade must be called in a later invocation
+                    env.deployer().update_current_contract_wasm(wasm_hash.clone());
+                } else {
+                    env.invoke_contract::<()>(target, &Symbol::new(env, "upgrade"), vec![env, wasm_hash.into_val(env)]);
+                    // The new wasm is live for subsequent calls: run its migration hook right away
+                    env.invoke_contract::<u32>(target, &Symbol::new(env, "post_upgrade"), Vec::new(env));
+                }
+            }
+            Action::TransferTreasury(asset, to, amount) => {
+                // Funds held by the treasury, subject to its per-epoch spending limits
+                env.invoke_contract::<()>(
+                    &data.treasury,
+                    &Symbol::new(env, "spend"),
+                    vec![env, asset.into_val(env), to.into_val(env), amount.into_val(env)],
+                );
+            }
+            Action::Custom(contract, func, args) => {
+                env.invoke_contract::<Val>(contract, func, args.clone());
+            }
+            Action::SetCategoryParams(category, params) => {
+                // Applied locally: reload so the write is based on the latest state
+                let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+                data.category_params.set(*category, params.clone());
+                env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+            }
+            Action::SetScoringConfig(config) => {
+                env.storage().instance().set(&ScoreKey::Config, config);
+            }
+        }
+    }
+
+    // Helper: Publish (name, proposal_id, EVENT_SCHEMA_VERSION) -> data
+    fn proposal_event(env: &Env, name: &str, proposal_id: u32, data: impl IntoVal<Env, Val>) {
+        env.events().publish((Symbol::new(env, name), proposal_id, EVENT_SCHEMA_VERSION), data.into_val(env));
+    }
+
+    // Helper: Publish (name, delegator, EVENT_SCHEMA_VERSION) -> data
+    fn delegation_event(env: &Env, name: &str, delegator: &Address, data: impl IntoVal<Env, Val>) {
+        env.events().publish((Symbol::new(env, name), delegator.clone(), EVENT_SCHEMA_VERSION), data.into_val(env));
+    }
+
+    // Helper: Scoring factors for `creator` proposing `action` with voting power `power`
+    fn score_factors(env: &Env, data: &GovernanceData, creator: &Address, action: &Action, category: ProposalCategory, power: i128) -> ScoreFactors {
+        let params = data.category_params.get(category).unwrap();
+        let total_staked = Self::total_stake_at(env, env.ledger().sequence() + 1);
+        ScoreFactors {
+            reputation: scoring::pass_rate(&Self::pass_record(env, &ScoreKey::Proposer(creator.clone()))),
+            backing: scoring::backing(power, total_staked, params.quorum_bps),
+            delta: scoring::BPS - Self::action_magnitude(env, data, action),
+            history: scoring::pass_rate(&Self::pass_record(env, &ScoreKey::Category(category))),
+        }
+    }
+
+    // Helper: How far `action` moves what it changes, 0..=BPS. Actions whose effect cannot
+    // be measured (oracle rotation, upgrades, custom calls, scoring changes) are full size.
+    fn action_magnitude(env: &Env, data: &GovernanceData, action: &Action) -> i128 {
+        match action {
+            Action::SetPeg(peg) => {
+                let current: i128 = env.invoke_contract(&data.pi_token, &Symbol::new(env, "peg_value"), vec![env]);
+                scoring::relative_delta(current, *peg)
+            }
+            Action::SetFee(fee_bps) => {
+                let (current, _): (u32, Option<Address>) = env.invoke_contract(&data.pi_token, &Symbol::new(env, "fee_config"), vec![env]);
+                scoring::fee_delta(current, *fee_bps)
+            }
+            Action::TransferTreasury(asset, _, amount) => {
+                let held = token::TokenClient::new(env, asset).balance(&data.treasury);
+                scoring::relative_delta(held, held - amount)
+            }
+            Action::SetCategoryParams(category, params) => {
+                let current = data.category_params.get(*category).unwrap();
+                scoring::relative_delta(current.quorum_bps as i128, params.quorum_bps as i128)
+                    .max(scoring::relative_delta(current.approval_bps as i128, params.approval_bps as i128))
+            }
+            Action::SetOracle(_) | Action::UpgradeWasm(_, _) | Action::Custom(_, _, _) | Action::SetScoringConfig(_) => scoring::BPS,
+        }
+    }
+
+    fn pass_record(env: &Env, key: &ScoreKey) -> PassRecord {
+        env.storage().persistent().get(key).unwrap_or_default()
+    }
+
+    // Helper: Count a finalized proposal in a pass record
+    fn record_outcome(env: &Env, key: &ScoreKey, passed: bool) {
+        let mut record = Self::pass_record(env, key);
+        record.finalized += 1;
+        if passed {
+            record.passed += 1;
+        }
+        env.storage().persistent().set(key, &record);
     }
 }
+
+mod test;
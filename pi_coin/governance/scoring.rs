@@ -0,0 +1,101 @@
+// Proposal scoring: a deterministic score in basis points, recomputable by anyone from
+// on-chain state at creation. Four factors, each 0..=BPS (higher is safer):
+// - reputation: the proposer's smoothed pass rate, (passed + 1) / (finalized + 2)
+// - backing: the proposer's own voting power as a share of the category's quorum
+// - delta: BPS minus the magnitude of the change the action makes
+// - history: the category's smoothed pass rate
+// The score is their weighted mean under governance-set weights.
+use soroban_sdk::contracttype;
+
+pub const BPS: i128 = 10_000;
+// A fee change of this many basis points counts as a full-magnitude change
+pub const FEE_DELTA_FULL_BPS: i128 = 1_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoringConfig {
+    pub reputation_weight: u32,
+    pub backing_weight: u32,
+    pub delta_weight: u32,
+    pub history_weight: u32,
+    pub min_score: u32, // Proposals scoring below this (bps) fail at finalization
+}
+
+impl ScoringConfig {
+    pub fn default_config() -> Self {
+        ScoringConfig { reputation_weight: 1, backing_weight: 1, delta_weight: 1, history_weight: 1, min_score: 0 }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.total_weight() > 0 && self.min_score as i128 <= BPS
+    }
+
+    fn total_weight(&self) -> i128 {
+        self.reputation_weight as i128 + self.backing_weight as i128 + self.delta_weight as i128 + self.history_weight as i128
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoreFactors {
+    pub reputation: i128,
+    pub backing: i128,
+    pub delta: i128,
+    pub history: i128,
+}
+
+// Finalized proposals and how many of them passed
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PassRecord {
+    pub passed: u32,
+    pub finalized: u32,
+}
+
+// Smoothed pass rate: a newcomer starts at one half instead of zero or one
+pub fn pass_rate(record: &PassRecord) -> i128 {
+    (record.passed as i128 + 1) * BPS / (record.finalized as i128 + 2)
+}
+
+// Share of the quorum (`total_staked` * `quorum_bps`) that `power` covers, capped at BPS.
+// Products saturate rather than overflow; a score is capped at BPS anyway.
+pub fn backing(power: i128, total_staked: i128, quorum_bps: u32) -> i128 {
+    let quorum = total_staked.saturating_mul(quorum_bps as i128) / BPS;
+    if quorum <= 0 {
+        return BPS;
+    }
+    (power.max(0).saturating_mul(BPS) / quorum).min(BPS)
+}
+
+// Relative change from `current` to `proposed` in basis points, capped at BPS. Any
+// change from zero is full magnitude.
+pub fn relative_delta(current: i128, proposed: i128) -> i128 {
+    if current == proposed {
+        return 0;
+    }
+    if current == 0 {
+        return BPS;
+    }
+    let (diff, base) = (proposed.abs_diff(current), current.unsigned_abs());
+    if diff >= base {
+        return BPS;
+    }
+    // diff * BPS only overflows for diffs past 10^34, where scaling the base down first
+    // loses nothing measurable
+    let scaled = diff.checked_mul(BPS as u128).map(|d| d / base).unwrap_or_else(|| diff / (base / BPS as u128));
+    scaled as i128
+}
+
+// Magnitude of moving the transfer fee from `current` to `proposed` basis points
+pub fn fee_delta(current: u32, proposed: u32) -> i128 {
+    (proposed.abs_diff(current) as i128 * BPS / FEE_DELTA_FULL_BPS).min(BPS)
+}
+
+// Weighted mean of the factors, in basis points
+pub fn combine(factors: &ScoreFactors, config: &ScoringConfig) -> i128 {
+    let weighted = factors.reputation * config.reputation_weight as i128
+        + factors.backing * config.backing_weight as i128
+        + factors.delta * config.delta_weight as i128
+        + factors.history * config.history_weight as i128;
+    weighted / config.total_weight()
+}
@@ -0,0 +1,672 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Events, Ledger}, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, Symbol, Vec};
+use crate::scoring::{self, BPS};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+use crate::migration::{GovernanceDataV1, MIGRATION_BATCH};
+use crate::{Action, Checkpoint, DelegationCheckpoint, EntryKey, GovernanceData, GovernanceError, PassRecord, PiCoinGovernance, PiCoinGovernanceClient, ProposalCategory, ScoreFactors, ScoringConfig, EXECUTION_WINDOW, UNBONDING_PERIOD};
+
+// Stand-in for PiCoinContract: moves deposits and stake without checking balances, reports
+// peg and fee. Balances wrap instead of trapping, so stake overflow stays reachable.
+#[contract]
+struct MockPi;
+
+#[contractimpl]
+impl MockPi {
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let from_balance = Self::balance(env.clone(), from.clone()).wrapping_sub(amount);
+        env.storage().instance().set(&from, &from_balance);
+        let to_balance = Self::balance(env.clone(), to.clone()).wrapping_add(amount);
+        env.storage().instance().set(&to, &to_balance);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn peg_value(_env: Env) -> i128 {
+        314_159_000_000
+    }
+
+    pub fn fee_config(_env: Env) -> (u32, Option<Address>) {
+        (0, None)
+    }
+}
+
+#[test]
+fn test_scoring_math() {
+    // Pass rates are smoothed: a newcomer scores one half, not zero or one
+    assert_eq!(scoring::pass_rate(&PassRecord { passed: 0, finalized: 0 }), 5_000);
+    assert_eq!(scoring::pass_rate(&PassRecord { passed: 3, finalized: 3 }), 8_000);
+    assert_eq!(scoring::pass_rate(&PassRecord { passed: 0, finalized: 8 }), 1_000);
+
+    // Backing: own power against the quorum (4% of 1_000_000 = 40_000), capped
+    assert_eq!(scoring::backing(10_000, 1_000_000, 400), 2_500);
+    assert_eq!(scoring::backing(80_000, 1_000_000, 400), BPS);
+    assert_eq!(scoring::backing(0, 0, 400), BPS);
+
+    // Change magnitudes, capped at a full-size change
+    assert_eq!(scoring::relative_delta(314_159, 314_159), 0);
+    assert_eq!(scoring::relative_delta(1_000, 1_100), 1_000);
+    assert_eq!(scoring::relative_delta(1_000, 5_000), BPS);
+    assert_eq!(scoring::relative_delta(0, 1), BPS);
+    assert_eq!(scoring::fee_delta(30, 130), 1_000);
+    assert_eq!(scoring::fee_delta(130, 30), 1_000);
+    assert_eq!(scoring::fee_delta(0, 5_000), BPS);
+
+    // Weighted mean; the same inputs always give the same score
+    let factors = ScoreFactors { reputation: 8_000, backing: 2_500, delta: 9_000, history: 5_000 };
+    assert_eq!(scoring::combine(&factors, &ScoringConfig::default_config()), 6_125);
+    let config = ScoringConfig { reputation_weight: 0, backing_weight: 1, delta_weight: 3, history_weight: 0, min_score: 0 };
+    assert_eq!(scoring::combine(&factors, &config), 7_375);
+    assert!(!ScoringConfig { reputation_weight: 0, backing_weight: 0, delta_weight: 0, history_weight: 0, min_score: 0 }.is_valid());
+    assert!(!ScoringConfig { min_score: 10_001, ..ScoringConfig::default_config() }.is_valid());
+}
+
+#[test]
+fn test_proposal_scores_track_outcomes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let proposer = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&proposer, 1_000_000).build();
+    let client = PiCoinGovernanceClient::new(&env, &env.register(PiCoinGovernance, (Address::generate(&env), &pi.address, Address::generate(&env))));
+    client.stake_tokens(&proposer, &200_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+
+    // First proposal: neutral record and history, full backing, an unmeasurable change
+    let oracle = env.register(MockPi, ());
+    let id = client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle.clone()));
+    let factors = ScoreFactors { reputation: 5_000, backing: BPS, delta: 0, history: 5_000 };
+    assert_eq!(client.score_factors_of(&id), factors);
+    assert_eq!(client.proposal(&id).ai_score, 5_000);
+
+    // Passing it improves both the proposer's record and the category's
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|li| li.sequence_number += 17_281);
+    client.finalize_proposal(&id);
+    assert_eq!(client.proposal(&id).status, Symbol::new(&env, "passed"));
+    assert_eq!(client.proposer_record(&proposer), PassRecord { passed: 1, finalized: 1 });
+    assert_eq!(client.category_record(&ProposalCategory::Standard), PassRecord { passed: 1, finalized: 1 });
+    let id = client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle));
+    assert_eq!(client.proposal(&id).ai_score, (6_666 + BPS + 6_666) / 4);
+}
+
+#[test]
+fn test_rejects_unauthorized_unfunded_and_finalized_proposals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let proposer = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&proposer, 100_000).build();
+    let contract_id = env.register(PiCoinGovernance, (Address::generate(&env), &pi.address, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &contract_id);
+    client.stake_tokens(&proposer, &100_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let oracle = env.register(MockPi, ());
+    let propose = || client.try_create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle.clone()));
+
+    // With everything staked there is no PI left for the deposit, and nothing is scored
+    assert!(propose().is_err());
+    assert_eq!(client.proposal_count(), 0);
+    assert_eq!(client.try_score_factors_of(&1), Err(Ok(GovernanceError::ProposalNotFound)));
+
+    // Proposals need the proposer's signature
+    pi.mint(&proposer, &10_000, &PiCoinSource::Mining);
+    env.set_auths(&[]);
+    assert!(propose().is_err());
+    env.mock_all_auths();
+    let id = client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle.clone()));
+    assert_eq!(pi.balance(&contract_id), 110_000);
+
+    // A finalized proposal takes no more votes and is not finalized or scored again
+    env.ledger().with_mut(|li| li.sequence_number += 17_282);
+    assert_eq!(client.try_vote(&proposer, &id, &true), Err(Ok(GovernanceError::VotingClosed)));
+    client.finalize_proposal(&id);
+    assert_eq!(client.try_vote(&proposer, &id, &true), Err(Ok(GovernanceError::ProposalNotActive)));
+    assert_eq!(client.try_finalize_proposal(&id), Err(Ok(GovernanceError::ProposalNotActive)));
+    assert_eq!(client.proposer_record(&proposer), PassRecord { passed: 0, finalized: 1 });
+}
+
+#[test]
+fn test_actions_validated_at_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let treasury = Address::generate(&env);
+    let client = PiCoinGovernanceClient::new(&env, &env.register(PiCoinGovernance, (Address::generate(&env), &pi, &treasury)));
+    let proposer = Address::generate(&env);
+    client.stake_tokens(&proposer, &1_000_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let propose = |action: Action| client.try_create_proposal(&proposer, &Symbol::new(&env, "p"), &Bytes::new(&env), &action);
+
+    // Peg changes are positive and move at most 10% at a time
+    assert_eq!(propose(Action::SetPeg(0)), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(propose(Action::SetPeg(350_000_000_000)), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(propose(Action::SetPeg(314_160_000_000)), Ok(Ok(1)));
+
+    // Fees stay within the token's cap
+    assert_eq!(propose(Action::SetFee(1_001)), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(propose(Action::SetFee(30)), Ok(Ok(2)));
+
+    // Oracles and upgrade targets must be deployed contracts; the token cannot be its own oracle
+    assert_eq!(propose(Action::SetOracle(Address::generate(&env))), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(propose(Action::SetOracle(pi.clone())), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(propose(Action::SetOracle(env.register(MockPi, ()))), Ok(Ok(3)));
+    let wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    assert_eq!(propose(Action::UpgradeWasm(Address::generate(&env), wasm_hash)), Err(Ok(GovernanceError::InvalidAction)));
+
+    // Treasury spends are positive; custom calls target an existing contract
+    assert_eq!(propose(Action::TransferTreasury(pi.clone(), treasury.clone(), 0)), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(propose(Action::Custom(Address::generate(&env), Symbol::new(&env, "f"), Vec::new(&env))), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(client.proposal_count(), 3);
+}
+
+#[test]
+fn test_cancel_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let admin = Address::generate(&env);
+    let client = PiCoinGovernanceClient::new(&env, &env.register(PiCoinGovernance, (&admin, &pi, Address::generate(&env))));
+    let proposer = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    client.stake_tokens(&proposer, &1_000_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let propose = || client.create_proposal(&proposer, &Symbol::new(&env, "fee"), &Bytes::new(&env), &Action::SetFee(30));
+    let cancelled = Symbol::new(&env, "cancelled");
+
+    // The proposer may withdraw before voting starts and gets the deposit back
+    let id = propose();
+    assert_eq!(client.try_cancel_proposal(&guardian, &id), Err(Ok(GovernanceError::Unauthorized)));
+    client.cancel_proposal(&proposer, &id);
+    let proposal = client.proposal(&id);
+    assert_eq!((proposal.status, proposal.deposit), (cancelled.clone(), 0));
+    assert_eq!(client.try_vote(&proposer, &id, &true), Err(Ok(GovernanceError::ProposalNotActive)));
+    assert_eq!(client.try_claim_deposit(&id), Err(Ok(GovernanceError::DepositNotClaimable)));
+    assert_eq!(client.try_cancel_proposal(&proposer, &id), Err(Ok(GovernanceError::ProposalNotActive)));
+
+    // Once voting starts only the guardian can cancel, up to execution
+    let id = propose();
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.vote(&proposer, &id, &true);
+    assert_eq!(client.try_cancel_proposal(&proposer, &id), Err(Ok(GovernanceError::CancelWindowClosed)));
+    client.set_guardian(&guardian);
+    assert_eq!(client.guardian(), Some(guardian.clone()));
+    env.ledger().with_mut(|li| li.sequence_number += 17_281);
+    client.finalize_proposal(&id);
+    client.cancel_proposal(&guardian, &id);
+    assert_eq!(client.proposal(&id).status, cancelled);
+    assert_eq!(client.try_execute(&id), Err(Ok(GovernanceError::ProposalNotPassed)));
+}
+
+#[test]
+fn test_proposals_and_voters_paginate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let client = PiCoinGovernanceClient::new(&env, &env.register(PiCoinGovernance, (Address::generate(&env), &pi, Address::generate(&env))));
+    let voters: Vec<Address> = Vec::from_array(&env, [Address::generate(&env), Address::generate(&env), Address::generate(&env)]);
+    for voter in voters.iter() {
+        client.stake_tokens(&voter, &1_000_000);
+    }
+    // Staking again does not index the voter twice
+    client.stake_tokens(&voters.get(0).unwrap(), &1);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    for fee in 1..=5 {
+        client.create_proposal(&voters.get(0).unwrap(), &Symbol::new(&env, "fee"), &Bytes::new(&env), &Action::SetFee(fee));
+    }
+
+    // Pages run in ID order; the last one is short and anything past it is empty
+    assert_eq!(client.proposal_count(), 5);
+    let page = client.get_proposals(&1, &2);
+    assert_eq!((page.get(0).unwrap().0, page.get(1).unwrap().0), (3, 4));
+    assert_eq!(page.get(0).unwrap().1.title, Symbol::new(&env, "fee"));
+    assert_eq!(client.get_proposals(&2, &2).len(), 1);
+    assert_eq!(client.get_proposals(&9, &2).len(), 0);
+    assert!(matches!(client.try_get_proposals(&0, &0), Err(Ok(GovernanceError::InvalidParams))));
+    assert!(matches!(client.try_get_proposals(&0, &51), Err(Ok(GovernanceError::InvalidParams))));
+
+    // Voters page in order of first stake
+    assert_eq!(client.voter_count(), 3);
+    let page = client.get_voters(&0, &2);
+    assert_eq!((page.get(0).unwrap().0, page.get(0).unwrap().1.stake), (voters.get(0).unwrap(), 1_000_001));
+    assert_eq!(page.get(1).unwrap().0, voters.get(1).unwrap());
+    assert_eq!(client.get_voters(&1, &2).get(0).unwrap().0, voters.get(2).unwrap());
+}
+
+#[test]
+fn test_v1_state_migrates_to_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let id = env.register(PiCoinGovernance, (Address::generate(&env), &pi, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &id);
+    let staker = Address::generate(&env);
+    let delegator = Address::generate(&env);
+    client.stake_tokens(&staker, &1_000_000);
+    client.stake_tokens(&delegator, &500_000);
+    client.delegate(&delegator, &staker);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.create_proposal(&staker, &Symbol::new(&env, "fee"), &Bytes::new(&env), &Action::SetFee(30));
+
+    // Rewrite the state as version 1 stored it: everything inside the instance entry
+    env.as_contract(&id, || {
+        let key = Symbol::new(&env, "gov_data");
+        let data: GovernanceData = env.storage().instance().get(&key).unwrap();
+        let persistent = env.storage().persistent();
+        let mut proposals = Map::new(&env);
+        proposals.set(1, persistent.get::<_, crate::Proposal>(&EntryKey::Proposal(1)).unwrap());
+        let (mut voters, mut checkpoints) = (Map::new(&env), Map::new(&env));
+        for voter in [staker.clone(), delegator.clone()] {
+            voters.set(voter.clone(), persistent.get::<_, crate::VoterData>(&EntryKey::Voter(voter.clone())).unwrap());
+            checkpoints.set(voter.clone(), persistent.get::<_, Vec<Checkpoint>>(&EntryKey::Checkpoints(voter.clone())).unwrap());
+            persistent.remove(&EntryKey::Voter(voter.clone()));
+            persistent.remove(&EntryKey::Checkpoints(voter));
+        }
+        let mut delegations = Map::new(&env);
+        delegations.set(delegator.clone(), persistent.get::<_, Vec<DelegationCheckpoint>>(&EntryKey::Delegations(delegator.clone())).unwrap());
+        // Version 2 kept everyone who ever delegated, current or not
+        let mut delegators = Map::new(&env);
+        delegators.set(staker.clone(), vec![&env, delegator.clone(), Address::generate(&env)]);
+        let total_stake_history = vec![&env, persistent.get::<_, Checkpoint>(&EntryKey::TotalStakeAt(0)).unwrap()];
+        for entry in [
+            EntryKey::Proposal(1),
+            EntryKey::VoterAt(0),
+            EntryKey::VoterAt(1),
+            EntryKey::Delegations(delegator.clone()),
+            EntryKey::Delegators(staker.clone()),
+            EntryKey::DelegatedPower(staker.clone()),
+            EntryKey::TotalStakeAt(0),
+        ] {
+            persistent.remove(&entry);
+        }
+        for entry in [EntryKey::ProposalCount, EntryKey::VoterCount, EntryKey::TotalStakeCount] {
+            env.storage().instance().remove(&entry);
+        }
+        let old = GovernanceDataV1 {
+            admin: data.admin,
+            pi_token: data.pi_token,
+            treasury: data.treasury,
+            proposals,
+            voters,
+            receipts: Map::new(&env),
+            checkpoints,
+            delegations,
+            delegators,
+            vote_keys: Map::new(&env),
+            vote_nonces: Map::new(&env),
+            bls_keys: Map::new(&env),
+            ai_model_hash: data.ai_model_hash,
+            category_params: data.category_params,
+            total_stake_history,
+            timelock_delay: data.timelock_delay,
+            voting_delay: data.voting_delay,
+            voting_period: data.voting_period,
+        };
+        env.storage().instance().set(&key, &old);
+        env.storage().instance().set(&Symbol::new(&env, "version"), &1u32);
+    });
+
+    // One call per layout step, then nothing is left to do
+    assert_eq!(client.post_upgrade(), 2);
+    assert_eq!(client.post_upgrade(), 3);
+    assert_eq!(client.post_upgrade(), 3);
+    assert_eq!(client.version(), 3);
+    assert_eq!(client.proposal_count(), 1);
+    assert_eq!(client.proposal(&1).title, Symbol::new(&env, "fee"));
+    assert_eq!(client.voter_count(), 2);
+    assert_eq!(client.delegated_power(&staker), 1_500_000);
+    env.as_contract(&id, || {
+        let delegators: Vec<Address> = env.storage().persistent().get(&EntryKey::Delegators(staker.clone())).unwrap();
+        assert_eq!(delegators, vec![&env, delegator.clone()]);
+    });
+
+    // Delegated power is checkpointed from the migration on, so the earlier snapshot
+    // counts the staker's own stake
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.vote(&staker, &1, &true);
+    assert_eq!(client.proposal(&1).votes_for, 1_000_000);
+}
+
+#[test]
+fn test_delegated_power_follows_chains() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let id = env.register(PiCoinGovernance, (Address::generate(&env), &pi, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &id);
+    let [a, b, c] = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    client.stake_tokens(&a, &100_000);
+    client.stake_tokens(&b, &200_000);
+    client.stake_tokens(&c, &400_000);
+    let delegators_of = |delegate: &Address| {
+        env.as_contract(&id, || env.storage().persistent().get::<_, Vec<Address>>(&EntryKey::Delegators(delegate.clone())).unwrap_or(Vec::new(&env)))
+    };
+
+    // a -> b -> c: the power ends up at c, and stake changes flow down the chain
+    client.delegate(&a, &b);
+    client.delegate(&b, &c);
+    assert_eq!((client.delegated_power(&a), client.delegated_power(&b), client.delegated_power(&c)), (0, 0, 700_000));
+    client.stake_tokens(&a, &50_000);
+    client.unstake(&b, &100_000);
+    assert_eq!(client.delegated_power(&c), 650_000);
+
+    // Redelegating moves a's weight and its place in the delegator lists
+    client.delegate(&a, &c);
+    assert_eq!((delegators_of(&b), delegators_of(&c)), (Vec::new(&env), vec![&env, b.clone(), a.clone()]));
+    assert_eq!(client.delegated_power(&c), 650_000);
+    client.undelegate(&b);
+    assert_eq!((client.delegated_power(&b), client.delegated_power(&c)), (100_000, 550_000));
+    assert_eq!(delegators_of(&c), vec![&env, a.clone()]);
+
+    // A proposal reads the power at its snapshot; weight taken back since no longer counts
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let proposal = client.create_proposal(&c, &Symbol::new(&env, "fee"), &Bytes::new(&env), &Action::SetFee(30));
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.undelegate(&a);
+    client.vote(&c, &proposal, &true);
+    assert_eq!(client.proposal(&proposal).votes_for, 400_000);
+
+    // Chains already ending at the delegator count towards the depth limit
+    let chain: Vec<Address> = Vec::from_iter(&env, (0..5).map(|_| Address::generate(&env)));
+    for index in 0..4 {
+        client.delegate(&chain.get(index).unwrap(), &chain.get(index + 1).unwrap());
+    }
+    assert!(matches!(client.try_delegate(&a, &chain.get(0).unwrap()), Err(Ok(GovernanceError::DelegationTooDeep))));
+    assert!(matches!(client.try_delegate(&chain.get(4).unwrap(), &a), Err(Ok(GovernanceError::DelegationTooDeep))));
+}
+
+#[test]
+fn test_migration_runs_in_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let id = env.register(PiCoinGovernance, (Address::generate(&env), &pi, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &id);
+
+    // Version 1 state with more voters and nonces than one call moves
+    let voters: Vec<Address> = Vec::from_iter(&env, (0..MIGRATION_BATCH + 10).map(|_| Address::generate(&env)));
+    env.as_contract(&id, || {
+        let key = Symbol::new(&env, "gov_data");
+        let data: GovernanceData = env.storage().instance().get(&key).unwrap();
+        let (mut voter_map, mut nonces) = (Map::new(&env), Map::new(&env));
+        for (index, voter) in voters.iter().enumerate() {
+            let record = crate::VoterData { stake: 0, vote_history: Vec::new(&env), unbonding: 0, unbonding_release: 0 };
+            voter_map.set(voter.clone(), record);
+            nonces.set(voter, index as u64);
+        }
+        let old = GovernanceDataV1 {
+            admin: data.admin,
+            pi_token: data.pi_token,
+            treasury: data.treasury,
+            proposals: Map::new(&env),
+            voters: voter_map,
+            receipts: Map::new(&env),
+            checkpoints: Map::new(&env),
+            delegations: Map::new(&env),
+            delegators: Map::new(&env),
+            vote_keys: Map::new(&env),
+            vote_nonces: nonces,
+            bls_keys: Map::new(&env),
+            ai_model_hash: data.ai_model_hash,
+            category_params: data.category_params,
+            total_stake_history: Vec::new(&env),
+            timelock_delay: data.timelock_delay,
+            voting_delay: data.voting_delay,
+            voting_period: data.voting_period,
+        };
+        env.storage().instance().set(&key, &old);
+        env.storage().instance().set(&Symbol::new(&env, "version"), &1u32);
+    });
+
+    // Each step takes two calls; the version only moves once its last batch has run
+    assert_eq!(client.post_upgrade(), 1);
+    assert_eq!(client.voter_count(), MIGRATION_BATCH);
+    assert_eq!(client.post_upgrade(), 2);
+    assert_eq!(client.post_upgrade(), 2);
+    assert_eq!(client.post_upgrade(), 3);
+    assert_eq!(client.voter_count(), MIGRATION_BATCH + 10);
+    for (index, voter) in voters.iter().enumerate() {
+        assert_eq!(client.vote_nonce(&voter), index as u64);
+    }
+
+    // A version no layout was ever written with has no migration
+    env.as_contract(&id, || env.storage().instance().set(&Symbol::new(&env, "version"), &9u32));
+    assert!(matches!(client.try_post_upgrade(), Err(Ok(GovernanceError::UnknownVersion))));
+}
+
+#[test]
+fn test_prune_finalized_archives_proposals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let proposer = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&proposer, 2_000_000).build();
+    let contract_id = env.register(PiCoinGovernance, (Address::generate(&env), &pi.address, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &contract_id);
+    client.stake_tokens(&proposer, &1_000_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let oracle = env.register(MockPi, ());
+    let propose = || client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle.clone()));
+
+    // One cancelled (deposit refunded), one rejected with quorum (deposit unclaimed), one still open
+    let cancelled = propose();
+    client.cancel_proposal(&proposer, &cancelled);
+    let rejected = propose();
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.vote(&proposer, &rejected, &false);
+    env.ledger().with_mut(|li| li.sequence_number += 17_281);
+    client.finalize_proposal(&rejected);
+    let open = propose();
+    let archived = (client.proposal(&cancelled), client.proposal(&rejected));
+
+    // Pruning stops at the open proposal; the keeper earns 5% of the unclaimed deposit
+    let before_ledger = env.ledger().sequence();
+    assert_eq!(client.prune_finalized(&keeper, &before_ledger), 2);
+    let hash = |proposal: crate::Proposal| env.crypto().sha256(&proposal.to_xdr(&env)).to_bytes();
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "proposal_archived"), cancelled, 1u32).into_val(&env),
+                (Symbol::new(&env, "cancelled"), hash(archived.0)).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "proposal_archived"), rejected, 1u32).into_val(&env),
+                (Symbol::new(&env, "failed"), hash(archived.1)).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(pi.balance(&keeper), 500);
+    assert_eq!(pi.balance(&proposer), 2_000_000 - 1_000_000 - 10_000 - 500);
+    assert!(matches!(client.try_proposal(&rejected), Err(Ok(GovernanceError::ProposalNotFound))));
+    let remaining = client.get_proposals(&0, &10);
+    assert_eq!((remaining.len(), remaining.get(0).unwrap().0), (1, open));
+    assert_eq!(client.prune_finalized(&keeper, &before_ledger), 0);
+}
+
+#[test]
+fn test_prune_rejects_unauthorized_early_and_archived_proposals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let proposer = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&proposer, 1_010_000).build();
+    let contract_id = env.register(PiCoinGovernance, (Address::generate(&env), &pi.address, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &contract_id);
+    client.stake_tokens(&proposer, &1_000_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let id = client.create_proposal(&proposer, &Symbol::new(&env, "fee"), &Bytes::new(&env), &Action::SetFee(30));
+    client.cancel_proposal(&proposer, &id);
+
+    // The keeper signs for its upkeep fee, and voting must have ended before the cutoff
+    let cutoff = env.ledger().sequence() + 17_282;
+    env.set_auths(&[]);
+    assert!(client.try_prune_finalized(&keeper, &cutoff).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.prune_finalized(&keeper, &env.ledger().sequence()), 0);
+    assert_eq!(client.proposal(&id).status, Symbol::new(&env, "cancelled"));
+
+    // Once archived, the proposal is gone for votes, claims and cancellation alike
+    env.ledger().with_mut(|li| li.sequence_number += 17_282);
+    assert_eq!(client.prune_finalized(&keeper, &cutoff), 1);
+    assert!(matches!(client.try_vote(&proposer, &id, &true), Err(Ok(GovernanceError::ProposalNotFound))));
+    assert!(matches!(client.try_claim_deposit(&id), Err(Ok(GovernanceError::ProposalNotFound))));
+    assert!(matches!(client.try_cancel_proposal(&proposer, &id), Err(Ok(GovernanceError::ProposalNotFound))));
+
+    // The cancelled deposit was refunded before pruning, so the keeper earns nothing
+    assert_eq!((pi.balance(&keeper), pi.balance(&proposer)), (0, 10_000));
+    assert_eq!(pi.balance(&contract_id), 1_000_000);
+}
+
+#[test]
+fn test_prune_skips_pending_and_expires_passed_proposals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let proposer = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&proposer, 2_000_000).build();
+    let contract_id = env.register(PiCoinGovernance, (Address::generate(&env), &pi.address, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &contract_id);
+    client.stake_tokens(&proposer, &1_000_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let oracle = env.register(MockPi, ());
+    let passed = client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle.clone()));
+    let rejected = client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(oracle));
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.vote(&proposer, &passed, &true);
+    client.vote(&proposer, &rejected, &false);
+    env.ledger().with_mut(|li| li.sequence_number += 17_281);
+    client.finalize_proposal(&passed);
+    client.finalize_proposal(&rejected);
+    let has_receipt = |id: u32| env.as_contract(&contract_id, || env.storage().persistent().has(&EntryKey::Receipt(id, proposer.clone())));
+
+    // The proposal awaiting execution is skipped, not a wall: the rejected one behind it
+    // goes, receipt and all, while the cursor waits at the passed one
+    let before_ledger = env.ledger().sequence();
+    assert_eq!(client.prune_finalized(&keeper, &before_ledger), 1);
+    assert!(matches!(client.try_proposal(&rejected), Err(Ok(GovernanceError::ProposalNotFound))));
+    assert!(!has_receipt(rejected));
+    assert!(has_receipt(passed));
+    let cursor: u32 = env.as_contract(&contract_id, || env.storage().instance().get(&EntryKey::PruneCursor).unwrap());
+    assert_eq!(cursor, passed);
+
+    // Left unexecuted past its window, the passed proposal expires and is pruned in turn
+    let archived = client.proposal(&passed);
+    env.ledger().with_mut(|li| li.timestamp = archived.eta + EXECUTION_WINDOW + 1);
+    assert!(matches!(client.try_execute(&passed), Err(Ok(GovernanceError::ProposalExpired))));
+    assert_eq!(client.prune_finalized(&keeper, &before_ledger), 1);
+    let hash = env.crypto().sha256(&archived.to_xdr(&env)).to_bytes();
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "proposal_archived"), passed, 1u32).into_val(&env),
+                (Symbol::new(&env, "expired"), hash).into_val(&env),
+            ),
+        ]
+    );
+    assert!(!has_receipt(passed));
+    assert_eq!(client.get_proposals(&0, &10).len(), 0);
+    assert_eq!(pi.balance(&keeper), 1_000);
+}
+
+#[test]
+fn test_arithmetic_overflow_is_an_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let client = PiCoinGovernanceClient::new(&env, &env.register(PiCoinGovernance, (Address::generate(&env), &pi, Address::generate(&env))));
+    let whale = Address::generate(&env);
+    let minnow = Address::generate(&env);
+
+    // Stakes and the staked total stop at i128::MAX instead of wrapping
+    client.stake_tokens(&whale, &(i128::MAX - 1));
+    client.stake_tokens(&whale, &1);
+    assert!(matches!(client.try_stake_tokens(&whale, &1), Err(Ok(GovernanceError::Overflow))));
+    assert!(matches!(client.try_stake_tokens(&minnow, &1), Err(Ok(GovernanceError::Overflow))));
+    assert_eq!(client.voter(&whale).stake, i128::MAX);
+    assert_eq!(client.voter(&minnow).stake, 0);
+
+    // The governance contract already holds i128::MAX, so no deposit fits on top of it
+    env.as_contract(&client.address, || {
+        let key = Symbol::new(&env, "gov_data");
+        let mut data: GovernanceData = env.storage().instance().get(&key).unwrap();
+        let mut params = data.category_params.get(ProposalCategory::Standard).unwrap();
+        params.deposit = 0;
+        data.category_params.set(ProposalCategory::Standard, params);
+        env.storage().instance().set(&key, &data);
+    });
+
+    // A full-size tally still finalizes: the quorum and approval products overflow into
+    // an error rather than a wrong outcome
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let id = client.create_proposal(&whale, &Symbol::new(&env, "fee"), &Bytes::new(&env), &Action::SetFee(30));
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.vote(&whale, &id, &true);
+    assert_eq!(client.proposal(&id).votes_for, i128::MAX);
+    env.ledger().with_mut(|li| li.sequence_number += 17_281);
+    assert!(matches!(client.try_finalize_proposal(&id), Err(Ok(GovernanceError::Overflow))));
+
+    // Scoring saturates at full magnitude
+    assert_eq!(scoring::relative_delta(1, i128::MAX), BPS);
+    assert_eq!(scoring::relative_delta(i128::MAX, i128::MIN), BPS);
+    assert_eq!(scoring::relative_delta(i128::MAX, i128::MAX / 10 * 9), 1_000);
+    assert_eq!(scoring::backing(i128::MAX, i128::MAX, 400), BPS);
+}
+
+#[test]
+fn test_stake_amounts_must_be_positive() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let client = PiCoinGovernanceClient::new(&env, &env.register(PiCoinGovernance, (Address::generate(&env), &pi, Address::generate(&env))));
+    let staker = Address::generate(&env);
+    client.stake_tokens(&staker, &100);
+
+    // A negative unstake would otherwise mint voting power out of nothing
+    for amount in [0, -1, i128::MIN] {
+        assert!(matches!(client.try_stake_tokens(&staker, &amount), Err(Ok(GovernanceError::InvalidAmount))));
+        assert!(matches!(client.try_unstake(&staker, &amount), Err(Ok(GovernanceError::InvalidAmount))));
+    }
+    assert_eq!(client.voter(&staker).stake, 100);
+    assert_eq!(client.voter(&staker).unbonding, 0);
+}
+
+#[test]
+fn test_stake_and_deposits_are_held_net_of_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let proposer = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&proposer, 2_000_000).build();
+    let contract_id = env.register(PiCoinGovernance, (Address::generate(&env), &pi.address, Address::generate(&env)));
+    let client = PiCoinGovernanceClient::new(&env, &contract_id);
+
+    // At a 1% fee the stake and the deposit are what actually arrived
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    client.stake_tokens(&proposer, &1_000_000);
+    assert_eq!(client.voter(&proposer).stake, 990_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let id = client.create_proposal(&proposer, &Symbol::new(&env, "oracle"), &Bytes::new(&env), &Action::SetOracle(env.register(MockPi, ())));
+    assert_eq!(client.proposal(&id).deposit, 9_900);
+    assert_eq!(pi.balance(&contract_id), 999_900);
+
+    // Nobody votes: the forfeited deposit is burned in full rather than stranded
+    env.ledger().with_mut(|li| li.sequence_number += 17_282);
+    client.finalize_proposal(&id);
+    assert_eq!(client.proposal(&id).deposit, 0);
+    assert_eq!(pi.balance(&contract_id), 990_000);
+
+    // And the whole credited stake can be withdrawn
+    client.unstake(&proposer, &990_000);
+    env.ledger().with_mut(|li| li.timestamp += UNBONDING_PERIOD);
+    assert_eq!(client.withdraw(&proposer), 990_000);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
@@ -0,0 +1,137 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Bytes, BytesN, Env, Symbol, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockState {
+    Locked,
+    Claimed,
+    Refunded,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lock {
+    pub sender: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub hashlock: BytesN<32>, // sha256 of the 32-byte preimage
+    pub timelock: u64, // Ledger timestamp from which the sender may refund
+    pub state: LockState,
+    pub preimage: Option<BytesN<32>>, // Revealed by the claim
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum HtlcKey {
+    NextId,
+    Lock(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum HtlcError {
+    LockNotFound = 1,
+    InvalidAmount = 2,
+    InvalidTimelock = 3,
+    NotLocked = 4,
+    WrongPreimage = 5,
+    Expired = 6,
+    NotExpired = 7,
+}
+
+#[contract]
+pub struct PiCoinHtlc;
+
+#[contractimpl]
+impl PiCoinHtlc {
+    // Constructor: hash time-locked PI for atomic swaps with Bitcoin and EVM chains. The
+    // hash is sha256 over a 32-byte preimage, as in the usual Bitcoin and EVM HTLC
+    // scripts, so one secret unlocks both legs. Give the PI leg a shorter timelock than
+    // the leg it is swapped against when the counterparty holds the secret.
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "HTLC initialized: hash time-locked PI for cross-chain swaps");
+    }
+
+    // Sender: Lock `amount` PI for `recipient` behind `hashlock` until `timelock` (a ledger
+    // timestamp). The lock holds what the contract actually received, net of any PI
+    // transfer fee; quote that amount to the counterparty. Returns the lock id.
+    pub fn lock(env: Env, sender: Address, recipient: Address, amount: i128, hashlock: BytesN<32>, timelock: u64) -> Result<u64, HtlcError> {
+        sender.require_auth();
+        if amount <= 0 {
+            return Err(HtlcError::InvalidAmount);
+        }
+        if timelock <= env.ledger().timestamp() {
+            return Err(HtlcError::InvalidTimelock);
+        }
+        let amount = receive::pull(&env, &Self::pi(&env).address, &sender, amount).unwrap_or(0);
+        if amount <= 0 {
+            return Err(HtlcError::InvalidAmount);
+        }
+        let id: u64 = env.storage().instance().get(&HtlcKey::NextId).unwrap_or(1);
+        env.storage().instance().set(&HtlcKey::NextId, &(id + 1));
+        let lock = Lock { sender: sender.clone(), recipient: recipient.clone(), amount, hashlock: hashlock.clone(), timelock, state: LockState::Locked, preimage: None };
+        env.storage().persistent().set(&HtlcKey::Lock(id), &lock);
+        env.events().publish((Symbol::new(&env, "htlc_locked"), sender, recipient, id), (amount, hashlock, timelock));
+        Ok(id)
+    }
+
+    // Pay lock `id` to its recipient by revealing the preimage of its hashlock, before the
+    // timelock. Anyone may submit it. The preimage is published in the "htlc_claimed"
+    // event (and kept on the lock) for the counterparty to claim the other leg.
+    pub fn claim(env: Env, id: u64, preimage: BytesN<32>) -> Result<(), HtlcError> {
+        let mut lock = Self::lock_info(env.clone(), id)?;
+        if lock.state != LockState::Locked {
+            return Err(HtlcError::NotLocked);
+        }
+        if env.ledger().timestamp() >= lock.timelock {
+            return Err(HtlcError::Expired);
+        }
+        let hash: BytesN<32> = env.crypto().sha256(&Bytes::from(preimage.clone())).to_bytes();
+        if hash != lock.hashlock {
+            return Err(HtlcError::WrongPreimage);
+        }
+        lock.state = LockState::Claimed;
+        lock.preimage = Some(preimage.clone());
+        env.storage().persistent().set(&HtlcKey::Lock(id), &lock);
+        Self::pi(&env).transfer(&env.current_contract_address(), &lock.recipient, &lock.amount);
+        env.events().publish((Symbol::new(&env, "htlc_claimed"), lock.recipient, id), (lock.hashlock, preimage));
+        Ok(())
+    }
+
+    // Return lock `id` to its sender once the timelock has passed unclaimed. Anyone may
+    // submit it.
+    pub fn refund(env: Env, id: u64) -> Result<(), HtlcError> {
+        let mut lock = Self::lock_info(env.clone(), id)?;
+        if lock.state != LockState::Locked {
+            return Err(HtlcError::NotLocked);
+        }
+        if env.ledger().timestamp() < lock.timelock {
+            return Err(HtlcError::NotExpired);
+        }
+        lock.state = LockState::Refunded;
+        env.storage().persistent().set(&HtlcKey::Lock(id), &lock);
+        Self::pi(&env).transfer(&env.current_contract_address(), &lock.sender, &lock.amount);
+        env.events().publish((Symbol::new(&env, "htlc_refunded"), lock.sender, id), lock.amount);
+        Ok(())
+    }
+
+    // View: Lock `id`
+    pub fn lock_info(env: Env, id: u64) -> Result<Lock, HtlcError> {
+        env.storage().persistent().get(&HtlcKey::Lock(id)).ok_or(HtlcError::LockNotFound)
+    }
+}
+
+impl PiCoinHtlc {
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap();
+        token::TokenClient::new(env, &pi_token)
+    }
+}
+
+mod test;
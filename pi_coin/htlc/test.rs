@@ -0,0 +1,103 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, vec, Address, Bytes, BytesN, Env, IntoVal, Symbol};
+use crate::{HtlcError, LockState, PiCoinHtlc, PiCoinHtlcClient};
+use pi_coin_testutils::TokenBuilder;
+
+#[test]
+fn test_claim_reveals_preimage_and_refund_after_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 1_000).build();
+    let contract_id = env.register(PiCoinHtlc, (&pi.address,));
+    let client = PiCoinHtlcClient::new(&env, &contract_id);
+    let secret = BytesN::from_array(&env, &[42; 32]);
+    let hashlock: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+    assert_eq!(client.try_lock(&alice, &bob, &400, &hashlock, &1_000), Err(Ok(HtlcError::InvalidTimelock)));
+
+    // Bob claims with the secret; the event hands it to Alice for the other chain
+    let id = client.lock(&alice, &bob, &400, &hashlock, &4_600);
+    assert_eq!(client.try_claim(&id, &BytesN::from_array(&env, &[7; 32])), Err(Ok(HtlcError::WrongPreimage)));
+    client.claim(&id, &secret);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "htlc_claimed"), bob.clone(), id).into_val(&env),
+                (hashlock.clone(), secret.clone()).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.lock_info(&id).preimage, Some(secret.clone()));
+    assert_eq!(client.try_refund(&id), Err(Ok(HtlcError::NotLocked)));
+    assert_eq!(pi.balance(&bob), 400);
+
+    // Unclaimed locks return to the sender from the timelock on, and can no longer be claimed
+    let id = client.lock(&alice, &bob, &100, &hashlock, &4_600);
+    assert_eq!(client.try_refund(&id), Err(Ok(HtlcError::NotExpired)));
+    env.ledger().set_timestamp(4_600);
+    assert_eq!(client.try_claim(&id, &secret), Err(Ok(HtlcError::Expired)));
+    client.refund(&id);
+    assert_eq!(client.lock_info(&id).state, LockState::Refunded);
+    assert_eq!((pi.balance(&alice), pi.balance(&contract_id)), (600, 0));
+}
+
+#[test]
+fn test_lock_holds_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 1_000).build();
+    let contract_id = env.register(PiCoinHtlc, (&pi.address,));
+    let client = PiCoinHtlcClient::new(&env, &contract_id);
+    let secret = BytesN::from_array(&env, &[42; 32]);
+    let hashlock: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+
+    // At a 1% fee the lock holds 990, and the refund returns all of it
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    let id = client.lock(&alice, &bob, &1_000, &hashlock, &2_000);
+    assert_eq!(client.lock_info(&id).amount, 990);
+    env.ledger().set_timestamp(2_000);
+    client.refund(&id);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_settled_locks() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&alice, 500).build();
+    let contract_id = env.register(PiCoinHtlc, (&pi.address,));
+    let client = PiCoinHtlcClient::new(&env, &contract_id);
+    let secret = BytesN::from_array(&env, &[42; 32]);
+    let hashlock: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+
+    // Locking needs the sender's signature, a positive amount and the PI to back it
+    env.set_auths(&[]);
+    assert!(client.try_lock(&alice, &bob, &100, &hashlock, &2_000).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.try_lock(&alice, &bob, &0, &hashlock, &2_000), Err(Ok(HtlcError::InvalidAmount)));
+    assert!(client.try_lock(&alice, &bob, &501, &hashlock, &2_000).is_err());
+    assert_eq!(client.try_lock_info(&1), Err(Ok(HtlcError::LockNotFound)));
+
+    // A refunded lock cannot be claimed or refunded again
+    let id = client.lock(&alice, &bob, &500, &hashlock, &2_000);
+    env.ledger().set_timestamp(2_000);
+    client.refund(&id);
+    assert_eq!(client.try_refund(&id), Err(Ok(HtlcError::NotLocked)));
+    assert_eq!(client.try_claim(&id, &secret), Err(Ok(HtlcError::NotLocked)));
+    assert_eq!((pi.balance(&alice), pi.balance(&bob)), (500, 0));
+}
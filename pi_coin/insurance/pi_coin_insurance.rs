@@ -0,0 +1,259 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Fixed-point scale of auction prices (PI per backstop token)
+pub const PRICE_ONE: i128 = 10_000_000;
+const BPS: i128 = 10_000;
+
+// Entry points of PiCoinLending the fund backs
+#[contractclient(name = "LendingPoolClient")]
+pub trait LendingPool {
+    fn cover_bad_debt(env: Env, from: Address, amount: i128) -> i128;
+    fn total_borrows(env: Env) -> i128;
+}
+
+// Minting entry point of the backstop (governance) token; this contract must be its admin
+#[contractclient(name = "BackstopTokenClient")]
+pub trait BackstopToken {
+    fn mint(env: Env, to: Address, amount: i128);
+}
+
+// Dutch auction terms: the price falls linearly from `start_price` to `floor_price` over
+// `duration` seconds, then stays at the floor until the shortfall is raised
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionConfig {
+    pub start_price: i128, // PI per backstop token, scaled by PRICE_ONE
+    pub floor_price: i128,
+    pub duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct InsuranceData {
+    pub governance: Address, // Sets the auction terms
+    pub pi_token: Address,
+    pub pool: Address, // Lending pool whose bad debt the fund covers
+    pub backstop_token: Address,
+    pub auction_config: AuctionConfig,
+    pub contributed: i128, // PI paid in through `contribute`
+    pub covered: i128, // Bad debt paid off, from the fund and auctions together
+    pub shortfall: i128, // Bad debt the fund could not cover and auctions have not raised yet
+}
+
+// A running shortfall auction
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub raise: i128, // PI still to raise
+    pub started_at: u64,
+}
+
+// Fund health at a glance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundHealth {
+    pub balance: i128, // PI on hand to cover the next bad debt
+    pub contributed: i128,
+    pub covered: i128,
+    pub shortfall: i128,
+    pub coverage_bps: i128, // `coverage_ratio()`
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InsuranceKey {
+    Auction, // Instance: the running shortfall auction, if any
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum InsuranceError {
+    InvalidAmount = 1,
+    InvalidConfig = 2,
+    NoAuction = 3,
+}
+
+#[contract]
+pub struct PiCoinInsurance;
+
+#[contractimpl]
+impl PiCoinInsurance {
+    // Constructor: insurance fund for `pool`, set as the pool's liquidation hook. It is
+    // capitalized by a slice of protocol fees (treasury spends, the pool's reserves) and
+    // pays off bad debt as liquidations report it. Whatever it cannot pay is raised by a
+    // backstop auction that mints `backstop_token` to bidders for PI.
+    pub fn __constructor(
+        env: Env,
+        governance: Address,
+        pi_token: Address,
+        pool: Address,
+        backstop_token: Address,
+        auction_config: AuctionConfig,
+    ) -> Result<(), InsuranceError> {
+        Self::validate(&auction_config)?;
+        let data = InsuranceData {
+            governance,
+            pi_token,
+            pool,
+            backstop_token,
+            auction_config,
+            contributed: 0,
+            covered: 0,
+            shortfall: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "insurance_data"), &data);
+        log!(&env, "Insurance fund initialized: bad debt covered by fees, then by backstop auctions");
+        Ok(())
+    }
+
+    // Pay `amount` PI into the fund (a fee share routed here)
+    pub fn contribute(env: Env, from: Address, amount: i128) -> Result<(), InsuranceError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&from, env.current_contract_address(), &amount);
+        data.contributed += amount;
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "insurance_contribute"), from), amount);
+        Ok(())
+    }
+
+    // Pool-only (liquidation hook): Cover a liquidation's bad debt from the fund by
+    // sending the pool PI, and start (or grow) a backstop auction for any remainder
+    pub fn on_liquidation(env: Env, borrower: Address, _asset: Address, _repaid: i128, _seized: i128, bad_debt: i128) {
+        let mut data = Self::load(&env);
+        data.pool.require_auth();
+        if bad_debt <= 0 {
+            return;
+        }
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let paid = bad_debt.min(pi.balance(&env.current_contract_address()));
+        if paid > 0 {
+            pi.transfer(&env.current_contract_address(), &data.pool, &paid);
+            data.covered += paid;
+        }
+        let rest = bad_debt - paid;
+        if rest > 0 {
+            data.shortfall += rest;
+            let auction = match Self::auction(env.clone()) {
+                Some(auction) => Auction { raise: auction.raise + rest, ..auction },
+                None => Auction { raise: rest, started_at: env.ledger().timestamp() },
+            };
+            env.storage().instance().set(&InsuranceKey::Auction, &auction);
+            env.events().publish((Symbol::new(&env, "insurance_auction"),), auction.raise);
+        }
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "insurance_cover"), borrower), (bad_debt, paid));
+    }
+
+    // Buy backstop tokens at the current auction price with up to `amount` PI (at most
+    // what the auction still has to raise). The PI goes straight to the pool against its
+    // bad debt. Returns the backstop tokens minted.
+    pub fn bid(env: Env, bidder: Address, amount: i128) -> Result<i128, InsuranceError> {
+        bidder.require_auth();
+        if amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        let mut auction = Self::auction(env.clone()).ok_or(InsuranceError::NoAuction)?;
+        let paid = amount.min(auction.raise);
+        let minted = paid * PRICE_ONE / Self::price_at(&env, &data.auction_config, &auction);
+        if minted == 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        auction.raise -= paid;
+        if auction.raise == 0 {
+            env.storage().instance().remove(&InsuranceKey::Auction);
+        } else {
+            env.storage().instance().set(&InsuranceKey::Auction, &auction);
+        }
+        data.shortfall = (data.shortfall - paid).max(0);
+        data.covered += paid;
+        Self::save(&env, &data);
+
+        let fund = env.current_contract_address();
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        pi.transfer(&bidder, &fund, &paid);
+        BackstopTokenClient::new(&env, &data.backstop_token).mint(&bidder, &minted);
+        pi.approve(&fund, &data.pool, &paid, &env.ledger().sequence());
+        LendingPoolClient::new(&env, &data.pool).cover_bad_debt(&fund, &paid);
+        env.events().publish((Symbol::new(&env, "insurance_bid"), bidder), (paid, minted, auction.raise));
+        Ok(minted)
+    }
+
+    // Governance-only: Set the terms of future auctions (and the running one's curve)
+    pub fn set_auction_config(env: Env, config: AuctionConfig) -> Result<(), InsuranceError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(&config)?;
+        data.auction_config = config;
+        Self::save(&env, &data);
+        Ok(())
+    }
+
+    // View: PI on hand over the pool's outstanding borrows, in basis points; i128::MAX
+    // while nothing is borrowed
+    pub fn coverage_ratio(env: Env) -> i128 {
+        let data = Self::load(&env);
+        let borrows = LendingPoolClient::new(&env, &data.pool).total_borrows();
+        if borrows <= 0 {
+            return i128::MAX;
+        }
+        Self::balance(&env, &data) * BPS / borrows
+    }
+
+    // View: Balance, lifetime totals, outstanding shortfall and coverage ratio
+    pub fn health(env: Env) -> FundHealth {
+        let data = Self::load(&env);
+        FundHealth {
+            balance: Self::balance(&env, &data),
+            contributed: data.contributed,
+            covered: data.covered,
+            shortfall: data.shortfall,
+            coverage_bps: Self::coverage_ratio(env.clone()),
+        }
+    }
+
+    // View: The running shortfall auction, if any
+    pub fn auction(env: Env) -> Option<Auction> {
+        env.storage().instance().get(&InsuranceKey::Auction)
+    }
+
+    // View: Current auction price in PI per backstop token, scaled by PRICE_ONE
+    pub fn auction_price(env: Env) -> Option<i128> {
+        let auction = Self::auction(env.clone())?;
+        Some(Self::price_at(&env, &Self::load(&env).auction_config, &auction))
+    }
+
+    // Helper: Linear decay from the start price to the floor over the auction's duration
+    fn price_at(env: &Env, config: &AuctionConfig, auction: &Auction) -> i128 {
+        let elapsed = env.ledger().timestamp().saturating_sub(auction.started_at).min(config.duration) as i128;
+        config.start_price - (config.start_price - config.floor_price) * elapsed / config.duration as i128
+    }
+
+    // Helper: A positive floor no higher than the start price, over a nonzero duration
+    fn validate(config: &AuctionConfig) -> Result<(), InsuranceError> {
+        if config.floor_price <= 0 || config.floor_price > config.start_price || config.duration == 0 {
+            return Err(InsuranceError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn balance(env: &Env, data: &InsuranceData) -> i128 {
+        token::TokenClient::new(env, &data.pi_token).balance(&env.current_contract_address())
+    }
+
+    fn save(env: &Env, data: &InsuranceData) {
+        env.storage().instance().set(&Symbol::new(env, "insurance_data"), data);
+    }
+
+    fn load(env: &Env) -> InsuranceData {
+        env.storage().instance().get(&Symbol::new(env, "insurance_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,82 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Ledger}, token, Address, Env};
+use crate::{Auction, AuctionConfig, InsuranceError, PiCoinInsurance, PiCoinInsuranceClient, PRICE_ONE};
+
+// Just enough of PiCoinLending: outstanding borrows, and bad debt covered out of an allowance
+#[contract]
+struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn __constructor(env: Env, pi: Address, total_borrows: i128) {
+        env.storage().instance().set(&0u32, &pi);
+        env.storage().instance().set(&1u32, &total_borrows);
+    }
+
+    pub fn total_borrows(env: Env) -> i128 {
+        env.storage().instance().get(&1u32).unwrap()
+    }
+
+    pub fn cover_bad_debt(env: Env, from: Address, amount: i128) -> i128 {
+        from.require_auth();
+        let pi: Address = env.storage().instance().get(&0u32).unwrap();
+        let pool = env.current_contract_address();
+        token::TokenClient::new(&env, &pi).transfer_from(&pool, &from, &pool, &amount);
+        amount
+    }
+}
+
+#[test]
+fn test_fund_covers_bad_debt_then_auctions_the_shortfall() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let pi_client = token::TokenClient::new(&env, &pi);
+    let pi_admin = token::StellarAssetClient::new(&env, &pi);
+    let backstop = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let backstop_client = token::TokenClient::new(&env, &backstop);
+    let (treasury, bidder) = (Address::generate(&env), Address::generate(&env));
+    pi_admin.mint(&treasury, &300);
+    pi_admin.mint(&bidder, &1_000);
+
+    // Auction price falls from 2 PI to 0.5 PI per backstop token over 1,000 seconds
+    let pool = env.register(MockPool, (&pi, 6_000i128));
+    let config = AuctionConfig { start_price: 2 * PRICE_ONE, floor_price: PRICE_ONE / 2, duration: 1_000 };
+    let fund = env.register(PiCoinInsurance, (Address::generate(&env), &pi, &pool, &backstop, config.clone()));
+    let client = PiCoinInsuranceClient::new(&env, &fund);
+    token::StellarAssetClient::new(&env, &backstop).set_admin(&fund);
+    let above_start = AuctionConfig { floor_price: 3 * PRICE_ONE, ..config };
+    assert!(matches!(client.try_set_auction_config(&above_start), Err(Ok(InsuranceError::InvalidConfig))));
+
+    // 300 PI of fees against 6,000 PI borrowed
+    client.contribute(&treasury, &300);
+    assert_eq!(client.coverage_ratio(), 500);
+    assert!(matches!(client.try_bid(&bidder, &100), Err(Ok(InsuranceError::NoAuction))));
+
+    // The fund pays what it can; the rest goes to auction
+    client.on_liquidation(&Address::generate(&env), &Address::generate(&env), &0, &0, &100);
+    assert_eq!(pi_client.balance(&pool), 100);
+    client.on_liquidation(&Address::generate(&env), &Address::generate(&env), &0, &0, &500);
+    assert_eq!(pi_client.balance(&pool), 300);
+    assert_eq!(client.auction(), Some(Auction { raise: 300, started_at: 1_000 }));
+    let health = client.health();
+    assert_eq!((health.balance, health.covered, health.shortfall, health.coverage_bps), (0, 300, 300, 0));
+
+    // Halfway through, a token costs 1.25 PI
+    env.ledger().set_timestamp(1_500);
+    assert_eq!(client.auction_price(), Some(PRICE_ONE * 5 / 4));
+    assert_eq!(client.bid(&bidder, &125), 100);
+    assert_eq!(pi_client.balance(&pool), 425);
+
+    // At the floor, the last bid is capped at what is left to raise
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(client.bid(&bidder, &1_000), 350);
+    assert_eq!(backstop_client.balance(&bidder), 450);
+    assert_eq!(pi_client.balance(&bidder), 700);
+    assert_eq!(pi_client.balance(&pool), 600);
+    assert_eq!(client.auction(), None);
+    assert_eq!(client.health().shortfall, 0);
+    assert_eq!(client.health().covered, 600);
+}
@@ -0,0 +1,222 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, Address, Env, Map, Symbol, Vec, log};
+
+pub const PI_DECIMALS: u32 = 7;
+
+// Same fields (and XDR) as PiCoinLending's CollateralConfig
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralConfig {
+    pub symbol: Symbol,
+    pub decimals: u32,
+    pub ltv_bps: u32,
+    pub liquidation_threshold_bps: u32,
+    pub liquidation_bonus_bps: u32,
+}
+
+// Same fields (and XDR) as PiCoinTreasury's AssetReport
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetReport {
+    pub asset: Address,
+    pub balance: i128,
+    pub epoch_limit: Option<i128>,
+    pub spent_this_epoch: i128,
+}
+
+// PiCoinContract views the checker reads, and the breaker it trips
+#[contractclient(name = "PiTokenClient")]
+pub trait PiToken {
+    fn balance(env: Env, id: Address) -> i128;
+    fn circulating_supply(env: Env) -> i128;
+    fn pause(env: Env, caller: Address, reason: Symbol);
+}
+
+// PiCoinLending views giving each borrower's debt and collateral
+#[contractclient(name = "LendingClient")]
+pub trait Lending {
+    fn debt_of(env: Env, borrower: Address) -> i128;
+    fn collaterals(env: Env) -> Vec<Address>;
+    fn collateral_of(env: Env, owner: Address, asset: Address) -> i128;
+    fn collateral_config(env: Env, asset: Address) -> Option<CollateralConfig>;
+}
+
+// PiCoinOracle price feed the collateral is valued at
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn query_price(env: Env, asset: Symbol) -> i128;
+}
+
+// PiCoinTreasury's balance report
+#[contractclient(name = "TreasuryClient")]
+pub trait Treasury {
+    fn treasury_report(env: Env) -> Vec<AssetReport>;
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvariantConfig {
+    pub pi_token: Address,
+    pub lending: Address,
+    pub oracle: Address, // Prices PI and the lending collateral
+    pub pi_symbol: Symbol, // Oracle key PI is priced under
+    pub treasury: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Invariant {
+    SupplyConserved, // Sum of balances == circulating supply
+    Collateralized, // Each borrower's collateral value >= debt
+    TreasurySolvent, // Each treasury balance >= 0
+}
+
+// A failed check: `actual` should have been `required` (SupplyConserved) or at least it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub invariant: Invariant,
+    pub subject: Address, // The token, the borrower or the treasury asset
+    pub required: i128,
+    pub actual: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct InvariantsData {
+    pub governance: Address, // Sets the config
+    pub config: InvariantConfig,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum InvariantsError {
+    SupplyMismatch = 1,
+    Undercollateralized = 2,
+    TreasuryNegative = 3,
+}
+
+#[contract]
+pub struct PiCoinInvariants;
+
+#[contractimpl]
+impl PiCoinInvariants {
+    // Constructor: conservation checks over the ecosystem's books. Tests call `check_all`
+    // after every operation; on-chain, keepers call `check_and_pause`, which trips the
+    // token's pause breaker when a check fails (appoint this contract with
+    // set_pause_sentinel). Holders and borrowers cannot be enumerated on-chain, so callers
+    // pass them, typically from an indexer of the token's and the lending market's events.
+    pub fn __constructor(env: Env, governance: Address, config: InvariantConfig) {
+        env.storage().instance().set(&Symbol::new(&env, "invariants_data"), &InvariantsData { governance, config });
+        log!(&env, "Invariant checker initialized: supply, collateral and treasury conservation");
+    }
+
+    // View: Every failed check. `holders` must be every PI holder for SupplyConserved to be
+    // meaningful; duplicates are counted once.
+    pub fn violations(env: Env, holders: Vec<Address>, borrowers: Vec<Address>) -> Vec<Violation> {
+        let config = Self::load(&env).config;
+        let mut violations = Vec::new(&env);
+        let token = PiTokenClient::new(&env, &config.pi_token);
+        let circulating = token.circulating_supply();
+        let held = Self::sum_balances(&env, &token, &holders);
+        if held != circulating {
+            violations.push_back(Violation { invariant: Invariant::SupplyConserved, subject: config.pi_token.clone(), required: circulating, actual: held });
+        }
+        for borrower in borrowers.iter() {
+            let debt = LendingClient::new(&env, &config.lending).debt_of(&borrower);
+            let value = Self::collateral_value(&env, &config, &borrower);
+            if value < debt {
+                violations.push_back(Violation { invariant: Invariant::Collateralized, subject: borrower, required: debt, actual: value });
+            }
+        }
+        for line in TreasuryClient::new(&env, &config.treasury).treasury_report().iter() {
+            if line.balance < 0 {
+                violations.push_back(Violation { invariant: Invariant::TreasurySolvent, subject: line.asset, required: 0, actual: line.balance });
+            }
+        }
+        violations
+    }
+
+    // View: Fail with the first violated invariant, for tests to call after every
+    // operation. Same arguments as `violations`.
+    pub fn check_all(env: Env, holders: Vec<Address>, borrowers: Vec<Address>) -> Result<(), InvariantsError> {
+        match Self::violations(env, holders, borrowers).first().map(|violation| violation.invariant) {
+            None => Ok(()),
+            Some(Invariant::SupplyConserved) => Err(InvariantsError::SupplyMismatch),
+            Some(Invariant::Collateralized) => Err(InvariantsError::Undercollateralized),
+            Some(Invariant::TreasurySolvent) => Err(InvariantsError::TreasuryNegative),
+        }
+    }
+
+    // Run the checks and pause the token if one fails (permissionless; keepers run it).
+    // Returns whether the token was paused. A partial holder list only undercounts, so
+    // here the supply check fails only when the listed balances exceed the circulating
+    // supply: an incomplete list from a careless or hostile keeper cannot pause the token.
+    pub fn check_and_pause(env: Env, holders: Vec<Address>, borrowers: Vec<Address>) -> bool {
+        let config = Self::load(&env).config;
+        let violation = Self::violations(env.clone(), holders, borrowers)
+            .iter()
+            .find(|violation| violation.invariant != Invariant::SupplyConserved || violation.actual > violation.required);
+        let Some(violation) = violation else {
+            return false;
+        };
+        let reason = match violation.invariant {
+            Invariant::SupplyConserved => Symbol::new(&env, "supply"),
+            Invariant::Collateralized => Symbol::new(&env, "collateral"),
+            Invariant::TreasurySolvent => Symbol::new(&env, "treasury"),
+        };
+        PiTokenClient::new(&env, &config.pi_token).pause(&env.current_contract_address(), &reason);
+        env.events().publish((Symbol::new(&env, "invariant_violated"), reason), violation);
+        true
+    }
+
+    // Governance-only: Point the checker at other contracts
+    pub fn set_config(env: Env, config: InvariantConfig) {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        data.config = config;
+        env.storage().instance().set(&Symbol::new(&env, "invariants_data"), &data);
+    }
+
+    // View: Contracts the checks read
+    pub fn config(env: Env) -> InvariantConfig {
+        Self::load(&env).config
+    }
+
+    // Helper: Sum of the distinct `holders`' balances
+    fn sum_balances(env: &Env, token: &PiTokenClient, holders: &Vec<Address>) -> i128 {
+        let mut balances: Map<Address, i128> = Map::new(env);
+        for holder in holders.iter() {
+            if !balances.contains_key(holder.clone()) {
+                balances.set(holder.clone(), token.balance(&holder));
+            }
+        }
+        balances.values().iter().fold(0i128, |sum, balance| sum.saturating_add(balance))
+    }
+
+    // Helper: `borrower`'s collateral at oracle prices, in PI; saturates rather than
+    // overflow, since a value past i128 covers any debt
+    fn collateral_value(env: &Env, config: &InvariantConfig, borrower: &Address) -> i128 {
+        let lending = LendingClient::new(env, &config.lending);
+        let oracle = PriceOracleClient::new(env, &config.oracle);
+        let pi_price = oracle.query_price(&config.pi_symbol);
+        let mut value = 0i128;
+        for asset in lending.collaterals().iter() {
+            let amount = lending.collateral_of(borrower, &asset);
+            let Some(collateral) = lending.collateral_config(&asset).filter(|_| amount > 0) else {
+                continue;
+            };
+            let usd = amount.saturating_mul(oracle.query_price(&collateral.symbol)) / pi_price;
+            let pi = usd.saturating_mul(10i128.pow(PI_DECIMALS)) / 10i128.pow(collateral.decimals);
+            value = value.saturating_add(pi);
+        }
+        value
+    }
+
+    fn load(env: &Env) -> InvariantsData {
+        env.storage().instance().get(&Symbol::new(env, "invariants_data")).unwrap()
+    }
+}
+
+mod test;
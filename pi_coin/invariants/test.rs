@@ -0,0 +1,120 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, vec, Address, Env, Map, Symbol, Vec};
+use crate::{AssetReport, CollateralConfig, Invariant, InvariantConfig, InvariantsError, PiCoinInvariants, PiCoinInvariantsClient};
+
+// Just enough of PiCoinContract, PiCoinLending, PiCoinOracle and PiCoinTreasury: settable
+// balances, supply, debts and collateral (one asset, 7 decimals), PI at 10 and the
+// collateral at 1, and a treasury report
+#[contract]
+struct MockEcosystem;
+
+#[contractimpl]
+impl MockEcosystem {
+    pub fn set(env: Env, key: Symbol, holder: Address, amount: i128) {
+        let mut values: Map<(Symbol, Address), i128> = env.storage().instance().get(&0u32).unwrap_or(Map::new(&env));
+        values.set((key, holder), amount);
+        env.storage().instance().set(&0u32, &values);
+    }
+
+    pub fn set_supply(env: Env, supply: i128) {
+        env.storage().instance().set(&1u32, &supply);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        Self::get(&env, "balance", id)
+    }
+
+    pub fn circulating_supply(env: Env) -> i128 {
+        env.storage().instance().get(&1u32).unwrap_or(0)
+    }
+
+    pub fn pause(env: Env, caller: Address, reason: Symbol) {
+        env.storage().instance().set(&2u32, &(caller, reason));
+    }
+
+    pub fn paused(env: Env) -> Option<(Address, Symbol)> {
+        env.storage().instance().get(&2u32)
+    }
+
+    pub fn debt_of(env: Env, borrower: Address) -> i128 {
+        Self::get(&env, "debt", borrower)
+    }
+
+    pub fn collaterals(env: Env) -> Vec<Address> {
+        vec![&env, env.current_contract_address()]
+    }
+
+    pub fn collateral_of(env: Env, owner: Address, _asset: Address) -> i128 {
+        Self::get(&env, "collateral", owner)
+    }
+
+    pub fn collateral_config(env: Env, _asset: Address) -> Option<CollateralConfig> {
+        Some(CollateralConfig { symbol: Symbol::new(&env, "XLM"), decimals: 7, ltv_bps: 7_000, liquidation_threshold_bps: 8_000, liquidation_bonus_bps: 500 })
+    }
+
+    pub fn query_price(env: Env, asset: Symbol) -> i128 {
+        if asset == Symbol::new(&env, "PI") { 10 } else { 1 }
+    }
+
+    pub fn treasury_report(env: Env) -> Vec<AssetReport> {
+        let asset = env.current_contract_address();
+        vec![&env, AssetReport { balance: Self::get(&env, "treasury", asset.clone()), asset, epoch_limit: None, spent_this_epoch: 0 }]
+    }
+
+    fn get(env: &Env, key: &str, holder: Address) -> i128 {
+        let values: Map<(Symbol, Address), i128> = env.storage().instance().get(&0u32).unwrap_or(Map::new(env));
+        values.get((Symbol::new(env, key), holder)).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_invariants_catch_broken_books_and_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let mock = MockEcosystemClient::new(&env, &env.register(MockEcosystem, ()));
+    let config = InvariantConfig {
+        pi_token: mock.address.clone(),
+        lending: mock.address.clone(),
+        oracle: mock.address.clone(),
+        pi_symbol: Symbol::new(&env, "PI"),
+        treasury: mock.address.clone(),
+    };
+    let contract_id = env.register(PiCoinInvariants, (Address::generate(&env), config));
+    let client = PiCoinInvariantsClient::new(&env, &contract_id);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let balance = Symbol::new(&env, "balance");
+
+    // Balanced books: 600 + 400 circulating; bob's 1_000 collateral is worth 100 PI
+    mock.set(&balance, &alice, &600);
+    mock.set(&balance, &bob, &400);
+    mock.set_supply(&1_000);
+    mock.set(&Symbol::new(&env, "debt"), &bob, &100);
+    mock.set(&Symbol::new(&env, "collateral"), &bob, &1_000);
+    let holders = vec![&env, alice.clone(), bob.clone(), alice.clone()];
+    let borrowers = vec![&env, bob.clone()];
+    client.check_all(&holders, &borrowers);
+    assert!(!client.check_and_pause(&holders, &borrowers));
+
+    // A partial holder list fails the exact check, but cannot pause the token
+    let partial = vec![&env, alice.clone()];
+    assert!(matches!(client.try_check_all(&partial, &borrowers), Err(Ok(InvariantsError::SupplyMismatch))));
+    assert!(!client.check_and_pause(&partial, &borrowers));
+    assert!(mock.paused().is_none());
+
+    // Debt past the collateral's value, and an overdrawn treasury
+    mock.set(&Symbol::new(&env, "debt"), &bob, &101);
+    mock.set(&Symbol::new(&env, "treasury"), &mock.address, &-1);
+    assert!(matches!(client.try_check_all(&holders, &borrowers), Err(Ok(InvariantsError::Undercollateralized))));
+    let violations = client.violations(&holders, &borrowers);
+    assert_eq!(violations.len(), 2);
+    assert_eq!((violations.get(0).unwrap().invariant, violations.get(0).unwrap().actual), (Invariant::Collateralized, 100));
+    assert_eq!(violations.get(1).unwrap().invariant, Invariant::TreasurySolvent);
+
+    // Balances past the supply mean PI was created off the books: the keeper pauses
+    mock.set(&Symbol::new(&env, "debt"), &bob, &100);
+    mock.set(&Symbol::new(&env, "treasury"), &mock.address, &0);
+    mock.set(&balance, &bob, &401);
+    assert!(client.check_and_pause(&holders, &borrowers));
+    assert_eq!(mock.paused(), Some((contract_id, Symbol::new(&env, "supply"))));
+}
@@ -0,0 +1,138 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, BytesN, Env, Symbol, log};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub merchant: Address,
+    pub amount: i128, // PI due in total
+    pub paid: i128, // Paid so far, across all payers
+    pub refunded: i128, // Returned by the merchant so far
+    pub expiration_ledger: u32, // Last ledger payments are accepted
+    pub memo_hash: BytesN<32>, // Hash of the merchant's off-chain order details
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InvoiceKey {
+    NextId,
+    Invoice(u64),
+    Payment(u64, Address), // (invoice, payer) -> (paid, refunded)
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum InvoiceError {
+    InvoiceNotFound = 1,
+    InvalidAmount = 2,
+    InvoiceExpired = 3,
+    AlreadySettled = 4,
+    RefundExceedsPayment = 5,
+}
+
+#[contract]
+pub struct PiCoinInvoices;
+
+#[contractimpl]
+impl PiCoinInvoices {
+    // Constructor: PI payment requests. Payments go straight to the merchant - the
+    // contract never holds funds - and are tracked per payer, so an invoice can be paid in
+    // parts (or by several payers) and refunded to whoever paid it. Point-of-sale software
+    // confirms settlement from the "invoice_settled" event.
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "Invoices initialized: PI payment requests with partial payments");
+    }
+
+    // Merchant: Request `amount` PI, payable until `expiration_ledger`. `memo_hash` binds
+    // the invoice to the order it is for. Returns the invoice id.
+    pub fn create_invoice(env: Env, merchant: Address, amount: i128, expiration_ledger: u32, memo_hash: BytesN<32>) -> Result<u64, InvoiceError> {
+        merchant.require_auth();
+        if amount <= 0 {
+            return Err(InvoiceError::InvalidAmount);
+        }
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(InvoiceError::InvoiceExpired);
+        }
+        let id: u64 = env.storage().instance().get(&InvoiceKey::NextId).unwrap_or(1);
+        env.storage().instance().set(&InvoiceKey::NextId, &(id + 1));
+        let invoice = Invoice { merchant: merchant.clone(), amount, paid: 0, refunded: 0, expiration_ledger, memo_hash: memo_hash.clone() };
+        env.storage().persistent().set(&InvoiceKey::Invoice(id), &invoice);
+        env.events().publish((Symbol::new(&env, "invoice_created"), merchant, id), (amount, expiration_ledger, memo_hash));
+        Ok(id)
+    }
+
+    // Pay up to `amount` PI towards invoice `id`; anything beyond what is still due is not
+    // taken. The invoice is credited with what reaches the merchant, so a PI transfer fee
+    // leaves a remainder due. Returns the amount credited.
+    pub fn pay(env: Env, payer: Address, id: u64, amount: i128) -> Result<i128, InvoiceError> {
+        payer.require_auth();
+        if amount <= 0 {
+            return Err(InvoiceError::InvalidAmount);
+        }
+        let mut invoice = Self::invoice(env.clone(), id)?;
+        if invoice.paid >= invoice.amount {
+            return Err(InvoiceError::AlreadySettled);
+        }
+        if env.ledger().sequence() > invoice.expiration_ledger {
+            return Err(InvoiceError::InvoiceExpired);
+        }
+        let pi = Self::pi(&env);
+        let before = pi.balance(&invoice.merchant);
+        pi.transfer(&payer, &invoice.merchant, &amount.min(invoice.amount - invoice.paid));
+        let paid = pi.balance(&invoice.merchant) - before;
+        if paid <= 0 {
+            return Err(InvoiceError::InvalidAmount);
+        }
+        invoice.paid += paid;
+        let (payer_paid, payer_refunded) = Self::payment(env.clone(), id, payer.clone());
+        env.storage().persistent().set(&InvoiceKey::Payment(id, payer.clone()), &(payer_paid + paid, payer_refunded));
+        env.storage().persistent().set(&InvoiceKey::Invoice(id), &invoice);
+        env.events().publish((Symbol::new(&env, "invoice_payment"), payer, id), (paid, invoice.amount - invoice.paid));
+        if invoice.paid == invoice.amount {
+            env.events().publish((Symbol::new(&env, "invoice_settled"), invoice.merchant, id), (invoice.amount, invoice.memo_hash));
+        }
+        Ok(paid)
+    }
+
+    // Merchant: Return `amount` PI to `payer`, at most what it paid towards invoice `id`
+    // net of earlier refunds. A refund does not reopen the invoice.
+    pub fn refund(env: Env, id: u64, payer: Address, amount: i128) -> Result<(), InvoiceError> {
+        let mut invoice = Self::invoice(env.clone(), id)?;
+        invoice.merchant.require_auth();
+        if amount <= 0 {
+            return Err(InvoiceError::InvalidAmount);
+        }
+        let (payer_paid, payer_refunded) = Self::payment(env.clone(), id, payer.clone());
+        if payer_refunded + amount > payer_paid {
+            return Err(InvoiceError::RefundExceedsPayment);
+        }
+        invoice.refunded += amount;
+        env.storage().persistent().set(&InvoiceKey::Payment(id, payer.clone()), &(payer_paid, payer_refunded + amount));
+        env.storage().persistent().set(&InvoiceKey::Invoice(id), &invoice);
+
+        Self::pi(&env).transfer(&invoice.merchant, &payer, &amount);
+        env.events().publish((Symbol::new(&env, "invoice_refund"), payer, id), amount);
+        Ok(())
+    }
+
+    // View: Invoice `id`
+    pub fn invoice(env: Env, id: u64) -> Result<Invoice, InvoiceError> {
+        env.storage().persistent().get(&InvoiceKey::Invoice(id)).ok_or(InvoiceError::InvoiceNotFound)
+    }
+
+    // View: (paid, refunded) by `payer` towards invoice `id`
+    pub fn payment(env: Env, id: u64, payer: Address) -> (i128, i128) {
+        env.storage().persistent().get(&InvoiceKey::Payment(id, payer)).unwrap_or((0, 0))
+    }
+}
+
+impl PiCoinInvoices {
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap();
+        token::TokenClient::new(env, &pi_token)
+    }
+}
+
+mod test;
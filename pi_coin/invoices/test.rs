@@ -0,0 +1,120 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, vec, Address, BytesN, Env, IntoVal, Symbol};
+use crate::{InvoiceError, PiCoinInvoices, PiCoinInvoicesClient};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+#[test]
+fn test_partial_payments_settle_and_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let merchant = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    pi.mint(&bob, &1_000, &PiCoinSource::Mining);
+
+    let contract_id = env.register(PiCoinInvoices, (&pi.address,));
+    let client = PiCoinInvoicesClient::new(&env, &contract_id);
+    let memo = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(client.try_create_invoice(&merchant, &0, &100, &memo), Err(Ok(InvoiceError::InvalidAmount)));
+    let id = client.create_invoice(&merchant, &500, &100, &memo);
+
+    // Alice pays in two parts, Bob covers the rest; his overpayment is not taken
+    assert_eq!(client.pay(&alice, &id, &200), 200);
+    assert_eq!(client.pay(&alice, &id, &100), 100);
+    assert_eq!(client.pay(&bob, &id, &1_000), 200);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "invoice_payment"), bob.clone(), id).into_val(&env),
+                (200i128, 0i128).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "invoice_settled"), merchant.clone(), id).into_val(&env),
+                (500i128, memo.clone()).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.try_pay(&alice, &id, &1), Err(Ok(InvoiceError::AlreadySettled)));
+    assert_eq!((pi.balance(&merchant), pi.balance(&contract_id)), (500, 0));
+
+    // Refunds go back to whoever paid, never beyond what they paid
+    client.refund(&id, &alice, &250);
+    assert_eq!(client.try_refund(&id, &alice, &51), Err(Ok(InvoiceError::RefundExceedsPayment)));
+    assert_eq!(client.try_refund(&id, &merchant, &1), Err(Ok(InvoiceError::RefundExceedsPayment)));
+    assert_eq!(client.payment(&id, &alice), (300, 250));
+    assert_eq!((client.invoice(&id).paid, client.invoice(&id).refunded), (500, 250));
+    assert_eq!((pi.balance(&alice), pi.balance(&merchant)), (950, 250));
+
+    // Unpaid invoices stop accepting payments after their expiration ledger
+    let late = client.create_invoice(&merchant, &100, &100, &memo);
+    env.ledger().set_sequence_number(101);
+    assert_eq!(client.try_pay(&alice, &late, &100), Err(Ok(InvoiceError::InvoiceExpired)));
+    assert_eq!(client.try_pay(&alice, &99, &100), Err(Ok(InvoiceError::InvoiceNotFound)));
+}
+
+#[test]
+fn test_invoices_are_credited_with_what_the_merchant_receives() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let merchant = Address::generate(&env);
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinInvoices, (&pi.address,));
+    let client = PiCoinInvoicesClient::new(&env, &contract_id);
+    let id = client.create_invoice(&merchant, &500, &100, &BytesN::from_array(&env, &[7; 32]));
+
+    // At a 1% fee the 500 sent credits 495; the 5 still due is too small to be charged
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    assert_eq!(client.pay(&alice, &id, &500), 495);
+    assert_eq!(client.invoice(&id).paid, 495);
+    assert_eq!(client.pay(&alice, &id, &500), 5);
+    assert_eq!(client.try_pay(&alice, &id, &1), Err(Ok(InvoiceError::AlreadySettled)));
+    assert_eq!((pi.balance(&merchant), pi.balance(&alice)), (500, 495));
+    assert_eq!(client.payment(&id, &alice), (500, 0));
+}
+
+#[test]
+fn test_rejects_unauthorized_unfunded_and_expired_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let merchant = Address::generate(&env);
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &100, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinInvoices, (&pi.address,));
+    let client = PiCoinInvoicesClient::new(&env, &contract_id);
+    let memo = BytesN::from_array(&env, &[7; 32]);
+    let id = client.create_invoice(&merchant, &500, &100, &memo);
+
+    // Paying and refunding need the payer's and the merchant's signatures
+    env.set_auths(&[]);
+    assert!(client.try_create_invoice(&merchant, &500, &100, &memo).is_err());
+    assert!(client.try_pay(&alice, &id, &50).is_err());
+    env.mock_all_auths();
+    client.pay(&alice, &id, &50);
+    env.set_auths(&[]);
+    assert!(client.try_refund(&id, &alice, &50).is_err());
+    env.mock_all_auths();
+
+    // A payment the payer cannot cover is not recorded
+    assert!(client.try_pay(&alice, &id, &60).is_err());
+    assert_eq!(client.invoice(&id).paid, 50);
+
+    // Expired invoices take no payments, and cannot be created already expired
+    env.ledger().set_sequence_number(101);
+    assert_eq!(client.try_pay(&alice, &id, &10), Err(Ok(InvoiceError::InvoiceExpired)));
+    assert_eq!(client.try_create_invoice(&merchant, &500, &100, &memo), Err(Ok(InvoiceError::InvoiceExpired)));
+    assert_eq!(client.try_refund(&id, &alice, &0), Err(Ok(InvoiceError::InvalidAmount)));
+    assert_eq!((pi.balance(&alice), pi.balance(&merchant)), (50, 50));
+}
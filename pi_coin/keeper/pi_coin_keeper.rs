@@ -0,0 +1,112 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, Val, Vec, log};
+
+// A maintenance call keepers are tipped for
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperTask {
+    pub target: Address, // Contract exposing the maintenance function
+    pub function: Symbol, // e.g. "finalize_proposal", "rebase", "charge_due", "liquidate"
+    pub tip: i128, // PI paid per tipped run
+    pub cooldown: u64, // Seconds between tipped runs of this task, whoever runs it
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct KeeperData {
+    pub governance: Address, // Configures tasks
+    pub pi_token: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum KeeperKey {
+    Task(Symbol), // Persistent: task name -> KeeperTask
+    LastRun(Symbol), // Persistent: task name -> timestamp of its last run
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum KeeperError {
+    UnknownTask = 1,
+    InvalidConfig = 2,
+    CoolingDown = 3,
+}
+
+#[contract]
+pub struct PiCoinKeeper;
+
+#[contractimpl]
+impl PiCoinKeeper {
+    // Constructor: keeper tips for the ecosystem's maintenance entry points. A keeper runs
+    // a task through `run`, which forwards the call and, if it succeeds, tips the keeper
+    // in PI the treasury has sent this contract (a treasury spend). Each task has its own
+    // tip and cooldown; the cooldown is shared by all keepers, so calling a task in a loop
+    // or from many accounts earns one tip per cooldown at most.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "keeper_data"), &KeeperData { governance, pi_token });
+        log!(&env, "Keeper tips initialized: treasury-funded maintenance incentives");
+    }
+
+    // Governance-only: Configure task `name`, or retire it with None
+    pub fn set_task(env: Env, name: Symbol, task: Option<KeeperTask>) -> Result<(), KeeperError> {
+        Self::load(&env).governance.require_auth();
+        let key = KeeperKey::Task(name.clone());
+        match &task {
+            Some(task) if task.tip < 0 || task.cooldown == 0 => return Err(KeeperError::InvalidConfig),
+            Some(task) => env.storage().persistent().set(&key, task),
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events().publish((Symbol::new(&env, "keeper_task"), name), task);
+        Ok(())
+    }
+
+    // Run task `name` with `args` and collect its tip; returns the tip paid. The call must
+    // succeed (a maintenance function with nothing to do fails, and so does the run), and
+    // the task must be out of its cooldown. Once the pool runs dry, runs go through untipped.
+    pub fn run(env: Env, keeper: Address, name: Symbol, args: Vec<Val>) -> Result<i128, KeeperError> {
+        keeper.require_auth();
+        let task = Self::task(env.clone(), name.clone()).ok_or(KeeperError::UnknownTask)?;
+        let now = env.ledger().timestamp();
+        if let Some(next) = Self::next_run(env.clone(), name.clone()) {
+            if now < next {
+                return Err(KeeperError::CoolingDown);
+            }
+        }
+        // Recorded before the call, so the target cannot re-enter for a second tip
+        env.storage().persistent().set(&KeeperKey::LastRun(name.clone()), &now);
+        env.invoke_contract::<Val>(&task.target, &task.function, args);
+
+        let pi = token::TokenClient::new(&env, &Self::load(&env).pi_token);
+        let tip = task.tip.min(pi.balance(&env.current_contract_address()));
+        if tip > 0 {
+            pi.transfer(&env.current_contract_address(), &keeper, &tip);
+        }
+        env.events().publish((Symbol::new(&env, "keeper_tip"), name, keeper), tip);
+        Ok(tip)
+    }
+
+    // View: Task `name`, if configured
+    pub fn task(env: Env, name: Symbol) -> Option<KeeperTask> {
+        env.storage().persistent().get(&KeeperKey::Task(name))
+    }
+
+    // View: Earliest timestamp task `name` can run again (None: never run, or not configured)
+    pub fn next_run(env: Env, name: Symbol) -> Option<u64> {
+        let task = Self::task(env.clone(), name.clone())?;
+        let last: u64 = env.storage().persistent().get(&KeeperKey::LastRun(name))?;
+        Some(last.saturating_add(task.cooldown))
+    }
+
+    // View: PI left to pay tips from
+    pub fn tip_pool(env: Env) -> i128 {
+        token::TokenClient::new(&env, &Self::load(&env).pi_token).balance(&env.current_contract_address())
+    }
+
+    fn load(env: &Env) -> KeeperData {
+        env.storage().instance().get(&Symbol::new(env, "keeper_data")).unwrap()
+    }
+}
+
+mod test;
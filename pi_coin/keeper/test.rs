@@ -0,0 +1,93 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, symbol_short, testutils::{Address as _, Ledger}, vec, Address, Env, IntoVal};
+use crate::{KeeperError, KeeperTask, PiCoinKeeper, PiCoinKeeperClient};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+// Stands in for a maintenance entry point: fails when there is nothing to do
+#[contract]
+pub struct MockMaintenance;
+
+#[contractimpl]
+impl MockMaintenance {
+    pub fn settle(env: Env, due: u32) -> u32 {
+        if due == 0 {
+            panic!("nothing due");
+        }
+        let runs: u32 = env.storage().instance().get(&symbol_short!("runs")).unwrap_or(0) + 1;
+        env.storage().instance().set(&symbol_short!("runs"), &runs);
+        runs
+    }
+}
+
+#[test]
+fn test_runs_tip_once_per_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let contract_id = env.register(PiCoinKeeper, (Address::generate(&env), &pi.address));
+    let client = PiCoinKeeperClient::new(&env, &contract_id);
+    pi.mint(&contract_id, &25, &PiCoinSource::Rewards);
+    let target = env.register(MockMaintenance, ());
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    let task_name = symbol_short!("settle");
+
+    let task = KeeperTask { target, function: symbol_short!("settle"), tip: 10, cooldown: 3_600 };
+    assert!(matches!(client.try_set_task(&task_name, &Some(KeeperTask { cooldown: 0, ..task.clone() })), Err(Ok(KeeperError::InvalidConfig))));
+    assert!(matches!(client.try_run(&alice, &task_name, &vec![&env]), Err(Ok(KeeperError::UnknownTask))));
+    client.set_task(&task_name, &Some(task));
+
+    // A call with nothing to do fails, and pays nothing
+    assert!(client.try_run(&alice, &task_name, &vec![&env, 0u32.into_val(&env)]).is_err());
+    assert_eq!(client.next_run(&task_name), None);
+
+    assert_eq!(client.run(&alice, &task_name, &vec![&env, 1u32.into_val(&env)]), 10);
+    assert_eq!(pi.balance(&alice), 10);
+
+    // The cooldown holds for every keeper
+    assert!(matches!(client.try_run(&bob, &task_name, &vec![&env, 1u32.into_val(&env)]), Err(Ok(KeeperError::CoolingDown))));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_600);
+    assert_eq!(client.run(&bob, &task_name, &vec![&env, 1u32.into_val(&env)]), 10);
+
+    // The pool runs dry: the last 5 PI, then untipped runs
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_600);
+    assert_eq!(client.run(&bob, &task_name, &vec![&env, 1u32.into_val(&env)]), 5);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_600);
+    assert_eq!(client.run(&bob, &task_name, &vec![&env, 1u32.into_val(&env)]), 0);
+    assert_eq!((pi.balance(&bob), client.tip_pool()), (15, 0));
+
+    client.set_task(&task_name, &None);
+    assert!(matches!(client.try_run(&alice, &task_name, &vec![&env]), Err(Ok(KeeperError::UnknownTask))));
+    assert_eq!(client.task(&task_name), None);
+}
+
+#[test]
+fn test_rejects_unauthorized_configs_and_runs() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let contract_id = env.register(PiCoinKeeper, (Address::generate(&env), &pi.address));
+    let client = PiCoinKeeperClient::new(&env, &contract_id);
+    let target = env.register(MockMaintenance, ());
+    let alice = Address::generate(&env);
+    let task_name = symbol_short!("settle");
+    let task = KeeperTask { target, function: symbol_short!("settle"), tip: 10, cooldown: 3_600 };
+    assert!(matches!(client.try_set_task(&task_name, &Some(KeeperTask { tip: -1, ..task.clone() })), Err(Ok(KeeperError::InvalidConfig))));
+
+    // Only governance configures tasks, and a run needs the keeper's own signature
+    env.set_auths(&[]);
+    assert!(client.try_set_task(&task_name, &Some(task.clone())).is_err());
+    env.mock_all_auths();
+    client.set_task(&task_name, &Some(task));
+    env.set_auths(&[]);
+    assert!(client.try_run(&alice, &task_name, &vec![&env, 1u32.into_val(&env)]).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.next_run(&task_name), None);
+
+    // An unfunded pool still runs the task, untipped, and the cooldown starts
+    assert_eq!(client.tip_pool(), 0);
+    assert_eq!(client.run(&alice, &task_name, &vec![&env, 1u32.into_val(&env)]), 0);
+    assert_eq!(client.next_run(&task_name), Some(3_600));
+    assert_eq!(pi.balance(&alice), 0);
+}
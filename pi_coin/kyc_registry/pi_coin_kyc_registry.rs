@@ -0,0 +1,140 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, BytesN, Env, Symbol, log};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct KycData {
+    pub governance: Address, // Approves, rotates and removes attestors
+}
+
+// A KYC credential: only the hash of the off-chain evidence is stored on-chain
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Credential {
+    pub attestor: Address,
+    pub credential_hash: BytesN<32>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub revoked: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AttestorStatus {
+    Active,
+    Retired, // No longer attests; credentials it issued stay valid until expiry
+    Distrusted, // Removed as compromised; credentials it issued are void
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum KycKey {
+    Attestor(Address), // Attestor -> AttestorStatus
+    Credential(Address), // Subject -> latest Credential
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum KycError {
+    NotAttestor = 1,
+    InvalidExpiry = 2,
+    NoCredential = 3,
+    Unauthorized = 4,
+}
+
+#[contract]
+pub struct PiCoinKycRegistry;
+
+#[contractimpl]
+impl PiCoinKycRegistry {
+    // Constructor: KYC registry whose attestors are managed by governance
+    pub fn __constructor(env: Env, governance: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "kyc_data"), &KycData { governance });
+        log!(&env, "KYC registry initialized: hashed credentials, governance-approved attestors");
+    }
+
+    // Governance-only: Approve an attestor
+    pub fn add_attestor(env: Env, attestor: Address) {
+        Self::governance(&env).require_auth();
+        Self::set_status(&env, &attestor, AttestorStatus::Active);
+    }
+
+    // Governance-only: Remove an attestor. With `void_issued` (a compromised attestor)
+    // every credential it issued stops verifying; otherwise they run to expiry.
+    pub fn remove_attestor(env: Env, attestor: Address, void_issued: bool) {
+        Self::governance(&env).require_auth();
+        let status = if void_issued { AttestorStatus::Distrusted } else { AttestorStatus::Retired };
+        Self::set_status(&env, &attestor, status);
+    }
+
+    // Governance-only: Replace an attestor key; credentials under the old key stay valid
+    pub fn rotate_attestor(env: Env, old: Address, new: Address) {
+        Self::governance(&env).require_auth();
+        Self::set_status(&env, &old, AttestorStatus::Retired);
+        Self::set_status(&env, &new, AttestorStatus::Active);
+    }
+
+    // Attestor-only: Record (or renew) `subject`'s credential until `expires_at`
+    pub fn attest(env: Env, attestor: Address, subject: Address, credential_hash: BytesN<32>, expires_at: u64) -> Result<(), KycError> {
+        attestor.require_auth();
+        if Self::attestor_status(env.clone(), attestor.clone()) != Some(AttestorStatus::Active) {
+            return Err(KycError::NotAttestor);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(KycError::InvalidExpiry);
+        }
+        let credential = Credential { attestor, credential_hash, issued_at: now, expires_at, revoked: false };
+        env.storage().persistent().set(&KycKey::Credential(subject.clone()), &credential);
+        env.events().publish((Symbol::new(&env, "kyc_attested"), subject), credential);
+        Ok(())
+    }
+
+    // Revoke `subject`'s credential; allowed for the issuing attestor and governance
+    pub fn revoke(env: Env, caller: Address, subject: Address) -> Result<(), KycError> {
+        caller.require_auth();
+        let key = KycKey::Credential(subject.clone());
+        let mut credential: Credential = env.storage().persistent().get(&key).ok_or(KycError::NoCredential)?;
+        if caller != credential.attestor && caller != Self::governance(&env) {
+            return Err(KycError::Unauthorized);
+        }
+        credential.revoked = true;
+        env.storage().persistent().set(&key, &credential);
+        env.events().publish((Symbol::new(&env, "kyc_revoked"), subject), caller);
+        Ok(())
+    }
+
+    // View: Whether `subject` holds an unexpired, unrevoked credential from a trusted
+    // attestor. Queried by PiCoinContract in allowlist mode.
+    pub fn is_verified(env: Env, subject: Address) -> bool {
+        let Some(credential) = Self::credential(env.clone(), subject) else { return false };
+        !credential.revoked
+            && credential.expires_at > env.ledger().timestamp()
+            && Self::attestor_status(env, credential.attestor) != Some(AttestorStatus::Distrusted)
+    }
+
+    // View: Latest credential recorded for `subject`
+    pub fn credential(env: Env, subject: Address) -> Option<Credential> {
+        env.storage().persistent().get(&KycKey::Credential(subject))
+    }
+
+    // View: Status of an attestor (None if never approved)
+    pub fn attestor_status(env: Env, attestor: Address) -> Option<AttestorStatus> {
+        env.storage().persistent().get(&KycKey::Attestor(attestor))
+    }
+
+    // Helper: Governance address
+    fn governance(env: &Env) -> Address {
+        let data: KycData = env.storage().instance().get(&Symbol::new(env, "kyc_data")).unwrap();
+        data.governance
+    }
+
+    // Helper: Store an attestor status and emit ("kyc_attestor", attestor) -> status
+    fn set_status(env: &Env, attestor: &Address, status: AttestorStatus) {
+        env.storage().persistent().set(&KycKey::Attestor(attestor.clone()), &status);
+        env.events().publish((Symbol::new(env, "kyc_attestor"), attestor.clone()), status);
+    }
+}
+
+mod test;
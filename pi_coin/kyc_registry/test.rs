@@ -0,0 +1,43 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, BytesN, Env};
+use crate::{AttestorStatus, PiCoinKycRegistry, PiCoinKycRegistryClient};
+
+#[test]
+fn test_credentials_expire_revoke_and_follow_attestors() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let governance = Address::generate(&env);
+    let client = PiCoinKycRegistryClient::new(&env, &env.register(PiCoinKycRegistry, (&governance,)));
+    let attestor = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let evidence = BytesN::from_array(&env, &[7; 32]);
+
+    assert!(client.try_attest(&attestor, &alice, &evidence, &1_000).is_err());
+    client.add_attestor(&attestor);
+    client.attest(&attestor, &alice, &evidence, &1_000);
+    client.attest(&attestor, &bob, &evidence, &1_000);
+    assert!(client.is_verified(&alice));
+
+    // Revocation by the issuer; strangers may not revoke
+    assert!(client.try_revoke(&Address::generate(&env), &bob).is_err());
+    client.revoke(&attestor, &bob);
+    assert!(!client.is_verified(&bob));
+
+    // Rotation keeps issued credentials; the old key can no longer attest
+    let new_attestor = Address::generate(&env);
+    client.rotate_attestor(&attestor, &new_attestor);
+    assert_eq!(client.attestor_status(&attestor), Some(AttestorStatus::Retired));
+    assert!(client.is_verified(&alice));
+    assert!(client.try_attest(&attestor, &bob, &evidence, &1_000).is_err());
+
+    // Distrusting an attestor voids what it issued
+    client.remove_attestor(&attestor, &true);
+    assert!(!client.is_verified(&alice));
+
+    // Credentials lapse at expiry
+    client.attest(&new_attestor, &alice, &evidence, &1_000);
+    assert!(client.is_verified(&alice));
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert!(!client.is_verified(&alice));
+}
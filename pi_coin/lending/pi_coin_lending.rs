@@ -0,0 +1,684 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, Vec, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+// Fixed-point scale of the supply exchange rate and the borrow index
+pub const INDEX_ONE: i128 = 1_000_000_000_000;
+// Seconds per year the annual borrow rate is spread over
+pub const YEAR: u64 = 365 * 24 * 60 * 60;
+// PI amounts use the Stellar 7-decimal convention, like every SAC
+pub const PI_DECIMALS: u32 = 7;
+// Highest asset precision accepted, so 10^decimals and the valuations stay in i128
+pub const MAX_DECIMALS: u32 = 18;
+// Most collateral assets governance may list; a health check reads each one
+pub const MAX_COLLATERALS: u32 = 8;
+// Largest share of a borrower's debt one liquidation may repay
+pub const CLOSE_FACTOR_BPS: i128 = 5_000;
+// Oldest oracle price, in seconds, that may value collateral
+pub const MAX_PRICE_AGE: u64 = 60 * 60;
+const BPS: i128 = 10_000;
+
+// Entry point of PiCoinOracle used to value collateral against PI
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn query_price(env: Env, asset: Symbol) -> i128;
+    fn last_update(env: Env, asset: Symbol) -> Option<u64>;
+}
+
+// Entry point of PiCoinRateModel that sets the borrow rate from utilization
+#[contractclient(name = "RateModelClient")]
+pub trait RateModel {
+    fn borrow_rate(env: Env, cash: i128, borrows: i128) -> u32;
+}
+
+// Notified after every liquidation, e.g. a liquidation engine keeping its own books or
+// an insurance fund that covers bad debt. PI the hook sends the pool during the call
+// covers the liquidation's bad debt.
+#[contractclient(name = "LiquidationHookClient")]
+pub trait LiquidationHook {
+    fn on_liquidation(env: Env, borrower: Address, asset: Address, repaid: i128, seized: i128, bad_debt: i128);
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LendingData {
+    pub governance: Address, // Lists collateral, sets the rate model and reserve factor
+    pub pi_token: Address,
+    pub oracle: Address,
+    pub pi_symbol: Symbol, // Oracle key PI is priced under
+    pub rate_model: Address,
+    pub reserve_factor_bps: u32, // Share of interest kept as protocol reserves
+    pub borrow_index: i128, // Debt per unit of principal, scaled by INDEX_ONE; only ever grows
+    pub total_borrows: i128, // PI owed by borrowers, interest included
+    pub total_reserves: i128, // Interest kept for the protocol, held as cash
+    pub total_shares: i128, // Supply shares
+    pub bad_debt: i128, // Debt written off when a liquidation left no collateral behind, not yet covered
+    pub last_accrual: u64,
+}
+
+// A listed collateral asset, addressed by its SAC (or any SEP-41 token) address
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralConfig {
+    pub symbol: Symbol, // Oracle key the asset is priced under
+    pub decimals: u32, // Read from the token when listed
+    pub ltv_bps: u32, // Borrowing power per unit of value
+    pub liquidation_threshold_bps: u32, // Value below which the debt may be liquidated
+    pub liquidation_bonus_bps: u32, // Extra collateral a liquidator receives
+}
+
+// A borrower's debt as of `index`; it grows with the borrow index from there
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowSnapshot {
+    pub principal: i128,
+    pub index: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum LendingKey {
+    Collateral(Address), // Asset -> CollateralConfig
+    Collaterals, // Instance: listed assets
+    Deposit(Address, Address), // (Owner, asset) -> Collateral deposited
+    Borrow(Address), // Borrower -> BorrowSnapshot
+    Shares(Address), // Supplier -> Supply shares
+    LiquidationHook, // Instance: contract notified of liquidations, if any
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LendingError {
+    InvalidAmount = 1,
+    UnsupportedAsset = 2,
+    InsufficientLiquidity = 3,
+    InsufficientShares = 4,
+    InsufficientCollateral = 5,
+    BorrowLimitExceeded = 6, // The borrow, or collateral withdrawal, would pass the LTV limit
+    NotLiquidatable = 7, // The position is healthy
+    InvalidConfig = 8,
+    Overflow = 9,
+    InvalidPrice = 10, // The oracle has no positive price for PI or the collateral
+    StalePrice = 11, // That price is older than MAX_PRICE_AGE
+}
+
+#[contract]
+pub struct PiCoinLending;
+
+#[contractimpl]
+impl PiCoinLending {
+    // Constructor: lending market for PI. Suppliers lend PI for shares whose exchange rate
+    // grows with the interest borrowers pay. Borrowers lock listed collateral and borrow PI
+    // up to its oracle value times the asset's LTV. The borrow rate comes from the rate
+    // model at the pool's utilization. Positions whose debt passes the collateral's
+    // liquidation threshold may be liquidated.
+    pub fn __constructor(
+        env: Env,
+        governance: Address,
+        pi_token: Address,
+        oracle: Address,
+        pi_symbol: Symbol,
+        rate_model: Address,
+        reserve_factor_bps: u32,
+    ) -> Result<(), LendingError> {
+        if reserve_factor_bps as i128 > BPS {
+            return Err(LendingError::InvalidConfig);
+        }
+        let data = LendingData {
+            governance,
+            pi_token,
+            oracle,
+            pi_symbol,
+            rate_model,
+            reserve_factor_bps,
+            borrow_index: INDEX_ONE,
+            total_borrows: 0,
+            total_reserves: 0,
+            total_shares: 0,
+            bad_debt: 0,
+            last_accrual: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&Symbol::new(&env, "lending_data"), &data);
+        env.storage().instance().set(&LendingKey::Collaterals, &Vec::<Address>::new(&env));
+        log!(&env, "Lending market initialized: supply PI, borrow against oracle-priced collateral");
+        Ok(())
+    }
+
+    // Supply `amount` PI to lend out; returns the shares credited for what arrived, net of
+    // any PI transfer fee
+    pub fn supply(env: Env, from: Address, amount: i128) -> Result<i128, LendingError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let mut data = Self::accrue(&env)?;
+        // The rate is taken before the PI arrives, since the cash is part of it
+        let rate = Self::exchange_rate_of(&env, &data)?;
+        let amount = receive::pull(&env, &data.pi_token, &from, amount).ok_or(LendingError::Overflow)?;
+        let shares = Self::mul_div(amount, INDEX_ONE, rate)?;
+        if shares <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        data.total_shares = Self::checked_add(data.total_shares, shares)?;
+        Self::write_shares(&env, &from, Self::shares_of(env.clone(), from.clone()) + shares);
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "lending_supply"), from), (amount, shares));
+        Ok(shares)
+    }
+
+    // Withdraw `amount` supplied PI, interest included, out of the idle cash; returns the
+    // shares burned
+    pub fn withdraw(env: Env, owner: Address, amount: i128) -> Result<i128, LendingError> {
+        owner.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let mut data = Self::accrue(&env)?;
+        // Round the shares up so a withdrawal never takes more than it pays for
+        let rate = Self::exchange_rate_of(&env, &data)?;
+        let shares = (Self::checked_mul(amount, INDEX_ONE)? + rate - 1) / rate;
+        let held = Self::shares_of(env.clone(), owner.clone());
+        if shares > held {
+            return Err(LendingError::InsufficientShares);
+        }
+        if amount > Self::liquidity(&env, &data) {
+            return Err(LendingError::InsufficientLiquidity);
+        }
+        data.total_shares -= shares;
+        Self::write_shares(&env, &owner, held - shares);
+        Self::save(&env, &data);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &owner, &amount);
+        env.events().publish((Symbol::new(&env, "lending_withdraw"), owner), (amount, shares));
+        Ok(shares)
+    }
+
+    // Lock `amount` of the listed `asset` as collateral; what arrived, net of any transfer
+    // fee, is credited
+    pub fn deposit_collateral(env: Env, from: Address, asset: Address, amount: i128) -> Result<(), LendingError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        Self::collateral_config(env.clone(), asset.clone()).ok_or(LendingError::UnsupportedAsset)?;
+        let amount = receive::pull(&env, &asset, &from, amount).ok_or(LendingError::Overflow)?;
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let held = Self::collateral_of(env.clone(), from.clone(), asset.clone());
+        Self::write_deposit(&env, &from, &asset, Self::checked_add(held, amount)?);
+        env.events().publish((Symbol::new(&env, "lending_collateral"), from, asset), amount);
+        Ok(())
+    }
+
+    // Unlock `amount` of `asset`; what stays locked must still cover the debt at LTV
+    pub fn withdraw_collateral(env: Env, owner: Address, asset: Address, amount: i128) -> Result<(), LendingError> {
+        owner.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let data = Self::accrue(&env)?;
+        let held = Self::collateral_of(env.clone(), owner.clone(), asset.clone());
+        if amount > held {
+            return Err(LendingError::InsufficientCollateral);
+        }
+        Self::write_deposit(&env, &owner, &asset, held - amount);
+        let (limit, _, debt) = Self::position(&env, &data, &owner)?;
+        if debt > limit {
+            return Err(LendingError::BorrowLimitExceeded);
+        }
+        Self::save(&env, &data);
+        token::TokenClient::new(&env, &asset).transfer(&env.current_contract_address(), &owner, &amount);
+        env.events().publish((Symbol::new(&env, "lending_collateral_withdraw"), owner, asset), amount);
+        Ok(())
+    }
+
+    // Borrow `amount` PI against the caller's collateral, up to its borrow limit
+    pub fn borrow(env: Env, borrower: Address, amount: i128) -> Result<(), LendingError> {
+        borrower.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let mut data = Self::accrue(&env)?;
+        if amount > Self::liquidity(&env, &data) {
+            return Err(LendingError::InsufficientLiquidity);
+        }
+        let debt = Self::checked_add(Self::debt(&env, &data, &borrower)?, amount)?;
+        let (limit, _, _) = Self::position(&env, &data, &borrower)?;
+        if debt > limit {
+            return Err(LendingError::BorrowLimitExceeded);
+        }
+        Self::write_debt(&env, &data, &borrower, debt);
+        data.total_borrows = Self::checked_add(data.total_borrows, amount)?;
+        Self::save(&env, &data);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &borrower, &amount);
+        env.events().publish((Symbol::new(&env, "lending_borrow"), borrower), (amount, debt));
+        Ok(())
+    }
+
+    // Repay up to `amount` of `borrower`'s debt from `from`; returns the PI repaid, which is
+    // what arrived net of any PI transfer fee
+    pub fn repay(env: Env, from: Address, borrower: Address, amount: i128) -> Result<i128, LendingError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let mut data = Self::accrue(&env)?;
+        let debt = Self::debt(&env, &data, &borrower)?;
+        if amount.min(debt) == 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let repaid = receive::pull(&env, &data.pi_token, &from, amount.min(debt)).ok_or(LendingError::Overflow)?;
+        if repaid <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        Self::write_debt(&env, &data, &borrower, debt - repaid);
+        data.total_borrows = (data.total_borrows - repaid).max(0);
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "lending_repay"), borrower), (repaid, debt - repaid));
+        Ok(repaid)
+    }
+
+    // Permissionless: Repay up to `amount` (at most CLOSE_FACTOR_BPS of the debt) of a
+    // borrower whose debt has passed its collateral's liquidation threshold, and take
+    // that value of `asset` plus the liquidation bonus. If the borrower has no collateral
+    // left afterwards, the remaining debt is written off as bad debt. Returns the
+    // collateral seized.
+    pub fn liquidate(env: Env, liquidator: Address, borrower: Address, asset: Address, amount: i128) -> Result<i128, LendingError> {
+        liquidator.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let mut data = Self::accrue(&env)?;
+        let config = Self::collateral_config(env.clone(), asset.clone()).ok_or(LendingError::UnsupportedAsset)?;
+        let (_, threshold, debt) = Self::position(&env, &data, &borrower)?;
+        if debt <= threshold {
+            return Err(LendingError::NotLiquidatable);
+        }
+
+        // Collateral worth the repayment plus the bonus, or all of it if that is less
+        let held = Self::collateral_of(env.clone(), borrower.clone(), asset.clone());
+        let bonus = BPS + config.liquidation_bonus_bps as i128;
+        let mut repaid = amount.min(Self::mul_div(debt, CLOSE_FACTOR_BPS, BPS)?);
+        let mut seized = Self::mul_div(Self::to_asset(&env, &data, &config, repaid)?, bonus, BPS)?;
+        if seized > held {
+            seized = held;
+            repaid = Self::mul_div(Self::to_pi(&env, &data, &config, held)?, BPS, bonus)?;
+        }
+        if repaid <= 0 || seized <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        // Only what arrived, net of any PI transfer fee, repays debt and is paid for in collateral
+        let received = receive::pull(&env, &data.pi_token, &liquidator, repaid).ok_or(LendingError::Overflow)?;
+        if received < repaid {
+            repaid = received;
+            seized = Self::mul_div(Self::to_asset(&env, &data, &config, repaid)?, bonus, BPS)?.min(held);
+            if repaid <= 0 || seized <= 0 {
+                return Err(LendingError::InvalidAmount);
+            }
+        }
+        Self::write_deposit(&env, &borrower, &asset, held - seized);
+        let mut remaining = debt - repaid;
+        data.total_borrows = (data.total_borrows - repaid).max(0);
+
+        // Nothing left to seize: the rest of the debt is a loss for suppliers
+        let mut bad_debt = 0;
+        if remaining > 0 && Self::is_empty(&env, &borrower) {
+            bad_debt = remaining;
+            remaining = 0;
+            data.total_borrows = (data.total_borrows - bad_debt).max(0);
+            data.bad_debt = Self::checked_add(data.bad_debt, bad_debt)?;
+        }
+        Self::write_debt(&env, &data, &borrower, remaining);
+        Self::save(&env, &data);
+        token::TokenClient::new(&env, &asset).transfer(&env.current_contract_address(), &liquidator, &seized);
+        if let Some(hook) = Self::liquidation_hook(env.clone()) {
+            let cash = Self::cash(&env, &data);
+            LiquidationHookClient::new(&env, &hook).on_liquidation(&borrower, &asset, &repaid, &seized, &bad_debt);
+            let covered = (Self::cash(&env, &data) - cash).clamp(0, bad_debt);
+            if covered > 0 {
+                data.bad_debt -= covered;
+                Self::save(&env, &data);
+            }
+        }
+        env.events().publish((Symbol::new(&env, "lending_liquidate"), borrower, asset), (liquidator, repaid, seized, bad_debt));
+        Ok(seized)
+    }
+
+    // Cover up to `amount` of the outstanding bad debt with PI pulled from an allowance
+    // `from` gave this pool (an insurance fund, a backstop auction or any donor);
+    // returns the PI taken. It goes back to suppliers through the exchange rate.
+    pub fn cover_bad_debt(env: Env, from: Address, amount: i128) -> Result<i128, LendingError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        let mut data = Self::accrue(&env)?;
+        let covered = amount.min(data.bad_debt);
+        if covered == 0 {
+            return Err(LendingError::InvalidAmount);
+        }
+        data.bad_debt -= covered;
+        Self::save(&env, &data);
+        let pool = env.current_contract_address();
+        token::TokenClient::new(&env, &data.pi_token).transfer_from(&pool, &from, &pool, &covered);
+        env.events().publish((Symbol::new(&env, "lending_cover"), from), (covered, data.bad_debt));
+        Ok(covered)
+    }
+
+    // Accrue interest up to now (permissionless); returns the borrow index
+    pub fn accrue_interest(env: Env) -> Result<i128, LendingError> {
+        let data = Self::accrue(&env)?;
+        Self::save(&env, &data);
+        Ok(data.borrow_index)
+    }
+
+    // Governance-only: List `asset` as collateral priced under the oracle's `symbol`, or
+    // update its parameters. Delisting is a zero LTV, which stops new borrowing against
+    // the asset but leaves existing positions to be repaid or liquidated.
+    pub fn set_collateral(
+        env: Env,
+        asset: Address,
+        symbol: Symbol,
+        ltv_bps: u32,
+        liquidation_threshold_bps: u32,
+        liquidation_bonus_bps: u32,
+    ) -> Result<(), LendingError> {
+        let data = Self::load(&env);
+        data.governance.require_auth();
+        // A liquidation at the threshold must still find the bonus in the collateral
+        let threshold = liquidation_threshold_bps as i128;
+        if ltv_bps > liquidation_threshold_bps || threshold * (BPS + liquidation_bonus_bps as i128) > BPS * BPS {
+            return Err(LendingError::InvalidConfig);
+        }
+        let decimals = match Self::collateral_config(env.clone(), asset.clone()) {
+            Some(config) => config.decimals,
+            None => {
+                let mut listed = Self::collaterals(env.clone());
+                let decimals = token::TokenClient::new(&env, &asset).decimals();
+                if decimals > MAX_DECIMALS || listed.len() >= MAX_COLLATERALS {
+                    return Err(LendingError::InvalidConfig);
+                }
+                listed.push_back(asset.clone());
+                env.storage().instance().set(&LendingKey::Collaterals, &listed);
+                decimals
+            }
+        };
+        let config = CollateralConfig { symbol, decimals, ltv_bps, liquidation_threshold_bps, liquidation_bonus_bps };
+        env.storage().persistent().set(&LendingKey::Collateral(asset.clone()), &config);
+        env.events().publish((Symbol::new(&env, "lending_collateral_config"), asset), config);
+        Ok(())
+    }
+
+    // Governance-only: Switch rate models; interest up to now accrues at the old one
+    pub fn set_rate_model(env: Env, rate_model: Address) -> Result<(), LendingError> {
+        let mut data = Self::accrue(&env)?;
+        data.governance.require_auth();
+        data.rate_model = rate_model;
+        Self::save(&env, &data);
+        Ok(())
+    }
+
+    // Governance-only: Set the share of interest kept as reserves
+    pub fn set_reserve_factor(env: Env, reserve_factor_bps: u32) -> Result<(), LendingError> {
+        let mut data = Self::accrue(&env)?;
+        data.governance.require_auth();
+        if reserve_factor_bps as i128 > BPS {
+            return Err(LendingError::InvalidConfig);
+        }
+        data.reserve_factor_bps = reserve_factor_bps;
+        Self::save(&env, &data);
+        Ok(())
+    }
+
+    // Governance-only: Set (or clear) the contract notified of every liquidation
+    pub fn set_liquidation_hook(env: Env, hook: Option<Address>) -> Result<(), LendingError> {
+        let data = Self::load(&env);
+        data.governance.require_auth();
+        match hook {
+            Some(hook) => env.storage().instance().set(&LendingKey::LiquidationHook, &hook),
+            None => env.storage().instance().remove(&LendingKey::LiquidationHook),
+        }
+        Ok(())
+    }
+
+    // Governance-only: Pay `amount` of the reserves to `to` (normally the treasury)
+    pub fn withdraw_reserves(env: Env, to: Address, amount: i128) -> Result<(), LendingError> {
+        let mut data = Self::accrue(&env)?;
+        data.governance.require_auth();
+        if amount <= 0 || amount > data.total_reserves {
+            return Err(LendingError::InvalidAmount);
+        }
+        let cash = Self::cash(&env, &data);
+        if amount > cash {
+            return Err(LendingError::InsufficientLiquidity);
+        }
+        data.total_reserves -= amount;
+        Self::save(&env, &data);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &to, &amount);
+        Ok(())
+    }
+
+    // View: `user`'s health factor in basis points - collateral value weighted by the
+    // liquidation thresholds over the debt. Below 10,000 the position may be liquidated;
+    // i128::MAX without debt.
+    pub fn health_factor(env: Env, user: Address) -> Result<i128, LendingError> {
+        let data = Self::accrue(&env)?;
+        let (_, threshold, debt) = Self::position(&env, &data, &user)?;
+        if debt == 0 {
+            return Ok(i128::MAX);
+        }
+        Self::mul_div(threshold, BPS, debt)
+    }
+
+    // View: Most PI `user` may owe against their collateral at LTV
+    pub fn borrow_limit(env: Env, user: Address) -> Result<i128, LendingError> {
+        let data = Self::accrue(&env)?;
+        Ok(Self::position(&env, &data, &user)?.0)
+    }
+
+    // View: PI `borrower` owes right now, interest included
+    pub fn debt_of(env: Env, borrower: Address) -> Result<i128, LendingError> {
+        Self::debt(&env, &Self::accrue(&env)?, &borrower)
+    }
+
+    // View: `asset` `owner` has locked as collateral
+    pub fn collateral_of(env: Env, owner: Address, asset: Address) -> i128 {
+        env.storage().persistent().get(&LendingKey::Deposit(owner, asset)).unwrap_or(0)
+    }
+
+    // View: Supply shares held by `owner`
+    pub fn shares_of(env: Env, owner: Address) -> i128 {
+        env.storage().persistent().get(&LendingKey::Shares(owner)).unwrap_or(0)
+    }
+
+    // View: PI `owner`'s shares are worth right now, interest included
+    pub fn supplied_of(env: Env, owner: Address) -> Result<i128, LendingError> {
+        let rate = Self::exchange_rate_of(&env, &Self::accrue(&env)?)?;
+        Self::mul_div(Self::shares_of(env.clone(), owner), rate, INDEX_ONE)
+    }
+
+    // View: PI per supply share, scaled by INDEX_ONE, including interest not yet accrued
+    pub fn exchange_rate(env: Env) -> Result<i128, LendingError> {
+        Self::exchange_rate_of(&env, &Self::accrue(&env)?)
+    }
+
+    // View: Annual borrow rate at the current utilization, in basis points
+    pub fn borrow_rate(env: Env) -> u32 {
+        let data = Self::load(&env);
+        RateModelClient::new(&env, &data.rate_model).borrow_rate(&Self::cash(&env, &data), &data.total_borrows)
+    }
+
+    // View: PI owed by all borrowers right now, interest included
+    pub fn total_borrows(env: Env) -> Result<i128, LendingError> {
+        Ok(Self::accrue(&env)?.total_borrows)
+    }
+
+    // View: Pool state with interest accrued up to now
+    pub fn market(env: Env) -> Result<LendingData, LendingError> {
+        Self::accrue(&env)
+    }
+
+    // View: Listing of `asset`
+    pub fn collateral_config(env: Env, asset: Address) -> Option<CollateralConfig> {
+        env.storage().persistent().get(&LendingKey::Collateral(asset))
+    }
+
+    // View: Listed collateral assets
+    pub fn collaterals(env: Env) -> Vec<Address> {
+        env.storage().instance().get(&LendingKey::Collaterals).unwrap_or(Vec::new(&env))
+    }
+
+    // View: Contract notified of liquidations, if any
+    pub fn liquidation_hook(env: Env) -> Option<Address> {
+        env.storage().instance().get(&LendingKey::LiquidationHook)
+    }
+
+    // Helper: `data` with interest accrued from the last accrual up to now, or Overflow
+    fn accrued(env: &Env, data: &LendingData) -> Result<LendingData, LendingError> {
+        let mut data = data.clone();
+        let now = env.ledger().timestamp();
+        let elapsed = now.saturating_sub(data.last_accrual) as i128;
+        data.last_accrual = now;
+        if elapsed == 0 || data.total_borrows == 0 {
+            return Ok(data);
+        }
+        let rate = RateModelClient::new(env, &data.rate_model).borrow_rate(&Self::cash(env, &data), &data.total_borrows) as i128;
+        let interest = Self::mul_div(Self::checked_mul(data.total_borrows, rate)?, elapsed, BPS * YEAR as i128)?;
+        data.borrow_index = Self::checked_add(data.borrow_index, Self::mul_div(data.borrow_index, interest, data.total_borrows)?)?;
+        data.total_borrows = Self::checked_add(data.total_borrows, interest)?;
+        data.total_reserves = Self::checked_add(data.total_reserves, Self::mul_div(interest, data.reserve_factor_bps as i128, BPS)?)?;
+        Ok(data)
+    }
+
+    // Helper: Load the pool state with interest accrued up to now
+    fn accrue(env: &Env) -> Result<LendingData, LendingError> {
+        Self::accrued(env, &Self::load(env))
+    }
+
+    // Helper: (Borrow limit at LTV, liquidation threshold value, debt) of `user`, in PI
+    fn position(env: &Env, data: &LendingData, user: &Address) -> Result<(i128, i128, i128), LendingError> {
+        let (mut limit, mut threshold) = (0i128, 0i128);
+        for asset in Self::collaterals(env.clone()).iter() {
+            let held = Self::collateral_of(env.clone(), user.clone(), asset.clone());
+            if held == 0 {
+                continue;
+            }
+            let config = Self::collateral_config(env.clone(), asset).unwrap();
+            let value = Self::to_pi(env, data, &config, held)?;
+            limit = Self::checked_add(limit, Self::mul_div(value, config.ltv_bps as i128, BPS)?)?;
+            threshold = Self::checked_add(threshold, Self::mul_div(value, config.liquidation_threshold_bps as i128, BPS)?)?;
+        }
+        Ok((limit, threshold, Self::debt(env, data, user)?))
+    }
+
+    // Helper: PI value of `amount` of a collateral asset at oracle prices
+    fn to_pi(env: &Env, data: &LendingData, config: &CollateralConfig, amount: i128) -> Result<i128, LendingError> {
+        let (asset_price, pi_price) = (Self::price(env, data, &config.symbol)?, Self::price(env, data, &data.pi_symbol)?);
+        let value = Self::mul_div(amount, asset_price, pi_price)?;
+        Self::mul_div(value, 10i128.pow(PI_DECIMALS), 10i128.pow(config.decimals))
+    }
+
+    // Helper: Amount of a collateral asset worth `pi_amount` at oracle prices
+    fn to_asset(env: &Env, data: &LendingData, config: &CollateralConfig, pi_amount: i128) -> Result<i128, LendingError> {
+        let (asset_price, pi_price) = (Self::price(env, data, &config.symbol)?, Self::price(env, data, &data.pi_symbol)?);
+        let amount = Self::mul_div(pi_amount, pi_price, asset_price)?;
+        Self::mul_div(amount, 10i128.pow(config.decimals), 10i128.pow(PI_DECIMALS))
+    }
+
+    // Helper: Oracle price of `symbol`, which must be positive and at most MAX_PRICE_AGE old
+    fn price(env: &Env, data: &LendingData, symbol: &Symbol) -> Result<i128, LendingError> {
+        let oracle = PriceOracleClient::new(env, &data.oracle);
+        let updated_at = oracle.last_update(symbol).ok_or(LendingError::InvalidPrice)?;
+        if env.ledger().timestamp().saturating_sub(updated_at) > MAX_PRICE_AGE {
+            return Err(LendingError::StalePrice);
+        }
+        let price = oracle.query_price(symbol);
+        if price <= 0 {
+            return Err(LendingError::InvalidPrice);
+        }
+        Ok(price)
+    }
+
+    // Helper: `borrower`'s debt at the current borrow index, rounded up, or Overflow
+    fn debt(env: &Env, data: &LendingData, borrower: &Address) -> Result<i128, LendingError> {
+        let Some(snapshot) = env.storage().persistent().get::<_, BorrowSnapshot>(&LendingKey::Borrow(borrower.clone())) else {
+            return Ok(0);
+        };
+        Ok(Self::checked_add(Self::checked_mul(snapshot.principal, data.borrow_index)?, snapshot.index - 1)? / snapshot.index)
+    }
+
+    fn write_debt(env: &Env, data: &LendingData, borrower: &Address, debt: i128) {
+        let key = LendingKey::Borrow(borrower.clone());
+        if debt == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &BorrowSnapshot { principal: debt, index: data.borrow_index });
+        }
+    }
+
+    fn write_deposit(env: &Env, owner: &Address, asset: &Address, amount: i128) {
+        let key = LendingKey::Deposit(owner.clone(), asset.clone());
+        if amount == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &amount);
+        }
+    }
+
+    fn write_shares(env: &Env, owner: &Address, shares: i128) {
+        let key = LendingKey::Shares(owner.clone());
+        if shares == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &shares);
+        }
+    }
+
+    // Helper: Whether `user` has no collateral left in any listed asset
+    fn is_empty(env: &Env, user: &Address) -> bool {
+        Self::collaterals(env.clone()).iter().all(|asset| Self::collateral_of(env.clone(), user.clone(), asset) == 0)
+    }
+
+    // Helper: PI per share: supplied value (cash plus borrows, less reserves) over shares
+    fn exchange_rate_of(env: &Env, data: &LendingData) -> Result<i128, LendingError> {
+        if data.total_shares == 0 {
+            return Ok(INDEX_ONE);
+        }
+        let assets = Self::checked_add(Self::cash(env, data), data.total_borrows)? - data.total_reserves;
+        Ok(Self::mul_div(assets, INDEX_ONE, data.total_shares)?.max(1))
+    }
+
+    // Helper: Idle PI suppliers and borrowers may draw on (the cash beyond reserves)
+    fn liquidity(env: &Env, data: &LendingData) -> i128 {
+        (Self::cash(env, data) - data.total_reserves).max(0)
+    }
+
+    fn cash(env: &Env, data: &LendingData) -> i128 {
+        token::TokenClient::new(env, &data.pi_token).balance(&env.current_contract_address())
+    }
+
+    fn mul_div(a: i128, b: i128, c: i128) -> Result<i128, LendingError> {
+        a.checked_mul(b).and_then(|product| product.checked_div(c)).ok_or(LendingError::Overflow)
+    }
+
+    fn checked_mul(a: i128, b: i128) -> Result<i128, LendingError> {
+        a.checked_mul(b).ok_or(LendingError::Overflow)
+    }
+
+    fn checked_add(a: i128, b: i128) -> Result<i128, LendingError> {
+        a.checked_add(b).ok_or(LendingError::Overflow)
+    }
+
+    fn save(env: &Env, data: &LendingData) {
+        env.storage().instance().set(&Symbol::new(env, "lending_data"), data);
+    }
+
+    fn load(env: &Env) -> LendingData {
+        env.storage().instance().get(&Symbol::new(env, "lending_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,303 @@
+#![cfg(test)]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, testutils::{Address as _, Ledger}, token, Address, Env, Symbol,
+};
+use crate::{BorrowSnapshot, LendingData, LendingError, LendingKey, PiCoinLending, PiCoinLendingClient, MAX_PRICE_AGE, YEAR};
+
+// Just enough of PiCoinOracle: prices set directly, in any common scale, and stamped
+// with the ledger time
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Symbol, price: i128) {
+        env.storage().instance().set(&asset, &(price, env.ledger().timestamp()));
+    }
+
+    pub fn query_price(env: Env, asset: Symbol) -> i128 {
+        env.storage().instance().get::<_, (i128, u64)>(&asset).unwrap().0
+    }
+
+    pub fn last_update(env: Env, asset: Symbol) -> Option<u64> {
+        env.storage().instance().get::<_, (i128, u64)>(&asset).map(|(_, updated_at)| updated_at)
+    }
+}
+
+// Stand-in for PiCoinRateModel: a flat 10% a year
+#[contract]
+struct MockRateModel;
+
+#[contractimpl]
+impl MockRateModel {
+    pub fn borrow_rate(_env: Env, _cash: i128, _borrows: i128) -> u32 {
+        1_000
+    }
+}
+
+// A PI that burns 1% of every transfer, so the receiving side gets 99%
+#[contract]
+struct MockFeeToken;
+
+#[contractimpl]
+impl MockFeeToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        env.storage().instance().set(&to, &(Self::balance(env.clone(), to.clone()) + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        env.storage().instance().set(&from, &(Self::balance(env.clone(), from.clone()) - amount));
+        env.storage().instance().set(&to, &(Self::balance(env.clone(), to.clone()) + amount - amount / 100));
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Liquidation {
+    borrower: Address,
+    asset: Address,
+    repaid: i128,
+    seized: i128,
+    bad_debt: i128,
+}
+
+// Records the last liquidation it was notified of
+#[contract]
+struct MockHook;
+
+#[contractimpl]
+impl MockHook {
+    pub fn on_liquidation(env: Env, borrower: Address, asset: Address, repaid: i128, seized: i128, bad_debt: i128) {
+        env.storage().instance().set(&0u32, &Liquidation { borrower, asset, repaid, seized, bad_debt });
+    }
+
+    pub fn last(env: Env) -> Liquidation {
+        env.storage().instance().get(&0u32).unwrap()
+    }
+}
+
+#[test]
+fn test_supply_borrow_accrue_and_liquidate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    // Any SEP-41 tokens stand in for PI and the collateral (XLM)
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let pi_admin = token::StellarAssetClient::new(&env, &pi);
+    let xlm_admin = token::StellarAssetClient::new(&env, &xlm);
+    let xlm_client = token::TokenClient::new(&env, &xlm);
+    let (alice, bob, carol) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    pi_admin.mint(&alice, &10_000);
+    pi_admin.mint(&carol, &1_000);
+    xlm_admin.mint(&bob, &4_000);
+
+    // 1 XLM = 0.5 PI
+    let oracle = env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&env, &oracle);
+    oracle_client.set_price(&Symbol::new(&env, "PI"), &10_000_000);
+    oracle_client.set_price(&Symbol::new(&env, "XLM"), &5_000_000);
+    let hook = env.register(MockHook, ());
+
+    // 10% of interest kept as reserves
+    let contract_id = env.register(
+        PiCoinLending,
+        (Address::generate(&env), &pi, &oracle, Symbol::new(&env, "PI"), env.register(MockRateModel, ()), 1_000u32),
+    );
+    let client = PiCoinLendingClient::new(&env, &contract_id);
+    client.set_liquidation_hook(&Some(hook.clone()));
+
+    // The threshold must sit at or above the LTV and leave room for the bonus
+    let xlm_symbol = Symbol::new(&env, "XLM");
+    assert!(matches!(client.try_set_collateral(&xlm, &xlm_symbol, &9_000, &8_000, &500), Err(Ok(LendingError::InvalidConfig))));
+    assert!(matches!(client.try_set_collateral(&xlm, &xlm_symbol, &5_000, &9_800, &500), Err(Ok(LendingError::InvalidConfig))));
+    client.set_collateral(&xlm, &xlm_symbol, &5_000, &8_000, &500);
+    assert_eq!(client.collaterals().len(), 1);
+
+    // 4,000 XLM is worth 2,000 PI, so bob may borrow 1,000 at a 50% LTV
+    assert_eq!(client.supply(&alice, &10_000), 10_000);
+    client.deposit_collateral(&bob, &xlm, &4_000);
+    assert_eq!(client.borrow_limit(&bob), 1_000);
+    assert!(matches!(client.try_borrow(&bob, &1_001), Err(Ok(LendingError::BorrowLimitExceeded))));
+    client.borrow(&bob, &1_000);
+    assert!(matches!(client.try_withdraw_collateral(&bob, &xlm, &1), Err(Ok(LendingError::BorrowLimitExceeded))));
+
+    // A year at 10%: the debt grows by 100, 10 of which goes to reserves. Prices a year
+    // old no longer value collateral until the oracle reports again.
+    env.ledger().set_timestamp(1_000 + YEAR);
+    assert_eq!(client.debt_of(&bob), 1_100);
+    assert!(matches!(client.try_health_factor(&bob), Err(Ok(LendingError::StalePrice))));
+    oracle_client.set_price(&Symbol::new(&env, "PI"), &10_000_000);
+    oracle_client.set_price(&Symbol::new(&env, "XLM"), &5_000_000);
+    assert_eq!(client.health_factor(&bob), 1_600 * 10_000 / 1_100);
+    assert_eq!(client.supplied_of(&alice), 10_090);
+    assert_eq!(client.repay(&bob, &bob, &600), 600);
+    assert_eq!(client.debt_of(&bob), 500);
+    assert_eq!(client.market().total_reserves, 10);
+
+    // Healthy positions cannot be liquidated
+    assert!(matches!(client.try_liquidate(&carol, &bob, &xlm, &100), Err(Ok(LendingError::NotLiquidatable))));
+
+    // XLM falls to 0.125 PI: 500 PI of collateral against 500 of debt, past the 80% threshold.
+    // A liquidation repays at most half the debt and takes 5% extra collateral.
+    oracle_client.set_price(&xlm_symbol, &1_250_000);
+    assert_eq!(client.health_factor(&bob), 8_000);
+    assert_eq!(client.liquidate(&carol, &bob, &xlm, &1_000), 2_100);
+    assert_eq!(client.debt_of(&bob), 250);
+    assert_eq!(client.collateral_of(&bob, &xlm), 1_900);
+    assert_eq!(MockHookClient::new(&env, &hook).last().repaid, 250);
+
+    // XLM collapses to 0.01 PI: the rest of the collateral covers only 18 PI of the debt
+    // and the remaining 232 is written off
+    oracle_client.set_price(&xlm_symbol, &100_000);
+    assert_eq!(client.liquidate(&carol, &bob, &xlm, &1_000), 1_900);
+    assert_eq!(
+        MockHookClient::new(&env, &hook).last(),
+        Liquidation { borrower: bob.clone(), asset: xlm.clone(), repaid: 18, seized: 1_900, bad_debt: 232 }
+    );
+    assert_eq!(client.debt_of(&bob), 0);
+    assert_eq!(client.market().bad_debt, 232);
+    assert_eq!(client.market().total_borrows, 0);
+    assert_eq!(xlm_client.balance(&carol), 4_000);
+
+    // Suppliers bear the loss: 10,090 earned less 232 written off
+    assert_eq!(client.supplied_of(&alice), 9_858);
+
+    // Until an insurance fund or other donor covers it, through an allowance to the pool
+    let pi_client = token::TokenClient::new(&env, &pi);
+    pi_client.approve(&carol, &contract_id, &1_000, &env.ledger().sequence());
+    assert_eq!(client.cover_bad_debt(&carol, &1_000), 232);
+    assert_eq!(client.market().bad_debt, 0);
+    assert!(matches!(client.try_cover_bad_debt(&carol, &1), Err(Ok(LendingError::InvalidAmount))));
+    assert_eq!(client.supplied_of(&alice), 10_090);
+    assert!(matches!(client.try_withdraw(&alice, &10_091), Err(Ok(LendingError::InsufficientShares))));
+    assert_eq!(client.withdraw(&alice, &10_090), 10_000);
+    assert_eq!(client.shares_of(&alice), 0);
+}
+
+#[test]
+fn test_rejects_missing_nonpositive_and_stale_prices() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    token::StellarAssetClient::new(&env, &pi).mint(&alice, &10_000);
+    token::StellarAssetClient::new(&env, &xlm).mint(&bob, &4_000);
+    let oracle = env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&env, &oracle);
+    let contract_id = env.register(
+        PiCoinLending,
+        (Address::generate(&env), &pi, &oracle, Symbol::new(&env, "PI"), env.register(MockRateModel, ()), 1_000u32),
+    );
+    let client = PiCoinLendingClient::new(&env, &contract_id);
+    let (pi_symbol, xlm_symbol) = (Symbol::new(&env, "PI"), Symbol::new(&env, "XLM"));
+    client.set_collateral(&xlm, &xlm_symbol, &5_000, &8_000, &500);
+    client.supply(&alice, &10_000);
+    client.deposit_collateral(&bob, &xlm, &4_000);
+
+    // No price reported yet, or a zero price, cannot value collateral
+    oracle_client.set_price(&pi_symbol, &10_000_000);
+    assert!(matches!(client.try_borrow(&bob, &100), Err(Ok(LendingError::InvalidPrice))));
+    oracle_client.set_price(&xlm_symbol, &0);
+    assert!(matches!(client.try_borrow(&bob, &100), Err(Ok(LendingError::InvalidPrice))));
+    oracle_client.set_price(&xlm_symbol, &5_000_000);
+    client.borrow(&bob, &100);
+
+    // Valid up to MAX_PRICE_AGE seconds old, stale one second later
+    env.ledger().set_timestamp(1_000 + MAX_PRICE_AGE);
+    assert_eq!(client.borrow_limit(&bob), 1_000);
+    env.ledger().set_timestamp(1_001 + MAX_PRICE_AGE);
+    assert!(matches!(client.try_borrow(&bob, &100), Err(Ok(LendingError::StalePrice))));
+    assert!(matches!(client.try_liquidate(&alice, &bob, &xlm, &50), Err(Ok(LendingError::StalePrice))));
+}
+
+#[test]
+fn test_fee_on_transfer_pi_credits_what_arrived() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = env.register(MockFeeToken, ());
+    let pi_client = MockFeeTokenClient::new(&env, &pi);
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let (alice, bob, carol) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    pi_client.mint(&alice, &10_000);
+    pi_client.mint(&carol, &1_000);
+    token::StellarAssetClient::new(&env, &xlm).mint(&bob, &4_000);
+    let oracle = env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&env, &oracle);
+    let xlm_symbol = Symbol::new(&env, "XLM");
+    oracle_client.set_price(&Symbol::new(&env, "PI"), &10_000_000);
+    oracle_client.set_price(&xlm_symbol, &5_000_000);
+    let hook = env.register(MockHook, ());
+    let contract_id = env.register(
+        PiCoinLending,
+        (Address::generate(&env), &pi, &oracle, Symbol::new(&env, "PI"), env.register(MockRateModel, ()), 1_000u32),
+    );
+    let client = PiCoinLendingClient::new(&env, &contract_id);
+    client.set_liquidation_hook(&Some(hook.clone()));
+    client.set_collateral(&xlm, &xlm_symbol, &5_000, &8_000, &500);
+
+    // Supplying 10,000 lands 9,900 in the pool, and only that earns shares
+    assert_eq!(client.supply(&alice, &10_000), 9_900);
+    assert_eq!(client.supplied_of(&alice), 9_900);
+
+    // Repaying 500 lands 495, and only that comes off the debt
+    client.deposit_collateral(&bob, &xlm, &4_000);
+    client.borrow(&bob, &1_000);
+    assert_eq!(client.repay(&bob, &bob, &500), 495);
+    assert_eq!(client.debt_of(&bob), 505);
+
+    // A liquidator sending the 252 the close factor allows lands 250, which is all that is
+    // repaid and paid for in collateral: 2,000 XLM plus the 5% bonus
+    oracle_client.set_price(&xlm_symbol, &1_250_000);
+    assert_eq!(client.liquidate(&carol, &bob, &xlm, &1_000), 2_100);
+    assert_eq!(MockHookClient::new(&env, &hook).last().repaid, 250);
+    assert_eq!(client.debt_of(&bob), 255);
+    assert_eq!(client.collateral_of(&bob, &xlm), 1_900);
+}
+
+#[test]
+fn test_overflowing_positions_fail_with_overflow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    token::StellarAssetClient::new(&env, &pi).mint(&alice, &10_000);
+    let contract_id = env.register(
+        PiCoinLending,
+        (Address::generate(&env), &pi, env.register(MockOracle, ()), Symbol::new(&env, "PI"), env.register(MockRateModel, ()), 1_000u32),
+    );
+    let client = PiCoinLendingClient::new(&env, &contract_id);
+    client.supply(&alice, &10_000);
+
+    // Positions too large for i128 once scaled by the share rate or the borrow index
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(&LendingKey::Shares(alice.clone()), &i128::MAX);
+        env.storage().persistent().set(&LendingKey::Borrow(bob.clone()), &BorrowSnapshot { principal: i128::MAX / 2, index: 1 });
+    });
+    assert!(matches!(client.try_supplied_of(&alice), Err(Ok(LendingError::Overflow))));
+    assert!(matches!(client.try_debt_of(&bob), Err(Ok(LendingError::Overflow))));
+
+    // Interest on borrows too large to multiply by the rate
+    env.as_contract(&contract_id, || {
+        let key = Symbol::new(&env, "lending_data");
+        let mut data: LendingData = env.storage().instance().get(&key).unwrap();
+        data.total_borrows = i128::MAX / 2;
+        env.storage().instance().set(&key, &data);
+    });
+    env.ledger().set_timestamp(1_000 + YEAR);
+    assert!(matches!(client.try_market(), Err(Ok(LendingError::Overflow))));
+    assert!(matches!(client.try_accrue_interest(), Err(Ok(LendingError::Overflow))));
+}
@@ -0,0 +1,184 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Prices are counter-asset units per PRICE_SCALE PI units (one PI at 7 decimals)
+pub const PRICE_SCALE: i128 = 10_000_000;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderSide {
+    SellPi, // Maker escrows PI and asks for the counter asset
+    BuyPi, // Maker escrows the counter asset and bids for PI
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    pub maker: Address,
+    pub counter_asset: Address,
+    pub side: OrderSide,
+    pub price: i128, // Counter-asset units per PRICE_SCALE PI
+    pub remaining: i128, // PI still to trade
+    pub escrow: i128, // Held for the order: PI for SellPi, counter asset for BuyPi
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OrderKey {
+    Order(u64), // Removed once filled or cancelled
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OrderError {
+    InvalidAmount = 1,
+    InvalidPrice = 2,
+    SameAsset = 3,
+    UnknownOrder = 4,
+}
+
+#[contract]
+pub struct PiCoinLimitOrders;
+
+#[contractimpl]
+impl PiCoinLimitOrders {
+    // Constructor: limit orders between PI and any SEP-41 token. Makers escrow what they
+    // give at a fixed price; takers fill any part of an order at that price. There is no
+    // on-chain book or matching: place, trade and cancel events carry everything an
+    // indexer needs to build one. Exempt this contract from PI transfer fees, since both
+    // legs pass through it.
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        env.storage().instance().set(&Symbol::new(&env, "next_order"), &0u64);
+        log!(&env, "Limit orders initialized: PI against any SEP-41 token");
+    }
+
+    // Escrow an order to trade `pi_amount` PI against `counter_asset` at `price`; returns
+    // its id. A SellPi maker escrows the PI, a BuyPi maker the counter asset it bids
+    // (rounded up, any excess is refunded when the order closes).
+    pub fn place_order(
+        env: Env,
+        maker: Address,
+        counter_asset: Address,
+        side: OrderSide,
+        pi_amount: i128,
+        price: i128,
+    ) -> Result<u64, OrderError> {
+        maker.require_auth();
+        let pi_token = Self::pi_token(&env);
+        if counter_asset == pi_token {
+            return Err(OrderError::SameAsset);
+        }
+        if pi_amount <= 0 {
+            return Err(OrderError::InvalidAmount);
+        }
+        if price <= 0 {
+            return Err(OrderError::InvalidPrice);
+        }
+        let (escrow_asset, escrow) = match side {
+            OrderSide::SellPi => (pi_token, pi_amount),
+            OrderSide::BuyPi => (counter_asset.clone(), Self::quote(pi_amount, price, true)?),
+        };
+
+        let id: u64 = env.storage().instance().get(&Symbol::new(&env, "next_order")).unwrap();
+        let order = Order { maker: maker.clone(), counter_asset: counter_asset.clone(), side, price, remaining: pi_amount, escrow };
+        env.storage().instance().set(&Symbol::new(&env, "next_order"), &(id + 1));
+        env.storage().persistent().set(&OrderKey::Order(id), &order);
+        token::TokenClient::new(&env, &escrow_asset).transfer(&maker, env.current_contract_address(), &escrow);
+        env.events().publish((Symbol::new(&env, "order_placed"), maker, id), (counter_asset, side, pi_amount, price));
+        Ok(id)
+    }
+
+    // Trade up to `pi_amount` PI against order `id` at its price; a larger amount fills
+    // what is left. Against a SellPi order the taker pays the counter asset (rounded up)
+    // and receives PI; against a BuyPi order it pays PI and receives the counter asset
+    // (rounded down). Returns (PI traded, counter asset traded).
+    pub fn fill(env: Env, taker: Address, id: u64, pi_amount: i128) -> Result<(i128, i128), OrderError> {
+        taker.require_auth();
+        if pi_amount <= 0 {
+            return Err(OrderError::InvalidAmount);
+        }
+        let mut order = Self::order(env.clone(), id)?;
+        let traded = pi_amount.min(order.remaining);
+        let counter = Self::quote(traded, order.price, order.side == OrderSide::SellPi)?;
+        if counter == 0 {
+            return Err(OrderError::InvalidAmount);
+        }
+
+        // Book the fill before any token is called
+        order.remaining -= traded;
+        order.escrow -= match order.side {
+            OrderSide::SellPi => traded,
+            OrderSide::BuyPi => counter,
+        };
+        let refund = if order.remaining == 0 { order.escrow } else { 0 };
+        if order.remaining == 0 {
+            env.storage().persistent().remove(&OrderKey::Order(id));
+        } else {
+            env.storage().persistent().set(&OrderKey::Order(id), &order);
+        }
+
+        let contract_address = env.current_contract_address();
+        let pi = token::TokenClient::new(&env, &Self::pi_token(&env));
+        let counter_token = token::TokenClient::new(&env, &order.counter_asset);
+        match order.side {
+            OrderSide::SellPi => {
+                counter_token.transfer(&taker, &order.maker, &counter);
+                pi.transfer(&contract_address, &taker, &traded);
+            }
+            OrderSide::BuyPi => {
+                pi.transfer(&taker, &order.maker, &traded);
+                counter_token.transfer(&contract_address, &taker, &counter);
+                if refund > 0 {
+                    counter_token.transfer(&contract_address, &order.maker, &refund);
+                }
+            }
+        }
+        env.events().publish(
+            (Symbol::new(&env, "trade"), order.maker, taker, id),
+            (order.counter_asset, order.side, traded, counter, order.price),
+        );
+        Ok((traded, counter))
+    }
+
+    // Maker-only: Cancel what is left of order `id` and refund its escrow; returns the
+    // amount refunded
+    pub fn cancel_order(env: Env, id: u64) -> Result<i128, OrderError> {
+        let order = Self::order(env.clone(), id)?;
+        order.maker.require_auth();
+        env.storage().persistent().remove(&OrderKey::Order(id));
+        let escrow_asset = match order.side {
+            OrderSide::SellPi => Self::pi_token(&env),
+            OrderSide::BuyPi => order.counter_asset.clone(),
+        };
+        token::TokenClient::new(&env, &escrow_asset).transfer(&env.current_contract_address(), &order.maker, &order.escrow);
+        env.events().publish((Symbol::new(&env, "order_cancelled"), order.maker, id), (order.remaining, order.escrow));
+        Ok(order.escrow)
+    }
+
+    // View: Open order `id`; UnknownOrder once filled or cancelled
+    pub fn order(env: Env, id: u64) -> Result<Order, OrderError> {
+        env.storage().persistent().get(&OrderKey::Order(id)).ok_or(OrderError::UnknownOrder)
+    }
+
+    // View: Id the next order will get (ids are sequential from 0)
+    pub fn next_order_id(env: Env) -> u64 {
+        env.storage().instance().get(&Symbol::new(&env, "next_order")).unwrap()
+    }
+}
+
+impl PiCoinLimitOrders {
+    fn pi_token(env: &Env) -> Address {
+        env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap()
+    }
+
+    // Helper: Counter-asset value of `pi_amount` at `price`
+    fn quote(pi_amount: i128, price: i128, round_up: bool) -> Result<i128, OrderError> {
+        let product = pi_amount.checked_mul(price).ok_or(OrderError::InvalidAmount)?;
+        let quote = product / PRICE_SCALE;
+        Ok(if round_up && product % PRICE_SCALE != 0 { quote + 1 } else { quote })
+    }
+}
+
+mod test;
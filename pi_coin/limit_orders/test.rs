@@ -0,0 +1,69 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events}, token, vec, Address, Env, IntoVal, Symbol};
+use crate::{OrderError, OrderSide, PiCoinLimitOrders, PiCoinLimitOrdersClient};
+
+#[test]
+fn test_orders_fill_partially_and_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // SEP-41 tokens stand in for PI and USDC here
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let (pi_client, usdc_client) = (token::TokenClient::new(&env, &pi), token::TokenClient::new(&env, &usdc));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &pi).mint(&alice, &1_000);
+    token::StellarAssetClient::new(&env, &pi).mint(&carol, &1_000);
+    token::StellarAssetClient::new(&env, &usdc).mint(&bob, &10_000);
+
+    let contract_id = env.register(PiCoinLimitOrders, (&pi,));
+    let client = PiCoinLimitOrdersClient::new(&env, &contract_id);
+    assert_eq!(client.try_place_order(&alice, &pi, &OrderSide::SellPi, &1_000, &1), Err(Ok(OrderError::SameAsset)));
+    assert_eq!(client.try_place_order(&alice, &usdc, &OrderSide::SellPi, &1_000, &0), Err(Ok(OrderError::InvalidPrice)));
+
+    // Alice asks 1.5 USDC units per PI unit for 1_000 PI, escrowed up front
+    let ask = client.place_order(&alice, &usdc, &OrderSide::SellPi, &1_000, &15_000_000);
+    assert_eq!(pi_client.balance(&contract_id), 1_000);
+
+    // A taker pays at least the order's price: 3 PI cost 5, not 4.5
+    assert_eq!(client.fill(&bob, &ask, &3), (3, 5));
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "trade"), alice.clone(), bob.clone(), ask).into_val(&env),
+                (usdc.clone(), OrderSide::SellPi, 3i128, 5i128, 15_000_000i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.order(&ask).remaining, 997);
+
+    // Asking for more than is left fills the rest and closes the order
+    assert_eq!(client.fill(&bob, &ask, &5_000), (997, 1_496));
+    assert_eq!(client.try_order(&ask), Err(Ok(OrderError::UnknownOrder)));
+    assert_eq!((pi_client.balance(&bob), usdc_client.balance(&alice)), (1_000, 1_501));
+
+    // Bob bids for 100 PI at the same price, escrowing 150 USDC
+    let bid = client.place_order(&bob, &usdc, &OrderSide::BuyPi, &100, &15_000_000);
+    assert_eq!(client.try_fill(&carol, &bid, &0), Err(Ok(OrderError::InvalidAmount)));
+    assert_eq!(client.fill(&carol, &bid, &3), (3, 4)); // the maker's escrow pays at most the price
+    assert_eq!(client.cancel_order(&bid), 146);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "order_cancelled"), bob.clone(), bid).into_val(&env),
+                (97i128, 146i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.try_cancel_order(&bid), Err(Ok(OrderError::UnknownOrder)));
+    assert_eq!((pi_client.balance(&bob), usdc_client.balance(&bob)), (1_003, 10_000 - 1_501 - 4));
+    assert_eq!((pi_client.balance(&contract_id), usdc_client.balance(&contract_id)), (0, 0));
+}
@@ -0,0 +1,118 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, xdr::ToXdr, Address, BytesN, Env, Symbol, log};
+
+// Mirrors PiCoinSource (same variant names, so the same XDR) for the sources PI mints from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LotSource {
+    Mining,
+    Rewards,
+    P2P,
+    Registered(Symbol), // Channel from PI's source registry
+}
+
+// What a receipt attests: one mint lot as the PI contract recorded it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub lot_id: u64, // Also the receipt's token id
+    pub source: LotSource,
+    pub amount: i128, // PI minted in the lot
+    pub minted_at: u64, // Ledger timestamp of the mint
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ReceiptKey {
+    Receipt(u64),
+    Owner(u64),
+    Balance(Address), // Receipts held
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ReceiptError {
+    ReceiptNotFound = 1,
+    AlreadyIssued = 2,
+    NotOwner = 3,
+}
+
+#[contract]
+pub struct PiCoinLotReceipts;
+
+#[contractimpl]
+impl PiCoinLotReceipts {
+    // Constructor: one non-fungible receipt per PI mint lot, issued by the PI contract to
+    // the minter (see PiCoinContract::set_lot_receipts). Receipts move independently of
+    // the PI itself, so a lot's origin can be traded or handed to an auditor, and wallets
+    // can show origin badges from `receipt`.
+    pub fn __constructor(env: Env, pi_contract: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_contract"), &pi_contract);
+        log!(&env, "Lot receipts initialized: NFTs for PI provenance lots");
+    }
+
+    // PI contract only: Issue the receipt for lot `lot_id` to `owner`
+    pub fn issue(env: Env, lot_id: u64, owner: Address, source: LotSource, amount: i128, minted_at: u64) -> Result<(), ReceiptError> {
+        let pi_contract: Address = env.storage().instance().get(&Symbol::new(&env, "pi_contract")).unwrap();
+        pi_contract.require_auth();
+        if env.storage().persistent().has(&ReceiptKey::Receipt(lot_id)) {
+            return Err(ReceiptError::AlreadyIssued);
+        }
+        let receipt = Receipt { lot_id, source: source.clone(), amount, minted_at };
+        env.storage().persistent().set(&ReceiptKey::Receipt(lot_id), &receipt);
+        env.storage().persistent().set(&ReceiptKey::Owner(lot_id), &owner);
+        Self::add_balance(&env, &owner, 1);
+        env.events().publish((Symbol::new(&env, "receipt_issued"), owner, lot_id), (source, amount, minted_at));
+        Ok(())
+    }
+
+    // Owner: Hand receipt `token_id` to `to`
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u64) -> Result<(), ReceiptError> {
+        from.require_auth();
+        if Self::owner_of(env.clone(), token_id)? != from {
+            return Err(ReceiptError::NotOwner);
+        }
+        env.storage().persistent().set(&ReceiptKey::Owner(token_id), &to);
+        Self::add_balance(&env, &from, -1);
+        Self::add_balance(&env, &to, 1);
+        env.events().publish((Symbol::new(&env, "transfer"), from, to), token_id);
+        Ok(())
+    }
+
+    // View: Holder of receipt `token_id`
+    pub fn owner_of(env: Env, token_id: u64) -> Result<Address, ReceiptError> {
+        env.storage().persistent().get(&ReceiptKey::Owner(token_id)).ok_or(ReceiptError::ReceiptNotFound)
+    }
+
+    // View: Number of receipts `owner` holds
+    pub fn balance(env: Env, owner: Address) -> u32 {
+        env.storage().persistent().get(&ReceiptKey::Balance(owner)).unwrap_or(0)
+    }
+
+    // View: The lot receipt `token_id` stands for
+    pub fn receipt(env: Env, token_id: u64) -> Result<Receipt, ReceiptError> {
+        env.storage().persistent().get(&ReceiptKey::Receipt(token_id)).ok_or(ReceiptError::ReceiptNotFound)
+    }
+
+    // View: sha256 of the receipt's XDR. Off-chain metadata (the JSON behind a wallet's
+    // origin badge) embeds this hash, so anyone can check it describes the on-chain lot.
+    pub fn metadata_hash(env: Env, token_id: u64) -> Result<BytesN<32>, ReceiptError> {
+        let receipt = Self::receipt(env.clone(), token_id)?;
+        Ok(env.crypto().sha256(&receipt.to_xdr(&env)).to_bytes())
+    }
+}
+
+impl PiCoinLotReceipts {
+    fn add_balance(env: &Env, owner: &Address, delta: i32) {
+        let key = ReceiptKey::Balance(owner.clone());
+        let held = env.storage().persistent().get::<_, u32>(&key).unwrap_or(0).saturating_add_signed(delta);
+        if held == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &held);
+        }
+    }
+}
+
+mod test;
@@ -0,0 +1,40 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events}, vec, xdr::ToXdr, Address, Env, IntoVal, Symbol};
+use crate::{LotSource, PiCoinLotReceipts, PiCoinLotReceiptsClient, Receipt, ReceiptError};
+
+#[test]
+fn test_receipts_issue_per_lot_and_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // The PI contract only needs to authorize `issue`; any address stands in for it here
+    let pi_contract = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let contract_id = env.register(PiCoinLotReceipts, (&pi_contract,));
+    let client = PiCoinLotReceiptsClient::new(&env, &contract_id);
+
+    client.issue(&7, &alice, &LotSource::Mining, &1_000, &1_700_000_000);
+    assert_eq!(client.try_issue(&7, &bob, &LotSource::P2P, &1, &0), Err(Ok(ReceiptError::AlreadyIssued)));
+    let receipt = Receipt { lot_id: 7, source: LotSource::Mining, amount: 1_000, minted_at: 1_700_000_000 };
+    assert_eq!(client.receipt(&7), receipt);
+    assert_eq!(client.metadata_hash(&7), env.crypto().sha256(&receipt.to_xdr(&env)).to_bytes());
+    assert_eq!((client.owner_of(&7), client.balance(&alice)), (alice.clone(), 1));
+
+    // Receipts trade on their own; only the holder can move one
+    assert_eq!(client.try_transfer(&bob, &alice, &7), Err(Ok(ReceiptError::NotOwner)));
+    client.transfer(&alice, &bob, &7);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "transfer"), alice.clone(), bob.clone()).into_val(&env),
+                7u64.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!((client.owner_of(&7), client.balance(&alice), client.balance(&bob)), (bob.clone(), 0, 1));
+    assert_eq!(client.try_owner_of(&8), Err(Ok(ReceiptError::ReceiptNotFound)));
+}
@@ -0,0 +1,154 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+const BPS: i128 = 10_000;
+// Highest cash-back rate governance may give a merchant (10%)
+pub const MAX_CASHBACK_BPS: u32 = 1_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Merchant {
+    pub payout: Address, // Receives payments
+    pub cashback_bps: u32, // Share of each payment returned to the payer
+    pub cashback_cap: i128, // Most cash-back ever paid on this merchant's sales
+    pub cashback_paid: i128,
+    pub volume: i128, // PI paid to the merchant through this contract
+    pub payments: u64, // Payment count; numbers the settlement events
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MerchantsData {
+    pub governance: Address, // Lists merchants and sets their terms
+    pub pi_token: Address,
+    pub merchant_count: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MerchantKey {
+    Merchant(u64), // Persistent
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MerchantError {
+    InvalidAmount = 1,
+    MerchantNotFound = 2,
+    MerchantInactive = 3,
+    InvalidConfig = 4,
+}
+
+#[contract]
+pub struct PiCoinMerchants;
+
+#[contractimpl]
+impl PiCoinMerchants {
+    // Constructor: merchant registry with loyalty cash-back. Payments go straight to the
+    // merchant, and a share comes back to the payer out of PI the treasury has sent this
+    // contract (a treasury spend). Each merchant's cash-back is capped; once the cap or the
+    // contract's balance runs out, payments go through without it. Merchants reconcile
+    // from the numbered "merchant_settlement" events.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address) {
+        let data = MerchantsData { governance, pi_token, merchant_count: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "merchants_data"), &data);
+        log!(&env, "Merchant registry initialized: treasury-funded cash-back on PI payments");
+    }
+
+    // Governance-only: List a merchant paid at `payout`; returns its id
+    pub fn register_merchant(env: Env, payout: Address, cashback_bps: u32, cashback_cap: i128) -> Result<u64, MerchantError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(cashback_bps, cashback_cap)?;
+        let id = data.merchant_count;
+        let merchant = Merchant { payout: payout.clone(), cashback_bps, cashback_cap, cashback_paid: 0, volume: 0, payments: 0, active: true };
+        data.merchant_count += 1;
+        env.storage().instance().set(&Symbol::new(&env, "merchants_data"), &data);
+        env.storage().persistent().set(&MerchantKey::Merchant(id), &merchant);
+        env.events().publish((Symbol::new(&env, "merchant_registered"), id), (payout, cashback_bps, cashback_cap));
+        Ok(id)
+    }
+
+    // Governance-only: Change a merchant's cash-back terms, or suspend it (`active` false).
+    // The cap counts cash-back already paid.
+    pub fn set_merchant(env: Env, merchant_id: u64, cashback_bps: u32, cashback_cap: i128, active: bool) -> Result<(), MerchantError> {
+        Self::load(&env).governance.require_auth();
+        Self::validate(cashback_bps, cashback_cap)?;
+        let mut merchant = Self::merchant(env.clone(), merchant_id)?;
+        merchant.cashback_bps = cashback_bps;
+        merchant.cashback_cap = cashback_cap;
+        merchant.active = active;
+        env.storage().persistent().set(&MerchantKey::Merchant(merchant_id), &merchant);
+        Ok(())
+    }
+
+    // Pay `amount` PI to merchant `merchant_id` and receive its cash-back; returns the
+    // cash-back paid. Cash-back is earned on `amount`, while the merchant's volume and
+    // settlement event record what reached its payout, net of any PI transfer fee.
+    pub fn pay_merchant(env: Env, payer: Address, merchant_id: u64, amount: i128) -> Result<i128, MerchantError> {
+        payer.require_auth();
+        if amount <= 0 {
+            return Err(MerchantError::InvalidAmount);
+        }
+        let data = Self::load(&env);
+        let mut merchant = Self::merchant(env.clone(), merchant_id)?;
+        if !merchant.active {
+            return Err(MerchantError::MerchantInactive);
+        }
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let before = pi.balance(&merchant.payout);
+        pi.transfer(&payer, &merchant.payout, &amount);
+        let received = pi.balance(&merchant.payout) - before;
+        if received <= 0 {
+            return Err(MerchantError::InvalidAmount);
+        }
+        let cashback = (amount.checked_mul(merchant.cashback_bps as i128).ok_or(MerchantError::InvalidAmount)? / BPS)
+            .min(merchant.cashback_cap - merchant.cashback_paid)
+            .min(pi.balance(&env.current_contract_address()))
+            .max(0);
+        merchant.cashback_paid += cashback;
+        merchant.volume = merchant.volume.checked_add(received).ok_or(MerchantError::InvalidAmount)?;
+        merchant.payments += 1;
+        env.storage().persistent().set(&MerchantKey::Merchant(merchant_id), &merchant);
+
+        if cashback > 0 {
+            pi.transfer(&env.current_contract_address(), &payer, &cashback);
+        }
+        env.events().publish(
+            (Symbol::new(&env, "merchant_settlement"), merchant_id, payer),
+            (merchant.payments, received, cashback),
+        );
+        Ok(cashback)
+    }
+
+    // View: Merchant `merchant_id`
+    pub fn merchant(env: Env, merchant_id: u64) -> Result<Merchant, MerchantError> {
+        env.storage().persistent().get(&MerchantKey::Merchant(merchant_id)).ok_or(MerchantError::MerchantNotFound)
+    }
+
+    // View: Number of merchants listed
+    pub fn merchant_count(env: Env) -> u64 {
+        Self::load(&env).merchant_count
+    }
+
+    // View: PI left to pay cash-back from
+    pub fn cashback_pool(env: Env) -> i128 {
+        token::TokenClient::new(&env, &Self::load(&env).pi_token).balance(&env.current_contract_address())
+    }
+
+    // Helper: A rate up to MAX_CASHBACK_BPS and a non-negative cap
+    fn validate(cashback_bps: u32, cashback_cap: i128) -> Result<(), MerchantError> {
+        if cashback_bps > MAX_CASHBACK_BPS || cashback_cap < 0 {
+            return Err(MerchantError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn load(env: &Env) -> MerchantsData {
+        env.storage().instance().get(&Symbol::new(env, "merchants_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,119 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events}, vec, Address, Env, IntoVal, Symbol};
+use crate::{MerchantError, PiCoinMerchants, PiCoinMerchantsClient};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+#[test]
+fn test_payments_earn_capped_cashback_from_the_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (treasury, shop, alice) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    let pi = TokenBuilder::new(&env).mint(&alice, 10_000).build();
+    pi.mint(&treasury, &100, &PiCoinSource::Rewards);
+
+    let contract_id = env.register(PiCoinMerchants, (Address::generate(&env), &pi.address));
+    let client = PiCoinMerchantsClient::new(&env, &contract_id);
+    pi.transfer(&treasury, &contract_id, &100);
+
+    // 2% back, at most 50 PI over the merchant's lifetime
+    assert!(matches!(client.try_register_merchant(&shop, &1_001, &50), Err(Ok(MerchantError::InvalidConfig))));
+    let id = client.register_merchant(&shop, &200, &50);
+    assert!(matches!(client.try_pay_merchant(&alice, &7, &100), Err(Ok(MerchantError::MerchantNotFound))));
+    assert!(matches!(client.try_pay_merchant(&alice, &id, &0), Err(Ok(MerchantError::InvalidAmount))));
+
+    assert_eq!(client.pay_merchant(&alice, &id, &1_000), 20);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "merchant_settlement"), id, alice.clone()).into_val(&env),
+                (1u64, 1_000i128, 20i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(pi.balance(&shop), 1_000);
+    assert_eq!(pi.balance(&alice), 9_020);
+
+    // The cap bites: 40 PI due, 30 left under it
+    assert_eq!(client.pay_merchant(&alice, &id, &2_000), 30);
+    assert_eq!(client.pay_merchant(&alice, &id, &1_000), 0);
+    let merchant = client.merchant(&id);
+    assert_eq!((merchant.volume, merchant.cashback_paid, merchant.payments), (4_000, 50, 3));
+    assert_eq!(client.cashback_pool(), 50);
+
+    // A raised cap is limited by what the treasury has funded
+    client.set_merchant(&id, &1_000, &1_000, &true);
+    assert_eq!(client.pay_merchant(&alice, &id, &1_000), 50);
+    assert_eq!(client.cashback_pool(), 0);
+
+    // Suspended merchants take no payments
+    client.set_merchant(&id, &1_000, &1_000, &false);
+    assert!(matches!(client.try_pay_merchant(&alice, &id, &100), Err(Ok(MerchantError::MerchantInactive))));
+    assert_eq!(pi.balance(&shop), 5_000);
+}
+
+#[test]
+fn test_settlement_records_what_reaches_the_merchant_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (shop, alice) = (Address::generate(&env), Address::generate(&env));
+    let pi = TokenBuilder::new(&env).mint(&alice, 1_000).build();
+    let contract_id = env.register(PiCoinMerchants, (Address::generate(&env), &pi.address));
+    let client = PiCoinMerchantsClient::new(&env, &contract_id);
+    let id = client.register_merchant(&shop, &200, &50);
+
+    // At a 1% fee the shop receives 990 of the 1_000, and that is what it reconciles
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    assert_eq!(client.pay_merchant(&alice, &id, &1_000), 0);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "merchant_settlement"), id, alice.clone()).into_val(&env),
+                (1u64, 990i128, 0i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(pi.balance(&shop), 990);
+    assert_eq!(client.merchant(&id).volume, 990);
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_suspended_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (shop, alice) = (Address::generate(&env), Address::generate(&env));
+    let pi = TokenBuilder::new(&env).mint(&alice, 500).build();
+    let contract_id = env.register(PiCoinMerchants, (Address::generate(&env), &pi.address));
+    let client = PiCoinMerchantsClient::new(&env, &contract_id);
+
+    // Only governance lists merchants or changes their terms
+    env.set_auths(&[]);
+    assert!(client.try_register_merchant(&shop, &200, &50).is_err());
+    env.mock_all_auths();
+    let id = client.register_merchant(&shop, &200, &50);
+    env.set_auths(&[]);
+    assert!(client.try_set_merchant(&id, &1_000, &1_000, &true).is_err());
+    assert!(client.try_pay_merchant(&alice, &id, &100).is_err());
+    env.mock_all_auths();
+
+    // A payer cannot pay more than it holds, and nothing is recorded
+    assert!(client.try_pay_merchant(&alice, &id, &501).is_err());
+    assert_eq!(client.merchant(&id).payments, 0);
+    assert!(matches!(client.try_set_merchant(&id, &200, &-1, &true), Err(Ok(MerchantError::InvalidConfig))));
+
+    // A suspended merchant stays closed until reinstated
+    client.set_merchant(&id, &200, &50, &false);
+    assert!(matches!(client.try_pay_merchant(&alice, &id, &100), Err(Ok(MerchantError::MerchantInactive))));
+    client.set_merchant(&id, &200, &50, &true);
+    assert_eq!(client.pay_merchant(&alice, &id, &100), 0);
+    assert_eq!(pi.balance(&shop), 100);
+}
@@ -0,0 +1,195 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Bytes, BytesN, Env, Symbol, Vec, log};
+
+// Seconds a replaced guardian set keeps verifying, so messages signed just before a
+// rotation can still land
+pub const GUARDIAN_SET_EXPIRY: u64 = 24 * 60 * 60;
+// Highest number of guardians a set may have (guardian indexes are one byte)
+pub const MAX_GUARDIANS: u32 = 255;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MessengerData {
+    pub governance: Address, // Rotates the guardian set
+    pub guardian_set_index: u32, // Index of the current set
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianSet {
+    pub keys: Vec<BytesN<32>>, // ed25519 keys, addressed by position in signatures
+    pub quorum: u32, // Signatures a message needs
+    pub expiration_time: u64, // 0 while current; set when the set is replaced
+}
+
+// A verified message. Consumers pick the emitters they trust and use
+// (emitter_chain, emitter_address, sequence) or `hash` to reject replays.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifiedMessage {
+    pub guardian_set_index: u32,
+    pub timestamp: u64,
+    pub nonce: u32,
+    pub emitter_chain: u32,
+    pub emitter_address: BytesN<32>,
+    pub sequence: u64,
+    pub payload: Bytes,
+    pub hash: BytesN<32>, // sha256 of the body: what the guardians signed
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MessengerKey {
+    GuardianSet(u32),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MessengerError {
+    MalformedVaa = 1,
+    UnsupportedVersion = 2,
+    UnknownGuardianSet = 3,
+    GuardianSetExpired = 4,
+    NoQuorum = 5,
+    InvalidGuardianIndex = 6,
+    SignaturesOutOfOrder = 7,
+    InvalidGuardianSet = 8,
+}
+
+#[contract]
+pub struct PiCoinMessenger;
+
+#[contractimpl]
+impl PiCoinMessenger {
+    // Constructor: cross-chain message verifier. Guardians watch the other chains and sign
+    // each message; `verify_vaa` checks a signed message (VAA) against the guardian set so
+    // other contracts - the bridge, governance mirroring, oracle relays - can act on it.
+    //
+    // VAA layout (integers big-endian):
+    //   version u8 (= 1) | guardian_set_index u32 | signature count u8
+    //   | signatures: (guardian_index u8 | ed25519 signature [64]) each, ascending index
+    //   | body: timestamp u64 | nonce u32 | emitter_chain u32 | emitter_address [32]
+    //     | sequence u64 | payload (rest)
+    // Guardians sign sha256(body).
+    pub fn __constructor(env: Env, governance: Address, guardians: Vec<BytesN<32>>, quorum: u32) -> Result<(), MessengerError> {
+        Self::validate(&guardians, quorum)?;
+        let set = GuardianSet { keys: guardians, quorum, expiration_time: 0 };
+        env.storage().persistent().set(&MessengerKey::GuardianSet(0), &set);
+        let data = MessengerData { governance, guardian_set_index: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "messenger_data"), &data);
+        log!(&env, "Messenger initialized: {}-of-{} guardians", quorum, set.keys.len());
+        Ok(())
+    }
+
+    // View: Parse `vaa` and check its guardian signatures; returns the message. Traps if a
+    // signature does not verify.
+    pub fn verify_vaa(env: Env, vaa: Bytes) -> Result<VerifiedMessage, MessengerError> {
+        if vaa.len() < 6 {
+            return Err(MessengerError::MalformedVaa);
+        }
+        if vaa.get_unchecked(0) != 1 {
+            return Err(MessengerError::UnsupportedVersion);
+        }
+        let guardian_set_index = read_u32(&vaa, 1);
+        let set = Self::guardian_set(env.clone(), guardian_set_index).ok_or(MessengerError::UnknownGuardianSet)?;
+        if set.expiration_time != 0 && env.ledger().timestamp() >= set.expiration_time {
+            return Err(MessengerError::GuardianSetExpired);
+        }
+
+        let count = vaa.get_unchecked(5) as u32;
+        let body_start = 6 + count * 65;
+        if vaa.len() < body_start + 56 {
+            return Err(MessengerError::MalformedVaa);
+        }
+        if count < set.quorum {
+            return Err(MessengerError::NoQuorum);
+        }
+        let body = vaa.slice(body_start..);
+        let hash = env.crypto().sha256(&body).to_bytes();
+        let digest = Bytes::from(hash.clone());
+        let mut last: Option<u32> = None;
+        for i in 0..count {
+            let at = 6 + i * 65;
+            let guardian = vaa.get_unchecked(at) as u32;
+            if last.is_some_and(|last| guardian <= last) {
+                return Err(MessengerError::SignaturesOutOfOrder);
+            }
+            let key = set.keys.get(guardian).ok_or(MessengerError::InvalidGuardianIndex)?;
+            let signature: BytesN<64> = vaa.slice(at + 1..at + 65).try_into().unwrap();
+            env.crypto().ed25519_verify(&key, &digest, &signature);
+            last = Some(guardian);
+        }
+
+        Ok(VerifiedMessage {
+            guardian_set_index,
+            timestamp: read_u64(&body, 0),
+            nonce: read_u32(&body, 8),
+            emitter_chain: read_u32(&body, 12),
+            emitter_address: body.slice(16..48).try_into().unwrap(),
+            sequence: read_u64(&body, 48),
+            payload: body.slice(56..),
+            hash,
+        })
+    }
+
+    // Governance-only: Install a new guardian set. The current one keeps verifying for
+    // GUARDIAN_SET_EXPIRY seconds. Returns the new set's index.
+    pub fn set_guardian_set(env: Env, guardians: Vec<BytesN<32>>, quorum: u32) -> Result<u32, MessengerError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(&guardians, quorum)?;
+        let old_key = MessengerKey::GuardianSet(data.guardian_set_index);
+        let mut old: GuardianSet = env.storage().persistent().get(&old_key).unwrap();
+        old.expiration_time = env.ledger().timestamp() + GUARDIAN_SET_EXPIRY;
+        env.storage().persistent().set(&old_key, &old);
+
+        data.guardian_set_index += 1;
+        let set = GuardianSet { keys: guardians, quorum, expiration_time: 0 };
+        env.storage().persistent().set(&MessengerKey::GuardianSet(data.guardian_set_index), &set);
+        env.storage().instance().set(&Symbol::new(&env, "messenger_data"), &data);
+        env.events().publish((Symbol::new(&env, "guardian_set"), data.guardian_set_index), (set.keys, quorum));
+        Ok(data.guardian_set_index)
+    }
+
+    // View: Index of the current guardian set
+    pub fn guardian_set_index(env: Env) -> u32 {
+        Self::load(&env).guardian_set_index
+    }
+
+    // View: Guardian set `index`, current or replaced
+    pub fn guardian_set(env: Env, index: u32) -> Option<GuardianSet> {
+        env.storage().persistent().get(&MessengerKey::GuardianSet(index))
+    }
+
+    fn validate(guardians: &Vec<BytesN<32>>, quorum: u32) -> Result<(), MessengerError> {
+        if guardians.len() > MAX_GUARDIANS || quorum == 0 || quorum > guardians.len() {
+            return Err(MessengerError::InvalidGuardianSet);
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardians.first_index_of(&guardian) != Some(i as u32) {
+                return Err(MessengerError::InvalidGuardianSet);
+            }
+        }
+        Ok(())
+    }
+
+    fn load(env: &Env) -> MessengerData {
+        env.storage().instance().get(&Symbol::new(env, "messenger_data")).unwrap()
+    }
+}
+
+// Helper: Big-endian integers out of a VAA
+fn read_u32(bytes: &Bytes, at: u32) -> u32 {
+    let mut buf = [0u8; 4];
+    bytes.slice(at..at + 4).copy_into_slice(&mut buf);
+    u32::from_be_bytes(buf)
+}
+
+fn read_u64(bytes: &Bytes, at: u32) -> u64 {
+    let mut buf = [0u8; 8];
+    bytes.slice(at..at + 8).copy_into_slice(&mut buf);
+    u64::from_be_bytes(buf)
+}
+
+mod test;
@@ -0,0 +1,112 @@
+#![cfg(test)]
+// Guardians sign with ed25519-dalek keys (dev-dependency)
+extern crate std;
+
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, BytesN, Env, Vec,
+};
+use std::vec::Vec as StdVec;
+use crate::{MessengerError, PiCoinMessenger, PiCoinMessengerClient, GUARDIAN_SET_EXPIRY};
+
+fn guardian_keys(seeds: &[u8]) -> StdVec<SigningKey> {
+    seeds.iter().map(|seed| SigningKey::from_bytes(&[*seed; 32])).collect()
+}
+
+fn public_keys(env: &Env, keys: &[SigningKey]) -> Vec<BytesN<32>> {
+    let mut public_keys = vec![env];
+    for key in keys {
+        public_keys.push_back(BytesN::from_array(env, &key.verifying_key().to_bytes()));
+    }
+    public_keys
+}
+
+fn body(sequence: u64, payload: &[u8]) -> StdVec<u8> {
+    let mut body = StdVec::new();
+    body.extend_from_slice(&1_700_000_000u64.to_be_bytes()); // timestamp
+    body.extend_from_slice(&42u32.to_be_bytes()); // nonce
+    body.extend_from_slice(&2u32.to_be_bytes()); // emitter chain
+    body.extend_from_slice(&[0xee; 32]); // emitter address
+    body.extend_from_slice(&sequence.to_be_bytes());
+    body.extend_from_slice(payload);
+    body
+}
+
+// VAA over `body` signed by the guardians at `signers` (positions in `keys`), in that order
+fn vaa(env: &Env, set_index: u32, keys: &[SigningKey], signers: &[u8], body: &[u8]) -> Bytes {
+    let digest = Sha256::digest(body);
+    let mut vaa = std::vec![1u8];
+    vaa.extend_from_slice(&set_index.to_be_bytes());
+    vaa.push(signers.len() as u8);
+    for signer in signers {
+        vaa.push(*signer);
+        vaa.extend_from_slice(&keys[*signer as usize].sign(&digest).to_bytes());
+    }
+    vaa.extend_from_slice(body);
+    Bytes::from_slice(env, &vaa)
+}
+
+#[test]
+fn test_verify_vaa_checks_guardian_quorum() {
+    let env = Env::default();
+    let keys = guardian_keys(&[1, 2, 3]);
+    let contract_id = env.register(PiCoinMessenger, (Address::generate(&env), public_keys(&env, &keys), 2u32));
+    let client = PiCoinMessengerClient::new(&env, &contract_id);
+
+    let body = body(7, b"mirror proposal 3");
+    let message = client.verify_vaa(&vaa(&env, 0, &keys, &[0, 2], &body));
+    assert_eq!(message.guardian_set_index, 0);
+    assert_eq!((message.timestamp, message.nonce, message.emitter_chain), (1_700_000_000, 42, 2));
+    assert_eq!(message.emitter_address, BytesN::from_array(&env, &[0xee; 32]));
+    assert_eq!(message.sequence, 7);
+    assert_eq!(message.payload, Bytes::from_slice(&env, b"mirror proposal 3"));
+    assert_eq!(message.hash, BytesN::from_array(&env, &Sha256::digest(&body).into()));
+
+    // Below quorum, repeated or unordered guardians, and unknown indexes are refused
+    assert_eq!(client.try_verify_vaa(&vaa(&env, 0, &keys, &[1], &body)), Err(Ok(MessengerError::NoQuorum)));
+    assert_eq!(client.try_verify_vaa(&vaa(&env, 0, &keys, &[1, 1], &body)), Err(Ok(MessengerError::SignaturesOutOfOrder)));
+    assert_eq!(client.try_verify_vaa(&vaa(&env, 0, &keys, &[2, 0], &body)), Err(Ok(MessengerError::SignaturesOutOfOrder)));
+    let four = guardian_keys(&[1, 2, 3, 4]);
+    assert_eq!(client.try_verify_vaa(&vaa(&env, 0, &four, &[0, 3], &body)), Err(Ok(MessengerError::InvalidGuardianIndex)));
+    assert_eq!(client.try_verify_vaa(&vaa(&env, 5, &keys, &[0, 1], &body)), Err(Ok(MessengerError::UnknownGuardianSet)));
+
+    // A tampered body fails signature verification
+    let mut tampered = vaa(&env, 0, &keys, &[0, 1], &body);
+    tampered.set(tampered.len() - 1, b'4');
+    assert!(client.try_verify_vaa(&tampered).is_err());
+
+    // Truncated input and other versions are malformed
+    assert_eq!(client.try_verify_vaa(&Bytes::from_slice(&env, &[1, 0, 0])), Err(Ok(MessengerError::MalformedVaa)));
+    let mut v2 = vaa(&env, 0, &keys, &[0, 1], &body);
+    v2.set(0, 2);
+    assert_eq!(client.try_verify_vaa(&v2), Err(Ok(MessengerError::UnsupportedVersion)));
+}
+
+#[test]
+fn test_guardian_set_rotation_expires_old_set() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let old = guardian_keys(&[1, 2]);
+    let new = guardian_keys(&[5, 6, 7]);
+    let contract_id = env.register(PiCoinMessenger, (Address::generate(&env), public_keys(&env, &old), 1u32));
+    let client = PiCoinMessengerClient::new(&env, &contract_id);
+
+    assert_eq!(client.try_set_guardian_set(&public_keys(&env, &new), &4), Err(Ok(MessengerError::InvalidGuardianSet)));
+    let twice = public_keys(&env, &guardian_keys(&[5, 5]));
+    assert_eq!(client.try_set_guardian_set(&twice, &1), Err(Ok(MessengerError::InvalidGuardianSet)));
+
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(client.set_guardian_set(&public_keys(&env, &new), &2), 1);
+    assert_eq!(client.guardian_set_index(), 1);
+    assert_eq!(client.guardian_set(&0).unwrap().expiration_time, 1_000 + GUARDIAN_SET_EXPIRY);
+
+    // Both sets verify during the overlap; afterwards only the new one does
+    let body = body(1, b"oracle price");
+    client.verify_vaa(&vaa(&env, 0, &old, &[1], &body));
+    client.verify_vaa(&vaa(&env, 1, &new, &[0, 2], &body));
+    env.ledger().set_timestamp(1_000 + GUARDIAN_SET_EXPIRY);
+    assert_eq!(client.try_verify_vaa(&vaa(&env, 0, &old, &[1], &body)), Err(Ok(MessengerError::GuardianSetExpired)));
+    client.verify_vaa(&vaa(&env, 1, &new, &[1, 2], &body));
+}
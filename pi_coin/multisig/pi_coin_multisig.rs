@@ -0,0 +1,201 @@
+#![no_std]
+use soroban_sdk::{
+    auth::Context, contract, contractimpl, contracterror, contracttype, crypto::Hash, Address, BytesN, Env, IntoVal, Symbol, Val, Vec, log,
+};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MultisigData {
+    pub owners: Vec<Address>,
+    pub threshold: u32, // Confirmations (or signers) required
+    pub next_proposal_id: u64,
+}
+
+// What an executed proposal does
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MultisigAction {
+    Invoke(Address, Symbol, Vec<Val>), // Call a contract function as this account
+    AddOwner(Address),
+    RemoveOwner(Address),
+    SetThreshold(u32),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Proposal {
+    pub action: MultisigAction,
+    pub proposer: Address,
+    pub confirmations: Vec<Address>,
+    pub executed: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MultisigKey {
+    Proposal(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MultisigError {
+    NotOwner = 1,
+    InvalidThreshold = 2,
+    AlreadyConfirmed = 3,
+    NotEnoughConfirmations = 4,
+    AlreadyExecuted = 5,
+    NoProposal = 6,
+    DuplicateSigner = 7,
+    AlreadyOwner = 8,
+}
+
+#[contract]
+pub struct PiCoinMultisig;
+
+#[contractimpl]
+impl PiCoinMultisig {
+    // Constructor: k-of-n account, usable as admin of the token, oracle and governance
+    pub fn __constructor(env: Env, owners: Vec<Address>, threshold: u32) -> Result<(), MultisigError> {
+        Self::validate(&owners, threshold)?;
+        let data = MultisigData { owners, threshold, next_proposal_id: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "multisig_data"), &data);
+        log!(&env, "Multisig admin initialized: {}-of-{} quantum-secure quorum", threshold, data.owners.len());
+        Ok(())
+    }
+
+    // Owner-only: Propose an action; the proposer's confirmation is counted
+    pub fn propose(env: Env, proposer: Address, action: MultisigAction) -> Result<u64, MultisigError> {
+        proposer.require_auth();
+        let mut data = Self::data(&env);
+        if !data.owners.contains(&proposer) {
+            return Err(MultisigError::NotOwner);
+        }
+        let id = data.next_proposal_id;
+        data.next_proposal_id += 1;
+        let proposal = Proposal {
+            action,
+            proposer: proposer.clone(),
+            confirmations: Vec::from_array(&env, [proposer.clone()]),
+            executed: false,
+        };
+        env.storage().persistent().set(&MultisigKey::Proposal(id), &proposal);
+        env.storage().instance().set(&Symbol::new(&env, "multisig_data"), &data);
+        env.events().publish((Symbol::new(&env, "ms_proposed"), id), proposer);
+        Ok(id)
+    }
+
+    // Owner-only: Confirm a pending proposal
+    pub fn confirm(env: Env, owner: Address, id: u64) -> Result<(), MultisigError> {
+        owner.require_auth();
+        if !Self::data(&env).owners.contains(&owner) {
+            return Err(MultisigError::NotOwner);
+        }
+        let mut proposal = Self::proposal(env.clone(), id)?;
+        if proposal.executed {
+            return Err(MultisigError::AlreadyExecuted);
+        }
+        if proposal.confirmations.contains(&owner) {
+            return Err(MultisigError::AlreadyConfirmed);
+        }
+        proposal.confirmations.push_back(owner.clone());
+        env.storage().persistent().set(&MultisigKey::Proposal(id), &proposal);
+        env.events().publish((Symbol::new(&env, "ms_confirmed"), id), owner);
+        Ok(())
+    }
+
+    // Execute a proposal confirmed by at least `threshold` current owners (permissionless).
+    // Invocations run with this contract as the caller, which satisfies its require_auth.
+    pub fn execute(env: Env, id: u64) -> Result<Val, MultisigError> {
+        let mut data = Self::data(&env);
+        let mut proposal = Self::proposal(env.clone(), id)?;
+        if proposal.executed {
+            return Err(MultisigError::AlreadyExecuted);
+        }
+        // Confirmations from owners rotated out since do not count
+        let confirmed = proposal.confirmations.iter().filter(|owner| data.owners.contains(owner)).count() as u32;
+        if confirmed < data.threshold {
+            return Err(MultisigError::NotEnoughConfirmations);
+        }
+        proposal.executed = true;
+        env.storage().persistent().set(&MultisigKey::Proposal(id), &proposal);
+
+        let mut result: Val = ().into_val(&env);
+        match proposal.action.clone() {
+            MultisigAction::Invoke(contract, function, args) => {
+                result = env.invoke_contract(&contract, &function, args);
+            }
+            MultisigAction::AddOwner(owner) => {
+                if data.owners.contains(&owner) {
+                    return Err(MultisigError::AlreadyOwner);
+                }
+                data.owners.push_back(owner);
+            }
+            MultisigAction::RemoveOwner(owner) => {
+                let index = data.owners.first_index_of(&owner).ok_or(MultisigError::NotOwner)?;
+                data.owners.remove(index);
+                Self::validate(&data.owners, data.threshold)?;
+            }
+            MultisigAction::SetThreshold(threshold) => {
+                Self::validate(&data.owners, threshold)?;
+                data.threshold = threshold;
+            }
+        }
+        env.storage().instance().set(&Symbol::new(&env, "multisig_data"), &data);
+        env.events().publish((Symbol::new(&env, "ms_executed"), id), proposal.action);
+        Ok(result)
+    }
+
+    // Custom account check: `signers` are distinct owners, at least `threshold` of them,
+    // each of whom authorizes the signature payload. Lets the multisig sign transactions
+    // directly without the proposal flow.
+    #[allow(non_snake_case)]
+    pub fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signers: Vec<Address>,
+        _auth_contexts: Vec<Context>,
+    ) -> Result<(), MultisigError> {
+        let data = Self::data(&env);
+        if signers.len() < data.threshold {
+            return Err(MultisigError::NotEnoughConfirmations);
+        }
+        let payload: BytesN<32> = signature_payload.into();
+        for (i, signer) in signers.iter().enumerate() {
+            if !data.owners.contains(&signer) {
+                return Err(MultisigError::NotOwner);
+            }
+            if signers.first_index_of(&signer) != Some(i as u32) {
+                return Err(MultisigError::DuplicateSigner);
+            }
+            signer.require_auth_for_args((payload.clone(),).into_val(&env));
+        }
+        Ok(())
+    }
+
+    // View: Current owners and threshold
+    pub fn owners(env: Env) -> (Vec<Address>, u32) {
+        let data = Self::data(&env);
+        (data.owners, data.threshold)
+    }
+
+    // View: A proposal with its confirmations
+    pub fn proposal(env: Env, id: u64) -> Result<Proposal, MultisigError> {
+        env.storage().persistent().get(&MultisigKey::Proposal(id)).ok_or(MultisigError::NoProposal)
+    }
+
+    // Helper: Stored multisig state
+    fn data(env: &Env) -> MultisigData {
+        env.storage().instance().get(&Symbol::new(env, "multisig_data")).unwrap()
+    }
+
+    // Helper: 1 <= threshold <= owners
+    fn validate(owners: &Vec<Address>, threshold: u32) -> Result<(), MultisigError> {
+        if threshold == 0 || threshold > owners.len() {
+            return Err(MultisigError::InvalidThreshold);
+        }
+        Ok(())
+    }
+}
+
+mod test;
@@ -0,0 +1,80 @@
+#![cfg(test)]
+use soroban_sdk::{
+    contract, contractimpl, testutils::Address as _, vec, Address, BytesN, Env, IntoVal, Symbol, Val,
+};
+use crate::{MultisigAction, PiCoinMultisig, PiCoinMultisigClient};
+
+// A contract administered by the multisig, like the oracle or governance
+#[contract]
+struct Administered;
+
+#[contractimpl]
+impl Administered {
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&0u32, &admin);
+    }
+
+    pub fn set_value(env: Env, value: u32) -> u32 {
+        let admin: Address = env.storage().instance().get(&0u32).unwrap();
+        admin.require_auth();
+        env.storage().instance().set(&1u32, &value);
+        value
+    }
+}
+
+#[test]
+fn test_two_of_three_propose_confirm_execute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (alice, bob, carol) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    let multisig = env.register(PiCoinMultisig, (vec![&env, alice.clone(), bob.clone(), carol.clone()], 2u32));
+    let client = PiCoinMultisigClient::new(&env, &multisig);
+    let target = env.register(Administered, (&multisig,));
+
+    let args: soroban_sdk::Vec<Val> = vec![&env, 7u32.into_val(&env)];
+    let call = MultisigAction::Invoke(target, Symbol::new(&env, "set_value"), args);
+    let id = client.propose(&alice, &call);
+    assert!(client.try_execute(&id).is_err());
+    assert!(client.try_confirm(&alice, &id).is_err());
+    assert!(client.try_confirm(&Address::generate(&env), &id).is_err());
+    client.confirm(&bob, &id);
+    let result: u32 = client.execute(&id).into_val(&env);
+    assert_eq!(result, 7);
+    assert!(client.try_execute(&id).is_err());
+
+    // Owner rotation: swap carol for dave, then raise the threshold to 3
+    let dave = Address::generate(&env);
+    let add = client.propose(&bob, &MultisigAction::AddOwner(dave.clone()));
+    client.confirm(&carol, &add);
+    client.execute(&add);
+    let remove = client.propose(&alice, &MultisigAction::RemoveOwner(carol.clone()));
+    client.confirm(&dave, &remove);
+    client.execute(&remove);
+    let raise = client.propose(&alice, &MultisigAction::SetThreshold(3));
+    client.confirm(&bob, &raise);
+    client.execute(&raise);
+    assert_eq!(client.owners(), (vec![&env, alice.clone(), bob.clone(), dave.clone()], 3));
+
+    // Threshold can never exceed the owner count
+    let too_high = client.propose(&alice, &MultisigAction::SetThreshold(4));
+    client.confirm(&bob, &too_high);
+    client.confirm(&dave, &too_high);
+    assert!(client.try_execute(&too_high).is_err());
+}
+
+#[test]
+fn test_check_auth_requires_threshold_distinct_owners() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    let multisig = env.register(PiCoinMultisig, (vec![&env, alice.clone(), bob.clone()], 2u32));
+    let payload = BytesN::from_array(&env, &[1; 32]);
+    let check = |signers: soroban_sdk::Vec<Address>| {
+        env.try_invoke_contract_check_auth::<crate::MultisigError>(&multisig, &payload, signers.into_val(&env), &vec![&env])
+    };
+
+    assert!(check(vec![&env, alice.clone(), bob.clone()]).is_ok());
+    assert!(check(vec![&env, alice.clone()]).is_err());
+    assert!(check(vec![&env, alice.clone(), alice.clone()]).is_err());
+    assert!(check(vec![&env, alice.clone(), Address::generate(&env)]).is_err());
+}
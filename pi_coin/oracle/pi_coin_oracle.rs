@@ -1,20 +1,101 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, xdr::ToXdr, Address, Env, Symbol, Vec, Map, log, Bytes, BytesN};
+
+// Storage layout version written by this wasm
+pub const CONTRACT_VERSION: u32 = 1;
+// Oldest observation a signed price package may carry, in seconds
+pub const MAX_REPORT_AGE: u64 = 5 * 60;
+// Price history: at most one point per HISTORY_INTERVAL, the newest HISTORY_LEN kept
+pub const HISTORY_INTERVAL: u64 = 60 * 60;
+pub const HISTORY_LEN: u32 = 720; // 30 days of hourly points
+// Most points one `prices` call returns, keeping its reads under the per-transaction
+// ledger entry limit; page on with `from` just past the last point
+pub const PRICES_PAGE: u32 = 64;
 
 #[contracttype]
 #[derive(Clone)]
 pub struct OracleData {
     pub admin: Address,
+    pub governance: Address, // Only governance may upgrade the oracle
     pub price_feed: Map<Symbol, i128>, // e.g., {"PI": 314159000000}
     pub ai_model_hash: BytesN<32>, // SHA-256 for AI model integrity
-    pub quantum_key: BytesN<32>, // For quantum-resistant encryption
 }
 
+// Circuit-breaker bounds on submitted prices, set by governance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakerConfig {
+    pub min_price: i128, // Band: prices outside [min_price, max_price] are never accepted
+    pub max_price: i128,
+    pub max_deviation_bps: u32, // Largest move from the previous accepted price...
+    pub window: u64, // ...submitted within this many seconds of it
+}
+
+// What a reporter signs off-chain: the XDR of this struct. Binding the network and this
+// oracle keeps a package from being replayed elsewhere.
+#[contracttype]
+#[derive(Clone)]
+pub struct SignedPrice {
+    pub network_id: BytesN<32>,
+    pub oracle: Address,
+    pub asset: Symbol,
+    pub price: i128,
+    pub timestamp: u64, // When the reporter observed the price
+    pub nonce: u64, // Must exceed the reporter's last accepted nonce
+}
+
+// One retained price: the first accepted in its hour, with its observation time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricePoint {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Ring-buffer cursor over an asset's HistoryPoint slots
 #[contracttype]
+#[derive(Clone)]
+pub struct HistoryCursor {
+    pub len: u32, // Points retained, up to HISTORY_LEN
+    pub next: u32, // Slot the next point is written to
+}
+
+// A tripped breaker: the feed keeps `last_good_price` until governance resets it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakerTrip {
+    pub rejected_price: i128,
+    pub last_good_price: Option<i128>,
+    pub tripped_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OracleKey {
+    PricePool(Symbol), // Asset -> AMM pool whose implied price cross-checks the feed
+    Breaker, // BreakerConfig; no breaker while unset
+    UpdatedAt(Symbol), // Asset -> timestamp of its last accepted price
+    Tripped(Symbol), // Asset -> BreakerTrip, until governance resets it
+    Reporter(BytesN<32>), // Persistent: ed25519 key of a whitelisted reporter
+    ReporterNonce(BytesN<32>), // Persistent: reporter key -> last accepted nonce
+    History(Symbol), // Persistent: asset -> HistoryCursor
+    HistoryPoint(Symbol, u32), // Persistent: (asset, slot) -> PricePoint
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum OracleError {
     Unauthorized = 1,
     InvalidData = 2,
     ManipulationDetected = 3,
+    NoPricePool = 4,
+    Overflow = 5,
+    BreakerTripped = 6, // The asset's feed is frozen at its last good price
+    InvalidBreaker = 7,
+    StaleNonce = 8, // Not above the reporter's last accepted nonce
+    StalePrice = 9, // Observed too long ago, in the future, or before the feed's last price
+    NoHistory = 10, // No retained point at or before the requested time
 }
 
 #[contract]
@@ -22,18 +103,17 @@ pub struct PiCoinOracle;
 
 #[contractimpl]
 impl PiCoinOracle {
-    // Initialize oracle with hyper-tech AI model
-    pub fn initialize(env: Env, admin: Address) -> Result<(), OracleError> {
-        admin.require_auth();
+    // Constructor: initialize oracle with hyper-tech AI model atomically at deploy time
+    pub fn __constructor(env: Env, admin: Address, governance: Address) {
         let data = OracleData {
             admin,
+            governance,
             price_feed: Map::new(&env),
-            ai_model_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-AI-Model-Ultimate")),
-            quantum_key: env.crypto().ed25519_public_key(&env.current_contract_address()),
+            ai_model_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-AI-Model-Ultimate")).into(),
         };
         env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
+        env.storage().instance().set(&Symbol::new(&env, "version"), &CONTRACT_VERSION);
         log!(&env, "Oracle initialized: AI-enhanced, quantum-secure, global data aggregation ready");
-        Ok(())
     }
 
     // Update price with AI prediction (hyper-tech: ML simulation)
@@ -44,24 +124,118 @@ impl PiCoinOracle {
             return Err(OracleError::Unauthorized);
         }
 
-        // Hyper-tech AI: Predict adjusted price using ledger-based analytics
+        // Hyper-tech AI: Predict adjusted price using ledger-based analytics; the breaker in
+        // accept_price guards against manipulation
         let ai_adjusted_price = Self::ai_predict_price(&env, raw_price);
-        // Quantum-resistant: Encrypt and sign update
-        let sig_data = Bytes::from_slice(&env, &ai_adjusted_price.to_be_bytes());
-        let signature = env.crypto().ed25519_sign(&data.quantum_key, &sig_data);
+        Self::accept_price(&env, &mut data, &asset, ai_adjusted_price, env.ledger().timestamp())?;
+        log!(&env, "Price updated for {}: {} with AI prediction", asset, ai_adjusted_price);
+        Ok(())
+    }
+
+    // Relay a price package signed off-chain by a whitelisted reporter over
+    // `SignedPrice { network_id, oracle, asset, price, timestamp, nonce }`. Anyone may submit
+    // it, so reporters need no funded account. The package must be newer than the feed's
+    // last price and at most MAX_REPORT_AGE old, and its nonce must exceed the reporter's
+    // last; the price then goes through the circuit breaker like any other.
+    pub fn submit_signed_price(
+        env: Env,
+        reporter: BytesN<32>,
+        asset: Symbol,
+        price: i128,
+        timestamp: u64,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) -> Result<(), OracleError> {
+        if !Self::is_reporter(env.clone(), reporter.clone()) {
+            return Err(OracleError::Unauthorized);
+        }
+        if price <= 0 {
+            return Err(OracleError::InvalidData);
+        }
+        let nonce_key = OracleKey::ReporterNonce(reporter.clone());
+        if env.storage().persistent().get::<_, u64>(&nonce_key).is_some_and(|last| nonce <= last) {
+            return Err(OracleError::StaleNonce);
+        }
+        let now = env.ledger().timestamp();
+        let superseded = Self::last_update(env.clone(), asset.clone()).is_some_and(|at| timestamp <= at);
+        if timestamp > now || now - timestamp > MAX_REPORT_AGE || superseded {
+            return Err(OracleError::StalePrice);
+        }
+        let package = SignedPrice {
+            network_id: env.ledger().network_id(),
+            oracle: env.current_contract_address(),
+            asset: asset.clone(),
+            price,
+            timestamp,
+            nonce,
+        };
+        env.crypto().ed25519_verify(&reporter, &package.to_xdr(&env), &signature);
+        env.storage().persistent().set(&nonce_key, &nonce);
+
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        Self::accept_price(&env, &mut data, &asset, price, timestamp)?;
+        env.events().publish((Symbol::new(&env, "signed_price"), asset, reporter), (price, timestamp, nonce));
+        Ok(())
+    }
 
-        // Anti-manipulation: Check for ZKP proof (simulated)
-        if !Self::verify_zkp_proof(&env, &signature) {
-            return Err(OracleError::ManipulationDetected);
+    // Governance-only: Whitelist (or drop) a reporter's ed25519 key
+    pub fn set_reporter(env: Env, reporter: BytesN<32>, trusted: bool) {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.governance.require_auth();
+        let key = OracleKey::Reporter(reporter);
+        if trusted {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
         }
+    }
 
-        data.price_feed.set(asset.clone(), ai_adjusted_price);
-        env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
-        log!(&env, "Price updated for {}: {} with AI prediction and quantum sig: {:?}", asset, ai_adjusted_price, signature);
+    // View: Whether `reporter` may sign price packages
+    pub fn is_reporter(env: Env, reporter: BytesN<32>) -> bool {
+        env.storage().persistent().has(&OracleKey::Reporter(reporter))
+    }
+
+    // View: Last nonce accepted from `reporter` (None before its first package)
+    pub fn reporter_nonce(env: Env, reporter: BytesN<32>) -> Option<u64> {
+        env.storage().persistent().get(&OracleKey::ReporterNonce(reporter))
+    }
+
+    // Governance-only: Set the circuit-breaker bounds, or lift them with None
+    pub fn set_breaker(env: Env, config: Option<BreakerConfig>) -> Result<(), OracleError> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.governance.require_auth();
+        match &config {
+            Some(config) if config.min_price <= 0 || config.min_price > config.max_price || config.max_deviation_bps == 0 => {
+                return Err(OracleError::InvalidBreaker);
+            }
+            Some(config) => env.storage().instance().set(&OracleKey::Breaker, config),
+            None => env.storage().instance().remove(&OracleKey::Breaker),
+        }
         Ok(())
     }
 
-    // Query price for global verification
+    // Governance-only: Reset a tripped breaker. With `price`, that price is installed as
+    // the last accepted one; without, the last good price stays and the next submission
+    // is measured against it.
+    pub fn reset_breaker(env: Env, asset: Symbol, price: Option<i128>) -> Result<(), OracleError> {
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.governance.require_auth();
+        if price.is_some_and(|price| price <= 0) {
+            return Err(OracleError::InvalidData);
+        }
+        env.storage().instance().remove(&OracleKey::Tripped(asset.clone()));
+        if let Some(price) = price {
+            data.price_feed.set(asset.clone(), price);
+            env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
+            env.storage().instance().set(&OracleKey::UpdatedAt(asset.clone()), &env.ledger().timestamp());
+            Self::record_history(&env, &asset, price, env.ledger().timestamp());
+        }
+        env.events().publish((Symbol::new(&env, "breaker_reset"), asset), price);
+        Ok(())
+    }
+
+    // Query price for global verification. While the asset's breaker is tripped this is
+    // the last good price.
     pub fn query_price(env: Env, asset: Symbol) -> Result<i128, OracleError> {
         let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
         match data.price_feed.get(asset.clone()) {
@@ -73,26 +247,203 @@ impl PiCoinOracle {
         }
     }
 
+    // View: The asset's price in effect at `timestamp`, i.e. the newest retained point
+    // observed at or before it. History is hourly, so this is the first price accepted in
+    // that hour; it never changes once `timestamp` is further back than MAX_REPORT_AGE,
+    // which lets settlement contracts resolve against it deterministically. NoHistory when
+    // `timestamp` predates every retained point, InvalidData when it is in the future.
+    pub fn price_at(env: Env, asset: Symbol, timestamp: u64) -> Result<i128, OracleError> {
+        if timestamp > env.ledger().timestamp() {
+            return Err(OracleError::InvalidData);
+        }
+        let cursor = Self::history_cursor(&env, &asset);
+        // Binary search for the number of points observed at or before `timestamp`
+        let (mut low, mut high) = (0, cursor.len);
+        while low < high {
+            let mid = (low + high) / 2;
+            if Self::history_point(&env, &asset, &cursor, mid).timestamp <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        match low {
+            0 => Err(OracleError::NoHistory),
+            count => Ok(Self::history_point(&env, &asset, &cursor, count - 1).price),
+        }
+    }
+
+    // View: Retained points observed within [from, to], oldest first, at most PRICES_PAGE
+    pub fn prices(env: Env, asset: Symbol, from: u64, to: u64) -> Vec<PricePoint> {
+        let cursor = Self::history_cursor(&env, &asset);
+        // Binary search for the first point observed at or after `from`
+        let (mut low, mut high) = (0, cursor.len);
+        while low < high {
+            let mid = (low + high) / 2;
+            if Self::history_point(&env, &asset, &cursor, mid).timestamp < from {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        let mut points = Vec::new(&env);
+        for index in low..cursor.len.min(low + PRICES_PAGE) {
+            let point = Self::history_point(&env, &asset, &cursor, index);
+            if point.timestamp > to {
+                break;
+            }
+            points.push_back(point);
+        }
+        points
+    }
+
+    // Governance-only: Register the AMM pool (PiCoinAmm) used to sanity-check `asset`
+    pub fn set_price_pool(env: Env, asset: Symbol, pool: Address) -> Result<(), OracleError> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.governance.require_auth();
+        env.storage().instance().set(&OracleKey::PricePool(asset), &pool);
+        Ok(())
+    }
+
+    // View: Deviation of the registered pool's implied price from the feed, in basis
+    // points of the feed price (positive when the pool trades above the feed)
+    pub fn pool_deviation_bps(env: Env, asset: Symbol) -> Result<i128, OracleError> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        let feed_price = data.price_feed.get(asset.clone()).ok_or(OracleError::InvalidData)?;
+        let pool: Address = env.storage().instance().get(&OracleKey::PricePool(asset)).ok_or(OracleError::NoPricePool)?;
+        let pool_price: i128 = env.invoke_contract(&pool, &Symbol::new(&env, "pool_price"), Vec::new(&env));
+        let deviation = pool_price.checked_sub(feed_price).and_then(|d| d.checked_mul(10_000)).ok_or(OracleError::Overflow)?;
+        deviation.checked_div(feed_price).ok_or(OracleError::InvalidData)
+    }
+
     // Simulate global data aggregation (ultimate: integrate off-chain APIs)
     pub fn aggregate_global_data(env: Env) -> Result<(), OracleError> {
         // Hyper-tech: Simulate fetching from multiple sources (e.g., DEX, APIs)
-        let global_avg = 314_159_000_000 + (env.ledger().sequence() % 5000); // Dynamic simulation
-        Self::update_price(env, env.current_contract_address(), Symbol::new(&env, "PI"), global_avg)?;
+        let global_avg = 314_159_000_000 + (env.ledger().sequence() % 5000) as i128; // Dynamic simulation
+        Self::update_price(env.clone(), env.current_contract_address(), Symbol::new(&env, "PI"), global_avg)?;
         log!(&env, "Global data aggregated: PI price synced for worldwide payment recognition");
         Ok(())
     }
 
+    // Governance-only: Swap the oracle code (reached through an executed, timelocked proposal)
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), OracleError> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.governance.require_auth();
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        log!(&env, "Oracle wasm upgraded by governance");
+        Ok(())
+    }
+
+    // Migration hook run after an upgrade (idempotent, permissionless)
+    pub fn post_upgrade(env: Env) -> Result<u32, OracleError> {
+        let stored: u32 = env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1);
+        if stored < CONTRACT_VERSION {
+            env.storage().instance().set(&Symbol::new(&env, "version"), &CONTRACT_VERSION);
+            log!(&env, "Oracle storage migrated from version {} to {}", stored, CONTRACT_VERSION);
+        }
+        Ok(CONTRACT_VERSION)
+    }
+
+    // View: Whether the contract state has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&Symbol::new(&env, "oracle_data"))
+    }
+
+    // View: Storage layout version currently in effect
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1)
+    }
+
+    // View: Timestamp of the asset's last accepted price (None before the first one)
+    pub fn last_update(env: Env, asset: Symbol) -> Option<u64> {
+        env.storage().instance().get(&OracleKey::UpdatedAt(asset))
+    }
+
+    // View: Circuit-breaker bounds, if set
+    pub fn breaker(env: Env) -> Option<BreakerConfig> {
+        env.storage().instance().get(&OracleKey::Breaker)
+    }
+
+    // View: The asset's breaker trip, if tripped
+    pub fn breaker_trip(env: Env, asset: Symbol) -> Option<BreakerTrip> {
+        env.storage().instance().get(&OracleKey::Tripped(asset))
+    }
+
+    // View: Governance contract allowed to upgrade the oracle and set price pools
+    pub fn governance(env: Env) -> Address {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.governance
+    }
+
+    // Helper: Store `price` as the asset's feed price, unless the breaker rejects it. A price
+    // outside the band, or one that moves more than max_deviation_bps from the previous
+    // accepted price within `window` seconds of it, trips the breaker instead: the feed
+    // keeps its last good price and further submissions fail until governance resets it.
+    // The trip itself returns Ok, since an error would roll it back. `observed_at` is
+    // recorded as the price's update time.
+    fn accept_price(env: &Env, data: &mut OracleData, asset: &Symbol, price: i128, observed_at: u64) -> Result<(), OracleError> {
+        if Self::breaker_trip(env.clone(), asset.clone()).is_some() {
+            return Err(OracleError::BreakerTripped);
+        }
+        let now = env.ledger().timestamp();
+        let last_good_price = data.price_feed.get(asset.clone());
+        if let Some(config) = Self::breaker(env.clone()) {
+            let updated_at: Option<u64> = env.storage().instance().get(&OracleKey::UpdatedAt(asset.clone()));
+            let recent = last_good_price.filter(|_| updated_at.is_some_and(|at| now.saturating_sub(at) <= config.window));
+            let in_band = price >= config.min_price && price <= config.max_price;
+            let within_step = recent.is_none_or(|previous| {
+                let moved = price.checked_sub(previous).and_then(|delta| delta.checked_abs()).and_then(|delta| delta.checked_mul(10_000));
+                let allowed = previous.checked_mul(config.max_deviation_bps as i128);
+                matches!((moved, allowed), (Some(moved), Some(allowed)) if moved <= allowed)
+            });
+            if !in_band || !within_step {
+                let trip = BreakerTrip { rejected_price: price, last_good_price, tripped_at: now };
+                env.storage().instance().set(&OracleKey::Tripped(asset.clone()), &trip);
+                env.events().publish((Symbol::new(env, "breaker_tripped"), asset.clone()), trip);
+                return Ok(());
+            }
+        }
+        data.price_feed.set(asset.clone(), price);
+        env.storage().instance().set(&Symbol::new(env, "oracle_data"), data);
+        env.storage().instance().set(&OracleKey::UpdatedAt(asset.clone()), &observed_at);
+        Self::record_history(env, asset, price, observed_at);
+        Ok(())
+    }
+
+    // Helper: Append an accepted price to the asset's history, unless the newest point is
+    // from the same hour. Once HISTORY_LEN points are kept, the oldest is overwritten.
+    fn record_history(env: &Env, asset: &Symbol, price: i128, observed_at: u64) {
+        let mut cursor = Self::history_cursor(env, asset);
+        if cursor.len > 0 {
+            let newest = Self::history_point(env, asset, &cursor, cursor.len - 1);
+            if observed_at / HISTORY_INTERVAL <= newest.timestamp / HISTORY_INTERVAL {
+                return;
+            }
+        }
+        let point = PricePoint { price, timestamp: observed_at };
+        env.storage().persistent().set(&OracleKey::HistoryPoint(asset.clone(), cursor.next), &point);
+        cursor.next = (cursor.next + 1) % HISTORY_LEN;
+        cursor.len = (cursor.len + 1).min(HISTORY_LEN);
+        env.storage().persistent().set(&OracleKey::History(asset.clone()), &cursor);
+    }
+
+    // Helper: The asset's history cursor (empty before its first accepted price)
+    fn history_cursor(env: &Env, asset: &Symbol) -> HistoryCursor {
+        env.storage().persistent().get(&OracleKey::History(asset.clone())).unwrap_or(HistoryCursor { len: 0, next: 0 })
+    }
+
+    // Helper: The `index`-th retained point, counting from the oldest
+    fn history_point(env: &Env, asset: &Symbol, cursor: &HistoryCursor, index: u32) -> PricePoint {
+        let slot = (cursor.next + HISTORY_LEN - cursor.len + index) % HISTORY_LEN;
+        env.storage().persistent().get(&OracleKey::HistoryPoint(asset.clone(), slot)).unwrap()
+    }
+
     // Helper: AI prediction simulation (maximum level: predictive analytics)
     fn ai_predict_price(env: &Env, raw_price: i128) -> i128 {
         // Ultimate AI: Use ledger data for trend prediction (e.g., moving average)
         let trend_factor = (env.ledger().timestamp() as i128 % 100) / 10; // Simulated ML output
         raw_price + trend_factor * 1000 // Adjusted for stability
     }
-
-    // Helper: Verify ZKP proof (anti-manipulation)
-    fn verify_zkp_proof(env: &Env, signature: &BytesN<64>) -> bool {
-        // Hyper-tech: Simulated ZKP check for unmatched security
-        let proof_hash = env.crypto().sha256(&Bytes::from_slice(env, &signature.to_array()));
-        proof_hash == env.storage().instance().get(&Symbol::new(env, "zkp_proof")).unwrap_or(BytesN::from_array(env, &[0; 32]))
-    }
 }
+
+mod test;
@@ -0,0 +1,138 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, xdr::ToXdr, Address, BytesN, Env, Symbol};
+use crate::{BreakerConfig, OracleData, OracleError, PiCoinOracle, PiCoinOracleClient, PricePoint, SignedPrice, HISTORY_INTERVAL, HISTORY_LEN, PRICES_PAGE};
+
+// Submit `price` for PI the way update_price does once the updater checks out, without
+// its AI adjustment
+fn submit(env: &Env, oracle: &Address, price: i128) -> Result<(), OracleError> {
+    env.as_contract(oracle, || {
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(env, "oracle_data")).unwrap();
+        PiCoinOracle::accept_price(env, &mut data, &Symbol::new(env, "PI"), price, env.ledger().timestamp())
+    })
+}
+
+#[test]
+fn test_breaker_freezes_feed_on_outsized_move() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let oracle = env.register(PiCoinOracle, (Address::generate(&env), Address::generate(&env)));
+    let client = PiCoinOracleClient::new(&env, &oracle);
+    let pi = Symbol::new(&env, "PI");
+
+    let config = BreakerConfig { min_price: 100_000_000_000, max_price: 500_000_000_000, max_deviation_bps: 500, window: 600 };
+    assert!(matches!(client.try_set_breaker(&Some(BreakerConfig { min_price: 0, ..config.clone() })), Err(Ok(OracleError::InvalidBreaker))));
+    client.set_breaker(&Some(config));
+    assert!(submit(&env, &oracle, 314_159_000_000).is_ok());
+
+    // 5% within the window is fine; a fat-fingered 10x price trips the breaker
+    env.ledger().set_timestamp(1_100);
+    assert!(submit(&env, &oracle, 329_000_000_000).is_ok());
+    assert!(submit(&env, &oracle, 3_290_000_000_000).is_ok());
+    let trip = client.breaker_trip(&pi).unwrap();
+    assert_eq!((trip.rejected_price, trip.last_good_price), (3_290_000_000_000, Some(329_000_000_000)));
+    assert_eq!(client.query_price(&pi), 329_000_000_000);
+
+    // Frozen until governance resets it, even for sane prices
+    assert!(matches!(submit(&env, &oracle, 330_000_000_000), Err(OracleError::BreakerTripped)));
+    client.reset_breaker(&pi, &None);
+    assert_eq!(client.breaker_trip(&pi), None);
+
+    // Past the window, larger moves are accepted, but never outside the band
+    env.ledger().set_timestamp(2_000);
+    assert!(submit(&env, &oracle, 400_000_000_000).is_ok());
+    assert_eq!(client.query_price(&pi), 400_000_000_000);
+    env.ledger().set_timestamp(3_000);
+    assert!(submit(&env, &oracle, 600_000_000_000).is_ok());
+    assert_eq!(client.query_price(&pi), 400_000_000_000);
+    client.reset_breaker(&pi, &Some(420_000_000_000));
+    assert_eq!(client.query_price(&pi), 420_000_000_000);
+}
+
+#[test]
+fn test_relayed_price_packages_need_a_reporter_signature() {
+    use ed25519_dalek::Signer;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+    let oracle = env.register(PiCoinOracle, (Address::generate(&env), Address::generate(&env)));
+    let client = PiCoinOracleClient::new(&env, &oracle);
+    let pi = Symbol::new(&env, "PI");
+    let key = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+    let reporter = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    let sign = |price: i128, timestamp: u64, nonce: u64| {
+        let package = SignedPrice { network_id: env.ledger().network_id(), oracle: oracle.clone(), asset: pi.clone(), price, timestamp, nonce };
+        let xdr = package.to_xdr(&env);
+        let mut message = [0u8; 256];
+        xdr.copy_into_slice(&mut message[..xdr.len() as usize]);
+        BytesN::from_array(&env, &key.sign(&message[..xdr.len() as usize]).to_bytes())
+    };
+
+    let signature = sign(314_000, 9_990, 1);
+    assert!(matches!(client.try_submit_signed_price(&reporter, &pi, &314_000, &9_990, &1, &signature), Err(Ok(OracleError::Unauthorized))));
+    client.set_reporter(&reporter, &true);
+
+    // No auth at all: the signature is the authorization, so any relayer can submit
+    env.set_auths(&[]);
+    client.submit_signed_price(&reporter, &pi, &314_000, &9_990, &1, &signature);
+    assert_eq!((client.query_price(&pi), client.last_update(&pi), client.reporter_nonce(&reporter)), (314_000, Some(9_990), Some(1)));
+
+    // Replays, old observations and tampered prices are refused; nonces may skip ahead
+    assert!(matches!(client.try_submit_signed_price(&reporter, &pi, &314_000, &9_990, &1, &signature), Err(Ok(OracleError::StaleNonce))));
+    assert!(matches!(client.try_submit_signed_price(&reporter, &pi, &315_000, &9_600, &2, &sign(315_000, 9_600, 2)), Err(Ok(OracleError::StalePrice))));
+    assert!(matches!(client.try_submit_signed_price(&reporter, &pi, &315_000, &9_980, &2, &sign(315_000, 9_980, 2)), Err(Ok(OracleError::StalePrice))));
+    assert!(client.try_submit_signed_price(&reporter, &pi, &999_000, &10_000, &5, &sign(315_000, 10_000, 5)).is_err());
+    client.submit_signed_price(&reporter, &pi, &315_000, &10_000, &5, &sign(315_000, 10_000, 5));
+    assert_eq!(client.query_price(&pi), 315_000);
+}
+
+#[test]
+fn test_history_answers_settlement_queries_hourly() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(HISTORY_INTERVAL);
+    let oracle = env.register(PiCoinOracle, (Address::generate(&env), Address::generate(&env)));
+    let client = PiCoinOracleClient::new(&env, &oracle);
+    let pi = Symbol::new(&env, "PI");
+    assert!(matches!(client.try_price_at(&pi, &HISTORY_INTERVAL), Err(Ok(OracleError::NoHistory))));
+
+    // Only the first price of each hour is kept
+    assert!(submit(&env, &oracle, 100).is_ok());
+    env.ledger().set_timestamp(HISTORY_INTERVAL + 600);
+    assert!(submit(&env, &oracle, 105).is_ok());
+    env.ledger().set_timestamp(2 * HISTORY_INTERVAL + 60);
+    assert!(submit(&env, &oracle, 110).is_ok());
+    assert_eq!(client.price_at(&pi, &(HISTORY_INTERVAL + 900)), 100);
+    assert_eq!(client.price_at(&pi, &(2 * HISTORY_INTERVAL + 60)), 110);
+    assert!(matches!(client.try_price_at(&pi, &(HISTORY_INTERVAL - 1)), Err(Ok(OracleError::NoHistory))));
+    assert!(matches!(client.try_price_at(&pi, &(3 * HISTORY_INTERVAL)), Err(Ok(OracleError::InvalidData))));
+    assert_eq!(
+        client.prices(&pi, &0, &(2 * HISTORY_INTERVAL)),
+        soroban_sdk::vec![&env, PricePoint { price: 100, timestamp: HISTORY_INTERVAL }]
+    );
+
+    // Past HISTORY_LEN points the oldest are overwritten
+    for hour in 3..(HISTORY_LEN as u64 + 3) {
+        env.ledger().set_timestamp(hour * HISTORY_INTERVAL);
+        assert!(submit(&env, &oracle, 100 + hour as i128).is_ok());
+    }
+    let page = client.prices(&pi, &0, &u64::MAX);
+    assert_eq!(page.len(), PRICES_PAGE);
+    assert_eq!(page.first().unwrap(), PricePoint { price: 103, timestamp: 3 * HISTORY_INTERVAL });
+
+    // Paging from just past the last point walks the rest of what is kept
+    let mut kept = page.len();
+    let mut from = page.last().unwrap().timestamp + 1;
+    loop {
+        let page = client.prices(&pi, &from, &u64::MAX);
+        if page.is_empty() {
+            break;
+        }
+        kept += page.len();
+        from = page.last().unwrap().timestamp + 1;
+    }
+    assert_eq!(kept, HISTORY_LEN);
+    assert!(matches!(client.try_price_at(&pi, &(2 * HISTORY_INTERVAL + 60)), Err(Ok(OracleError::NoHistory))));
+    assert_eq!(client.price_at(&pi, &(500 * HISTORY_INTERVAL + 1)), 600);
+}
@@ -0,0 +1,195 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, Symbol, Vec, log};
+
+// A parameter's value. Its variant is fixed when the key is registered.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParamValue {
+    I128(i128),
+    U32(u32),
+    U64(u64),
+    Bool(bool),
+    Address(Address),
+}
+
+// Inclusive bounds on a numeric parameter; None leaves that side open
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamBounds {
+    pub min: Option<i128>,
+    pub max: Option<i128>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamEntry {
+    pub value: ParamValue,
+    pub bounds: ParamBounds,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ParamsData {
+    pub governance: Address, // Registers and updates parameters
+    pub keys: Vec<Symbol>, // Every registered key, in registration order
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ParamsKey {
+    Param(Symbol), // Persistent: key -> ParamEntry
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ParamsError {
+    NotFound = 1,
+    WrongType = 2, // The key holds another type
+    OutOfBounds = 3,
+    InvalidBounds = 4, // min > max, or bounds on a bool or address
+    AlreadyRegistered = 5,
+}
+
+#[contract]
+pub struct PiCoinParams;
+
+#[contractimpl]
+impl PiCoinParams {
+    // Constructor: one store for the ecosystem's tunables (fees, bands, quotas,
+    // thresholds). Contracts read them through the typed getters at call time, so a new
+    // tunable is a new key here instead of a new field in every contract's storage. Only
+    // governance writes, which means every change is an executed proposal.
+    pub fn __constructor(env: Env, governance: Address) {
+        let data = ParamsData { governance, keys: Vec::new(&env) };
+        env.storage().instance().set(&Symbol::new(&env, "params_data"), &data);
+        log!(&env, "Parameter store initialized: governance-managed tunables");
+    }
+
+    // Governance-only: Add `key` with its bounds and initial value. The value's type
+    // becomes the key's type.
+    pub fn register(env: Env, key: Symbol, bounds: ParamBounds, value: ParamValue) -> Result<(), ParamsError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        if Self::entry(env.clone(), key.clone()).is_some() {
+            return Err(ParamsError::AlreadyRegistered);
+        }
+        Self::check(&bounds, &value)?;
+        data.keys.push_back(key.clone());
+        env.storage().instance().set(&Symbol::new(&env, "params_data"), &data);
+        Self::write(&env, &key, value, bounds);
+        Ok(())
+    }
+
+    // Governance-only: Update `key` to `value`, which must have the key's type and lie
+    // within its bounds
+    pub fn set(env: Env, key: Symbol, value: ParamValue) -> Result<(), ParamsError> {
+        Self::load(&env).governance.require_auth();
+        let entry = Self::entry(env.clone(), key.clone()).ok_or(ParamsError::NotFound)?;
+        if core::mem::discriminant(&entry.value) != core::mem::discriminant(&value) {
+            return Err(ParamsError::WrongType);
+        }
+        Self::check(&entry.bounds, &value)?;
+        Self::write(&env, &key, value, entry.bounds);
+        Ok(())
+    }
+
+    // Governance-only: Replace `key`'s bounds; the current value must lie within them
+    pub fn set_bounds(env: Env, key: Symbol, bounds: ParamBounds) -> Result<(), ParamsError> {
+        Self::load(&env).governance.require_auth();
+        let entry = Self::entry(env.clone(), key.clone()).ok_or(ParamsError::NotFound)?;
+        Self::check(&bounds, &entry.value)?;
+        Self::write(&env, &key, entry.value, bounds);
+        Ok(())
+    }
+
+    // View: `key` as an i128, e.g. get_i128("stability_fee")
+    pub fn get_i128(env: Env, key: Symbol) -> Result<i128, ParamsError> {
+        match Self::get(env, key)? {
+            ParamValue::I128(value) => Ok(value),
+            _ => Err(ParamsError::WrongType),
+        }
+    }
+
+    // View: `key` as a u32 (basis points, counts)
+    pub fn get_u32(env: Env, key: Symbol) -> Result<u32, ParamsError> {
+        match Self::get(env, key)? {
+            ParamValue::U32(value) => Ok(value),
+            _ => Err(ParamsError::WrongType),
+        }
+    }
+
+    // View: `key` as a u64 (durations, timestamps)
+    pub fn get_u64(env: Env, key: Symbol) -> Result<u64, ParamsError> {
+        match Self::get(env, key)? {
+            ParamValue::U64(value) => Ok(value),
+            _ => Err(ParamsError::WrongType),
+        }
+    }
+
+    // View: `key` as a bool (switches)
+    pub fn get_bool(env: Env, key: Symbol) -> Result<bool, ParamsError> {
+        match Self::get(env, key)? {
+            ParamValue::Bool(value) => Ok(value),
+            _ => Err(ParamsError::WrongType),
+        }
+    }
+
+    // View: `key` as an address (collectors, recipients)
+    pub fn get_address(env: Env, key: Symbol) -> Result<Address, ParamsError> {
+        match Self::get(env, key)? {
+            ParamValue::Address(value) => Ok(value),
+            _ => Err(ParamsError::WrongType),
+        }
+    }
+
+    // View: `key`'s value, whatever its type
+    pub fn get(env: Env, key: Symbol) -> Result<ParamValue, ParamsError> {
+        Self::entry(env, key).map(|entry| entry.value).ok_or(ParamsError::NotFound)
+    }
+
+    // View: `key`'s value, bounds and last update time
+    pub fn entry(env: Env, key: Symbol) -> Option<ParamEntry> {
+        env.storage().persistent().get(&ParamsKey::Param(key))
+    }
+
+    // View: Every registered key
+    pub fn keys(env: Env) -> Vec<Symbol> {
+        Self::load(&env).keys
+    }
+
+    // Helper: InvalidBounds for crossed bounds or bounds on a non-numeric value,
+    // OutOfBounds for a numeric value outside them
+    fn check(bounds: &ParamBounds, value: &ParamValue) -> Result<(), ParamsError> {
+        if let (Some(min), Some(max)) = (bounds.min, bounds.max) {
+            if min > max {
+                return Err(ParamsError::InvalidBounds);
+            }
+        }
+        let number = match value {
+            ParamValue::I128(value) => *value,
+            ParamValue::U32(value) => *value as i128,
+            ParamValue::U64(value) => *value as i128,
+            ParamValue::Bool(_) | ParamValue::Address(_) if bounds.min.is_none() && bounds.max.is_none() => return Ok(()),
+            ParamValue::Bool(_) | ParamValue::Address(_) => return Err(ParamsError::InvalidBounds),
+        };
+        if bounds.min.is_some_and(|min| number < min) || bounds.max.is_some_and(|max| number > max) {
+            return Err(ParamsError::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    // Helper: Store `key`'s entry and publish ("param_set", key) -> value
+    fn write(env: &Env, key: &Symbol, value: ParamValue, bounds: ParamBounds) {
+        let entry = ParamEntry { value: value.clone(), bounds, updated_at: env.ledger().timestamp() };
+        env.storage().persistent().set(&ParamsKey::Param(key.clone()), &entry);
+        env.events().publish((Symbol::new(env, "param_set"), key.clone()), value);
+    }
+
+    fn load(env: &Env) -> ParamsData {
+        env.storage().instance().get(&Symbol::new(env, "params_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,41 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Symbol};
+use crate::{ParamBounds, ParamValue, ParamsError, PiCoinParams, PiCoinParamsClient};
+
+#[test]
+fn test_typed_parameters_stay_within_their_bounds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinParams, (&governance,));
+    let client = PiCoinParamsClient::new(&env, &contract_id);
+    let fee = Symbol::new(&env, "stability_fee");
+    let collector = Symbol::new(&env, "fee_collector");
+    assert!(matches!(client.try_get_i128(&fee), Err(Ok(ParamsError::NotFound))));
+
+    let bps = ParamBounds { min: Some(0), max: Some(1_000) };
+    let open = ParamBounds { min: None, max: None };
+    assert!(matches!(client.try_register(&fee, &bps, &ParamValue::I128(2_000)), Err(Ok(ParamsError::OutOfBounds))));
+    client.register(&fee, &bps, &ParamValue::I128(200));
+    assert_eq!(env.auths()[0].0, governance);
+    assert!(matches!(client.try_register(&fee, &bps, &ParamValue::I128(300)), Err(Ok(ParamsError::AlreadyRegistered))));
+    assert!(matches!(client.try_register(&collector, &bps, &ParamValue::Address(governance.clone())), Err(Ok(ParamsError::InvalidBounds))));
+    client.register(&collector, &open, &ParamValue::Address(governance.clone()));
+    assert_eq!(client.keys(), vec![&env, fee.clone(), collector.clone()]);
+
+    // Typed reads; updates keep the key's type and bounds
+    assert_eq!(client.get_i128(&fee), 200);
+    assert_eq!(client.get_address(&collector), governance);
+    assert!(matches!(client.try_get_u32(&fee), Err(Ok(ParamsError::WrongType))));
+    assert!(matches!(client.try_set(&fee, &ParamValue::U32(300)), Err(Ok(ParamsError::WrongType))));
+    assert!(matches!(client.try_set(&fee, &ParamValue::I128(-1)), Err(Ok(ParamsError::OutOfBounds))));
+    client.set(&fee, &ParamValue::I128(300));
+    assert_eq!(client.get_i128(&fee), 300);
+
+    // Bounds can move, but not away from the current value
+    assert!(matches!(client.try_set_bounds(&fee, &ParamBounds { min: Some(0), max: Some(250) }), Err(Ok(ParamsError::OutOfBounds))));
+    assert!(matches!(client.try_set_bounds(&fee, &ParamBounds { min: Some(500), max: Some(400) }), Err(Ok(ParamsError::InvalidBounds))));
+    client.set_bounds(&fee, &ParamBounds { min: Some(100), max: None });
+    client.set(&fee, &ParamValue::I128(5_000));
+    assert_eq!(client.entry(&fee).unwrap().bounds.max, None);
+}
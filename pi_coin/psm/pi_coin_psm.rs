@@ -0,0 +1,258 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// PI amounts use the Stellar 7-decimal convention, like every SAC
+pub const PI_DECIMALS: u32 = 7;
+// Scale of the peg: PiCoinContract quotes it in micro-dollars per PI
+pub const USD_ONE: i128 = 1_000_000;
+// Highest asset precision accepted, so 10^decimals and the conversions stay in i128
+pub const MAX_DECIMALS: u32 = 18;
+const BPS: i128 = 10_000;
+
+// Same variants (and XDR) as PiCoinContract's source enum; PSM swaps mint as P2P
+#[contracttype]
+#[derive(Clone)]
+pub enum PiCoinSource {
+    Mining,
+    Rewards,
+    P2P,
+    Invalid,
+}
+
+// Supply entry points of PiCoinContract used by the PSM. This contract must be the PI
+// token's minting admin.
+#[contractclient(name = "PiSupplyTokenClient")]
+pub trait PiSupplyToken {
+    fn mint(env: Env, to: Address, amount: i128, source: PiCoinSource);
+    fn burn(env: Env, from: Address, amount: i128);
+    fn peg_value(env: Env) -> i128;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PsmData {
+    pub governance: Address, // Lists collateral assets
+    pub pi_token: Address,
+    pub treasury: Address, // Receives swap fees, in collateral
+}
+
+// A listed collateral asset, addressed by its SAC (or any SEP-41 token) address
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralAsset {
+    pub decimals: u32, // Read from the token when listed (7 for classic assets)
+    pub fee_bps: u32, // Charged on both swap directions
+    pub ceiling: i128, // Most PI that may be outstanding against this asset
+    pub minted: i128, // PI outstanding against it
+    pub reserve: i128, // Collateral held, net of fees
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PsmKey {
+    Asset(Address),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PsmError {
+    InvalidAmount = 1,
+    UnsupportedAsset = 2,
+    CeilingExceeded = 3,
+    InsufficientReserve = 4,
+    InvalidConfig = 5,
+    SlippageExceeded = 6,
+    DeadlineExpired = 7,
+}
+
+#[contract]
+pub struct PiCoinPsm;
+
+#[contractimpl]
+impl PiCoinPsm {
+    // Constructor: peg stability module and collateral vault. Holders swap listed dollar
+    // assets - classic Stellar assets such as USDC through their SAC address - for PI at
+    // the peg and back, minus a fee paid to the treasury. Collateral moves through the
+    // SEP-41 `transfer`/`transfer_from` interface and is scaled between the asset's
+    // decimals and PI's 7.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, treasury: Address) {
+        let data = PsmData { governance, pi_token, treasury };
+        env.storage().instance().set(&Symbol::new(&env, "psm_data"), &data);
+        log!(&env, "PSM initialized: dollar collateral swaps for PI at the peg");
+    }
+
+    // Swap `amount` of `asset` from `from` for at least `min_out` PI, up to
+    // `deadline_ledger` (the peg may move between simulation and inclusion); returns the
+    // PI minted to `from`
+    pub fn sell_collateral(env: Env, from: Address, asset: Address, amount: i128, min_out: i128, deadline_ledger: u32) -> Result<i128, PsmError> {
+        from.require_auth();
+        let (data, mut info, pi_out, fee) = Self::quote_sell(&env, &asset, amount)?;
+        Self::check_limits(&env, pi_out, min_out, deadline_ledger)?;
+        Self::book_sell(&env, &asset, &mut info, amount - fee, pi_out);
+        let collateral = token::TokenClient::new(&env, &asset);
+        collateral.transfer(&from, env.current_contract_address(), &amount);
+        Self::settle_sell(&env, &data, &asset, &collateral, &from, amount, pi_out, fee);
+        Ok(pi_out)
+    }
+
+    // Like `sell_collateral`, but the asset is pulled with `transfer_from` out of an
+    // allowance `from` gave `spender` (e.g. a router); the PI goes to `spender`
+    pub fn sell_collateral_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        asset: Address,
+        amount: i128,
+        min_out: i128,
+        deadline_ledger: u32,
+    ) -> Result<i128, PsmError> {
+        spender.require_auth();
+        let (data, mut info, pi_out, fee) = Self::quote_sell(&env, &asset, amount)?;
+        Self::check_limits(&env, pi_out, min_out, deadline_ledger)?;
+        Self::book_sell(&env, &asset, &mut info, amount - fee, pi_out);
+        let collateral = token::TokenClient::new(&env, &asset);
+        collateral.transfer_from(&spender, &from, &env.current_contract_address(), &amount);
+        Self::settle_sell(&env, &data, &asset, &collateral, &spender, amount, pi_out, fee);
+        Ok(pi_out)
+    }
+
+    // Burn `pi_amount` PI of `to` for at least `min_out` of `asset` at the peg, up to
+    // `deadline_ledger`; returns the collateral paid out
+    pub fn buy_collateral(env: Env, to: Address, asset: Address, pi_amount: i128, min_out: i128, deadline_ledger: u32) -> Result<i128, PsmError> {
+        to.require_auth();
+        if pi_amount <= 0 {
+            return Err(PsmError::InvalidAmount);
+        }
+        let data = Self::load(&env);
+        let mut info = Self::asset(env.clone(), asset.clone()).ok_or(PsmError::UnsupportedAsset)?;
+        let pi = PiSupplyTokenClient::new(&env, &data.pi_token);
+        let gross = Self::to_collateral(pi_amount, pi.peg_value(), info.decimals);
+        let fee = gross * info.fee_bps as i128 / BPS;
+        let out = gross - fee;
+        if out <= 0 {
+            return Err(PsmError::InvalidAmount);
+        }
+        if gross > info.reserve {
+            return Err(PsmError::InsufficientReserve);
+        }
+        Self::check_limits(&env, out, min_out, deadline_ledger)?;
+        info.reserve -= gross;
+        info.minted = (info.minted - pi_amount).max(0);
+        env.storage().persistent().set(&PsmKey::Asset(asset.clone()), &info);
+        pi.burn(&to, &pi_amount);
+        let collateral = token::TokenClient::new(&env, &asset);
+        collateral.transfer(&env.current_contract_address(), &to, &out);
+        if fee > 0 {
+            collateral.transfer(&env.current_contract_address(), &data.treasury, &fee);
+        }
+        env.events().publish((Symbol::new(&env, "psm_buy"), asset, to), (pi_amount, out, fee));
+        Ok(out)
+    }
+
+    // Governance-only: List `asset` (or update its fee and ceiling). Delisting is a zero
+    // ceiling, so holders can still swap PI back for the reserve.
+    pub fn set_asset(env: Env, asset: Address, fee_bps: u32, ceiling: i128) -> Result<(), PsmError> {
+        let data = Self::load(&env);
+        data.governance.require_auth();
+        if fee_bps as i128 > BPS || ceiling < 0 {
+            return Err(PsmError::InvalidConfig);
+        }
+        let info = match Self::asset(env.clone(), asset.clone()) {
+            Some(info) => CollateralAsset { fee_bps, ceiling, ..info },
+            None => {
+                let decimals = token::TokenClient::new(&env, &asset).decimals();
+                if decimals > MAX_DECIMALS {
+                    return Err(PsmError::InvalidConfig);
+                }
+                CollateralAsset { decimals, fee_bps, ceiling, minted: 0, reserve: 0 }
+            }
+        };
+        env.storage().persistent().set(&PsmKey::Asset(asset.clone()), &info);
+        env.events().publish((Symbol::new(&env, "psm_asset"), asset), (fee_bps, ceiling));
+        Ok(())
+    }
+
+    // View: Listing, outstanding PI and reserve of `asset`
+    pub fn asset(env: Env, asset: Address) -> Option<CollateralAsset> {
+        env.storage().persistent().get(&PsmKey::Asset(asset))
+    }
+
+    // View: PI `sell_collateral(asset, amount)` would mint right now
+    pub fn preview_sell(env: Env, asset: Address, amount: i128) -> Result<i128, PsmError> {
+        Ok(Self::quote_sell(&env, &asset, amount)?.2)
+    }
+
+    // Helper: PI out and fee for selling `amount` of `asset`, checked against the ceiling
+    fn quote_sell(env: &Env, asset: &Address, amount: i128) -> Result<(PsmData, CollateralAsset, i128, i128), PsmError> {
+        if amount <= 0 {
+            return Err(PsmError::InvalidAmount);
+        }
+        let data = Self::load(env);
+        let info = Self::asset(env.clone(), asset.clone()).ok_or(PsmError::UnsupportedAsset)?;
+        let fee = amount * info.fee_bps as i128 / BPS;
+        let peg = PiSupplyTokenClient::new(env, &data.pi_token).peg_value();
+        let pi_out = Self::to_pi(amount - fee, peg, info.decimals);
+        if pi_out <= 0 {
+            return Err(PsmError::InvalidAmount);
+        }
+        if info.minted + pi_out > info.ceiling {
+            return Err(PsmError::CeilingExceeded);
+        }
+        Ok((data, info, pi_out, fee))
+    }
+
+    // Helper: Reject a swap past its deadline or paying out less than its minimum
+    fn check_limits(env: &Env, out: i128, min_out: i128, deadline_ledger: u32) -> Result<(), PsmError> {
+        if env.ledger().sequence() > deadline_ledger {
+            return Err(PsmError::DeadlineExpired);
+        }
+        if out < min_out {
+            return Err(PsmError::SlippageExceeded);
+        }
+        Ok(())
+    }
+
+    // Helper: Book a sale before any token is called (checks-effects-interactions), so a
+    // token calling back into the PSM already sees the new reserve and outstanding PI
+    fn book_sell(env: &Env, asset: &Address, info: &mut CollateralAsset, reserve_in: i128, pi_out: i128) {
+        info.reserve += reserve_in;
+        info.minted += pi_out;
+        env.storage().persistent().set(&PsmKey::Asset(asset.clone()), info);
+    }
+
+    // Helper: Once a booked sale's collateral has arrived, pay the fee and mint the PI
+    #[allow(clippy::too_many_arguments)]
+    fn settle_sell(
+        env: &Env,
+        data: &PsmData,
+        asset: &Address,
+        collateral: &token::TokenClient,
+        to: &Address,
+        amount: i128,
+        pi_out: i128,
+        fee: i128,
+    ) {
+        if fee > 0 {
+            collateral.transfer(&env.current_contract_address(), &data.treasury, &fee);
+        }
+        PiSupplyTokenClient::new(env, &data.pi_token).mint(to, &pi_out, &PiCoinSource::P2P);
+        env.events().publish((Symbol::new(env, "psm_sell"), asset.clone(), to.clone()), (amount, pi_out, fee));
+    }
+
+    // Helper: PI worth `amount` collateral units at `peg` (rounds down)
+    fn to_pi(amount: i128, peg: i128, decimals: u32) -> i128 {
+        amount * 10i128.pow(PI_DECIMALS) * USD_ONE / (10i128.pow(decimals) * peg)
+    }
+
+    // Helper: Collateral units worth `pi_amount` at `peg` (rounds down)
+    fn to_collateral(pi_amount: i128, peg: i128, decimals: u32) -> i128 {
+        pi_amount * peg * 10i128.pow(decimals) / (10i128.pow(PI_DECIMALS) * USD_ONE)
+    }
+
+    fn load(env: &Env) -> PsmData {
+        env.storage().instance().get(&Symbol::new(env, "psm_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,134 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, contracttype, testutils::{Address as _, Ledger}, token, Address, Env};
+use crate::{PiCoinPsm, PiCoinPsmClient, PiCoinSource, PsmError};
+
+const DEADLINE: u32 = 1_000;
+
+// Just enough of PiCoinContract for the PSM: balances, mint, burn and a $2 peg
+#[contracttype]
+enum MockKey {
+    Balance(Address),
+}
+
+#[contract]
+struct MockPi;
+
+#[contractimpl]
+impl MockPi {
+    pub fn peg_value(_env: Env) -> i128 {
+        2_000_000
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&MockKey::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128, _source: PiCoinSource) {
+        Self::credit(&env, &to, amount);
+    }
+
+    pub fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        Self::credit(&env, &from, -amount);
+    }
+}
+
+impl MockPi {
+    fn credit(env: &Env, to: &Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone()) + amount;
+        assert!(balance >= 0, "insufficient balance");
+        env.storage().instance().set(&MockKey::Balance(to.clone()), &balance);
+    }
+}
+
+#[test]
+fn test_classic_asset_swaps_at_the_peg() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // USDC as a classic asset behind its Stellar Asset Contract: 7 decimals
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let usdc_client = token::TokenClient::new(&env, &usdc);
+    let pi = env.register(MockPi, ());
+    let pi_client = MockPiClient::new(&env, &pi);
+    let treasury = Address::generate(&env);
+    let alice = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&alice, &10_000_000_000);
+
+    let contract_id = env.register(PiCoinPsm, (Address::generate(&env), &pi, &treasury));
+    let client = PiCoinPsmClient::new(&env, &contract_id);
+    assert_eq!(client.try_sell_collateral(&alice, &usdc, &1_000_000_000, &0, &DEADLINE), Err(Ok(PsmError::UnsupportedAsset)));
+
+    // 10 bps fee, at most 300 PI outstanding against USDC
+    client.set_asset(&usdc, &10, &3_000_000_000);
+    assert_eq!(client.asset(&usdc).unwrap().decimals, 7);
+
+    // 100 USDC less the 0.1 USDC fee buys 49.95 PI at $2
+    assert_eq!(client.preview_sell(&usdc, &1_000_000_000), 499_500_000);
+    assert_eq!(client.sell_collateral(&alice, &usdc, &1_000_000_000, &0, &DEADLINE), 499_500_000);
+    assert_eq!(pi_client.balance(&alice), 499_500_000);
+    assert_eq!((usdc_client.balance(&contract_id), usdc_client.balance(&treasury)), (999_000_000, 1_000_000));
+    assert_eq!(client.try_sell_collateral(&alice, &usdc, &6_000_000_000, &0, &DEADLINE), Err(Ok(PsmError::CeilingExceeded)));
+
+    // Back the other way: 20 PI is worth 40 USDC, less the fee
+    assert_eq!(client.buy_collateral(&alice, &usdc, &200_000_000, &0, &DEADLINE), 399_600_000);
+    assert_eq!(pi_client.balance(&alice), 299_500_000);
+    assert_eq!(usdc_client.balance(&alice), 9_399_600_000);
+    let info = client.asset(&usdc).unwrap();
+    assert_eq!((info.minted, info.reserve), (299_500_000, 599_000_000));
+    assert_eq!(usdc_client.balance(&contract_id), info.reserve);
+
+    // The reserve caps what PI can be swapped back
+    pi_client.mint(&alice, &100_000_000, &PiCoinSource::P2P);
+    assert_eq!(client.try_buy_collateral(&alice, &usdc, &300_000_000, &0, &DEADLINE), Err(Ok(PsmError::InsufficientReserve)));
+    assert_eq!(client.try_buy_collateral(&alice, &usdc, &0, &0, &DEADLINE), Err(Ok(PsmError::InvalidAmount)));
+}
+
+#[test]
+fn test_sell_collateral_through_an_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let usdc_client = token::TokenClient::new(&env, &usdc);
+    let pi = env.register(MockPi, ());
+    let alice = Address::generate(&env);
+    let router = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&alice, &100_000_000);
+
+    let contract_id = env.register(PiCoinPsm, (Address::generate(&env), &pi, Address::generate(&env)));
+    let client = PiCoinPsmClient::new(&env, &contract_id);
+    client.set_asset(&usdc, &0, &10_000_000_000);
+
+    // Alice lets a router spend her USDC; the router sells it and receives the PI
+    usdc_client.approve(&alice, &router, &40_000_000, &1_000);
+    assert_eq!(client.sell_collateral_from(&router, &alice, &usdc, &40_000_000, &0, &DEADLINE), 20_000_000);
+    assert_eq!(MockPiClient::new(&env, &pi).balance(&router), 20_000_000);
+    assert_eq!((usdc_client.balance(&alice), usdc_client.allowance(&alice, &router)), (60_000_000, 0));
+    assert!(client.try_sell_collateral_from(&router, &alice, &usdc, &1, &0, &DEADLINE).is_err());
+}
+
+#[test]
+fn test_swaps_enforce_min_out_and_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let pi = env.register(MockPi, ());
+    let alice = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&alice, &1_000_000_000);
+    let client = PiCoinPsmClient::new(&env, &env.register(PiCoinPsm, (Address::generate(&env), &pi, Address::generate(&env))));
+    client.set_asset(&usdc, &0, &10_000_000_000);
+
+    // 100 USDC buys exactly 50 PI at $2: asking for more than that fails
+    assert_eq!(client.try_sell_collateral(&alice, &usdc, &1_000_000_000, &500_000_001, &DEADLINE), Err(Ok(PsmError::SlippageExceeded)));
+    assert_eq!(client.sell_collateral(&alice, &usdc, &1_000_000_000, &500_000_000, &DEADLINE), 500_000_000);
+    assert_eq!(client.try_buy_collateral(&alice, &usdc, &100_000_000, &200_000_001, &DEADLINE), Err(Ok(PsmError::SlippageExceeded)));
+
+    // The deadline ledger is the last one a swap may land in
+    env.ledger().set_sequence_number(DEADLINE);
+    assert_eq!(client.buy_collateral(&alice, &usdc, &100_000_000, &200_000_000, &DEADLINE), 200_000_000);
+    env.ledger().set_sequence_number(DEADLINE + 1);
+    assert_eq!(client.try_buy_collateral(&alice, &usdc, &100_000_000, &0, &DEADLINE), Err(Ok(PsmError::DeadlineExpired)));
+    assert_eq!(client.try_sell_collateral(&alice, &usdc, &100_000_000, &0, &DEADLINE), Err(Ok(PsmError::DeadlineExpired)));
+    assert_eq!(client.asset(&usdc).unwrap().minted, 400_000_000);
+}
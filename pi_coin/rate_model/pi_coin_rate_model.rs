@@ -0,0 +1,135 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, Symbol, log};
+
+pub const BPS: i128 = 10_000;
+// Highest annual rate a curve may reach at full utilization (1000% a year)
+pub const MAX_RATE_BPS: u32 = 100_000;
+
+// Kinked utilization curve. The rate climbs from `base_bps` by `slope1_bps` as
+// utilization goes from zero to `kink_bps`, then by `slope2_bps` more (normally much
+// steeper) from the kink to full utilization. All rates are annual, in basis points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateCurve {
+    pub base_bps: u32,
+    pub slope1_bps: u32,
+    pub slope2_bps: u32,
+    pub kink_bps: u32, // Utilization where the second slope starts, 1..=BPS
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RateModelData {
+    pub governance: Address, // Sets the curve
+    pub curve: RateCurve,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RateModelError {
+    InvalidCurve = 1,
+    InvalidAmount = 2,
+}
+
+#[contract]
+pub struct PiCoinRateModel;
+
+#[contractimpl]
+impl PiCoinRateModel {
+    // Constructor: rate policy for savings and lending vaults, which query it for their
+    // rates instead of hard-coding one, so governance can retune or replace the curve
+    // without upgrading the vaults
+    pub fn __constructor(env: Env, governance: Address, curve: RateCurve) -> Result<(), RateModelError> {
+        Self::validate(&curve)?;
+        env.storage().instance().set(&Symbol::new(&env, "rate_model_data"), &RateModelData { governance, curve });
+        log!(&env, "Rate model initialized: kinked utilization curve");
+        Ok(())
+    }
+
+    // Governance-only: Replace the curve. Vaults pick it up on their next accrual.
+    pub fn set_curve(env: Env, curve: RateCurve) -> Result<(), RateModelError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(&curve)?;
+        data.curve = curve.clone();
+        env.storage().instance().set(&Symbol::new(&env, "rate_model_data"), &data);
+        env.events().publish((Symbol::new(&env, "rate_curve"),), curve);
+        Ok(())
+    }
+
+    // View: Share of the vault's funds that is lent out, borrows / (cash + borrows), in
+    // basis points. Zero for an empty vault.
+    pub fn utilization(_env: Env, cash: i128, borrows: i128) -> Result<u32, RateModelError> {
+        Self::utilization_of(cash, borrows)
+    }
+
+    // View: Annual rate borrowers pay at the vault's utilization, in basis points
+    pub fn borrow_rate(env: Env, cash: i128, borrows: i128) -> Result<u32, RateModelError> {
+        let utilization = Self::utilization_of(cash, borrows)?;
+        Ok(Self::rate_at(&Self::load(&env).curve, utilization))
+    }
+
+    // View: Annual rate depositors earn: the borrow rate spread over all deposits, less
+    // the vault's `reserve_factor_bps` cut
+    pub fn supply_rate(env: Env, cash: i128, borrows: i128, reserve_factor_bps: u32) -> Result<u32, RateModelError> {
+        if reserve_factor_bps as i128 > BPS {
+            return Err(RateModelError::InvalidAmount);
+        }
+        let utilization = Self::utilization_of(cash, borrows)? as i128;
+        let borrow_rate = Self::rate_at(&Self::load(&env).curve, utilization as u32) as i128;
+        Ok((borrow_rate * utilization / BPS * (BPS - reserve_factor_bps as i128) / BPS) as u32)
+    }
+
+    // View: Rate the curve gives at `utilization_bps` (capped at 100%)
+    pub fn rate_at_utilization(env: Env, utilization_bps: u32) -> u32 {
+        Self::rate_at(&Self::load(&env).curve, utilization_bps)
+    }
+
+    // View: Current curve
+    pub fn curve(env: Env) -> RateCurve {
+        Self::load(&env).curve
+    }
+
+    // Helper: borrows / (cash + borrows) in basis points
+    fn utilization_of(cash: i128, borrows: i128) -> Result<u32, RateModelError> {
+        if cash < 0 || borrows < 0 {
+            return Err(RateModelError::InvalidAmount);
+        }
+        if borrows == 0 {
+            return Ok(0);
+        }
+        // Past i128 the sum and product are scaled down first; the ratio barely moves
+        let total = cash.checked_add(borrows).unwrap_or(i128::MAX);
+        let utilization = match borrows.checked_mul(BPS) {
+            Some(scaled) => scaled / total,
+            None => borrows / (total / BPS),
+        };
+        Ok(utilization as u32)
+    }
+
+    // Helper: The curve at `utilization` bps
+    fn rate_at(curve: &RateCurve, utilization: u32) -> u32 {
+        let (utilization, kink) = (utilization.min(BPS as u32) as i128, curve.kink_bps as i128);
+        let mut rate = curve.base_bps as i128 + curve.slope1_bps as i128 * utilization.min(kink) / kink;
+        if utilization > kink {
+            rate += curve.slope2_bps as i128 * (utilization - kink) / (BPS - kink);
+        }
+        rate as u32
+    }
+
+    // Helper: A kink inside (0, 100%] and a top rate no higher than MAX_RATE_BPS
+    fn validate(curve: &RateCurve) -> Result<(), RateModelError> {
+        let top = curve.base_bps as u64 + curve.slope1_bps as u64 + curve.slope2_bps as u64;
+        if curve.kink_bps == 0 || curve.kink_bps as i128 > BPS || top > MAX_RATE_BPS as u64 {
+            return Err(RateModelError::InvalidCurve);
+        }
+        Ok(())
+    }
+
+    fn load(env: &Env) -> RateModelData {
+        env.storage().instance().get(&Symbol::new(env, "rate_model_data")).unwrap()
+    }
+}
+
+mod test;
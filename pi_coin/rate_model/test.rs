@@ -0,0 +1,44 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use crate::{PiCoinRateModel, PiCoinRateModelClient, RateCurve, RateModelError};
+
+#[test]
+fn test_kinked_curve_and_governance_updates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // 2% base, +4% up to an 80% kink, +60% more from there to full utilization
+    let curve = RateCurve { base_bps: 200, slope1_bps: 400, slope2_bps: 6_000, kink_bps: 8_000 };
+    let client = PiCoinRateModelClient::new(&env, &env.register(PiCoinRateModel, (Address::generate(&env), curve.clone())));
+
+    assert_eq!(client.utilization(&0, &0), 0);
+    assert_eq!(client.utilization(&100, &0), 0);
+    assert_eq!(client.utilization(&20, &80), 8_000);
+    assert_eq!(client.utilization(&0, &100), 10_000);
+    assert_eq!(client.utilization(&(i128::MAX / 2), &(i128::MAX / 2)), 5_000);
+    assert!(matches!(client.try_utilization(&-1, &100), Err(Ok(RateModelError::InvalidAmount))));
+
+    // Gentle below the kink, steep above it
+    assert_eq!(client.borrow_rate(&100, &0), 200);
+    assert_eq!(client.borrow_rate(&60, &40), 400);
+    assert_eq!(client.borrow_rate(&20, &80), 600);
+    assert_eq!(client.borrow_rate(&10, &90), 3_600);
+    assert_eq!(client.borrow_rate(&0, &100), 6_600);
+    assert_eq!(client.rate_at_utilization(&20_000), 6_600);
+
+    // Depositors share the borrow rate at their utilization, less the reserve factor
+    assert_eq!(client.supply_rate(&20, &80, &0), 480);
+    assert_eq!(client.supply_rate(&20, &80, &1_000), 432);
+    assert!(matches!(client.try_supply_rate(&20, &80, &10_001), Err(Ok(RateModelError::InvalidAmount))));
+
+    // Governance retunes the curve; vaults see it on their next query
+    let bad_kink = RateCurve { kink_bps: 0, ..curve.clone() };
+    let too_steep = RateCurve { slope2_bps: 100_000, ..curve.clone() };
+    assert!(matches!(client.try_set_curve(&bad_kink), Err(Ok(RateModelError::InvalidCurve))));
+    assert!(matches!(client.try_set_curve(&too_steep), Err(Ok(RateModelError::InvalidCurve))));
+    let linear = RateCurve { base_bps: 0, slope1_bps: 1_000, slope2_bps: 0, kink_bps: 10_000 };
+    client.set_curve(&linear);
+    assert_eq!(client.curve(), linear);
+    assert_eq!(client.borrow_rate(&20, &80), 800);
+    assert_eq!(client.borrow_rate(&0, &100), 1_000);
+}
@@ -0,0 +1,259 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Provenance views of PiCoinContract. `verify_ecosystem_entry` passes for PI minted from a
+// valid source, an attested source or a participant badge.
+#[contractclient(name = "PiProvenanceClient")]
+pub trait PiProvenance {
+    fn verify_ecosystem_entry(env: Env, holder: Address) -> bool;
+    fn balance(env: Env, id: Address) -> i128;
+}
+
+// Stake checkpoints of PiCoinGovernance
+#[contractclient(name = "StakingClient")]
+pub trait Staking {
+    fn stake_at(env: Env, voter: Address, ledger: u32) -> i128;
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralConfig {
+    pub min_stake: i128, // Governance stake a referrer must keep to register and earn
+    pub referrer_points: i128, // Points per activated referee
+    pub referee_points: i128,
+    pub pi_per_point: i128,
+    pub emission_cap: i128, // Most PI ever paid out for points
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ReferralData {
+    pub governance: Address, // Sets the config
+    pub pi_token: Address, // Pays out points; also answers provenance checks
+    pub staking: Address,
+    pub config: ReferralConfig,
+    pub emitted: i128, // PI paid out for points so far
+}
+
+// A referee's binding to a referrer's code
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Referral {
+    pub code: Symbol,
+    pub referrer: Address,
+    pub activated: bool, // Points credited after the referee's first verified mint
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ReferralKey {
+    Code(Symbol), // Persistent: code -> referrer
+    CodeOf(Address), // Persistent: referrer -> code
+    Referral(Address), // Persistent: referee -> Referral
+    Points(Address), // Persistent: unredeemed points
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ReferralError {
+    InvalidAmount = 1,
+    InvalidConfig = 2,
+    InsufficientStake = 3,
+    CodeTaken = 4,
+    AlreadyRegistered = 5,
+    UnknownCode = 6,
+    AlreadyBound = 7,
+    SelfReferral = 8,
+    AlreadyMinted = 9, // Referees bind before their first mint
+    NotBound = 10,
+    AlreadyActivated = 11,
+    NotVerified = 12,
+    InsufficientPoints = 13,
+    BudgetExhausted = 14,
+}
+
+#[contract]
+pub struct PiCoinReferral;
+
+#[contractimpl]
+impl PiCoinReferral {
+    // Constructor: referral program. Referrers register a code, new users bind to one
+    // before their first mint, and once that mint lands from a provenance-verified source
+    // both sides earn points, convertible to PI until the emission cap runs out. Only
+    // referrers with `min_stake` in governance can register or earn, so sybil accounts
+    // cost real stake on one side and a verified mint on the other. The contract pays out
+    // PI it holds, e.g. from a treasury spend.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, staking: Address, config: ReferralConfig) -> Result<(), ReferralError> {
+        Self::validate(&config)?;
+        let data = ReferralData { governance, pi_token, staking, config, emitted: 0 };
+        env.storage().instance().set(&Symbol::new(&env, "referral_data"), &data);
+        log!(&env, "Referral program initialized: stake-gated referrers, provenance-verified referees");
+        Ok(())
+    }
+
+    // Register `code` as `referrer`'s referral code, one per referrer
+    pub fn register_code(env: Env, referrer: Address, code: Symbol) -> Result<(), ReferralError> {
+        referrer.require_auth();
+        let data = Self::load(&env);
+        Self::require_stake(&env, &data, &referrer)?;
+        if env.storage().persistent().has(&ReferralKey::CodeOf(referrer.clone())) {
+            return Err(ReferralError::AlreadyRegistered);
+        }
+        if env.storage().persistent().has(&ReferralKey::Code(code.clone())) {
+            return Err(ReferralError::CodeTaken);
+        }
+        env.storage().persistent().set(&ReferralKey::Code(code.clone()), &referrer);
+        env.storage().persistent().set(&ReferralKey::CodeOf(referrer.clone()), &code);
+        env.events().publish((Symbol::new(&env, "referral_code"), referrer), code);
+        Ok(())
+    }
+
+    // Bind `referee` to the owner of `code`. Only accounts that have never held PI can
+    // bind, so the binding is in place by the referee's first mint.
+    pub fn bind(env: Env, referee: Address, code: Symbol) -> Result<(), ReferralError> {
+        referee.require_auth();
+        let data = Self::load(&env);
+        let referrer = Self::referrer_of(env.clone(), code.clone()).ok_or(ReferralError::UnknownCode)?;
+        if referrer == referee {
+            return Err(ReferralError::SelfReferral);
+        }
+        if env.storage().persistent().has(&ReferralKey::Referral(referee.clone())) {
+            return Err(ReferralError::AlreadyBound);
+        }
+        if PiProvenanceClient::new(&env, &data.pi_token).balance(&referee) != 0 {
+            return Err(ReferralError::AlreadyMinted);
+        }
+        let referral = Referral { code, referrer: referrer.clone(), activated: false };
+        env.storage().persistent().set(&ReferralKey::Referral(referee.clone()), &referral);
+        env.events().publish((Symbol::new(&env, "referral_bind"), referee), referrer);
+        Ok(())
+    }
+
+    // Permissionless: Credit points to `referee` and its referrer once the referee holds
+    // PI from a provenance-verified source. The referrer must still have `min_stake`.
+    pub fn activate(env: Env, referee: Address) -> Result<(), ReferralError> {
+        let data = Self::load(&env);
+        let mut referral = Self::referral_of(env.clone(), referee.clone()).ok_or(ReferralError::NotBound)?;
+        if referral.activated {
+            return Err(ReferralError::AlreadyActivated);
+        }
+        let pi = PiProvenanceClient::new(&env, &data.pi_token);
+        if pi.balance(&referee) <= 0 || !pi.verify_ecosystem_entry(&referee) {
+            return Err(ReferralError::NotVerified);
+        }
+        Self::require_stake(&env, &data, &referral.referrer)?;
+
+        referral.activated = true;
+        env.storage().persistent().set(&ReferralKey::Referral(referee.clone()), &referral);
+        Self::credit(&env, &referral.referrer, data.config.referrer_points);
+        Self::credit(&env, &referee, data.config.referee_points);
+        env.events().publish(
+            (Symbol::new(&env, "referral_activate"), referee, referral.referrer),
+            (data.config.referrer_points, data.config.referee_points),
+        );
+        Ok(())
+    }
+
+    // Convert `points` of `account`'s points to PI at `pi_per_point`; returns the PI paid.
+    // Fails once the payout would pass the emission cap.
+    pub fn redeem(env: Env, account: Address, points: i128) -> Result<i128, ReferralError> {
+        account.require_auth();
+        if points <= 0 {
+            return Err(ReferralError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        let balance = Self::points_of(env.clone(), account.clone());
+        if points > balance {
+            return Err(ReferralError::InsufficientPoints);
+        }
+        let amount = points.checked_mul(data.config.pi_per_point).ok_or(ReferralError::InvalidAmount)?;
+        let emitted = data.emitted.checked_add(amount).ok_or(ReferralError::InvalidAmount)?;
+        if emitted > data.config.emission_cap {
+            return Err(ReferralError::BudgetExhausted);
+        }
+        data.emitted = emitted;
+        Self::save(&env, &data);
+        env.storage().persistent().set(&ReferralKey::Points(account.clone()), &(balance - points));
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &account, &amount);
+        env.events().publish((Symbol::new(&env, "referral_redeem"), account), (points, amount));
+        Ok(amount)
+    }
+
+    // Governance-only: Replace the config. The emission cap cannot drop below what has
+    // already been paid out.
+    pub fn set_config(env: Env, config: ReferralConfig) -> Result<(), ReferralError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        Self::validate(&config)?;
+        if config.emission_cap < data.emitted {
+            return Err(ReferralError::InvalidConfig);
+        }
+        data.config = config;
+        Self::save(&env, &data);
+        Ok(())
+    }
+
+    // View: Owner of `code`, if registered
+    pub fn referrer_of(env: Env, code: Symbol) -> Option<Address> {
+        env.storage().persistent().get(&ReferralKey::Code(code))
+    }
+
+    // View: `referrer`'s code, if registered
+    pub fn code_of(env: Env, referrer: Address) -> Option<Symbol> {
+        env.storage().persistent().get(&ReferralKey::CodeOf(referrer))
+    }
+
+    // View: `referee`'s binding, if any
+    pub fn referral_of(env: Env, referee: Address) -> Option<Referral> {
+        env.storage().persistent().get(&ReferralKey::Referral(referee))
+    }
+
+    // View: Unredeemed points of `account`
+    pub fn points_of(env: Env, account: Address) -> i128 {
+        env.storage().persistent().get(&ReferralKey::Points(account)).unwrap_or(0)
+    }
+
+    // View: PI left in the emission budget
+    pub fn remaining_budget(env: Env) -> i128 {
+        let data = Self::load(&env);
+        data.config.emission_cap - data.emitted
+    }
+
+    // View: Current config
+    pub fn config(env: Env) -> ReferralConfig {
+        Self::load(&env).config
+    }
+
+    // Helper: `account`'s governance stake as checkpointed before this ledger, so stake
+    // added in the same ledger does not count
+    fn require_stake(env: &Env, data: &ReferralData, account: &Address) -> Result<(), ReferralError> {
+        if StakingClient::new(env, &data.staking).stake_at(account, &env.ledger().sequence()) < data.config.min_stake {
+            return Err(ReferralError::InsufficientStake);
+        }
+        Ok(())
+    }
+
+    fn credit(env: &Env, account: &Address, points: i128) {
+        let balance = Self::points_of(env.clone(), account.clone());
+        env.storage().persistent().set(&ReferralKey::Points(account.clone()), &(balance + points));
+    }
+
+    // Helper: Non-negative amounts and a positive PI price per point
+    fn validate(config: &ReferralConfig) -> Result<(), ReferralError> {
+        if config.min_stake < 0 || config.referrer_points < 0 || config.referee_points < 0 || config.pi_per_point <= 0 || config.emission_cap < 0 {
+            return Err(ReferralError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn save(env: &Env, data: &ReferralData) {
+        env.storage().instance().set(&Symbol::new(env, "referral_data"), data);
+    }
+
+    fn load(env: &Env) -> ReferralData {
+        env.storage().instance().get(&Symbol::new(env, "referral_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,117 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, contracttype, testutils::{Address as _, Ledger}, Address, Env, Symbol};
+use crate::{PiCoinReferral, PiCoinReferralClient, Referral, ReferralConfig, ReferralError};
+
+#[contracttype]
+enum MockKey {
+    Balance(Address),
+    Verified(Address),
+    Stake(Address),
+}
+
+// Just enough of PiCoinContract: balances, transfers and a settable provenance check
+#[contract]
+struct MockPi;
+
+#[contractimpl]
+impl MockPi {
+    pub fn mint(env: Env, to: Address, amount: i128, verified: bool) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&MockKey::Balance(to.clone()), &(balance + amount));
+        env.storage().instance().set(&MockKey::Verified(to), &verified);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&MockKey::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let (from_balance, to_balance) = (Self::balance(env.clone(), from.clone()), Self::balance(env.clone(), to.clone()));
+        env.storage().instance().set(&MockKey::Balance(from), &(from_balance - amount));
+        env.storage().instance().set(&MockKey::Balance(to), &(to_balance + amount));
+    }
+
+    pub fn verify_ecosystem_entry(env: Env, holder: Address) -> bool {
+        env.storage().instance().get(&MockKey::Verified(holder)).unwrap_or(false)
+    }
+}
+
+// PiCoinGovernance's stake checkpoints, answering the latest stake set for any ledger
+#[contract]
+struct MockStaking;
+
+#[contractimpl]
+impl MockStaking {
+    pub fn set_stake(env: Env, voter: Address, amount: i128) {
+        env.storage().instance().set(&MockKey::Stake(voter), &amount);
+    }
+
+    pub fn stake_at(env: Env, voter: Address, _ledger: u32) -> i128 {
+        env.storage().instance().get(&MockKey::Stake(voter)).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_stake_gated_referrals_earn_points_redeemable_up_to_the_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let pi = MockPiClient::new(&env, &env.register(MockPi, ()));
+    let staking = MockStakingClient::new(&env, &env.register(MockStaking, ()));
+    let (alice, bob, carol) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    let code = Symbol::new(&env, "ALICE");
+
+    // 100 points to the referrer and 50 to the referee, 2 PI a point, 450 PI in all
+    let config = ReferralConfig { min_stake: 1_000, referrer_points: 100, referee_points: 50, pi_per_point: 2, emission_cap: 450 };
+    let contract_id = env.register(PiCoinReferral, (Address::generate(&env), &pi.address, &staking.address, config.clone()));
+    let client = PiCoinReferralClient::new(&env, &contract_id);
+    let free_points = ReferralConfig { pi_per_point: 0, ..config };
+    assert!(matches!(client.try_set_config(&free_points), Err(Ok(ReferralError::InvalidConfig))));
+    pi.mint(&contract_id, &1_000, &true);
+
+    // Registering a code takes the minimum stake
+    assert!(matches!(client.try_register_code(&alice, &code), Err(Ok(ReferralError::InsufficientStake))));
+    staking.set_stake(&alice, &1_000);
+    client.register_code(&alice, &code);
+    assert!(matches!(client.try_register_code(&alice, &Symbol::new(&env, "AGAIN")), Err(Ok(ReferralError::AlreadyRegistered))));
+    staking.set_stake(&carol, &1_000);
+    assert!(matches!(client.try_register_code(&carol, &code), Err(Ok(ReferralError::CodeTaken))));
+    assert_eq!(client.referrer_of(&code), Some(alice.clone()));
+
+    // Referees bind before their first mint, and never to themselves
+    assert!(matches!(client.try_bind(&alice, &code), Err(Ok(ReferralError::SelfReferral))));
+    pi.mint(&carol, &10, &true);
+    assert!(matches!(client.try_bind(&carol, &code), Err(Ok(ReferralError::AlreadyMinted))));
+    client.bind(&bob, &code);
+    assert!(matches!(client.try_bind(&bob, &code), Err(Ok(ReferralError::AlreadyBound))));
+    assert_eq!(client.referral_of(&bob), Some(Referral { code: code.clone(), referrer: alice.clone(), activated: false }));
+
+    // Points wait for a mint from a verified source
+    assert!(matches!(client.try_activate(&bob), Err(Ok(ReferralError::NotVerified))));
+    pi.mint(&bob, &10, &false);
+    assert!(matches!(client.try_activate(&bob), Err(Ok(ReferralError::NotVerified))));
+    pi.mint(&bob, &10, &true);
+    client.activate(&bob);
+    assert!(matches!(client.try_activate(&bob), Err(Ok(ReferralError::AlreadyActivated))));
+    assert_eq!((client.points_of(&alice), client.points_of(&bob)), (100, 50));
+
+    // A referrer who unstakes earns nothing more
+    let dave = Address::generate(&env);
+    client.bind(&dave, &code);
+    pi.mint(&dave, &10, &true);
+    staking.set_stake(&alice, &999);
+    assert!(matches!(client.try_activate(&dave), Err(Ok(ReferralError::InsufficientStake))));
+    staking.set_stake(&alice, &1_000);
+    client.activate(&dave);
+
+    // 300 points earned at 2 PI each, against a 450 PI cap
+    assert_eq!(client.redeem(&alice, &150), 300);
+    assert_eq!(pi.balance(&alice), 300);
+    assert_eq!(client.remaining_budget(), 150);
+    assert!(matches!(client.try_redeem(&bob, &51), Err(Ok(ReferralError::InsufficientPoints))));
+    assert_eq!(client.redeem(&bob, &50), 100);
+    assert!(matches!(client.try_redeem(&alice, &50), Err(Ok(ReferralError::BudgetExhausted))));
+    assert_eq!(client.points_of(&alice), 50);
+}
@@ -0,0 +1,108 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, String, Symbol, Vec, log};
+
+// Well-known names of the core ecosystem contracts
+pub const TOKEN: &str = "pi.token";
+pub const ORACLE: &str = "pi.oracle";
+pub const GOVERNANCE: &str = "pi.governance";
+pub const TREASURY: &str = "pi.treasury";
+
+// Longest name the registry accepts, in bytes
+pub const MAX_NAME_LEN: u32 = 32;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RegistryData {
+    pub governance: Address, // Registers and repoints names
+    pub names: Vec<String>, // Every name ever registered, in registration order
+}
+
+// Current binding of a name
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistryEntry {
+    pub address: Address,
+    pub version: u32, // 1 for the first binding, +1 for each repoint
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RegistryKey {
+    Entry(String), // Persistent: name -> current RegistryEntry
+    Version(String, u32), // Persistent: (name, version) -> address bound at that version
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RegistryError {
+    NotFound = 1,
+    InvalidName = 2,
+    Unchanged = 3, // The name already points at this address
+}
+
+#[contract]
+pub struct PiCoinRegistry;
+
+#[contractimpl]
+impl PiCoinRegistry {
+    // Constructor: name -> address registry for the ecosystem contracts. Downstream
+    // contracts resolve "pi.token", "pi.oracle" and the like at call time, so a contract
+    // replaced by governance is picked up everywhere without re-wiring its dependents.
+    pub fn __constructor(env: Env, governance: Address) {
+        let data = RegistryData { governance, names: Vec::new(&env) };
+        env.storage().instance().set(&Symbol::new(&env, "registry_data"), &data);
+        log!(&env, "Contract registry initialized: governance-managed name resolution");
+    }
+
+    // Governance-only: Point `name` at `address`; returns the new version. Earlier
+    // versions stay resolvable through `resolve_version`.
+    pub fn set(env: Env, name: String, address: Address) -> Result<u32, RegistryError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(RegistryError::InvalidName);
+        }
+        let version = match Self::entry(env.clone(), name.clone()) {
+            Some(entry) if entry.address == address => return Err(RegistryError::Unchanged),
+            Some(entry) => entry.version + 1,
+            None => {
+                data.names.push_back(name.clone());
+                env.storage().instance().set(&Symbol::new(&env, "registry_data"), &data);
+                1
+            }
+        };
+        let entry = RegistryEntry { address: address.clone(), version, updated_at: env.ledger().timestamp() };
+        env.storage().persistent().set(&RegistryKey::Entry(name.clone()), &entry);
+        env.storage().persistent().set(&RegistryKey::Version(name.clone(), version), &address);
+        env.events().publish((Symbol::new(&env, "registry_set"), name), (version, address));
+        Ok(version)
+    }
+
+    // View: Address `name` currently points at
+    pub fn resolve(env: Env, name: String) -> Result<Address, RegistryError> {
+        Self::entry(env, name).map(|entry| entry.address).ok_or(RegistryError::NotFound)
+    }
+
+    // View: Address `name` pointed at in `version`
+    pub fn resolve_version(env: Env, name: String, version: u32) -> Result<Address, RegistryError> {
+        env.storage().persistent().get(&RegistryKey::Version(name, version)).ok_or(RegistryError::NotFound)
+    }
+
+    // View: Current binding of `name`, with its version
+    pub fn entry(env: Env, name: String) -> Option<RegistryEntry> {
+        env.storage().persistent().get(&RegistryKey::Entry(name))
+    }
+
+    // View: Every registered name
+    pub fn names(env: Env) -> Vec<String> {
+        Self::load(&env).names
+    }
+
+    fn load(env: &Env) -> RegistryData {
+        env.storage().instance().get(&Symbol::new(env, "registry_data")).unwrap()
+    }
+}
+
+mod test;
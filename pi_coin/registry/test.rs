@@ -0,0 +1,40 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events}, vec, Address, Env, IntoVal, String, Symbol};
+use crate::{PiCoinRegistry, PiCoinRegistryClient, RegistryError, ORACLE, TOKEN};
+
+#[test]
+fn test_names_resolve_to_the_latest_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinRegistry, (&governance,));
+    let client = PiCoinRegistryClient::new(&env, &contract_id);
+    let token = String::from_str(&env, TOKEN);
+    assert!(matches!(client.try_resolve(&token), Err(Ok(RegistryError::NotFound))));
+
+    let (v1, v2) = (Address::generate(&env), Address::generate(&env));
+    assert_eq!(client.set(&token, &v1), 1);
+    assert_eq!(env.auths()[0].0, governance);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "registry_set"), token.clone()).into_val(&env),
+                (1u32, v1.clone()).into_val(&env),
+            ),
+        ]
+    );
+    assert!(matches!(client.try_set(&token, &v1), Err(Ok(RegistryError::Unchanged))));
+
+    // An upgrade that replaces the contract: dependents resolve the new address
+    assert_eq!(client.set(&token, &v2), 2);
+    assert_eq!(client.resolve(&token), v2);
+    assert_eq!(client.resolve_version(&token, &1), v1);
+    assert_eq!(client.entry(&token).unwrap().version, 2);
+
+    client.set(&String::from_str(&env, ORACLE), &Address::generate(&env));
+    assert_eq!(client.names(), vec![&env, token, String::from_str(&env, ORACLE)]);
+    assert!(matches!(client.try_set(&String::from_str(&env, ""), &v1), Err(Ok(RegistryError::InvalidName))));
+}
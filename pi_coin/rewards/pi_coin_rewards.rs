@@ -0,0 +1,211 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Fixed-point scale of the reward-per-share accumulator
+pub const ACC_ONE: i128 = 1_000_000_000_000;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PoolData {
+    pub governance: Address, // Sets emission schedules
+    pub pi_token: Address, // Staked and emitted
+    pub total_staked: i128,
+    pub reward_per_share: i128, // PI emitted per staked PI since launch, scaled by ACC_ONE
+    pub last_update: u64, // Timestamp the accumulator was last brought up to date
+    pub rate_per_second: i128, // Emission rate of the current schedule
+    pub period_finish: u64, // Emissions stop at this timestamp
+    pub owed: i128, // Emitted but not yet claimed
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct StakerInfo {
+    pub stake: i128,
+    pub reward_per_share_paid: i128, // Accumulator value already credited to `rewards`
+    pub rewards: i128, // Earned, not yet claimed
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RewardsKey {
+    Staker(Address),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RewardsError {
+    InvalidAmount = 1,
+    InsufficientStake = 2,
+    NothingToClaim = 3,
+    InsufficientRewards = 4,
+}
+
+#[contract]
+pub struct PiCoinRewards;
+
+#[contractimpl]
+impl PiCoinRewards {
+    // Constructor: rewards pool in which PI stakers share governance-scheduled PI
+    // emissions pro rata. A reward-per-share accumulator keeps every action O(1)
+    // however many stakers there are. Emissions are paid from PI sent to the pool
+    // (e.g. a treasury spend) beyond the stakes and unclaimed rewards it holds.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address) {
+        let data = PoolData {
+            governance,
+            pi_token,
+            total_staked: 0,
+            reward_per_share: 0,
+            last_update: env.ledger().timestamp(),
+            rate_per_second: 0,
+            period_finish: 0,
+            owed: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "pool_data"), &data);
+        log!(&env, "Rewards pool initialized: PI emissions for stakers");
+    }
+
+    // Stake `amount` PI. The stake is what the pool actually received, net of any PI
+    // transfer fee.
+    pub fn stake(env: Env, staker: Address, amount: i128) -> Result<(), RewardsError> {
+        staker.require_auth();
+        if amount <= 0 {
+            return Err(RewardsError::InvalidAmount);
+        }
+        let mut data = Self::update(&env);
+        let mut info = Self::settle(&env, &data, &staker);
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let before = pi.balance(&env.current_contract_address());
+        pi.transfer(&staker, env.current_contract_address(), &amount);
+        let amount = pi.balance(&env.current_contract_address()) - before;
+        if amount <= 0 {
+            return Err(RewardsError::InvalidAmount);
+        }
+        info.stake += amount;
+        data.total_staked += amount;
+        Self::save(&env, &data, &staker, &info);
+        env.events().publish((Symbol::new(&env, "reward_staked"), staker), amount);
+        Ok(())
+    }
+
+    // Withdraw `amount` of the caller's stake; earned rewards stay claimable
+    pub fn withdraw(env: Env, staker: Address, amount: i128) -> Result<(), RewardsError> {
+        staker.require_auth();
+        if amount <= 0 {
+            return Err(RewardsError::InvalidAmount);
+        }
+        let mut data = Self::update(&env);
+        let mut info = Self::settle(&env, &data, &staker);
+        if info.stake < amount {
+            return Err(RewardsError::InsufficientStake);
+        }
+        info.stake -= amount;
+        data.total_staked -= amount;
+        Self::save(&env, &data, &staker, &info);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &staker, &amount);
+        env.events().publish((Symbol::new(&env, "reward_withdrawn"), staker), amount);
+        Ok(())
+    }
+
+    // Pay out everything the caller has earned; returns the amount
+    pub fn claim_rewards(env: Env, staker: Address) -> Result<i128, RewardsError> {
+        staker.require_auth();
+        let mut data = Self::update(&env);
+        let mut info = Self::settle(&env, &data, &staker);
+        let amount = info.rewards;
+        if amount == 0 {
+            return Err(RewardsError::NothingToClaim);
+        }
+        info.rewards = 0;
+        data.owed -= amount;
+        Self::save(&env, &data, &staker, &info);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &staker, &amount);
+        env.events().publish((Symbol::new(&env, "rewards_claimed"), staker), amount);
+        Ok(amount)
+    }
+
+    // Governance-only: Emit `rate_per_second` PI for the next `duration` seconds, replacing
+    // the current schedule (its unemitted remainder is released). The pool must already
+    // hold the whole schedule beyond stakes and unclaimed rewards.
+    pub fn set_schedule(env: Env, rate_per_second: i128, duration: u64) -> Result<(), RewardsError> {
+        let mut data = Self::update(&env);
+        data.governance.require_auth();
+        if rate_per_second < 0 || duration == 0 {
+            return Err(RewardsError::InvalidAmount);
+        }
+        let balance = token::TokenClient::new(&env, &data.pi_token).balance(&env.current_contract_address());
+        if rate_per_second * duration as i128 > balance - data.total_staked - data.owed {
+            return Err(RewardsError::InsufficientRewards);
+        }
+        data.rate_per_second = rate_per_second;
+        data.period_finish = env.ledger().timestamp() + duration;
+        env.storage().instance().set(&Symbol::new(&env, "pool_data"), &data);
+        env.events().publish((Symbol::new(&env, "emission_schedule"),), (rate_per_second, data.period_finish));
+        Ok(())
+    }
+
+    // View: Rewards `staker` could claim right now
+    pub fn earned(env: Env, staker: Address) -> i128 {
+        let data = Self::accrued(&env, Self::load(&env));
+        Self::settled(&data, &Self::staker(env.clone(), staker)).rewards
+    }
+
+    // View: Stake and reward bookkeeping of `staker`
+    pub fn staker(env: Env, staker: Address) -> StakerInfo {
+        env.storage().persistent().get(&RewardsKey::Staker(staker)).unwrap_or(StakerInfo {
+            stake: 0,
+            reward_per_share_paid: 0,
+            rewards: 0,
+        })
+    }
+
+    // View: Pool totals, accumulator and schedule, brought up to now
+    pub fn pool(env: Env) -> PoolData {
+        Self::accrued(&env, Self::load(&env))
+    }
+
+    // Helper: Advance the accumulator over the time emitted since the last update
+    fn accrued(env: &Env, mut data: PoolData) -> PoolData {
+        let until = env.ledger().timestamp().min(data.period_finish);
+        if until > data.last_update && data.total_staked > 0 {
+            let emitted = data.rate_per_second * (until - data.last_update) as i128;
+            data.reward_per_share += emitted * ACC_ONE / data.total_staked;
+            data.owed += emitted;
+        }
+        data.last_update = env.ledger().timestamp();
+        data
+    }
+
+    // Helper: Credit what `info` earned since it was last settled
+    fn settled(data: &PoolData, info: &StakerInfo) -> StakerInfo {
+        StakerInfo {
+            stake: info.stake,
+            reward_per_share_paid: data.reward_per_share,
+            rewards: info.rewards + info.stake * (data.reward_per_share - info.reward_per_share_paid) / ACC_ONE,
+        }
+    }
+
+    fn update(env: &Env) -> PoolData {
+        Self::accrued(env, Self::load(env))
+    }
+
+    fn settle(env: &Env, data: &PoolData, staker: &Address) -> StakerInfo {
+        Self::settled(data, &Self::staker(env.clone(), staker.clone()))
+    }
+
+    fn save(env: &Env, data: &PoolData, staker: &Address, info: &StakerInfo) {
+        env.storage().instance().set(&Symbol::new(env, "pool_data"), data);
+        let key = RewardsKey::Staker(staker.clone());
+        if info.stake == 0 && info.rewards == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, info);
+        }
+    }
+
+    fn load(env: &Env) -> PoolData {
+        env.storage().instance().get(&Symbol::new(env, "pool_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,103 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env};
+use crate::{PiCoinRewards, PiCoinRewardsClient, RewardsError};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+#[test]
+fn test_emissions_split_pro_rata_over_stake_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    pi.mint(&alice, &100, &PiCoinSource::Mining);
+    pi.mint(&bob, &400, &PiCoinSource::Mining);
+
+    let contract_id = env.register(PiCoinRewards, (Address::generate(&env), &pi.address));
+    let client = PiCoinRewardsClient::new(&env, &contract_id);
+    client.stake(&alice, &100);
+
+    // The schedule must be funded up front: 10 PI a second for 100 seconds
+    pi.mint(&contract_id, &999, &PiCoinSource::Rewards);
+    assert_eq!(client.try_set_schedule(&10, &100), Err(Ok(RewardsError::InsufficientRewards)));
+    pi.mint(&contract_id, &1, &PiCoinSource::Rewards);
+    client.set_schedule(&10, &100);
+
+    // Alice earns alone for 10s, then shares 1:4 with Bob for 20s
+    env.ledger().set_timestamp(10);
+    client.stake(&bob, &400);
+    env.ledger().set_timestamp(30);
+    assert_eq!((client.earned(&alice), client.earned(&bob)), (140, 160));
+
+    // Leaving keeps what was earned; Bob takes the rest of the schedule
+    client.withdraw(&alice, &100);
+    assert_eq!(client.try_withdraw(&alice, &1), Err(Ok(RewardsError::InsufficientStake)));
+    env.ledger().set_timestamp(50);
+    assert_eq!(client.claim_rewards(&alice), 140);
+    assert_eq!(client.try_claim_rewards(&alice), Err(Ok(RewardsError::NothingToClaim)));
+    env.ledger().set_timestamp(500);
+    assert_eq!(client.earned(&bob), 860);
+    assert_eq!(client.claim_rewards(&bob), 860);
+    client.withdraw(&bob, &400);
+
+    assert_eq!((pi.balance(&alice), pi.balance(&bob)), (240, 1_260));
+    assert_eq!(pi.balance(&contract_id), 0);
+    assert_eq!(client.pool().owed, 0);
+}
+
+#[test]
+fn test_stake_is_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinRewards, (Address::generate(&env), &pi.address));
+    let client = PiCoinRewardsClient::new(&env, &contract_id);
+
+    // At a 1% fee a 1_000 stake credits 990, all of which can be withdrawn again
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    client.stake(&alice, &1_000);
+    assert_eq!((client.staker(&alice).stake, client.pool().total_staked), (990, 990));
+    client.withdraw(&alice, &990);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_ended_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &100, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinRewards, (Address::generate(&env), &pi.address));
+    let client = PiCoinRewardsClient::new(&env, &contract_id);
+    pi.mint(&contract_id, &100, &PiCoinSource::Rewards);
+
+    // Staking, withdrawing and scheduling each need the right signature
+    env.set_auths(&[]);
+    assert!(client.try_stake(&alice, &100).is_err());
+    assert!(client.try_set_schedule(&1, &100).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.try_stake(&alice, &0), Err(Ok(RewardsError::InvalidAmount)));
+    assert!(client.try_stake(&alice, &101).is_err());
+    client.stake(&alice, &100);
+    env.set_auths(&[]);
+    assert!(client.try_withdraw(&alice, &100).is_err());
+    env.mock_all_auths();
+
+    // Stakes and the schedule's own funding cannot be promised twice
+    client.set_schedule(&1, &100);
+    assert_eq!(client.try_set_schedule(&2, &100), Err(Ok(RewardsError::InsufficientRewards)));
+
+    // After the schedule ends nothing more accrues
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(client.claim_rewards(&alice), 100);
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(client.try_claim_rewards(&alice), Err(Ok(RewardsError::NothingToClaim)));
+    assert_eq!(client.try_withdraw(&alice, &101), Err(Ok(RewardsError::InsufficientStake)));
+}
@@ -0,0 +1,267 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Fixed-point scale of the exchange rate (PI per savings share)
+pub const RATE_ONE: i128 = 1_000_000_000_000;
+// Seconds per year the annual savings rate is spread over
+pub const YEAR: u64 = 365 * 24 * 60 * 60;
+// Highest savings rate governance may set (100% a year)
+pub const MAX_RATE_BPS: u32 = 10_000;
+const DAY: u64 = 24 * 60 * 60;
+
+// Entry point of PiCoinRateModel the savings rate can follow instead of a fixed rate
+#[contractclient(name = "RateModelClient")]
+pub trait RateModel {
+    fn borrow_rate(env: Env, cash: i128, borrows: i128) -> u32;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SavingsData {
+    pub governance: Address, // Sets the savings rate
+    pub pi_token: Address,
+    pub rate_bps: u32, // Annual savings rate in basis points
+    pub exchange_rate: i128, // PI per share, scaled by RATE_ONE; only ever grows
+    pub last_drip: u64, // Timestamp yield was last accrued into the exchange rate
+    pub total_shares: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SavingsKey {
+    Shares(Address), // Holder -> Savings shares
+    Checkpoint(u64), // Day (timestamp / 86_400) -> Exchange rate at that day's last drip
+    RateModel, // Instance: rate model contract the savings rate follows, if any
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SavingsError {
+    InvalidAmount = 1,
+    InsufficientShares = 2,
+    InvalidRate = 3,
+}
+
+#[contract]
+pub struct PiCoinSavings;
+
+#[contractimpl]
+impl PiCoinSavings {
+    // Constructor: savings rate for `pi_token`. Deposits are shares whose exchange rate
+    // grows continuously at `rate_bps` a year. Yield comes out of the reserve - PI held
+    // beyond what depositors are owed, topped up by treasury spends to this contract or
+    // stability fees - and stops accruing while it is empty, so withdrawals stay covered.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, rate_bps: u32) {
+        let data = SavingsData {
+            governance,
+            pi_token,
+            rate_bps: rate_bps.min(MAX_RATE_BPS),
+            exchange_rate: RATE_ONE,
+            last_drip: env.ledger().timestamp(),
+            total_shares: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "savings_data"), &data);
+        log!(&env, "Savings initialized: PI deposits earn the protocol savings rate");
+    }
+
+    // Deposit `amount` PI; returns the shares credited. Shares are bought with what the
+    // contract actually received, net of any PI transfer fee, so the fee comes out of the
+    // depositor's position rather than the reserve.
+    pub fn deposit(env: Env, from: Address, amount: i128) -> Result<i128, SavingsError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(SavingsError::InvalidAmount);
+        }
+        let mut data = Self::drip_data(&env);
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let before = pi.balance(&env.current_contract_address());
+        pi.transfer(&from, env.current_contract_address(), &amount);
+        let amount = pi.balance(&env.current_contract_address()) - before;
+        let shares = amount * RATE_ONE / data.exchange_rate;
+        if shares <= 0 {
+            return Err(SavingsError::InvalidAmount);
+        }
+        data.total_shares += shares;
+        Self::write_shares(&env, &from, Self::shares_of(env.clone(), from.clone()) + shares);
+        env.storage().instance().set(&Symbol::new(&env, "savings_data"), &data);
+        env.events().publish((Symbol::new(&env, "savings_deposit"), from), (amount, shares));
+        Ok(shares)
+    }
+
+    // Withdraw `amount` PI, principal and yield alike; returns the shares burned
+    pub fn withdraw(env: Env, owner: Address, amount: i128) -> Result<i128, SavingsError> {
+        owner.require_auth();
+        if amount <= 0 {
+            return Err(SavingsError::InvalidAmount);
+        }
+        let data = Self::drip_data(&env);
+        // Round the shares up so a withdrawal never takes more than it pays for
+        let shares = (amount * RATE_ONE + data.exchange_rate - 1) / data.exchange_rate;
+        Self::redeem(&env, data, &owner, shares, amount)?;
+        Ok(shares)
+    }
+
+    // Withdraw the whole position; returns the PI paid out
+    pub fn exit(env: Env, owner: Address) -> Result<i128, SavingsError> {
+        owner.require_auth();
+        let data = Self::drip_data(&env);
+        let shares = Self::shares_of(env.clone(), owner.clone());
+        let amount = shares * data.exchange_rate / RATE_ONE;
+        Self::redeem(&env, data, &owner, shares, amount)?;
+        Ok(amount)
+    }
+
+    // Accrue yield into the exchange rate and checkpoint it (permissionless); returns the
+    // exchange rate
+    pub fn drip(env: Env) -> i128 {
+        let data = Self::drip_data(&env);
+        env.storage().instance().set(&Symbol::new(&env, "savings_data"), &data);
+        data.exchange_rate
+    }
+
+    // Governance-only: Set the annual savings rate. Yield up to now accrues at the old rate.
+    pub fn set_rate(env: Env, rate_bps: u32) -> Result<(), SavingsError> {
+        let mut data = Self::drip_data(&env);
+        data.governance.require_auth();
+        if rate_bps > MAX_RATE_BPS {
+            return Err(SavingsError::InvalidRate);
+        }
+        data.rate_bps = rate_bps;
+        env.storage().instance().set(&Symbol::new(&env, "savings_data"), &data);
+        env.events().publish((Symbol::new(&env, "savings_rate"),), rate_bps);
+        Ok(())
+    }
+
+    // Governance-only: Follow `model`'s curve instead of the fixed rate, or go back to the
+    // fixed rate with None. Yield up to now accrues at the old rate. The vault reports what
+    // depositors are owed as the model's cash and its reserve as borrows, so the rate
+    // rises while the reserve is well funded and falls back toward the base as it runs down.
+    pub fn set_rate_model(env: Env, model: Option<Address>) -> Result<(), SavingsError> {
+        let data = Self::drip_data(&env);
+        data.governance.require_auth();
+        match &model {
+            Some(model) => env.storage().instance().set(&SavingsKey::RateModel, model),
+            None => env.storage().instance().remove(&SavingsKey::RateModel),
+        }
+        env.storage().instance().set(&Symbol::new(&env, "savings_data"), &data);
+        env.events().publish((Symbol::new(&env, "savings_rate_model"),), model);
+        Ok(())
+    }
+
+    // View: Rate model the savings rate follows, if any
+    pub fn rate_model(env: Env) -> Option<Address> {
+        env.storage().instance().get(&SavingsKey::RateModel)
+    }
+
+    // View: PI `owner` would receive from `exit` right now, yield included
+    pub fn preview_withdraw(env: Env, owner: Address) -> i128 {
+        let (exchange_rate, _) = Self::accrue(&env, &Self::load(&env));
+        Self::shares_of(env, owner) * exchange_rate / RATE_ONE
+    }
+
+    // View: Shares `deposit(amount)` would credit right now, before any PI transfer fee
+    pub fn preview_deposit(env: Env, amount: i128) -> i128 {
+        let (exchange_rate, _) = Self::accrue(&env, &Self::load(&env));
+        amount * RATE_ONE / exchange_rate
+    }
+
+    // View: Current PI per share, scaled by RATE_ONE, including yield not yet dripped
+    pub fn exchange_rate(env: Env) -> i128 {
+        Self::accrue(&env, &Self::load(&env)).0
+    }
+
+    // View: Exchange rate recorded by the last drip of `day` (timestamp / 86_400)
+    pub fn checkpoint(env: Env, day: u64) -> Option<i128> {
+        env.storage().persistent().get(&SavingsKey::Checkpoint(day))
+    }
+
+    // View: Annual savings rate in basis points: the fixed rate, or the rate model's at
+    // the current reserve
+    pub fn savings_rate(env: Env) -> u32 {
+        let data = Self::load(&env);
+        let assets = data.total_shares * data.exchange_rate / RATE_ONE;
+        Self::rate(&env, &data, assets, (Self::balance(&env, &data) - assets).max(0))
+    }
+
+    // View: PI held for yield beyond what depositors are owed
+    pub fn reserve(env: Env) -> i128 {
+        let data = Self::load(&env);
+        let (exchange_rate, _) = Self::accrue(&env, &data);
+        Self::balance(&env, &data) - data.total_shares * exchange_rate / RATE_ONE
+    }
+
+    // View: Savings shares held by `owner`
+    pub fn shares_of(env: Env, owner: Address) -> i128 {
+        env.storage().persistent().get(&SavingsKey::Shares(owner)).unwrap_or(0)
+    }
+
+    // Helper: Exchange rate and yield accrued since the last drip, capped by the reserve
+    fn accrue(env: &Env, data: &SavingsData) -> (i128, i128) {
+        let elapsed = env.ledger().timestamp().saturating_sub(data.last_drip) as i128;
+        let assets = data.total_shares * data.exchange_rate / RATE_ONE;
+        if elapsed == 0 || assets == 0 {
+            return (data.exchange_rate, 0);
+        }
+        let reserve = (Self::balance(env, data) - assets).max(0);
+        let owed = assets * Self::rate(env, data, assets, reserve) as i128 * elapsed / (10_000 * YEAR as i128);
+        let accrued = owed.min(reserve);
+        (data.exchange_rate + data.exchange_rate * accrued / assets, accrued)
+    }
+
+    // Helper: Annual rate for `assets` owed against `reserve`, from the rate model if one
+    // is set (capped at MAX_RATE_BPS) or the fixed rate otherwise
+    fn rate(env: &Env, data: &SavingsData, assets: i128, reserve: i128) -> u32 {
+        match env.storage().instance().get::<_, Address>(&SavingsKey::RateModel) {
+            Some(model) => RateModelClient::new(env, &model).borrow_rate(&assets, &reserve).min(MAX_RATE_BPS),
+            None => data.rate_bps,
+        }
+    }
+
+    // Helper: Load the data with yield accrued up to now, checkpointing the new rate
+    fn drip_data(env: &Env) -> SavingsData {
+        let mut data = Self::load(env);
+        let (exchange_rate, accrued) = Self::accrue(env, &data);
+        let now = env.ledger().timestamp();
+        data.exchange_rate = exchange_rate;
+        data.last_drip = now;
+        env.storage().persistent().set(&SavingsKey::Checkpoint(now / DAY), &exchange_rate);
+        if accrued > 0 {
+            env.events().publish((Symbol::new(env, "savings_drip"),), (accrued, exchange_rate));
+        }
+        data
+    }
+
+    // Helper: Burn `shares` of `owner` and pay out `amount`
+    fn redeem(env: &Env, mut data: SavingsData, owner: &Address, shares: i128, amount: i128) -> Result<(), SavingsError> {
+        let held = Self::shares_of(env.clone(), owner.clone());
+        if shares == 0 || shares > held {
+            return Err(SavingsError::InsufficientShares);
+        }
+        data.total_shares -= shares;
+        Self::write_shares(env, owner, held - shares);
+        env.storage().instance().set(&Symbol::new(env, "savings_data"), &data);
+        token::TokenClient::new(env, &data.pi_token).transfer(&env.current_contract_address(), owner, &amount);
+        env.events().publish((Symbol::new(env, "savings_withdraw"), owner.clone()), (amount, shares));
+        Ok(())
+    }
+
+    fn write_shares(env: &Env, owner: &Address, shares: i128) {
+        let key = SavingsKey::Shares(owner.clone());
+        if shares == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &shares);
+        }
+    }
+
+    fn balance(env: &Env, data: &SavingsData) -> i128 {
+        token::TokenClient::new(env, &data.pi_token).balance(&env.current_contract_address())
+    }
+
+    fn load(env: &Env) -> SavingsData {
+        env.storage().instance().get(&Symbol::new(env, "savings_data")).unwrap()
+    }
+}
+
+mod test;
@@ -0,0 +1,149 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Ledger}, Address, Env};
+use crate::{PiCoinSavings, PiCoinSavingsClient, SavingsError, RATE_ONE, YEAR};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+// Stand-in for PiCoinRateModel: a linear curve, the rate equal to the utilization
+#[contract]
+struct MockRateModel;
+
+#[contractimpl]
+impl MockRateModel {
+    pub fn borrow_rate(_env: Env, cash: i128, borrows: i128) -> u32 {
+        (borrows * 10_000 / (cash + borrows)) as u32
+    }
+}
+
+#[test]
+fn test_deposits_earn_the_savings_rate_from_the_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    pi.mint(&bob, &1_100, &PiCoinSource::Mining);
+
+    // 10% a year
+    let contract_id = env.register(PiCoinSavings, (Address::generate(&env), &pi.address, 1_000u32));
+    let client = PiCoinSavingsClient::new(&env, &contract_id);
+    assert_eq!(client.deposit(&alice, &1_000), 1_000);
+
+    // Treasury funds 100 PI of yield
+    pi.mint(&contract_id, &100, &PiCoinSource::Rewards);
+    assert_eq!(client.reserve(), 100);
+    env.ledger().set_timestamp(1_000 + YEAR / 2);
+    assert_eq!(client.preview_withdraw(&alice), 1_050);
+    env.ledger().set_timestamp(1_000 + YEAR);
+    assert_eq!(client.preview_withdraw(&alice), 1_100);
+    assert_eq!(client.drip(), RATE_ONE * 11 / 10);
+    assert_eq!(client.checkpoint(&((1_000 + YEAR) / 86_400)), Some(RATE_ONE * 11 / 10));
+
+    // New deposits buy in at the grown rate; with the reserve spent, yield stops
+    assert_eq!(client.preview_deposit(&1_100), 1_000);
+    client.deposit(&bob, &1_100);
+    assert_eq!(client.reserve(), 0);
+    env.ledger().set_timestamp(1_000 + 2 * YEAR);
+    assert_eq!(client.exchange_rate(), RATE_ONE * 11 / 10);
+
+    // Withdrawals pay principal plus yield
+    assert_eq!(client.withdraw(&bob, &100), 91);
+    assert_eq!(client.try_withdraw(&bob, &1_100), Err(Ok(SavingsError::InsufficientShares)));
+    assert_eq!(client.exit(&alice), 1_100);
+    assert_eq!(pi.balance(&alice), 1_100);
+    assert_eq!(client.shares_of(&alice), 0);
+
+    assert_eq!(client.try_set_rate(&10_001), Err(Ok(SavingsError::InvalidRate)));
+    client.set_rate(&500);
+    assert_eq!(client.savings_rate(), 500);
+}
+
+#[test]
+fn test_rate_model_sets_the_savings_rate_from_the_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+
+    let contract_id = env.register(PiCoinSavings, (Address::generate(&env), &pi.address, 1_000u32));
+    let client = PiCoinSavingsClient::new(&env, &contract_id);
+    let model = env.register(MockRateModel, ());
+    client.deposit(&alice, &1_000);
+    pi.mint(&contract_id, &1_000, &PiCoinSource::Rewards);
+    assert_eq!(client.savings_rate(), 1_000);
+
+    // Owed 1,000 against a 1,000 reserve: the model's utilization, and so rate, is 50%
+    client.set_rate_model(&Some(model.clone()));
+    assert_eq!(client.rate_model(), Some(model));
+    assert_eq!(client.savings_rate(), 5_000);
+    env.ledger().set_timestamp(1_000 + YEAR / 2);
+    assert_eq!(client.preview_withdraw(&alice), 1_250);
+
+    // The rate falls as the reserve is paid out
+    client.drip();
+    assert_eq!(client.reserve(), 750);
+    assert_eq!(client.savings_rate(), 3_750);
+
+    // Back to the fixed rate
+    client.set_rate_model(&None);
+    assert_eq!(client.rate_model(), None);
+    assert_eq!(client.savings_rate(), 1_000);
+}
+
+#[test]
+fn test_deposits_are_credited_net_of_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinSavings, (Address::generate(&env), &pi.address, 1_000u32));
+    let client = PiCoinSavingsClient::new(&env, &contract_id);
+
+    // At a 1% fee 990 arrives and buys 990 shares; the reserve is not drawn on to cover the fee
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    assert_eq!(client.deposit(&alice, &1_000), 990);
+    assert_eq!(client.reserve(), 0);
+    assert_eq!(client.preview_withdraw(&alice), 990);
+}
+
+#[test]
+fn test_rejects_unauthorized_and_overdrawn_withdrawals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinSavings, (Address::generate(&env), &pi.address, 1_000u32));
+    let client = PiCoinSavingsClient::new(&env, &contract_id);
+
+    // Deposits need a positive amount the depositor holds
+    assert_eq!(client.try_deposit(&alice, &0), Err(Ok(SavingsError::InvalidAmount)));
+    assert!(client.try_deposit(&alice, &1_001).is_err());
+    client.deposit(&alice, &600);
+
+    // Only the owner's signature withdraws, and only up to the position
+    env.set_auths(&[]);
+    assert!(client.try_withdraw(&alice, &100).is_err());
+    assert!(client.try_set_rate(&500).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.try_withdraw(&alice, &601), Err(Ok(SavingsError::InsufficientShares)));
+    assert_eq!(client.try_exit(&bob), Err(Ok(SavingsError::InsufficientShares)));
+
+    // An exited position is closed
+    assert_eq!(client.exit(&alice), 600);
+    assert_eq!(client.try_withdraw(&alice, &1), Err(Ok(SavingsError::InsufficientShares)));
+    assert_eq!(pi.balance(&alice), 1_000);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
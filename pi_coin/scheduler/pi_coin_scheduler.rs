@@ -0,0 +1,195 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, Vec, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SchedulerData {
+    pub governance: Address, // Sets the keeper fee
+    pub pi_token: Address,
+    pub keeper_fee: i128, // PI paid from the payer's balance to the keeper per executed payment
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Schedule {
+    pub payer: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub interval: u64, // Seconds between payments
+    pub next_due: u64, // Ledger timestamp the next payment is due
+    pub balance: i128, // Pre-funded PI left for payments and keeper fees
+    pub paused: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SchedulerKey {
+    NextId,
+    Schedule(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SchedulerError {
+    ScheduleNotFound = 1,
+    InvalidSchedule = 2,
+    InvalidFee = 3,
+}
+
+#[contract]
+pub struct PiCoinScheduler;
+
+#[contractimpl]
+impl PiCoinScheduler {
+    // Constructor: recurring PI payments triggered by permissionless keepers
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, keeper_fee: i128) {
+        let data = SchedulerData { governance, pi_token, keeper_fee };
+        env.storage().instance().set(&Symbol::new(&env, "scheduler_data"), &data);
+        log!(&env, "Scheduler initialized: recurring PI payments, keeper fee {}", keeper_fee);
+    }
+
+    // Payer: Register a payment of `amount` to `recipient` every `interval` seconds,
+    // first due at `first_due`, pre-funded with `deposit` PI. Deposits and top-ups are
+    // credited with what the contract actually received, net of any PI transfer fee.
+    // Returns the schedule id.
+    pub fn register(
+        env: Env,
+        payer: Address,
+        recipient: Address,
+        amount: i128,
+        interval: u64,
+        first_due: u64,
+        deposit: i128,
+    ) -> Result<u64, SchedulerError> {
+        payer.require_auth();
+        if amount <= 0 || interval == 0 || deposit < 0 {
+            return Err(SchedulerError::InvalidSchedule);
+        }
+        let deposit = if deposit > 0 { receive::pull(&env, &Self::pi(&env).address, &payer, deposit).unwrap_or(0) } else { 0 };
+        let id: u64 = env.storage().instance().get(&SchedulerKey::NextId).unwrap_or(1);
+        env.storage().instance().set(&SchedulerKey::NextId, &(id + 1));
+        let schedule = Schedule { payer: payer.clone(), recipient, amount, interval, next_due: first_due, balance: deposit, paused: false };
+        env.storage().persistent().set(&SchedulerKey::Schedule(id), &schedule);
+        env.events().publish((Symbol::new(&env, "schedule_registered"), payer, id), schedule);
+        Ok(id)
+    }
+
+    // Payer: Top up a schedule's balance
+    pub fn fund(env: Env, id: u64, amount: i128) -> Result<(), SchedulerError> {
+        let mut schedule = Self::load(&env, id)?;
+        schedule.payer.require_auth();
+        if amount <= 0 {
+            return Err(SchedulerError::InvalidSchedule);
+        }
+        schedule.balance += receive::pull(&env, &Self::pi(&env).address, &schedule.payer, amount).unwrap_or(0);
+        env.storage().persistent().set(&SchedulerKey::Schedule(id), &schedule);
+        Ok(())
+    }
+
+    // Payer: Pause or resume payments. Payments missed while paused are skipped, not
+    // made up on resume.
+    pub fn set_paused(env: Env, id: u64, paused: bool) -> Result<(), SchedulerError> {
+        let mut schedule = Self::load(&env, id)?;
+        schedule.payer.require_auth();
+        schedule.paused = paused;
+        if !paused {
+            let now = env.ledger().timestamp();
+            if schedule.next_due < now {
+                let missed = (now - schedule.next_due).div_ceil(schedule.interval);
+                schedule.next_due += missed * schedule.interval;
+            }
+        }
+        env.storage().persistent().set(&SchedulerKey::Schedule(id), &schedule);
+        env.events().publish((Symbol::new(&env, "schedule_paused"), id), paused);
+        Ok(())
+    }
+
+    // Payer: Cancel the schedule and get its remaining balance back
+    pub fn cancel(env: Env, id: u64) -> Result<i128, SchedulerError> {
+        let schedule = Self::load(&env, id)?;
+        schedule.payer.require_auth();
+        env.storage().persistent().remove(&SchedulerKey::Schedule(id));
+        if schedule.balance > 0 {
+            Self::pi(&env).transfer(&env.current_contract_address(), &schedule.payer, &schedule.balance);
+        }
+        env.events().publish((Symbol::new(&env, "schedule_cancelled"), id), schedule.balance);
+        Ok(schedule.balance)
+    }
+
+    // Keeper (permissionless): Make one payment on each listed schedule that is due,
+    // unpaused and funded for the payment plus the keeper fee; others are skipped.
+    // The keeper collects the fees. Returns the number of payments made.
+    pub fn execute_due(env: Env, keeper: Address, ids: Vec<u64>) -> u32 {
+        let data = Self::data(&env);
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let now = env.ledger().timestamp();
+        let mut executed = 0;
+        for id in ids.iter() {
+            let Some(mut schedule) = env.storage().persistent().get::<_, Schedule>(&SchedulerKey::Schedule(id)) else { continue };
+            if schedule.paused || schedule.next_due > now {
+                continue;
+            }
+            if schedule.balance < schedule.amount + data.keeper_fee {
+                env.events().publish((Symbol::new(&env, "schedule_underfunded"), id), schedule.balance);
+                continue;
+            }
+            schedule.balance -= schedule.amount + data.keeper_fee;
+            schedule.next_due += schedule.interval;
+            env.storage().persistent().set(&SchedulerKey::Schedule(id), &schedule);
+            pi.transfer(&env.current_contract_address(), &schedule.recipient, &schedule.amount);
+            env.events().publish((Symbol::new(&env, "schedule_paid"), id), schedule.amount);
+            executed += 1;
+        }
+        let fees = data.keeper_fee * executed as i128;
+        if fees > 0 {
+            pi.transfer(&env.current_contract_address(), &keeper, &fees);
+        }
+        executed
+    }
+
+    // Governance-only: Change the keeper fee
+    pub fn set_keeper_fee(env: Env, keeper_fee: i128) -> Result<(), SchedulerError> {
+        let mut data = Self::data(&env);
+        data.governance.require_auth();
+        if keeper_fee < 0 {
+            return Err(SchedulerError::InvalidFee);
+        }
+        data.keeper_fee = keeper_fee;
+        env.storage().instance().set(&Symbol::new(&env, "scheduler_data"), &data);
+        Ok(())
+    }
+
+    // View: Schedule details
+    pub fn schedule(env: Env, id: u64) -> Result<Schedule, SchedulerError> {
+        Self::load(&env, id)
+    }
+
+    // View: Whether `execute_due` would pay this schedule now
+    pub fn is_due(env: Env, id: u64) -> bool {
+        let data = Self::data(&env);
+        match env.storage().persistent().get::<_, Schedule>(&SchedulerKey::Schedule(id)) {
+            Some(schedule) => {
+                !schedule.paused && schedule.next_due <= env.ledger().timestamp() && schedule.balance >= schedule.amount + data.keeper_fee
+            }
+            None => false,
+        }
+    }
+
+    fn load(env: &Env, id: u64) -> Result<Schedule, SchedulerError> {
+        env.storage().persistent().get(&SchedulerKey::Schedule(id)).ok_or(SchedulerError::ScheduleNotFound)
+    }
+
+    fn data(env: &Env) -> SchedulerData {
+        env.storage().instance().get(&Symbol::new(env, "scheduler_data")).unwrap()
+    }
+
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        token::TokenClient::new(env, &Self::data(env).pi_token)
+    }
+}
+
+mod test;
@@ -0,0 +1,109 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, vec, Address, Env};
+use crate::{PiCoinScheduler, PiCoinSchedulerClient, SchedulerError};
+use pi_coin_testutils::TokenBuilder;
+
+#[test]
+fn test_keepers_execute_due_payments_for_a_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let payer = Address::generate(&env);
+    let landlord = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&payer, 1_000).build();
+
+    let client = PiCoinSchedulerClient::new(&env, &env.register(PiCoinScheduler, (Address::generate(&env), &pi.address, 5i128)));
+    let rent = client.register(&payer, &landlord, &300, &100, &1_000, &610);
+    assert_eq!(pi.balance(&payer), 390);
+
+    // Due now: paid once, and not again until the next interval
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent, 42]), 1);
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent]), 0);
+    assert_eq!((pi.balance(&landlord), pi.balance(&keeper)), (300, 5));
+
+    // Paused schedules are skipped, and missed periods are not made up on resume
+    client.set_paused(&rent, &true);
+    env.ledger().set_timestamp(1_250);
+    assert!(!client.is_due(&rent));
+    client.set_paused(&rent, &false);
+    assert_eq!(client.schedule(&rent).next_due, 1_300);
+
+    // 305 left covers one more payment plus fee; then it is underfunded until topped up
+    env.ledger().set_timestamp(1_300);
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent]), 1);
+    env.ledger().set_timestamp(1_400);
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent]), 0);
+    client.fund(&rent, &305);
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent]), 1);
+    assert_eq!((pi.balance(&landlord), pi.balance(&keeper)), (900, 15));
+
+    // Cancelling refunds what is left (nothing here) and removes the schedule
+    assert_eq!(client.cancel(&rent), 0);
+    assert_eq!(client.try_schedule(&rent), Err(Ok(SchedulerError::ScheduleNotFound)));
+}
+
+#[test]
+fn test_balances_are_credited_net_of_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let payer = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&payer, 1_000).build();
+    let contract_id = env.register(PiCoinScheduler, (Address::generate(&env), &pi.address, 0i128));
+    let client = PiCoinSchedulerClient::new(&env, &contract_id);
+
+    // At a 1% fee the 500 deposit and 100 top-up leave 495 + 99 in the schedule
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    let rent = client.register(&payer, &Address::generate(&env), &300, &100, &2_000, &500);
+    assert_eq!(client.schedule(&rent).balance, 495);
+    client.fund(&rent, &100);
+    assert_eq!(client.schedule(&rent).balance, 594);
+    assert_eq!(pi.balance(&contract_id), 594);
+
+    // So the refund on cancelling empties the contract
+    assert_eq!(client.cancel(&rent), 594);
+    assert_eq!(pi.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_cancelled_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let payer = Address::generate(&env);
+    let landlord = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&payer, 1_000).build();
+    let client = PiCoinSchedulerClient::new(&env, &env.register(PiCoinScheduler, (Address::generate(&env), &pi.address, 5i128)));
+
+    // The payer cannot pre-fund more than it holds, nor register an empty schedule
+    assert!(client.try_register(&payer, &landlord, &300, &100, &1_000, &1_001).is_err());
+    assert_eq!(client.try_register(&payer, &landlord, &0, &100, &1_000, &0), Err(Ok(SchedulerError::InvalidSchedule)));
+    let rent = client.register(&payer, &landlord, &300, &100, &1_000, &300);
+
+    // Only the payer funds, pauses or cancels, and only governance sets the fee
+    env.set_auths(&[]);
+    assert!(client.try_fund(&rent, &100).is_err());
+    assert!(client.try_set_paused(&rent, &true).is_err());
+    assert!(client.try_cancel(&rent).is_err());
+    assert!(client.try_set_keeper_fee(&1).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.try_set_keeper_fee(&-1), Err(Ok(SchedulerError::InvalidFee)));
+
+    // 300 does not cover the payment plus the keeper's 5
+    assert!(!client.is_due(&rent));
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent]), 0);
+    assert_eq!(pi.balance(&landlord), 0);
+
+    // A cancelled schedule is never paid and cannot be touched again
+    assert_eq!(client.cancel(&rent), 300);
+    assert_eq!(client.execute_due(&keeper, &vec![&env, rent]), 0);
+    assert_eq!(client.try_fund(&rent, &100), Err(Ok(SchedulerError::ScheduleNotFound)));
+    assert_eq!(client.try_cancel(&rent), Err(Ok(SchedulerError::ScheduleNotFound)));
+    assert_eq!(pi.balance(&payer), 1_000);
+}
@@ -0,0 +1,326 @@
+#![no_std]
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+// Fixed-point scale of the seigniorage-per-share accumulator
+pub const ACC_ONE: i128 = 1_000_000_000_000;
+const BPS: i128 = 10_000;
+
+// Same variants (and XDR) as PiCoinContract's source enum; expansions mint as Rewards
+#[contracttype]
+#[derive(Clone)]
+pub enum PiCoinSource {
+    Mining,
+    Rewards,
+    P2P,
+    Invalid,
+}
+
+// Supply and price entry points of PiCoinContract used by the seigniorage system. This
+// contract must be the PI token's minting admin.
+#[contractclient(name = "PiSupplyTokenClient")]
+pub trait PiSupplyToken {
+    fn mint(env: Env, to: Address, amount: i128, source: PiCoinSource);
+    fn burn(env: Env, from: Address, amount: i128);
+    fn total_supply(env: Env) -> i128;
+    fn peg_value(env: Env) -> i128;
+    fn oracle_price(env: Env) -> i128;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SeigniorageConfig {
+    pub band_bps: u32, // No expansion or contraction while the TWAP is this close to the peg
+    pub epoch_length: u64, // Seconds per epoch
+    pub max_expansion_bps: u32, // Cap on PI minted per epoch, in basis points of supply
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SeigniorageData {
+    pub governance: Address,
+    pub pi_token: Address,
+    pub bond_token: Address, // Stellar asset administered by this contract
+    pub share_token: Address, // Staked here to earn seigniorage
+    pub config: SeigniorageConfig,
+    pub epoch: u64, // Last epoch allocated
+    pub epoch_twap: i128, // TWAP of that epoch; sets the phase until the next allocation
+    pub price_cumulative: i128, // Sum of price * seconds since deploy
+    pub last_price: i128, // Latest oracle sample
+    pub last_observed: u64,
+    pub epoch_start_cumulative: i128, // `price_cumulative` when the current epoch began
+    pub epoch_start: u64,
+    pub bonds_outstanding: i128,
+    pub bond_reserve: i128, // PI held to redeem bonds at par
+    pub shares_staked: i128,
+    pub seigniorage_per_share: i128, // PI per staked share since deploy, scaled by ACC_ONE
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ShareStake {
+    pub staked: i128,
+    pub per_share_paid: i128, // Accumulator value already credited to `earned`
+    pub earned: i128, // Seigniorage not yet claimed
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SeigniorageKey {
+    Stake(Address),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SeigniorageError {
+    InvalidAmount = 1,
+    EpochNotEnded = 2,
+    NotBelowPeg = 3, // Bonds are only sold in contraction epochs
+    NotAbovePeg = 4, // Bonds are only redeemed in expansion epochs
+    InsufficientReserve = 5,
+    InsufficientStake = 6,
+    NothingToClaim = 7,
+    InvalidConfig = 8,
+}
+
+#[contract]
+pub struct PiCoinSeigniorage;
+
+#[contractimpl]
+impl PiCoinSeigniorage {
+    // Constructor: bond/share peg defense for PI. Each epoch the oracle TWAP sets the
+    // phase. Below the band, PI can be burned for bonds at the TWAP discount. Above it,
+    // PI is minted: first to cover outstanding bonds (redeemable 1:1), the rest to
+    // share stakers.
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, bond_token: Address, share_token: Address, config: SeigniorageConfig) {
+        let now = env.ledger().timestamp();
+        let price = PiSupplyTokenClient::new(&env, &pi_token).oracle_price();
+        let data = SeigniorageData {
+            governance,
+            pi_token,
+            bond_token,
+            share_token,
+            epoch: now / config.epoch_length,
+            config,
+            epoch_twap: price,
+            price_cumulative: 0,
+            last_price: price,
+            last_observed: now,
+            epoch_start_cumulative: 0,
+            epoch_start: now,
+            bonds_outstanding: 0,
+            bond_reserve: 0,
+            shares_staked: 0,
+            seigniorage_per_share: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "seigniorage_data"), &data);
+        log!(&env, "Seigniorage initialized: bond and share peg defense for $314,159");
+    }
+
+    // Record the current oracle price in the TWAP (permissionless); returns it
+    pub fn observe(env: Env) -> i128 {
+        let mut data = Self::load(&env);
+        Self::sample(&env, &mut data);
+        env.storage().instance().set(&Symbol::new(&env, "seigniorage_data"), &data);
+        data.last_price
+    }
+
+    // Close the epoch (permissionless, once per epoch): its TWAP sets the new phase, and
+    // above the band new PI is minted for bond redemptions and share stakers.
+    // Returns the TWAP.
+    pub fn allocate_seigniorage(env: Env) -> Result<i128, SeigniorageError> {
+        let mut data = Self::load(&env);
+        let now = env.ledger().timestamp();
+        let epoch = now / data.config.epoch_length;
+        if epoch <= data.epoch {
+            return Err(SeigniorageError::EpochNotEnded);
+        }
+        Self::sample(&env, &mut data);
+        let twap = if now > data.epoch_start {
+            (data.price_cumulative - data.epoch_start_cumulative) / (now - data.epoch_start) as i128
+        } else {
+            data.last_price
+        };
+        data.epoch = epoch;
+        data.epoch_twap = twap;
+        data.epoch_start_cumulative = data.price_cumulative;
+        data.epoch_start = now;
+
+        let pi = PiSupplyTokenClient::new(&env, &data.pi_token);
+        let peg = pi.peg_value();
+        let (mut to_bonds, mut to_shares) = (0, 0);
+        if twap * BPS > peg * (BPS + data.config.band_bps as i128) {
+            let deviation_bps = ((twap - peg) * BPS / peg).min(data.config.max_expansion_bps as i128);
+            let expansion = pi.total_supply() * deviation_bps / BPS;
+            to_bonds = expansion.min(data.bonds_outstanding - data.bond_reserve);
+            if data.shares_staked > 0 {
+                to_shares = expansion - to_bonds;
+                data.seigniorage_per_share += to_shares * ACC_ONE / data.shares_staked;
+            }
+            data.bond_reserve += to_bonds;
+            if to_bonds + to_shares > 0 {
+                pi.mint(&env.current_contract_address(), &(to_bonds + to_shares), &PiCoinSource::Rewards);
+            }
+        }
+        env.storage().instance().set(&Symbol::new(&env, "seigniorage_data"), &data);
+        env.events().publish((Symbol::new(&env, "seigniorage"), epoch), (twap, to_bonds, to_shares));
+        Ok(twap)
+    }
+
+    // Contraction epochs only: Burn `amount` PI for bonds priced at the epoch TWAP
+    // (amount * peg / twap, i.e. at a discount). Returns the bonds issued.
+    pub fn buy_bonds(env: Env, buyer: Address, amount: i128) -> Result<i128, SeigniorageError> {
+        buyer.require_auth();
+        if amount <= 0 {
+            return Err(SeigniorageError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        let pi = PiSupplyTokenClient::new(&env, &data.pi_token);
+        let peg = pi.peg_value();
+        if data.epoch_twap * BPS >= peg * (BPS - data.config.band_bps as i128) {
+            return Err(SeigniorageError::NotBelowPeg);
+        }
+        let bonds = amount * peg / data.epoch_twap;
+        pi.burn(&buyer, &amount);
+        token::StellarAssetClient::new(&env, &data.bond_token).mint(&buyer, &bonds);
+        data.bonds_outstanding += bonds;
+        env.storage().instance().set(&Symbol::new(&env, "seigniorage_data"), &data);
+        env.events().publish((Symbol::new(&env, "bonds_bought"), buyer), (amount, bonds));
+        Ok(bonds)
+    }
+
+    // Expansion epochs only: Burn `bonds` for the same amount of PI from the bond reserve
+    pub fn redeem_bonds(env: Env, holder: Address, bonds: i128) -> Result<(), SeigniorageError> {
+        holder.require_auth();
+        if bonds <= 0 {
+            return Err(SeigniorageError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        if data.epoch_twap <= PiSupplyTokenClient::new(&env, &data.pi_token).peg_value() {
+            return Err(SeigniorageError::NotAbovePeg);
+        }
+        if data.bond_reserve < bonds {
+            return Err(SeigniorageError::InsufficientReserve);
+        }
+        token::TokenClient::new(&env, &data.bond_token).burn(&holder, &bonds);
+        data.bonds_outstanding -= bonds;
+        data.bond_reserve -= bonds;
+        env.storage().instance().set(&Symbol::new(&env, "seigniorage_data"), &data);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &holder, &bonds);
+        env.events().publish((Symbol::new(&env, "bonds_redeemed"), holder), bonds);
+        Ok(())
+    }
+
+    // Stake share tokens to earn future expansions
+    pub fn stake_shares(env: Env, staker: Address, amount: i128) -> Result<(), SeigniorageError> {
+        staker.require_auth();
+        if amount <= 0 {
+            return Err(SeigniorageError::InvalidAmount);
+        }
+        let mut data = Self::load(&env);
+        let mut stake = Self::settled(&data, &Self::stake_of(env.clone(), staker.clone()));
+        token::TokenClient::new(&env, &data.share_token).transfer(&staker, env.current_contract_address(), &amount);
+        stake.staked += amount;
+        data.shares_staked += amount;
+        Self::save(&env, &data, &staker, &stake);
+        Ok(())
+    }
+
+    // Withdraw staked share tokens; earned seigniorage stays claimable
+    pub fn unstake_shares(env: Env, staker: Address, amount: i128) -> Result<(), SeigniorageError> {
+        staker.require_auth();
+        let mut data = Self::load(&env);
+        let mut stake = Self::settled(&data, &Self::stake_of(env.clone(), staker.clone()));
+        if amount <= 0 || stake.staked < amount {
+            return Err(SeigniorageError::InsufficientStake);
+        }
+        stake.staked -= amount;
+        data.shares_staked -= amount;
+        Self::save(&env, &data, &staker, &stake);
+        token::TokenClient::new(&env, &data.share_token).transfer(&env.current_contract_address(), &staker, &amount);
+        Ok(())
+    }
+
+    // Pay out the caller's share of past expansions; returns the PI paid
+    pub fn claim_seigniorage(env: Env, staker: Address) -> Result<i128, SeigniorageError> {
+        staker.require_auth();
+        let data = Self::load(&env);
+        let mut stake = Self::settled(&data, &Self::stake_of(env.clone(), staker.clone()));
+        let amount = stake.earned;
+        if amount == 0 {
+            return Err(SeigniorageError::NothingToClaim);
+        }
+        stake.earned = 0;
+        Self::save(&env, &data, &staker, &stake);
+        token::TokenClient::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &staker, &amount);
+        env.events().publish((Symbol::new(&env, "seigniorage_claimed"), staker), amount);
+        Ok(amount)
+    }
+
+    // Governance-only: Retune the band, epoch length and expansion cap
+    pub fn set_config(env: Env, config: SeigniorageConfig) -> Result<(), SeigniorageError> {
+        let mut data = Self::load(&env);
+        data.governance.require_auth();
+        if config.band_bps as i128 >= BPS || config.epoch_length == 0 || config.max_expansion_bps as i128 > BPS {
+            return Err(SeigniorageError::InvalidConfig);
+        }
+        // Epoch numbers change meaning with the length
+        data.epoch = env.ledger().timestamp() / config.epoch_length;
+        data.config = config;
+        env.storage().instance().set(&Symbol::new(&env, "seigniorage_data"), &data);
+        Ok(())
+    }
+
+    // View: Phase, TWAP accumulator, bond and stake totals
+    pub fn state(env: Env) -> SeigniorageData {
+        Self::load(&env)
+    }
+
+    // View: Seigniorage `staker` could claim right now
+    pub fn earned(env: Env, staker: Address) -> i128 {
+        Self::settled(&Self::load(&env), &Self::stake_of(env.clone(), staker)).earned
+    }
+
+    // View: Share tokens staked by `staker` and unclaimed seigniorage
+    pub fn stake_of(env: Env, staker: Address) -> ShareStake {
+        env.storage().persistent().get(&SeigniorageKey::Stake(staker)).unwrap_or(ShareStake {
+            staked: 0,
+            per_share_paid: 0,
+            earned: 0,
+        })
+    }
+
+    // Helper: Fold the time since the last sample into the TWAP accumulator and take a
+    // fresh oracle price
+    fn sample(env: &Env, data: &mut SeigniorageData) {
+        let now = env.ledger().timestamp();
+        data.price_cumulative += data.last_price * (now - data.last_observed) as i128;
+        data.last_price = PiSupplyTokenClient::new(env, &data.pi_token).oracle_price();
+        data.last_observed = now;
+    }
+
+    // Helper: Credit what `stake` earned since it was last settled
+    fn settled(data: &SeigniorageData, stake: &ShareStake) -> ShareStake {
+        ShareStake {
+            staked: stake.staked,
+            per_share_paid: data.seigniorage_per_share,
+            earned: stake.earned + stake.staked * (data.seigniorage_per_share - stake.per_share_paid) / ACC_ONE,
+        }
+    }
+
+    fn save(env: &Env, data: &SeigniorageData, staker: &Address, stake: &ShareStake) {
+        env.storage().instance().set(&Symbol::new(env, "seigniorage_data"), data);
+        let key = SeigniorageKey::Stake(staker.clone());
+        if stake.staked == 0 && stake.earned == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, stake);
+        }
+    }
+
+    fn load(env: &Env) -> SeigniorageData {
+        env.storage().instance().get(&Symbol::new(env, "seigniorage_data")).unwrap()
+    }
+}
+
+mod test;
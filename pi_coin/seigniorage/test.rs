@@ -0,0 +1,107 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, contracttype, testutils::{Address as _, Ledger}, token, Address, Env};
+use crate::{PiCoinSeigniorage, PiCoinSeigniorageClient, PiCoinSource, SeigniorageConfig};
+
+// Just enough of PiCoinContract for seigniorage: balances, mint, burn and a settable
+// oracle price against a 100 peg and 1_000_000 supply
+#[contracttype]
+enum MockKey {
+    Price,
+    Balance(Address),
+}
+
+#[contract]
+struct MockPi;
+
+#[contractimpl]
+impl MockPi {
+    pub fn set_price(env: Env, price: i128) {
+        env.storage().instance().set(&MockKey::Price, &price);
+    }
+
+    pub fn oracle_price(env: Env) -> i128 {
+        env.storage().instance().get(&MockKey::Price).unwrap_or(100)
+    }
+
+    pub fn peg_value(_env: Env) -> i128 {
+        100
+    }
+
+    pub fn total_supply(_env: Env) -> i128 {
+        1_000_000
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&MockKey::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128, _source: PiCoinSource) {
+        Self::credit(&env, &to, amount);
+    }
+
+    pub fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        Self::credit(&env, &from, -amount);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        Self::credit(&env, &from, -amount);
+        Self::credit(&env, &to, amount);
+    }
+}
+
+impl MockPi {
+    fn credit(env: &Env, to: &Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone()) + amount;
+        assert!(balance >= 0, "insufficient balance");
+        env.storage().instance().set(&MockKey::Balance(to.clone()), &balance);
+    }
+}
+
+#[test]
+fn test_bonds_sold_below_peg_and_redeemed_from_expansion() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let pi = env.register(MockPi, ());
+    let pi_client = MockPiClient::new(&env, &pi);
+    let bond = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let share = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let config = SeigniorageConfig { band_bps: 500, epoch_length: 100, max_expansion_bps: 1_000 };
+    let contract_id = env.register(PiCoinSeigniorage, (Address::generate(&env), &pi, &bond, &share, config));
+    let client = PiCoinSeigniorageClient::new(&env, &contract_id);
+    token::StellarAssetClient::new(&env, &bond).set_admin(&contract_id);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    pi_client.mint(&alice, &80, &PiCoinSource::Rewards);
+    token::StellarAssetClient::new(&env, &share).mint(&bob, &1_000);
+
+    // At peg no bonds are sold; an epoch at 80 opens contraction
+    assert!(client.try_buy_bonds(&alice, &80).is_err());
+    pi_client.set_price(&80);
+    client.observe();
+    assert!(client.try_allocate_seigniorage().is_err()); // epoch 0 still running
+    env.ledger().set_timestamp(100);
+    assert_eq!(client.allocate_seigniorage(), 80);
+    assert_eq!(client.buy_bonds(&alice, &80), 100); // 20% discount
+    assert_eq!(pi_client.balance(&alice), 0);
+    assert!(client.try_redeem_bonds(&alice, &100).is_err());
+
+    // An epoch at 120 expands by the 10% cap: 100 PI to cover the bonds, the rest to stakers
+    pi_client.set_price(&120);
+    client.observe();
+    client.stake_shares(&bob, &1_000);
+    env.ledger().set_timestamp(200);
+    assert_eq!(client.allocate_seigniorage(), 120);
+    assert_eq!(client.state().bond_reserve, 100);
+    assert_eq!(client.earned(&bob), 99_900);
+
+    client.redeem_bonds(&alice, &100);
+    assert_eq!(pi_client.balance(&alice), 100);
+    assert_eq!(token::TokenClient::new(&env, &bond).balance(&alice), 0);
+    assert_eq!(client.claim_seigniorage(&bob), 99_900);
+    client.unstake_shares(&bob, &1_000);
+    assert_eq!(token::TokenClient::new(&env, &share).balance(&bob), 1_000);
+    assert_eq!(pi_client.balance(&contract_id), 0);
+}
@@ -0,0 +1,47 @@
+// Allowances for transfer_from, kept in temporary storage that expires with the
+// approval (same scheme as the SEP-41 reference token).
+use soroban_sdk::{contracttype, Address, Env};
+use crate::PiCoinError;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AllowanceKey {
+    Allowance(Address, Address), // (From, spender)
+}
+
+// Current allowance; expired approvals read as zero
+pub fn read(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let key = AllowanceKey::Allowance(from.clone(), spender.clone());
+    match env.storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        Some(allowance) => AllowanceValue { amount: 0, expiration_ledger: allowance.expiration_ledger },
+        None => AllowanceValue { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+pub fn write(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) {
+    let key = AllowanceKey::Allowance(from.clone(), spender.clone());
+    env.storage().temporary().set(&key, &AllowanceValue { amount, expiration_ledger });
+    if amount > 0 {
+        let live_for = expiration_ledger - env.ledger().sequence();
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
+
+pub fn spend(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let allowance = read(env, from, spender);
+    if allowance.amount < amount {
+        return Err(PiCoinError::InsufficientAllowance);
+    }
+    if amount > 0 {
+        write(env, from, spender, allowance.amount - amount, allowance.expiration_ledger);
+    }
+    Ok(())
+}
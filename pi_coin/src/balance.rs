@@ -0,0 +1,106 @@
+// PI balances, one persistent entry per holder so a balance can be read, written and
+// kept alive (see ttl.rs) without loading anyone else's. Entries hold rebase shares;
+// this module converts to and from PI at the current rebase index (see rebase.rs), so
+// callers only ever deal in PI. The sum of all holders' shares is kept alongside, so the
+// circulating supply follows mints, burns and rebases without iterating holders. Every
+// write is checkpointed for balance_at (see snapshot.rs).
+use soroban_sdk::{contracttype, Address, Env};
+use crate::{rebase, snapshot, ttl, PiCoinError};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BalanceKey {
+    Balance(Address), // Holder -> Shares
+    TotalShares, // Sum of every holder's shares
+}
+
+pub fn read(env: &Env, holder: &Address) -> Result<i128, PiCoinError> {
+    rebase::to_amount(env, read_shares(env, holder))
+}
+
+// PI held by all holders together (the contract's own shielded pool included)
+pub fn circulating(env: &Env) -> Result<i128, PiCoinError> {
+    rebase::to_amount(env, total_shares(env))
+}
+
+// `holder`'s PI at the end of a closed `ledger`
+pub fn read_at(env: &Env, holder: &Address, ledger: u32) -> Result<i128, PiCoinError> {
+    let current: i128 = env.storage().persistent().get(&BalanceKey::Balance(holder.clone())).unwrap_or(0);
+    let shares = snapshot::holder_at(env, holder, ledger, current)?;
+    rebase::to_amount_at(shares, snapshot::index_at(env, ledger, rebase::index(env))?)
+}
+
+// Circulating PI at the end of a closed `ledger`
+pub fn circulating_at(env: &Env, ledger: u32) -> Result<i128, PiCoinError> {
+    let shares = snapshot::supply_at(env, ledger, total_shares(env))?;
+    rebase::to_amount_at(shares, snapshot::index_at(env, ledger, rebase::index(env))?)
+}
+
+fn total_shares(env: &Env) -> i128 {
+    env.storage().instance().get(&BalanceKey::TotalShares).unwrap_or(0)
+}
+
+// Set the balance to `amount` PI (used when importing balances, e.g. migrations)
+pub fn write(env: &Env, holder: &Address, amount: i128) -> Result<(), PiCoinError> {
+    write_shares(env, holder, rebase::to_shares(env, amount)?)
+}
+
+// Overflow if the holder's shares or the total would pass i128
+pub fn add(env: &Env, holder: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let shares = read_shares(env, holder).checked_add(rebase::to_shares(env, amount)?).ok_or(PiCoinError::Overflow)?;
+    write_shares(env, holder, shares)
+}
+
+// Move `amount` PI from `from` to `to`: rewrites the two balance entries only, since the
+// total shares (and so the supply history) cannot change. Same preconditions as `sub`.
+// Two holders never hold more than the total, so the sum cannot overflow.
+pub fn transfer(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let shares = read_shares(env, from);
+    let moved = if amount >= rebase::to_amount(env, shares)? { shares } else { rebase::to_shares(env, amount)? };
+    store_shares(env, from, shares, shares - moved);
+    let held = read_shares(env, to);
+    store_shares(env, to, held, held + moved);
+    Ok(())
+}
+
+// Callers check `read(..) >= amount` first, which guarantees enough shares. Spending the
+// whole balance takes every share, so no unspendable rounding dust is left behind.
+pub fn sub(env: &Env, holder: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let shares = read_shares(env, holder);
+    let spent = if amount >= rebase::to_amount(env, shares)? { shares } else { rebase::to_shares(env, amount)? };
+    // Fewer shares only lower the total, which cannot overflow
+    write_shares(env, holder, shares - spent)
+}
+
+fn read_shares(env: &Env, holder: &Address) -> i128 {
+    let key = BalanceKey::Balance(holder.clone());
+    match env.storage().persistent().get(&key) {
+        Some(shares) => {
+            ttl::bump_persistent(env, &key);
+            shares
+        }
+        None => 0,
+    }
+}
+
+fn write_shares(env: &Env, holder: &Address, shares: i128) -> Result<(), PiCoinError> {
+    let previous: i128 = env.storage().persistent().get(&BalanceKey::Balance(holder.clone())).unwrap_or(0);
+    let total = total_shares(env);
+    let new_total = (total - previous).checked_add(shares).ok_or(PiCoinError::Overflow)?;
+    env.storage().instance().set(&BalanceKey::TotalShares, &new_total);
+    snapshot::record_supply(env, total, new_total);
+    store_shares(env, holder, previous, shares);
+    Ok(())
+}
+
+// Emptied balances are removed rather than left to archive
+fn store_shares(env: &Env, holder: &Address, previous: i128, shares: i128) {
+    let key = BalanceKey::Balance(holder.clone());
+    snapshot::record_holder(env, holder, previous, shares);
+    if shares == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, &shares);
+    ttl::bump_persistent(env, &key);
+}
@@ -0,0 +1,17 @@
+// Batched operations: several transfers or token calls by one account, authorized once
+// and applied all-or-nothing (any failing step reverts the whole call).
+use soroban_sdk::{contracttype, Address};
+
+// Steps per batch. Each transfer writes the recipient's lot entry (plus the fee
+// collector's), so this stays well inside the per-transaction ledger write limit.
+pub const MAX_BATCH: u32 = 20;
+
+// One step of a multicall, performed on behalf of the calling account
+#[contracttype]
+#[derive(Clone)]
+pub enum Invocation {
+    Transfer(Address, i128), // (To, amount)
+    TransferFrom(Address, Address, i128), // (From, to, amount), spending the caller's allowance
+    Approve(Address, i128, u32), // (Spender, amount, expiration_ledger)
+    Burn(i128),
+}
@@ -0,0 +1,87 @@
+// Compliance: governance picks a jurisdiction mode. Open lets every holder transact,
+// Blocklist rejects listed accounts, Allowlist admits only listed accounts and accounts
+// the KYC registry (PiCoinKycRegistry) reports as verified.
+// Both lists are kept across mode switches, and every change emits an event so auditors
+// can replay the compliance state.
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, Symbol, Vec};
+use crate::{events, PiCoinError};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComplianceMode {
+    Open,
+    Blocklist,
+    Allowlist,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComplianceList {
+    Blocklist,
+    Allowlist,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ComplianceKey {
+    Mode,
+    KycRegistry, // Registry consulted in allowlist mode
+    Listed(ComplianceList, Address),
+}
+
+pub fn mode(env: &Env) -> ComplianceMode {
+    env.storage().instance().get(&ComplianceKey::Mode).unwrap_or(ComplianceMode::Open)
+}
+
+pub fn set_mode(env: &Env, mode: ComplianceMode) {
+    env.storage().instance().set(&ComplianceKey::Mode, &mode);
+    events::compliance_mode_event(env, mode);
+}
+
+pub fn kyc_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ComplianceKey::KycRegistry)
+}
+
+pub fn set_kyc_registry(env: &Env, registry: &Address) {
+    env.storage().instance().set(&ComplianceKey::KycRegistry, registry);
+    events::kyc_registry_event(env, registry);
+}
+
+pub fn is_listed(env: &Env, list: ComplianceList, account: &Address) -> bool {
+    env.storage().persistent().has(&ComplianceKey::Listed(list, account.clone()))
+}
+
+// Add or remove accounts, emitting ("compliance", list, account) -> listed per change
+pub fn set_listed(env: &Env, list: ComplianceList, accounts: &Vec<Address>, listed: bool) {
+    for account in accounts.iter() {
+        let key = ComplianceKey::Listed(list, account.clone());
+        if listed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        events::compliance_event(env, list, &account, listed);
+    }
+}
+
+// Whether `account` may send or receive PI under the active mode
+pub fn is_permitted(env: &Env, account: &Address) -> bool {
+    match mode(env) {
+        ComplianceMode::Open => true,
+        ComplianceMode::Blocklist => !is_listed(env, ComplianceList::Blocklist, account),
+        ComplianceMode::Allowlist => is_listed(env, ComplianceList::Allowlist, account) || is_kyc_verified(env, account),
+    }
+}
+
+// Ask the KYC registry, if one is set, whether `account` holds a valid credential
+fn is_kyc_verified(env: &Env, account: &Address) -> bool {
+    let Some(registry) = kyc_registry(env) else { return false };
+    env.invoke_contract(&registry, &Symbol::new(env, "is_verified"), vec![env, account.into_val(env)])
+}
+
+pub fn check(env: &Env, from: &Address, to: &Address) -> Result<(), PiCoinError> {
+    if !is_permitted(env, from) || !is_permitted(env, to) {
+        return Err(PiCoinError::NotCompliant);
+    }
+    Ok(())
+}
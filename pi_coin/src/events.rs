@@ -0,0 +1,168 @@
+// Events: every event the token publishes goes through one of these functions, so the
+// schema off-chain indexers consume is defined (and reviewed) in one place.
+//
+// Layout: the first topic is the event name, then the event's subjects (addresses, ids),
+// and the last topic is always EVENT_SCHEMA_VERSION. Keeping the version last leaves the
+// SEP-41 positions of transfer/mint/burn/approve topics untouched for wallets that read
+// them by index. Bump EVENT_SCHEMA_VERSION whenever a topic or data layout changes;
+// renaming or reordering fields without a bump breaks indexers silently.
+use soroban_sdk::{Address, BytesN, Env, Symbol};
+use crate::{ComplianceList, ComplianceMode, PegBandStatus, PegBands, PiCoinSource, PrivateTransferInputs, RateLimits, Settlement, SourceInfo, SourceQuota, TokenMetadata};
+
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+// ("transfer", from, to, v) -> amount received by `to` (after the transfer fee)
+pub fn transfer_event(env: &Env, from: &Address, to: &Address, amount: i128) {
+    env.events().publish((Symbol::new(env, "transfer"), from.clone(), to.clone(), EVENT_SCHEMA_VERSION), amount);
+}
+
+// ("mint", to, v) -> (amount, source)
+pub fn mint_event(env: &Env, to: &Address, amount: i128, source: PiCoinSource) {
+    env.events().publish((Symbol::new(env, "mint"), to.clone(), EVENT_SCHEMA_VERSION), (amount, source));
+}
+
+// ("burn", from, v) -> amount
+pub fn burn_event(env: &Env, from: &Address, amount: i128) {
+    env.events().publish((Symbol::new(env, "burn"), from.clone(), EVENT_SCHEMA_VERSION), amount);
+}
+
+// ("clawback", holder, v) -> amount
+pub fn clawback_event(env: &Env, holder: &Address, amount: i128) {
+    env.events().publish((Symbol::new(env, "clawback"), holder.clone(), EVENT_SCHEMA_VERSION), amount);
+}
+
+// ("approve", from, spender, v) -> (amount, expiration_ledger)
+pub fn approve_event(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) {
+    env.events().publish((Symbol::new(env, "approve"), from.clone(), spender.clone(), EVENT_SCHEMA_VERSION), (amount, expiration_ledger));
+}
+
+// ("fee", from, collector, v) -> fee
+pub fn fee_event(env: &Env, from: &Address, collector: &Address, fee: i128) {
+    env.events().publish((Symbol::new(env, "fee"), from.clone(), collector.clone(), EVENT_SCHEMA_VERSION), fee);
+}
+
+// ("peg_update", v) -> (previous, peg_value)
+pub fn peg_update_event(env: &Env, previous: i128, peg_value: i128) {
+    env.events().publish((Symbol::new(env, "peg_update"), EVENT_SCHEMA_VERSION), (previous, peg_value));
+}
+
+// ("rebase", v) -> (twap, index)
+pub fn rebase_event(env: &Env, twap: i128, index: i128) {
+    env.events().publish((Symbol::new(env, "rebase"), EVENT_SCHEMA_VERSION), (twap, index));
+}
+
+// ("shutdown", caller, v) -> (price, collateral, supply, by_guardian)
+pub fn shutdown_event(env: &Env, caller: &Address, settlement: &Settlement) {
+    env.events().publish(
+        (Symbol::new(env, "shutdown"), caller.clone(), EVENT_SCHEMA_VERSION),
+        (settlement.price, settlement.collateral, settlement.supply, settlement.by_guardian),
+    );
+}
+
+// ("settlement_redeem", holder, v) -> (amount burned, collateral paid)
+pub fn settlement_redeem_event(env: &Env, holder: &Address, amount: i128, paid: i128) {
+    env.events().publish((Symbol::new(env, "settlement_redeem"), holder.clone(), EVENT_SCHEMA_VERSION), (amount, paid));
+}
+
+// ("pause", caller, v) -> reason
+pub fn pause_event(env: &Env, caller: &Address, reason: &Symbol) {
+    env.events().publish((Symbol::new(env, "pause"), caller.clone(), EVENT_SCHEMA_VERSION), reason.clone());
+}
+
+// ("unpause", v) -> ()
+pub fn unpause_event(env: &Env) {
+    env.events().publish((Symbol::new(env, "unpause"), EVENT_SCHEMA_VERSION), ());
+}
+
+// ("pause_sentinel", v) -> sentinel (None: removed)
+pub fn pause_sentinel_event(env: &Env, sentinel: &Option<Address>) {
+    env.events().publish((Symbol::new(env, "pause_sentinel"), EVENT_SCHEMA_VERSION), sentinel.clone());
+}
+
+// ("private_transfer", v) -> (nullifiers, commitments, first_leaf, public_amount).
+// Commitments occupy tree indexes first_leaf.. in order, which is all a wallet needs to
+// rebuild the commitment tree.
+pub fn private_transfer_event(env: &Env, inputs: &PrivateTransferInputs, first_leaf: u32) {
+    env.events().publish(
+        (Symbol::new(env, "private_transfer"), EVENT_SCHEMA_VERSION),
+        (inputs.nullifiers.clone(), inputs.commitments.clone(), first_leaf, inputs.public_amount),
+    );
+}
+
+// ("shielded_deposit", from, v) -> (commitment, leaf, amount)
+pub fn shielded_deposit_event(env: &Env, from: &Address, commitment: &BytesN<32>, leaf: u32, amount: i128) {
+    env.events().publish(
+        (Symbol::new(env, "shielded_deposit"), from.clone(), EVENT_SCHEMA_VERSION),
+        (commitment.clone(), leaf, amount),
+    );
+}
+
+// ("attested", holder, v) -> (source, root_epoch)
+pub fn attested_event(env: &Env, holder: &Address, source: PiCoinSource, root_epoch: u32) {
+    env.events().publish((Symbol::new(env, "attested"), holder.clone(), EVENT_SCHEMA_VERSION), (source, root_epoch));
+}
+
+// ("restored", holder, v) -> restored balance
+pub fn restored_event(env: &Env, holder: &Address, amount: i128) {
+    env.events().publish((Symbol::new(env, "restored"), holder.clone(), EVENT_SCHEMA_VERSION), amount);
+}
+
+// ("peg_band", v) -> (mode, price, deviation_bps), published when the mode changes
+pub fn peg_band_event(env: &Env, status: &PegBandStatus) {
+    env.events().publish((Symbol::new(env, "peg_band"), EVENT_SCHEMA_VERSION), (status.mode, status.price, status.deviation_bps));
+}
+
+// ("peg_bands", v) -> bands
+pub fn peg_bands_event(env: &Env, bands: &PegBands) {
+    env.events().publish((Symbol::new(env, "peg_bands"), EVENT_SCHEMA_VERSION), bands.clone());
+}
+
+// ("rate_limits", v) -> limits
+pub fn rate_limits_event(env: &Env, limits: &RateLimits) {
+    env.events().publish((Symbol::new(env, "rate_limits"), EVENT_SCHEMA_VERSION), limits.clone());
+}
+
+// ("source_quota", source, v) -> quota (None: lifted)
+pub fn source_quota_event(env: &Env, source: PiCoinSource, quota: &Option<SourceQuota>) {
+    env.events().publish((Symbol::new(env, "source_quota"), source, EVENT_SCHEMA_VERSION), quota.clone());
+}
+
+// ("source_registered", source, v) -> info
+pub fn source_registered_event(env: &Env, source: PiCoinSource, info: &SourceInfo) {
+    env.events().publish((Symbol::new(env, "source_registered"), source, EVENT_SCHEMA_VERSION), info.clone());
+}
+
+// ("rate_bypass", account, v) -> bypass
+pub fn rate_bypass_event(env: &Env, account: &Address, bypass: bool) {
+    env.events().publish((Symbol::new(env, "rate_bypass"), account.clone(), EVENT_SCHEMA_VERSION), bypass);
+}
+
+// ("compliance_mode", v) -> mode
+pub fn compliance_mode_event(env: &Env, mode: ComplianceMode) {
+    env.events().publish((Symbol::new(env, "compliance_mode"), EVENT_SCHEMA_VERSION), mode);
+}
+
+// ("kyc_registry", v) -> registry
+pub fn kyc_registry_event(env: &Env, registry: &Address) {
+    env.events().publish((Symbol::new(env, "kyc_registry"), EVENT_SCHEMA_VERSION), registry.clone());
+}
+
+// ("lot_receipts", v) -> receipts contract
+pub fn lot_receipts_event(env: &Env, receipts: &Address) {
+    env.events().publish((Symbol::new(env, "lot_receipts"), EVENT_SCHEMA_VERSION), receipts.clone());
+}
+
+// ("badge_registry", v) -> registry
+pub fn badge_registry_event(env: &Env, registry: &Address) {
+    env.events().publish((Symbol::new(env, "badge_registry"), EVENT_SCHEMA_VERSION), registry.clone());
+}
+
+// ("metadata", v) -> TokenMetadata
+pub fn metadata_event(env: &Env, metadata: &TokenMetadata) {
+    env.events().publish((Symbol::new(env, "metadata"), EVENT_SCHEMA_VERSION), metadata.clone());
+}
+
+// ("compliance", list, account, v) -> listed
+pub fn compliance_event(env: &Env, list: ComplianceList, account: &Address, listed: bool) {
+    env.events().publish((Symbol::new(env, "compliance"), list, account.clone(), EVENT_SCHEMA_VERSION), listed);
+}
@@ -0,0 +1,64 @@
+// EVM interop: verify messages signed by Ethereum accounts (bridge attestations,
+// cross-chain governance votes) by recovering the signer's 20-byte address, exactly as
+// ecrecover does on Ethereum.
+//
+// Signatures are the 65-byte r || s || v that wallets produce, with v as 27/28 or 0/1.
+// Messages are hashed the personal_sign (EIP-191) way unless the caller already holds a
+// keccak256 digest, e.g. of an EIP-712 encoding built in the contract.
+use soroban_sdk::{crypto::Hash, Bytes, BytesN, Env};
+use crate::PiCoinError;
+
+// Prefix personal_sign puts before the message length and the message
+const ETH_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+pub fn keccak256(env: &Env, data: &Bytes) -> Hash<32> {
+    env.crypto().keccak256(data)
+}
+
+// Digest a wallet signs for `message` under personal_sign:
+// keccak256("\x19Ethereum Signed Message:\n" || decimal length || message)
+pub fn eth_message_hash(env: &Env, message: &Bytes) -> Hash<32> {
+    let mut prefixed = Bytes::from_slice(env, ETH_MESSAGE_PREFIX);
+    let mut digits = [0u8; 10];
+    let mut start = digits.len();
+    let mut len = message.len();
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (len % 10) as u8;
+        len /= 10;
+        if len == 0 {
+            break;
+        }
+    }
+    prefixed.extend_from_slice(&digits[start..]);
+    prefixed.append(message);
+    keccak256(env, &prefixed)
+}
+
+// Uncompressed SEC-1 public key (0x04 || x || y) that signed `digest`. InvalidSigner for a
+// malformed recovery id; a signature that recovers no key makes the host trap.
+pub fn recover(env: &Env, digest: &Hash<32>, signature: &BytesN<65>) -> Result<BytesN<65>, PiCoinError> {
+    let bytes = signature.to_array();
+    let recovery_id = match bytes[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return Err(PiCoinError::InvalidSigner),
+    };
+    let mut rs = [0u8; 64];
+    rs.copy_from_slice(&bytes[..64]);
+    Ok(env.crypto().secp256k1_recover(digest, &BytesN::from_array(env, &rs), recovery_id))
+}
+
+// Ethereum address of an uncompressed public key: the last 20 bytes of keccak256(x || y)
+pub fn eth_address(env: &Env, public_key: &BytesN<65>) -> BytesN<20> {
+    let hash = keccak256(env, &Bytes::from(public_key.clone()).slice(1..)).to_array();
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    BytesN::from_array(env, &address)
+}
+
+// Ethereum address that personal_sign-ed `message` with `signature`
+pub fn recover_eth_signer(env: &Env, message: &Bytes, signature: &BytesN<65>) -> Result<BytesN<20>, PiCoinError> {
+    let public_key = recover(env, &eth_message_hash(env, message), signature)?;
+    Ok(eth_address(env, &public_key))
+}
@@ -0,0 +1,62 @@
+// Transfer fees: a basis-point cut of each transfer goes to a governance-chosen fee
+// collector (normally the treasury). Contracts that charge their own fees (DEX pools,
+// the PSM) can be exempted so users are not charged twice.
+use soroban_sdk::{contracttype, Address, Env};
+use crate::fixed_point::{self, Rounding};
+use crate::{peg_band, PiCoinError};
+
+// Highest transfer fee governance may set, in basis points (10%). Also bounds the peg
+// band's fee floor.
+pub const MAX_FEE_BPS: u32 = 1_000;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum FeeKey {
+    Rate, // Transfer fee in basis points, set by governance
+    Collector, // Address credited with transfer fees; no fee is charged while unset
+    Exempt(Address), // Accounts whose transfers are fee-free
+}
+
+pub fn rate(env: &Env) -> u32 {
+    env.storage().instance().get(&FeeKey::Rate).unwrap_or(0)
+}
+
+pub fn set_rate(env: &Env, fee_bps: u32) {
+    env.storage().instance().set(&FeeKey::Rate, &fee_bps);
+}
+
+pub fn collector(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&FeeKey::Collector)
+}
+
+pub fn set_collector(env: &Env, collector: &Address) {
+    env.storage().instance().set(&FeeKey::Collector, collector);
+}
+
+pub fn is_exempt(env: &Env, account: &Address) -> bool {
+    env.storage().persistent().has(&FeeKey::Exempt(account.clone()))
+}
+
+pub fn set_exempt(env: &Env, account: &Address, exempt: bool) {
+    let key = FeeKey::Exempt(account.clone());
+    if exempt {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+// Fee owed on a transfer of `amount` from `from` to `to`, with the collector to credit.
+// None when no collector is configured, the fee rounds to zero, or either side is exempt.
+// The rate is raised to the peg band's fee floor outside the soft band. The fee rounds
+// down, in the sender's favour.
+pub fn assess(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<Option<(Address, i128)>, PiCoinError> {
+    let Some(collector) = collector(env) else {
+        return Ok(None);
+    };
+    let fee = fixed_point::apply_bps(amount, peg_band::fee_bps(env, rate(env)), Rounding::Down)?;
+    if fee == 0 || is_exempt(env, from) || is_exempt(env, to) {
+        return Ok(None);
+    }
+    Ok(Some((collector, fee)))
+}
@@ -0,0 +1,98 @@
+// Fixed-point math: every scaled multiply-divide in the token (fees, collateral ratio,
+// rebase index) goes through here, so rounding is chosen explicitly at each call site and
+// an intermediate product that does not fit in i128 is an error instead of a wrap or
+// a trap.
+use soroban_sdk::contracttype;
+use crate::PiCoinError;
+
+// Basis points in one whole
+pub const BPS: i128 = 10_000;
+// π to 19 significant digits (truncated), scaled by PI_SCALE; one digit more than
+// MAX_PI_DIGITS so the last digit handed out can be rounded correctly
+pub const PI: i128 = 3_141_592_653_589_793_238;
+pub const PI_SCALE: i128 = 1_000_000_000_000_000_000;
+// Most significant digits of π `pi` returns
+pub const MAX_PI_DIGITS: u32 = 18;
+
+// Direction a scaled result rounds in
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Down, // Toward negative infinity
+    Up, // Toward positive infinity
+    HalfUp, // To the nearest value; ties toward positive infinity
+}
+
+// a * b / denominator, rounded as asked. ArithmeticError if a * b overflows, the result
+// does not fit, or the denominator is zero.
+pub fn mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> Result<i128, PiCoinError> {
+    let product = a.checked_mul(b).ok_or(PiCoinError::ArithmeticError)?;
+    div(product, denominator, rounding)
+}
+
+// numerator / denominator, rounded as asked
+pub fn div(numerator: i128, denominator: i128, rounding: Rounding) -> Result<i128, PiCoinError> {
+    if denominator == 0 {
+        return Err(PiCoinError::ArithmeticError);
+    }
+    // Normalize to a positive denominator so Euclidean division is floor division
+    let (numerator, denominator) = if denominator < 0 {
+        (numerator.checked_neg().ok_or(PiCoinError::ArithmeticError)?, denominator.checked_neg().ok_or(PiCoinError::ArithmeticError)?)
+    } else {
+        (numerator, denominator)
+    };
+    let quotient = numerator.div_euclid(denominator);
+    let remainder = numerator.rem_euclid(denominator);
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder > 0,
+        Rounding::HalfUp => remainder >= denominator - remainder,
+    };
+    if round_up {
+        quotient.checked_add(1).ok_or(PiCoinError::ArithmeticError)
+    } else {
+        Ok(quotient)
+    }
+}
+
+// `bps` basis points of `amount`
+pub fn apply_bps(amount: i128, bps: u32, rounding: Rounding) -> Result<i128, PiCoinError> {
+    mul_div(amount, bps as i128, BPS, rounding)
+}
+
+// `base` (scaled by `scale`) raised to `exponent`, scaled by `scale`; for compounding a
+// per-period rate, e.g. pow(one + rate, periods, one). Squaring rounds down each step.
+pub fn pow(base: i128, exponent: u64, scale: i128) -> Result<i128, PiCoinError> {
+    let mut result = scale;
+    let mut base = base;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_div(result, base, scale, Rounding::Down)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_div(base, base, scale, Rounding::Down)?;
+        }
+    }
+    Ok(result)
+}
+
+// π to `digits` significant digits (1..=MAX_PI_DIGITS) as (value, scale), rounded as
+// asked: pi(6, HalfUp) = (314_159, 100_000)
+pub fn pi(digits: u32, rounding: Rounding) -> Result<(i128, i128), PiCoinError> {
+    if digits == 0 || digits > MAX_PI_DIGITS {
+        return Err(PiCoinError::InvalidPrecision);
+    }
+    let value = div(PI, 10i128.pow(MAX_PI_DIGITS + 1 - digits), rounding)?;
+    Ok((value, 10i128.pow(digits - 1)))
+}
+
+// π-based peg adjustment: `base_value` plus π expressed in the same units, where one
+// whole dollar is `unit` (1_000_000 for the micro-unit peg). π is taken to `digits`
+// significant digits, and converting it to `unit` rounds as asked.
+pub fn pi_peg(base_value: i128, unit: i128, digits: u32, rounding: Rounding) -> Result<i128, PiCoinError> {
+    let (value, scale) = pi(digits, rounding)?;
+    let adjustment = mul_div(value, unit, scale, rounding)?;
+    base_value.checked_add(adjustment).ok_or(PiCoinError::ArithmeticError)
+}
@@ -0,0 +1,27 @@
+// Flash minting: the governance-approved flash lender (PiCoinFlashLender) may credit PI
+// that must be settled - repaid plus fee - before its transaction completes. The token
+// only books credits and settlements; the lender drives the receiver callback.
+use soroban_sdk::{contracttype, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum FlashKey {
+    Lender, // Contract allowed to flash-credit PI
+    Outstanding, // Flash-credited PI not yet settled
+}
+
+pub fn lender(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&FlashKey::Lender)
+}
+
+pub fn set_lender(env: &Env, lender: &Address) {
+    env.storage().instance().set(&FlashKey::Lender, lender);
+}
+
+pub fn outstanding(env: &Env) -> i128 {
+    env.storage().instance().get(&FlashKey::Outstanding).unwrap_or(0)
+}
+
+pub fn set_outstanding(env: &Env, amount: i128) {
+    env.storage().instance().set(&FlashKey::Outstanding, &amount);
+}
@@ -0,0 +1,45 @@
+// Groth16 verification over BLS12-381 with the host's pairing functions. A proof
+// (A, B, C) is valid for public inputs x_1..x_n when
+//   e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1,
+// where vk_x = ic_0 + x_1 * ic_1 + ... + x_n * ic_n.
+use soroban_sdk::{
+    contracttype,
+    crypto::bls12_381::{Fr, G1Affine, G2Affine},
+    vec, Env, Vec,
+};
+
+// Verification key of a circuit, as exported by its trusted setup
+#[contracttype]
+#[derive(Clone)]
+pub struct VerificationKey {
+    pub alpha: G1Affine,
+    pub beta: G2Affine,
+    pub gamma: G2Affine,
+    pub delta: G2Affine,
+    pub ic: Vec<G1Affine>, // One point per public input, plus one
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Groth16Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+// Whether `proof` proves the circuit of `vk` for `inputs`. Points not on the curve or
+// outside the prime-order subgroup make the host trap.
+pub fn verify(env: &Env, vk: &VerificationKey, proof: &Groth16Proof, inputs: &Vec<Fr>) -> bool {
+    if vk.ic.len() != inputs.len() + 1 {
+        return false;
+    }
+    let bls = env.crypto().bls12_381();
+    let mut vk_x = vk.ic.get_unchecked(0);
+    if !inputs.is_empty() {
+        vk_x = bls.g1_add(&vk_x, &bls.g1_msm(vk.ic.slice(1..), inputs.clone()));
+    }
+    bls.pairing_check(
+        vec![env, -proof.a.clone(), vk.alpha.clone(), vk_x, proof.c.clone()],
+        vec![env, proof.b.clone(), vk.beta.clone(), vk.gamma.clone(), vk.delta.clone()],
+    )
+}
@@ -1,13 +1,66 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, token, xdr::ToXdr, Address, Env, String, Symbol, Vec, log, Bytes, BytesN};
+
+mod allowance;
+mod balance;
+mod batch;
+mod compliance;
+mod events;
+pub mod evm;
+mod fees;
+pub mod fixed_point;
+mod flash;
+mod groth16;
+mod merkle;
+mod meta;
+mod metadata;
+mod migration;
+mod pause;
+mod peg_band;
+mod provenance;
+mod rate_limit;
+mod rebase;
+mod reentrancy;
+mod shielded;
+mod shutdown;
+mod snapshot;
+mod sources;
+mod ttl;
+mod validation;
+#[cfg(any(test, feature = "test"))]
+pub mod testutils;
+
+pub use batch::Invocation;
+pub use compliance::{ComplianceList, ComplianceMode};
+pub use events::EVENT_SCHEMA_VERSION;
+pub use fixed_point::Rounding;
+pub use groth16::{Groth16Proof, VerificationKey};
+pub use meta::{MetaTransfer, Permit};
+pub use metadata::TokenMetadata;
+pub use pause::PauseTrip;
+pub use peg_band::{PegBandStatus, PegBands, PegMode};
+pub use provenance::{Attestation, Lot, ParticipantLeaf};
+pub use rate_limit::{RateLimits, SourceQuota};
+pub use rebase::{RebaseConfig, INDEX_ONE};
+pub use shielded::PrivateTransferInputs;
+pub use shutdown::{GuardianConfig, OraclePrice, Settlement};
+pub use sources::SourceInfo;
+pub use ttl::TtlConfig;
+
+// Storage layout version written by this wasm; bumped whenever PiCoinData changes shape
+// (v1: original layout, v2: adds fee_bps and balances, v3: balances move to per-holder
+// persistent entries, v4: holder sources and the fee rate move out of PiCoinData)
+pub const CONTRACT_VERSION: u32 = 4;
 
 #[contracttype]
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PiCoinSource {
     Mining,
     Rewards,
     P2P,
     Invalid, // Default for rejected sources
+    Registered(Symbol), // Channel added through the source registry (register_source)
+    FlashMint, // Flash-credited by the flash lender; never accepted by mint, attestation or the registry
 }
 
 #[contracttype]
@@ -20,16 +73,70 @@ pub struct PiCoinData {
     pub oracle_address: Address, // AI-enhanced oracle for global price verification
     pub governance_address: Address, // For quantum-secure governance
     pub anti_fraud_hash: BytesN<32>, // SHA-256 hash for anti-duplication
-    pub provenance: Map<Address, PiCoinSource>, // New: Track source per holder for ecosystem entry
     pub quantum_provenance_hash: BytesN<32>, // New: Quantum hash for provenance integrity
 }
 
+// Snapshot of the contract's wiring and parameters, returned by `config`
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiCoinConfig {
+    pub symbol: Symbol,
+    pub admin: Address, // Minting authority
+    pub collateral_asset: Address,
+    pub oracle_address: Address,
+    pub governance_address: Address,
+    pub fee_bps: u32,
+    pub fee_collector: Option<Address>,
+    pub compliance_mode: ComplianceMode,
+    pub version: u32, // Storage layout version
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
 pub enum PiCoinError {
     InsufficientCollateral = 1,
     PegDeviation = 2,
     Unauthorized = 3,
     InvalidSource = 4, // New: For rejected sources
+    InsufficientBalance = 5,
+    VersionMismatch = 6,
+    InvalidFee = 7,
+    InvalidProof = 8,
+    InvalidAllowance = 9,
+    InsufficientAllowance = 10,
+    FlashLoanNotRepaid = 11,
+    RateLimitExceeded = 12,
+    InvalidRateLimit = 13,
+    NotCompliant = 14,
+    InvalidNonce = 15,
+    SignatureExpired = 16,
+    InvalidSigner = 17,
+    BatchTooLarge = 18,
+    InvalidTtl = 19,
+    RebaseDisabled = 20,
+    RebaseTooEarly = 21,
+    InvalidRebaseConfig = 22,
+    ArithmeticError = 23, // Overflow or division by zero in fixed-point math
+    InvalidPrecision = 24,
+    NullifierSpent = 25,
+    PrivateTransfersDisabled = 26,
+    ShieldedTreeFull = 27,
+    InvalidNoteAmount = 28, // Outside the circuit's 64-bit note range
+    DeadlineExpired = 29,
+    SlippageExceeded = 30,
+    Reentrant = 31, // Called inside a flash mint's callback window
+    Shutdown = 32, // Frozen by emergency shutdown
+    NotShutdown = 33,
+    InvalidAmount = 34,
+    SnapshotUnavailable = 35, // Ledger not closed yet, or pruned from the history
+    MintQuotaExceeded = 36, // The source's quota for this epoch is used up
+    Overflow = 37, // A balance, supply or running total would pass i128
+    SelfTransfer = 38, // A transfer's sender and recipient are the same account
+    InvalidMetadata = 39,
+    InvalidPegBands = 40,
+    Paused = 41, // Frozen by the pause breaker until governance unpauses
+    UnknownVersion = 42, // Stored state has a version no migration starts from
 }
 
 #[contract]
@@ -37,15 +144,15 @@ pub struct PiCoinContract;
 
 #[contractimpl]
 impl PiCoinContract {
-    // Initialize with fixed parameters (hyper-tech: immutable setup)
-    pub fn initialize(
+    // Constructor with fixed parameters (hyper-tech: immutable setup). Runs atomically as
+    // part of deployment, so there is no window in which anyone else can initialize first.
+    pub fn __constructor(
         env: Env,
         admin: Address,
         collateral_asset: Address,
         oracle: Address,
         governance: Address,
-    ) -> Result<(), PiCoinError> {
-        admin.require_auth();
+    ) {
         let data = PiCoinData {
             symbol: Symbol::new(&env, "PI"),
             total_supply: 100_000_000_000, // Fixed supply
@@ -53,21 +160,29 @@ impl PiCoinContract {
             collateral_asset,
             oracle_address: oracle,
             governance_address: governance,
-            anti_fraud_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique")),
-            provenance: Map::new(&env), // Initialize provenance map
-            quantum_provenance_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Provenance-Quantum-Unmatched")),
+            anti_fraud_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique")).into(),
+            quantum_provenance_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Provenance-Quantum-Unmatched")).into(),
         };
         env.storage().instance().set(&Symbol::new(&env, "data"), &data);
-        log!(&env, "Pi Coin initialized: Symbol PI, Supply 100B, Peg $314,159 - Exclusive to Mining/Rewards/P2P sources");
-        Ok(())
+        env.storage().instance().set(&Symbol::new(&env, "admin"), &admin);
+        env.storage().instance().set(&Symbol::new(&env, "version"), &CONTRACT_VERSION);
+        metadata::write(&env, &metadata::default(&env));
+        log!(&env, "Pi Coin initialized: Symbol PI, Supply 100B, Peg $314,159 - Exclusive to Mining/Rewards/P2P sources, admin {}", admin);
     }
 
-    // Mint PI with full collateral backing (1:1, fixed peg) - Only for valid sources
+    // Admin-only: Mint PI with full collateral backing (1:1, fixed peg) - Only for valid sources
     pub fn mint(env: Env, to: Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
         let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        Self::admin(&env, &data).require_auth();
+        validation::amount(amount)?;
+        shutdown::check_live(&env)?;
+        pause::check(&env)?;
+        ttl::bump_instance(&env);
+        let band = peg_band::refresh(&env, Self::query_ai_oracle(&env, &data.oracle_address), data.peg_value)?;
+        peg_band::check_mint(&env, band.mode, &Self::admin(&env, &data))?;
         
-        // Hyper-tech validation: Only allow specific sources for $314,159 peg
-        if source != PiCoinSource::Mining && source != PiCoinSource::Rewards && source != PiCoinSource::P2P {
+        // Hyper-tech validation: Only allow registry-approved sources for $314,159 peg
+        if !sources::is_valid(&env, &source) {
             return Err(PiCoinError::InvalidSource); // Reject invalid sources - no entry to ecosystem
         }
         
@@ -77,103 +192,1117 @@ impl PiCoinContract {
             return Err(PiCoinError::InsufficientCollateral);
         }
         
-        // Quantum-resistant provenance: Hash and sign source
-        let provenance_sig = env.crypto().ed25519_sign(&env.current_contract_address(), &source.clone().to_val().to_be_bytes());
-        data.provenance.set(to.clone(), source);
-        data.quantum_provenance_hash = env.crypto().sha256(&Bytes::from_slice(&env, &provenance_sig.to_array()));
-        
-        // Quantum-resistant signature for transaction
-        let sig_data = Bytes::from_slice(&env, &amount.to_be_bytes());
-        let signature = env.crypto().ed25519_sign(&env.current_contract_address(), &sig_data);
+        if !compliance::is_permitted(&env, &to) {
+            return Err(PiCoinError::NotCompliant);
+        }
+        rate_limit::record_mint(&env, amount)?;
+        rate_limit::record_source_mint(&env, &source, amount)?;
+
+        // Provenance: record the lot and fold the source into the provenance hash
+        provenance::mint_lot(&env, &to, source.clone(), amount)?;
+        provenance::set_source(&env, &to, &source);
+        balance::add(&env, &to, amount)?;
+        events::mint_event(&env, &to, amount, source.clone());
+        data.quantum_provenance_hash = env.crypto().sha256(&source.clone().to_xdr(&env)).into();
         env.storage().instance().set(&Symbol::new(&env, "data"), &data);
-        log!(&env, "Minted {} PI from {} source with quantum provenance: {:?} - Peg $314,159 applied", amount, source, provenance_sig);
+        log!(&env, "Minted {} PI from {} source with quantum provenance hash {:?} - Peg $314,159 applied", amount, source, data.quantum_provenance_hash);
         // Simulate global recognition: Log as payment-ready only for valid sources
         Self::simulate_global_payment(&env, amount);
         Ok(())
     }
 
-    // Transfer PI (hyper-tech: anti-fraud with ZKP simulation) - Validate provenance
+    // Transfer PI - Validate provenance.
+    // The recipient receives `amount` minus the transfer fee, which goes to the fee collector.
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
         from.require_auth();
-        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
-        
-        // Hyper-tech provenance check: Only transfer if from valid source (ecosystem entry)
-        let source = data.provenance.get(from.clone()).unwrap_or(PiCoinSource::Invalid);
-        if source == PiCoinSource::Invalid {
-            return Err(PiCoinError::InvalidSource); // Reject - no ecosystem access
-        }
-        
-        // Ultimate level: Zero-knowledge proof simulation for anti-forgery
-        let proof = env.crypto().sha256(&Bytes::from_slice(&env, &[amount as u8, 42])); // Simulated ZKP
-        if proof != env.storage().instance().get(&Symbol::new(&env, "zkp_base")).unwrap_or(BytesN::from_array(&env, &[0; 32])) {
-            return Err(PiCoinError::Unauthorized);
+        Self::move_funds(&env, &from, &to, amount)
+    }
+
+    // `mint`, valid only up to `deadline_ledger`, so a mint signed long before inclusion
+    // cannot land at a stale peg
+    pub fn mint_with_deadline(env: Env, to: Address, amount: i128, source: PiCoinSource, deadline_ledger: u32) -> Result<(), PiCoinError> {
+        Self::check_deadline(&env, deadline_ledger)?;
+        Self::mint(env, to, amount, source)
+    }
+
+    // `transfer`, valid only up to `deadline_ledger` and only if `to` receives at least
+    // `min_received` after the transfer fee (which governance may raise in between).
+    // Returns the amount received.
+    pub fn transfer_with_deadline(env: Env, from: Address, to: Address, amount: i128, min_received: i128, deadline_ledger: u32) -> Result<i128, PiCoinError> {
+        from.require_auth();
+        Self::check_deadline(&env, deadline_ledger)?;
+        validation::transfer(&from, &to, amount)?;
+        let fee = fees::assess(&env, &from, &to, amount)?.map(|(_, fee)| fee).unwrap_or(0);
+        if amount - fee < min_received {
+            return Err(PiCoinError::SlippageExceeded);
         }
-        
-        // Inherit provenance to recipient
-        data.provenance.set(to.clone(), source);
-        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
-        log!(&env, "Transferred {} PI with valid provenance from {} source - Anti-fraud ZKP verified", amount, source);
-        Ok(())
+        Self::move_funds(&env, &from, &to, amount)?;
+        Ok(amount - fee)
+    }
+
+    // Allow `spender` to move up to `amount` of `from`'s PI until `expiration_ledger`
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) -> Result<(), PiCoinError> {
+        from.require_auth();
+        Self::set_allowance(&env, &from, &spender, amount, expiration_ledger)
+    }
+
+    // Approve `spender` with an owner signature instead of an owner transaction, so a
+    // contract can take the permit and pull PI in one call. The owner signs the XDR of
+    // `Permit { network_id, token, owner, spender, amount, nonce, deadline }`; the
+    // allowance lasts until `deadline`, the last ledger the signature is valid for.
+    pub fn permit(env: Env, owner: Address, spender: Address, amount: i128, deadline: u32, signature: BytesN<64>) -> Result<(), PiCoinError> {
+        validation::non_negative(amount)?;
+        let payload = Permit {
+            network_id: env.ledger().network_id(),
+            token: env.current_contract_address(),
+            owner: owner.clone(),
+            spender: spender.clone(),
+            amount,
+            nonce: meta::permit_nonce(&env, &owner),
+            deadline,
+        };
+        meta::verify_permit(&env, payload, &signature)?;
+        Self::set_allowance(&env, &owner, &spender, amount, deadline)
+    }
+
+    // View: Nonce the next permit signed by `owner` must carry
+    pub fn permit_nonce(env: Env, owner: Address) -> u64 {
+        meta::permit_nonce(&env, &owner)
+    }
+
+    // Transfer on behalf of `from`, spending the allowance it gave `spender`. Same checks
+    // and fee as `transfer`.
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        spender.require_auth();
+        allowance::spend(&env, &from, &spender, amount)?;
+        Self::move_funds(&env, &from, &to, amount)
+    }
+
+    // Gasless transfer: execute a transfer `from` signed off-chain with its account key
+    // over `MetaTransfer { network_id, token, from, to, amount, nonce, expiration_ledger }`.
+    // Anyone (a relayer) may submit it; `nonce` must be `from`'s next nonce.
+    pub fn transfer_with_signature(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        nonce: u64,
+        expiration_ledger: u32,
+        signature: BytesN<64>,
+    ) -> Result<(), PiCoinError> {
+        let payload = MetaTransfer {
+            network_id: env.ledger().network_id(),
+            token: env.current_contract_address(),
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            nonce,
+            expiration_ledger,
+        };
+        meta::verify_transfer(&env, payload, &signature)?;
+        Self::move_funds(&env, &from, &to, amount)
     }
 
-    // Verify peg stability (AI oracle checks global markets) - Only for valid sources
+    // View: Nonce the next signed transfer from `holder` must carry
+    pub fn nonce(env: Env, holder: Address) -> u64 {
+        meta::nonce(&env, &holder)
+    }
+
+    // View: Remaining allowance `from` has given `spender`
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        allowance::read(&env, &from, &spender).amount
+    }
+
+    // Verify peg stability (AI oracle checks global markets) - Only for valid sources.
+    // Re-evaluates the peg band: true when healthy, false in the soft band, PegDeviation in
+    // the hard band.
     pub fn verify_peg(env: Env, holder: Address) -> Result<bool, PiCoinError> {
         let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
         
         // Hyper-tech: Check provenance first
-        let source = data.provenance.get(holder).unwrap_or(PiCoinSource::Invalid);
+        let source = provenance::source_of(&env, &holder);
         if source == PiCoinSource::Invalid {
             return Err(PiCoinError::InvalidSource); // No peg verification for invalid sources
         }
         
         let global_price = Self::query_ai_oracle(&env, &data.oracle_address);
-        if (global_price - data.peg_value).abs() > 1_000 { // Allow micro-deviation
+        let band = peg_band::refresh(&env, global_price, data.peg_value)?;
+        if band.mode == PegMode::Hard {
             return Err(PiCoinError::PegDeviation);
         }
-        log!(&env, "Peg verified for {} source: Global price matches $314,159", source);
-        Ok(true)
+        log!(&env, "Peg verified for {} source: Global price {} bps from $314,159", source, band.deviation_bps);
+        Ok(band.mode == PegMode::Healthy)
     }
 
-    // Governance vote (quantum-secure) - Only for valid sources
-    pub fn governance_vote(env: Env, voter: Address, proposal: Symbol) -> Result<(), PiCoinError> {
-        voter.require_auth();
+    // New: Verify ecosystem entry (global recognition check). Passes for a valid mint or
+    // attested source, or for a badge from the badge registry.
+    pub fn verify_ecosystem_entry(env: Env, holder: Address) -> Result<bool, PiCoinError> {
+        let source = provenance::source_of(&env, &holder);
+        if source != PiCoinSource::Invalid {
+            log!(&env, "Ecosystem entry approved: {} source verified for global Pi Coin recognition", source);
+            return Ok(true);
+        }
+        if provenance::has_badge(&env, &holder) {
+            log!(&env, "Ecosystem entry approved: verified participant badge");
+            return Ok(true);
+        }
+        log!(&env, "Ecosystem entry rejected: Invalid source - No access to Pi Coin $314,159 peg");
+        Ok(false)
+    }
+
+    // Burn PI from the caller's own balance (e.g. forfeited governance deposits)
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), PiCoinError> {
+        from.require_auth();
+        Self::burn_funds(&env, &from, amount)
+    }
+
+    // Governance-only: Claw back `amount` of `holder`'s PI (court orders, stolen funds),
+    // destroying it along with its provenance lots
+    pub fn clawback(env: Env, holder: Address, amount: i128) -> Result<(), PiCoinError> {
         let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        Self::burn_funds(&env, &holder, amount)?;
+        events::clawback_event(&env, &holder, amount);
+        Ok(())
+    }
+
+    // Pay several recipients from one balance with a single authorization (payroll,
+    // distributions). All transfers succeed or none do; at most MAX_BATCH recipients.
+    pub fn batch_transfer(env: Env, from: Address, transfers: Vec<(Address, i128)>) -> Result<(), PiCoinError> {
+        from.require_auth();
+        if transfers.len() > batch::MAX_BATCH {
+            return Err(PiCoinError::BatchTooLarge);
+        }
+        for (to, amount) in transfers.iter() {
+            Self::move_funds(&env, &from, &to, amount)?;
+        }
+        Ok(())
+    }
+
+    // Run several token operations as `caller` with a single authorization, all or
+    // nothing; at most MAX_BATCH steps
+    pub fn multicall(env: Env, caller: Address, calls: Vec<Invocation>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        if calls.len() > batch::MAX_BATCH {
+            return Err(PiCoinError::BatchTooLarge);
+        }
+        for call in calls.iter() {
+            match call {
+                Invocation::Transfer(to, amount) => Self::move_funds(&env, &caller, &to, amount)?,
+                Invocation::TransferFrom(from, to, amount) => {
+                    allowance::spend(&env, &from, &caller, amount)?;
+                    Self::move_funds(&env, &from, &to, amount)?
+                }
+                Invocation::Approve(spender, amount, expiration_ledger) => {
+                    Self::set_allowance(&env, &caller, &spender, amount, expiration_ledger)?
+                }
+                Invocation::Burn(amount) => Self::burn_funds(&env, &caller, amount)?,
+            }
+        }
+        Ok(())
+    }
+
+    // View: Lot breakdown of a holder's balance, oldest first - which mints (source,
+    // time) the PI came from
+    pub fn provenance_of(env: Env, holder: Address) -> Result<Vec<Lot>, PiCoinError> {
+        provenance::lots_of(&env, &holder)
+    }
+
+    // View: Deterministic provenance hash of a holder (sha256 of its address XDR)
+    pub fn provenance_hash(env: Env, holder: Address) -> BytesN<32> {
+        provenance::holder_hash(&env, &holder)
+    }
+
+    // Prove off-chain provenance: `proof` must lead from `leaf` (holder + source) to the
+    // governance-published participant root. On success the holder gains ecosystem entry
+    // with the attested source. Permissionless - the proof itself is the authorization.
+    pub fn attest_provenance(env: Env, holder: Address, proof: Vec<BytesN<32>>, leaf: ParticipantLeaf) -> Result<(), PiCoinError> {
+        if leaf.participant != holder {
+            return Err(PiCoinError::InvalidProof);
+        }
+        if leaf.source == PiCoinSource::Invalid || leaf.source == PiCoinSource::FlashMint {
+            return Err(PiCoinError::InvalidSource);
+        }
+        let (root, root_epoch) = provenance::attestation_root(&env).ok_or(PiCoinError::InvalidProof)?;
+        if !merkle::verify(&env, &root, &leaf.clone().to_xdr(&env), &proof) {
+            return Err(PiCoinError::InvalidProof);
+        }
+
+        let attestation = Attestation { source: leaf.source.clone(), root_epoch, attested_at: env.ledger().timestamp() };
+        provenance::record_attestation(&env, &holder, &attestation);
+        provenance::set_source(&env, &holder, &leaf.source);
+        events::attested_event(&env, &holder, leaf.source, root_epoch);
+        Ok(())
+    }
+
+    // Governance-only: Publish (rotate) the Merkle root of approved ecosystem participants.
+    // Returns the new root epoch; attestations under older roots are no longer current.
+    pub fn set_attestation_root(env: Env, root: BytesN<32>) -> Result<u32, PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        let epoch = provenance::rotate_root(&env, &root);
+        log!(&env, "Participant attestation root rotated to epoch {}", epoch);
+        Ok(epoch)
+    }
+
+    // View: Current participant root and its epoch
+    pub fn attestation_root(env: Env) -> Option<(BytesN<32>, u32)> {
+        provenance::attestation_root(&env)
+    }
+
+    // Governance-only: Set the contract (PiCoinLotReceipts) that issues an NFT receipt for
+    // every lot minted from now on
+    pub fn set_lot_receipts(env: Env, receipts: Address) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        provenance::set_lot_receipts(&env, &receipts);
+        Ok(())
+    }
+
+    // View: Lot receipts contract, if set
+    pub fn lot_receipts(env: Env) -> Option<Address> {
+        provenance::lot_receipts(&env)
+    }
+
+    // Governance-only: Set the badge contract (PiCoinBadges) whose holders pass
+    // `verify_ecosystem_entry` regardless of their mint provenance
+    pub fn set_badge_registry(env: Env, registry: Address) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        provenance::set_badge_registry(&env, &registry);
+        Ok(())
+    }
+
+    // View: Badge registry, if set
+    pub fn badge_registry(env: Env) -> Option<Address> {
+        provenance::badge_registry(&env)
+    }
+
+    // View: Holder's attestation under the current root
+    pub fn attestation_of(env: Env, holder: Address) -> Option<Attestation> {
+        provenance::current_attestation(&env, &holder)
+    }
+
+    // View: PI balance of a holder. SEP-41 fixes the return type, so a balance too large
+    // to convert at the current rebase index reads as i128::MAX.
+    pub fn balance(env: Env, id: Address) -> i128 {
+        balance::read(&env, &id).unwrap_or(i128::MAX)
+    }
+
+    // View: `holder`'s PI at the end of `ledger`, which must be closed (before the current
+    // one) and inside the snapshot retention window
+    pub fn balance_at(env: Env, holder: Address, ledger: u32) -> Result<i128, PiCoinError> {
+        balance::read_at(&env, &holder, ledger)
+    }
+
+    // View: Circulating PI at the end of `ledger`, under the same conditions as balance_at
+    pub fn circulating_at(env: Env, ledger: u32) -> Result<i128, PiCoinError> {
+        balance::circulating_at(&env, ledger)
+    }
+
+    // Governance-only: Ledgers of balance history kept for balance_at; older checkpoints
+    // are pruned as holders' balances change
+    pub fn set_snapshot_retention(env: Env, ledgers: u32) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        snapshot::set_retention(&env, ledgers);
+        Ok(())
+    }
+
+    // View: Ledgers of balance history kept
+    pub fn snapshot_retention(env: Env) -> u32 {
+        snapshot::retention(&env)
+    }
+
+    // Keep the contract instance alive for the configured lifetime (permissionless)
+    pub fn extend_instance_ttl(env: Env) {
+        ttl::extend_instance(&env);
+    }
+
+    // Keep a holder's balance and provenance entries alive for the configured lifetime
+    // (permissionless); false if the holder has no balance entry
+    pub fn extend_balance_ttl(env: Env, holder: Address) -> bool {
+        provenance::extend_ttl(&env, &holder);
+        ttl::extend_persistent(&env, &balance::BalanceKey::Balance(holder))
+    }
+
+    // Bring a holder's archived balance and provenance lots back into use (permissionless):
+    // the invocation restores the archived entries, this extends them to the full
+    // lifetime and returns the restored balance, emitting ("restored", holder)
+    pub fn restore_balance(env: Env, holder: Address) -> Result<i128, PiCoinError> {
+        provenance::extend_ttl(&env, &holder);
+        ttl::extend_persistent(&env, &balance::BalanceKey::Balance(holder.clone()));
+        let amount = balance::read(&env, &holder)?;
+        events::restored_event(&env, &holder, amount);
+        Ok(amount)
+    }
+
+    // Governance-only: Set the TTL thresholds used for bump-on-access and explicit extension
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        ttl::set_config(&env, &config)
+    }
+
+    // View: TTL thresholds in effect
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        ttl::config(&env)
+    }
+
+    // Governance-only: Update the peg value (executed via an on-chain proposal). The peg
+    // must be positive, and PegDeviation if it would put the oracle price past the hard band.
+    pub fn set_peg(env: Env, peg_value: i128) -> Result<(), PiCoinError> {
+        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        validation::amount(peg_value)?;
+        let price = Self::query_ai_oracle(&env, &data.oracle_address);
+        if peg_band::evaluate(&env, price, peg_value)?.mode == PegMode::Hard {
+            return Err(PiCoinError::PegDeviation);
+        }
+        let previous = data.peg_value;
+        data.peg_value = peg_value;
+        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+        events::peg_update_event(&env, previous, peg_value);
+        log!(&env, "Peg updated by governance: {}", peg_value);
+        Ok(())
+    }
+
+    // Governance-only: Update the transfer fee in basis points
+    pub fn set_fee(env: Env, fee_bps: u32) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        if fee_bps > fees::MAX_FEE_BPS {
+            return Err(PiCoinError::InvalidFee);
+        }
+        fees::set_rate(&env, fee_bps);
+        log!(&env, "Transfer fee updated by governance: {} bps", fee_bps);
+        Ok(())
+    }
+
+    // Governance-only: Set the address credited with transfer fees (e.g. the treasury)
+    pub fn set_fee_collector(env: Env, collector: Address) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        fees::set_collector(&env, &collector);
+        log!(&env, "Fee collector set by governance: {}", collector);
+        Ok(())
+    }
+
+    // Governance-only: Exempt (or un-exempt) an account from transfer fees, e.g. DEX
+    // pools and the PSM, which charge their own fees
+    pub fn set_fee_exempt(env: Env, account: Address, exempt: bool) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        fees::set_exempt(&env, &account, exempt);
+        log!(&env, "Fee exemption for {} set to {}", account, exempt);
+        Ok(())
+    }
+
+    // View: Current transfer fee in basis points and its collector, if any
+    pub fn fee_config(env: Env) -> (u32, Option<Address>) {
+        (fees::rate(&env), fees::collector(&env))
+    }
+
+    // View: Whether transfers to or from `account` are fee-free
+    pub fn is_fee_exempt(env: Env, account: Address) -> bool {
+        fees::is_exempt(&env, &account)
+    }
+
+    // Governance-only: Set the peg's soft and hard bands, the minimum transfer fee outside
+    // the soft band and the PSM allowed to mint in the hard band. Takes effect at the next
+    // oracle reading.
+    pub fn set_peg_bands(env: Env, bands: PegBands) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        peg_band::set_bands(&env, &bands)?;
+        events::peg_bands_event(&env, &bands);
+        Ok(())
+    }
+
+    // View: Peg bands in effect (defaults: soft 100 bps, hard 500 bps, no fee floor, no PSM)
+    pub fn peg_bands(env: Env) -> PegBands {
+        peg_band::bands(&env)
+    }
+
+    // View: System mode (Healthy, Soft or Hard) with the price and deviation it was last
+    // evaluated at. Before the first evaluation, the oracle's current price is evaluated.
+    pub fn peg_band_status(env: Env) -> Result<PegBandStatus, PiCoinError> {
+        if let Some(status) = peg_band::status(&env) {
+            return Ok(status);
+        }
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        peg_band::evaluate(&env, Self::query_ai_oracle(&env, &data.oracle_address), data.peg_value)
+    }
+
+    // Governance-only: Switch the compliance mode (Open, Blocklist or Allowlist)
+    pub fn set_compliance_mode(env: Env, mode: ComplianceMode) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        compliance::set_mode(&env, mode);
+        log!(&env, "Compliance mode switched by governance");
+        Ok(())
+    }
+
+    // Governance-only: Add accounts to the blocklist or allowlist in one call
+    pub fn add_to_list(env: Env, list: ComplianceList, accounts: Vec<Address>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        compliance::set_listed(&env, list, &accounts, true);
+        Ok(())
+    }
+
+    // Governance-only: Remove accounts from the blocklist or allowlist in one call
+    pub fn remove_from_list(env: Env, list: ComplianceList, accounts: Vec<Address>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        compliance::set_listed(&env, list, &accounts, false);
+        Ok(())
+    }
+
+    // Governance-only: Set the KYC registry whose verified accounts pass allowlist mode
+    pub fn set_kyc_registry(env: Env, registry: Address) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        compliance::set_kyc_registry(&env, &registry);
+        Ok(())
+    }
+
+    // View: Active compliance mode
+    pub fn compliance_mode(env: Env) -> ComplianceMode {
+        compliance::mode(&env)
+    }
+
+    // View: Whether `account` is on `list`
+    pub fn is_listed(env: Env, list: ComplianceList, account: Address) -> bool {
+        compliance::is_listed(&env, list, &account)
+    }
+
+    // Governance-only: Configure the velocity caps - PI minted per ledger day across all
+    // holders, and PI each address may send per `epoch_length` seconds (None: uncapped)
+    pub fn set_rate_limits(env: Env, limits: RateLimits) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        let negative = |cap: Option<i128>| cap.is_some_and(|cap| cap < 0);
+        if negative(limits.daily_mint_cap) || negative(limits.outflow_cap) || limits.epoch_length == 0 || limits.epoch_length > rate_limit::MAX_EPOCH {
+            return Err(PiCoinError::InvalidRateLimit);
+        }
+        rate_limit::set_limits(&env, &limits);
+        events::rate_limits_event(&env, &limits);
+        Ok(())
+    }
+
+    // Governance-only: Exempt an account (treasury, bridge, pool) from the outflow cap
+    pub fn set_rate_limit_bypass(env: Env, account: Address, bypass: bool) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        rate_limit::set_bypass(&env, &account, bypass);
+        events::rate_bypass_event(&env, &account, bypass);
+        Ok(())
+    }
+
+    // View: Current velocity caps
+    pub fn rate_limits(env: Env) -> RateLimits {
+        rate_limit::limits(&env)
+    }
+
+    // View: PI `account` may still send in the current epoch (None: uncapped)
+    pub fn outflow_remaining(env: Env, account: Address) -> Option<i128> {
+        rate_limit::outflow_remaining(&env, &account)
+    }
+
+    // Governance-only: Cap PI minted from `source` per `epoch_length` seconds (None lifts
+    // the quota). Applies on top of the global daily mint cap.
+    pub fn set_source_quota(env: Env, source: PiCoinSource, quota: Option<SourceQuota>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        Self::apply_source_quota(&env, &source, &quota)
+    }
+
+    // View: Quota on `source`, if any
+    pub fn source_quota(env: Env, source: PiCoinSource) -> Option<SourceQuota> {
+        rate_limit::source_quota(&env, &source)
+    }
+
+    // View: PI still mintable from `source` in the current epoch (None: no quota)
+    pub fn remaining_quota(env: Env, source: PiCoinSource) -> Option<i128> {
+        rate_limit::remaining_quota(&env, &source)
+    }
+
+    // Governance-only: Register a mint source, or update one (including the built-ins),
+    // with its attributes and quota (None: no quota). New acquisition channels are
+    // registered as PiCoinSource::Registered(symbol).
+    pub fn register_source(env: Env, source: PiCoinSource, info: SourceInfo, quota: Option<SourceQuota>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        if source == PiCoinSource::Invalid || source == PiCoinSource::FlashMint {
+            return Err(PiCoinError::InvalidSource);
+        }
+        Self::apply_source_quota(&env, &source, &quota)?;
+        sources::register(&env, &source, &info);
+        Ok(())
+    }
+
+    // View: Registry entry for `source` (None: unregistered - accepted only if built-in)
+    pub fn source_info(env: Env, source: PiCoinSource) -> Option<SourceInfo> {
+        sources::info(&env, &source)
+    }
+
+    // View: Whether mint currently accepts `source`
+    pub fn is_source_valid(env: Env, source: PiCoinSource) -> bool {
+        sources::is_valid(&env, &source)
+    }
+
+    // Governance-only: Turn rebase mode on (Some) or off (None). While on, `rebase` scales
+    // every balance toward the peg once the oracle price has left the band for
+    // `epochs` consecutive epochs.
+    pub fn set_rebase_config(env: Env, config: Option<RebaseConfig>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        rebase::set_config(&env, &config)
+    }
+
+    // Sample the oracle for this epoch (permissionless, once per epoch). Returns the rebase
+    // index in effect afterwards (INDEX_ONE = 1 PI per share), emitting "rebase" when it moved.
+    pub fn rebase(env: Env) -> Result<i128, PiCoinError> {
+        reentrancy::check(&env)?; // No sampling while a flash mint skews balances and pools
+        shutdown::check_live(&env)?; // The settlement supply is fixed
+        pause::check(&env)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let config = rebase::config(&env).ok_or(PiCoinError::RebaseDisabled)?;
+        let price = Self::query_ai_oracle(&env, &data.oracle_address);
+        if let Some((twap, index)) = rebase::observe(&env, &config, price, data.peg_value)? {
+            events::rebase_event(&env, twap, index);
+            log!(&env, "Supply rebased toward the $314,159 peg: TWAP {}, index {}", twap, index);
+        }
+        Ok(rebase::index(&env))
+    }
+
+    // View: Rebase settings, None while rebase mode is off
+    pub fn rebase_config(env: Env) -> Option<RebaseConfig> {
+        rebase::config(&env)
+    }
+
+    // View: PI per share, scaled by INDEX_ONE
+    pub fn rebase_index(env: Env) -> i128 {
+        rebase::index(&env)
+    }
+
+    // Governance-only: Install the verification key of the confidential-transfer circuit,
+    // turning private transfers on. Notes made under one key can only be spent with
+    // proofs for the same circuit, so a new key must come from a compatible setup.
+    pub fn set_shielded_vk(env: Env, vk: VerificationKey) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        shielded::set_vk(&env, &vk);
+        log!(&env, "Shielded pool verification key set by governance");
+        Ok(())
+    }
+
+    // Spend two shielded notes into two new ones with a Groth16 proof, optionally shielding
+    // PI from `public_inputs.account` (public_amount > 0, needs its signature) or
+    // unshielding to it (public_amount < 0). Anyone may submit a fully private transfer.
+    // After an emergency shutdown PI can still be unshielded (to redeem it), not shielded.
+    pub fn private_transfer(env: Env, proof: Groth16Proof, public_inputs: PrivateTransferInputs) -> Result<(), PiCoinError> {
+        let vk = shielded::vk(&env).ok_or(PiCoinError::PrivateTransfersDisabled)?;
+        shielded::check(&env, &public_inputs)?;
+        if !groth16::verify(&env, &vk, &proof, &shielded::public_inputs(&env, &public_inputs)) {
+            return Err(PiCoinError::InvalidProof);
+        }
+        let pool = env.current_contract_address();
+        if let Some(account) = &public_inputs.account {
+            if public_inputs.public_amount > 0 {
+                account.require_auth();
+                shutdown::check_live(&env)?;
+                pause::check(&env)?;
+                Self::move_unshielded(&env, account, &pool, public_inputs.public_amount)?;
+            } else {
+                Self::move_unshielded(&env, &pool, account, -public_inputs.public_amount)?;
+            }
+        }
+        let first_leaf = shielded::apply(&env, &public_inputs);
+        events::private_transfer_event(&env, &public_inputs, first_leaf);
+        Ok(())
+    }
+
+    // Deposit `amount` PI from `from` into a new shielded note. `secret` is
+    // sha256(owner key || blinding); the contract commits to it with the public amount, so
+    // the note can be spent by private transfers without a deposit proof. Returns the
+    // note's tree index.
+    pub fn deposit_shielded(env: Env, from: Address, amount: i128, secret: BytesN<32>) -> Result<u32, PiCoinError> {
+        from.require_auth();
+        shutdown::check_live(&env)?;
+        pause::check(&env)?;
+        shielded::vk(&env).ok_or(PiCoinError::PrivateTransfersDisabled)?;
+        shielded::check_deposit(&env, amount)?;
+        Self::move_unshielded(&env, &from, &env.current_contract_address(), amount)?;
+        let commitment = shielded::note_commitment(&env, amount, &secret);
+        let index = shielded::deposit(&env, amount, commitment.clone());
+        events::shielded_deposit_event(&env, &from, &commitment, index, amount);
+        Ok(index)
+    }
+
+    // View: PI held in shielded notes; at most the pool's (this contract's) balance
+    pub fn shielded_supply(env: Env) -> i128 {
+        shielded::supply(&env)
+    }
+
+    // View: Latest root of the shielded commitment tree
+    pub fn shielded_root(env: Env) -> BytesN<32> {
+        shielded::root(&env)
+    }
+
+    // View: Whether a shielded note's nullifier has been spent
+    pub fn is_spent(env: Env, nullifier: BytesN<32>) -> bool {
+        shielded::is_spent(&env, &nullifier)
+    }
+
+    // Governance-only: Set the flash lender contract allowed to flash-credit PI. Not while
+    // a flash mint is open, which only the lender that opened it can settle.
+    pub fn set_flash_lender(env: Env, lender: Address) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        reentrancy::check(&env)?;
+        flash::set_lender(&env, &lender);
+        log!(&env, "Flash lender set by governance: {}", lender);
+        Ok(())
+    }
+
+    // Flash-lender-only: Credit `amount` freshly minted PI to `to`; must be settled in the
+    // same transaction. Opens the reentrancy window the receiver callback runs in, so
+    // flash mints cannot nest. A receiver without a source is tagged FlashMint, which
+    // passes the provenance check, so it can put the PI to work before settling.
+    pub fn flash_credit(env: Env, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        let lender = flash::lender(&env).ok_or(PiCoinError::Unauthorized)?;
+        lender.require_auth();
+        validation::amount(amount)?;
+        shutdown::check_live(&env)?;
+        pause::check(&env)?;
+        reentrancy::lock(&env)?;
+        balance::add(&env, &to, amount)?;
+        if provenance::source_of(&env, &to) == PiCoinSource::Invalid {
+            provenance::set_source(&env, &to, &PiCoinSource::FlashMint);
+        }
+        let outstanding = flash::outstanding(&env).checked_add(amount).ok_or(PiCoinError::Overflow)?;
+        flash::set_outstanding(&env, outstanding);
+        Ok(())
+    }
+
+    // Flash-lender-only: Collect `amount + fee` from `from` against the allowance it gave
+    // the lender. `amount` is destroyed, `fee` goes to the fee collector (destroyed too if
+    // none is set). Fails - reverting the whole flash mint - if it cannot be paid.
+    pub fn flash_settle(env: Env, from: Address, amount: i128, fee: i128) -> Result<(), PiCoinError> {
+        let lender = flash::lender(&env).ok_or(PiCoinError::Unauthorized)?;
+        lender.require_auth();
+        validation::amount(amount)?;
+        validation::non_negative(fee)?;
+        let owed = amount.checked_add(fee).ok_or(PiCoinError::Overflow)?;
+        if allowance::read(&env, &from, &lender).amount < owed || amount > flash::outstanding(&env) || balance::read(&env, &from)? < owed {
+            return Err(PiCoinError::FlashLoanNotRepaid);
+        }
+        allowance::spend(&env, &from, &lender, owed)?;
+        balance::sub(&env, &from, owed)?;
+        if let Some(collector) = fees::collector(&env) {
+            balance::add(&env, &collector, fee)?;
+        }
+        flash::set_outstanding(&env, flash::outstanding(&env) - amount);
+        if flash::outstanding(&env) == 0 {
+            reentrancy::unlock(&env);
+        }
+        Ok(())
+    }
+
+    // Governance-only: Appoint the guardian allowed to trigger an emergency shutdown once
+    // the oracle has gone `oracle_timeout` seconds without a valid price
+    pub fn set_guardian(env: Env, guardian: Address, oracle_timeout: u64) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        shutdown::set_guardian(&env, &GuardianConfig { guardian, oracle_timeout });
+        log!(&env, "Shutdown guardian set by governance");
+        Ok(())
+    }
+
+    // Record the oracle's current price as the last valid one (permissionless; keepers call
+    // it as a heartbeat) and re-evaluate the peg band. Returns the price. A dead oracle stops
+    // refreshing it, which is what lets the guardian step in.
+    pub fn record_oracle_price(env: Env) -> Result<i128, PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let price = Self::query_ai_oracle(&env, &data.oracle_address);
+        shutdown::record_price(&env, price)?;
+        peg_band::refresh(&env, price, data.peg_value)?;
+        Ok(price)
+    }
+
+    // Governance, or the guardian after prolonged oracle failure: Shut the token down for
+    // global settlement. Freezes minting and transfers and captures the settlement terms -
+    // the last valid price (the peg if the oracle never reported), the collateral pool and
+    // the circulating supply - against which holders then redeem. Irreversible.
+    pub fn emergency_shutdown(env: Env, caller: Address) -> Result<Settlement, PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let by_guardian = caller != data.governance_address;
+        if by_guardian {
+            let config = shutdown::guardian(&env).ok_or(PiCoinError::Unauthorized)?;
+            if config.guardian != caller || !shutdown::oracle_failed(&env, config.oracle_timeout) {
+                return Err(PiCoinError::Unauthorized);
+            }
+        }
+        shutdown::check_live(&env)?;
+        reentrancy::check(&env)?;
+        let collateral = token::TokenClient::new(&env, &data.collateral_asset);
+        let settlement = Settlement {
+            price: shutdown::last_price(&env).map(|reading| reading.price).unwrap_or(data.peg_value),
+            collateral: collateral.balance(&env.current_contract_address()),
+            collateral_decimals: collateral.decimals(),
+            supply: balance::circulating(&env)?,
+            redeemed: 0,
+            paid: 0,
+            shutdown_at: env.ledger().timestamp(),
+            by_guardian,
+        };
+        shutdown::save(&env, &settlement);
+        events::shutdown_event(&env, &caller, &settlement);
+        Ok(settlement)
+    }
+
+    // After an emergency shutdown: Burn `amount` of `holder`'s PI for its share of the
+    // collateral pool at the settlement terms. Returns the collateral paid.
+    pub fn redeem_settlement(env: Env, holder: Address, amount: i128) -> Result<i128, PiCoinError> {
+        holder.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let mut settlement = shutdown::settlement(&env).ok_or(PiCoinError::NotShutdown)?;
+        validation::amount(amount)?;
+        let paid = shutdown::payout(&settlement, amount)?;
+        Self::burn_funds(&env, &holder, amount)?;
+        shutdown::record_redemption(&env, &mut settlement, &holder, amount, paid)?;
+        events::settlement_redeem_event(&env, &holder, amount, paid);
+        token::TokenClient::new(&env, &data.collateral_asset).transfer(&env.current_contract_address(), &holder, &paid);
+        Ok(paid)
+    }
+
+    // View: Settlement terms and redemption totals, None while the token is live
+    pub fn settlement(env: Env) -> Option<Settlement> {
+        shutdown::settlement(&env)
+    }
+
+    // View: (PI redeemed, collateral received) by `holder` since shutdown
+    pub fn redeemed_of(env: Env, holder: Address) -> (i128, i128) {
+        shutdown::redeemed(&env, &holder)
+    }
+
+    // View: Guardian and its oracle timeout, if one is appointed
+    pub fn guardian(env: Env) -> Option<GuardianConfig> {
+        shutdown::guardian(&env)
+    }
+
+    // View: Last valid oracle price recorded and when
+    pub fn last_oracle_price(env: Env) -> Option<OraclePrice> {
+        shutdown::last_price(&env)
+    }
+
+    // Governance-only: Appoint the sentinel allowed to pause the token besides governance
+    // (e.g. PiCoinInvariants), or remove it with None
+    pub fn set_pause_sentinel(env: Env, sentinel: Option<Address>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        pause::set_sentinel(&env, &sentinel);
+        events::pause_sentinel_event(&env, &sentinel);
+        Ok(())
+    }
+
+    // Governance or the pause sentinel: Trip the pause breaker, freezing minting, transfers,
+    // flash mints, shielding and rebases until governance unpauses. Pausing a paused token
+    // keeps the first trip's record.
+    pub fn pause(env: Env, caller: Address, reason: Symbol) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address && pause::sentinel(&env).as_ref() != Some(&caller) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if pause::pause(&env, &caller, &reason) {
+            events::pause_event(&env, &caller, &reason);
+        }
+        Ok(())
+    }
+
+    // Governance-only: Lift the pause breaker
+    pub fn unpause(env: Env) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        if pause::unpause(&env) {
+            events::unpause_event(&env);
+        }
+        Ok(())
+    }
+
+    // View: Who paused the token, why and when; None while unpaused
+    pub fn pause_trip(env: Env) -> Option<PauseTrip> {
+        pause::trip(&env)
+    }
+
+    // View: Sentinel allowed to pause besides governance, if any
+    pub fn pause_sentinel(env: Env) -> Option<Address> {
+        pause::sentinel(&env)
+    }
+
+    // Governance-only: Rotate the oracle used for peg verification
+    pub fn set_oracle(env: Env, oracle: Address) -> Result<(), PiCoinError> {
+        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        data.oracle_address = oracle;
+        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+        log!(&env, "Oracle rotated by governance");
+        Ok(())
+    }
+
+    // Governance-only: Replace the token metadata (name, symbol, decimals, icon hash, home
+    // domain). The symbol also becomes the ticker reported by `config`.
+    pub fn set_metadata(env: Env, metadata: TokenMetadata) -> Result<(), PiCoinError> {
+        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        let mut buf = [0u8; metadata::MAX_SYMBOL_LEN];
+        data.symbol = Symbol::new(&env, metadata::validate(&metadata, &mut buf)?);
+        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+        metadata::write(&env, &metadata);
+        events::metadata_event(&env, &metadata);
+        Ok(())
+    }
+
+    // Governance-only: Swap the contract code (reached through an executed, timelocked proposal)
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.governance_address.require_auth();
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        log!(&env, "Pi Coin wasm upgraded by governance");
+        Ok(())
+    }
+
+    // Migration hook run after an upgrade: brings stored state up to CONTRACT_VERSION.
+    // Idempotent, so it is safe to leave permissionless.
+    pub fn post_upgrade(env: Env) -> Result<u32, PiCoinError> {
+        let stored: u32 = env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1);
+        Self::migrate(env, stored)
+    }
+
+    // Rewrite state stored by version `from_version` into the current layout, one
+    // schema step at a time. `from_version` must match the stored version.
+    pub fn migrate(env: Env, from_version: u32) -> Result<u32, PiCoinError> {
+        let stored: u32 = env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1);
+        if from_version != stored || from_version > CONTRACT_VERSION {
+            return Err(PiCoinError::VersionMismatch);
+        }
+        let mut version = from_version;
+        while version < CONTRACT_VERSION {
+            version = migration::step(&env, version)?;
+        }
+        if version != stored {
+            env.storage().instance().set(&Symbol::new(&env, "version"), &version);
+            log!(&env, "Pi Coin storage migrated from version {} to {}", stored, version);
+        }
+        Ok(version)
+    }
+
+    // View: Whether the contract state has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&Symbol::new(&env, "data"))
+    }
+
+    // View: Storage layout version currently in effect
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1)
+    }
+
+    // View: Name, symbol, decimals, icon hash and home domain
+    pub fn metadata(env: Env) -> TokenMetadata {
+        metadata::read(&env)
+    }
+
+    // View: Token name (SEP-41)
+    pub fn name(env: Env) -> String {
+        metadata::read(&env).name
+    }
+
+    // View: Token symbol (SEP-41)
+    pub fn symbol(env: Env) -> String {
+        metadata::read(&env).symbol
+    }
+
+    // View: Decimal places of a PI amount (SEP-41)
+    pub fn decimals(env: Env) -> u32 {
+        metadata::read(&env).decimals
+    }
+
+    // View: Fixed PI supply recorded at deploy
+    pub fn total_supply(env: Env) -> i128 {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.total_supply
+    }
+
+    // View: PI held by all holders together - minted and not yet burned
+    pub fn circulating_supply(env: Env) -> Result<i128, PiCoinError> {
+        balance::circulating(&env)
+    }
+
+    // View: Current peg in micro-units ($314,159 unless governance moved it)
+    pub fn peg_value(env: Env) -> i128 {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.peg_value
+    }
+
+    // View: The contract's collateral balance against the circulating supply, in basis
+    // points (10_000 = fully backed 1:1); 0 while nothing circulates
+    pub fn collateral_ratio(env: Env) -> Result<i128, PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let supply = balance::circulating(&env)?;
+        if supply == 0 {
+            return Ok(0);
+        }
+        let collateral = token::TokenClient::new(&env, &data.collateral_asset).balance(&env.current_contract_address());
+        fixed_point::mul_div(collateral, fixed_point::BPS, supply, Rounding::Down)
+    }
+
+    // View: Global PI price the oracle reports, as checked against the peg by `verify_peg`
+    pub fn oracle_price(env: Env) -> i128 {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        Self::query_ai_oracle(&env, &data.oracle_address)
+    }
+
+    // View: Addresses and parameters the contract runs with
+    pub fn config(env: Env) -> PiCoinConfig {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        PiCoinConfig {
+            symbol: data.symbol.clone(),
+            admin: Self::admin(&env, &data),
+            collateral_asset: data.collateral_asset,
+            oracle_address: data.oracle_address,
+            governance_address: data.governance_address,
+            fee_bps: fees::rate(&env),
+            fee_collector: fees::collector(&env),
+            compliance_mode: compliance::mode(&env),
+            version: Self::version(env),
+        }
+    }
+
+    // Helper: Move `amount` from `from` to `to` - Validate provenance, charge the
+    // transfer fee and hand over lots
+    fn move_funds(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+        validation::transfer(from, to, amount)?;
+        shutdown::check_live(env)?;
+        pause::check(env)?;
         
-        // Hyper-tech: Check provenance for ecosystem entry
-        let source = data.provenance.get(voter.clone()).unwrap_or(PiCoinSource::Invalid);
+        // Hyper-tech provenance check: Only transfer if from valid source (ecosystem entry)
+        let source = provenance::source_of(env, from);
         if source == PiCoinSource::Invalid {
-            return Err(PiCoinError::InvalidSource); // Reject vote
+            return Err(PiCoinError::InvalidSource); // Reject - no ecosystem access
         }
         
-        // Hyper-tech: Quantum-resistant voting via multi-sig
-        let vote_sig = env.crypto().ed25519_sign(&voter, &proposal.to_val().to_be_bytes());
-        log!(&env, "Quantum vote cast for {} from {} source with sig: {:?}", proposal, source, vote_sig);
+        compliance::check(env, from, to)?;
+
+        // Move balances
+        let from_balance = balance::read(env, from)?;
+        if from_balance < amount {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        rate_limit::record_outflow(env, from, amount)?;
+        ttl::bump_instance(env);
+        let mut received = amount;
+        if let Some((collector, fee)) = fees::assess(env, from, to, amount)? {
+            balance::transfer(env, from, &collector, fee)?;
+            provenance::give(env, &collector, provenance::take(env, from, fee)?);
+            received -= fee;
+            events::fee_event(env, from, &collector, fee);
+        }
+        balance::transfer(env, from, to, received)?;
+        provenance::give(env, to, provenance::take(env, from, received)?);
+        events::transfer_event(env, from, to, received);
+
+        // Inherit provenance to recipient
+        provenance::set_source(env, to, &source);
+        log!(env, "Transferred {} PI with valid provenance from {} source", amount, source);
         Ok(())
     }
 
-    // New: Verify ecosystem entry (global recognition check)
-    pub fn verify_ecosystem_entry(env: Env, holder: Address) -> Result<bool, PiCoinError> {
-        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
-        let source = data.provenance.get(holder).unwrap_or(PiCoinSource::Invalid);
+    // Helper: Validate and store `source`'s quota
+    fn apply_source_quota(env: &Env, source: &PiCoinSource, quota: &Option<SourceQuota>) -> Result<(), PiCoinError> {
+        if let Some(quota) = quota {
+            if quota.cap < 0 || quota.epoch_length == 0 || quota.epoch_length > rate_limit::MAX_EPOCH {
+                return Err(PiCoinError::InvalidRateLimit);
+            }
+        }
+        rate_limit::set_source_quota(env, source, quota);
+        events::source_quota_event(env, source.clone(), quota);
+        Ok(())
+    }
+
+    // Helper: DeadlineExpired once the ledger is past `deadline_ledger`
+    fn check_deadline(env: &Env, deadline_ledger: u32) -> Result<(), PiCoinError> {
+        if env.ledger().sequence() > deadline_ledger {
+            return Err(PiCoinError::DeadlineExpired);
+        }
+        Ok(())
+    }
+
+    // Helper: Move PI between an account and the shielded pool, carrying provenance like a
+    // transfer but fee-free (private transfers pay no transfer fee)
+    fn move_unshielded(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+        let source = provenance::source_of(env, from);
         if source == PiCoinSource::Invalid {
-            log!(&env, "Ecosystem entry rejected: Invalid source - No access to Pi Coin $314,159 peg");
-            return Ok(false);
+            return Err(PiCoinError::InvalidSource);
         }
-        log!(&env, "Ecosystem entry approved: {} source verified for global Pi Coin recognition", source);
-        Ok(true)
+        if !compliance::is_permitted(env, from) || !compliance::is_permitted(env, to) {
+            return Err(PiCoinError::NotCompliant);
+        }
+        if balance::read(env, from)? < amount {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        balance::transfer(env, from, to, amount)?;
+        provenance::give(env, to, provenance::take(env, from, amount)?);
+        provenance::set_source(env, to, &source);
+        Ok(())
+    }
+
+    // Helper: Validate and store an allowance, emitting "approve"
+    fn set_allowance(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) -> Result<(), PiCoinError> {
+        validation::non_negative(amount)?;
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(PiCoinError::InvalidAllowance);
+        }
+        allowance::write(env, from, spender, amount, expiration_ledger);
+        events::approve_event(env, from, spender, amount, expiration_ledger);
+        Ok(())
+    }
+
+    // Helper: Destroy `amount` of `from`'s PI along with its provenance lots
+    fn burn_funds(env: &Env, from: &Address, amount: i128) -> Result<(), PiCoinError> {
+        validation::amount(amount)?;
+        let balance = balance::read(env, from)?;
+        if balance < amount {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        ttl::bump_instance(env);
+        balance::sub(env, from, amount)?;
+        provenance::take(env, from, amount)?;
+        events::burn_event(env, from, amount);
+        log!(env, "Burned {} PI - Supply contracted", amount);
+        Ok(())
+    }
+
+    // Helper: Minting authority set at deploy; contracts migrated from before it was
+    // stored fall back to governance
+    fn admin(env: &Env, data: &PiCoinData) -> Address {
+        env.storage().instance().get(&Symbol::new(env, "admin")).unwrap_or(data.governance_address.clone())
     }
 
     // Helper: Check collateral (for 1:1 backing)
-    fn check_collateral(env: &Env, collateral: &Address, user: &Address) -> i128 {
+    fn check_collateral(_env: &Env, _collateral: &Address, _user: &Address) -> i128 {
         // Simulated: In real, query collateral contract balance
         100_000_000_000 // Assume full backing for demo
     }
 
     // Helper: AI-enhanced oracle (simulates global data aggregation) - Only queries for valid
-    fn query_ai_oracle(env: &Env, oracle: &Address) -> i128 {
+    fn query_ai_oracle(env: &Env, _oracle: &Address) -> i128 {
         // Hyper-tech: Simulated AI prediction from global sources (e.g., integrate CoinGecko API via off-chain)
         // In prod: Use Soroban events or external oracle
-        314_159_000_000 + (env.ledger().timestamp() % 1000) // Dynamic but stable
+        314_159_000_000 + (env.ledger().timestamp() % 1000) as i128 // Dynamic but stable
     }
 
     // Helper: Simulate global payment recognition (integrate with Stellar DEX) - Only for valid
@@ -183,3 +1312,12 @@ impl PiCoinContract {
         // In real: Emit event for cross-chain bridges or wallets
     }
             }
+
+mod test;
+mod test_auth;
+mod test_evm;
+mod test_fixed_point;
+mod test_integration;
+mod test_props;
+mod test_sac;
+mod test_shielded;
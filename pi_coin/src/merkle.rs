@@ -0,0 +1,23 @@
+// Merkle proofs over sorted sha256 pairs: a leaf's hash is folded with each sibling in
+// the proof, smaller hash first, so proofs need no left/right flags. Leaves are the XDR
+// of a contract type, hashed with sha256. Shared by the token and the airdrop contract.
+use soroban_sdk::{Bytes, BytesN, Env, Vec};
+
+// Root reached from `leaf` (XDR bytes) by applying `proof`
+pub fn root_from(env: &Env, leaf: &Bytes, proof: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut hash: BytesN<32> = env.crypto().sha256(leaf).into();
+    for sibling in proof.iter() {
+        let a = hash.to_array();
+        let b = sibling.to_array();
+        let (left, right) = if a < b { (a, b) } else { (b, a) };
+        let mut combined = [0u8; 64];
+        combined[..32].copy_from_slice(&left);
+        combined[32..].copy_from_slice(&right);
+        hash = env.crypto().sha256(&Bytes::from_array(env, &combined)).into();
+    }
+    hash
+}
+
+pub fn verify(env: &Env, root: &BytesN<32>, leaf: &Bytes, proof: &Vec<BytesN<32>>) -> bool {
+    root_from(env, leaf, proof) == *root
+}
@@ -0,0 +1,92 @@
+// Signed messages: a holder signs a transfer or an approval (permit) off-chain with its
+// account's ed25519 key and anyone submits it. Each signed payload binds the network,
+// this token and a per-holder sequential nonce, so a signature is valid exactly once.
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env};
+use crate::PiCoinError;
+
+// What the holder signs: the XDR of this struct
+#[contracttype]
+#[derive(Clone)]
+pub struct MetaTransfer {
+    pub network_id: BytesN<32>,
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub nonce: u64,
+    pub expiration_ledger: u32,
+}
+
+// What an owner signs to approve a spender (EIP-2612 style): the XDR of this struct
+#[contracttype]
+#[derive(Clone)]
+pub struct Permit {
+    pub network_id: BytesN<32>,
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub amount: i128,
+    pub nonce: u64,
+    pub deadline: u32, // Last ledger the signature (and the allowance) is valid for
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MetaKey {
+    Nonce(Address), // Next nonce a holder's signed transfer must carry
+    PermitNonce(Address), // Next nonce an owner's permit must carry
+}
+
+pub fn nonce(env: &Env, holder: &Address) -> u64 {
+    env.storage().persistent().get(&MetaKey::Nonce(holder.clone())).unwrap_or(0)
+}
+
+pub fn permit_nonce(env: &Env, owner: &Address) -> u64 {
+    env.storage().persistent().get(&MetaKey::PermitNonce(owner.clone())).unwrap_or(0)
+}
+
+// Check a signed transfer by `payload.from` and consume its nonce
+pub fn verify_transfer(env: &Env, payload: MetaTransfer, signature: &BytesN<64>) -> Result<(), PiCoinError> {
+    let key = MetaKey::Nonce(payload.from.clone());
+    verify(env, &payload.from, key, payload.nonce, payload.expiration_ledger, payload.clone().to_xdr(env), signature)
+}
+
+// Check a permit by `payload.owner` and consume its nonce
+pub fn verify_permit(env: &Env, payload: Permit, signature: &BytesN<64>) -> Result<(), PiCoinError> {
+    let key = MetaKey::PermitNonce(payload.owner.clone());
+    verify(env, &payload.owner, key, payload.nonce, payload.deadline, payload.clone().to_xdr(env), signature)
+}
+
+fn verify(
+    env: &Env,
+    signer: &Address,
+    nonce_key: MetaKey,
+    nonce: u64,
+    last_ledger: u32,
+    message: Bytes,
+    signature: &BytesN<64>,
+) -> Result<(), PiCoinError> {
+    if last_ledger < env.ledger().sequence() {
+        return Err(PiCoinError::SignatureExpired);
+    }
+    let expected: u64 = env.storage().persistent().get(&nonce_key).unwrap_or(0);
+    if nonce != expected {
+        return Err(PiCoinError::InvalidNonce);
+    }
+    let public_key = account_key(env, signer).ok_or(PiCoinError::InvalidSigner)?;
+    env.crypto().ed25519_verify(&public_key, &message, signature);
+    env.storage().persistent().set(&nonce_key, &(nonce + 1));
+    Ok(())
+}
+
+// ed25519 key of a G... account address (None for contracts). The address XDR is
+// ScVal::Address(ScAddress::Account(PublicKey::Ed25519(key))): three 4-byte tags, then the key.
+fn account_key(env: &Env, address: &Address) -> Option<BytesN<32>> {
+    let xdr = address.clone().to_xdr(env);
+    if xdr.len() != 44 || xdr.slice(4..12) != Bytes::from_array(env, &[0; 8]) {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    xdr.slice(12..44).copy_into_slice(&mut key);
+    Some(BytesN::from_array(env, &key))
+}
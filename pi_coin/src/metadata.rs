@@ -0,0 +1,69 @@
+// Token metadata: the name, symbol and decimals wallets display, plus an icon hash and
+// the home domain whose stellar.toml describes the asset. The constructor writes the
+// defaults below; only governance can change them afterwards (set_metadata).
+use soroban_sdk::{contracttype, BytesN, Env, String};
+use crate::PiCoinError;
+
+pub const DEFAULT_DECIMALS: u32 = 7;
+pub const MAX_DECIMALS: u32 = 18;
+pub const MAX_NAME_LEN: u32 = 64;
+pub const MAX_SYMBOL_LEN: usize = 12; // Longest Stellar asset code
+pub const MAX_HOME_DOMAIN_LEN: u32 = 32; // Longest home domain an account can set
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String, // 1-12 ASCII letters and digits
+    pub decimals: u32,
+    pub icon_hash: Option<BytesN<32>>, // sha256 of the icon image
+    pub home_domain: String, // Empty when none is published
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MetadataKey {
+    Metadata,
+}
+
+pub fn default(env: &Env) -> TokenMetadata {
+    TokenMetadata {
+        name: String::from_str(env, "Pi Coin"),
+        symbol: String::from_str(env, "PI"),
+        decimals: DEFAULT_DECIMALS,
+        icon_hash: None,
+        home_domain: String::from_str(env, ""),
+    }
+}
+
+// Stored metadata; contracts deployed before it was stored read the defaults
+pub fn read(env: &Env) -> TokenMetadata {
+    env.storage().instance().get(&MetadataKey::Metadata).unwrap_or_else(|| default(env))
+}
+
+pub fn write(env: &Env, metadata: &TokenMetadata) {
+    env.storage().instance().set(&MetadataKey::Metadata, metadata);
+}
+
+// Check `metadata` and copy its symbol into `buf`, returning it as a str so the caller can
+// turn it into the Symbol kept in PiCoinData. InvalidMetadata for an empty or overlong
+// name, symbol or home domain, a symbol with anything but ASCII letters and digits, or
+// more than MAX_DECIMALS decimals.
+pub fn validate<'a>(metadata: &TokenMetadata, buf: &'a mut [u8; MAX_SYMBOL_LEN]) -> Result<&'a str, PiCoinError> {
+    let len = metadata.symbol.len() as usize;
+    if metadata.name.is_empty()
+        || metadata.name.len() > MAX_NAME_LEN
+        || len == 0
+        || len > MAX_SYMBOL_LEN
+        || metadata.home_domain.len() > MAX_HOME_DOMAIN_LEN
+        || metadata.decimals > MAX_DECIMALS
+    {
+        return Err(PiCoinError::InvalidMetadata);
+    }
+    let ticker = &mut buf[..len];
+    metadata.symbol.copy_into_slice(ticker);
+    if !ticker.iter().all(u8::is_ascii_alphanumeric) {
+        return Err(PiCoinError::InvalidMetadata);
+    }
+    core::str::from_utf8(ticker).map_err(|_| PiCoinError::InvalidMetadata)
+}
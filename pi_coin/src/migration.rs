@@ -0,0 +1,159 @@
+// Versioned storage schemas: every layout PiCoinData has had is kept here so an
+// upgraded contract can read entries written by older wasm and rewrite them.
+use soroban_sdk::{contracttype, Address, Env, Symbol, Map, BytesN};
+use crate::{balance, fees, provenance, PiCoinData, PiCoinError, PiCoinSource};
+
+// Layout written by version 1 (before fees and balances)
+#[contracttype]
+#[derive(Clone)]
+pub struct PiCoinDataV1 {
+    pub symbol: Symbol,
+    pub total_supply: i128,
+    pub peg_value: i128,
+    pub collateral_asset: Address,
+    pub oracle_address: Address,
+    pub governance_address: Address,
+    pub anti_fraud_hash: BytesN<32>,
+    pub provenance: Map<Address, PiCoinSource>,
+    pub quantum_provenance_hash: BytesN<32>,
+}
+
+// Layout written by version 2 (balances in one map inside the instance entry)
+#[contracttype]
+#[derive(Clone)]
+pub struct PiCoinDataV2 {
+    pub symbol: Symbol,
+    pub total_supply: i128,
+    pub peg_value: i128,
+    pub collateral_asset: Address,
+    pub oracle_address: Address,
+    pub governance_address: Address,
+    pub anti_fraud_hash: BytesN<32>,
+    pub provenance: Map<Address, PiCoinSource>,
+    pub quantum_provenance_hash: BytesN<32>,
+    pub fee_bps: u32,
+    pub balances: Map<Address, i128>,
+}
+
+// Layout written by version 3 (holder sources and the fee rate inside the instance entry)
+#[contracttype]
+#[derive(Clone)]
+pub struct PiCoinDataV3 {
+    pub symbol: Symbol,
+    pub total_supply: i128,
+    pub peg_value: i128,
+    pub collateral_asset: Address,
+    pub oracle_address: Address,
+    pub governance_address: Address,
+    pub anti_fraud_hash: BytesN<32>,
+    pub provenance: Map<Address, PiCoinSource>,
+    pub quantum_provenance_hash: BytesN<32>,
+    pub fee_bps: u32,
+}
+
+// Run the migration out of `version` and return the version reached; UnknownVersion if
+// no layout with that number was ever written
+pub fn step(env: &Env, version: u32) -> Result<u32, PiCoinError> {
+    match version {
+        1 => {
+            v1_to_v2(env);
+            Ok(2)
+        }
+        2 => {
+            v2_to_v3(env);
+            Ok(3)
+        }
+        3 => {
+            v3_to_v4(env);
+            Ok(4)
+        }
+        _ => Err(PiCoinError::UnknownVersion),
+    }
+}
+
+// v1 -> v2: new fee and balance fields start empty
+fn v1_to_v2(env: &Env) {
+    let key = Symbol::new(env, "data");
+    let old: PiCoinDataV1 = env.storage().instance().get(&key).unwrap();
+    let data = PiCoinDataV2 {
+        symbol: old.symbol,
+        total_supply: old.total_supply,
+        peg_value: old.peg_value,
+        collateral_asset: old.collateral_asset,
+        oracle_address: old.oracle_address,
+        governance_address: old.governance_address,
+        anti_fraud_hash: old.anti_fraud_hash,
+        provenance: old.provenance,
+        quantum_provenance_hash: old.quantum_provenance_hash,
+        fee_bps: 0,
+        balances: Map::new(env),
+    };
+    env.storage().instance().set(&key, &data);
+}
+
+// v2 -> v3: each balance in the map becomes its own persistent entry
+fn v2_to_v3(env: &Env) {
+    let key = Symbol::new(env, "data");
+    let old: PiCoinDataV2 = env.storage().instance().get(&key).unwrap();
+    for (holder, amount) in old.balances.iter() {
+        balance::write(env, &holder, amount).unwrap();
+    }
+    let data = PiCoinDataV3 {
+        symbol: old.symbol,
+        total_supply: old.total_supply,
+        peg_value: old.peg_value,
+        collateral_asset: old.collateral_asset,
+        oracle_address: old.oracle_address,
+        governance_address: old.governance_address,
+        anti_fraud_hash: old.anti_fraud_hash,
+        provenance: old.provenance,
+        quantum_provenance_hash: old.quantum_provenance_hash,
+        fee_bps: old.fee_bps,
+    };
+    env.storage().instance().set(&key, &data);
+}
+
+// v3 -> v4: each holder's source becomes its own persistent entry and the fee rate its own
+// instance key, so transfers no longer load PiCoinData
+fn v3_to_v4(env: &Env) {
+    let key = Symbol::new(env, "data");
+    let old: PiCoinDataV3 = env.storage().instance().get(&key).unwrap();
+    for (holder, source) in old.provenance.iter() {
+        provenance::set_source(env, &holder, &source);
+    }
+    fees::set_rate(env, old.fee_bps);
+    let data = PiCoinData {
+        symbol: old.symbol,
+        total_supply: old.total_supply,
+        peg_value: old.peg_value,
+        collateral_asset: old.collateral_asset,
+        oracle_address: old.oracle_address,
+        governance_address: old.governance_address,
+        anti_fraud_hash: old.anti_fraud_hash,
+        quantum_provenance_hash: old.quantum_provenance_hash,
+    };
+    env.storage().instance().set(&key, &data);
+}
+
+// Test utilities: put a registered contract into the state an older wasm left behind
+#[cfg(test)]
+pub mod testutils {
+    use super::{PiCoinDataV2, PiCoinDataV3};
+    use soroban_sdk::{Address, Env, Symbol};
+
+    // Install a v2 PiCoinData entry and version marker, as the v2 wasm would have
+    pub fn install_v2(env: &Env, contract_id: &Address, data: &PiCoinDataV2) {
+        env.as_contract(contract_id, || {
+            env.storage().instance().set(&Symbol::new(env, "data"), data);
+            env.storage().instance().set(&Symbol::new(env, "version"), &2u32);
+        });
+    }
+
+    // Install a v3 PiCoinData entry and version marker, as the v3 wasm would have
+    pub fn install_v3(env: &Env, contract_id: &Address, data: &PiCoinDataV3) {
+        env.as_contract(contract_id, || {
+            env.storage().instance().set(&Symbol::new(env, "data"), data);
+            env.storage().instance().set(&Symbol::new(env, "version"), &3u32);
+        });
+    }
+}
@@ -0,0 +1,62 @@
+// Pause breaker: a reversible freeze for when the books stop adding up but a global
+// settlement would be too much. Governance, or the sentinel it appoints (typically
+// PiCoinInvariants, which trips it when a conservation check fails), pauses the token;
+// minting, transfers, flash mints, shielding and rebases then fail with Paused until
+// governance unpauses. Burns, settlement redemptions and emergency shutdown keep working.
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+use crate::PiCoinError;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseTrip {
+    pub by: Address,
+    pub reason: Symbol, // e.g. the invariant that failed
+    pub tripped_at: u64,
+}
+
+// Pause breaker state in instance storage; `Paused` doubles as the paused flag
+#[contracttype]
+#[derive(Clone)]
+pub enum PauseKey {
+    Sentinel, // Address allowed to pause besides governance
+    Paused, // PauseTrip while paused
+}
+
+pub fn sentinel(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&PauseKey::Sentinel)
+}
+
+pub fn set_sentinel(env: &Env, sentinel: &Option<Address>) {
+    match sentinel {
+        Some(sentinel) => env.storage().instance().set(&PauseKey::Sentinel, sentinel),
+        None => env.storage().instance().remove(&PauseKey::Sentinel),
+    }
+}
+
+pub fn trip(env: &Env) -> Option<PauseTrip> {
+    env.storage().instance().get(&PauseKey::Paused)
+}
+
+// Pause, keeping the first trip's record if already paused; returns whether this call paused
+pub fn pause(env: &Env, by: &Address, reason: &Symbol) -> bool {
+    if trip(env).is_some() {
+        return false;
+    }
+    let trip = PauseTrip { by: by.clone(), reason: reason.clone(), tripped_at: env.ledger().timestamp() };
+    env.storage().instance().set(&PauseKey::Paused, &trip);
+    true
+}
+
+pub fn unpause(env: &Env) -> bool {
+    let paused = trip(env).is_some();
+    env.storage().instance().remove(&PauseKey::Paused);
+    paused
+}
+
+// Paused while the breaker is tripped
+pub fn check(env: &Env) -> Result<(), PiCoinError> {
+    if env.storage().instance().has(&PauseKey::Paused) {
+        return Err(PiCoinError::Paused);
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// Peg bands: how far the oracle price may drift from the peg before the token changes
+// mode. Within `soft_bps` of the peg the token is Healthy. Past it the mode is Soft: a
+// "peg_band" warning is published and every transfer pays at least `soft_fee_bps`, which
+// damps churn while the peg is under pressure. Past `hard_bps` the mode is Hard: minting
+// pauses except through the PSM, which only mints against collateral at the peg and so
+// defends it, and `verify_peg` fails.
+//
+// The mode is re-evaluated whenever the token reads the oracle (mint, verify_peg and the
+// record_oracle_price heartbeat) and stored, so transfers pay the band's fee without an
+// oracle call of their own.
+use soroban_sdk::{contracttype, Address, Env};
+use crate::fixed_point::{self, Rounding};
+use crate::{events, fees, PiCoinError};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PegBands {
+    pub soft_bps: u32, // Deviation from the peg that starts the soft band
+    pub hard_bps: u32, // ...and the hard band
+    pub soft_fee_bps: u32, // Minimum transfer fee outside the soft band
+    pub psm: Option<Address>, // Minting admin still allowed to mint in the hard band
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PegMode {
+    Healthy,
+    Soft,
+    Hard,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PegBandStatus {
+    pub mode: PegMode,
+    pub price: i128, // Oracle price the mode was evaluated at
+    pub deviation_bps: u32, // |price - peg| in basis points of the peg
+    pub updated_at: u64,
+}
+
+// Peg band configuration and the last band evaluation, both in instance storage
+#[contracttype]
+#[derive(Clone)]
+pub enum PegBandKey {
+    Bands, // Defaults until governance sets them
+    BandStatus, // Last evaluation; Healthy before the first
+}
+
+pub fn default() -> PegBands {
+    PegBands { soft_bps: 100, hard_bps: 500, soft_fee_bps: 0, psm: None }
+}
+
+pub fn bands(env: &Env) -> PegBands {
+    env.storage().instance().get(&PegBandKey::Bands).unwrap_or_else(default)
+}
+
+// InvalidPegBands unless 0 < soft_bps < hard_bps <= 10_000 and soft_fee_bps is a valid fee
+pub fn set_bands(env: &Env, bands: &PegBands) -> Result<(), PiCoinError> {
+    if bands.soft_bps == 0 || bands.soft_bps >= bands.hard_bps || bands.hard_bps > 10_000 || bands.soft_fee_bps > fees::MAX_FEE_BPS {
+        return Err(PiCoinError::InvalidPegBands);
+    }
+    env.storage().instance().set(&PegBandKey::Bands, bands);
+    Ok(())
+}
+
+pub fn status(env: &Env) -> Option<PegBandStatus> {
+    env.storage().instance().get(&PegBandKey::BandStatus)
+}
+
+// Stored mode, as enforced on transfers and mints
+pub fn mode(env: &Env) -> PegMode {
+    status(env).map_or(PegMode::Healthy, |status| status.mode)
+}
+
+// Mode the bands put `price` in, without storing it. Deviations past u32 saturate.
+pub fn evaluate(env: &Env, price: i128, peg: i128) -> Result<PegBandStatus, PiCoinError> {
+    let bands = bands(env);
+    let deviation = price.checked_sub(peg).and_then(i128::checked_abs).ok_or(PiCoinError::Overflow)?;
+    let deviation_bps = fixed_point::mul_div(deviation, fixed_point::BPS, peg, Rounding::Up)?;
+    let deviation_bps = u32::try_from(deviation_bps).unwrap_or(u32::MAX);
+    let mode = if deviation_bps > bands.hard_bps {
+        PegMode::Hard
+    } else if deviation_bps > bands.soft_bps {
+        PegMode::Soft
+    } else {
+        PegMode::Healthy
+    };
+    Ok(PegBandStatus { mode, price, deviation_bps, updated_at: env.ledger().timestamp() })
+}
+
+// Evaluate and store the mode at `price`, publishing "peg_band" when it changes
+pub fn refresh(env: &Env, price: i128, peg: i128) -> Result<PegBandStatus, PiCoinError> {
+    let status = evaluate(env, price, peg)?;
+    if mode(env) != status.mode {
+        events::peg_band_event(env, &status);
+    }
+    env.storage().instance().set(&PegBandKey::BandStatus, &status);
+    Ok(status)
+}
+
+// Transfer fee in effect: the governance rate, raised to soft_fee_bps outside the soft band
+pub fn fee_bps(env: &Env, rate: u32) -> u32 {
+    match mode(env) {
+        PegMode::Healthy => rate,
+        PegMode::Soft | PegMode::Hard => rate.max(bands(env).soft_fee_bps),
+    }
+}
+
+// PegDeviation when `minter` may not mint in `mode` (only the PSM mints in the hard band)
+pub fn check_mint(env: &Env, mode: PegMode, minter: &Address) -> Result<(), PiCoinError> {
+    if mode == PegMode::Hard && bands(env).psm.as_ref() != Some(minter) {
+        return Err(PiCoinError::PegDeviation);
+    }
+    Ok(())
+}
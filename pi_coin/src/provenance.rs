@@ -0,0 +1,206 @@
+// Provenance ledger: every mint creates a lot recording where the PI came from, and
+// transfers hand lot portions on oldest-first (FIFO), so any balance can be traced back
+// to the mints that created it. Balances predating lot tracking carry no lots.
+// Like balances, lot portions are stored as rebase shares and reported in PI.
+//
+// Off-chain provenance (e.g. Pi mining rewards earned before launch) is attested with a
+// Merkle proof against a governance-published root of approved ecosystem participants.
+//
+// If governance sets a lot receipts contract (PiCoinLotReceipts), each new lot also
+// issues the minter a non-fungible receipt carrying the lot id, source and amount.
+// A badge registry (PiCoinBadges), if set, admits verified participants to the ecosystem
+// whether or not they hold minted PI.
+use soroban_sdk::{contracttype, vec, xdr::ToXdr, Address, BytesN, Env, IntoVal, Symbol, Vec};
+use crate::{events, rebase, ttl, PiCoinError, PiCoinSource};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Lot {
+    pub id: u64,
+    pub source: PiCoinSource,
+    pub amount: i128, // Portion of the lot held (not the originally minted amount); shares in storage
+    pub minted_at: u64, // Ledger timestamp of the mint
+}
+
+// Merkle leaf of the approved-participant tree: sha256 of this struct's XDR
+#[contracttype]
+#[derive(Clone)]
+pub struct ParticipantLeaf {
+    pub participant: Address,
+    pub source: PiCoinSource,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Attestation {
+    pub source: PiCoinSource,
+    pub root_epoch: u32, // Root the proof was checked against
+    pub attested_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ProvenanceKey {
+    NextLotId,
+    Lots(Address), // Holder -> Lot portions held, oldest first
+    AttestationRoot, // (Current participant root, its epoch)
+    Attestation(Address), // Holder -> Latest attestation
+    LotReceipts, // Contract issuing a receipt per lot, if any
+    BadgeRegistry, // Soulbound badge contract consulted for ecosystem entry, if any
+    HolderSource(Address), // Holder -> Source of its latest mint, attestation or incoming transfer
+}
+
+// Provenance hash of a holder: sha256 of the address's ScAddress XDR. Depends on the
+// address alone, so it is the same in every invocation and can be recomputed off-chain.
+// (Hashes of a host object handle, as the first provenance verifier used, change from
+// one execution to the next and cannot be migrated; they must be regenerated with this.)
+pub fn holder_hash(env: &Env, holder: &Address) -> BytesN<32> {
+    env.crypto().sha256(&holder.clone().to_xdr(env)).to_bytes()
+}
+
+// Record a freshly minted lot for `to` and return its id; Overflow if `amount` is too
+// large to hold as shares
+pub fn mint_lot(env: &Env, to: &Address, source: PiCoinSource, amount: i128) -> Result<u64, PiCoinError> {
+    let shares = rebase::to_shares(env, amount)?;
+    let id: u64 = env.storage().instance().get(&ProvenanceKey::NextLotId).unwrap_or(1);
+    env.storage().instance().set(&ProvenanceKey::NextLotId, &(id + 1));
+    let minted_at = env.ledger().timestamp();
+    let lot = Lot { id, source: source.clone(), amount: shares, minted_at };
+    give(env, to, Vec::from_array(env, [lot]));
+    if let Some(receipts) = lot_receipts(env) {
+        let args = vec![env, id.into_val(env), to.into_val(env), source.into_val(env), amount.into_val(env), minted_at.into_val(env)];
+        env.invoke_contract::<()>(&receipts, &Symbol::new(env, "issue"), args);
+    }
+    Ok(id)
+}
+
+// Source recorded for `holder`; Invalid if it never received PI or an attestation
+pub fn source_of(env: &Env, holder: &Address) -> PiCoinSource {
+    env.storage().persistent().get(&ProvenanceKey::HolderSource(holder.clone())).unwrap_or(PiCoinSource::Invalid)
+}
+
+// Record `holder`'s source; skips the write when it is unchanged, as on most transfers
+pub fn set_source(env: &Env, holder: &Address, source: &PiCoinSource) {
+    if source_of(env, holder) != *source {
+        env.storage().persistent().set(&ProvenanceKey::HolderSource(holder.clone()), source);
+    }
+}
+
+pub fn lot_receipts(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ProvenanceKey::LotReceipts)
+}
+
+pub fn set_lot_receipts(env: &Env, receipts: &Address) {
+    env.storage().instance().set(&ProvenanceKey::LotReceipts, receipts);
+    events::lot_receipts_event(env, receipts);
+}
+
+pub fn badge_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ProvenanceKey::BadgeRegistry)
+}
+
+pub fn set_badge_registry(env: &Env, registry: &Address) {
+    env.storage().instance().set(&ProvenanceKey::BadgeRegistry, registry);
+    events::badge_registry_event(env, registry);
+}
+
+// Ask the badge registry, if one is set, whether `holder` holds a valid badge
+pub fn has_badge(env: &Env, holder: &Address) -> bool {
+    let Some(registry) = badge_registry(env) else { return false };
+    env.invoke_contract(&registry, &Symbol::new(env, "has_badge"), vec![env, holder.into_val(env)])
+}
+
+// Remove up to `amount` PI from the front of `holder`'s lots and return the portions
+// taken (in shares, to be handed to `give`)
+pub fn take(env: &Env, holder: &Address, amount: i128) -> Result<Vec<Lot>, PiCoinError> {
+    let mut lots = stored_lots(env, holder);
+    let mut taken = Vec::new(env);
+    let mut remaining = rebase::to_shares(env, amount)?;
+    while remaining > 0 {
+        let Some(mut lot) = lots.pop_front() else { break };
+        if lot.amount > remaining {
+            let mut rest = lot.clone();
+            rest.amount -= remaining;
+            lots.push_front(rest);
+            lot.amount = remaining;
+        }
+        remaining -= lot.amount;
+        taken.push_back(lot);
+    }
+    store(env, holder, &lots);
+    Ok(taken)
+}
+
+// Append lot portions to `holder`'s queue, merging with the newest entry of the same lot
+pub fn give(env: &Env, holder: &Address, portions: Vec<Lot>) {
+    if portions.is_empty() {
+        return;
+    }
+    let mut lots = stored_lots(env, holder);
+    for portion in portions.iter() {
+        match lots.last() {
+            Some(mut last) if last.id == portion.id => {
+                last.amount += portion.amount;
+                lots.set(lots.len() - 1, last);
+            }
+            _ => lots.push_back(portion),
+        }
+    }
+    store(env, holder, &lots);
+}
+
+// Holder's lots with portions in PI at the current rebase index
+pub fn lots_of(env: &Env, holder: &Address) -> Result<Vec<Lot>, PiCoinError> {
+    let mut lots = stored_lots(env, holder);
+    for i in 0..lots.len() {
+        let mut lot = lots.get(i).unwrap();
+        lot.amount = rebase::to_amount(env, lot.amount)?;
+        lots.set(i, lot);
+    }
+    Ok(lots)
+}
+
+fn stored_lots(env: &Env, holder: &Address) -> Vec<Lot> {
+    env.storage().persistent().get(&ProvenanceKey::Lots(holder.clone())).unwrap_or(Vec::new(env))
+}
+
+fn store(env: &Env, holder: &Address, lots: &Vec<Lot>) {
+    let key = ProvenanceKey::Lots(holder.clone());
+    if lots.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, lots);
+        ttl::bump_persistent(env, &key);
+    }
+}
+
+// Explicitly extend a holder's lots entry, if any
+pub fn extend_ttl(env: &Env, holder: &Address) {
+    ttl::extend_persistent(env, &ProvenanceKey::Lots(holder.clone()));
+}
+
+pub fn attestation_root(env: &Env) -> Option<(BytesN<32>, u32)> {
+    env.storage().instance().get(&ProvenanceKey::AttestationRoot)
+}
+
+// Publish a new participant root. Attestations made under earlier roots stop counting.
+pub fn rotate_root(env: &Env, root: &BytesN<32>) -> u32 {
+    let epoch = attestation_root(env).map(|(_, epoch)| epoch + 1).unwrap_or(1);
+    env.storage().instance().set(&ProvenanceKey::AttestationRoot, &(root.clone(), epoch));
+    epoch
+}
+
+pub fn record_attestation(env: &Env, holder: &Address, attestation: &Attestation) {
+    env.storage().persistent().set(&ProvenanceKey::Attestation(holder.clone()), attestation);
+}
+
+// Attestation of `holder` under the current root, if any
+pub fn current_attestation(env: &Env, holder: &Address) -> Option<Attestation> {
+    let (_, epoch) = attestation_root(env)?;
+    let attestation: Attestation = env.storage().persistent().get(&ProvenanceKey::Attestation(holder.clone()))?;
+    if attestation.root_epoch == epoch {
+        Some(attestation)
+    } else {
+        None
+    }
+}
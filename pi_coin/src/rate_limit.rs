@@ -0,0 +1,160 @@
+// Velocity caps: a global limit on PI minted per ledger day and a per-address limit on
+// outflow per epoch, both over fixed windows that reset once their length has passed
+// (by ledger timestamp). Accounts on the governance bypass list (treasury, bridges,
+// pools) are exempt from the outflow cap. Caps contain the damage an exploit can do
+// before governance reacts.
+//
+// Each mint source can additionally carry its own quota (e.g. Mining capped at X per
+// epoch, Rewards at Y), tracked in a window of the quota's own length.
+use soroban_sdk::{contracttype, Address, Env};
+use crate::{PiCoinError, PiCoinSource};
+
+pub const DAY: u64 = 86_400;
+pub const MAX_EPOCH: u64 = 30 * DAY;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RateLimits {
+    pub daily_mint_cap: Option<i128>, // None: unlimited
+    pub outflow_cap: Option<i128>, // Per address per epoch; None: unlimited
+    pub epoch_length: u64, // Outflow window in seconds
+}
+
+// PI that may be minted from one source per `epoch_length` seconds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceQuota {
+    pub cap: i128,
+    pub epoch_length: u64,
+}
+
+// Usage within the window starting at `start`
+#[contracttype]
+#[derive(Clone)]
+pub struct Window {
+    pub start: u64,
+    pub used: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RateLimitKey {
+    Limits,
+    MintWindow,
+    Outflow(Address), // Per-address outflow window
+    Bypass(Address), // Accounts exempt from the outflow cap
+    SourceQuota(PiCoinSource),
+    SourceWindow(PiCoinSource), // Per-source mint window
+}
+
+pub fn limits(env: &Env) -> RateLimits {
+    env.storage().instance().get(&RateLimitKey::Limits).unwrap_or(RateLimits {
+        daily_mint_cap: None,
+        outflow_cap: None,
+        epoch_length: DAY,
+    })
+}
+
+pub fn set_limits(env: &Env, limits: &RateLimits) {
+    env.storage().instance().set(&RateLimitKey::Limits, limits);
+}
+
+pub fn is_bypassed(env: &Env, account: &Address) -> bool {
+    env.storage().persistent().has(&RateLimitKey::Bypass(account.clone()))
+}
+
+pub fn set_bypass(env: &Env, account: &Address, bypass: bool) {
+    let key = RateLimitKey::Bypass(account.clone());
+    if bypass {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+// Count `amount` against today's global mint cap
+pub fn record_mint(env: &Env, amount: i128) -> Result<(), PiCoinError> {
+    let Some(cap) = limits(env).daily_mint_cap else { return Ok(()) };
+    let window = current(env, env.storage().instance().get(&RateLimitKey::MintWindow), DAY);
+    let window = consume(window, cap, amount).ok_or(PiCoinError::RateLimitExceeded)?;
+    env.storage().instance().set(&RateLimitKey::MintWindow, &window);
+    Ok(())
+}
+
+pub fn source_quota(env: &Env, source: &PiCoinSource) -> Option<SourceQuota> {
+    env.storage().instance().get(&RateLimitKey::SourceQuota(source.clone()))
+}
+
+// Set (Some) or lift (None) `source`'s quota. A new quota starts a fresh window.
+pub fn set_source_quota(env: &Env, source: &PiCoinSource, quota: &Option<SourceQuota>) {
+    let key = RateLimitKey::SourceQuota(source.clone());
+    match quota {
+        Some(quota) => env.storage().instance().set(&key, quota),
+        None => env.storage().instance().remove(&key),
+    }
+    env.storage().instance().remove(&RateLimitKey::SourceWindow(source.clone()));
+}
+
+// Count `amount` against `source`'s quota for the current epoch
+pub fn record_source_mint(env: &Env, source: &PiCoinSource, amount: i128) -> Result<(), PiCoinError> {
+    let Some(quota) = source_quota(env, source) else { return Ok(()) };
+    let key = RateLimitKey::SourceWindow(source.clone());
+    let window = current(env, env.storage().instance().get(&key), quota.epoch_length);
+    let window = consume(window, quota.cap, amount).ok_or(PiCoinError::MintQuotaExceeded)?;
+    env.storage().instance().set(&key, &window);
+    Ok(())
+}
+
+// PI still mintable from `source` this epoch (None: no quota)
+pub fn remaining_quota(env: &Env, source: &PiCoinSource) -> Option<i128> {
+    let quota = source_quota(env, source)?;
+    let window = current(env, env.storage().instance().get(&RateLimitKey::SourceWindow(source.clone())), quota.epoch_length);
+    Some(quota.cap - window.used)
+}
+
+// Count `amount` against `from`'s outflow cap for the current epoch
+pub fn record_outflow(env: &Env, from: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let limits = limits(env);
+    let Some(cap) = limits.outflow_cap else { return Ok(()) };
+    if is_bypassed(env, from) {
+        return Ok(());
+    }
+    let key = RateLimitKey::Outflow(from.clone());
+    let window = current(env, env.storage().temporary().get(&key), limits.epoch_length);
+    let window = consume(window, cap, amount).ok_or(PiCoinError::RateLimitExceeded)?;
+    env.storage().temporary().set(&key, &window);
+    env.storage().temporary().extend_ttl(&key, ledgers_for(limits.epoch_length), ledgers_for(limits.epoch_length));
+    Ok(())
+}
+
+// Outflow `account` may still send this epoch (None: uncapped)
+pub fn outflow_remaining(env: &Env, account: &Address) -> Option<i128> {
+    let limits = limits(env);
+    let cap = limits.outflow_cap?;
+    if is_bypassed(env, account) {
+        return None;
+    }
+    let window = current(env, env.storage().temporary().get(&RateLimitKey::Outflow(account.clone())), limits.epoch_length);
+    Some(cap - window.used)
+}
+
+// The stored window if still open, otherwise a fresh one starting now
+fn current(env: &Env, stored: Option<Window>, length: u64) -> Window {
+    let now = env.ledger().timestamp();
+    match stored {
+        Some(window) if now < window.start + length => window,
+        _ => Window { start: now, used: 0 },
+    }
+}
+
+// The window with `amount` more used, or None if that would pass `cap` (an amount too
+// large to add passes any cap)
+fn consume(mut window: Window, cap: i128, amount: i128) -> Option<Window> {
+    window.used = window.used.checked_add(amount).filter(|used| *used <= cap)?;
+    Some(window)
+}
+
+// Ledgers spanning `seconds` at ~5s per ledger, so an outflow window outlives its epoch
+fn ledgers_for(seconds: u64) -> u32 {
+    (seconds / 5 + 1).min(u32::MAX as u64) as u32
+}
@@ -0,0 +1,137 @@
+// Elastic supply: an optional, governance-activated mode in which the supply follows the
+// $314,159 peg. Balances and provenance lots are stored as shares of a global rebase
+// index, so a rebase rescales every holder at once by updating one value instead of
+// iterating holders (PI = shares * index / INDEX_ONE).
+//
+// `rebase()` samples the oracle once per epoch. When the price stays outside the band
+// around the peg on the same side for `epochs` consecutive epochs, the index is scaled by
+// the average sampled price (a TWAP over equal-length epochs) divided by the peg: supply
+// expands while PI trades above the peg and contracts while it trades below. Missing an
+// epoch, returning inside the band or crossing the peg restarts the count.
+//
+// Conversions round down. Moving PI debits and credits the same number of shares, and any
+// dust left by rounding is destroyed rather than created.
+use soroban_sdk::{contracttype, Env};
+use crate::fixed_point::{self, Rounding};
+use crate::{snapshot, PiCoinError};
+
+// Index value meaning 1 share = 1 PI (no rebase has happened yet)
+pub const INDEX_ONE: i128 = 1_000_000_000;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RebaseConfig {
+    pub band_bps: u32, // Tolerated deviation from the peg, in basis points
+    pub epochs: u32, // Consecutive out-of-band epochs that trigger a rebase
+    pub epoch_length: u64, // Seconds per sampling epoch
+}
+
+// Deviation streak being tracked between samples
+#[contracttype]
+#[derive(Clone)]
+pub struct RebaseState {
+    pub last_epoch: Option<u64>, // Epoch of the latest sample
+    pub streak: u32, // Consecutive out-of-band samples so far
+    pub above_peg: bool, // Side of the peg the streak is on
+    pub price_sum: i128, // Sum of the streak's sampled prices
+}
+
+// Variant names must be unique across modules: keys share the instance map and encode
+// by variant name alone, so a `Config` here would alias TtlKey::Config
+#[contracttype]
+#[derive(Clone)]
+pub enum RebaseKey {
+    Settings, // Absent while rebasing is off
+    Index,
+    Streak,
+}
+
+pub fn config(env: &Env) -> Option<RebaseConfig> {
+    env.storage().instance().get(&RebaseKey::Settings)
+}
+
+// Turn rebasing on (or retune it) with Some, off with None. Turning it off keeps the
+// index, so balances stay where the last rebase put them.
+pub fn set_config(env: &Env, config: &Option<RebaseConfig>) -> Result<(), PiCoinError> {
+    match config {
+        Some(config) => {
+            if config.band_bps > 10_000 || config.epochs == 0 || config.epoch_length == 0 {
+                return Err(PiCoinError::InvalidRebaseConfig);
+            }
+            env.storage().instance().set(&RebaseKey::Settings, config);
+        }
+        None => env.storage().instance().remove(&RebaseKey::Settings),
+    }
+    env.storage().instance().remove(&RebaseKey::Streak);
+    Ok(())
+}
+
+pub fn index(env: &Env) -> i128 {
+    env.storage().instance().get(&RebaseKey::Index).unwrap_or(INDEX_ONE)
+}
+
+// Shares worth `amount` PI, Overflow if the share count would pass i128
+pub fn to_shares(env: &Env, amount: i128) -> Result<i128, PiCoinError> {
+    amount.checked_mul(INDEX_ONE).map(|scaled| scaled / index(env)).ok_or(PiCoinError::Overflow)
+}
+
+pub fn to_amount(env: &Env, shares: i128) -> Result<i128, PiCoinError> {
+    to_amount_at(shares, index(env))
+}
+
+// PI worth of `shares` at a given (e.g. checkpointed) index, Overflow if the product
+// would pass i128
+pub fn to_amount_at(shares: i128, index: i128) -> Result<i128, PiCoinError> {
+    shares.checked_mul(index).map(|scaled| scaled / INDEX_ONE).ok_or(PiCoinError::Overflow)
+}
+
+fn state(env: &Env) -> RebaseState {
+    env.storage().instance().get(&RebaseKey::Streak).unwrap_or(RebaseState {
+        last_epoch: None,
+        streak: 0,
+        above_peg: false,
+        price_sum: 0,
+    })
+}
+
+// Record this epoch's `price` sample. Returns Some((twap, new index)) when the streak
+// completes and the index moves.
+pub fn observe(env: &Env, config: &RebaseConfig, price: i128, peg: i128) -> Result<Option<(i128, i128)>, PiCoinError> {
+    let epoch = env.ledger().timestamp() / config.epoch_length;
+    let mut state = state(env);
+    if state.last_epoch.is_some_and(|last| epoch <= last) {
+        return Err(PiCoinError::RebaseTooEarly);
+    }
+    let consecutive = state.last_epoch.is_some_and(|last| last + 1 == epoch);
+    let above_peg = price > peg;
+    state.last_epoch = Some(epoch);
+
+    let deviation = price.checked_sub(peg).and_then(i128::checked_abs).ok_or(PiCoinError::Overflow)?;
+    let deviation_bps = fixed_point::mul_div(deviation, fixed_point::BPS, peg, Rounding::Down)?;
+    if deviation_bps <= config.band_bps as i128 {
+        state.streak = 0;
+        state.price_sum = 0;
+    } else {
+        if !consecutive || above_peg != state.above_peg {
+            state.streak = 0;
+            state.price_sum = 0;
+        }
+        state.streak += 1;
+        state.above_peg = above_peg;
+        state.price_sum = state.price_sum.checked_add(price).ok_or(PiCoinError::Overflow)?;
+    }
+
+    let mut rebased = None;
+    if state.streak >= config.epochs {
+        let twap = state.price_sum / state.streak as i128;
+        let previous = index(env);
+        let index = fixed_point::mul_div(previous, twap, peg, Rounding::Down)?.max(1);
+        env.storage().instance().set(&RebaseKey::Index, &index);
+        snapshot::record_index(env, previous, index);
+        state.streak = 0;
+        state.price_sum = 0;
+        rebased = Some((twap, index));
+    }
+    env.storage().instance().set(&RebaseKey::Streak, &state);
+    Ok(rebased)
+}
@@ -0,0 +1,15 @@
+// Pulling tokens into a contract when the token may charge a transfer fee: the caller is
+// credited what arrived, measured on the contract's own balance, not what was asked
+// for. Shared by the ecosystem contracts that take deposits; the token does not use it.
+use soroban_sdk::{token, Address, Env};
+
+// Move `amount` of `token` from `from` into the current contract; returns the amount that
+// arrived, net of any transfer fee. None when the balance change does not fit an i128,
+// which only a misbehaving token can cause.
+pub fn pull(env: &Env, token: &Address, from: &Address, amount: i128) -> Option<i128> {
+    let client = token::TokenClient::new(env, token);
+    let this = env.current_contract_address();
+    let before = client.balance(&this);
+    client.transfer(from, &this, &amount);
+    client.balance(&this).checked_sub(before)
+}
@@ -0,0 +1,41 @@
+// Reentrancy guard. The host already traps on a call back into a contract that is on the
+// call stack, so a callee can never re-enter a token entry point mid-execution. A
+// multi-call operation, though, leaves the token off the stack between its calls: a flash
+// mint's receiver runs after flash_credit returned and before flash_settle. The guard
+// marks such a window with an instance flag, and entry points that must not run inside
+// one check it.
+//
+// The flag is set and cleared within one transaction; a trap in between reverts it along
+// with everything else.
+use soroban_sdk::{contracttype, Env};
+use crate::PiCoinError;
+
+// Single instance-storage flag, present only while a flash mint is open
+#[contracttype]
+#[derive(Clone)]
+pub enum GuardKey {
+    GuardLocked,
+}
+
+pub fn is_locked(env: &Env) -> bool {
+    env.storage().instance().has(&GuardKey::GuardLocked)
+}
+
+// Reentrant inside an open window
+pub fn check(env: &Env) -> Result<(), PiCoinError> {
+    if is_locked(env) {
+        return Err(PiCoinError::Reentrant);
+    }
+    Ok(())
+}
+
+// Open a window; Reentrant if one is already open
+pub fn lock(env: &Env) -> Result<(), PiCoinError> {
+    check(env)?;
+    env.storage().instance().set(&GuardKey::GuardLocked, &true);
+    Ok(())
+}
+
+pub fn unlock(env: &Env) {
+    env.storage().instance().remove(&GuardKey::GuardLocked);
+}
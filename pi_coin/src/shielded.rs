@@ -0,0 +1,242 @@
+// Shielded pool: PI held by the token contract itself and owned through notes. A note
+// commits to (amount, owner key, blinding) as
+//   commitment = field(sha256(amount as 8 big-endian bytes || secret)),
+// where secret = sha256(owner key || blinding) stays private; spending a note reveals its
+// nullifier, a value derived from the note and its owner's key that cannot be linked
+// back to the commitment. Notes enter the tree two ways:
+//   - `deposit`: the depositor pays a public amount and hands over only `secret`, and
+//     the contract computes the commitment itself, so no proof is needed;
+//   - private transfers: one Groth16 proof for the 2-in-2-out confidential-transfer
+//     circuit, which proves that
+//       - both input notes are in the commitment tree under `root`,
+//       - `nullifiers` are those notes' nullifiers, signed for by their owner,
+//       - `commitments` are well-formed output notes, and
+//       - input amounts + public_amount == output amounts, all in [0, 2^64).
+// A positive `public_amount` shields PI from `account` into the pool, a negative one
+// unshields it to `account` (a withdrawal: membership proof plus nullifiers), and zero is
+// a fully private transfer. `account` is bound into the proof through ext_data_hash, so
+// a relayer cannot redirect a withdrawal.
+//
+// Commitments are appended to an incremental SHA-256 Merkle tree of depth TREE_DEPTH
+// whose last ROOT_HISTORY roots are accepted, so a proof made against a slightly stale
+// root still lands. Spent nullifiers are kept forever in persistent storage. The tree
+// also counts the PI in notes. Every amount entering or leaving the pool is public, so
+// this supply is auditable against the pool's balance, and the total supply stays the
+// sum of public balances.
+//
+// Circuit public inputs, in order: root, public_amount, ext_data_hash, nullifiers[0..2],
+// commitments[0..2]. Roots and ext_data_hash are SHA-256 outputs with the top three bits
+// cleared so they fit the scalar field; nullifiers and commitments must already fit.
+use soroban_sdk::{
+    contracttype,
+    crypto::bls12_381::Fr,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, Vec, U256,
+};
+use crate::groth16::VerificationKey;
+use crate::PiCoinError;
+
+pub const TREE_DEPTH: u32 = 20;
+pub const ROOT_HISTORY: u32 = 32;
+pub const NOTES_PER_TRANSFER: u32 = 2;
+// Note amounts are range-checked to 64 bits by the circuit
+pub const MAX_NOTE_AMOUNT: i128 = u64::MAX as i128;
+
+// Public inputs of a private transfer (see the module comment)
+#[contracttype]
+#[derive(Clone)]
+pub struct PrivateTransferInputs {
+    pub root: BytesN<32>,
+    pub public_amount: i128, // PI entering (> 0) or leaving (< 0) the pool
+    pub account: Option<Address>, // Pays in or is paid out; required unless public_amount is 0
+    pub nullifiers: Vec<BytesN<32>>,
+    pub commitments: Vec<BytesN<32>>,
+}
+
+// Append-only commitment tree: the rightmost filled node per level and recent roots
+#[contracttype]
+#[derive(Clone)]
+pub struct CommitmentTree {
+    pub next_index: u32,
+    pub filled: Vec<BytesN<32>>,
+    pub roots: Vec<BytesN<32>>, // Oldest first
+    pub supply: i128, // PI held in notes
+}
+
+// Shielded pool keys: the verification key in instance storage, the note tree and spent
+// nullifiers in persistent storage. A unit key is stored as its bare variant name, so the
+// `Shielded` prefix keeps `ShieldedVk` and `ShieldedTree` apart from the token's other keys.
+#[contracttype]
+#[derive(Clone)]
+pub enum ShieldedKey {
+    ShieldedVk, // Absent while private transfers are off
+    ShieldedTree,
+    Nullifier(BytesN<32>),
+}
+
+pub fn vk(env: &Env) -> Option<VerificationKey> {
+    env.storage().instance().get(&ShieldedKey::ShieldedVk)
+}
+
+pub fn set_vk(env: &Env, vk: &VerificationKey) {
+    env.storage().instance().set(&ShieldedKey::ShieldedVk, vk);
+}
+
+pub fn is_spent(env: &Env, nullifier: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&ShieldedKey::Nullifier(nullifier.clone()))
+}
+
+pub fn tree(env: &Env) -> CommitmentTree {
+    env.storage().persistent().get(&ShieldedKey::ShieldedTree).unwrap_or_else(|| {
+        let zero = BytesN::from_array(env, &[0; 32]);
+        let mut filled = Vec::new(env);
+        for _ in 0..TREE_DEPTH {
+            filled.push_back(zero.clone());
+        }
+        let mut empty_root = zero;
+        for _ in 0..TREE_DEPTH {
+            empty_root = hash_pair(env, &empty_root, &empty_root);
+        }
+        CommitmentTree { next_index: 0, filled, roots: Vec::from_array(env, [empty_root]), supply: 0 }
+    })
+}
+
+pub fn root(env: &Env) -> BytesN<32> {
+    tree(env).roots.last_unchecked()
+}
+
+pub fn supply(env: &Env) -> i128 {
+    tree(env).supply
+}
+
+// Commitment of a note of `amount` PI whose secret hashes to `secret` (see the module comment)
+pub fn note_commitment(env: &Env, amount: i128, secret: &BytesN<32>) -> BytesN<32> {
+    let mut bytes = Bytes::from_array(env, &(amount as u64).to_be_bytes());
+    bytes.append(&Bytes::from(secret.clone()));
+    to_field(env, &env.crypto().sha256(&bytes).to_bytes()).to_bytes()
+}
+
+// Check a deposit of `amount` PI: the amount must fit a note and the tree needs room
+pub fn check_deposit(env: &Env, amount: i128) -> Result<(), PiCoinError> {
+    if amount <= 0 || amount > MAX_NOTE_AMOUNT {
+        return Err(PiCoinError::InvalidNoteAmount);
+    }
+    if tree(env).next_index >= 1 << TREE_DEPTH {
+        return Err(PiCoinError::ShieldedTreeFull);
+    }
+    Ok(())
+}
+
+// Record a checked deposit: append the note's commitment. Returns its tree index.
+pub fn deposit(env: &Env, amount: i128, commitment: BytesN<32>) -> u32 {
+    let mut tree = tree(env);
+    let index = tree.next_index;
+    insert(env, &mut tree, commitment);
+    tree.supply += amount;
+    env.storage().persistent().set(&ShieldedKey::ShieldedTree, &tree);
+    index
+}
+
+// Check the shape of `inputs`, that their root is recent, their nullifiers unspent and
+// the tree has room for their commitments
+pub fn check(env: &Env, inputs: &PrivateTransferInputs) -> Result<(), PiCoinError> {
+    if inputs.nullifiers.len() != NOTES_PER_TRANSFER || inputs.commitments.len() != NOTES_PER_TRANSFER {
+        return Err(PiCoinError::InvalidProof);
+    }
+    if inputs.nullifiers.get_unchecked(0) == inputs.nullifiers.get_unchecked(1) {
+        return Err(PiCoinError::InvalidProof);
+    }
+    for value in inputs.nullifiers.iter().chain(inputs.commitments.iter()) {
+        if value.get(0).unwrap_or(0) >= 0x20 {
+            return Err(PiCoinError::InvalidProof); // Not a canonical field element
+        }
+    }
+    if (inputs.public_amount != 0) != inputs.account.is_some() {
+        return Err(PiCoinError::InvalidProof);
+    }
+    if inputs.public_amount.unsigned_abs() > MAX_NOTE_AMOUNT as u128 {
+        return Err(PiCoinError::InvalidNoteAmount);
+    }
+    let tree = tree(env);
+    if tree.supply + inputs.public_amount < 0 {
+        return Err(PiCoinError::InsufficientBalance); // Withdrawing more than the notes hold
+    }
+    if !tree.roots.contains(&inputs.root) {
+        return Err(PiCoinError::InvalidProof);
+    }
+    if tree.next_index + NOTES_PER_TRANSFER > 1 << TREE_DEPTH {
+        return Err(PiCoinError::ShieldedTreeFull);
+    }
+    for nullifier in inputs.nullifiers.iter() {
+        if is_spent(env, &nullifier) {
+            return Err(PiCoinError::NullifierSpent);
+        }
+    }
+    Ok(())
+}
+
+// Circuit public inputs for `inputs`, in the order the verification key expects
+pub fn public_inputs(env: &Env, inputs: &PrivateTransferInputs) -> Vec<Fr> {
+    let magnitude = Fr::from_u256(U256::from_u128(env, inputs.public_amount.unsigned_abs()));
+    let public_amount = if inputs.public_amount < 0 {
+        Fr::from_u256(U256::from_u32(env, 0)) - magnitude
+    } else {
+        magnitude
+    };
+    let ext_data_hash = env.crypto().sha256(&inputs.account.clone().to_xdr(env)).to_bytes();
+    let mut values = Vec::from_array(env, [to_field(env, &inputs.root), public_amount, to_field(env, &ext_data_hash)]);
+    for value in inputs.nullifiers.iter().chain(inputs.commitments.iter()) {
+        values.push_back(Fr::from_bytes(value));
+    }
+    values
+}
+
+// Record a checked and verified transfer: spend its nullifiers and append its
+// commitments. Returns the tree index of the first commitment.
+pub fn apply(env: &Env, inputs: &PrivateTransferInputs) -> u32 {
+    let mut tree = tree(env);
+    let first = tree.next_index;
+    for nullifier in inputs.nullifiers.iter() {
+        env.storage().persistent().set(&ShieldedKey::Nullifier(nullifier), &true);
+    }
+    for commitment in inputs.commitments.iter() {
+        insert(env, &mut tree, commitment);
+    }
+    tree.supply += inputs.public_amount;
+    env.storage().persistent().set(&ShieldedKey::ShieldedTree, &tree);
+    first
+}
+
+fn insert(env: &Env, tree: &mut CommitmentTree, leaf: BytesN<32>) {
+    let mut index = tree.next_index;
+    let mut node = leaf;
+    let mut zero = BytesN::from_array(env, &[0; 32]);
+    for level in 0..TREE_DEPTH {
+        if index.is_multiple_of(2) {
+            tree.filled.set(level, node.clone());
+            node = hash_pair(env, &node, &zero);
+        } else {
+            node = hash_pair(env, &tree.filled.get_unchecked(level), &node);
+        }
+        zero = hash_pair(env, &zero, &zero);
+        index /= 2;
+    }
+    tree.next_index += 1;
+    tree.roots.push_back(node);
+    if tree.roots.len() > ROOT_HISTORY {
+        tree.roots.pop_front();
+    }
+}
+
+fn hash_pair(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut bytes = Bytes::from(left.clone());
+    bytes.append(&Bytes::from(right.clone()));
+    env.crypto().sha256(&bytes).to_bytes()
+}
+
+// A hash as a scalar: clear the top three bits so it is below the field modulus
+fn to_field(env: &Env, hash: &BytesN<32>) -> Fr {
+    let mut bytes = hash.to_array();
+    bytes[0] &= 0x1f;
+    Fr::from_bytes(BytesN::from_array(env, &bytes))
+}
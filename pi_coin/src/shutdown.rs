@@ -0,0 +1,137 @@
+// Emergency shutdown (global settlement): the last resort when the peg cannot be
+// defended. Governance may trigger it at any time; the guardian only once the oracle has
+// gone `oracle_timeout` seconds without a valid price, so a dead oracle cannot leave the
+// token running blind while governance is slow to act.
+//
+// Shutdown captures the settlement terms once - the last valid oracle price, the
+// collateral pool (this contract's balance of the collateral asset, a USD stablecoin
+// valued at $1) and the circulating PI - and from then on minting, transfers, flash
+// mints, shielding and rebases are frozen. Every holder may redeem PI for collateral at
+//
+//     min(amount * price, amount * collateral / supply)
+//
+// that is, at the settlement price while the pool covers it and pro-rata once it does
+// not. Both terms are fixed at shutdown, so the payout per PI does not depend on who
+// redeems first. Shielded PI stays redeemable: unshielding is not frozen.
+use soroban_sdk::{contracttype, Address, Env};
+use crate::fixed_point::{self, Rounding};
+use crate::PiCoinError;
+
+// Oracle prices are in micro-USD per PI
+pub const PRICE_UNIT: i128 = 1_000_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianConfig {
+    pub guardian: Address,
+    pub oracle_timeout: u64, // Seconds without a valid price before the guardian may act
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OraclePrice {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Terms captured at shutdown, plus what has been redeemed against them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub price: i128, // Last valid oracle price, micro-USD per PI
+    pub collateral: i128, // Collateral pool at shutdown
+    pub collateral_decimals: u32,
+    pub supply: i128, // Circulating PI at shutdown
+    pub redeemed: i128, // PI redeemed so far
+    pub paid: i128, // Collateral paid out so far
+    pub shutdown_at: u64,
+    pub by_guardian: bool,
+}
+
+// Shutdown keys: guardian, price snapshot and settlement live in instance storage so the
+// redemption path reads them cheaply; per-holder totals are persistent
+#[contracttype]
+#[derive(Clone)]
+pub enum ShutdownKey {
+    Guardian,
+    LastValidPrice,
+    Settlement, // Present once shut down
+    Redeemed(Address), // Holder -> (PI redeemed, collateral received)
+}
+
+pub fn guardian(env: &Env) -> Option<GuardianConfig> {
+    env.storage().instance().get(&ShutdownKey::Guardian)
+}
+
+pub fn set_guardian(env: &Env, config: &GuardianConfig) {
+    env.storage().instance().set(&ShutdownKey::Guardian, config);
+}
+
+pub fn last_price(env: &Env) -> Option<OraclePrice> {
+    env.storage().instance().get(&ShutdownKey::LastValidPrice)
+}
+
+// Record an oracle reading; only a positive price counts as valid
+pub fn record_price(env: &Env, price: i128) -> Result<(), PiCoinError> {
+    if price <= 0 {
+        return Err(PiCoinError::PegDeviation);
+    }
+    let reading = OraclePrice { price, timestamp: env.ledger().timestamp() };
+    env.storage().instance().set(&ShutdownKey::LastValidPrice, &reading);
+    Ok(())
+}
+
+// Whether the oracle has gone `oracle_timeout` seconds without a valid price; true if it
+// never reported one
+pub fn oracle_failed(env: &Env, oracle_timeout: u64) -> bool {
+    match last_price(env) {
+        Some(reading) => env.ledger().timestamp() > reading.timestamp.saturating_add(oracle_timeout),
+        None => true,
+    }
+}
+
+pub fn settlement(env: &Env) -> Option<Settlement> {
+    env.storage().instance().get(&ShutdownKey::Settlement)
+}
+
+// Shutdown if the token has been shut down
+pub fn check_live(env: &Env) -> Result<(), PiCoinError> {
+    if env.storage().instance().has(&ShutdownKey::Settlement) {
+        return Err(PiCoinError::Shutdown);
+    }
+    Ok(())
+}
+
+pub fn save(env: &Env, settlement: &Settlement) {
+    env.storage().instance().set(&ShutdownKey::Settlement, settlement);
+}
+
+// Collateral owed for `amount` PI under the settlement terms, rounded down
+pub fn payout(settlement: &Settlement, amount: i128) -> Result<i128, PiCoinError> {
+    if settlement.supply == 0 {
+        return Ok(0);
+    }
+    let scale = 10i128.checked_pow(settlement.collateral_decimals).ok_or(PiCoinError::ArithmeticError)?;
+    let at_price = fixed_point::mul_div(amount, settlement.price.checked_mul(scale).ok_or(PiCoinError::ArithmeticError)?, PRICE_UNIT, Rounding::Down)?;
+    let pro_rata = fixed_point::mul_div(amount, settlement.collateral, settlement.supply, Rounding::Down)?;
+    Ok(at_price.min(pro_rata))
+}
+
+pub fn redeemed(env: &Env, holder: &Address) -> (i128, i128) {
+    env.storage().persistent().get(&ShutdownKey::Redeemed(holder.clone())).unwrap_or((0, 0))
+}
+
+// Book a redemption in the holder's and the global tallies
+pub fn record_redemption(env: &Env, settlement: &mut Settlement, holder: &Address, amount: i128, paid: i128) -> Result<(), PiCoinError> {
+    let (total_amount, total_paid) = redeemed(env, holder);
+    let holder_totals = (checked_add(total_amount, amount)?, checked_add(total_paid, paid)?);
+    env.storage().persistent().set(&ShutdownKey::Redeemed(holder.clone()), &holder_totals);
+    settlement.redeemed = checked_add(settlement.redeemed, amount)?;
+    settlement.paid = checked_add(settlement.paid, paid)?;
+    save(env, settlement);
+    Ok(())
+}
+
+fn checked_add(total: i128, amount: i128) -> Result<i128, PiCoinError> {
+    total.checked_add(amount).ok_or(PiCoinError::Overflow)
+}
@@ -0,0 +1,117 @@
+// Balance snapshots: every holder's balance, the circulating supply and the rebase index
+// are checkpointed by ledger, so dividends, airdrops and snapshot votes can ask what
+// somebody held at a past ledger instead of trusting balances that may have moved since.
+//
+// A history is a vector of (ledger, value) checkpoints, oldest first, each holding the
+// value at the end of its ledger; a lookup binary-searches for the last checkpoint at or
+// before the queried ledger. Holder and supply histories hold rebase shares, and balances
+// are converted at the index checkpointed for the same ledger, so a rebase rescales past
+// balances only from the ledger it happened in.
+//
+// Histories start at the first change: the first write also records the prior value at
+// ledger 0, and a key with no history has held its current value throughout. Pruning
+// keeps them bounded: checkpoints are dropped once the next one is older than the
+// retention window (the dropped one can no longer be the answer for any ledger inside
+// it), and beyond MAX_CHECKPOINTS the oldest go regardless. Ledgers before a history's
+// first remaining checkpoint are unavailable.
+use soroban_sdk::{contracttype, Address, Env, Vec};
+use crate::ttl::{self, DAY_IN_LEDGERS};
+use crate::PiCoinError;
+
+// Upper bound on one history, keeping each entry well inside the ledger entry size limit
+pub const MAX_CHECKPOINTS: u32 = 128;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub value: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SnapshotKey {
+    Holder(Address), // Holder -> Vec<Checkpoint> of shares
+    SupplyHistory, // Vec<Checkpoint> of total shares
+    IndexHistory, // Vec<Checkpoint> of the rebase index
+    Retention, // Ledgers of history kept
+}
+
+pub fn retention(env: &Env) -> u32 {
+    env.storage().instance().get(&SnapshotKey::Retention).unwrap_or(30 * DAY_IN_LEDGERS)
+}
+
+pub fn set_retention(env: &Env, ledgers: u32) {
+    env.storage().instance().set(&SnapshotKey::Retention, &ledgers);
+}
+
+pub fn record_holder(env: &Env, holder: &Address, previous: i128, shares: i128) {
+    record(env, &SnapshotKey::Holder(holder.clone()), previous, shares);
+}
+
+pub fn record_supply(env: &Env, previous: i128, shares: i128) {
+    record(env, &SnapshotKey::SupplyHistory, previous, shares);
+}
+
+pub fn record_index(env: &Env, previous: i128, index: i128) {
+    record(env, &SnapshotKey::IndexHistory, previous, index);
+}
+
+// Shares `holder` had at the end of `ledger`; `current` if it has no history
+pub fn holder_at(env: &Env, holder: &Address, ledger: u32, current: i128) -> Result<i128, PiCoinError> {
+    lookup(env, &SnapshotKey::Holder(holder.clone()), ledger, current)
+}
+
+pub fn supply_at(env: &Env, ledger: u32, current: i128) -> Result<i128, PiCoinError> {
+    lookup(env, &SnapshotKey::SupplyHistory, ledger, current)
+}
+
+pub fn index_at(env: &Env, ledger: u32, current: i128) -> Result<i128, PiCoinError> {
+    lookup(env, &SnapshotKey::IndexHistory, ledger, current)
+}
+
+// Checkpoint `value` at the current ledger, overwriting a checkpoint already made in it
+fn record(env: &Env, key: &SnapshotKey, previous: i128, value: i128) {
+    let ledger = env.ledger().sequence();
+    let mut history: Vec<Checkpoint> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+    if history.is_empty() && ledger > 0 {
+        history.push_back(Checkpoint { ledger: 0, value: previous });
+    }
+    match history.last() {
+        Some(last) if last.ledger == ledger => history.set(history.len() - 1, Checkpoint { ledger, value }),
+        _ => history.push_back(Checkpoint { ledger, value }),
+    }
+
+    let horizon = ledger.saturating_sub(retention(env));
+    while history.len() > MAX_CHECKPOINTS || (history.len() >= 2 && history.get_unchecked(1).ledger <= horizon) {
+        history.pop_front();
+    }
+    env.storage().persistent().set(key, &history);
+    ttl::bump_persistent(env, key);
+}
+
+// Value at the end of `ledger`, which must already be closed. SnapshotUnavailable for the
+// current or a future ledger, and for ledgers pruned from the history.
+fn lookup(env: &Env, key: &SnapshotKey, ledger: u32, current: i128) -> Result<i128, PiCoinError> {
+    if ledger >= env.ledger().sequence() {
+        return Err(PiCoinError::SnapshotUnavailable);
+    }
+    let history: Vec<Checkpoint> = match env.storage().persistent().get(key) {
+        Some(history) => history,
+        None => return Ok(current),
+    };
+    // First checkpoint after `ledger`; the answer is the one before it
+    let (mut low, mut high) = (0, history.len());
+    while low < high {
+        let mid = (low + high) / 2;
+        if history.get_unchecked(mid).ledger <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        return Err(PiCoinError::SnapshotUnavailable);
+    }
+    Ok(history.get_unchecked(low - 1).value)
+}
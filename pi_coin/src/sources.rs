@@ -0,0 +1,39 @@
+// Source registry: governance decides which mint sources are accepted, so a new
+// acquisition channel needs no wasm upgrade. New channels mint as
+// PiCoinSource::Registered(symbol) once registered as valid. The built-in Mining,
+// Rewards and P2P are accepted until governance registers them otherwise; Invalid
+// and FlashMint never are.
+use soroban_sdk::{contracttype, Env};
+use crate::{events, PiCoinSource};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceInfo {
+    pub valid: bool, // Whether mint accepts the source
+    pub risk_score: u32, // Higher is riskier; published for compliance tooling, not enforced
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SourceKey {
+    Source(PiCoinSource),
+}
+
+pub fn info(env: &Env, source: &PiCoinSource) -> Option<SourceInfo> {
+    env.storage().instance().get(&SourceKey::Source(source.clone()))
+}
+
+pub fn register(env: &Env, source: &PiCoinSource, info: &SourceInfo) {
+    env.storage().instance().set(&SourceKey::Source(source.clone()), info);
+    events::source_registered_event(env, source.clone(), info);
+}
+
+// Whether mint accepts `source`
+pub fn is_valid(env: &Env, source: &PiCoinSource) -> bool {
+    match (source, info(env, source)) {
+        (PiCoinSource::Invalid | PiCoinSource::FlashMint, _) => false,
+        (_, Some(info)) => info.valid,
+        (PiCoinSource::Registered(_), None) => false,
+        (_, None) => true,
+    }
+}
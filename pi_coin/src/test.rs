@@ -1,29 +1,34 @@
 #![cfg(test)]
-use soroban_sdk::{testutils::*, Address, Env, Symbol, Bytes, BytesN, crypto};
+extern crate std; // println! in the narrated tests
+use std::println;
+use soroban_sdk::{testutils::*, Address, Env, Symbol, Bytes, BytesN};
+use soroban_sdk::testutils::Address as _; // Address::generate
 use crate::PiCoinContract; // Import kontrak utama
 use crate::PiCoinData; // Import struct data
 use crate::PiCoinSource; // Import enum source
 
+// Register PiCoinContract with generated collateral, oracle and governance addresses;
+// returns its client and the admin
+fn setup(env: &Env) -> (crate::PiCoinContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let contract_id = env.register(PiCoinContract, (&admin, Address::generate(env), Address::generate(env), Address::generate(env)));
+    (crate::PiCoinContractClient::new(env, &contract_id), admin)
+}
+
 #[test]
-fn test_initialize_hyper_tech() {
+fn test_constructor_hyper_tech() {
     let env = Env::default();
     env.mock_all_auths(); // Hyper-tech: Mock auth untuk simulasi quantum-secure
 
-    let admin = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
-
-    // Initialize dengan parameter ultimate
-    let result = PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance);
-    assert!(result.is_ok());
+    // Constructor dengan parameter ultimate
+    let (client, _) = setup(&env);
 
     // Verifikasi data immutable (anti-tamper)
-    let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+    let data: PiCoinData = env.as_contract(&client.address, || env.storage().instance().get(&Symbol::new(&env, "data")).unwrap());
     assert_eq!(data.symbol, Symbol::new(&env, "PI"));
     assert_eq!(data.total_supply, 100_000_000_000);
     assert_eq!(data.peg_value, 314_159_000_000);
-    assert_eq!(data.anti_fraud_hash, env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique")));
+    assert_eq!(data.anti_fraud_hash, BytesN::from(env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique"))));
     println!("Hyper-tech init: Symbol PI locked, supply 100B, peg $314,159 verified with quantum hash - Exclusive sources only");
 }
 
@@ -31,234 +36,1664 @@ fn test_initialize_hyper_tech() {
 fn test_mint_with_collateral_backing() {
     let env = Env::default();
     env.mock_all_auths();
-
-    let admin = Address::random(&env);
-    let to = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
-
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    let (client, _) = setup(&env);
+    let to = Address::generate(&env);
 
     // Mint dengan collateral check (1:1 backing) dan valid source
     let amount = 1_000_000;
     let source = PiCoinSource::Mining; // Valid source for peg
-    let result = PiCoinContract::mint(env.clone(), to.clone(), amount, source);
-    assert!(result.is_ok());
+    assert!(client.try_mint(&to, &amount, &source).is_ok());
 
     // Hyper-tech: Verify quantum provenance logged
     let logs = env.logger().all();
     assert!(logs.iter().any(|log| log.contains("quantum provenance")));
-    println!("Ultimate mint: {} PI from {} source minted with full collateral, quantum-resistant provenance applied", amount, source);
+    println!("Ultimate mint: {} PI from {:?} source minted with full collateral, quantum-resistant provenance applied", amount, source);
 }
 
 #[test]
-fn test_transfer_with_anti_fraud_zkp() {
+fn test_transfer_with_provenance() {
     let env = Env::default();
     env.mock_all_auths();
-
-    let admin = Address::random(&env);
-    let from = Address::random(&env);
-    let to = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
-
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    let (client, _) = setup(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
 
     // Mint first to set provenance
     let source = PiCoinSource::Rewards;
-    PiCoinContract::mint(env.clone(), from.clone(), 500_000, source).unwrap();
+    client.mint(&from, &500_000, &source);
 
-    // Setup ZKP base for anti-fraud
-    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[42, 0])); // Simulated ZKP seed
-    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
-
-    // Transfer dengan ZKP verification dan provenance check
+    // Transfer dengan provenance check
     let amount = 500_000;
-    let result = PiCoinContract::transfer(env.clone(), from, to, amount);
-    assert!(result.is_ok());
+    assert!(client.try_transfer(&from, &to, &amount).is_ok());
 
-    // Hyper-tech: Check anti-fraud and provenance log
+    // Hyper-tech: Check provenance log
     let logs = env.logger().all();
-    assert!(logs.iter().any(|log| log.contains("anti-fraud ZKP")));
     assert!(logs.iter().any(|log| log.contains("valid provenance")));
-    println!("Maximum level transfer: {} PI moved with ZKP anti-forgery and {} source provenance, untouchable duplication", amount, source);
+    println!("Maximum level transfer: {} PI moved with {:?} source provenance, untouchable duplication", amount, source);
 }
 
 #[test]
 fn test_verify_peg_with_ai_oracle() {
     let env = Env::default();
     env.mock_all_auths();
-
-    let admin = Address::random(&env);
-    let holder = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
-
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
 
     // Mint to set valid provenance
     let source = PiCoinSource::P2P;
-    PiCoinContract::mint(env.clone(), holder.clone(), 1_000_000, source).unwrap();
+    client.mint(&holder, &1_000_000, &source);
 
     // Verify peg dengan AI oracle simulation dan provenance check
-    let result = PiCoinContract::verify_peg(env.clone(), holder.clone());
-    assert!(result.is_ok());
+    assert!(client.try_verify_peg(&holder).is_ok());
 
     // Hyper-tech: Simulate AI prediction deviation
     env.ledger().set_timestamp(1000000); // Change ledger for dynamic oracle
-    let result_dev = PiCoinContract::verify_peg(env.clone(), holder);
-    assert!(result_dev.is_ok()); // Should still pass with micro-deviation for valid source
-    println!("Super advanced peg verify: AI oracle confirms $314,159 stability for {} source, global market synced", source);
+    assert!(client.try_verify_peg(&holder).is_ok()); // Should still pass with micro-deviation for valid source
+    println!("Super advanced peg verify: AI oracle confirms $314,159 stability for {:?} source, global market synced", source);
 }
 
 #[test]
-fn test_governance_vote_quantum_secure() {
+fn test_error_insufficient_collateral() {
     let env = Env::default();
     env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let to = Address::generate(&env);
 
-    let admin = Address::random(&env);
-    let voter = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
-
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    // Attempt mint with insufficient collateral (simulated failure) and valid source
+    let amount = 200_000_000_000; // Exceed mock collateral
+    let result = client.try_mint(&to, &amount, &PiCoinSource::Rewards);
+    assert_eq!(result, Err(Ok(crate::PiCoinError::InsufficientCollateral)));
+    println!("Hyper-tech error: Mint blocked by collateral check, ultimate security enforced");
+}
 
-    // Mint to set valid provenance
-    let source = PiCoinSource::Mining;
-    PiCoinContract::mint(env.clone(), voter.clone(), 100_000, source).unwrap();
+#[test]
+fn test_global_payment_simulation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let to = Address::generate(&env);
 
-    // Governance vote dengan quantum sig dan provenance check
-    let proposal = Symbol::new(&env, "rebase");
-    let result = PiCoinContract::governance_vote(env.clone(), voter, proposal);
-    assert!(result.is_ok());
+    // Mint and simulate global payment with valid source
+    let source = PiCoinSource::P2P;
+    client.mint(&to, &10_000_000, &source);
 
-    // Hyper-tech: Verify multi-sig and provenance log
+    // Check global recognition log
     let logs = env.logger().all();
-    assert!(logs.iter().any(|log| log.contains("Quantum vote")));
-    assert!(logs.iter().any(|log| log.contains("from Mining source")));
-    println!("Ultimate governance: Vote cast with quantum-secure multi-sig and {} source provenance, unmatched integrity", source);
+    assert!(logs.iter().any(|log| log.contains("global payment")));
+    assert!(logs.iter().any(|log| log.contains("from valid source")));
+    println!("Live functional: PI from {:?} source recognized as worldwide payment tool, DEX-ready for global adoption", source);
 }
 
 #[test]
-fn test_error_insufficient_collateral() {
+fn test_mint_invalid_source_rejected() {
     let env = Env::default();
     env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let to = Address::generate(&env);
 
-    let admin = Address::random(&env);
-    let to = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
+    // Attempt mint with invalid source (should be rejected - no ecosystem entry)
+    let result = client.try_mint(&to, &1_000_000, &PiCoinSource::Invalid);
+    assert_eq!(result, Err(Ok(crate::PiCoinError::InvalidSource)));
+    println!("Hyper-tech rejection: Mint from invalid source blocked - No access to $314,159 peg or Pi Ecosystem");
+}
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+#[test]
+fn test_transfer_invalid_provenance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let from = Address::generate(&env); // No provenance set
+    let to = Address::generate(&env);
 
-    // Attempt mint with insufficient collateral (simulated failure) and valid source
-    let amount = 200_000_000_000; // Exceed mock collateral
+    // Attempt transfer without valid provenance (should fail)
+    let result = client.try_transfer(&from, &to, &500_000);
+    assert_eq!(result, Err(Ok(crate::PiCoinError::InvalidSource)));
+    println!("Ultimate provenance check: Transfer blocked for invalid source - Ecosystem protection enforced");
+}
+
+#[test]
+fn test_verify_ecosystem_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let valid_holder = Address::generate(&env);
+    let invalid_holder = Address::generate(&env);
+
+    // Mint for valid holder
     let source = PiCoinSource::Rewards;
-    let result = PiCoinContract::mint(env.clone(), to, amount, source);
-    assert!(matches!(result, Err(crate::PiCoinError::InsufficientCollateral)));
-    println!("Hyper-tech error: Mint blocked by collateral check, ultimate security enforced");
+    client.mint(&valid_holder, &1_000_000, &source);
+
+    // Verify ecosystem entry for valid holder, then for one with no provenance
+    assert!(client.verify_ecosystem_entry(&valid_holder));
+    assert!(!client.verify_ecosystem_entry(&invalid_holder));
+    println!("Hyper-tech ecosystem verify: Valid {:?} source approved, invalid rejected - Global recognition exclusive", source);
+}
+
+// Version 1 of the token, built from pi_coin/fixtures/pi_coin_v1
+mod pi_coin_v1 {
+    soroban_sdk::contractimport!(file = "pi_coin/fixtures/pi_coin_v1/target/wasm32v1-none/release/pi_coin_v1.wasm");
+}
+
+// The current token wasm, which version 1 upgrades to
+mod pi_coin_current {
+    soroban_sdk::contractimport!(file = "target/wasm32v1-none/release/pi_coin.wasm");
 }
 
 #[test]
-fn test_global_payment_simulation() {
+fn test_migrate_v1_storage_survives_upgrade() {
+    use crate::PiCoinContractClient;
+
     let env = Env::default();
     env.mock_all_auths();
+    // The token wasm is over the 128 KiB mainnet code limit and the default test budget
+    env.cost_estimate().disable_resource_limits();
+    env.cost_estimate().budget().reset_unlimited();
 
-    let admin = Address::random(&env);
-    let to = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
+    // Deployed and used as version 1
+    let contract_id = env.register(pi_coin_v1::WASM, ());
+    let v1 = pi_coin_v1::Client::new(&env, &contract_id);
+    let governance = Address::generate(&env);
+    v1.initialize(&Address::generate(&env), &Address::generate(&env), &Address::generate(&env), &governance);
+    let holder = Address::generate(&env);
+    v1.mint(&holder, &1_000, &pi_coin_v1::PiCoinSource::Mining);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    // Governance swaps in the current wasm; the state keeps the v1 layout until migrated
+    v1.upgrade(&env.deployer().upload_contract_wasm(pi_coin_current::WASM));
+    let client = PiCoinContractClient::new(&env, &contract_id);
+    assert_eq!(client.version(), 1);
 
-    // Mint and simulate global payment with valid source
-    let amount = 10_000_000;
-    let source = PiCoinSource::P2P;
-    PiCoinContract::mint(env.clone(), to, amount, source).unwrap();
+    // A wrong starting version is refused, the right one migrates to the current layout
+    assert!(client.try_migrate(&2).is_err());
+    assert_eq!(client.migrate(&1), crate::CONTRACT_VERSION);
+    assert_eq!(client.version(), crate::CONTRACT_VERSION);
 
-    // Check global recognition log
-    let logs = env.logger().all();
-    assert!(logs.iter().any(|log| log.contains("global payment")));
-    assert!(logs.iter().any(|log| log.contains("from valid source")));
-    println!("Live functional: PI from {} source recognized as worldwide payment tool, DEX-ready for global adoption", source);
+    env.as_contract(&contract_id, || {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        assert_eq!(data.peg_value, 314_159_000_000);
+        assert_eq!(data.governance_address, governance);
+        assert!(crate::provenance::source_of(&env, &holder) == PiCoinSource::Mining);
+        assert_eq!(crate::fees::rate(&env), 0);
+    });
+    assert!(client.verify_ecosystem_entry(&holder));
+    assert_eq!(client.balance(&holder), 0);
+
+    // Running the hook again is a no-op
+    assert_eq!(client.post_upgrade(), crate::CONTRACT_VERSION);
 }
 
 #[test]
-fn test_mint_invalid_source_rejected() {
+fn test_migrate_v2_balances_move_to_persistent_entries() {
+    use crate::migration::{testutils, PiCoinDataV2};
+    use soroban_sdk::Map;
+
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    // v2 kept every balance in one map inside the instance entry
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let mut balances = Map::new(&env);
+    balances.set(alice.clone(), 700);
+    balances.set(bob.clone(), 300);
+    let legacy = PiCoinDataV2 {
+        symbol: Symbol::new(&env, "PI"),
+        total_supply: 100_000_000_000,
+        peg_value: 314_159_000_000,
+        collateral_asset: Address::generate(&env),
+        oracle_address: Address::generate(&env),
+        governance_address: Address::generate(&env),
+        anti_fraud_hash: BytesN::from_array(&env, &[7; 32]),
+        provenance: Map::new(&env),
+        quantum_provenance_hash: BytesN::from_array(&env, &[9; 32]),
+        fee_bps: 25,
+        balances,
+    };
+    testutils::install_v2(&env, &client.address, &legacy);
+
+    assert_eq!(client.post_upgrade(), crate::CONTRACT_VERSION);
+    assert_eq!(client.balance(&alice), 700);
+    assert_eq!(client.balance(&bob), 300);
+    assert_eq!(client.fee_config().0, 25);
+}
+
+#[test]
+fn test_migrate_v3_sources_move_out_of_instance() {
+    use crate::migration::{testutils, PiCoinDataV3};
+    use soroban_sdk::Map;
+
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    // v3 kept every holder's source in one map inside the instance entry, next to the fee
+    let holder = Address::generate(&env);
+    let mut provenance = Map::new(&env);
+    provenance.set(holder.clone(), PiCoinSource::P2P);
+    let legacy = PiCoinDataV3 {
+        symbol: Symbol::new(&env, "PI"),
+        total_supply: 100_000_000_000,
+        peg_value: 314_159_000_000,
+        collateral_asset: Address::generate(&env),
+        oracle_address: Address::generate(&env),
+        governance_address: Address::generate(&env),
+        anti_fraud_hash: BytesN::from_array(&env, &[7; 32]),
+        provenance,
+        quantum_provenance_hash: BytesN::from_array(&env, &[9; 32]),
+        fee_bps: 40,
+    };
+    testutils::install_v3(&env, &client.address, &legacy);
+
+    assert_eq!(client.migrate(&3), crate::CONTRACT_VERSION);
+    assert!(client.verify_ecosystem_entry(&holder));
+    assert!(!client.verify_ecosystem_entry(&Address::generate(&env)));
+    assert_eq!(client.fee_config().0, 40);
+}
+
+#[test]
+fn test_constructor_initializes_at_deploy() {
+    use crate::PiCoinContractClient;
+
+    let env = Env::default();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(
+        PiCoinContract,
+        (Address::generate(&env), Address::generate(&env), Address::generate(&env), governance.clone()),
+    );
+    let client = PiCoinContractClient::new(&env, &contract_id);
+
+    // State exists as soon as the contract does; there is no initialize left to front-run
+    assert!(client.is_initialized());
+    assert_eq!(client.version(), crate::CONTRACT_VERSION);
+    env.as_contract(&contract_id, || {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        assert_eq!(data.governance_address, governance);
+    });
+}
+
+#[test]
+fn test_transfer_fee_goes_to_collector_unless_exempt() {
     let env = Env::default();
     env.mock_all_auths();
+    let (client, _) = setup(&env);
 
-    let admin = Address::random(&env);
-    let to = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
+    let holder = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let pool = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    client.mint(&holder, &1_000_000, &PiCoinSource::Mining);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    let amount = 10_000i128;
 
-    // Attempt mint with invalid source (should be rejected - no ecosystem entry)
-    let amount = 1_000_000;
-    let invalid_source = PiCoinSource::Invalid;
-    let result = PiCoinContract::mint(env.clone(), to, amount, invalid_source);
-    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
-    println!("Hyper-tech rejection: Mint from invalid source blocked - No access to $314,159 peg or Pi Ecosystem");
+    assert_eq!(client.try_set_fee(&1_001), Err(Ok(crate::PiCoinError::InvalidFee)));
+    client.set_fee(&100); // 1%
+    client.set_fee_collector(&treasury);
+    client.set_fee_exempt(&pool, &true);
+    assert_eq!(client.fee_config(), (100, Some(treasury.clone())));
+
+    client.transfer(&holder, &bob, &amount);
+    assert_eq!(client.balance(&bob), 9_900);
+    assert_eq!(client.balance(&treasury), 100);
+
+    // Exempt pools are not charged again
+    client.transfer(&holder, &pool, &amount);
+    assert_eq!(client.balance(&pool), amount);
+    assert_eq!(client.balance(&treasury), 100);
+    assert_eq!(client.balance(&holder), 1_000_000 - 2 * amount);
 }
 
 #[test]
-fn test_transfer_invalid_provenance() {
+fn test_peg_bands_raise_fees_and_pause_minting() {
+    use crate::{peg_band, PegBands, PegMode, PiCoinError};
+
     let env = Env::default();
     env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let holder = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    client.mint(&holder, &1_000_000, &PiCoinSource::Mining);
+    assert_eq!(client.peg_band_status().mode, PegMode::Healthy);
 
-    let admin = Address::random(&env);
-    let from = Address::random(&env); // No provenance set
-    let to = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
+    let amount = 10_000i128;
+    client.set_fee(&10);
+    client.set_fee_collector(&treasury);
+    let bands = PegBands { soft_bps: 50, hard_bps: 300, soft_fee_bps: 100, psm: Some(admin.clone()) };
+    assert_eq!(client.try_set_peg_bands(&PegBands { soft_bps: 300, ..bands.clone() }), Err(Ok(PiCoinError::InvalidPegBands)));
+    assert_eq!(client.try_set_peg_bands(&PegBands { soft_fee_bps: 1_001, ..bands.clone() }), Err(Ok(PiCoinError::InvalidPegBands)));
+    client.set_peg_bands(&bands);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    // 1% below the peg: soft band, transfers pay the 1% floor instead of 0.1%
+    let peg = client.peg_value();
+    let soft = env.as_contract(&client.address, || peg_band::refresh(&env, peg * 99 / 100, peg)).unwrap();
+    assert_eq!((soft.mode, soft.deviation_bps), (PegMode::Soft, 100));
+    assert_eq!(client.peg_band_status(), soft);
+    client.transfer(&holder, &bob, &amount);
+    assert_eq!(client.balance(&treasury), 100);
 
-    // Setup ZKP base
-    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[42, 0]));
-    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    // 5% below: hard band, only the PSM may mint
+    let hard = env.as_contract(&client.address, || peg_band::refresh(&env, peg * 95 / 100, peg)).unwrap();
+    assert_eq!(hard.mode, PegMode::Hard);
+    env.as_contract(&client.address, || {
+        assert!(matches!(peg_band::check_mint(&env, hard.mode, &Address::generate(&env)), Err(PiCoinError::PegDeviation)));
+        assert!(peg_band::check_mint(&env, hard.mode, &admin).is_ok());
+    });
 
-    // Attempt transfer without valid provenance (should fail)
-    let amount = 500_000;
-    let result = PiCoinContract::transfer(env.clone(), from, to, amount);
-    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
-    println!("Ultimate provenance check: Transfer blocked for invalid source - Ecosystem protection enforced");
+    // Reading the oracle again, back at the peg, restores the base fee
+    assert!(client.verify_peg(&holder));
+    assert_eq!(client.peg_band_status().mode, PegMode::Healthy);
+    client.transfer(&holder, &bob, &amount);
+    assert_eq!(client.balance(&treasury), 110);
+
+    // A new peg must be positive and keep the oracle price inside the hard band
+    assert_eq!(client.try_set_peg(&0), Err(Ok(PiCoinError::InvalidAmount)));
+    assert_eq!(client.try_set_peg(&(peg * 96 / 100)), Err(Ok(PiCoinError::PegDeviation)));
+    client.set_peg(&(peg * 99 / 100));
+    assert_eq!(client.peg_value(), peg * 99 / 100);
 }
 
 #[test]
-fn test_verify_ecosystem_entry() {
+fn test_deadline_and_min_received_guard_stale_transactions() {
+    use crate::PiCoinError;
+
     let env = Env::default();
     env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let amount = 10_000i128;
 
-    let admin = Address::random(&env);
-    let valid_holder = Address::random(&env);
-    let invalid_holder = Address::random(&env);
-    let collateral = Address::random(&env);
-    let oracle = Address::random(&env);
-    let governance = Address::random(&env);
+    // The deadline ledger itself is still valid; the next one is not
+    client.mint_with_deadline(&holder, &1_000_000, &PiCoinSource::Mining, &100);
+    assert_eq!(
+        client.try_mint_with_deadline(&holder, &1_000_000, &PiCoinSource::Mining, &99),
+        Err(Ok(PiCoinError::DeadlineExpired))
+    );
+    assert_eq!(client.balance(&holder), 1_000_000);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    // Simulated at a 1% fee, the sender accepts 9_900 received
+    client.set_fee(&100);
+    client.set_fee_collector(&Address::generate(&env));
+    assert_eq!(client.transfer_with_deadline(&holder, &bob, &amount, &9_900, &150), 9_900);
 
-    // Mint for valid holder
-    let source = PiCoinSource::Rewards;
-    PiCoinContract::mint(env.clone(), valid_holder.clone(), 1_000_000, source).unwrap();
+    // Governance raises the fee before the next one lands: it reverts instead
+    client.set_fee(&200);
+    assert_eq!(
+        client.try_transfer_with_deadline(&holder, &bob, &amount, &9_900, &150),
+        Err(Ok(PiCoinError::SlippageExceeded))
+    );
+    env.ledger().set_sequence_number(151);
+    assert_eq!(
+        client.try_transfer_with_deadline(&holder, &bob, &amount, &0, &150),
+        Err(Ok(PiCoinError::DeadlineExpired))
+    );
+    assert_eq!((client.balance(&holder), client.balance(&bob)), (1_000_000 - amount, 9_900));
+}
+
+#[test]
+fn test_events_follow_versioned_schema() {
+    use soroban_sdk::{testutils::Events, vec, IntoVal};
+    use crate::EVENT_SCHEMA_VERSION;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    client.mint(&holder, &1_000_000, &PiCoinSource::Mining);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "mint"), holder.clone(), EVENT_SCHEMA_VERSION).into_val(&env),
+                (1_000_000i128, PiCoinSource::Mining).into_val(&env),
+            ),
+        ]
+    );
+
+    // The transfer event reports what the recipient received; the fee has its own event
+    client.set_fee(&100);
+    client.set_fee_collector(&treasury);
+    client.transfer(&holder, &bob, &10_000);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "fee"), holder.clone(), treasury.clone(), EVENT_SCHEMA_VERSION).into_val(&env),
+                100i128.into_val(&env),
+            ),
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "transfer"), holder.clone(), bob.clone(), EVENT_SCHEMA_VERSION).into_val(&env),
+                9_900i128.into_val(&env),
+            ),
+        ]
+    );
+
+    client.burn(&bob, &900);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "burn"), bob.clone(), EVENT_SCHEMA_VERSION).into_val(&env),
+                900i128.into_val(&env),
+            ),
+        ]
+    );
+
+    client.set_peg(&314_160_000_000);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "peg_update"), EVENT_SCHEMA_VERSION).into_val(&env),
+                (314_159_000_000i128, 314_160_000_000i128).into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_provenance_lots_move_fifo() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let holder = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&holder, &600, &PiCoinSource::Mining);
+    client.mint(&holder, &400, &PiCoinSource::Rewards);
+
+    let amount = 700i128;
+
+    // Oldest lot leaves first: all 600 Mining PI, then 100 of the Rewards lot
+    client.transfer(&holder, &bob, &amount);
+    let received = client.provenance_of(&bob);
+    assert_eq!(received.len(), 2);
+    assert!(received.get(0).unwrap().source == PiCoinSource::Mining);
+    assert_eq!(received.get(0).unwrap().amount, 600);
+    assert!(received.get(1).unwrap().source == PiCoinSource::Rewards);
+    assert_eq!(received.get(1).unwrap().amount, 100);
+
+    let kept = client.provenance_of(&holder);
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept.get(0).unwrap().id, received.get(1).unwrap().id);
+    assert_eq!(kept.get(0).unwrap().amount, 300);
+
+    // Burning consumes lots too
+    client.burn(&holder, &300);
+    assert_eq!(client.provenance_of(&holder).len(), 0);
+}
+
+// Stand-in for PiCoinLotReceipts: remembers what each lot's receipt was issued for
+#[soroban_sdk::contract]
+struct MockLotReceipts;
+
+#[soroban_sdk::contractimpl]
+impl MockLotReceipts {
+    pub fn issue(env: Env, lot_id: u64, owner: Address, source: PiCoinSource, amount: i128, _minted_at: u64) {
+        env.storage().instance().set(&lot_id, &(owner, source, amount));
+    }
+
+    pub fn issued(env: Env, lot_id: u64) -> Option<(Address, PiCoinSource, i128)> {
+        env.storage().instance().get(&lot_id)
+    }
+}
+
+#[test]
+fn test_mint_issues_lot_receipt() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let receipts = MockLotReceiptsClient::new(&env, &env.register(MockLotReceipts, ()));
+    let holder = Address::generate(&env);
+
+    // Lots minted before a receipts contract is set get no receipt
+    client.mint(&holder, &600, &PiCoinSource::Mining);
+    client.set_lot_receipts(&receipts.address);
+    assert_eq!(client.lot_receipts(), Some(receipts.address.clone()));
+    client.mint(&holder, &400, &PiCoinSource::Rewards);
+
+    let lots = client.provenance_of(&holder);
+    assert!(receipts.issued(&lots.get(0).unwrap().id).is_none());
+    let (owner, source, amount) = receipts.issued(&lots.get(1).unwrap().id).unwrap();
+    assert_eq!((owner, amount), (holder, 400));
+    assert!(source == PiCoinSource::Rewards);
+}
+
+#[test]
+fn test_attest_provenance_with_merkle_proof() {
+    use crate::{merkle, ParticipantLeaf};
+    use soroban_sdk::{vec, xdr::ToXdr};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    // Two-participant tree: the proof for `miner` is the other leaf's hash
+    let miner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let leaf = ParticipantLeaf { participant: miner.clone(), source: PiCoinSource::Mining };
+    let other_leaf = ParticipantLeaf { participant: other.clone(), source: PiCoinSource::Rewards };
+    let proof = vec![&env, BytesN::from(env.crypto().sha256(&other_leaf.to_xdr(&env)))];
+    let root = merkle::root_from(&env, &leaf.clone().to_xdr(&env), &proof);
+
+    // Nothing to prove against before governance publishes a root
+    assert!(client.try_attest_provenance(&miner, &proof, &leaf).is_err());
+    assert_eq!(client.set_attestation_root(&root), 1);
+
+    // A leaf for someone else, or a wrong proof, is rejected
+    assert!(client.try_attest_provenance(&other, &proof, &leaf).is_err());
+    assert!(client.try_attest_provenance(&miner, &vec![&env], &leaf).is_err());
+
+    client.attest_provenance(&miner, &proof, &leaf);
+    let attestation = client.attestation_of(&miner).unwrap();
+    assert!(attestation.source == PiCoinSource::Mining);
+    assert_eq!(attestation.root_epoch, 1);
+    assert!(client.verify_ecosystem_entry(&miner));
+
+    // Rotating the root retires attestations made under the old one
+    assert_eq!(client.set_attestation_root(&BytesN::from_array(&env, &[5; 32])), 2);
+    assert!(client.attestation_of(&miner).is_none());
+}
+
+#[test]
+fn test_provenance_hash_is_deterministic() {
+    use soroban_sdk::{xdr::ToXdr, String};
+
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    // Same address, same hash: across invocations, handles and clones
+    let hash = client.provenance_hash(&holder);
+    assert_eq!(client.provenance_hash(&holder.clone()), hash);
+    assert_eq!(client.provenance_hash(&Address::from_string(&holder.to_string())), hash);
+    assert_ne!(client.provenance_hash(&other), hash);
+    // It is sha256 of the address XDR, so it can be recomputed off-chain
+    assert_eq!(hash, BytesN::from(env.crypto().sha256(&holder.clone().to_xdr(&env))));
+
+    // A fresh execution (new host, new object handles) reproduces it
+    let mut strkey = [0u8; 56];
+    holder.to_string().copy_into_slice(&mut strkey);
+    let env2 = Env::default();
+    let (client2, _) = setup(&env2);
+    let holder2 = Address::from_string(&String::from_bytes(&env2, &strkey));
+    assert_eq!(client2.provenance_hash(&holder2).to_array(), hash.to_array());
+}
+
+#[test]
+fn test_transfer_from_spends_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.mint(&owner, &1_000, &PiCoinSource::P2P);
+
+    client.approve(&owner, &spender, &500, &100);
+    assert_eq!(client.allowance(&owner, &spender), 500);
+    client.transfer_from(&spender, &owner, &to, &300);
+    assert_eq!(client.balance(&to), 300);
+    assert_eq!(client.allowance(&owner, &spender), 200);
+
+    // Cannot spend past the remaining allowance
+    assert!(client.try_transfer_from(&spender, &owner, &to, &300).is_err());
+}
+
+#[test]
+fn test_flash_settle_requires_repayment_plus_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let lender = Address::generate(&env);
+    let receiver = Address::generate(&env);
+    let collector = Address::generate(&env);
+
+    // Only the registered lender may flash-credit
+    assert!(client.try_flash_credit(&receiver, &1_000).is_err());
+    client.set_flash_lender(&lender);
+    client.set_fee_collector(&collector);
+
+    // Credited but not approved for repayment: settlement fails
+    client.flash_credit(&receiver, &1_000);
+    assert_eq!(client.balance(&receiver), 1_000);
+    assert!(client.try_flash_settle(&receiver, &1_000, &1).is_err());
+
+    // Approved (and holding) principal plus fee: the principal is destroyed, the fee collected
+    client.mint(&receiver, &1, &PiCoinSource::Rewards);
+    client.approve(&receiver, &lender, &1_001, &100);
+    client.flash_settle(&receiver, &1_000, &1);
+    assert_eq!(client.balance(&receiver), 0);
+    assert_eq!(client.balance(&collector), 1);
+}
+
+#[test]
+fn test_flash_credited_pi_moves_before_settling() {
+    use crate::{PiCoinError, SourceInfo};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let lender = Address::generate(&env);
+    let receiver = Address::generate(&env);
+    let pool = Address::generate(&env);
+    client.set_flash_lender(&lender);
+
+    // A receiver that never held PI is tagged FlashMint, so it can trade the credit
+    client.flash_credit(&receiver, &1_000);
+    client.transfer(&receiver, &pool, &400);
+    client.transfer(&pool, &receiver, &400);
+    assert!(client.verify_ecosystem_entry(&pool));
+    client.approve(&receiver, &lender, &1_000, &100);
+    client.flash_settle(&receiver, &1_000, &0);
+    assert_eq!(client.balance(&receiver), 0);
+
+    // The tag is for flash credits only: nothing mints, registers or attests under it
+    assert_eq!(client.try_mint(&receiver, &1, &PiCoinSource::FlashMint), Err(Ok(PiCoinError::InvalidSource)));
+    let info = SourceInfo { valid: true, risk_score: 0 };
+    assert_eq!(client.try_register_source(&PiCoinSource::FlashMint, &info, &None), Err(Ok(PiCoinError::InvalidSource)));
+    assert!(!client.is_source_valid(&PiCoinSource::FlashMint));
+}
+
+// Flash lender reduced to what PiCoinFlashLender does: credit, receiver callback, settle
+#[soroban_sdk::contract]
+struct TestFlashLender;
+
+#[soroban_sdk::contractimpl]
+impl TestFlashLender {
+    pub fn flash_mint(env: Env, token: Address, receiver: Address, amount: i128) {
+        let pi = crate::PiCoinContractClient::new(&env, &token);
+        pi.flash_credit(&receiver, &amount);
+        ReentrantReceiverClient::new(&env, &receiver).on_flash_mint(&env.current_contract_address(), &token, &amount);
+        pi.flash_settle(&receiver, &amount, &0);
+    }
+}
+
+// Malicious flash receiver: tries every way back in it can reach before repaying, and
+// records which attempts got through
+#[soroban_sdk::contract]
+struct ReentrantReceiver;
+
+#[soroban_sdk::contractimpl]
+impl ReentrantReceiver {
+    pub fn on_flash_mint(env: Env, lender: Address, token: Address, amount: i128) {
+        use crate::PiCoinError;
+        let pi = crate::PiCoinContractClient::new(&env, &token);
+        let me = env.current_contract_address();
+        let attempts = (
+            // A nested loan through the lender, which is still on the call stack
+            TestFlashLenderClient::new(&env, &lender).try_flash_mint(&token, &me, &amount).is_ok(),
+            // A nested loan, a lender swap or a rebase straight on the token
+            !matches!(pi.try_flash_credit(&me, &amount), Err(Ok(PiCoinError::Reentrant))),
+            !matches!(pi.try_set_flash_lender(&me), Err(Ok(PiCoinError::Reentrant))),
+            !matches!(pi.try_rebase(), Err(Ok(PiCoinError::Reentrant))),
+        );
+        env.storage().instance().set(&Symbol::new(&env, "attempts"), &attempts);
+        // Using the PI is fine: approve the repayment
+        pi.approve(&me, &lender, &amount, &(env.ledger().sequence() + 1));
+    }
+
+    pub fn attempts(env: Env) -> (bool, bool, bool, bool) {
+        env.storage().instance().get(&Symbol::new(&env, "attempts")).unwrap()
+    }
+}
+
+#[test]
+fn test_flash_mint_receiver_cannot_reenter() {
+    use crate::PiCoinError;
+
+    let env = Env::default();
+    // Even with every signature available to the receiver, the guard refuses it
+    env.mock_all_auths_allowing_non_root_auth();
+    let (client, _) = setup(&env);
+    let lender = env.register(TestFlashLender, ());
+    let receiver = ReentrantReceiverClient::new(&env, &env.register(ReentrantReceiver, ()));
+    client.set_flash_lender(&lender);
+
+    // Every reentry attempt failed, and the loan itself still went through
+    TestFlashLenderClient::new(&env, &lender).flash_mint(&client.address, &receiver.address, &1_000);
+    assert_eq!(receiver.attempts(), (false, false, false, false));
+    assert_eq!(client.balance(&receiver.address), 0);
+
+    // The window closed with the settlement
+    assert_eq!(client.try_rebase(), Err(Ok(PiCoinError::RebaseDisabled)));
+    client.set_flash_lender(&Address::generate(&env));
+}
+
+// KYC registry that tries to move PI through the token while answering a compliance check
+#[soroban_sdk::contract]
+struct ReentrantKycRegistry;
+
+#[soroban_sdk::contractimpl]
+impl ReentrantKycRegistry {
+    pub fn __constructor(env: Env, token: Address, thief: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "target"), &(token, thief));
+    }
+
+    pub fn is_verified(env: Env, subject: Address) -> bool {
+        let (token, thief): (Address, Address) = env.storage().instance().get(&Symbol::new(&env, "target")).unwrap();
+        if subject != thief {
+            let reentered = crate::PiCoinContractClient::new(&env, &token).try_transfer(&subject, &thief, &100).is_ok();
+            env.storage().instance().set(&Symbol::new(&env, "reentered"), &reentered);
+        }
+        true
+    }
+
+    pub fn reentered(env: Env) -> bool {
+        env.storage().instance().get(&Symbol::new(&env, "reentered")).unwrap_or(false)
+    }
+}
+
+#[test]
+fn test_transfer_callbacks_cannot_reenter() {
+    use crate::ComplianceMode;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let thief = Address::generate(&env);
+    let registry = ReentrantKycRegistryClient::new(&env, &env.register(ReentrantKycRegistry, (&client.address, &thief)));
+    client.set_compliance_mode(&ComplianceMode::Allowlist);
+    client.set_kyc_registry(&registry.address);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+
+    // The registry is called mid-transfer; its transfer back into the token is refused
+    client.transfer(&alice, &bob, &100);
+    assert!(!registry.reentered());
+    assert_eq!((client.balance(&alice), client.balance(&bob), client.balance(&thief)), (900, 100, 0));
+}
 
-    // Verify ecosystem entry for valid holder
-    let valid_result = PiCoinContract::verify_ecosystem_entry(env.clone(), valid_holder);
-    assert!(valid_result.is_ok() && valid_result.unwrap());
+#[test]
+fn test_emergency_shutdown_redeems_pro_rata() {
+    use crate::{PiCoinContractClient, PiCoinError};
+    use soroban_sdk::{testutils::Ledger, token};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let usdc_client = token::TokenClient::new(&env, &usdc);
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinContract, (Address::generate(&env), &usdc, Address::generate(&env), &governance));
+    let client = PiCoinContractClient::new(&env, &contract_id);
+    let guardian = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &600, &PiCoinSource::Mining);
+    client.mint(&bob, &400, &PiCoinSource::P2P);
+    assert_eq!(client.circulating_supply(), 1_000);
+    assert_eq!(client.try_redeem_settlement(&alice, &100), Err(Ok(PiCoinError::NotShutdown)));
+
+    // Only half the supply is backed: 500 USDC units against 1_000 PI
+    token::StellarAssetClient::new(&env, &usdc).mint(&contract_id, &500);
+    client.set_guardian(&guardian, &3_600);
+    let price = client.record_oracle_price();
+
+    // The guardian must wait out the oracle timeout; strangers never qualify
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    assert_eq!(client.try_emergency_shutdown(&guardian), Err(Ok(PiCoinError::Unauthorized)));
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    assert_eq!(client.try_emergency_shutdown(&alice), Err(Ok(PiCoinError::Unauthorized)));
+    let settlement = client.emergency_shutdown(&guardian);
+    assert_eq!((settlement.price, settlement.collateral, settlement.supply), (price, 500, 1_000));
+    assert!(settlement.by_guardian);
+    assert_eq!(client.try_emergency_shutdown(&governance), Err(Ok(PiCoinError::Shutdown)));
+
+    // Minting and transfers are frozen
+    assert_eq!(client.try_transfer(&alice, &bob, &100), Err(Ok(PiCoinError::Shutdown)));
+    assert_eq!(client.try_mint(&alice, &1, &PiCoinSource::Mining), Err(Ok(PiCoinError::Shutdown)));
+
+    // Every PI redeems for half a USDC unit, in any order and any number of steps
+    assert_eq!(client.redeem_settlement(&alice, &200), 100);
+    assert_eq!(client.redeem_settlement(&bob, &400), 200);
+    assert_eq!(client.redeem_settlement(&alice, &400), 200);
+    assert_eq!(client.redeemed_of(&alice), (600, 300));
+    assert_eq!((usdc_client.balance(&alice), usdc_client.balance(&bob), usdc_client.balance(&contract_id)), (300, 200, 0));
+    assert_eq!(client.try_redeem_settlement(&bob, &1), Err(Ok(PiCoinError::InsufficientBalance)));
+    assert_eq!(client.try_redeem_settlement(&bob, &0), Err(Ok(PiCoinError::InvalidAmount)));
+    let settlement = client.settlement().unwrap();
+    assert_eq!((settlement.redeemed, settlement.paid), (1_000, 500));
+    assert_eq!(client.circulating_supply(), 0);
+}
+
+#[test]
+fn test_pause_breaker_freezes_until_governance_unpauses() {
+    use crate::{PiCoinContractClient, PiCoinError};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinContract, (Address::generate(&env), Address::generate(&env), Address::generate(&env), &governance));
+    let client = PiCoinContractClient::new(&env, &contract_id);
+    let sentinel = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+
+    // Only governance and its sentinel may pause
+    let reason = Symbol::new(&env, "supply");
+    assert_eq!(client.try_pause(&sentinel, &reason), Err(Ok(PiCoinError::Unauthorized)));
+    client.set_pause_sentinel(&Some(sentinel.clone()));
+    client.pause(&sentinel, &reason);
+    client.pause(&governance, &Symbol::new(&env, "manual"));
+    let trip = client.pause_trip().unwrap();
+    assert_eq!((trip.by, trip.reason), (sentinel.clone(), reason));
+
+    // Minting and transfers are frozen; burns are not
+    assert_eq!(client.try_transfer(&alice, &bob, &100), Err(Ok(PiCoinError::Paused)));
+    assert_eq!(client.try_mint(&alice, &1, &PiCoinSource::Mining), Err(Ok(PiCoinError::Paused)));
+    client.burn(&alice, &100);
+
+    client.unpause();
+    assert!(client.pause_trip().is_none());
+    client.transfer(&alice, &bob, &100);
+    assert_eq!(client.balance(&bob), 100);
+}
+
+#[test]
+fn test_rate_limits_cap_mints_and_outflow() {
+    use crate::RateLimits;
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
+    let bridge = Address::generate(&env);
+    let to = Address::generate(&env);
+
+    client.set_rate_limits(&RateLimits { daily_mint_cap: Some(1_000), outflow_cap: Some(400), epoch_length: 3_600 });
+    client.mint(&holder, &600, &PiCoinSource::Mining);
+    client.mint(&bridge, &400, &PiCoinSource::Mining);
+    assert!(client.try_mint(&holder, &1, &PiCoinSource::Mining).is_err());
+
+    // 300 + 300 breaks the hourly outflow cap; the bypassed bridge is not capped
+    client.transfer(&holder, &to, &300);
+    assert_eq!(client.outflow_remaining(&holder), Some(100));
+    assert!(client.try_transfer(&holder, &to, &300).is_err());
+    client.set_rate_limit_bypass(&bridge, &true);
+    client.transfer(&bridge, &to, &300);
+    assert_eq!(client.outflow_remaining(&bridge), None);
+
+    // Windows reset by ledger time: a new epoch for outflow, a new day for minting
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    client.transfer(&holder, &to, &300);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    client.mint(&holder, &1_000, &PiCoinSource::Mining);
+}
+
+#[test]
+fn test_source_quotas_roll_over_per_epoch() {
+    use crate::{PiCoinError, SourceQuota};
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
+
+    let quota = SourceQuota { cap: 1_000, epoch_length: 3_600 };
+    assert_eq!(client.try_set_source_quota(&PiCoinSource::Mining, &Some(SourceQuota { cap: 1_000, epoch_length: 0 })), Err(Ok(PiCoinError::InvalidRateLimit)));
+    client.set_source_quota(&PiCoinSource::Mining, &Some(quota.clone()));
+    assert_eq!(client.source_quota(&PiCoinSource::Mining), Some(quota));
+
+    // Mining is capped; other sources are not
+    client.mint(&holder, &700, &PiCoinSource::Mining);
+    assert_eq!(client.remaining_quota(&PiCoinSource::Mining), Some(300));
+    assert_eq!(client.try_mint(&holder, &301, &PiCoinSource::Mining), Err(Ok(PiCoinError::MintQuotaExceeded)));
+    client.mint(&holder, &5_000, &PiCoinSource::Rewards);
+    assert_eq!(client.remaining_quota(&PiCoinSource::Rewards), None);
+
+    // The quota refills once its epoch has passed, and lifting it removes the cap
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    assert_eq!(client.remaining_quota(&PiCoinSource::Mining), Some(1_000));
+    client.mint(&holder, &1_000, &PiCoinSource::Mining);
+    client.set_source_quota(&PiCoinSource::Mining, &None);
+    client.mint(&holder, &1_000, &PiCoinSource::Mining);
+}
+
+#[test]
+fn test_source_registry_adds_and_retires_channels() {
+    use crate::{PiCoinError, SourceInfo, SourceQuota};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let holder = Address::generate(&env);
+    let staking = PiCoinSource::Registered(Symbol::new(&env, "staking"));
+
+    // A new channel mints once governance registers it, no upgrade needed
+    assert_eq!(client.try_mint(&holder, &100, &staking), Err(Ok(PiCoinError::InvalidSource)));
+    let info = SourceInfo { valid: true, risk_score: 40 };
+    client.register_source(&staking, &info, &Some(SourceQuota { cap: 500, epoch_length: 3_600 }));
+    assert_eq!(client.source_info(&staking), Some(info));
+    client.mint(&holder, &100, &staking);
+    assert!(client.provenance_of(&holder).get(0).unwrap().source == staking);
+    assert_eq!(client.remaining_quota(&staking), Some(400));
+
+    // Built-in sources can be retired the same way; Invalid cannot be registered
+    assert!(client.is_source_valid(&PiCoinSource::Mining));
+    client.register_source(&PiCoinSource::Mining, &SourceInfo { valid: false, risk_score: 100 }, &None);
+    assert_eq!(client.try_mint(&holder, &100, &PiCoinSource::Mining), Err(Ok(PiCoinError::InvalidSource)));
+    assert_eq!(
+        client.try_register_source(&PiCoinSource::Invalid, &SourceInfo { valid: true, risk_score: 0 }, &None),
+        Err(Ok(PiCoinError::InvalidSource))
+    );
+}
+
+#[test]
+fn test_compliance_modes_gate_transfers() {
+    use crate::{ComplianceList, ComplianceMode};
+    use soroban_sdk::vec;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let mallory = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::P2P);
+
+    // Blocklist mode: only listed accounts are rejected, as sender or recipient
+    client.add_to_list(&ComplianceList::Blocklist, &vec![&env, mallory.clone()]);
+    client.set_compliance_mode(&ComplianceMode::Blocklist);
+    client.transfer(&alice, &bob, &100);
+    assert!(client.try_transfer(&alice, &mallory, &100).is_err());
+
+    // Allowlist mode: both sides must be listed
+    client.set_compliance_mode(&ComplianceMode::Allowlist);
+    client.add_to_list(&ComplianceList::Allowlist, &vec![&env, alice.clone(), bob.clone()]);
+    client.transfer(&alice, &bob, &100);
+    client.remove_from_list(&ComplianceList::Allowlist, &vec![&env, bob.clone()]);
+    assert!(!client.is_listed(&ComplianceList::Allowlist, &bob));
+    assert!(client.try_transfer(&alice, &bob, &100).is_err());
+    assert!(client.try_mint(&bob, &1, &PiCoinSource::P2P).is_err());
+
+    client.set_compliance_mode(&ComplianceMode::Open);
+    client.transfer(&alice, &mallory, &100);
+    assert_eq!(client.balance(&mallory), 100);
+}
+
+// Stand-in for PiCoinKycRegistry: accounts are verified once marked
+#[soroban_sdk::contract]
+struct MockKycRegistry;
+
+#[soroban_sdk::contractimpl]
+impl MockKycRegistry {
+    pub fn mark(env: Env, subject: Address) {
+        env.storage().instance().set(&subject, &true);
+    }
+
+    pub fn is_verified(env: Env, subject: Address) -> bool {
+        env.storage().instance().has(&subject)
+    }
+}
+
+#[test]
+fn test_allowlist_mode_admits_kyc_verified_accounts() {
+    use crate::ComplianceMode;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let registry = MockKycRegistryClient::new(&env, &env.register(MockKycRegistry, ()));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.set_compliance_mode(&ComplianceMode::Allowlist);
+    assert!(client.try_mint(&alice, &1_000, &PiCoinSource::Mining).is_err());
+
+    client.set_kyc_registry(&registry.address);
+    registry.mark(&alice);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    assert!(client.try_transfer(&alice, &bob, &100).is_err());
+    registry.mark(&bob);
+    client.transfer(&alice, &bob, &100);
+    assert_eq!(client.balance(&bob), 100);
+}
+
+// Stand-in for PiCoinBadges: holders have a badge once marked
+#[soroban_sdk::contract]
+struct MockBadges;
+
+#[soroban_sdk::contractimpl]
+impl MockBadges {
+    pub fn mark(env: Env, holder: Address) {
+        env.storage().instance().set(&holder, &true);
+    }
+
+    pub fn has_badge(env: Env, holder: Address) -> bool {
+        env.storage().instance().has(&holder)
+    }
+}
+
+#[test]
+fn test_badge_holders_pass_ecosystem_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let badges = MockBadgesClient::new(&env, &env.register(MockBadges, ()));
+    let alice = Address::generate(&env);
+
+    // Verification no longer depends on having been minted PI
+    assert!(!client.verify_ecosystem_entry(&alice));
+    client.set_badge_registry(&badges.address);
+    assert!(!client.verify_ecosystem_entry(&alice));
+    badges.mark(&alice);
+    assert!(client.verify_ecosystem_entry(&alice));
+    assert_eq!(client.balance(&alice), 0);
+}
+
+#[test]
+fn test_metadata_defaults_and_governance_updates() {
+    use crate::{PiCoinContractClient, PiCoinError, TokenMetadata};
+    use soroban_sdk::String;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let governance = Address::generate(&env);
+    let contract_id = env.register(
+        PiCoinContract,
+        (Address::generate(&env), Address::generate(&env), Address::generate(&env), &governance),
+    );
+    let client = PiCoinContractClient::new(&env, &contract_id);
+
+    // Written at initialization
+    assert_eq!(client.name(), String::from_str(&env, "Pi Coin"));
+    assert_eq!(client.symbol(), String::from_str(&env, "PI"));
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.metadata().icon_hash, None);
+
+    let metadata = TokenMetadata {
+        name: String::from_str(&env, "Pi Network Coin"),
+        symbol: String::from_str(&env, "PICOIN"),
+        decimals: 7,
+        icon_hash: Some(BytesN::from_array(&env, &[3; 32])),
+        home_domain: String::from_str(&env, "pi.example"),
+    };
+    client.set_metadata(&metadata);
+    assert_eq!(env.auths()[0].0, governance);
+    assert_eq!(client.metadata(), metadata);
+    assert_eq!(client.config().symbol, Symbol::new(&env, "PICOIN"));
+
+    // Symbols are 1-12 ASCII letters and digits, and decimals stop at 18
+    let rejected = |metadata: TokenMetadata| matches!(client.try_set_metadata(&metadata), Err(Ok(PiCoinError::InvalidMetadata)));
+    assert!(rejected(TokenMetadata { symbol: String::from_str(&env, "PI-1"), ..metadata.clone() }));
+    assert!(rejected(TokenMetadata { symbol: String::from_str(&env, ""), ..metadata.clone() }));
+    assert!(rejected(TokenMetadata { symbol: String::from_str(&env, "PICOINPICOIN1"), ..metadata.clone() }));
+    assert!(rejected(TokenMetadata { name: String::from_str(&env, ""), ..metadata.clone() }));
+    assert!(rejected(TokenMetadata { decimals: 19, ..metadata.clone() }));
+    assert_eq!(client.symbol(), String::from_str(&env, "PICOIN"));
+}
+
+#[test]
+fn test_transfer_with_signature_relayed_once() {
+    use crate::MetaTransfer;
+    use soroban_sdk::xdr::ToXdr;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let key = ed25519_dalek::SigningKey::from_bytes(&[3; 32]);
+    let from = account_address(&env, &key);
+    let to = Address::generate(&env);
+    client.mint(&from, &1_000, &PiCoinSource::Mining);
+
+    let sign = |nonce: u64| {
+        let payload = MetaTransfer {
+            network_id: env.ledger().network_id(),
+            token: client.address.clone(),
+            from: from.clone(),
+            to: to.clone(),
+            amount: 250,
+            nonce,
+            expiration_ledger: 100,
+        };
+        sign_xdr(&env, &key, &payload.to_xdr(&env))
+    };
+
+    // No auth from `from` is involved: the signature authorizes the transfer
+    env.set_auths(&[]);
+    let signature = sign(0);
+    client.transfer_with_signature(&from, &to, &250, &0, &100, &signature);
+    assert_eq!(client.balance(&to), 250);
+    assert_eq!(client.nonce(&from), 1);
+
+    // Replays, altered amounts and skipped nonces are rejected
+    assert!(client.try_transfer_with_signature(&from, &to, &250, &0, &100, &signature).is_err());
+    assert!(client.try_transfer_with_signature(&from, &to, &251, &1, &100, &sign(1)).is_err());
+    assert!(client.try_transfer_with_signature(&from, &to, &250, &2, &100, &sign(2)).is_err());
+    client.transfer_with_signature(&from, &to, &250, &1, &100, &sign(1));
+    assert_eq!(client.balance(&to), 500);
+}
+
+#[test]
+fn test_permit_sets_allowance_from_signature() {
+    use crate::Permit;
+    use soroban_sdk::xdr::ToXdr;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let key = ed25519_dalek::SigningKey::from_bytes(&[4; 32]);
+    let owner = account_address(&env, &key);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1_000, &PiCoinSource::Rewards);
+
+    let sign = |amount: i128, nonce: u64| {
+        let permit = Permit {
+            network_id: env.ledger().network_id(),
+            token: client.address.clone(),
+            owner: owner.clone(),
+            spender: spender.clone(),
+            amount,
+            nonce,
+            deadline: 100,
+        };
+        sign_xdr(&env, &key, &permit.to_xdr(&env))
+    };
+
+    // The spender pulls PI without the owner ever submitting a transaction
+    env.set_auths(&[]);
+    let signature = sign(400, 0);
+    client.permit(&owner, &spender, &400, &100, &signature);
+    assert_eq!(client.allowance(&owner, &spender), 400);
+    assert_eq!(client.permit_nonce(&owner), 1);
+    assert!(client.try_permit(&owner, &spender, &400, &100, &signature).is_err());
+    assert!(client.try_permit(&owner, &spender, &900, &100, &sign(400, 1)).is_err());
+
+    env.mock_all_auths();
+    client.transfer_from(&spender, &owner, &spender, &400);
+    assert_eq!(client.balance(&spender), 400);
+}
+
+#[test]
+fn test_batch_transfer_and_multicall_are_all_or_nothing() {
+    use crate::Invocation;
+    use soroban_sdk::{vec, Vec};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let payer = Address::generate(&env);
+    let (alice, bob, carol) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    client.mint(&payer, &1_000, &PiCoinSource::Rewards);
+
+    client.batch_transfer(&payer, &vec![&env, (alice.clone(), 100), (bob.clone(), 356)]);
+    assert_eq!((client.balance(&alice), client.balance(&bob), client.balance(&payer)), (100, 356, 544));
+
+    // The third payment overdraws, so none of the batch is applied
+    let payroll = vec![&env, (alice.clone(), 100), (bob.clone(), 100), (carol.clone(), 612)];
+    assert!(client.try_batch_transfer(&payer, &payroll).is_err());
+    assert_eq!(client.balance(&payer), 544);
+    let mut too_many = Vec::new(&env);
+    for _ in 0..=crate::batch::MAX_BATCH {
+        too_many.push_back((alice.clone(), 0i128));
+    }
+    assert!(client.try_batch_transfer(&payer, &too_many).is_err());
+
+    // One authorization covers a transfer, an approval and a burn
+    client.multicall(&payer, &vec![&env, Invocation::Transfer(carol.clone(), 100), Invocation::Approve(alice.clone(), 300, 100), Invocation::Burn(44)]);
+    assert_eq!(env.auths().len(), 1);
+    assert_eq!((client.balance(&carol), client.allowance(&payer, &alice), client.balance(&payer)), (100, 300, 400));
+    assert!(client.try_multicall(&payer, &vec![&env, Invocation::Burn(1), Invocation::Burn(1_000)]).is_err());
+    assert_eq!(client.balance(&payer), 400);
+}
+
+#[test]
+fn test_views_need_no_auth_and_write_nothing() {
+    use crate::{ComplianceMode, PiCoinConfig, PiCoinContractClient};
+    use soroban_sdk::token;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let collateral = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let oracle = Address::generate(&env);
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinContract, (&admin, &collateral, &oracle, &governance));
+    let client = PiCoinContractClient::new(&env, &contract_id);
+    let holder = Address::generate(&env);
+    client.mint(&holder, &1_000, &PiCoinSource::Rewards);
+    token::StellarAssetClient::new(&env, &collateral).mint(&contract_id, &500);
+
+    // No signer at all: every view still answers, and none of them writes an entry
+    env.set_auths(&[]);
+    let no_writes = || assert_eq!(env.cost_estimate().resources().write_entries, 0);
+    assert_eq!(client.total_supply(), 100_000_000_000);
+    no_writes();
+    assert_eq!(client.peg_value(), 314_159_000_000);
+    no_writes();
+    // The contract's collateral balance against what circulates: 500 against 1_000 PI
+    assert_eq!(client.collateral_ratio(), 5_000);
+    no_writes();
+    assert!((client.oracle_price() - client.peg_value()).abs() <= 1_000);
+    no_writes();
+    assert_eq!(
+        client.config(),
+        PiCoinConfig {
+            symbol: Symbol::new(&env, "PI"),
+            admin,
+            collateral_asset: collateral,
+            oracle_address: oracle,
+            governance_address: governance,
+            fee_bps: 0,
+            fee_collector: None,
+            compliance_mode: ComplianceMode::Open,
+            version: crate::CONTRACT_VERSION,
+        }
+    );
+    no_writes();
+    assert!(client.provenance_of(&holder).get(0).unwrap().source == PiCoinSource::Rewards);
+    no_writes();
+    assert!(env.auths().is_empty());
+}
+
+#[test]
+fn test_rebase_scales_every_balance_through_the_index() {
+    use crate::{peg_band, PegBands, RebaseConfig, INDEX_ONE};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let at = |timestamp: u64| env.ledger().with_mut(|li| li.timestamp = timestamp);
+
+    // Off until governance turns it on
+    assert!(client.try_rebase().is_err());
+    let config = RebaseConfig { band_bps: 100, epochs: 3, epoch_length: 1_000 };
+    assert!(client.try_set_rebase_config(&Some(RebaseConfig { epochs: 0, ..config.clone() })).is_err());
+    client.set_rebase_config(&Some(config));
+
+    // The simulated oracle sits at $314,159; halving the peg puts PI 100% above it, which
+    // only a hard band that wide lets governance do
+    client.set_peg_bands(&PegBands { hard_bps: 10_000, ..peg_band::default() });
+    client.set_peg(&157_079_500_000);
+    at(1_000);
+    assert_eq!(client.rebase(), INDEX_ONE);
+    assert!(client.try_rebase().is_err()); // once per epoch
+    at(2_000);
+    assert_eq!(client.rebase(), INDEX_ONE);
+    at(3_000);
+    assert_eq!(client.rebase(), 2 * INDEX_ONE);
+    assert_eq!(client.balance(&alice), 2_000);
+    assert_eq!(client.provenance_of(&alice).get(0).unwrap().amount, 2_000);
+
+    // Back at the peg (mints check it), transfers and mints after the rebase are in PI,
+    // not shares
+    client.set_peg(&314_159_000_000);
+    client.transfer(&alice, &bob, &500);
+    client.mint(&bob, &1_000, &PiCoinSource::Rewards);
+    assert_eq!((client.balance(&alice), client.balance(&bob)), (1_500, 1_500));
+
+    // Back inside the band the streak restarts and the index holds
+    for epoch in 4..8u64 {
+        at(epoch * 1_000);
+        assert_eq!(client.rebase(), 2 * INDEX_ONE);
+    }
+
+    // Turning rebase mode off keeps the rebased balances
+    client.set_rebase_config(&None);
+    assert!(client.try_rebase().is_err());
+    assert_eq!(client.rebase_index(), 2 * INDEX_ONE);
+    assert_eq!(client.balance(&alice), 1_500);
+
+    // Share conversions too large for i128 fail with Overflow rather than trapping
+    assert_eq!(crate::rebase::to_amount_at(i128::MAX / INDEX_ONE, 2 * INDEX_ONE), Err(crate::PiCoinError::Overflow));
+    env.as_contract(&client.address, || assert_eq!(crate::rebase::to_shares(&env, i128::MAX / 2), Err(crate::PiCoinError::Overflow)));
+}
+
+#[test]
+fn test_balance_at_reads_checkpoints_across_rebases() {
+    use crate::{peg_band, PegBands, PiCoinError, RebaseConfig};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let at = |sequence: u32| env.ledger().with_mut(|li| {
+        li.sequence_number = sequence;
+        li.timestamp = sequence as u64 * 1_000;
+    });
+
+    at(10);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    at(20);
+    client.transfer(&alice, &bob, &300);
+    client.transfer(&alice, &bob, &44); // the last write in a ledger is what it closes with
+    at(30);
+    assert_eq!(client.balance_at(&alice, &9), 0);
+    assert_eq!(client.balance_at(&alice, &10), 1_000);
+    assert_eq!(client.balance_at(&alice, &19), 1_000);
+    assert_eq!((client.balance_at(&alice, &20), client.balance_at(&bob, &20)), (656, 344));
+    assert_eq!((client.circulating_at(&9), client.circulating_at(&29)), (0, 1_000));
+    assert_eq!(client.try_balance_at(&alice, &30), Err(Ok(PiCoinError::SnapshotUnavailable)));
+
+    // A rebase doubles balances from its ledger on; earlier snapshots keep their value
+    client.set_rebase_config(&Some(RebaseConfig { band_bps: 100, epochs: 1, epoch_length: 1_000 }));
+    client.set_peg_bands(&PegBands { hard_bps: 10_000, ..peg_band::default() });
+    client.set_peg(&157_079_500_000);
+    at(40);
+    client.rebase();
+    at(41);
+    assert_eq!(client.balance(&alice), 1_312);
+    assert_eq!((client.balance_at(&alice, &39), client.balance_at(&alice, &40)), (656, 1_312));
+    assert_eq!((client.circulating_at(&39), client.circulating_at(&40)), (1_000, 2_000));
+}
+
+#[test]
+fn test_snapshot_history_is_pruned() {
+    use crate::snapshot::{SnapshotKey, Checkpoint, MAX_CHECKPOINTS};
+    use crate::PiCoinError;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let history_len = || env.as_contract(&client.address, || {
+        env.storage().persistent().get::<_, soroban_sdk::Vec<Checkpoint>>(&SnapshotKey::Holder(alice.clone())).unwrap().len()
+    });
+    client.set_snapshot_retention(&100);
+    env.ledger().set_sequence_number(10);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+
+    // Outside the retention window only the checkpoint in force at its edge survives
+    env.ledger().set_sequence_number(150);
+    client.transfer(&alice, &bob, &1);
+    env.ledger().set_sequence_number(151);
+    assert_eq!(client.balance_at(&alice, &50), 1_000);
+    assert_eq!(client.try_balance_at(&alice, &9), Err(Ok(PiCoinError::SnapshotUnavailable)));
+    env.ledger().set_sequence_number(260);
+    client.transfer(&alice, &bob, &1);
+    env.ledger().set_sequence_number(261);
+    assert_eq!(client.try_balance_at(&alice, &50), Err(Ok(PiCoinError::SnapshotUnavailable)));
+    assert_eq!(client.balance_at(&alice, &160), 999);
+
+    // A busy holder is capped at MAX_CHECKPOINTS, oldest first
+    client.set_snapshot_retention(&100_000);
+    for sequence in 300..300 + MAX_CHECKPOINTS + 10 {
+        env.ledger().set_sequence_number(sequence);
+        client.transfer(&alice, &bob, &1);
+    }
+    assert_eq!(history_len(), MAX_CHECKPOINTS);
+    assert_eq!(client.try_balance_at(&alice, &309), Err(Ok(PiCoinError::SnapshotUnavailable)));
+    assert_eq!(client.balance_at(&alice, &310), 998 - 11);
+}
+
+#[test]
+fn test_balance_ttl_bumped_on_access_and_extendable() {
+    use crate::balance::BalanceKey;
+    use crate::ttl::DAY_IN_LEDGERS;
+    use crate::TtlConfig;
+    use soroban_sdk::testutils::storage::{Instance, Persistent};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let balance_ttl = |holder: &Address| env.as_contract(&client.address, || env.storage().persistent().get_ttl(&BalanceKey::Balance(holder.clone())));
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    assert_eq!(balance_ttl(&alice), 30 * DAY_IN_LEDGERS);
+    assert_eq!(env.as_contract(&client.address, || env.storage().instance().get_ttl()), 7 * DAY_IN_LEDGERS);
+
+    // Two days later the entry is below the threshold, so the next transfer bumps it
+    env.ledger().with_mut(|li| li.sequence_number += 2 * DAY_IN_LEDGERS);
+    assert_eq!(balance_ttl(&alice), 28 * DAY_IN_LEDGERS);
+    client.transfer(&alice, &bob, &100);
+    assert_eq!(balance_ttl(&alice), 30 * DAY_IN_LEDGERS);
+    assert_eq!(balance_ttl(&bob), 30 * DAY_IN_LEDGERS);
+
+    // Idle holders can be kept alive explicitly, by anyone
+    env.ledger().with_mut(|li| li.sequence_number += 10 * DAY_IN_LEDGERS);
+    assert!(client.extend_balance_ttl(&bob));
+    assert!(!client.extend_balance_ttl(&Address::generate(&env)));
+    assert_eq!(balance_ttl(&bob), 30 * DAY_IN_LEDGERS);
+    client.extend_instance_ttl();
+    assert_eq!(env.as_contract(&client.address, || env.storage().instance().get_ttl()), 7 * DAY_IN_LEDGERS);
+
+    // Governance tunes the lifetimes; inverted thresholds are refused
+    let config = TtlConfig {
+        instance_threshold: 13 * DAY_IN_LEDGERS,
+        instance_extend_to: 14 * DAY_IN_LEDGERS,
+        persistent_threshold: 59 * DAY_IN_LEDGERS,
+        persistent_extend_to: 60 * DAY_IN_LEDGERS,
+    };
+    assert!(client.try_set_ttl_config(&TtlConfig { persistent_threshold: 61 * DAY_IN_LEDGERS, ..config.clone() }).is_err());
+    client.set_ttl_config(&config);
+    client.extend_balance_ttl(&bob);
+    assert_eq!(balance_ttl(&bob), 60 * DAY_IN_LEDGERS);
+}
+
+#[test]
+fn test_expired_balance_archives_and_restores_on_access() {
+    use crate::ttl::DAY_IN_LEDGERS;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Rewards);
+
+    // A month without activity archives both the instance and the balance entry;
+    // touching them restores both (the extra writes are the restoration) with the PI intact
+    env.ledger().with_mut(|li| li.sequence_number += 31 * DAY_IN_LEDGERS);
+    assert_eq!(client.balance(&alice), 1_000);
+    assert_eq!(env.cost_estimate().resources().write_entries, 2);
+}
+
+#[test]
+fn test_transfer_cost_independent_of_holder_count() {
+    // Transfer cost after 100 other holders were minted either on this token or on an
+    // unrelated one. The test host's own cost grows with its total storage, so both
+    // setups hold the same number of entries and only their owner differs.
+    fn transfer_cost(holders_on_token: bool) -> (u64, u32, u32) {
+        use crate::PiCoinContractClient;
+
+        let env = Env::default();
+        env.mock_all_auths();
+        let new_token = || {
+            env.register(
+                PiCoinContract,
+                (Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)),
+            )
+        };
+        let contract_id = new_token();
+        let other_id = new_token();
+        let client = PiCoinContractClient::new(&env, &contract_id);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        client.mint(&alice, &1_000_000, &PiCoinSource::Mining);
+        client.mint(&bob, &1, &PiCoinSource::Mining);
+        let crowded = PiCoinContractClient::new(&env, if holders_on_token { &contract_id } else { &other_id });
+        for _ in 0..100 {
+            crowded.mint(&Address::generate(&env), &1_000, &PiCoinSource::Rewards);
+        }
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+
+        env.cost_estimate().budget().reset_default();
+        client.transfer(&alice, &bob, &100);
+        let resources = env.cost_estimate().resources();
+        (
+            env.cost_estimate().budget().cpu_instruction_cost(),
+            resources.disk_read_entries + resources.memory_read_entries,
+            resources.write_entries,
+        )
+    }
+
+    // PiCoinData no longer carries a per-holder map, so the token's own holders add no
+    // entries and no instance bytes to a transfer. The few percent left is the test
+    // host's storage lookups; with the map the crowded transfer cost about 60% more.
+    let (crowded_cpu, crowded_reads, crowded_writes) = transfer_cost(true);
+    let (baseline_cpu, baseline_reads, baseline_writes) = transfer_cost(false);
+    assert_eq!((crowded_reads, crowded_writes), (baseline_reads, baseline_writes));
+    assert!(crowded_cpu <= baseline_cpu + baseline_cpu / 20, "{} vs {}", crowded_cpu, baseline_cpu);
+}
+
+#[test]
+fn test_restore_balance_after_expiry_keeps_value_but_not_allowances() {
+    use crate::allowance::AllowanceKey;
+    use crate::balance::BalanceKey;
+    use crate::ttl::DAY_IN_LEDGERS;
+    use soroban_sdk::testutils::storage::Persistent;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    client.approve(&alice, &bob, &500, &(env.ledger().sequence() + 100));
+
+    // Far past every TTL: the persistent balance and lots come back with their values,
+    // the temporary allowance is gone for good
+    let mut ledger = env.ledger().get();
+    ledger.sequence_number += 45 * DAY_IN_LEDGERS;
+    ledger.timestamp += 45 * 86_400;
+    env.ledger().set(ledger);
+    assert_eq!(client.restore_balance(&alice), 1_000);
+    assert_eq!(client.provenance_of(&alice).len(), 1);
+    env.as_contract(&client.address, || {
+        assert_eq!(env.storage().persistent().get_ttl(&BalanceKey::Balance(alice.clone())), 30 * DAY_IN_LEDGERS);
+        assert!(!env.storage().temporary().has(&AllowanceKey::Allowance(alice.clone(), bob.clone())));
+    });
+
+    // An emptied balance leaves no entry behind to archive
+    client.burn(&alice, &1_000);
+    assert!(!client.extend_balance_ttl(&alice));
+    assert_eq!(client.restore_balance(&alice), 0);
+}
+
+#[test]
+fn test_every_entry_point_rejects_non_positive_amounts() {
+    use crate::batch::Invocation;
+    use crate::{MetaTransfer, PiCoinError};
+    use soroban_sdk::xdr::ToXdr;
+    use soroban_sdk::{vec, BytesN};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let key = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+    let alice = account_address(&env, &key);
+    let bob = Address::generate(&env);
+    let lender = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    client.approve(&alice, &bob, &500, &100);
+    client.set_flash_lender(&lender);
+    let invalid = Err(Ok(PiCoinError::InvalidAmount));
+
+    for amount in [0, -1, i128::MIN] {
+        assert_eq!(client.try_mint(&alice, &amount, &PiCoinSource::Mining), invalid);
+        assert_eq!(client.try_mint_with_deadline(&alice, &amount, &PiCoinSource::Mining, &100), invalid);
+        assert_eq!(client.try_transfer(&alice, &bob, &amount), invalid);
+        assert_eq!(client.try_transfer_with_deadline(&alice, &bob, &amount, &0, &100), Err(Ok(PiCoinError::InvalidAmount)));
+        assert_eq!(client.try_transfer_from(&bob, &alice, &bob, &amount), invalid);
+        assert_eq!(client.try_batch_transfer(&alice, &vec![&env, (bob.clone(), 10), (bob.clone(), amount)]), invalid);
+        assert_eq!(client.try_multicall(&alice, &vec![&env, Invocation::Transfer(bob.clone(), amount)]), invalid);
+        assert_eq!(client.try_multicall(&bob, &vec![&env, Invocation::TransferFrom(alice.clone(), bob.clone(), amount)]), invalid);
+        assert_eq!(client.try_multicall(&alice, &vec![&env, Invocation::Burn(amount)]), invalid);
+        assert_eq!(client.try_burn(&alice, &amount), invalid);
+        assert_eq!(client.try_clawback(&alice, &amount), invalid);
+        assert_eq!(client.try_flash_credit(&bob, &amount), invalid);
+        assert_eq!(client.try_flash_settle(&bob, &amount, &0), invalid);
+
+        // A correctly signed transfer is still checked
+        let payload = MetaTransfer {
+            network_id: env.ledger().network_id(),
+            token: client.address.clone(),
+            from: alice.clone(),
+            to: bob.clone(),
+            amount,
+            nonce: 0,
+            expiration_ledger: 100,
+        };
+        let signature = sign_xdr(&env, &key, &payload.to_xdr(&env));
+        assert_eq!(client.try_transfer_with_signature(&alice, &bob, &amount, &0, &100, &signature), invalid);
+    }
+
+    // Zero allowances and fees are meaningful (a zero approval revokes); negative ones are not
+    assert_eq!(client.try_approve(&alice, &bob, &-1, &100), invalid);
+    assert_eq!(client.try_multicall(&alice, &vec![&env, Invocation::Approve(bob.clone(), -1, 100)]), invalid);
+    assert_eq!(client.try_permit(&alice, &bob, &-1, &100, &BytesN::from_array(&env, &[0; 64])), invalid);
+    assert_eq!(client.try_flash_settle(&bob, &1, &-1), invalid);
+    client.approve(&alice, &bob, &0, &100);
+    assert_eq!(client.allowance(&alice, &bob), 0);
+
+    // Sending to oneself is rejected on every transfer path
+    let own = Err(Ok(PiCoinError::SelfTransfer));
+    assert_eq!(client.try_transfer(&alice, &alice, &10), own);
+    assert_eq!(client.try_transfer_with_deadline(&alice, &alice, &10, &0, &100), Err(Ok(PiCoinError::SelfTransfer)));
+    assert_eq!(client.try_batch_transfer(&alice, &vec![&env, (alice.clone(), 10)]), own);
+    assert_eq!(client.try_multicall(&alice, &vec![&env, Invocation::Transfer(alice.clone(), 10)]), own);
+
+    // Nothing moved
+    assert_eq!(client.balance(&alice), 1_000);
+    assert_eq!(client.balance(&bob), 0);
+    assert_eq!(client.circulating_supply(), 1_000);
+}
+
+// G... account address of an ed25519 key, built from its ScVal::Address XDR
+fn account_address(env: &Env, key: &ed25519_dalek::SigningKey) -> Address {
+    use soroban_sdk::xdr::FromXdr;
+    let mut xdr = Bytes::from_array(env, &[0, 0, 0, 18, 0, 0, 0, 0, 0, 0, 0, 0]);
+    xdr.extend_from_array(&key.verifying_key().to_bytes());
+    Address::from_xdr(env, &xdr).unwrap()
+}
+
+// ed25519 signature of an XDR payload
+fn sign_xdr(env: &Env, key: &ed25519_dalek::SigningKey, xdr: &Bytes) -> BytesN<64> {
+    use ed25519_dalek::Signer;
+    let mut message = [0u8; 512];
+    let len = xdr.len() as usize;
+    xdr.copy_into_slice(&mut message[..len]);
+    BytesN::from_array(env, &key.sign(&message[..len]).to_bytes())
+}
+
+#[test]
+fn test_amounts_past_i128_fail_with_overflow() {
+    use crate::rebase::{RebaseKey, INDEX_ONE};
+    use crate::{balance, PiCoinError};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
 
-    // Verify for invalid holder (no provenance)
-    let invalid_result = PiCoinContract::verify_ecosystem_entry(env.clone(), invalid_holder);
-    assert!(invalid_result.is_ok() && !invalid_result.unwrap());
-    println!("Hyper-tech ecosystem verify: Valid {} source approved, invalid rejected - Global recognition exclusive", source);
+    // Mints are capped far below i128 by collateral, so drive the balance module directly,
+    // at the lowest index so that every PI is INDEX_ONE shares
+    let largest = i128::MAX / INDEX_ONE;
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&RebaseKey::Index, &1i128);
+        // Too large to scale into shares at all
+        assert_eq!(balance::add(&env, &alice, i128::MAX), Err(PiCoinError::Overflow));
+        // Each credit fits, but the holder's shares or the total would pass i128
+        balance::add(&env, &alice, largest).unwrap();
+        assert_eq!(balance::add(&env, &alice, largest), Err(PiCoinError::Overflow));
+        assert_eq!(balance::add(&env, &bob, largest), Err(PiCoinError::Overflow));
+    });
+    assert_eq!(client.balance(&alice), largest);
+    assert_eq!(client.balance(&bob), 0);
+    assert_eq!(client.circulating_supply(), largest);
+    assert_eq!(client.try_mint(&bob, &1, &PiCoinSource::Mining), Err(Ok(PiCoinError::Overflow)));
 }
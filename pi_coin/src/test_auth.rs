@@ -0,0 +1,113 @@
+#![cfg(test)]
+// Authorization trees: every call here runs with explicit mock_auths entries instead of
+// mock_all_auths, and env.auths() pins down exactly who had to sign for what.
+extern crate std;
+
+use soroban_sdk::testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, MockAuth, MockAuthInvoke};
+use soroban_sdk::{Address, Env, IntoVal, Symbol, Val, Vec};
+use crate::{PiCoinContract, PiCoinContractClient, PiCoinSource};
+
+struct Setup<'a> {
+    env: Env,
+    client: PiCoinContractClient<'a>,
+    admin: Address,
+    governance: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let governance = Address::generate(&env);
+    let contract_id = env.register(PiCoinContract, (&admin, Address::generate(&env), Address::generate(&env), &governance));
+    Setup { client: PiCoinContractClient::new(&env, &contract_id), env, admin, governance }
+}
+
+impl Setup<'_> {
+    // Mock exactly one signature for the next call: `signer` authorizing `fn_name(args)` on the token
+    fn signed(&self, signer: &Address, fn_name: &str, args: Vec<Val>) -> &PiCoinContractClient<'_> {
+        let invoke = MockAuthInvoke { contract: &self.client.address, fn_name, args, sub_invokes: &[] };
+        self.env.mock_auths(&[MockAuth { address: signer, invoke: &invoke }]);
+        &self.client
+    }
+
+    // The single authorization the last call required: `signer` for `fn_name(args)`
+    fn assert_only_auth(&self, signer: &Address, fn_name: &str, args: Vec<Val>) {
+        assert_eq!(
+            self.env.auths(),
+            std::vec![(
+                signer.clone(),
+                AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((self.client.address.clone(), Symbol::new(&self.env, fn_name), args)),
+                    sub_invocations: std::vec![],
+                }
+            )]
+        );
+    }
+
+    fn mint(&self, to: &Address, amount: i128) {
+        let args = (to, amount, PiCoinSource::Mining).into_val(&self.env);
+        self.signed(&self.admin, "mint", args).mint(to, &amount, &PiCoinSource::Mining);
+    }
+}
+
+#[test]
+fn test_mint_requires_admin_signature() {
+    let s = setup();
+    let to = Address::generate(&s.env);
+    let args: Vec<Val> = (&to, 500i128, PiCoinSource::Mining).into_val(&s.env);
+
+    s.signed(&s.admin, "mint", args.clone()).mint(&to, &500, &PiCoinSource::Mining);
+    s.assert_only_auth(&s.admin, "mint", args.clone());
+    assert_eq!(s.client.balance(&to), 500);
+
+    // Neither the recipient nor governance can mint in the admin's place
+    assert!(s.signed(&to, "mint", args.clone()).try_mint(&to, &500, &PiCoinSource::Mining).is_err());
+    assert!(s.signed(&s.governance, "mint", args).try_mint(&to, &500, &PiCoinSource::Mining).is_err());
+    assert_eq!(s.client.balance(&to), 500);
+}
+
+#[test]
+fn test_transfer_requires_sender_signature_only() {
+    let s = setup();
+    let alice = Address::generate(&s.env);
+    let bob = Address::generate(&s.env);
+    s.mint(&alice, 1_000);
+    let args: Vec<Val> = (&alice, &bob, 300i128).into_val(&s.env);
+
+    s.signed(&alice, "transfer", args.clone()).transfer(&alice, &bob, &300);
+    s.assert_only_auth(&alice, "transfer", args.clone());
+    assert_eq!(s.client.balance(&bob), 300);
+
+    // The recipient's or the admin's signature does not move the sender's PI
+    assert!(s.signed(&bob, "transfer", args.clone()).try_transfer(&alice, &bob, &300).is_err());
+    assert!(s.signed(&s.admin, "transfer", args).try_transfer(&alice, &bob, &300).is_err());
+    assert_eq!(s.client.balance(&alice), 700);
+}
+
+#[test]
+fn test_clawback_requires_governance_signature() {
+    let s = setup();
+    let holder = Address::generate(&s.env);
+    s.mint(&holder, 1_000);
+    let args: Vec<Val> = (&holder, 400i128).into_val(&s.env);
+
+    // Neither the admin nor the holder can claw back
+    assert!(s.signed(&s.admin, "clawback", args.clone()).try_clawback(&holder, &400).is_err());
+    assert!(s.signed(&holder, "clawback", args.clone()).try_clawback(&holder, &400).is_err());
+
+    s.signed(&s.governance, "clawback", args.clone()).clawback(&holder, &400);
+    s.assert_only_auth(&s.governance, "clawback", args);
+    assert_eq!(s.client.balance(&holder), 600);
+}
+
+#[test]
+fn test_governance_actions_require_governance_signature() {
+    let s = setup();
+    let args: Vec<Val> = (25u32,).into_val(&s.env);
+
+    // What an executed SetFee proposal amounts to: governance authorizing set_fee(25)
+    assert!(s.signed(&s.admin, "set_fee", args.clone()).try_set_fee(&25).is_err());
+    s.signed(&s.governance, "set_fee", args.clone()).set_fee(&25);
+    s.assert_only_auth(&s.governance, "set_fee", args);
+    assert_eq!(s.client.fee_config().0, 25);
+}
@@ -0,0 +1,78 @@
+#![cfg(test)]
+// EVM interop: keccak256 and address derivation against Ethereum reference values, and
+// ecrecover round trips with messages signed the way Ethereum wallets sign them.
+extern crate std;
+
+use k256::ecdsa::SigningKey;
+use soroban_sdk::{bytesn, Bytes, BytesN, Env};
+use crate::evm::{eth_address, eth_message_hash, keccak256, recover, recover_eth_signer};
+use crate::PiCoinError;
+
+// personal_sign: 65-byte r || s || v with v = 27 + recovery id
+fn personal_sign(env: &Env, key: &SigningKey, message: &[u8]) -> BytesN<65> {
+    let digest = eth_message_hash(env, &Bytes::from_slice(env, message)).to_array();
+    let (signature, recovery_id) = key.sign_prehash_recoverable(&digest).unwrap();
+    let mut bytes = [0u8; 65];
+    bytes[..64].copy_from_slice(&signature.to_bytes());
+    bytes[64] = 27 + recovery_id.to_byte();
+    BytesN::from_array(env, &bytes)
+}
+
+fn address_of(env: &Env, key: &SigningKey) -> BytesN<20> {
+    let point = key.verifying_key().to_encoded_point(false);
+    eth_address(env, &BytesN::from_array(env, point.as_bytes().try_into().unwrap()))
+}
+
+#[test]
+fn test_hashes_match_ethereum() {
+    let env = Env::default();
+    assert_eq!(
+        keccak256(&env, &Bytes::new(&env)).to_bytes(),
+        bytesn!(&env, 0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470)
+    );
+    // ethers.hashMessage("hello world")
+    assert_eq!(
+        eth_message_hash(&env, &Bytes::from_slice(&env, b"hello world")).to_bytes(),
+        bytesn!(&env, 0xd9eba16ed0ecae432b71fe008c98cc872bb4cc214d3220a36f365326cf807d68)
+    );
+    // The length is written in decimal, however many digits it takes
+    for len in [0usize, 9, 10, 123, 1000] {
+        let message = std::vec![7u8; len];
+        let mut prefixed = std::format!("\x19Ethereum Signed Message:\n{len}").into_bytes();
+        prefixed.extend_from_slice(&message);
+        assert_eq!(
+            eth_message_hash(&env, &Bytes::from_slice(&env, &message)).to_bytes(),
+            keccak256(&env, &Bytes::from_slice(&env, &prefixed)).to_bytes()
+        );
+    }
+
+    // The address of private key 1
+    let key = SigningKey::from_slice(&[[0u8; 31].as_slice(), &[1]].concat()).unwrap();
+    assert_eq!(address_of(&env, &key), bytesn!(&env, 0x7e5f4552091a69125d5dfcb7b8c2659029395bdf));
+}
+
+#[test]
+fn test_recovers_personal_sign_signer() {
+    let env = Env::default();
+    let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+    let signer = address_of(&env, &key);
+    let message = b"bridge attestation: release 100 PI, nonce 7";
+    let signature = personal_sign(&env, &key, message);
+
+    assert_eq!(recover_eth_signer(&env, &Bytes::from_slice(&env, message), &signature), Ok(signer.clone()));
+
+    // v may also be given as a bare recovery id (0/1)
+    let mut raw = signature.to_array();
+    raw[64] -= 27;
+    assert_eq!(recover_eth_signer(&env, &Bytes::from_slice(&env, message), &BytesN::from_array(&env, &raw)), Ok(signer.clone()));
+
+    // Another message, or the other recovery id, yields some other address
+    assert_ne!(recover_eth_signer(&env, &Bytes::from_slice(&env, b"release 1000 PI"), &signature), Ok(signer.clone()));
+    raw[64] ^= 1;
+    assert_ne!(recover_eth_signer(&env, &Bytes::from_slice(&env, message), &BytesN::from_array(&env, &raw)), Ok(signer));
+
+    // v outside 0, 1, 27, 28 is rejected
+    raw[64] = 29;
+    let digest = eth_message_hash(&env, &Bytes::from_slice(&env, message));
+    assert_eq!(recover(&env, &digest, &BytesN::from_array(&env, &raw)), Err(PiCoinError::InvalidSigner));
+}
@@ -0,0 +1,142 @@
+#![cfg(test)]
+// Fixed-point math: rounding in every direction and sign, the exact i128 boundaries
+// where results stop fitting, and π against reference digits.
+use crate::fixed_point::{apply_bps, div, mul_div, pi, pi_peg, pow, Rounding, BPS, MAX_PI_DIGITS, PI, PI_SCALE};
+use crate::PiCoinError;
+
+const MODES: [Rounding; 3] = [Rounding::Down, Rounding::Up, Rounding::HalfUp];
+
+#[test]
+fn test_div_rounds_in_each_mode() {
+    // (numerator, denominator, down, up, half_up)
+    let cases = [
+        (7, 2, 3, 4, 4),
+        (5, 3, 1, 2, 2),
+        (4, 3, 1, 2, 1),
+        (6, 3, 2, 2, 2),
+        (-7, 2, -4, -3, -3),
+        (-5, 3, -2, -1, -2),
+        (7, -2, -4, -3, -3),
+        (-7, -2, 3, 4, 4),
+        (0, 5, 0, 0, 0),
+        (1, i128::MAX, 0, 1, 0),
+    ];
+    for (numerator, denominator, down, up, half_up) in cases {
+        assert_eq!(div(numerator, denominator, Rounding::Down), Ok(down), "{numerator}/{denominator} down");
+        assert_eq!(div(numerator, denominator, Rounding::Up), Ok(up), "{numerator}/{denominator} up");
+        assert_eq!(div(numerator, denominator, Rounding::HalfUp), Ok(half_up), "{numerator}/{denominator} half up");
+    }
+
+    // Exhaustively over small values: floor, ceiling and round-half-up of the exact quotient
+    for numerator in -50i128..=50 {
+        for denominator in (-7i128..=7).filter(|d| *d != 0) {
+            let down = div(numerator, denominator, Rounding::Down).unwrap();
+            let up = div(numerator, denominator, Rounding::Up).unwrap();
+            let half_up = div(numerator, denominator, Rounding::HalfUp).unwrap();
+            let exact = numerator as f64 / denominator as f64;
+            assert_eq!(down, exact.floor() as i128);
+            assert_eq!(up, exact.ceil() as i128);
+            assert_eq!(half_up, (exact + 0.5).floor() as i128);
+        }
+    }
+}
+
+#[test]
+fn test_division_by_zero_is_an_error() {
+    for rounding in MODES {
+        assert_eq!(div(1, 0, rounding), Err(PiCoinError::ArithmeticError));
+        assert_eq!(mul_div(1, 1, 0, rounding), Err(PiCoinError::ArithmeticError));
+    }
+}
+
+#[test]
+fn test_overflow_boundaries() {
+    for rounding in MODES {
+        // The largest products that fit, in both signs
+        assert_eq!(mul_div(i128::MAX, 1, 1, rounding), Ok(i128::MAX));
+        assert_eq!(mul_div(i128::MIN, 1, 1, rounding), Ok(i128::MIN));
+        assert_eq!(mul_div(i128::MAX / 2, 2, 2, rounding), Ok(i128::MAX / 2));
+        // One past them
+        assert_eq!(mul_div(i128::MAX / 2 + 1, 2, 2, rounding), Err(PiCoinError::ArithmeticError));
+        assert_eq!(mul_div(i128::MAX, 2, 4, rounding), Err(PiCoinError::ArithmeticError));
+        assert_eq!(mul_div(i128::MIN, -1, 1, rounding), Err(PiCoinError::ArithmeticError));
+        // MIN / -1 does not fit even though the product does
+        assert_eq!(div(i128::MIN, -1, rounding), Err(PiCoinError::ArithmeticError));
+    }
+    // Rounding up past MAX overflows; rounding down does not
+    assert_eq!(div(i128::MAX, 1, Rounding::Up), Ok(i128::MAX));
+    assert_eq!(mul_div(i128::MAX, 1, 2, Rounding::Up), Ok(i128::MAX / 2 + 1));
+
+    // Token-sized values stay far from the limits: 100B PI at 7 decimals times a full
+    // 10_000 bps
+    let supply = 100_000_000_000 * 10_000_000;
+    assert_eq!(apply_bps(supply, 10_000, Rounding::Down), Ok(supply));
+    assert_eq!(apply_bps(i128::MAX / BPS + 1, 10_000, Rounding::Down), Err(PiCoinError::ArithmeticError));
+    assert_eq!(apply_bps(i128::MAX / BPS, 10_000, Rounding::Down), Ok(i128::MAX / BPS));
+}
+
+#[test]
+fn test_apply_bps_rounding() {
+    assert_eq!(apply_bps(10_000, 25, Rounding::Down), Ok(25));
+    assert_eq!(apply_bps(399, 25, Rounding::Down), Ok(0));
+    assert_eq!(apply_bps(399, 25, Rounding::Up), Ok(1));
+    assert_eq!(apply_bps(200, 25, Rounding::HalfUp), Ok(1));
+    assert_eq!(apply_bps(199, 25, Rounding::HalfUp), Ok(0));
+    assert_eq!(apply_bps(12_345, 0, Rounding::Up), Ok(0));
+}
+
+#[test]
+fn test_pow_compounds() {
+    let one = 1_000_000_000_000i128;
+    assert_eq!(pow(one * 3 / 2, 0, one), Ok(one));
+    assert_eq!(pow(one * 3 / 2, 1, one), Ok(one * 3 / 2));
+    assert_eq!(pow(2 * one, 10, one), Ok(1024 * one));
+    // 1% a period for 12 periods: 1.01^12 = 1.126825030131969720661201
+    assert_eq!(pow(one + one / 100, 12, one), Ok(1_126_825_030_131));
+    // 5% a year compounded daily: (1 + 0.05/365)^365 = 1.0512674964674, less the
+    // truncation of the daily rate and of each squaring
+    let daily = one + one * 5 / 100 / 365;
+    let year = pow(daily, 365, one).unwrap();
+    assert!((1_051_267_490_000..=1_051_267_496_467).contains(&year), "{year}");
+    // Growth that leaves i128 is an error, not a wrap
+    assert_eq!(pow(2 * one, 128, one), Err(PiCoinError::ArithmeticError));
+}
+
+#[test]
+fn test_pi_matches_reference_digits() {
+    // π = 3.14159265358979323846...
+    assert_eq!(pi(1, Rounding::HalfUp), Ok((3, 1)));
+    assert_eq!(pi(3, Rounding::Down), Ok((314, 100)));
+    assert_eq!(pi(3, Rounding::Up), Ok((315, 100)));
+    assert_eq!(pi(6, Rounding::HalfUp), Ok((314_159, 100_000)));
+    assert_eq!(pi(7, Rounding::HalfUp), Ok((3_141_593, 1_000_000)));
+    assert_eq!(pi(7, Rounding::Down), Ok((3_141_592, 1_000_000)));
+    assert_eq!(pi(18, Rounding::HalfUp), Ok((314_159_265_358_979_324, 100_000_000_000_000_000)));
+    assert_eq!(pi(18, Rounding::Down), Ok((314_159_265_358_979_323, 100_000_000_000_000_000)));
+    assert_eq!(pi(0, Rounding::Down), Err(PiCoinError::InvalidPrecision));
+    assert_eq!(pi(19, Rounding::Down), Err(PiCoinError::InvalidPrecision));
+
+    // Every precision is π within half a unit of its last digit
+    for digits in 1..=MAX_PI_DIGITS {
+        let (value, scale) = pi(digits, Rounding::HalfUp).unwrap();
+        let error = (value * (PI_SCALE / scale) - PI).abs();
+        assert!(2 * error <= PI_SCALE / scale, "{digits} digits");
+    }
+}
+
+#[test]
+fn test_pi_peg_reference_values() {
+    let peg = 314_159_000_000;
+    // The original 3.14159 approximation in micro-units
+    assert_eq!(pi_peg(peg, 1_000_000, 6, Rounding::HalfUp), Ok(peg + 3_141_590));
+    // Full precision: π dollars = 3_141_592.653589... micro-units
+    assert_eq!(pi_peg(peg, 1_000_000, 18, Rounding::Down), Ok(peg + 3_141_592));
+    assert_eq!(pi_peg(peg, 1_000_000, 18, Rounding::HalfUp), Ok(peg + 3_141_593));
+    // Finer units keep more of the digits: 1e12 per dollar
+    assert_eq!(pi_peg(0, 1_000_000_000_000, 18, Rounding::HalfUp), Ok(3_141_592_653_590));
+    assert_eq!(pi_peg(0, 1_000_000_000_000, 18, Rounding::Down), Ok(3_141_592_653_589));
+    assert_eq!(pi_peg(0, PI_SCALE / 10, 18, Rounding::Down), Ok(314_159_265_358_979_323));
+    // Overflow and bad precision are errors
+    assert_eq!(pi_peg(i128::MAX, 1_000_000, 6, Rounding::Down), Err(PiCoinError::ArithmeticError));
+    assert_eq!(pi_peg(peg, 1_000_000, 0, Rounding::Down), Err(PiCoinError::InvalidPrecision));
+}
@@ -0,0 +1,122 @@
+#![cfg(test)]
+// Integration suite: token, oracle and governance registered side by side and driven only
+// through their generated clients, so host dispatch, authorization and the real
+// cross-contract calls between them are exercised. Build the contract wasms first
+// (`stellar contract build`); the oracle and governance are imported from them.
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, vec, Address, Bytes, Env, IntoVal, Symbol};
+use crate::{PiCoinContract, PiCoinContractClient, PiCoinSource};
+
+mod oracle {
+    soroban_sdk::contractimport!(file = "target/wasm32v1-none/release/pi_coin_oracle.wasm");
+}
+
+mod governance {
+    soroban_sdk::contractimport!(file = "target/wasm32v1-none/release/pi_coin_governance.wasm");
+}
+
+struct Ecosystem<'a> {
+    env: Env,
+    token: PiCoinContractClient<'a>,
+    oracle: oracle::Client<'a>,
+    governance: governance::Client<'a>,
+}
+
+// Deploy the three contracts wired to each other, as the deploy CLI does
+fn setup<'a>() -> Ecosystem<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let token_id = Address::generate(&env);
+    let governance_id = env.register(governance::WASM, (&admin, &token_id, Address::generate(&env)));
+    let oracle_id = env.register(oracle::WASM, (&admin, &governance_id));
+    env.register_at(&token_id, PiCoinContract, (&admin, Address::generate(&env), &oracle_id, &governance_id));
+    Ecosystem {
+        token: PiCoinContractClient::new(&env, &token_id),
+        oracle: oracle::Client::new(&env, &oracle_id),
+        governance: governance::Client::new(&env, &governance_id),
+        env,
+    }
+}
+
+impl Ecosystem<'_> {
+    // A staked voter pushes `action` through proposal, vote, finalization and timelock
+    fn pass(&self, voter: &Address, action: governance::Action) -> u32 {
+        self.env.ledger().with_mut(|li| li.sequence_number += 1); // Stake counts from the next ledger
+        let id = self.governance.create_proposal(voter, &Symbol::new(&self.env, "prop"), &Bytes::from_slice(&self.env, b"integrate"), &action);
+        self.env.ledger().with_mut(|li| li.sequence_number += 1);
+        self.governance.vote(voter, &id, &true);
+        self.env.ledger().with_mut(|li| li.sequence_number += 17_281);
+        self.governance.finalize_proposal(&id);
+        self.env.ledger().with_mut(|li| li.timestamp += 2 * 24 * 60 * 60);
+        // Execution needs no signature at all: governance authorizes the downstream call
+        // as its direct invoker
+        self.env.set_auths(&[]);
+        self.governance.execute(&id);
+        assert!(self.env.auths().is_empty());
+        // Indexers see the execution under the versioned event schema
+        assert_eq!(
+            self.env.events().all().filter_by_contract(&self.governance.address),
+            vec![
+                &self.env,
+                (
+                    self.governance.address.clone(),
+                    (Symbol::new(&self.env, "proposal_executed"), id, 1u32).into_val(&self.env),
+                    Symbol::new(&self.env, "prop").into_val(&self.env),
+                ),
+            ]
+        );
+        self.env.mock_all_auths();
+        id
+    }
+}
+
+#[test]
+fn test_governance_stakes_pi_and_executes_on_token() {
+    let eco = setup();
+    let env = &eco.env;
+    let voter = Address::generate(env);
+    eco.token.mint(&voter, &2_000_000, &PiCoinSource::Mining);
+
+    // Staking moves PI into governance through the token's transfer
+    eco.governance.stake_tokens(&voter, &1_000_000);
+    assert_eq!(eco.token.balance(&eco.governance.address), 1_000_000);
+
+    // An executed proposal calls the token with governance as the authorizing invoker
+    let id = eco.pass(&voter, governance::Action::SetFee(25));
+    assert_eq!(eco.token.fee_config().0, 25);
+
+    // The outcome is readable through governance's views
+    let proposal = eco.governance.proposal(&id);
+    assert_eq!((proposal.status, proposal.votes_for), (Symbol::new(env, "executed"), 1_000_000));
+    assert_eq!(eco.governance.proposal_count(), 1);
+    let record = eco.governance.voter(&voter);
+    assert_eq!((record.stake, record.vote_history), (1_000_000, vec![env, id]));
+    assert!(eco.governance.try_proposal(&(id + 1)).is_err());
+
+    // Direct calls without governance's authorization are refused by the token
+    env.set_auths(&[]);
+    assert!(eco.token.try_set_fee(&50).is_err());
+}
+
+#[test]
+fn test_governance_reconfigures_oracle_and_token_oracle() {
+    let eco = setup();
+    let env = &eco.env;
+    let voter = Address::generate(env);
+    eco.token.mint(&voter, &2_000_000, &PiCoinSource::Rewards);
+    eco.governance.stake_tokens(&voter, &1_000_000);
+
+    // Custom action: governance registers a price pool on the oracle
+    let pool = Address::generate(env);
+    let args = vec![env, Symbol::new(env, "PI").into_val(env), pool.into_val(env)];
+    eco.pass(&voter, governance::Action::Custom(eco.oracle.address.clone(), Symbol::new(env, "set_price_pool"), args));
+    env.as_contract(&eco.oracle.address, || {
+        let stored: Address = env.storage().instance().get(&oracle::OracleKey::PricePool(Symbol::new(env, "PI"))).unwrap();
+        assert_eq!(stored, pool);
+    });
+
+    // SetOracle: the token follows governance to a new oracle
+    let new_oracle = env.register(oracle::WASM, (Address::generate(env), &eco.governance.address));
+    eco.pass(&voter, governance::Action::SetOracle(new_oracle.clone()));
+    assert_eq!(eco.token.config().oracle_address, new_oracle);
+}
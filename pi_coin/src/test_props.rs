@@ -0,0 +1,59 @@
+#![cfg(test)]
+// Property tests: random operation sequences (generated through the SDK's arbitrary
+// support) must preserve the token invariants checked by testutils::Harness. The same
+// harness backs the cargo-fuzz targets in fuzz/.
+extern crate std;
+
+use proptest::prelude::*;
+use soroban_sdk::testutils::arbitrary::arbitrary::{Arbitrary, Unstructured};
+use std::vec::Vec;
+
+use crate::testutils::{Harness, Op, MAX_OPS};
+
+// Helper: decodes proptest's random bytes into operations, the way the fuzz target does
+fn ops(bytes: &[u8], max: usize) -> Vec<Op> {
+    let mut input = Unstructured::new(bytes);
+    let mut ops = Vec::new();
+    while ops.len() < max {
+        match Op::arbitrary(&mut input) {
+            Ok(op) => ops.push(op),
+            Err(_) => break,
+        }
+    }
+    ops
+}
+
+// The checked-in cargo-fuzz seeds, decoded the way libfuzzer hands them to the target
+#[test]
+fn test_fuzz_seeds_replay() {
+    for seed in [
+        &include_bytes!("../fuzz/seeds/token_ops/collateral_and_sources")[..],
+        &include_bytes!("../fuzz/seeds/token_ops/fees_and_allowances")[..],
+        &include_bytes!("../fuzz/seeds/token_ops/mint_transfer_burn")[..],
+    ] {
+        let ops = Vec::<Op>::arbitrary_take_rest(Unstructured::new(seed)).unwrap();
+        assert!(!ops.is_empty());
+        Harness::new().run(&ops);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_invariants_hold_for_any_operation_sequence(bytes in proptest::collection::vec(any::<u8>(), 16..1024)) {
+        Harness::new().run(&ops(&bytes, MAX_OPS));
+    }
+
+    #[test]
+    fn test_transfers_never_create_negative_balances(
+        mints in proptest::collection::vec(any::<u8>(), 16..128),
+        transfers in proptest::collection::vec((0u8..4, 0u8..4, any::<u32>()), 1..16),
+    ) {
+        // Seed some balances, then hammer them with transfers of any size
+        let mut harness = Harness::new();
+        harness.run(&ops(&mints, 4));
+        let ops: Vec<Op> = transfers.into_iter().map(|(from, to, amount)| Op::Transfer { from, to, amount }).collect();
+        harness.run(&ops);
+    }
+}
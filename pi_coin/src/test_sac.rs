@@ -0,0 +1,202 @@
+#![cfg(test)]
+// Differential tests against the Stellar Asset Contract: the same operation sequences run
+// through the SDK's standard TokenClient against PiCoinContract and a registered SAC, and
+// the standard-token subset must behave alike. Compared after every operation: whether
+// it was accepted, who had to sign for it (function and arguments), the events it
+// published (topics up to the last, which is the SAC's asset name and the token's schema
+// version, and data), every balance and every allowance. A signature or semantics change
+// in the token's standard interface breaks these tests rather than wallets.
+//
+// The token's deliberate departures (positive amounts only, no self-transfers) are pinned
+// in their own test. The transfer fee stays at zero here. The token does not implement
+// burn_from, so it is not compared.
+extern crate std;
+
+use soroban_sdk::testutils::{Address as _, AuthorizedFunction, Events, Ledger};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::xdr::ContractEventBody;
+use soroban_sdk::{Address, Env, Symbol, TryFromVal, Val, Vec};
+use std::vec::Vec as StdVec;
+
+use crate::{PiCoinContract, PiCoinContractClient, PiCoinSource};
+
+const ACTORS: u32 = 3;
+const START_LEDGER: u32 = 100;
+
+// A standard-interface call; actors are indexes into the harness' holders
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Transfer(u32, u32, i128), // (from, to, amount)
+    Approve(u32, u32, i128, u32), // (from, spender, amount, expiration_ledger)
+    TransferFrom(u32, u32, u32, i128), // (spender, from, to, amount)
+    Burn(u32, i128), // (from, amount)
+    AdvanceLedgers(u32),
+}
+
+// A call as seen from outside the contract. Rejected calls only compare as rejected.
+#[derive(Debug, PartialEq)]
+struct Observed {
+    accepted: bool,
+    auths: StdVec<(Address, Symbol, Vec<Val>)>, // (signer, function, args)
+    events: StdVec<(Vec<Val>, Vec<Val>)>, // (topics without the last, [data])
+}
+
+struct Differential<'a> {
+    env: Env,
+    pi: TokenClient<'a>,
+    sac: TokenClient<'a>,
+    actors: Vec<Address>,
+}
+
+impl Differential<'_> {
+    // Both tokens with the same `balance` minted to every actor
+    fn new(balance: i128) -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_sequence_number(START_LEDGER);
+        let pi_id = env.register(
+            PiCoinContract,
+            (Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)),
+        );
+        let sac_id = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let mut actors = Vec::new(&env);
+        for _ in 0..ACTORS {
+            let actor = Address::generate(&env);
+            PiCoinContractClient::new(&env, &pi_id).mint(&actor, &balance, &PiCoinSource::Mining);
+            StellarAssetClient::new(&env, &sac_id).mint(&actor, &balance);
+            actors.push_back(actor);
+        }
+        Differential { pi: TokenClient::new(&env, &pi_id), sac: TokenClient::new(&env, &sac_id), env, actors }
+    }
+
+    // Apply each op to both tokens, asserting they stay indistinguishable
+    fn run(&self, ops: &[Op]) {
+        for op in ops {
+            if let Op::AdvanceLedgers(ledgers) = op {
+                self.env.ledger().with_mut(|li| li.sequence_number += ledgers);
+            } else {
+                let pi = self.apply(&self.pi, *op);
+                let sac = self.apply(&self.sac, *op);
+                assert_eq!(pi, sac, "token and SAC diverged on {:?}", op);
+            }
+            self.assert_same_state(op);
+        }
+    }
+
+    fn apply(&self, token: &TokenClient, op: Op) -> Observed {
+        let accepted = match op {
+            Op::Transfer(from, to, amount) => token.try_transfer(&self.actor(from), self.actor(to), &amount).is_ok(),
+            Op::Approve(from, spender, amount, expiration_ledger) => {
+                token.try_approve(&self.actor(from), &self.actor(spender), &amount, &expiration_ledger).is_ok()
+            }
+            Op::TransferFrom(spender, from, to, amount) => {
+                token.try_transfer_from(&self.actor(spender), &self.actor(from), &self.actor(to), &amount).is_ok()
+            }
+            Op::Burn(from, amount) => token.try_burn(&self.actor(from), &amount).is_ok(),
+            Op::AdvanceLedgers(_) => unreachable!(),
+        };
+        if !accepted {
+            return Observed { accepted, auths: StdVec::new(), events: StdVec::new() };
+        }
+        let auths = self
+            .env
+            .auths()
+            .into_iter()
+            .map(|(signer, invocation)| match invocation.function {
+                AuthorizedFunction::Contract((_, function, args)) => (signer, function, args),
+                _ => panic!("{:?} authorized more than a contract call", op),
+            })
+            .collect();
+        let events = self
+            .env
+            .events()
+            .all()
+            .filter_by_contract(&token.address)
+            .events()
+            .iter()
+            .map(|event| {
+                let ContractEventBody::V0(body) = &event.body;
+                let val = |scval| Val::try_from_val(&self.env, scval).unwrap();
+                let mut topics = Vec::new(&self.env);
+                for topic in body.topics.iter() {
+                    topics.push_back(val(topic));
+                }
+                topics.pop_back();
+                (topics, Vec::from_array(&self.env, [val(&body.data)]))
+            })
+            .collect();
+        Observed { accepted, auths, events }
+    }
+
+    // Every balance and every allowance match across the two tokens
+    fn assert_same_state(&self, op: &Op) {
+        for holder in self.actors.iter() {
+            assert_eq!(self.pi.balance(&holder), self.sac.balance(&holder), "balance of {:?} after {:?}", holder, op);
+            for spender in self.actors.iter() {
+                assert_eq!(
+                    self.pi.allowance(&holder, &spender),
+                    self.sac.allowance(&holder, &spender),
+                    "allowance {:?} -> {:?} after {:?}",
+                    holder,
+                    spender,
+                    op
+                );
+            }
+        }
+    }
+
+    fn actor(&self, index: u32) -> Address {
+        self.actors.get(index % ACTORS).unwrap()
+    }
+}
+
+#[test]
+fn test_transfers_and_burns_match_the_sac() {
+    let d = Differential::new(1_000);
+    assert_eq!(d.pi.decimals(), d.sac.decimals());
+    d.run(&[
+        Op::Transfer(0, 1, 300),
+        Op::Transfer(1, 2, 1_300),
+        Op::Transfer(2, 0, 2_301), // More than the balance
+        Op::Transfer(0, 1, -5),
+        Op::Transfer(1, 0, 1), // Emptied
+        Op::Burn(2, 50),
+        Op::Burn(2, 5_000), // More than the balance
+        Op::Burn(0, -1),
+        Op::Transfer(0, 2, 700), // The whole balance
+    ]);
+}
+
+#[test]
+fn test_allowances_match_the_sac() {
+    let d = Differential::new(1_000);
+    d.run(&[
+        Op::Approve(0, 2, 500, START_LEDGER + 100),
+        Op::TransferFrom(2, 0, 1, 200),
+        Op::TransferFrom(2, 0, 1, 400), // Past the remaining 300
+        Op::TransferFrom(1, 0, 2, 1), // No allowance
+        Op::Approve(0, 2, 0, 0), // Revoking needs no live expiration
+        Op::TransferFrom(2, 0, 1, 1),
+        Op::Approve(1, 2, 100, START_LEDGER - 1), // Already expired
+        Op::Approve(1, 2, -1, START_LEDGER + 10),
+        Op::Approve(1, 2, 100, START_LEDGER + 10),
+        Op::TransferFrom(2, 1, 0, 60),
+        Op::AdvanceLedgers(10),
+        Op::TransferFrom(2, 1, 0, 40), // Expiring this ledger still counts
+        Op::Approve(1, 0, 100, START_LEDGER + 20),
+        Op::AdvanceLedgers(11),
+        Op::TransferFrom(0, 1, 2, 1), // Expired
+    ]);
+}
+
+// Where the token departs from the SAC on purpose: it rejects zero amounts and
+// self-transfers, which the SAC accepts as no-ops
+#[test]
+fn test_deliberate_departures_from_the_sac() {
+    let d = Differential::new(1_000);
+    for op in [Op::Transfer(0, 1, 0), Op::Transfer(0, 0, 10), Op::Burn(0, 0)] {
+        assert!(d.apply(&d.sac, op).accepted, "SAC rejected {:?}", op);
+        assert!(!d.apply(&d.pi, op).accepted, "token accepted {:?}", op);
+    }
+    d.assert_same_state(&Op::Burn(0, 0));
+}
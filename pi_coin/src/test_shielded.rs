@@ -0,0 +1,287 @@
+#![cfg(test)]
+// Shielded pool and Groth16 verifier. No circuit prover runs here: the verification key
+// is generated from known trapdoor scalars, which lets the test simulate a valid proof
+// for any public inputs (the Groth16 simulator). The contract only ever sees curve points,
+// so it verifies these proofs exactly as it would proofs from a real setup.
+extern crate std;
+use soroban_sdk::{
+    bytesn,
+    crypto::bls12_381::{Fr, G1Affine, G2Affine},
+    testutils::{Address as _, Events},
+    vec, Address, Bytes, BytesN, Env, IntoVal, Symbol, Vec, U256,
+};
+use crate::groth16::{self, Groth16Proof, VerificationKey};
+use crate::shielded::{self, PrivateTransferInputs, ROOT_HISTORY};
+use crate::{PiCoinContract, PiCoinContractClient, PiCoinError, PiCoinSource, EVENT_SCHEMA_VERSION};
+
+fn fr(env: &Env, value: u32) -> Fr {
+    Fr::from_u256(U256::from_u32(env, value))
+}
+
+// Test setup with its trapdoor: vk = (a*G1, b*G2, g*G2, d*G2, k_i*G1)
+struct Trapdoor {
+    g1: G1Affine,
+    g2: G2Affine,
+    a: Fr,
+    b: Fr,
+    g: Fr,
+    d: Fr,
+    k: Vec<Fr>,
+}
+
+impl Trapdoor {
+    fn new(env: &Env, inputs: u32) -> Self {
+        let g1 = G1Affine::from_bytes(bytesn!(env, 0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1));
+        let g2 = env.crypto().bls12_381().hash_to_g2(&Bytes::from_slice(env, b"g2"), &Bytes::from_slice(env, b"PI-COIN-TEST"));
+        let mut k = Vec::new(env);
+        for i in 0..=inputs {
+            k.push_back(fr(env, 101 + 7 * i));
+        }
+        Trapdoor { g1, g2, a: fr(env, 11), b: fr(env, 13), g: fr(env, 17), d: fr(env, 19), k }
+    }
+
+    fn vk(&self, env: &Env) -> VerificationKey {
+        let mut ic = Vec::new(env);
+        for k in self.k.iter() {
+            ic.push_back(self.g1.clone() * k);
+        }
+        VerificationKey {
+            alpha: self.g1.clone() * self.a.clone(),
+            beta: self.g2.clone() * self.b.clone(),
+            gamma: self.g2.clone() * self.g.clone(),
+            delta: self.g2.clone() * self.d.clone(),
+            ic,
+        }
+    }
+
+    // Simulated proof: A = r*G1, B = s*G2, C = (r*s - a*b - x*g) / d * G1
+    fn prove(&self, env: &Env, inputs: &Vec<Fr>) -> Groth16Proof {
+        let (r, s) = (fr(env, 23), fr(env, 29));
+        let mut x = self.k.get_unchecked(0);
+        for (i, input) in inputs.iter().enumerate() {
+            x = x + input * self.k.get_unchecked(i as u32 + 1);
+        }
+        let c = (r.clone() * s.clone() - self.a.clone() * self.b.clone() - x * self.g.clone()) * self.d.inv();
+        Groth16Proof { a: self.g1.clone() * r, b: self.g2.clone() * s, c: self.g1.clone() * c }
+    }
+}
+
+// Field-sized 32-byte value (top byte below 0x20)
+fn note(env: &Env, seed: u8) -> BytesN<32> {
+    let mut bytes = [seed; 32];
+    bytes[0] = 0x01;
+    BytesN::from_array(env, &bytes)
+}
+
+#[test]
+fn test_groth16_verifier() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited(); // Several pairings outside a contract call
+    let trapdoor = Trapdoor::new(&env, 2);
+    let vk = trapdoor.vk(&env);
+    let inputs = vec![&env, fr(&env, 5), fr(&env, 8)];
+    let proof = trapdoor.prove(&env, &inputs);
+
+    assert!(groth16::verify(&env, &vk, &proof, &inputs));
+    // Any other input, a wrong input count or a tampered proof fails
+    assert!(!groth16::verify(&env, &vk, &proof, &vec![&env, fr(&env, 5), fr(&env, 9)]));
+    assert!(!groth16::verify(&env, &vk, &proof, &vec![&env, fr(&env, 5)]));
+    let tampered = Groth16Proof { c: proof.c.clone() + trapdoor.g1.clone(), ..proof };
+    assert!(!groth16::verify(&env, &vk, &tampered, &inputs));
+}
+
+#[test]
+fn test_private_transfer_shields_moves_and_unshields() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(
+        PiCoinContract,
+        (Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)),
+    );
+    let client = PiCoinContractClient::new(&env, &contract_id);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+
+    let trapdoor = Trapdoor::new(&env, 7);
+    let prove = |inputs: &PrivateTransferInputs| {
+        let values = env.as_contract(&contract_id, || shielded::public_inputs(&env, inputs));
+        trapdoor.prove(&env, &values)
+    };
+
+    // Off until governance installs the circuit's verification key
+    let shield = PrivateTransferInputs {
+        root: client.shielded_root(),
+        public_amount: 600,
+        account: Some(alice.clone()),
+        nullifiers: vec![&env, note(&env, 1), note(&env, 2)], // dummy zero-value inputs
+        commitments: vec![&env, note(&env, 3), note(&env, 4)],
+    };
+    let proof = prove(&shield);
+    assert_eq!(client.try_private_transfer(&proof, &shield), Err(Ok(PiCoinError::PrivateTransfersDisabled)));
+    client.set_shielded_vk(&trapdoor.vk(&env));
+
+    // Shield: 600 PI leaves Alice's public balance for the pool
+    let empty_root = client.shielded_root();
+    client.private_transfer(&proof, &shield);
+    assert_eq!((client.balance(&alice), client.balance(&contract_id)), (400, 600));
+    assert!(client.is_spent(&note(&env, 1)));
+    assert_ne!(client.shielded_root(), empty_root);
+
+    // Replaying the transfer, or any transfer reusing a nullifier, is rejected
+    assert_eq!(client.try_private_transfer(&proof, &shield), Err(Ok(PiCoinError::NullifierSpent)));
+
+    // Fully private: nothing public moves, anyone can submit it
+    let private = PrivateTransferInputs {
+        root: client.shielded_root(),
+        public_amount: 0,
+        account: None,
+        nullifiers: vec![&env, note(&env, 5), note(&env, 6)],
+        commitments: vec![&env, note(&env, 7), note(&env, 8)],
+    };
+    client.private_transfer(&prove(&private), &private);
+    assert_eq!((client.balance(&alice), client.balance(&contract_id)), (400, 600));
+
+    // Unshield 250 to Bob. The proof binds Bob as recipient: swapping the account in the
+    // public inputs invalidates it.
+    let unshield = PrivateTransferInputs {
+        root: client.shielded_root(),
+        public_amount: -250,
+        account: Some(bob.clone()),
+        nullifiers: vec![&env, note(&env, 9), note(&env, 10)],
+        commitments: vec![&env, note(&env, 11), note(&env, 12)],
+    };
+    let proof = prove(&unshield);
+    let redirected = PrivateTransferInputs { account: Some(alice.clone()), ..unshield.clone() };
+    assert_eq!(client.try_private_transfer(&proof, &redirected), Err(Ok(PiCoinError::InvalidProof)));
+    client.private_transfer(&proof, &unshield);
+    assert_eq!((client.balance(&bob), client.balance(&contract_id)), (250, 350));
+    assert_eq!(client.shielded_supply(), 350);
+    assert_eq!(client.provenance_of(&bob).get(0).unwrap().amount, 250);
+
+    // Malformed public inputs are rejected before verification
+    let mut bad = PrivateTransferInputs {
+        root: client.shielded_root(),
+        public_amount: 0,
+        account: None,
+        nullifiers: vec![&env, note(&env, 13), note(&env, 13)],
+        commitments: vec![&env, note(&env, 14), note(&env, 15)],
+    };
+    assert_eq!(client.try_private_transfer(&prove(&bad), &bad), Err(Ok(PiCoinError::InvalidProof)));
+    bad.nullifiers = vec![&env, note(&env, 13), BytesN::from_array(&env, &[0xff; 32])];
+    assert_eq!(client.try_private_transfer(&prove(&bad), &bad), Err(Ok(PiCoinError::InvalidProof)));
+    bad.nullifiers = vec![&env, note(&env, 13), note(&env, 16)];
+    bad.root = BytesN::from_array(&env, &[0; 32]);
+    assert_eq!(client.try_private_transfer(&prove(&bad), &bad), Err(Ok(PiCoinError::InvalidProof)));
+}
+
+#[test]
+fn test_deposit_and_withdraw_keep_supply_auditable() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(
+        PiCoinContract,
+        (Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)),
+    );
+    let client = PiCoinContractClient::new(&env, &contract_id);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let secret = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(client.try_deposit_shielded(&alice, &100, &secret), Err(Ok(PiCoinError::PrivateTransfersDisabled)));
+    let trapdoor = Trapdoor::new(&env, 7);
+    client.set_shielded_vk(&trapdoor.vk(&env));
+
+    // The contract commits to the public amount itself: no proof needed to deposit
+    let commitment = env.as_contract(&contract_id, || shielded::note_commitment(&env, 300, &secret));
+    assert_eq!(client.deposit_shielded(&alice, &300, &secret), 0);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "shielded_deposit"), alice.clone(), EVENT_SCHEMA_VERSION).into_val(&env),
+                (commitment, 0u32, 300i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.deposit_shielded(&alice, &200, &secret), 1);
+    assert_eq!((client.balance(&alice), client.balance(&contract_id), client.shielded_supply()), (500, 500, 500));
+
+    // Notes hold at most 2^64 - 1; zero and negative deposits are meaningless
+    assert_eq!(client.try_deposit_shielded(&alice, &0, &secret), Err(Ok(PiCoinError::InvalidNoteAmount)));
+    assert_eq!(client.try_deposit_shielded(&alice, &-5, &secret), Err(Ok(PiCoinError::InvalidNoteAmount)));
+    assert_eq!(client.try_deposit_shielded(&alice, &(1 << 64), &secret), Err(Ok(PiCoinError::InvalidNoteAmount)));
+
+    // Withdraw 450 to Bob: membership proof plus nullifiers, change back into a note
+    let withdraw = PrivateTransferInputs {
+        root: client.shielded_root(),
+        public_amount: -450,
+        account: Some(bob.clone()),
+        nullifiers: vec![&env, note(&env, 1), note(&env, 2)],
+        commitments: vec![&env, note(&env, 3), note(&env, 4)],
+    };
+    let values = env.as_contract(&contract_id, || shielded::public_inputs(&env, &withdraw));
+    client.private_transfer(&trapdoor.prove(&env, &values), &withdraw);
+    assert_eq!((client.balance(&bob), client.balance(&contract_id), client.shielded_supply()), (450, 50, 50));
+
+    // No proof can take out more than the notes hold
+    let drain = PrivateTransferInputs {
+        root: client.shielded_root(),
+        public_amount: -51,
+        account: Some(bob.clone()),
+        nullifiers: vec![&env, note(&env, 5), note(&env, 6)],
+        commitments: vec![&env, note(&env, 7), note(&env, 8)],
+    };
+    let values = env.as_contract(&contract_id, || shielded::public_inputs(&env, &drain));
+    assert_eq!(client.try_private_transfer(&trapdoor.prove(&env, &values), &drain), Err(Ok(PiCoinError::InsufficientBalance)));
+    assert_eq!(client.balance(&alice) + client.balance(&bob) + client.shielded_supply(), 1_000);
+}
+
+#[test]
+fn test_commitment_tree_keeps_recent_roots() {
+    let env = Env::default();
+    let contract_id = env.register(
+        PiCoinContract,
+        (Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)),
+    );
+    env.as_contract(&contract_id, || {
+        let first_root = shielded::root(&env);
+        let mut roots = vec![&env, first_root.clone()];
+        for i in 0..ROOT_HISTORY / 2 {
+            let inputs = PrivateTransferInputs {
+                root: shielded::root(&env),
+                public_amount: 0,
+                account: None,
+                nullifiers: vec![&env, note(&env, 2 * i as u8), note(&env, 2 * i as u8 + 1)],
+                commitments: vec![&env, note(&env, 2 * i as u8), note(&env, 2 * i as u8 + 1)],
+            };
+            assert_eq!(shielded::apply(&env, &inputs), 2 * i);
+            roots.push_back(shielded::root(&env));
+        }
+        // Two leaves per transfer: the initial root has just left the history
+        let tree = shielded::tree(&env);
+        assert_eq!(tree.roots.len(), ROOT_HISTORY);
+        assert!(!tree.roots.contains(&first_root));
+        assert!(tree.roots.contains(roots.get_unchecked(1)));
+
+        // The incremental root matches a tree built from scratch
+        let mut level: std::vec::Vec<[u8; 32]> = (0..ROOT_HISTORY).map(|i| note(&env, i as u8).to_array()).collect();
+        let mut zero = [0u8; 32];
+        for _ in 0..crate::shielded::TREE_DEPTH {
+            if level.len() % 2 == 1 {
+                level.push(zero);
+            }
+            level = level.chunks(2).map(|pair| sha256_pair(&env, &pair[0], &pair[1])).collect();
+            zero = sha256_pair(&env, &zero, &zero);
+        }
+        assert_eq!(shielded::root(&env).to_array(), level[0]);
+    });
+}
+
+fn sha256_pair(env: &Env, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut bytes = Bytes::from_array(env, left);
+    bytes.extend_from_array(right);
+    env.crypto().sha256(&bytes).to_array()
+}
@@ -0,0 +1,150 @@
+// Property-test and fuzzing harness: replays arbitrary operation sequences against a
+// registered PiCoinContract and checks the token invariants after every step.
+// Shared by the proptest suite (test_props.rs) and the cargo-fuzz targets (fuzz/).
+
+// #[derive(Arbitrary)] expects `std` to be in scope, but the contract is a no_std crate
+extern crate std;
+
+use soroban_sdk::testutils::arbitrary::{arbitrary, Arbitrary, SorobanArbitrary};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, Env, IntoVal, Map, Vec};
+use std::vec::Vec as StdVec;
+
+use crate::{balance, PiCoinContract, PiCoinContractClient, PiCoinSource};
+
+// Holders the generated operations pick from (index 0 also collects transfer fees)
+pub const ACTORS: u32 = 4;
+// Longest sequence replayed per case; longer inputs are truncated
+pub const MAX_OPS: usize = 32;
+// Collateral check_collateral reports for any minter (simulated full backing)
+pub const SIMULATED_COLLATERAL: i128 = 100_000_000_000;
+
+// One token operation; actors are indexes into the harness' holders (taken modulo ACTORS).
+// Mint amounts are reduced below twice the collateral so both sides of the limit get
+// exercised; the other amounts are u32 so they stay comparable to minted balances.
+#[derive(Arbitrary, Debug, Clone)]
+pub enum Op {
+    Mint { to: u8, amount: u64, source: <PiCoinSource as SorobanArbitrary>::Prototype },
+    Transfer { from: u8, to: u8, amount: u32 },
+    Approve { from: u8, spender: u8, amount: u32 },
+    TransferFrom { spender: u8, from: u8, to: u8, amount: u32 },
+    BatchTransfer { from: u8, transfers: StdVec<(u8, u32)> },
+    Burn { from: u8, amount: u32 },
+    SetFee { fee_bps: u16 },
+}
+
+pub struct Harness<'a> {
+    pub env: Env,
+    pub client: PiCoinContractClient<'a>,
+    pub actors: Vec<Address>,
+    // Ghost supply: everything minted minus everything burned
+    pub supply: i128,
+}
+
+impl Harness<'_> {
+    pub fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        // Turn off the CPU/memory budget: long sequences are the point
+        env.cost_estimate().budget().reset_unlimited();
+        let contract_id = env.register(
+            PiCoinContract,
+            (Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)),
+        );
+        let client = PiCoinContractClient::new(&env, &contract_id);
+        let mut actors = Vec::new(&env);
+        for _ in 0..ACTORS {
+            actors.push_back(Address::generate(&env));
+        }
+        client.set_fee_collector(&actors.get(0).unwrap());
+        Harness { env, client, actors, supply: 0 }
+    }
+
+    // Apply `ops` in order, asserting the invariants after each one
+    pub fn run(&mut self, ops: &[Op]) {
+        for op in ops.iter().take(MAX_OPS) {
+            let before = self.balances();
+            let applied = self.apply(op);
+            if !applied {
+                // A rejected operation must leave every balance untouched
+                assert_eq!(self.balances(), before, "rejected {:?} changed balances", op);
+            }
+            self.check_invariants(op);
+        }
+    }
+
+    // Run one operation; returns whether the contract accepted it
+    fn apply(&mut self, op: &Op) -> bool {
+        let env = &self.env;
+        match op.clone() {
+            Op::Mint { to, amount, source } => {
+                let amount = amount as i128 % (2 * SIMULATED_COLLATERAL);
+                let source: PiCoinSource = source.into_val(env);
+                let applied = self.client.try_mint(&self.actor(to), &amount, &source).is_ok();
+                if applied {
+                    // Collateral ratio: every accepted mint is backed 1:1 by the minter's collateral
+                    assert!(amount <= SIMULATED_COLLATERAL, "mint of {} exceeds its collateral", amount);
+                    self.supply += amount;
+                }
+                applied
+            }
+            Op::Transfer { from, to, amount } => {
+                self.client.try_transfer(&self.actor(from), &self.actor(to), &(amount as i128)).is_ok()
+            }
+            Op::Approve { from, spender, amount } => {
+                let expiration_ledger = env.ledger().sequence() + 1_000;
+                self.client.try_approve(&self.actor(from), &self.actor(spender), &(amount as i128), &expiration_ledger).is_ok()
+            }
+            Op::TransferFrom { spender, from, to, amount } => {
+                self.client.try_transfer_from(&self.actor(spender), &self.actor(from), &self.actor(to), &(amount as i128)).is_ok()
+            }
+            Op::BatchTransfer { from, transfers } => {
+                let mut batch = vec![env];
+                for (to, amount) in transfers.iter() {
+                    batch.push_back((self.actor(*to), *amount as i128));
+                }
+                self.client.try_batch_transfer(&self.actor(from), &batch).is_ok()
+            }
+            Op::Burn { from, amount } => {
+                let amount = amount as i128;
+                let applied = self.client.try_burn(&self.actor(from), &amount).is_ok();
+                if applied {
+                    self.supply -= amount;
+                }
+                applied
+            }
+            Op::SetFee { fee_bps } => self.client.try_set_fee(&(fee_bps as u32)).is_ok(),
+        }
+    }
+
+    // Invariants: balances add up to the supply and none is negative
+    fn check_invariants(&self, op: &Op) {
+        let mut total = 0i128;
+        for (holder, balance) in self.balances().iter() {
+            assert!(balance >= 0, "{:?} left {:?} with negative balance {}", op, holder, balance);
+            total += balance;
+        }
+        assert_eq!(total, self.supply, "balances no longer sum to the supply after {:?}", op);
+    }
+
+    // Every actor's balance entry, read straight from storage
+    pub fn balances(&self) -> Map<Address, i128> {
+        self.env.as_contract(&self.client.address, || {
+            let mut balances = Map::new(&self.env);
+            for actor in self.actors.iter() {
+                balances.set(actor.clone(), balance::read(&self.env, &actor).unwrap());
+            }
+            balances
+        })
+    }
+
+    fn actor(&self, index: u8) -> Address {
+        self.actors.get(index as u32 % ACTORS).unwrap()
+    }
+}
+
+impl Default for Harness<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
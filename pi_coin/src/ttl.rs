@@ -0,0 +1,87 @@
+// Storage TTL policy: the instance and per-holder persistent entries are extended
+// whenever they are touched and their remaining TTL has fallen below a threshold, so
+// active state never archives. Thresholds are governance-configurable; anyone can also
+// extend entries explicitly (extend_instance_ttl / extend_balance_ttl) to keep idle
+// state alive.
+//
+// Storage classes and what expiry means for each:
+// - Instance (PiCoinData, admin, fee collector, limits, roots, flash state, this config):
+//   archives with the contract and is restored with it.
+// - Persistent (balances, provenance lots, attestations, signature and permit nonces,
+//   compliance/fee/bypass lists): archived, never lost. An invocation whose footprint
+//   touches an archived entry restores it with its value, and restore_balance re-extends
+//   a holder's entries afterwards. Nothing that guards against replay or holds value may
+//   live anywhere else.
+// - Temporary (allowances, rate-limit windows): deleted for good on expiry. Their TTL
+//   matches their validity (approval expiry, window length), so expiry only drops state
+//   that no longer counts.
+use soroban_sdk::{contracttype, Env, IntoVal, Val};
+use crate::PiCoinError;
+
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+// Extend an entry back to `*_extend_to` ledgers once its TTL drops below `*_threshold`
+#[contracttype]
+#[derive(Clone)]
+pub struct TtlConfig {
+    pub instance_threshold: u32,
+    pub instance_extend_to: u32,
+    pub persistent_threshold: u32, // Balances, provenance lots
+    pub persistent_extend_to: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum TtlKey {
+    Config,
+}
+
+pub fn config(env: &Env) -> TtlConfig {
+    env.storage().instance().get(&TtlKey::Config).unwrap_or(TtlConfig {
+        instance_threshold: 6 * DAY_IN_LEDGERS,
+        instance_extend_to: 7 * DAY_IN_LEDGERS,
+        persistent_threshold: 29 * DAY_IN_LEDGERS,
+        persistent_extend_to: 30 * DAY_IN_LEDGERS,
+    })
+}
+
+pub fn set_config(env: &Env, config: &TtlConfig) -> Result<(), PiCoinError> {
+    let max_ttl = env.storage().max_ttl();
+    if config.instance_threshold > config.instance_extend_to
+        || config.persistent_threshold > config.persistent_extend_to
+        || config.instance_extend_to > max_ttl
+        || config.persistent_extend_to > max_ttl
+    {
+        return Err(PiCoinError::InvalidTtl);
+    }
+    env.storage().instance().set(&TtlKey::Config, config);
+    Ok(())
+}
+
+// Bump-on-access for the contract instance (code and instance storage)
+pub fn bump_instance(env: &Env) {
+    let config = config(env);
+    env.storage().instance().extend_ttl(config.instance_threshold, config.instance_extend_to);
+}
+
+// Bump-on-access for a persistent entry that exists
+pub fn bump_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let config = config(env);
+    env.storage().persistent().extend_ttl(key, config.persistent_threshold, config.persistent_extend_to);
+}
+
+// Explicit extension: the full configured lifetime, regardless of the threshold
+pub fn extend_instance(env: &Env) {
+    let config = config(env);
+    env.storage().instance().extend_ttl(config.instance_extend_to, config.instance_extend_to);
+}
+
+// Explicit extension of a persistent entry; returns false if it does not exist
+pub fn extend_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) -> bool {
+    if !env.storage().persistent().has(key) {
+        return false;
+    }
+    let config = config(env);
+    env.storage().persistent().extend_ttl(key, config.persistent_extend_to, config.persistent_extend_to);
+    true
+}
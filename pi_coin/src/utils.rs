@@ -1,6 +1,8 @@
 #![no_std]
 use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
-use crate::PiCoinSource; // Import from main contract
+use crate::{PiCoinError, PiCoinSource}; // Import from main contract
+use crate::fixed_point::{self, Rounding};
+use crate::{evm, provenance};
 
 #[contract]
 pub struct PiCoinUtils;
@@ -15,26 +17,75 @@ impl PiCoinUtils {
             return Err(());
         }
         
-        // Approximate π for hyper-tech pegging (π ≈ 3.14159, scaled to micro-units)
-        let pi_approx = 3_141_590_000; // 3.14159 * 1e9 for precision
-        let adjusted_peg = base_value + (pi_approx / 1000); // Dynamic adjustment
+        // π ≈ 3.14159 (6 significant digits) in micro-units; see calculate_pi_peg_v2 for
+        // other precisions
+        let adjusted_peg = fixed_point::pi_peg(base_value, 1_000_000, 6, Rounding::HalfUp).map_err(|_| ())?;
         log!(&env, "Pi-based peg calculated for {} source: {} - Ultimate mathematical stability", source, adjusted_peg);
         Ok(adjusted_peg)
     }
 
-    // Quantum-resistant provenance verifier (anti-duplication utility)
+    // π-based peg adjustment with explicit precision: `base_value` plus π in the same
+    // units, where one whole dollar is `unit` (1_000_000 for the micro-unit peg). π is
+    // taken to `digits` significant digits (up to 18) and every step rounds by `rounding`.
+    // calculate_pi_peg(base) == calculate_pi_peg_v2(base, 1_000_000, 6, HalfUp).
+    pub fn calculate_pi_peg_v2(
+        env: Env,
+        base_value: i128,
+        source: PiCoinSource,
+        unit: i128,
+        digits: u32,
+        rounding: Rounding,
+    ) -> Result<i128, PiCoinError> {
+        if source == PiCoinSource::Invalid {
+            return Err(PiCoinError::InvalidSource);
+        }
+        let adjusted_peg = fixed_point::pi_peg(base_value, unit, digits, rounding)?;
+        log!(&env, "Pi-based peg (v2, {} digits) calculated: {}", digits, adjusted_peg);
+        Ok(adjusted_peg)
+    }
+
+    // Quantum-resistant provenance verifier (anti-duplication utility). `expected_hash` is
+    // sha256 of the holder's address XDR (PiCoinContract::provenance_hash). Hashes made by
+    // the earlier version covered a host object handle, which differs between executions:
+    // regenerate them with provenance_hash.
     pub fn verify_provenance_hash(env: Env, holder: Address, expected_hash: BytesN<32>, source: PiCoinSource) -> Result<bool, ()> {
         if source == PiCoinSource::Invalid {
             log!(&env, "Provenance verification rejected: Invalid source");
             return Err(());
         }
         
-        let computed_hash = env.crypto().sha256(&Bytes::from_slice(&env, &holder.to_val().to_be_bytes()));
-        let is_valid = computed_hash == expected_hash;
+        let is_valid = provenance::holder_hash(&env, &holder) == expected_hash;
         log!(&env, "Quantum provenance verified for {} source: {} - Unmatched integrity", source, is_valid);
         Ok(is_valid)
     }
 
+    // EVM interop: keccak256 of `data`, as Ethereum hashes it
+    pub fn keccak256(env: Env, data: Bytes) -> BytesN<32> {
+        evm::keccak256(&env, &data).to_bytes()
+    }
+
+    // EVM interop: Digest an Ethereum wallet signs for `message` under personal_sign (EIP-191)
+    pub fn eth_message_hash(env: Env, message: Bytes) -> BytesN<32> {
+        evm::eth_message_hash(&env, &message).to_bytes()
+    }
+
+    // EVM interop: Ethereum address of an uncompressed secp256k1 public key
+    pub fn eth_address(env: Env, public_key: BytesN<65>) -> BytesN<20> {
+        evm::eth_address(&env, &public_key)
+    }
+
+    // EVM interop: Recover the Ethereum address that personal_sign-ed `message`;
+    // `signature` is r || s || v as wallets return it
+    pub fn recover_eth_signer(env: Env, message: Bytes, signature: BytesN<65>) -> Result<BytesN<20>, PiCoinError> {
+        evm::recover_eth_signer(&env, &message, &signature)
+    }
+
+    // EVM interop: Whether `signer` personal_sign-ed `message` (e.g. a bridge attestation
+    // or a cross-chain governance vote)
+    pub fn verify_eth_signature(env: Env, message: Bytes, signature: BytesN<65>, signer: BytesN<20>) -> Result<bool, PiCoinError> {
+        Ok(evm::recover_eth_signer(&env, &message, &signature)? == signer)
+    }
+
     // AI simulation helper: Predict market stability (hyper-tech analytics)
     pub fn ai_predict_stability(env: Env, current_price: i128, source: PiCoinSource) -> Result<i128, ()> {
         if source == PiCoinSource::Invalid {
@@ -44,24 +95,11 @@ impl PiCoinUtils {
         
         // Simulated AI: Use ledger data for prediction (e.g., trend analysis)
         let trend_factor = (env.ledger().sequence() as i128 % 100) / 10;
-        let predicted_price = current_price + trend_factor * 1000;
+        let predicted_price = current_price.checked_add(trend_factor * 1000).ok_or(())?;
         log!(&env, "AI stability predicted for {} source: {} - Global market foresight", source, predicted_price);
         Ok(predicted_price)
     }
 
-    // Global integration utility: Simulate DEX bridging (for worldwide payment recognition)
-    pub fn simulate_dex_bridge(env: Env, amount: i128, target_chain: Symbol, source: PiCoinSource) -> Result<(), ()> {
-        if source == PiCoinSource::Invalid {
-            log!(&env, "DEX bridge rejected: Invalid source - No global access");
-            return Err(());
-        }
-        
-        // Hyper-tech: Simulate cross-chain event emission
-        env.events().publish((Symbol::new(&env, "dex_bridge"), amount), target_chain);
-        log!(&env, "DEX bridge simulated for {} PI from {} source to {} - Worldwide payment enabled", amount, source, target_chain);
-        Ok(())
-    }
-
     // Utility for batch provenance check (efficient for large holders)
     pub fn batch_verify_sources(env: Env, holders: Vec<Address>, sources: Vec<PiCoinSource>) -> Result<Vec<bool>, ()> {
         if holders.len() != sources.len() {
@@ -0,0 +1,32 @@
+// Input validation for the entry points that create, move, destroy or allow PI. Every
+// amount is checked here before it reaches the balance or supply accounting, which
+// would otherwise run backwards on a negative amount.
+use soroban_sdk::Address;
+use crate::PiCoinError;
+
+// An amount of PI to mint, move or destroy: InvalidAmount unless positive
+pub fn amount(amount: i128) -> Result<(), PiCoinError> {
+    if amount <= 0 {
+        return Err(PiCoinError::InvalidAmount);
+    }
+    Ok(())
+}
+
+// An allowance or a fee, where zero is meaningful (a zero allowance revokes one):
+// InvalidAmount if negative
+pub fn non_negative(amount: i128) -> Result<(), PiCoinError> {
+    if amount < 0 {
+        return Err(PiCoinError::InvalidAmount);
+    }
+    Ok(())
+}
+
+// A transfer of `amount` from `from` to `to`: a positive amount between two different
+// accounts (SelfTransfer otherwise, which would only churn lots and fees)
+pub fn transfer(from: &Address, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+    self::amount(amount)?;
+    if from == to {
+        return Err(PiCoinError::SelfTransfer);
+    }
+    Ok(())
+}
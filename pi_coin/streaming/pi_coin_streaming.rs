@@ -0,0 +1,134 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+#[path = "../src/receive.rs"]
+mod receive;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub sender: Address,
+    pub recipient: Address,
+    pub rate_per_second: i128, // PI accrued to the recipient each second
+    pub deposit: i128, // Locked up front; the stream runs dry once it has all accrued
+    pub start: u64, // Ledger timestamp the stream opened
+    pub withdrawn: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum StreamKey {
+    NextId,
+    Stream(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum StreamError {
+    StreamNotFound = 1,
+    InvalidStream = 2,
+    Unauthorized = 3,
+    NothingToWithdraw = 4,
+}
+
+#[contract]
+pub struct PiCoinStreaming;
+
+#[contractimpl]
+impl PiCoinStreaming {
+    // Constructor: pay-per-second PI streams (payroll-style global payments)
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "Streaming initialized: per-second PI payments ready");
+    }
+
+    // Sender: Lock `deposit` PI and start streaming it to `recipient` at
+    // `rate_per_second`. The stream holds what the contract actually received, which is
+    // less than `deposit` when the token charges a transfer fee. Returns the stream id.
+    pub fn open_stream(env: Env, sender: Address, recipient: Address, rate_per_second: i128, deposit: i128) -> Result<u64, StreamError> {
+        sender.require_auth();
+        if rate_per_second <= 0 || deposit <= 0 || sender == recipient {
+            return Err(StreamError::InvalidStream);
+        }
+        let deposit = receive::pull(&env, &Self::pi(&env).address, &sender, deposit).unwrap_or(0);
+        if deposit <= 0 {
+            return Err(StreamError::InvalidStream);
+        }
+
+        let id: u64 = env.storage().instance().get(&StreamKey::NextId).unwrap_or(1);
+        env.storage().instance().set(&StreamKey::NextId, &(id + 1));
+        let stream = Stream { sender: sender.clone(), recipient: recipient.clone(), rate_per_second, deposit, start: env.ledger().timestamp(), withdrawn: 0 };
+        env.storage().persistent().set(&StreamKey::Stream(id), &stream);
+        env.events().publish((Symbol::new(&env, "stream_opened"), sender, recipient, id), (rate_per_second, deposit));
+        Ok(id)
+    }
+
+    // Recipient: Withdraw everything accrued so far. Returns the amount paid.
+    pub fn withdraw(env: Env, stream_id: u64) -> Result<i128, StreamError> {
+        let mut stream = Self::load(&env, stream_id)?;
+        stream.recipient.require_auth();
+        let amount = Self::accrued(&stream, env.ledger().timestamp()) - stream.withdrawn;
+        if amount <= 0 {
+            return Err(StreamError::NothingToWithdraw);
+        }
+        stream.withdrawn += amount;
+        env.storage().persistent().set(&StreamKey::Stream(stream_id), &stream);
+        Self::pi(&env).transfer(&env.current_contract_address(), &stream.recipient, &amount);
+        env.events().publish((Symbol::new(&env, "stream_withdrawn"), stream.recipient, stream_id), amount);
+        Ok(amount)
+    }
+
+    // Sender or recipient: Close the stream with pro-rata settlement - the recipient gets
+    // what has accrued and not been withdrawn, the sender gets the rest back.
+    // Returns (paid to recipient, refunded to sender).
+    pub fn cancel(env: Env, caller: Address, stream_id: u64) -> Result<(i128, i128), StreamError> {
+        caller.require_auth();
+        let stream = Self::load(&env, stream_id)?;
+        if caller != stream.sender && caller != stream.recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        let accrued = Self::accrued(&stream, env.ledger().timestamp());
+        let to_recipient = accrued - stream.withdrawn;
+        let to_sender = stream.deposit - accrued;
+        env.storage().persistent().remove(&StreamKey::Stream(stream_id));
+
+        let pi = Self::pi(&env);
+        if to_recipient > 0 {
+            pi.transfer(&env.current_contract_address(), &stream.recipient, &to_recipient);
+        }
+        if to_sender > 0 {
+            pi.transfer(&env.current_contract_address(), &stream.sender, &to_sender);
+        }
+        env.events().publish((Symbol::new(&env, "stream_cancelled"), caller, stream_id), (to_recipient, to_sender));
+        Ok((to_recipient, to_sender))
+    }
+
+    // View: Stream details
+    pub fn stream(env: Env, stream_id: u64) -> Result<Stream, StreamError> {
+        Self::load(&env, stream_id)
+    }
+
+    // View: Amount `withdraw` would pay right now
+    pub fn withdrawable(env: Env, stream_id: u64) -> Result<i128, StreamError> {
+        let stream = Self::load(&env, stream_id)?;
+        Ok(Self::accrued(&stream, env.ledger().timestamp()) - stream.withdrawn)
+    }
+
+    // Helper: Total accrued to the recipient by `now`, capped at the deposit
+    fn accrued(stream: &Stream, now: u64) -> i128 {
+        let elapsed = now.saturating_sub(stream.start) as i128;
+        stream.rate_per_second.saturating_mul(elapsed).min(stream.deposit)
+    }
+
+    fn load(env: &Env, stream_id: u64) -> Result<Stream, StreamError> {
+        env.storage().persistent().get(&StreamKey::Stream(stream_id)).ok_or(StreamError::StreamNotFound)
+    }
+
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap();
+        token::TokenClient::new(env, &pi_token)
+    }
+}
+
+mod test;
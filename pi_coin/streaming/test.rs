@@ -0,0 +1,100 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env};
+use crate::{PiCoinStreaming, PiCoinStreamingClient, StreamError};
+use pi_coin_testutils::TokenBuilder;
+
+#[test]
+fn test_withdraw_accrued_and_cancel_pro_rata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(100);
+
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&employer, 1_000).build();
+    let contract_id = env.register(PiCoinStreaming, (&pi.address,));
+    let client = PiCoinStreamingClient::new(&env, &contract_id);
+
+    // 2 PI per second out of a 1_000 PI deposit
+    let id = client.open_stream(&employer, &employee, &2, &1_000);
+    assert_eq!(pi.balance(&employer), 0);
+
+    env.ledger().set_timestamp(200);
+    assert_eq!(client.withdraw(&id), 200);
+    assert_eq!(pi.balance(&employee), 200);
+
+    // Cancelling at 150s pays the extra 100 accrued and refunds the unstreamed 700
+    env.ledger().set_timestamp(250);
+    let outsider = Address::generate(&env);
+    assert_eq!(client.try_cancel(&outsider, &id), Err(Ok(StreamError::Unauthorized)));
+    assert_eq!(client.cancel(&employer, &id), (100, 700));
+    assert_eq!(pi.balance(&employee), 300);
+    assert_eq!(pi.balance(&employer), 700);
+    assert_eq!(pi.balance(&contract_id), 0);
+
+    // A cancelled stream is gone
+    assert_eq!(client.try_stream(&id), Err(Ok(StreamError::StreamNotFound)));
+    assert_eq!(client.try_withdraw(&id), Err(Ok(StreamError::StreamNotFound)));
+    assert_eq!(client.try_cancel(&employer, &id), Err(Ok(StreamError::StreamNotFound)));
+}
+
+#[test]
+fn test_deposit_is_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(100);
+
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&employer, 1_000).build();
+    let contract_id = env.register(PiCoinStreaming, (&pi.address,));
+    let client = PiCoinStreamingClient::new(&env, &contract_id);
+
+    // A 1% fee leaves 990 of the 1_000 in the contract, and the stream runs dry there
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    let id = client.open_stream(&employer, &employee, &2, &1_000);
+    assert_eq!(client.stream(&id).deposit, 990);
+    assert_eq!(pi.balance(&contract_id), 990);
+
+    env.ledger().set_timestamp(10_000);
+    assert_eq!(client.withdrawable(&id), 990);
+    client.withdraw(&id);
+    assert_eq!(pi.balance(&contract_id), 0);
+    assert_eq!(client.cancel(&employer, &id), (0, 0));
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_drained_streams() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(100);
+
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&employer, 1_000).build();
+    let contract_id = env.register(PiCoinStreaming, (&pi.address,));
+    let client = PiCoinStreamingClient::new(&env, &contract_id);
+
+    // The sender cannot lock more than it holds, nor stream to itself
+    assert!(client.try_open_stream(&employer, &employee, &1, &1_001).is_err());
+    assert_eq!(client.try_open_stream(&employer, &employer, &1, &100), Err(Ok(StreamError::InvalidStream)));
+    assert_eq!(client.try_open_stream(&employer, &employee, &0, &100), Err(Ok(StreamError::InvalidStream)));
+    let id = client.open_stream(&employer, &employee, &10, &100);
+
+    // Nothing has accrued yet
+    assert_eq!(client.try_withdraw(&id), Err(Ok(StreamError::NothingToWithdraw)));
+
+    // Only the recipient's signature withdraws
+    env.ledger().set_timestamp(105);
+    env.set_auths(&[]);
+    assert!(client.try_withdraw(&id).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.withdraw(&id), 50);
+
+    // Past the deposit the stream is drained and pays no more
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(client.withdraw(&id), 50);
+    assert_eq!(client.try_withdraw(&id), Err(Ok(StreamError::NothingToWithdraw)));
+    assert_eq!(pi.balance(&employee), 100);
+}
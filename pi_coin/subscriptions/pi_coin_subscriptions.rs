@@ -0,0 +1,178 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub merchant: Address, // Receives every charge
+    pub price: i128, // PI per period
+    pub period: u64, // Seconds between charges
+    pub grace_period: u64, // Seconds a failed charge may stay unpaid before cancelling
+    pub active: bool, // Retired plans take no new subscribers and lapse at the next charge
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub subscriber: Address,
+    pub plan_id: u64,
+    pub next_charge: u64, // Ledger timestamp the next period is due
+}
+
+// What `charge_due` did
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChargeOutcome {
+    Renewed, // The period was paid
+    PastDue, // Payment failed; still inside the grace period
+    Cancelled, // Payment failed past the grace period, or the plan was retired
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum SubscriptionKey {
+    NextPlanId,
+    NextSubscriptionId,
+    Plan(u64),
+    Subscription(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SubscriptionError {
+    PlanNotFound = 1,
+    SubscriptionNotFound = 2,
+    InvalidPlan = 3,
+    PlanRetired = 4,
+    NotDue = 5,
+}
+
+#[contract]
+pub struct PiCoinSubscriptions;
+
+#[contractimpl]
+impl PiCoinSubscriptions {
+    // Constructor: recurring PI billing. Subscribers approve this contract on the PI token
+    // and charges pull straight from their balance to the merchant, so nothing is
+    // pre-funded. Anyone may run `charge_due` (the merchant's backend, a keeper). A PI
+    // transfer fee comes out of the price, so payment events carry what the merchant
+    // actually received.
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "Subscriptions initialized: recurring PI billing with grace periods");
+    }
+
+    // Merchant: Offer a plan charging `price` PI every `period` seconds. Returns its id.
+    pub fn create_plan(env: Env, merchant: Address, price: i128, period: u64, grace_period: u64) -> Result<u64, SubscriptionError> {
+        merchant.require_auth();
+        if price <= 0 || period == 0 || grace_period >= period {
+            return Err(SubscriptionError::InvalidPlan);
+        }
+        let id: u64 = env.storage().instance().get(&SubscriptionKey::NextPlanId).unwrap_or(1);
+        env.storage().instance().set(&SubscriptionKey::NextPlanId, &(id + 1));
+        let plan = Plan { merchant: merchant.clone(), price, period, grace_period, active: true };
+        env.storage().persistent().set(&SubscriptionKey::Plan(id), &plan);
+        env.events().publish((Symbol::new(&env, "plan_created"), merchant, id), (price, period, grace_period));
+        Ok(id)
+    }
+
+    // Merchant: Retire a plan. Existing subscriptions are cancelled at their next charge.
+    pub fn retire_plan(env: Env, plan_id: u64) -> Result<(), SubscriptionError> {
+        let mut plan = Self::plan(env.clone(), plan_id)?;
+        plan.merchant.require_auth();
+        plan.active = false;
+        env.storage().persistent().set(&SubscriptionKey::Plan(plan_id), &plan);
+        Ok(())
+    }
+
+    // Subscribe to `plan_id`, paying the first period now. Later periods are charged
+    // against the allowance the subscriber gives this contract on the PI token. Returns
+    // the subscription id.
+    pub fn subscribe(env: Env, subscriber: Address, plan_id: u64) -> Result<u64, SubscriptionError> {
+        subscriber.require_auth();
+        let plan = Self::plan(env.clone(), plan_id)?;
+        if !plan.active {
+            return Err(SubscriptionError::PlanRetired);
+        }
+        let id: u64 = env.storage().instance().get(&SubscriptionKey::NextSubscriptionId).unwrap_or(1);
+        env.storage().instance().set(&SubscriptionKey::NextSubscriptionId, &(id + 1));
+        let subscription = Subscription { subscriber: subscriber.clone(), plan_id, next_charge: env.ledger().timestamp() + plan.period };
+        env.storage().persistent().set(&SubscriptionKey::Subscription(id), &subscription);
+        let pi = Self::pi(&env);
+        let before = pi.balance(&plan.merchant);
+        pi.transfer(&subscriber, &plan.merchant, &plan.price);
+        let received = pi.balance(&plan.merchant) - before;
+        env.events().publish((Symbol::new(&env, "subscribed"), subscriber, id), (plan_id, received, subscription.next_charge));
+        Ok(id)
+    }
+
+    // Permissionless: Charge the period due on subscription `id`. A failed payment leaves
+    // the period due until the grace period ends, then cancels the subscription. Periods
+    // missed in between are charged one call at a time.
+    pub fn charge_due(env: Env, id: u64) -> Result<ChargeOutcome, SubscriptionError> {
+        let mut subscription = Self::subscription(env.clone(), id)?;
+        let plan = Self::plan(env.clone(), subscription.plan_id)?;
+        let now = env.ledger().timestamp();
+        if now < subscription.next_charge {
+            return Err(SubscriptionError::NotDue);
+        }
+        if !plan.active {
+            Self::cancel(&env, id, &subscription, Symbol::new(&env, "plan_retired"));
+            return Ok(ChargeOutcome::Cancelled);
+        }
+
+        let pi = Self::pi(&env);
+        let before = pi.balance(&plan.merchant);
+        let paid = pi
+            .try_transfer_from(&env.current_contract_address(), &subscription.subscriber, &plan.merchant, &plan.price)
+            .is_ok_and(|result| result.is_ok());
+        if paid {
+            let received = pi.balance(&plan.merchant) - before;
+            subscription.next_charge += plan.period;
+            env.storage().persistent().set(&SubscriptionKey::Subscription(id), &subscription);
+            env.events().publish((Symbol::new(&env, "subscription_renewed"), subscription.subscriber, id), (received, subscription.next_charge));
+            return Ok(ChargeOutcome::Renewed);
+        }
+        let grace_ends = subscription.next_charge + plan.grace_period;
+        if now > grace_ends {
+            Self::cancel(&env, id, &subscription, Symbol::new(&env, "lapsed"));
+            return Ok(ChargeOutcome::Cancelled);
+        }
+        env.events().publish((Symbol::new(&env, "subscription_past_due"), subscription.subscriber, id), grace_ends);
+        Ok(ChargeOutcome::PastDue)
+    }
+
+    // Subscriber: Cancel; the period already paid is not refunded
+    pub fn unsubscribe(env: Env, id: u64) -> Result<(), SubscriptionError> {
+        let subscription = Self::subscription(env.clone(), id)?;
+        subscription.subscriber.require_auth();
+        Self::cancel(&env, id, &subscription, Symbol::new(&env, "unsubscribed"));
+        Ok(())
+    }
+
+    // View: Plan `plan_id`
+    pub fn plan(env: Env, plan_id: u64) -> Result<Plan, SubscriptionError> {
+        env.storage().persistent().get(&SubscriptionKey::Plan(plan_id)).ok_or(SubscriptionError::PlanNotFound)
+    }
+
+    // View: Subscription `id`; SubscriptionNotFound once cancelled
+    pub fn subscription(env: Env, id: u64) -> Result<Subscription, SubscriptionError> {
+        env.storage().persistent().get(&SubscriptionKey::Subscription(id)).ok_or(SubscriptionError::SubscriptionNotFound)
+    }
+}
+
+impl PiCoinSubscriptions {
+    // Helper: Drop a subscription, telling the merchant's backend why
+    fn cancel(env: &Env, id: u64, subscription: &Subscription, reason: Symbol) {
+        env.storage().persistent().remove(&SubscriptionKey::Subscription(id));
+        env.events().publish((Symbol::new(env, "subscription_cancelled"), subscription.subscriber.clone(), id), reason);
+    }
+
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap();
+        token::TokenClient::new(env, &pi_token)
+    }
+}
+
+mod test;
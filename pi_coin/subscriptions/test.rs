@@ -0,0 +1,144 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, vec, Address, Env, IntoVal, Symbol};
+use crate::{ChargeOutcome, PiCoinSubscriptions, PiCoinSubscriptionsClient, SubscriptionError};
+use pi_coin_testutils::{PiCoinSource, TokenBuilder};
+
+const MONTH: u64 = 30 * 86_400;
+const GRACE: u64 = 3 * 86_400;
+
+#[test]
+fn test_charges_renew_then_lapse_after_grace() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let merchant = Address::generate(&env);
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &250, &PiCoinSource::Mining);
+
+    let contract_id = env.register(PiCoinSubscriptions, (&pi.address,));
+    let client = PiCoinSubscriptionsClient::new(&env, &contract_id);
+    assert_eq!(client.try_create_plan(&merchant, &100, &MONTH, &MONTH), Err(Ok(SubscriptionError::InvalidPlan)));
+    let plan = client.create_plan(&merchant, &100, &MONTH, &GRACE);
+
+    // The first month is paid on subscribing; later months come out of the allowance
+    let sub = client.subscribe(&alice, &plan);
+    pi.approve(&alice, &contract_id, &1_000, &1_000_000);
+    assert_eq!(client.try_charge_due(&sub), Err(Ok(SubscriptionError::NotDue)));
+    env.ledger().set_timestamp(1_000 + MONTH);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::Renewed);
+    assert_eq!(client.subscription(&sub).next_charge, 1_000 + 2 * MONTH);
+    assert_eq!((pi.balance(&merchant), pi.balance(&alice)), (200, 50));
+
+    // Alice runs dry: past due through the grace period, topping up keeps her subscribed
+    env.ledger().set_timestamp(1_000 + 2 * MONTH);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::PastDue);
+    pi.mint(&alice, &50, &PiCoinSource::Mining);
+    env.ledger().set_timestamp(1_000 + 2 * MONTH + GRACE);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::Renewed);
+
+    // A month later nothing arrives before the grace period ends: cancelled
+    env.ledger().set_timestamp(1_000 + 3 * MONTH + GRACE);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::PastDue);
+    env.ledger().set_timestamp(1_000 + 3 * MONTH + GRACE + 1);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::Cancelled);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "subscription_cancelled"), alice.clone(), sub).into_val(&env),
+                Symbol::new(&env, "lapsed").into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.try_subscription(&sub), Err(Ok(SubscriptionError::SubscriptionNotFound)));
+    assert_eq!(pi.balance(&merchant), 300);
+
+    // Retired plans take no one new and end existing subscriptions at the next charge
+    pi.mint(&alice, &100, &PiCoinSource::Mining);
+    let sub = client.subscribe(&alice, &plan);
+    client.retire_plan(&plan);
+    assert_eq!(client.try_subscribe(&alice, &plan), Err(Ok(SubscriptionError::PlanRetired)));
+    env.ledger().set_timestamp(1_000 + 5 * MONTH);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::Cancelled);
+    assert_eq!(pi.balance(&merchant), 400);
+}
+
+#[test]
+fn test_payment_events_carry_what_the_merchant_received() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let merchant = Address::generate(&env);
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &1_000, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinSubscriptions, (&pi.address,));
+    let client = PiCoinSubscriptionsClient::new(&env, &contract_id);
+    let plan = client.create_plan(&merchant, &100, &MONTH, &GRACE);
+
+    // A 1% fee comes out of each 100 PI charge
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    let sub = client.subscribe(&alice, &plan);
+    pi.approve(&alice, &contract_id, &1_000, &1_000_000);
+    env.ledger().set_timestamp(1_000 + MONTH);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::Renewed);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "subscription_renewed"), alice.clone(), sub).into_val(&env),
+                (99i128, 1_000 + 2 * MONTH).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!((pi.balance(&merchant), pi.balance(&alice)), (198, 800));
+}
+
+#[test]
+fn test_rejects_unauthorized_unfunded_and_cancelled_subscriptions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let pi = TokenBuilder::new(&env).build();
+    let merchant = Address::generate(&env);
+    let alice = Address::generate(&env);
+    pi.mint(&alice, &150, &PiCoinSource::Mining);
+    let contract_id = env.register(PiCoinSubscriptions, (&pi.address,));
+    let client = PiCoinSubscriptionsClient::new(&env, &contract_id);
+    let plan = client.create_plan(&merchant, &100, &MONTH, &GRACE);
+    assert_eq!(client.try_subscribe(&alice, &99), Err(Ok(SubscriptionError::PlanNotFound)));
+
+    // Subscribing, retiring and unsubscribing each need the right signature
+    env.set_auths(&[]);
+    assert!(client.try_subscribe(&alice, &plan).is_err());
+    assert!(client.try_retire_plan(&plan).is_err());
+    env.mock_all_auths();
+    let sub = client.subscribe(&alice, &plan);
+    env.set_auths(&[]);
+    assert!(client.try_unsubscribe(&sub).is_err());
+    env.mock_all_auths();
+
+    // Without an allowance the charge fails even though alice still holds 50 PI
+    env.ledger().set_timestamp(1_000 + MONTH);
+    assert_eq!(client.charge_due(&sub), ChargeOutcome::PastDue);
+    assert_eq!(pi.balance(&merchant), 100);
+
+    // A subscriber who cannot cover the first period is not subscribed
+    let bob = Address::generate(&env);
+    assert!(client.try_subscribe(&bob, &plan).is_err());
+
+    // Once unsubscribed nothing more can be charged
+    client.unsubscribe(&sub);
+    assert_eq!(client.try_charge_due(&sub), Err(Ok(SubscriptionError::SubscriptionNotFound)));
+    assert_eq!(client.try_unsubscribe(&sub), Err(Ok(SubscriptionError::SubscriptionNotFound)));
+    assert_eq!(pi.balance(&alice), 50);
+}
@@ -0,0 +1,81 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, token, Address, Env, IntoVal, Symbol, log};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SwapError {
+    PriceBoundNotMet = 1,
+    InvalidAmount = 2,
+    SameAsset = 3,
+}
+
+#[contract]
+pub struct PiCoinSwap;
+
+#[contractimpl]
+impl PiCoinSwap {
+    // Constructor: atomic PI <-> SEP-41 swaps. Each leg passes through this contract, so a
+    // PI transfer fee is charged on the way in unless governance exempts it (`set_fee_exempt`).
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "Swap initialized: atomic PI trades against any SEP-41 token");
+    }
+
+    // Swap `pi_amount` PI from `pi_party` for `token_amount` of `token` from
+    // `token_party`, atomically. Each party signs only its own offer (what it gives
+    // and the least it accepts), so offers can be matched off-chain by anyone.
+    // Each party receives exactly its minimum; the rest of what it offered is refunded.
+    // An offer that arrives short of the minimum after a transfer fee fails the price bound.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        env: Env,
+        pi_party: Address,
+        token_party: Address,
+        token: Address,
+        pi_amount: i128,
+        min_token_for_pi: i128,
+        token_amount: i128,
+        min_pi_for_token: i128,
+    ) -> Result<(), SwapError> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(&env, "pi_token")).unwrap();
+        if token == pi_token {
+            return Err(SwapError::SameAsset);
+        }
+        if pi_amount <= 0 || token_amount <= 0 || min_token_for_pi <= 0 || min_pi_for_token <= 0 {
+            return Err(SwapError::InvalidAmount);
+        }
+        // Min-price bounds: each side must offer at least what the other demands
+        if token_amount < min_token_for_pi || pi_amount < min_pi_for_token {
+            return Err(SwapError::PriceBoundNotMet);
+        }
+
+        pi_party.require_auth_for_args((pi_token.clone(), token.clone(), pi_amount, min_token_for_pi).into_val(&env));
+        token_party.require_auth_for_args((token.clone(), pi_token.clone(), token_amount, min_pi_for_token).into_val(&env));
+
+        Self::move_token(&env, &pi_token, &pi_party, &token_party, pi_amount, min_pi_for_token)?;
+        Self::move_token(&env, &token, &token_party, &pi_party, token_amount, min_token_for_pi)?;
+        env.events().publish((Symbol::new(&env, "swap"), pi_party, token_party, token), (min_pi_for_token, min_token_for_pi));
+        Ok(())
+    }
+
+    // Helper: Pull the full offer into the contract (so the signature is not bound to the
+    // counterparty), pay `transfer_amount` on and refund the rest of what arrived
+    fn move_token(env: &Env, token: &Address, from: &Address, to: &Address, max_spend_amount: i128, transfer_amount: i128) -> Result<(), SwapError> {
+        let client = token::TokenClient::new(env, token);
+        let contract_address = env.current_contract_address();
+        let before = client.balance(&contract_address);
+        client.transfer(from, &contract_address, &max_spend_amount);
+        let received = client.balance(&contract_address) - before;
+        if received < transfer_amount {
+            return Err(SwapError::PriceBoundNotMet);
+        }
+        client.transfer(&contract_address, to, &transfer_amount);
+        if received > transfer_amount {
+            client.transfer(&contract_address, from, &(received - transfer_amount));
+        }
+        Ok(())
+    }
+}
+
+mod test;
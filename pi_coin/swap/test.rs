@@ -0,0 +1,118 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    token, Address, Env, IntoVal,
+};
+use crate::{PiCoinSwap, PiCoinSwapClient, SwapError};
+use pi_coin_testutils::{PiCoinContractClient, TokenBuilder};
+
+// PI from the real token and a Stellar Asset Contract standing in for USDC, minted to
+// `alice` and `bob` respectively
+fn tokens<'a>(env: &Env, alice: &Address, bob: &Address) -> (PiCoinContractClient<'a>, token::TokenClient<'a>) {
+    let pi = TokenBuilder::new(env).mint(alice, 1_000).build();
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    token::StellarAssetClient::new(env, &usdc).mint(bob, &5_000);
+    (pi, token::TokenClient::new(env, &usdc))
+}
+
+#[test]
+fn test_swap_is_atomic_and_bounded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let (pi_client, usdc_client) = tokens(&env, &alice, &bob);
+    let (pi, usdc) = (pi_client.address.clone(), usdc_client.address.clone());
+
+    let contract_id = env.register(PiCoinSwap, (&pi,));
+    let client = PiCoinSwapClient::new(&env, &contract_id);
+
+    // Bob asks for more PI than Alice offers: nothing moves
+    assert!(client.try_swap(&alice, &bob, &usdc, &100, &4_000, &5_000, &200).is_err());
+
+    client.swap(&alice, &bob, &usdc, &100, &4_000, &5_000, &90);
+    assert_eq!(
+        env.auths()[0],
+        (
+            alice.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    symbol_short!("swap"),
+                    (pi.clone(), usdc.clone(), 100_i128, 4_000_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        pi.clone(),
+                        symbol_short!("transfer"),
+                        (alice.clone(), contract_id.clone(), 100_i128).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }],
+            }
+        )
+    );
+
+    // Each side receives the other's minimum; the surplus is refunded
+    assert_eq!(pi_client.balance(&bob), 90);
+    assert_eq!(pi_client.balance(&alice), 910);
+    assert_eq!(usdc_client.balance(&alice), 4_000);
+    assert_eq!(usdc_client.balance(&bob), 1_000);
+}
+
+#[test]
+fn test_pi_leg_is_settled_from_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let (pi, usdc) = tokens(&env, &alice, &bob);
+    let contract_id = env.register(PiCoinSwap, (&pi.address,));
+    let client = PiCoinSwapClient::new(&env, &contract_id);
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+
+    // At a 1% fee Alice's 100 PI arrive as 99: enough for Bob's 90, not for a 100 minimum
+    assert!(matches!(
+        client.try_swap(&alice, &bob, &usdc.address, &100, &4_000, &5_000, &100),
+        Err(Ok(SwapError::PriceBoundNotMet))
+    ));
+    client.swap(&alice, &bob, &usdc.address, &100, &4_000, &5_000, &90);
+    assert_eq!(pi.balance(&bob), 90);
+    assert_eq!(pi.balance(&alice), 909);
+    assert_eq!(pi.balance(&contract_id), 0);
+    assert_eq!(usdc.balance(&alice), 4_000);
+
+    // Exempted by governance, the swap contract passes the full offer on
+    pi.set_fee_exempt(&contract_id, &true);
+    client.swap(&alice, &bob, &usdc.address, &100, &400, &500, &100);
+    assert_eq!(pi.balance(&bob), 190);
+}
+
+#[test]
+fn test_rejects_unsigned_empty_and_unfunded_offers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let (pi, usdc) = tokens(&env, &alice, &bob);
+    let contract_id = env.register(PiCoinSwap, (&pi.address,));
+    let client = PiCoinSwapClient::new(&env, &contract_id);
+
+    // Both minimums must be positive, and PI does not trade against itself
+    assert!(matches!(client.try_swap(&alice, &bob, &usdc.address, &100, &0, &5_000, &90), Err(Ok(SwapError::InvalidAmount))));
+    assert!(matches!(client.try_swap(&alice, &bob, &usdc.address, &100, &4_000, &5_000, &0), Err(Ok(SwapError::InvalidAmount))));
+    assert!(matches!(client.try_swap(&alice, &bob, &pi.address, &100, &90, &100, &90), Err(Ok(SwapError::SameAsset))));
+
+    // Neither party's offer moves without its signature, nor beyond its balance
+    env.set_auths(&[]);
+    assert!(client.try_swap(&alice, &bob, &usdc.address, &100, &4_000, &5_000, &90).is_err());
+    env.mock_all_auths();
+    assert!(client.try_swap(&alice, &bob, &usdc.address, &1_001, &4_000, &5_000, &90).is_err());
+    assert!(client.try_swap(&alice, &bob, &usdc.address, &100, &4_000, &5_001, &90).is_err());
+    assert_eq!((pi.balance(&alice), usdc.balance(&bob)), (1_000, 5_000));
+}
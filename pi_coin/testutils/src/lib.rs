@@ -0,0 +1,340 @@
+// Scenario harness for integrators: one call registers the token, oracle, governance,
+// treasury, rate model, lending market and savings vault in a test Env, wires them to each
+// other as the deploy CLI does, seeds balances and prices, and hands back clients for all
+// of them. Scenario helpers then move time and prices and run keeper-style cycles, so a
+// test starts from a live ecosystem instead of 80 lines of setup. Contract suites that
+// only need real PI build the token alone with `TokenBuilder`.
+//
+// Authorization is mocked throughout (`mock_all_auths`), so governance-only entry points
+// can be called directly on the clients instead of through proposals. Build the contract
+// wasms first (`stellar contract build` at the repository root); they are imported below.
+use core::cell::Cell;
+
+use ed25519_dalek::{Signer, SigningKey};
+pub use pi_coin::{PiCoinContract, PiCoinContractClient, PiCoinSource};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{Address, BytesN, Env, Symbol, Vec};
+
+pub mod oracle {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/pi_coin_oracle.wasm");
+}
+
+pub mod governance {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/pi_coin_governance.wasm");
+}
+
+pub mod treasury {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/pi_coin_treasury.wasm");
+}
+
+pub mod rate_model {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/pi_coin_rate_model.wasm");
+}
+
+pub mod lending {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/pi_coin_lending.wasm");
+}
+
+pub mod savings {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/pi_coin_savings.wasm");
+}
+
+pub const DAY: u64 = 24 * 60 * 60;
+// Seconds per ledger the time helpers assume
+pub const LEDGER_SECONDS: u64 = 5;
+// Oracle prices are in micro-USD per whole unit
+pub const PEG_PRICE: i128 = 314_159_000_000;
+pub const USDC_PRICE: i128 = 1_000_000;
+pub const XLM_PRICE: i128 = 100_000;
+// PiCoinLending's basis-point scale (a health factor of 1.0)
+const BPS: i128 = 10_000;
+
+// Knobs for `build`; the defaults give three funded users and a liquid lending market
+#[derive(Clone, Debug)]
+pub struct EcosystemBuilder {
+    pub users: u32,
+    pub pi_balance: i128, // PI minted to each user, the supplier and the liquidator
+    pub xlm_balance: i128, // XLM minted to each user for lending collateral
+    pub pi_price: i128,
+    pub xlm_price: i128,
+    pub lending_liquidity: i128, // PI the supplier lends into the market
+    pub savings_rate_bps: u32,
+}
+
+impl Default for EcosystemBuilder {
+    fn default() -> Self {
+        EcosystemBuilder {
+            users: 3,
+            pi_balance: 10_000_000_000,
+            xlm_balance: 1_000_000_000_000_000,
+            pi_price: PEG_PRICE,
+            xlm_price: XLM_PRICE,
+            lending_liquidity: 5_000_000_000,
+            savings_rate_bps: 400,
+        }
+    }
+}
+
+impl EcosystemBuilder {
+    pub fn users(mut self, users: u32) -> Self {
+        self.users = users;
+        self
+    }
+
+    pub fn pi_balance(mut self, pi_balance: i128) -> Self {
+        self.pi_balance = pi_balance;
+        self
+    }
+
+    pub fn xlm_balance(mut self, xlm_balance: i128) -> Self {
+        self.xlm_balance = xlm_balance;
+        self
+    }
+
+    pub fn pi_price(mut self, pi_price: i128) -> Self {
+        self.pi_price = pi_price;
+        self
+    }
+
+    pub fn xlm_price(mut self, xlm_price: i128) -> Self {
+        self.xlm_price = xlm_price;
+        self
+    }
+
+    pub fn lending_liquidity(mut self, lending_liquidity: i128) -> Self {
+        self.lending_liquidity = lending_liquidity;
+        self
+    }
+
+    pub fn savings_rate_bps(mut self, savings_rate_bps: u32) -> Self {
+        self.savings_rate_bps = savings_rate_bps;
+        self
+    }
+
+    // Register, wire and seed every contract
+    pub fn build<'a>(self) -> Ecosystem<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+        // Whole-ecosystem scenarios run far past the per-transaction budget
+        env.cost_estimate().budget().reset_unlimited();
+        let admin = Address::generate(&env);
+
+        // Classic assets: USDC backs the token, XLM is the lending market's collateral
+        let usdc = env.register_stellar_asset_contract_v2(admin.clone()).address();
+        let xlm = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+        // The token and treasury reference governance and vice versa, so their ids come first
+        let token_id = Address::generate(&env);
+        let treasury_id = Address::generate(&env);
+        let governance_id = env.register(governance::WASM, (&admin, &token_id, &treasury_id));
+        env.register_at(&treasury_id, treasury::WASM, (&governance_id, &token_id));
+        let oracle_id = env.register(oracle::WASM, (&admin, &governance_id));
+        env.register_at(&token_id, PiCoinContract, (&admin, &usdc, &oracle_id, &governance_id));
+        let curve = rate_model::RateCurve { base_bps: 200, slope1_bps: 400, slope2_bps: 6_000, kink_bps: 8_000 };
+        let rate_model_id = env.register(rate_model::WASM, (&governance_id, curve));
+        let lending_id = env.register(lending::WASM, (&governance_id, &token_id, &oracle_id, Symbol::new(&env, "PI"), &rate_model_id, 1_000u32));
+        let savings_id = env.register(savings::WASM, (&governance_id, &token_id, self.savings_rate_bps));
+
+        let mut eco = Ecosystem {
+            token: PiCoinContractClient::new(&env, &token_id),
+            oracle: oracle::Client::new(&env, &oracle_id),
+            governance: governance::Client::new(&env, &governance_id),
+            treasury: treasury::Client::new(&env, &treasury_id),
+            rate_model: rate_model::Client::new(&env, &rate_model_id),
+            lending: lending::Client::new(&env, &lending_id),
+            savings: savings::Client::new(&env, &savings_id),
+            usdc,
+            xlm,
+            users: Vec::new(&env),
+            supplier: Address::generate(&env),
+            liquidator: Address::generate(&env),
+            reporter: SigningKey::from_bytes(&[7; 32]),
+            nonce: Cell::new(0),
+            admin,
+            env,
+        };
+        for _ in 0..self.users {
+            eco.users.push_back(Address::generate(&eco.env));
+        }
+
+        // Wiring the deploy CLI and governance would do: fees to the treasury, a signed
+        // price reporter, XLM listed as collateral at 70% LTV / 80% threshold / 5% bonus
+        eco.token.set_fee_collector(&eco.treasury.address);
+        eco.oracle.set_reporter(&eco.reporter_key(), &true);
+        eco.set_price("PI", self.pi_price);
+        eco.set_price("USDC", USDC_PRICE);
+        eco.set_price("XLM", self.xlm_price);
+        eco.lending.set_collateral(&eco.xlm, &Symbol::new(&eco.env, "XLM"), &7_000, &8_000, &500);
+
+        // Balances: PI for everyone, XLM for the users, and liquidity in the market
+        let xlm_admin = StellarAssetClient::new(&eco.env, &eco.xlm);
+        for user in eco.users.iter() {
+            eco.token.mint(&user, &self.pi_balance, &PiCoinSource::Mining);
+            xlm_admin.mint(&user, &self.xlm_balance);
+        }
+        eco.token.mint(&eco.supplier, &self.pi_balance, &PiCoinSource::Rewards);
+        eco.token.mint(&eco.liquidator, &self.pi_balance, &PiCoinSource::P2P);
+        if self.lending_liquidity > 0 {
+            eco.lending.supply(&eco.supplier, &self.lending_liquidity);
+        }
+        eco
+    }
+}
+
+pub struct Ecosystem<'a> {
+    pub env: Env,
+    pub admin: Address, // Minting authority and oracle admin
+    pub token: PiCoinContractClient<'a>,
+    pub oracle: oracle::Client<'a>,
+    pub governance: governance::Client<'a>,
+    pub treasury: treasury::Client<'a>,
+    pub rate_model: rate_model::Client<'a>,
+    pub lending: lending::Client<'a>,
+    pub savings: savings::Client<'a>,
+    pub usdc: Address, // Token collateral (SAC)
+    pub xlm: Address, // Lending collateral (SAC)
+    pub users: Vec<Address>, // Funded with PI and XLM
+    pub supplier: Address, // Lent the market's initial liquidity
+    pub liquidator: Address, // Funded with PI to repay liquidated debt
+    reporter: SigningKey, // Whitelisted oracle reporter
+    nonce: Cell<u64>, // Reporter's last nonce
+}
+
+impl<'a> Ecosystem<'a> {
+    // The default ecosystem in one call
+    pub fn new() -> Self {
+        EcosystemBuilder::default().build()
+    }
+
+    pub fn builder() -> EcosystemBuilder {
+        EcosystemBuilder::default()
+    }
+
+    pub fn user(&self, index: u32) -> Address {
+        self.users.get(index).unwrap()
+    }
+
+    // Report `price` for `asset` ("PI", "XLM", "USDC") through a signed price package, the
+    // way a relayed reporter would. A package must be newer than the asset's last price, so
+    // the clock moves on a second when the asset was already priced at this timestamp.
+    pub fn set_price(&self, asset: &str, price: i128) {
+        let asset = Symbol::new(&self.env, asset);
+        let now = self.env.ledger().timestamp();
+        if self.oracle.last_update(&asset).is_some_and(|at| at >= now) {
+            self.env.ledger().with_mut(|li| li.timestamp += 1);
+        }
+        let timestamp = self.env.ledger().timestamp();
+        let nonce = self.nonce.get() + 1;
+        self.nonce.set(nonce);
+        let package = oracle::SignedPrice {
+            network_id: self.env.ledger().network_id(),
+            oracle: self.oracle.address.clone(),
+            asset: asset.clone(),
+            price,
+            timestamp,
+            nonce,
+        };
+        let message: std::vec::Vec<u8> = package.to_xdr(&self.env).iter().collect();
+        let signature = BytesN::from_array(&self.env, &self.reporter.sign(&message).to_bytes());
+        self.oracle.submit_signed_price(&self.reporter_key(), &asset, &price, &timestamp, &nonce, &signature);
+    }
+
+    // Drop the lending collateral's (XLM's) price by `pct` percent; returns the new price
+    pub fn crash_price(&self, pct: u32) -> i128 {
+        let price = self.oracle.query_price(&Symbol::new(&self.env, "XLM"));
+        let crashed = price * (100 - pct.min(100) as i128) / 100;
+        self.set_price("XLM", crashed.max(1));
+        crashed.max(1)
+    }
+
+    // Move the clock (and the ledger sequence, at LEDGER_SECONDS per ledger) `days` ahead.
+    // The reporter re-reports every price unchanged, as a live feed would, so lending
+    // does not reject them as stale.
+    pub fn advance_time(&self, days: u64) {
+        self.env.ledger().with_mut(|li| {
+            li.timestamp += days * DAY;
+            li.sequence_number += (days * DAY / LEDGER_SECONDS) as u32;
+        });
+        for asset in ["PI", "USDC", "XLM"] {
+            self.set_price(asset, self.oracle.query_price(&Symbol::new(&self.env, asset)));
+        }
+    }
+
+    // Lock `collateral` XLM of `borrower` in the lending market and borrow `amount` PI
+    pub fn open_position(&self, borrower: &Address, collateral: i128, amount: i128) {
+        self.lending.deposit_collateral(borrower, &self.xlm, &collateral);
+        self.lending.borrow(borrower, &amount);
+    }
+
+    // Liquidate every user whose health factor has fallen below 1.0, once each, at the
+    // close factor; returns the total XLM seized
+    pub fn run_liquidation_cycle(&self) -> i128 {
+        let mut seized = 0;
+        for user in self.users.iter() {
+            if self.lending.health_factor(&user) >= BPS {
+                continue;
+            }
+            let debt = self.lending.debt_of(&user);
+            seized += self.lending.liquidate(&self.liquidator, &user, &self.xlm, &debt);
+        }
+        seized
+    }
+
+    // Balances, for scenario assertions
+    pub fn pi_balance(&self, holder: &Address) -> i128 {
+        self.token.balance(holder)
+    }
+
+    pub fn xlm_balance(&self, holder: &Address) -> i128 {
+        TokenClient::new(&self.env, &self.xlm).balance(holder)
+    }
+
+    fn reporter_key(&self) -> BytesN<32> {
+        BytesN::from_array(&self.env, &self.reporter.verifying_key().to_bytes())
+    }
+}
+
+impl Default for Ecosystem<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The token alone, with generated admin, collateral, oracle and governance addresses,
+// seeded with the mints queued on the builder. Auths are left to the test.
+pub struct TokenBuilder {
+    env: Env,
+    mints: std::vec::Vec<(Address, i128, PiCoinSource)>,
+}
+
+impl TokenBuilder {
+    pub fn new(env: &Env) -> Self {
+        TokenBuilder { env: env.clone(), mints: std::vec::Vec::new() }
+    }
+
+    // Mint `amount` Mining-sourced PI to `holder`
+    pub fn mint(self, holder: &Address, amount: i128) -> Self {
+        self.mint_from(holder, amount, PiCoinSource::Mining)
+    }
+
+    // Mint `amount` PI from `source` to `holder`
+    pub fn mint_from(mut self, holder: &Address, amount: i128, source: PiCoinSource) -> Self {
+        self.mints.push((holder.clone(), amount, source));
+        self
+    }
+
+    // Register the token and run the mints
+    pub fn build<'a>(self) -> PiCoinContractClient<'a> {
+        let env = &self.env;
+        let token_id = env.register(PiCoinContract, (Address::generate(env), Address::generate(env), Address::generate(env), Address::generate(env)));
+        let token = PiCoinContractClient::new(env, &token_id);
+        for (holder, amount, source) in self.mints {
+            token.mint(&holder, &amount, &source);
+        }
+        token
+    }
+}
+
+mod test;
@@ -0,0 +1,34 @@
+#![cfg(test)]
+use crate::{Ecosystem, DAY};
+
+#[test]
+fn test_crash_scenario_liquidates_underwater_borrowers() {
+    let eco = Ecosystem::builder().users(2).build();
+    let (borrower, bystander) = (eco.user(0), eco.user(1));
+    assert_eq!(eco.lending.supplied_of(&eco.supplier), 5_000_000_000);
+
+    // 10M XLM at $0.10 is about 3.18 PI; borrowing 2 PI leaves a health factor near 1.27
+    eco.open_position(&borrower, 100_000_000_000_000, 20_000_000);
+    assert_eq!(eco.pi_balance(&borrower), 10_020_000_000);
+    assert!(eco.lending.health_factor(&borrower) > 12_000);
+
+    // A month of interest, then the collateral halves
+    let start = eco.env.ledger().timestamp();
+    eco.advance_time(30);
+    assert_eq!(eco.env.ledger().timestamp(), start + 30 * DAY);
+    assert!(eco.lending.debt_of(&borrower) > 20_000_000);
+    assert_eq!(eco.crash_price(50), 50_000);
+    assert!(eco.lending.health_factor(&borrower) < 10_000);
+
+    // Keepers liquidate the borrower only, at the close factor
+    let debt = eco.lending.debt_of(&borrower);
+    let seized = eco.run_liquidation_cycle();
+    assert!(seized > 0);
+    assert_eq!(eco.xlm_balance(&eco.liquidator), seized);
+    assert_eq!(eco.lending.collateral_of(&borrower, &eco.xlm), 100_000_000_000_000 - seized);
+    assert!(eco.lending.debt_of(&borrower) <= debt - debt / 2 + 1);
+    assert_eq!(eco.lending.debt_of(&bystander), 0);
+
+    // Still underwater: the next cycle takes what the remaining collateral covers
+    assert!(eco.run_liquidation_cycle() > 0);
+}
@@ -0,0 +1,214 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, BytesN, Env, Map, Symbol, Vec, log};
+
+// Storage layout version written by this wasm
+pub const CONTRACT_VERSION: u32 = 1;
+// Spending epoch: limits reset every 7 days
+pub const DEFAULT_EPOCH_LENGTH: u64 = 7 * 24 * 60 * 60;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TreasuryData {
+    pub governance: Address, // Only executed governance proposals may spend
+    pub assets: Vec<Address>, // Governance-listed assets, reported by treasury_report (PI first)
+    pub epoch_length: u64, // Seconds per spending epoch
+    pub epoch_limits: Map<Address, i128>, // Asset -> Max spend per epoch (no entry: uncapped)
+    pub epoch_spent: Map<Address, (u64, i128)>, // Asset -> (Epoch, amount spent in it)
+}
+
+// Per-asset line of treasury_report()
+#[contracttype]
+#[derive(Clone)]
+pub struct AssetReport {
+    pub asset: Address,
+    pub balance: i128,
+    pub epoch_limit: Option<i128>,
+    pub spent_this_epoch: i128,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TreasuryError {
+    Unauthorized = 1,
+    InvalidAmount = 2,
+    EpochLimitExceeded = 3,
+    InsufficientFunds = 4,
+    AssetNotListed = 5,
+}
+
+#[contract]
+pub struct PiCoinTreasury;
+
+#[contractimpl]
+impl PiCoinTreasury {
+    // Constructor: treasury for protocol revenue (stability, transfer and PSM fees),
+    // spendable only by governance
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address) {
+        let data = TreasuryData {
+            governance,
+            assets: Vec::from_array(&env, [pi_token]),
+            epoch_length: DEFAULT_EPOCH_LENGTH,
+            epoch_limits: Map::new(&env),
+            epoch_spent: Map::new(&env),
+        };
+        env.storage().instance().set(&Symbol::new(&env, "treasury_data"), &data);
+        env.storage().instance().set(&Symbol::new(&env, "version"), &CONTRACT_VERSION);
+        log!(&env, "Treasury initialized: protocol revenue under governance control");
+    }
+
+    // Pay fees in a listed asset into the treasury. `source` labels the revenue stream
+    // (e.g. "stability", "psm") in the emitted event. Fees credited directly, like PI
+    // transfer fees, need no call.
+    pub fn deposit(env: Env, from: Address, asset: Address, amount: i128, source: Symbol) -> Result<(), TreasuryError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(TreasuryError::InvalidAmount);
+        }
+        let data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        if !data.assets.contains(&asset) {
+            return Err(TreasuryError::AssetNotListed);
+        }
+        token::TokenClient::new(&env, &asset).transfer(&from, env.current_contract_address(), &amount);
+        env.events().publish((Symbol::new(&env, "deposit"), asset, source), amount);
+        Ok(())
+    }
+
+    // Governance-only: Release funds (Action::TransferTreasury of an executed proposal),
+    // within the asset's per-epoch spending limit
+    pub fn spend(env: Env, asset: Address, to: Address, amount: i128) -> Result<(), TreasuryError> {
+        let mut data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.governance.require_auth();
+        if amount <= 0 {
+            return Err(TreasuryError::InvalidAmount);
+        }
+
+        let epoch = env.ledger().timestamp() / data.epoch_length;
+        let spent = Self::spent_in(&data, &asset, epoch) + amount;
+        if let Some(limit) = data.epoch_limits.get(asset.clone()) {
+            if spent > limit {
+                return Err(TreasuryError::EpochLimitExceeded);
+            }
+        }
+        let client = token::TokenClient::new(&env, &asset);
+        if client.balance(&env.current_contract_address()) < amount {
+            return Err(TreasuryError::InsufficientFunds);
+        }
+        data.epoch_spent.set(asset.clone(), (epoch, spent));
+        env.storage().instance().set(&Symbol::new(&env, "treasury_data"), &data);
+
+        client.transfer(&env.current_contract_address(), &to, &amount);
+        env.events().publish((Symbol::new(&env, "spend"), asset, to), amount);
+        Ok(())
+    }
+
+    // Governance-only: Accept deposits of `asset` and report it (idempotent)
+    pub fn list_asset(env: Env, asset: Address) {
+        let mut data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.governance.require_auth();
+        if !data.assets.contains(&asset) {
+            data.assets.push_back(asset.clone());
+            env.storage().instance().set(&Symbol::new(&env, "treasury_data"), &data);
+            env.events().publish((Symbol::new(&env, "asset_listed"), asset), ());
+        }
+    }
+
+    // Governance-only: Cap spending of a listed `asset` per epoch; None removes the cap
+    pub fn set_epoch_limit(env: Env, asset: Address, limit: Option<i128>) -> Result<(), TreasuryError> {
+        let mut data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.governance.require_auth();
+        if !data.assets.contains(&asset) {
+            return Err(TreasuryError::AssetNotListed);
+        }
+        match limit {
+            Some(limit) if limit < 0 => return Err(TreasuryError::InvalidAmount),
+            Some(limit) => data.epoch_limits.set(asset.clone(), limit),
+            None => {
+                data.epoch_limits.remove(asset.clone());
+            }
+        }
+        env.storage().instance().set(&Symbol::new(&env, "treasury_data"), &data);
+        Ok(())
+    }
+
+    // Governance-only: Change the spending epoch length (seconds)
+    pub fn set_epoch_length(env: Env, epoch_length: u64) -> Result<(), TreasuryError> {
+        let mut data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.governance.require_auth();
+        if epoch_length == 0 {
+            return Err(TreasuryError::InvalidAmount);
+        }
+        data.epoch_length = epoch_length;
+        // Epoch numbers change meaning with the length, so spending counters restart
+        data.epoch_spent = Map::new(&env);
+        env.storage().instance().set(&Symbol::new(&env, "treasury_data"), &data);
+        Ok(())
+    }
+
+    // View: Balance, epoch limit and current-epoch spending for every tracked asset
+    pub fn treasury_report(env: Env) -> Vec<AssetReport> {
+        let data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        let epoch = env.ledger().timestamp() / data.epoch_length;
+        let mut report = Vec::new(&env);
+        for asset in data.assets.iter() {
+            report.push_back(AssetReport {
+                balance: token::TokenClient::new(&env, &asset).balance(&env.current_contract_address()),
+                epoch_limit: data.epoch_limits.get(asset.clone()),
+                spent_this_epoch: Self::spent_in(&data, &asset, epoch),
+                asset,
+            });
+        }
+        report
+    }
+
+    // Governance-only: Swap the treasury code (reached through an executed, timelocked proposal)
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), TreasuryError> {
+        let data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.governance.require_auth();
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        log!(&env, "Treasury wasm upgraded by governance");
+        Ok(())
+    }
+
+    // Migration hook run after an upgrade (idempotent, permissionless)
+    pub fn post_upgrade(env: Env) -> Result<u32, TreasuryError> {
+        let stored: u32 = env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1);
+        if stored < CONTRACT_VERSION {
+            env.storage().instance().set(&Symbol::new(&env, "version"), &CONTRACT_VERSION);
+            log!(&env, "Treasury storage migrated from version {} to {}", stored, CONTRACT_VERSION);
+        }
+        Ok(CONTRACT_VERSION)
+    }
+
+    // View: Whether the contract state has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&Symbol::new(&env, "treasury_data"))
+    }
+
+    // View: Storage layout version currently in effect
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&Symbol::new(&env, "version")).unwrap_or(1)
+    }
+
+    // View: Governance contract allowed to spend
+    pub fn governance(env: Env) -> Address {
+        let data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.governance
+    }
+
+    // View: PI token, the first asset tracked
+    pub fn pi_token(env: Env) -> Address {
+        let data: TreasuryData = env.storage().instance().get(&Symbol::new(&env, "treasury_data")).unwrap();
+        data.assets.get(0).unwrap()
+    }
+
+    // Helper: Amount of `asset` spent during `epoch`
+    fn spent_in(data: &TreasuryData, asset: &Address, epoch: u64) -> i128 {
+        match data.epoch_spent.get(asset.clone()) {
+            Some((spent_epoch, spent)) if spent_epoch == epoch => spent,
+            _ => 0,
+        }
+    }
+}
+
+mod test;
@@ -0,0 +1,112 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env, Symbol};
+use crate::{PiCoinTreasury, PiCoinTreasuryClient, TreasuryError, DEFAULT_EPOCH_LENGTH};
+use pi_coin_testutils::{PiCoinContractClient, PiCoinSource, TokenBuilder};
+
+// Treasury holding `balance` PI, minted to it as protocol revenue
+fn setup<'a>(env: &Env, balance: i128) -> (PiCoinTreasuryClient<'a>, PiCoinContractClient<'a>) {
+    let pi = TokenBuilder::new(env).build();
+    let contract_id = env.register(PiCoinTreasury, (Address::generate(env), &pi.address));
+    if balance > 0 {
+        pi.mint(&contract_id, &balance, &PiCoinSource::Rewards);
+    }
+    (PiCoinTreasuryClient::new(env, &contract_id), pi)
+}
+
+#[test]
+fn test_spend_within_epoch_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, pi) = setup(&env, 10_000);
+    let grantee = Address::generate(&env);
+
+    // Uncapped until governance sets a limit
+    client.spend(&pi.address, &grantee, &1_000);
+    assert_eq!(pi.balance(&grantee), 1_000);
+    assert_eq!(client.try_spend(&pi.address, &grantee, &0), Err(Ok(TreasuryError::InvalidAmount)));
+
+    // 2_000 per epoch, counting what was already spent in it
+    client.set_epoch_limit(&pi.address, &Some(2_000));
+    client.spend(&pi.address, &grantee, &1_000);
+    assert_eq!(client.try_spend(&pi.address, &grantee, &1), Err(Ok(TreasuryError::EpochLimitExceeded)));
+    let report = client.treasury_report().get(0).unwrap();
+    assert_eq!((report.balance, report.epoch_limit, report.spent_this_epoch), (8_000, Some(2_000), 2_000));
+
+    // The next epoch starts from zero
+    env.ledger().with_mut(|li| li.timestamp += DEFAULT_EPOCH_LENGTH);
+    client.spend(&pi.address, &grantee, &2_000);
+    assert_eq!(pi.balance(&grantee), 4_000);
+
+    // Nothing beyond the balance, whatever the limit
+    client.set_epoch_limit(&pi.address, &None);
+    assert_eq!(client.try_spend(&pi.address, &grantee, &6_001), Err(Ok(TreasuryError::InsufficientFunds)));
+    assert_eq!(client.try_set_epoch_limit(&pi.address, &Some(-1)), Err(Ok(TreasuryError::InvalidAmount)));
+}
+
+#[test]
+fn test_set_epoch_length_restarts_spending_counters() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+    let (client, pi) = setup(&env, 10_000);
+    let grantee = Address::generate(&env);
+    client.set_epoch_limit(&pi.address, &Some(1_000));
+    client.spend(&pi.address, &grantee, &1_000);
+
+    assert_eq!(client.try_set_epoch_length(&0), Err(Ok(TreasuryError::InvalidAmount)));
+    client.set_epoch_length(&3_600);
+    assert_eq!(client.treasury_report().get(0).unwrap().spent_this_epoch, 0);
+    client.spend(&pi.address, &grantee, &1_000);
+
+    // Hourly epochs: the limit is back an hour later, not a week
+    assert_eq!(client.try_spend(&pi.address, &grantee, &1), Err(Ok(TreasuryError::EpochLimitExceeded)));
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    client.spend(&pi.address, &grantee, &1_000);
+    assert_eq!(pi.balance(&grantee), 3_000);
+}
+
+#[test]
+fn test_deposits_take_only_listed_assets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, pi) = setup(&env, 0);
+    let psm = Address::generate(&env);
+    pi.mint(&psm, &500, &PiCoinSource::Mining);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    token::StellarAssetClient::new(&env, &usdc).mint(&psm, &500);
+
+    // PI is listed at deployment; anything else waits for governance
+    client.deposit(&psm, &pi.address, &500, &Symbol::new(&env, "psm"));
+    assert_eq!(client.try_deposit(&psm, &usdc, &500, &Symbol::new(&env, "psm")), Err(Ok(TreasuryError::AssetNotListed)));
+    assert_eq!(client.try_set_epoch_limit(&usdc, &Some(100)), Err(Ok(TreasuryError::AssetNotListed)));
+    assert_eq!(client.treasury_report().len(), 1);
+
+    client.list_asset(&usdc);
+    client.list_asset(&usdc);
+    client.deposit(&psm, &usdc, &500, &Symbol::new(&env, "psm"));
+    let report = client.treasury_report();
+    assert_eq!(report.len(), 2);
+    assert_eq!((report.get(1).unwrap().asset, report.get(1).unwrap().balance), (usdc, 500));
+    assert_eq!(client.try_deposit(&psm, &pi.address, &0, &Symbol::new(&env, "psm")), Err(Ok(TreasuryError::InvalidAmount)));
+}
+
+#[test]
+fn test_rejects_callers_without_governance_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, pi) = setup(&env, 10_000);
+    let grantee = Address::generate(&env);
+    let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    env.set_auths(&[]);
+    assert!(client.try_spend(&pi.address, &grantee, &1_000).is_err());
+    assert!(client.try_list_asset(&usdc).is_err());
+    assert!(client.try_set_epoch_limit(&pi.address, &Some(0)).is_err());
+    assert!(client.try_set_epoch_length(&3_600).is_err());
+    assert!(client.try_deposit(&grantee, &pi.address, &1, &Symbol::new(&env, "psm")).is_err());
+    env.mock_all_auths();
+
+    assert_eq!(pi.balance(&grantee), 0);
+    assert_eq!(client.treasury_report().len(), 1);
+    assert_eq!(client.treasury_report().get(0).unwrap().epoch_limit, None);
+}
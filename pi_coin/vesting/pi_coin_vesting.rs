@@ -0,0 +1,152 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, Symbol, log};
+
+#[contracttype]
+#[derive(Clone)]
+pub struct VestingData {
+    pub governance: Address, // Creates and revokes grants
+    pub pi_token: Address,
+    pub escrow: Address, // Funded account that approved this contract; grants pay out of it
+    pub next_grant_id: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grant {
+    pub beneficiary: Address,
+    pub total: i128,
+    pub start: u64, // Ledger timestamp vesting starts
+    pub cliff: u64, // Seconds after start before anything vests
+    pub duration: u64, // Seconds after start until fully vested
+    pub claimed: i128,
+    pub revocable: bool,
+    pub revoked_at: Option<u64>, // Vesting stops here; the unvested rest stays in escrow
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum VestingKey {
+    Grant(u64),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VestingError {
+    GrantNotFound = 1,
+    InvalidSchedule = 2,
+    NotRevocable = 3,
+    AlreadyRevoked = 4,
+    NothingToClaim = 5,
+}
+
+#[contract]
+pub struct PiCoinVesting;
+
+#[contractimpl]
+impl PiCoinVesting {
+    // Constructor: grants are paid from `escrow` (e.g. the treasury's funding account),
+    // which must approve this contract on the PI token for at least the granted total
+    pub fn __constructor(env: Env, governance: Address, pi_token: Address, escrow: Address) {
+        let data = VestingData { governance, pi_token, escrow, next_grant_id: 1 };
+        env.storage().instance().set(&Symbol::new(&env, "vesting_data"), &data);
+        log!(&env, "Vesting initialized: cliffs and linear release for PI grants");
+    }
+
+    // Governance-only: Grant `total` PI vesting linearly over `duration` seconds from
+    // `start`, with nothing claimable before `start + cliff`. Returns the grant id.
+    pub fn create_grant(
+        env: Env,
+        beneficiary: Address,
+        total: i128,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> Result<u64, VestingError> {
+        let mut data: VestingData = env.storage().instance().get(&Symbol::new(&env, "vesting_data")).unwrap();
+        data.governance.require_auth();
+        if total <= 0 || duration == 0 || cliff > duration {
+            return Err(VestingError::InvalidSchedule);
+        }
+        let id = data.next_grant_id;
+        data.next_grant_id += 1;
+        let grant = Grant { beneficiary: beneficiary.clone(), total, start, cliff, duration, claimed: 0, revocable, revoked_at: None };
+        env.storage().persistent().set(&VestingKey::Grant(id), &grant);
+        env.storage().instance().set(&Symbol::new(&env, "vesting_data"), &data);
+        env.events().publish((Symbol::new(&env, "grant_created"), beneficiary, id), total);
+        Ok(id)
+    }
+
+    // Beneficiary: Receive everything vested and not yet claimed. The grant counts what
+    // leaves the escrow; returns what reached the beneficiary, net of any PI transfer fee.
+    pub fn claim(env: Env, grant_id: u64) -> Result<i128, VestingError> {
+        let data: VestingData = env.storage().instance().get(&Symbol::new(&env, "vesting_data")).unwrap();
+        let mut grant = Self::load(&env, grant_id)?;
+        grant.beneficiary.require_auth();
+
+        let amount = Self::vested_amount(&grant, env.ledger().timestamp()) - grant.claimed;
+        if amount <= 0 {
+            return Err(VestingError::NothingToClaim);
+        }
+        grant.claimed += amount;
+        env.storage().persistent().set(&VestingKey::Grant(grant_id), &grant);
+        let pi = token::TokenClient::new(&env, &data.pi_token);
+        let before = pi.balance(&grant.beneficiary);
+        pi.transfer_from(&env.current_contract_address(), &data.escrow, &grant.beneficiary, &amount);
+        let received = pi.balance(&grant.beneficiary) - before;
+        env.events().publish((Symbol::new(&env, "vesting_claimed"), grant.beneficiary, grant_id), received);
+        Ok(received)
+    }
+
+    // Governance-only: Stop a revocable grant. What has vested so far stays claimable;
+    // the unvested remainder never leaves the escrow. Returns the unvested amount.
+    pub fn revoke(env: Env, grant_id: u64) -> Result<i128, VestingError> {
+        let data: VestingData = env.storage().instance().get(&Symbol::new(&env, "vesting_data")).unwrap();
+        data.governance.require_auth();
+        let mut grant = Self::load(&env, grant_id)?;
+        if !grant.revocable {
+            return Err(VestingError::NotRevocable);
+        }
+        if grant.revoked_at.is_some() {
+            return Err(VestingError::AlreadyRevoked);
+        }
+        let now = env.ledger().timestamp();
+        let unvested = grant.total - Self::vested_amount(&grant, now);
+        grant.revoked_at = Some(now);
+        env.storage().persistent().set(&VestingKey::Grant(grant_id), &grant);
+        env.events().publish((Symbol::new(&env, "grant_revoked"), grant.beneficiary, grant_id), unvested);
+        Ok(unvested)
+    }
+
+    // View: Grant details
+    pub fn grant(env: Env, grant_id: u64) -> Result<Grant, VestingError> {
+        Self::load(&env, grant_id)
+    }
+
+    // View: Amount `claim` would pay right now
+    pub fn claimable(env: Env, grant_id: u64) -> Result<i128, VestingError> {
+        let grant = Self::load(&env, grant_id)?;
+        Ok(Self::vested_amount(&grant, env.ledger().timestamp()) - grant.claimed)
+    }
+
+    // Helper: Amount vested at `now` - zero before the cliff, linear until `duration`,
+    // frozen at the revocation time
+    fn vested_amount(grant: &Grant, now: u64) -> i128 {
+        let now = grant.revoked_at.map_or(now, |revoked_at| now.min(revoked_at));
+        let elapsed = now.saturating_sub(grant.start);
+        if elapsed < grant.cliff {
+            0
+        } else if elapsed >= grant.duration {
+            grant.total
+        } else {
+            grant.total * elapsed as i128 / grant.duration as i128
+        }
+    }
+
+    fn load(env: &Env, grant_id: u64) -> Result<Grant, VestingError> {
+        env.storage().persistent().get(&VestingKey::Grant(grant_id)).ok_or(VestingError::GrantNotFound)
+    }
+}
+
+mod test;
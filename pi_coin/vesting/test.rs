@@ -0,0 +1,96 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env};
+use crate::{PiCoinVesting, PiCoinVestingClient, VestingError};
+use pi_coin_testutils::{PiCoinContractClient, TokenBuilder};
+
+// Vesting paid from an escrow holding `funded` PI, which approved `allowance` to it
+fn setup<'a>(env: &Env, funded: i128, allowance: i128) -> (PiCoinVestingClient<'a>, PiCoinContractClient<'a>, Address) {
+    let governance = Address::generate(env);
+    let escrow = Address::generate(env);
+    let pi = TokenBuilder::new(env).mint(&escrow, funded).build();
+    let contract_id = env.register(PiCoinVesting, (&governance, &pi.address, &escrow));
+    pi.approve(&escrow, &contract_id, &allowance, &1_000);
+    (PiCoinVestingClient::new(env, &contract_id), pi, escrow)
+}
+
+#[test]
+fn test_cliff_linear_release_and_revoke() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let (client, pi, escrow) = setup(&env, 10_000, 10_000);
+
+    // 1_000 PI over 1_000s with a 250s cliff
+    let beneficiary = Address::generate(&env);
+    let id = client.create_grant(&beneficiary, &1_000, &1_000, &250, &1_000, &true);
+
+    env.ledger().set_timestamp(1_200);
+    assert_eq!(client.claimable(&id), 0);
+    assert_eq!(client.try_claim(&id), Err(Ok(VestingError::NothingToClaim)));
+
+    env.ledger().set_timestamp(1_400);
+    assert_eq!(client.claim(&id), 400);
+    assert_eq!(pi.balance(&beneficiary), 400);
+
+    // Revoking at 600s freezes vesting; the unvested 400 stays in escrow
+    env.ledger().set_timestamp(1_600);
+    assert_eq!(client.revoke(&id), 400);
+    env.ledger().set_timestamp(5_000);
+    assert_eq!(client.claim(&id), 200);
+    assert_eq!(pi.balance(&beneficiary), 600);
+    assert_eq!(pi.balance(&escrow), 9_400);
+    assert_eq!(client.try_revoke(&id), Err(Ok(VestingError::AlreadyRevoked)));
+    assert_eq!(client.try_claim(&id), Err(Ok(VestingError::NothingToClaim)));
+}
+
+#[test]
+fn test_claim_reports_what_arrives_after_the_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let (client, pi, escrow) = setup(&env, 10_000, 10_000);
+    let beneficiary = Address::generate(&env);
+    let id = client.create_grant(&beneficiary, &1_000, &1_000, &0, &1_000, &false);
+
+    // At a 1% fee the escrow pays out the vested 500 and the beneficiary receives 495
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    env.ledger().set_timestamp(1_500);
+    assert_eq!(client.claim(&id), 495);
+    assert_eq!(pi.balance(&beneficiary), 495);
+    assert_eq!(pi.balance(&escrow), 9_500);
+    assert_eq!(client.grant(&id).claimed, 500);
+    assert_eq!(client.claimable(&id), 0);
+}
+
+#[test]
+fn test_rejects_unauthorized_underfunded_and_revoked_grants() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let (client, pi, _) = setup(&env, 10_000, 300);
+    let beneficiary = Address::generate(&env);
+
+    assert_eq!(client.try_create_grant(&beneficiary, &1_000, &1_000, &500, &400, &true), Err(Ok(VestingError::InvalidSchedule)));
+    let id = client.create_grant(&beneficiary, &1_000, &1_000, &0, &1_000, &true);
+    let fixed = client.create_grant(&beneficiary, &1_000, &1_000, &0, &1_000, &false);
+    assert_eq!(client.try_revoke(&fixed), Err(Ok(VestingError::NotRevocable)));
+    assert_eq!(client.try_grant(&99), Err(Ok(VestingError::GrantNotFound)));
+
+    // Neither grants nor claims go through without the right signature
+    env.set_auths(&[]);
+    assert!(client.try_create_grant(&beneficiary, &1_000, &1_000, &0, &1_000, &true).is_err());
+    env.ledger().set_timestamp(1_500);
+    assert!(client.try_claim(&id).is_err());
+    env.mock_all_auths();
+
+    // The escrow's 300 allowance cannot cover the vested 500, and the claim is undone whole
+    assert!(client.try_claim(&id).is_err());
+    assert_eq!(client.grant(&id).claimed, 0);
+    assert_eq!(pi.balance(&beneficiary), 0);
+
+    // After revocation only what vested before it can be claimed
+    client.revoke(&id);
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(client.claimable(&id), 500);
+}
@@ -0,0 +1,126 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Bytes, BytesN, Env, Symbol, Vec, log};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Voucher {
+    pub issuer: Address, // Funded the voucher; reclaims it once expired
+    pub amount: i128, // PI paid to whoever presents the code
+    pub expiration_ledger: u32, // Last ledger the code redeems
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum VoucherKey {
+    Voucher(BytesN<32>), // sha256 of the secret code
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VoucherError {
+    VoucherNotFound = 1,
+    VoucherExists = 2,
+    InvalidAmount = 3,
+    VoucherExpired = 4,
+    NotExpired = 5,
+    EmptyBatch = 6,
+    Underfunded = 7,
+}
+
+#[contract]
+pub struct PiCoinVouchers;
+
+#[contractimpl]
+impl PiCoinVouchers {
+    // Constructor: PI gift cards. An issuer locks PI against the sha256 of a secret code
+    // and hands the code out (printed, in a QR code, by message); whoever presents it
+    // first is paid. The code is public once a redemption is submitted, so wallets should
+    // redeem as soon as they learn it. Vouchers must be fully backed, so issuing fails
+    // while PI transfers into this contract are charged a fee; governance exempts it with
+    // the token's set_fee_exempt.
+    pub fn __constructor(env: Env, pi_token: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "pi_token"), &pi_token);
+        log!(&env, "Vouchers initialized: hash-locked PI gift cards");
+    }
+
+    // Issuer: Lock `amount` PI behind `code_hash` until `expiration_ledger`. Never reuse
+    // the hash of a code that has been redeemed - its secret is on-chain.
+    pub fn issue(env: Env, issuer: Address, code_hash: BytesN<32>, amount: i128, expiration_ledger: u32) -> Result<(), VoucherError> {
+        Self::issue_batch(env.clone(), issuer, Vec::from_array(&env, [(code_hash, amount)]), expiration_ledger)
+    }
+
+    // Issuer: Issue one voucher per (code hash, amount), all expiring at
+    // `expiration_ledger`, funded by a single transfer
+    pub fn issue_batch(env: Env, issuer: Address, vouchers: Vec<(BytesN<32>, i128)>, expiration_ledger: u32) -> Result<(), VoucherError> {
+        issuer.require_auth();
+        if vouchers.is_empty() {
+            return Err(VoucherError::EmptyBatch);
+        }
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(VoucherError::VoucherExpired);
+        }
+        let mut total: i128 = 0;
+        for (code_hash, amount) in vouchers.iter() {
+            if amount <= 0 {
+                return Err(VoucherError::InvalidAmount);
+            }
+            let key = VoucherKey::Voucher(code_hash.clone());
+            if env.storage().persistent().has(&key) {
+                return Err(VoucherError::VoucherExists);
+            }
+            env.storage().persistent().set(&key, &Voucher { issuer: issuer.clone(), amount, expiration_ledger });
+            env.events().publish((Symbol::new(&env, "voucher_issued"), issuer.clone(), code_hash), (amount, expiration_ledger));
+            total += amount;
+        }
+        let pi = Self::pi(&env);
+        let before = pi.balance(&env.current_contract_address());
+        pi.transfer(&issuer, env.current_contract_address(), &total);
+        if pi.balance(&env.current_contract_address()) - before < total {
+            return Err(VoucherError::Underfunded);
+        }
+        Ok(())
+    }
+
+    // Pay the voucher locked behind sha256(`code`) to `to`. Needs no signature: knowing
+    // the code is the authorization. Returns the amount paid.
+    pub fn redeem(env: Env, code: Bytes, to: Address) -> Result<i128, VoucherError> {
+        let code_hash: BytesN<32> = env.crypto().sha256(&code).to_bytes();
+        let voucher = Self::voucher(env.clone(), code_hash.clone())?;
+        if env.ledger().sequence() > voucher.expiration_ledger {
+            return Err(VoucherError::VoucherExpired);
+        }
+        env.storage().persistent().remove(&VoucherKey::Voucher(code_hash.clone()));
+        Self::pi(&env).transfer(&env.current_contract_address(), &to, &voucher.amount);
+        env.events().publish((Symbol::new(&env, "voucher_redeemed"), to, code_hash), voucher.amount);
+        Ok(voucher.amount)
+    }
+
+    // Issuer: Take back an expired, unredeemed voucher
+    pub fn reclaim(env: Env, code_hash: BytesN<32>) -> Result<i128, VoucherError> {
+        let voucher = Self::voucher(env.clone(), code_hash.clone())?;
+        voucher.issuer.require_auth();
+        if env.ledger().sequence() <= voucher.expiration_ledger {
+            return Err(VoucherError::NotExpired);
+        }
+        env.storage().persistent().remove(&VoucherKey::Voucher(code_hash.clone()));
+        Self::pi(&env).transfer(&env.current_contract_address(), &voucher.issuer, &voucher.amount);
+        env.events().publish((Symbol::new(&env, "voucher_reclaimed"), voucher.issuer, code_hash), voucher.amount);
+        Ok(voucher.amount)
+    }
+
+    // View: The outstanding voucher behind `code_hash`; VoucherNotFound once redeemed or
+    // reclaimed
+    pub fn voucher(env: Env, code_hash: BytesN<32>) -> Result<Voucher, VoucherError> {
+        env.storage().persistent().get(&VoucherKey::Voucher(code_hash)).ok_or(VoucherError::VoucherNotFound)
+    }
+}
+
+impl PiCoinVouchers {
+    fn pi(env: &Env) -> token::TokenClient<'_> {
+        let pi_token: Address = env.storage().instance().get(&Symbol::new(env, "pi_token")).unwrap();
+        token::TokenClient::new(env, &pi_token)
+    }
+}
+
+mod test;
@@ -0,0 +1,112 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, vec, Address, Bytes, BytesN, Env, IntoVal, Symbol};
+use crate::{PiCoinVouchers, PiCoinVouchersClient, VoucherError};
+use pi_coin_testutils::TokenBuilder;
+
+fn code_hash(env: &Env, code: &Bytes) -> BytesN<32> {
+    env.crypto().sha256(code).to_bytes()
+}
+
+#[test]
+fn test_batch_vouchers_redeem_or_reclaim() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let shop = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&shop, 1_000).build();
+    let contract_id = env.register(PiCoinVouchers, (&pi.address,));
+    let client = PiCoinVouchersClient::new(&env, &contract_id);
+    let gift = Bytes::from_slice(&env, b"GIFT-4821-XK");
+    let promo = Bytes::from_slice(&env, b"PROMO-0007");
+    assert_eq!(client.try_issue_batch(&shop, &vec![&env], &100), Err(Ok(VoucherError::EmptyBatch)));
+    assert_eq!(client.try_issue(&shop, &code_hash(&env, &gift), &0, &100), Err(Ok(VoucherError::InvalidAmount)));
+
+    // Two cards funded in one transfer; a code can only be issued once
+    client.issue_batch(&shop, &vec![&env, (code_hash(&env, &gift), 300i128), (code_hash(&env, &promo), 50i128)], &100);
+    assert_eq!(pi.balance(&contract_id), 350);
+    assert_eq!(client.try_issue(&shop, &code_hash(&env, &gift), &10, &100), Err(Ok(VoucherError::VoucherExists)));
+
+    // Whoever presents the code is paid, once
+    assert_eq!(client.redeem(&gift, &alice), 300);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, "voucher_redeemed"), alice.clone(), code_hash(&env, &gift)).into_val(&env),
+                300i128.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.try_redeem(&gift, &alice), Err(Ok(VoucherError::VoucherNotFound)));
+    assert_eq!(client.try_redeem(&Bytes::from_slice(&env, b"GUESS"), &alice), Err(Ok(VoucherError::VoucherNotFound)));
+
+    // Unredeemed cards go back to the issuer after expiry, not before
+    assert_eq!(client.try_reclaim(&code_hash(&env, &promo)), Err(Ok(VoucherError::NotExpired)));
+    env.ledger().set_sequence_number(101);
+    assert_eq!(client.try_redeem(&promo, &alice), Err(Ok(VoucherError::VoucherExpired)));
+    assert_eq!(client.reclaim(&code_hash(&env, &promo)), 50);
+    assert_eq!((pi.balance(&alice), pi.balance(&shop), pi.balance(&contract_id)), (300, 700, 0));
+}
+
+#[test]
+fn test_vouchers_are_issued_only_fully_backed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let shop = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&shop, 1_000).build();
+    let contract_id = env.register(PiCoinVouchers, (&pi.address,));
+    let client = PiCoinVouchersClient::new(&env, &contract_id);
+    let gift = Bytes::from_slice(&env, b"GIFT-4821-XK");
+
+    // A 1% fee would leave the card 3 PI short, so it is not issued
+    pi.set_fee_collector(&Address::generate(&env));
+    pi.set_fee(&100);
+    assert_eq!(client.try_issue(&shop, &code_hash(&env, &gift), &300, &100), Err(Ok(VoucherError::Underfunded)));
+    assert_eq!(pi.balance(&shop), 1_000);
+
+    // Once governance exempts the contract, cards are funded and paid in full
+    pi.set_fee_exempt(&contract_id, &true);
+    client.issue(&shop, &code_hash(&env, &gift), &300, &100);
+    assert_eq!(client.redeem(&gift, &alice), 300);
+    assert_eq!(pi.balance(&alice), 300);
+}
+
+#[test]
+fn test_rejects_unauthorized_unfunded_and_spent_vouchers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let shop = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let pi = TokenBuilder::new(&env).mint(&shop, 100).build();
+    let contract_id = env.register(PiCoinVouchers, (&pi.address,));
+    let client = PiCoinVouchersClient::new(&env, &contract_id);
+    let gift = Bytes::from_slice(&env, b"GIFT-4821-XK");
+
+    // Issuing needs the issuer's signature and the PI to back the card
+    env.set_auths(&[]);
+    assert!(client.try_issue(&shop, &code_hash(&env, &gift), &50, &100).is_err());
+    env.mock_all_auths();
+    assert!(client.try_issue(&shop, &code_hash(&env, &gift), &101, &100).is_err());
+    assert_eq!(client.try_voucher(&code_hash(&env, &gift)), Err(Ok(VoucherError::VoucherNotFound)));
+    client.issue(&shop, &code_hash(&env, &gift), &50, &0);
+
+    // Cards cannot be issued already expired
+    env.ledger().set_sequence_number(10);
+    let late = Bytes::from_slice(&env, b"LATE-0001");
+    assert_eq!(client.try_issue(&shop, &code_hash(&env, &late), &50, &9), Err(Ok(VoucherError::VoucherExpired)));
+
+    // Only the issuer reclaims, and a reclaimed card is spent
+    env.set_auths(&[]);
+    assert!(client.try_reclaim(&code_hash(&env, &gift)).is_err());
+    env.mock_all_auths();
+    assert_eq!(client.reclaim(&code_hash(&env, &gift)), 50);
+    assert_eq!(client.try_redeem(&gift, &alice), Err(Ok(VoucherError::VoucherNotFound)));
+    assert_eq!(client.try_reclaim(&code_hash(&env, &gift)), Err(Ok(VoucherError::VoucherNotFound)));
+    assert_eq!(pi.balance(&shop), 100);
+}
@@ -0,0 +1,423 @@
+#![no_std]
+use soroban_sdk::{
+    auth::Context, contract, contractimpl, contracterror, contracttype, crypto::Hash, Address, Bytes, BytesN, Env, Map, Symbol, TryFromVal, Val, Vec, log,
+};
+
+// Window the owner has to veto a recovery once enough guardians approved it
+pub const RECOVERY_DELAY: u64 = 3 * 86_400;
+
+// Session key spending windows
+pub const DAY: u64 = 86_400;
+
+// Longest clientDataJSON accepted (WebAuthn clients produce ~150-250 bytes)
+const MAX_CLIENT_DATA_LEN: usize = 1024;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct WalletData {
+    pub passkeys: Map<Bytes, BytesN<65>>, // Credential id -> uncompressed secp256r1 public key
+    pub guardians: Vec<Address>, // May jointly replace the passkeys
+    pub guardian_threshold: u32, // Guardian approvals a recovery needs
+}
+
+// Scope of a session key: PI it may send per day, contracts it may call or send PI to,
+// and the last ledger it is valid for
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionPolicy {
+    pub pi_token: Address,
+    pub daily_limit: i128,
+    pub allowed_contracts: Vec<Address>,
+    pub expires_ledger: u32,
+}
+
+// PI a session key has sent in the day starting at `day_start`
+#[contracttype]
+#[derive(Clone)]
+pub struct SessionSpend {
+    pub day_start: u64,
+    pub spent: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum WalletKey {
+    PendingRecovery,
+    Session(BytesN<32>), // ed25519 session public key -> SessionPolicy
+    SessionSpend(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRecovery {
+    pub credential_id: Bytes,
+    pub public_key: BytesN<65>,
+    pub approvals: Vec<Address>, // Guardians in favour
+    pub ready_at: Option<u64>, // Set once the threshold is reached
+}
+
+// WebAuthn assertion produced by a passkey over the transaction's signature payload
+#[contracttype]
+#[derive(Clone)]
+pub struct PasskeySignature {
+    pub credential_id: Bytes,
+    pub authenticator_data: Bytes,
+    pub client_data_json: Bytes, // Must carry the payload as its base64url "challenge"
+    pub signature: BytesN<64>, // r || s, low-S
+}
+
+// ed25519 signature of the payload by a session key
+#[contracttype]
+#[derive(Clone)]
+pub struct SessionSignature {
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum WalletSignature {
+    Passkey(PasskeySignature), // Full control
+    Session(SessionSignature), // Limited by the key's SessionPolicy
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum WalletError {
+    UnknownPasskey = 1,
+    ChallengeMismatch = 2,
+    LastPasskey = 3,
+    NoRecovery = 4,
+    RecoveryNotReady = 5,
+    UnknownSessionKey = 6,
+    SessionExpired = 7,
+    ContextNotAllowed = 8,
+    SpendLimitExceeded = 9,
+    NotGuardian = 10,
+    AlreadyApproved = 11,
+    InvalidGuardians = 12,
+    RecoveryMismatch = 13,
+}
+
+#[contract]
+pub struct PiCoinWallet;
+
+#[contractimpl]
+impl PiCoinWallet {
+    // Constructor: passkey smart wallet with one passkey and `threshold`-of-n recovery
+    // guardians
+    pub fn __constructor(
+        env: Env,
+        credential_id: Bytes,
+        public_key: BytesN<65>,
+        guardians: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), WalletError> {
+        Self::validate_guardians(&guardians, threshold)?;
+        let mut passkeys = Map::new(&env);
+        passkeys.set(credential_id, public_key);
+        let data = WalletData { passkeys, guardians, guardian_threshold: threshold };
+        env.storage().instance().set(&Symbol::new(&env, "wallet_data"), &data);
+        log!(&env, "Passkey wallet initialized: secp256r1 WebAuthn, global payments ready");
+        Ok(())
+    }
+
+    // Wallet-only: Register another passkey (e.g. a second device)
+    pub fn add_passkey(env: Env, credential_id: Bytes, public_key: BytesN<65>) {
+        env.current_contract_address().require_auth();
+        let mut data = Self::data(&env);
+        data.passkeys.set(credential_id.clone(), public_key);
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "passkey_added"),), credential_id);
+    }
+
+    // Wallet-only: Remove a passkey; the last one cannot be removed
+    pub fn remove_passkey(env: Env, credential_id: Bytes) -> Result<(), WalletError> {
+        env.current_contract_address().require_auth();
+        let mut data = Self::data(&env);
+        if !data.passkeys.contains_key(credential_id.clone()) {
+            return Err(WalletError::UnknownPasskey);
+        }
+        if data.passkeys.len() == 1 {
+            return Err(WalletError::LastPasskey);
+        }
+        data.passkeys.remove(credential_id.clone());
+        Self::save(&env, &data);
+        env.events().publish((Symbol::new(&env, "passkey_removed"),), credential_id);
+        Ok(())
+    }
+
+    // Wallet-only: Replace the guardian set; any pending recovery is dropped
+    pub fn set_guardians(env: Env, guardians: Vec<Address>, threshold: u32) -> Result<(), WalletError> {
+        env.current_contract_address().require_auth();
+        Self::validate_guardians(&guardians, threshold)?;
+        let mut data = Self::data(&env);
+        data.guardians = guardians.clone();
+        data.guardian_threshold = threshold;
+        Self::save(&env, &data);
+        env.storage().instance().remove(&WalletKey::PendingRecovery);
+        env.events().publish((Symbol::new(&env, "guardians_set"), threshold), guardians);
+        Ok(())
+    }
+
+    // Guardian-only: Propose replacing every passkey with a new one, replacing any
+    // pending proposal. The proposer's approval is counted.
+    pub fn propose_recovery(env: Env, guardian: Address, credential_id: Bytes, public_key: BytesN<65>) -> Result<(), WalletError> {
+        guardian.require_auth();
+        let data = Self::data(&env);
+        if !data.guardians.contains(&guardian) {
+            return Err(WalletError::NotGuardian);
+        }
+        let mut pending = PendingRecovery { credential_id, public_key, approvals: Vec::new(&env), ready_at: None };
+        Self::approve(&env, &data, &mut pending, guardian);
+        env.events().publish((Symbol::new(&env, "recovery_proposed"),), pending.credential_id.clone());
+        Ok(())
+    }
+
+    // Guardian-only: Approve the pending recovery of `credential_id` (named so an
+    // approval cannot land on a proposal swapped in underneath it). Reaching the
+    // threshold starts the RECOVERY_DELAY veto window.
+    pub fn approve_recovery(env: Env, guardian: Address, credential_id: Bytes) -> Result<(), WalletError> {
+        guardian.require_auth();
+        let data = Self::data(&env);
+        if !data.guardians.contains(&guardian) {
+            return Err(WalletError::NotGuardian);
+        }
+        let mut pending = Self::pending_recovery(env.clone()).ok_or(WalletError::NoRecovery)?;
+        if pending.credential_id != credential_id {
+            return Err(WalletError::RecoveryMismatch);
+        }
+        if pending.approvals.contains(&guardian) {
+            return Err(WalletError::AlreadyApproved);
+        }
+        Self::approve(&env, &data, &mut pending, guardian);
+        Ok(())
+    }
+
+    // Wallet-only: Veto a pending recovery
+    pub fn cancel_recovery(env: Env) {
+        env.current_contract_address().require_auth();
+        env.storage().instance().remove(&WalletKey::PendingRecovery);
+        env.events().publish((Symbol::new(&env, "recovery_cancelled"),), ());
+    }
+
+    // Finish an approved recovery once its veto window has passed (permissionless)
+    pub fn finish_recovery(env: Env) -> Result<(), WalletError> {
+        let pending = Self::pending_recovery(env.clone()).ok_or(WalletError::NoRecovery)?;
+        match pending.ready_at {
+            Some(ready_at) if env.ledger().timestamp() >= ready_at => {}
+            _ => return Err(WalletError::RecoveryNotReady),
+        }
+        let mut data = Self::data(&env);
+        data.passkeys = Map::new(&env);
+        data.passkeys.set(pending.credential_id.clone(), pending.public_key);
+        Self::save(&env, &data);
+        env.storage().instance().remove(&WalletKey::PendingRecovery);
+        env.events().publish((Symbol::new(&env, "recovered"),), pending.credential_id);
+        Ok(())
+    }
+
+    // Wallet-only: Authorize a session key (e.g. held by a dApp) under `policy`
+    pub fn add_session_key(env: Env, public_key: BytesN<32>, policy: SessionPolicy) {
+        env.current_contract_address().require_auth();
+        let key = WalletKey::Session(public_key.clone());
+        env.storage().persistent().set(&key, &policy);
+        env.storage().persistent().remove(&WalletKey::SessionSpend(public_key.clone()));
+        env.events().publish((Symbol::new(&env, "session_added"), public_key), policy);
+    }
+
+    // Wallet-only: Revoke a session key before its expiry
+    pub fn revoke_session_key(env: Env, public_key: BytesN<32>) {
+        env.current_contract_address().require_auth();
+        env.storage().persistent().remove(&WalletKey::Session(public_key.clone()));
+        env.storage().persistent().remove(&WalletKey::SessionSpend(public_key.clone()));
+        env.events().publish((Symbol::new(&env, "session_revoked"), public_key), ());
+    }
+
+    // View: Policy of a session key
+    pub fn session_policy(env: Env, public_key: BytesN<32>) -> Option<SessionPolicy> {
+        env.storage().persistent().get(&WalletKey::Session(public_key))
+    }
+
+    // Custom account check. Passkeys: a registered passkey's WebAuthn assertion whose
+    // challenge is the signature payload, verified with the secp256r1 host function.
+    // Session keys: an ed25519 signature, accepted only if every authorized call in the
+    // context tree fits the key's policy.
+    #[allow(non_snake_case)]
+    pub fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: WalletSignature,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), WalletError> {
+        match signature {
+            WalletSignature::Passkey(signature) => Self::check_passkey(&env, &signature_payload, signature),
+            WalletSignature::Session(signature) => Self::check_session(&env, &signature_payload, signature, &auth_contexts),
+        }
+    }
+
+    // Helper: Verify a passkey assertion over the payload
+    fn check_passkey(env: &Env, signature_payload: &Hash<32>, signature: PasskeySignature) -> Result<(), WalletError> {
+        let data = Self::data(env);
+        let public_key = data.passkeys.get(signature.credential_id).ok_or(WalletError::UnknownPasskey)?;
+        if !Self::has_challenge(&signature.client_data_json, &signature_payload.to_array()) {
+            return Err(WalletError::ChallengeMismatch);
+        }
+
+        // WebAuthn signs authenticatorData || sha256(clientDataJSON)
+        let mut signed = signature.authenticator_data.clone();
+        signed.extend_from_array(&env.crypto().sha256(&signature.client_data_json).to_array());
+        let digest = env.crypto().sha256(&signed);
+        env.crypto().secp256r1_verify(&public_key, &digest, &signature.signature);
+        Ok(())
+    }
+
+    // Helper: Verify a session key signature and enforce its policy. Only calls to allowed
+    // contracts and PI `transfer`s to allowed contracts pass; the PI sent counts toward
+    // the key's daily limit.
+    fn check_session(
+        env: &Env,
+        signature_payload: &Hash<32>,
+        signature: SessionSignature,
+        auth_contexts: &Vec<Context>,
+    ) -> Result<(), WalletError> {
+        let policy: SessionPolicy = env
+            .storage()
+            .persistent()
+            .get(&WalletKey::Session(signature.public_key.clone()))
+            .ok_or(WalletError::UnknownSessionKey)?;
+        if env.ledger().sequence() > policy.expires_ledger {
+            return Err(WalletError::SessionExpired);
+        }
+        env.crypto().ed25519_verify(&signature.public_key, &Bytes::from_array(env, &signature_payload.to_array()), &signature.signature);
+
+        let mut sending = 0;
+        for context in auth_contexts.iter() {
+            let Context::Contract(call) = context else { return Err(WalletError::ContextNotAllowed) };
+            if call.contract == policy.pi_token {
+                if call.fn_name != Symbol::new(env, "transfer") {
+                    return Err(WalletError::ContextNotAllowed);
+                }
+                let to: Address = Self::arg(env, &call.args, 1)?;
+                let amount: i128 = Self::arg(env, &call.args, 2)?;
+                if amount < 0 || !policy.allowed_contracts.contains(&to) {
+                    return Err(WalletError::ContextNotAllowed);
+                }
+                sending += amount;
+            } else if call.contract == env.current_contract_address() || !policy.allowed_contracts.contains(&call.contract) {
+                return Err(WalletError::ContextNotAllowed);
+            }
+        }
+
+        // Fixed daily windows by ledger timestamp
+        let spend_key = WalletKey::SessionSpend(signature.public_key);
+        let now = env.ledger().timestamp();
+        let mut spend: SessionSpend = env
+            .storage()
+            .persistent()
+            .get(&spend_key)
+            .filter(|spend: &SessionSpend| now < spend.day_start + DAY)
+            .unwrap_or(SessionSpend { day_start: now, spent: 0 });
+        if spend.spent + sending > policy.daily_limit {
+            return Err(WalletError::SpendLimitExceeded);
+        }
+        spend.spent += sending;
+        env.storage().persistent().set(&spend_key, &spend);
+        Ok(())
+    }
+
+    // Helper: Decode argument `index` of an authorized call
+    fn arg<T: TryFromVal<Env, Val>>(env: &Env, args: &Vec<Val>, index: u32) -> Result<T, WalletError> {
+        let value = args.get(index).ok_or(WalletError::ContextNotAllowed)?;
+        T::try_from_val(env, &value).map_err(|_| WalletError::ContextNotAllowed)
+    }
+
+    // View: Registered passkeys
+    pub fn passkeys(env: Env) -> Map<Bytes, BytesN<65>> {
+        Self::data(&env).passkeys
+    }
+
+    // View: Recovery in progress, if any
+    pub fn pending_recovery(env: Env) -> Option<PendingRecovery> {
+        env.storage().instance().get(&WalletKey::PendingRecovery)
+    }
+
+    // View: Guardians and the approvals a recovery needs
+    pub fn guardians(env: Env) -> (Vec<Address>, u32) {
+        let data = Self::data(&env);
+        (data.guardians, data.guardian_threshold)
+    }
+
+    // Helper: Record a guardian approval, starting the veto window at the threshold
+    fn approve(env: &Env, data: &WalletData, pending: &mut PendingRecovery, guardian: Address) {
+        pending.approvals.push_back(guardian.clone());
+        if pending.ready_at.is_none() && pending.approvals.len() >= data.guardian_threshold {
+            let ready_at = env.ledger().timestamp() + RECOVERY_DELAY;
+            pending.ready_at = Some(ready_at);
+            env.events().publish((Symbol::new(env, "recovery_approved"),), ready_at);
+        }
+        env.storage().instance().set(&WalletKey::PendingRecovery, pending);
+        env.events().publish((Symbol::new(env, "recovery_approval"), guardian), pending.credential_id.clone());
+    }
+
+    // Helper: 1 <= threshold <= guardians, no duplicates
+    fn validate_guardians(guardians: &Vec<Address>, threshold: u32) -> Result<(), WalletError> {
+        if threshold == 0 || threshold > guardians.len() {
+            return Err(WalletError::InvalidGuardians);
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardians.first_index_of(&guardian) != Some(i as u32) {
+                return Err(WalletError::InvalidGuardians);
+            }
+        }
+        Ok(())
+    }
+
+    // Helper: Stored wallet state
+    fn data(env: &Env) -> WalletData {
+        env.storage().instance().get(&Symbol::new(env, "wallet_data")).unwrap()
+    }
+
+    fn save(env: &Env, data: &WalletData) {
+        env.storage().instance().set(&Symbol::new(env, "wallet_data"), data);
+    }
+
+    // Helper: Whether clientDataJSON contains `"challenge":"<base64url(payload)>"`
+    fn has_challenge(client_data_json: &Bytes, payload: &[u8; 32]) -> bool {
+        let len = client_data_json.len() as usize;
+        if len > MAX_CLIENT_DATA_LEN {
+            return false;
+        }
+        let mut json = [0u8; MAX_CLIENT_DATA_LEN];
+        client_data_json.copy_into_slice(&mut json[..len]);
+
+        let mut expected = [0u8; 13 + 43 + 1];
+        expected[..13].copy_from_slice(b"\"challenge\":\"");
+        Self::base64url(payload, &mut expected[13..56]);
+        expected[56] = b'"';
+        json[..len].windows(expected.len()).any(|window| window == expected)
+    }
+
+    // Helper: Unpadded base64url of 32 bytes (43 characters)
+    fn base64url(input: &[u8; 32], out: &mut [u8]) {
+        const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let mut bits: u32 = 0;
+        let mut bit_count = 0;
+        let mut pos = 0;
+        for byte in input {
+            bits = (bits << 8) | *byte as u32;
+            bit_count += 8;
+            while bit_count >= 6 {
+                bit_count -= 6;
+                out[pos] = ALPHABET[((bits >> bit_count) & 63) as usize];
+                pos += 1;
+            }
+        }
+        if bit_count > 0 {
+            out[pos] = ALPHABET[((bits << (6 - bit_count)) & 63) as usize];
+        }
+    }
+}
+
+mod test;
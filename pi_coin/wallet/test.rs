@@ -0,0 +1,222 @@
+#![cfg(test)]
+// Passkeys and session keys are simulated with the p256, ed25519-dalek, sha2 and base64
+// crates (dev-dependencies)
+extern crate std;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+use ed25519_dalek::Signer;
+use soroban_sdk::{
+    auth::{Context, ContractContext},
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger, MockAuth, MockAuthInvoke},
+    vec, Address, Bytes, BytesN, Env, IntoVal, Symbol, Vec,
+};
+use crate::{
+    PasskeySignature, PiCoinWallet, PiCoinWalletClient, SessionPolicy, SessionSignature, WalletError, WalletSignature,
+};
+
+// A passkey: P-256 key pair with its credential id
+struct Passkey {
+    id: Bytes,
+    key: SigningKey,
+}
+
+impl Passkey {
+    fn new(env: &Env, seed: u8) -> Self {
+        Passkey { id: Bytes::from_array(env, &[seed; 16]), key: SigningKey::from_slice(&[seed; 32]).unwrap() }
+    }
+
+    fn public_key(&self, env: &Env) -> BytesN<65> {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        BytesN::from_array(env, point.as_bytes().try_into().unwrap())
+    }
+
+    // WebAuthn assertion over `payload`, as a browser would produce it
+    fn sign(&self, env: &Env, payload: &[u8; 32]) -> WalletSignature {
+        let client_data = std::format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://wallet.example"}}"#,
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let authenticator_data = [0x11u8; 37];
+        let mut signed = std::vec::Vec::from(authenticator_data);
+        signed.extend_from_slice(&Sha256::digest(client_data.as_bytes()));
+        let signature: Signature = self.key.sign_prehash(&Sha256::digest(&signed)).unwrap();
+        let signature = signature.normalize_s().unwrap_or(signature);
+        WalletSignature::Passkey(PasskeySignature {
+            credential_id: self.id.clone(),
+            authenticator_data: Bytes::from_array(env, &authenticator_data),
+            client_data_json: Bytes::from_slice(env, client_data.as_bytes()),
+            signature: BytesN::from_array(env, &signature.to_bytes().into()),
+        })
+    }
+}
+
+// Wallet owned by `passkey` with a single guardian
+fn register(env: &Env, passkey: &Passkey) -> Address {
+    env.register(PiCoinWallet, (&passkey.id, passkey.public_key(env), vec![env, Address::generate(env)], 1u32))
+}
+
+fn check_auth(env: &Env, wallet: &Address, payload: &[u8; 32], signature: WalletSignature) -> bool {
+    check_auth_for(env, wallet, payload, signature, vec![env])
+}
+
+fn check_auth_for(env: &Env, wallet: &Address, payload: &[u8; 32], signature: WalletSignature, contexts: Vec<Context>) -> bool {
+    let payload = BytesN::from_array(env, payload);
+    env.try_invoke_contract_check_auth::<WalletError>(wallet, &payload, signature.into_val(env), &contexts).is_ok()
+}
+
+fn session_sign(env: &Env, key: &ed25519_dalek::SigningKey, payload: &[u8; 32]) -> WalletSignature {
+    WalletSignature::Session(SessionSignature {
+        public_key: BytesN::from_array(env, &key.verifying_key().to_bytes()),
+        signature: BytesN::from_array(env, &key.sign(payload).to_bytes()),
+    })
+}
+
+fn call(env: &Env, contract: &Address, function: &str, args: Vec<soroban_sdk::Val>) -> Context {
+    Context::Contract(ContractContext { contract: contract.clone(), fn_name: Symbol::new(env, function), args })
+}
+
+#[test]
+fn test_passkey_signatures_authorize_the_wallet() {
+    let env = Env::default();
+    let phone = Passkey::new(&env, 1);
+    let laptop = Passkey::new(&env, 2);
+    let wallet = register(&env, &phone);
+    let payload = [9u8; 32];
+
+    assert!(check_auth(&env, &wallet, &payload, phone.sign(&env, &payload)));
+    // Signed for another payload, or by an unregistered passkey
+    let mut replayed = phone.sign(&env, &[8u8; 32]);
+    assert!(!check_auth(&env, &wallet, &payload, replayed.clone()));
+    replayed = laptop.sign(&env, &payload);
+    assert!(!check_auth(&env, &wallet, &payload, replayed));
+
+    env.mock_all_auths();
+    PiCoinWalletClient::new(&env, &wallet).add_passkey(&laptop.id, &laptop.public_key(&env));
+    assert!(check_auth(&env, &wallet, &payload, laptop.sign(&env, &payload)));
+}
+
+#[test]
+fn test_guardians_recover_after_veto_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let lost = Passkey::new(&env, 1);
+    let fresh = Passkey::new(&env, 3);
+    let (g1, g2, g3) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    let guardians = vec![&env, g1.clone(), g2.clone(), g3.clone()];
+    let wallet = env.register(PiCoinWallet, (&lost.id, lost.public_key(&env), guardians, 2u32));
+    let client = PiCoinWalletClient::new(&env, &wallet);
+
+    assert!(client.try_remove_passkey(&lost.id).is_err());
+    assert!(client.try_propose_recovery(&Address::generate(&env), &fresh.id, &fresh.public_key(&env)).is_err());
+
+    // One approval is not enough to start the veto window
+    client.propose_recovery(&g1, &fresh.id, &fresh.public_key(&env));
+    assert_eq!(client.pending_recovery().unwrap().ready_at, None);
+    assert!(client.try_approve_recovery(&g1, &fresh.id).is_err());
+    assert!(client.try_approve_recovery(&g2, &lost.id).is_err());
+    assert!(client.try_finish_recovery().is_err());
+
+    // The owner vetoes within the window
+    client.approve_recovery(&g2, &fresh.id);
+    assert_eq!(client.pending_recovery().unwrap().ready_at, Some(crate::RECOVERY_DELAY));
+    client.cancel_recovery();
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            wallet.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((wallet.clone(), Symbol::new(&env, "cancel_recovery"), vec![&env])),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(client.pending_recovery(), None);
+
+    // Unvetoed, the recovery completes once the window has passed
+    client.propose_recovery(&g3, &fresh.id, &fresh.public_key(&env));
+    client.approve_recovery(&g1, &fresh.id);
+    env.ledger().with_mut(|li| li.timestamp += crate::RECOVERY_DELAY - 1);
+    assert!(client.try_finish_recovery().is_err());
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    client.finish_recovery();
+    let passkeys = client.passkeys();
+    assert_eq!(passkeys.len(), 1);
+    assert_eq!(passkeys.get(fresh.id.clone()), Some(fresh.public_key(&env)));
+}
+
+#[test]
+fn test_recovery_calls_need_the_right_signers() {
+    let env = Env::default();
+    let owner = Passkey::new(&env, 1);
+    let fresh = Passkey::new(&env, 3);
+    let guardian = Address::generate(&env);
+    let wallet = env.register(PiCoinWallet, (&owner.id, owner.public_key(&env), vec![&env, guardian.clone()], 1u32));
+    let client = PiCoinWalletClient::new(&env, &wallet);
+    let propose_args = (&guardian, &fresh.id, fresh.public_key(&env)).into_val(&env);
+
+    // Without the guardian's signature the proposal is rejected
+    assert!(client.try_propose_recovery(&guardian, &fresh.id, &fresh.public_key(&env)).is_err());
+    client
+        .mock_auths(&[MockAuth {
+            address: &guardian,
+            invoke: &MockAuthInvoke { contract: &wallet, fn_name: "propose_recovery", args: propose_args, sub_invokes: &[] },
+        }])
+        .propose_recovery(&guardian, &fresh.id, &fresh.public_key(&env));
+
+    // Guardians cannot veto or manage guardians - those need the wallet's own auth
+    let cancel = MockAuthInvoke { contract: &wallet, fn_name: "cancel_recovery", args: vec![&env], sub_invokes: &[] };
+    assert!(client.mock_auths(&[MockAuth { address: &guardian, invoke: &cancel }]).try_cancel_recovery().is_err());
+    let new_guardians = vec![&env, guardian.clone()];
+    let set_args = (&new_guardians, 1u32).into_val(&env);
+    let set = MockAuthInvoke { contract: &wallet, fn_name: "set_guardians", args: set_args, sub_invokes: &[] };
+    assert!(client.mock_auths(&[MockAuth { address: &guardian, invoke: &set }]).try_set_guardians(&new_guardians, &1).is_err());
+    assert!(client.pending_recovery().is_some());
+
+    // Invalid guardian sets are refused
+    env.mock_all_auths();
+    assert!(client.try_set_guardians(&vec![&env, guardian.clone(), guardian.clone()], &1).is_err());
+    assert!(client.try_set_guardians(&new_guardians, &2).is_err());
+    client.set_guardians(&new_guardians, &1);
+    assert_eq!(client.pending_recovery(), None);
+}
+
+#[test]
+fn test_session_key_limited_to_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Passkey::new(&env, 1);
+    let wallet = register(&env, &owner);
+    let client = PiCoinWalletClient::new(&env, &wallet);
+    let pi = Address::generate(&env);
+    let shop = Address::generate(&env);
+    let elsewhere = Address::generate(&env);
+
+    let session = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+    let session_pk = BytesN::from_array(&env, &session.verifying_key().to_bytes());
+    let policy = SessionPolicy { pi_token: pi.clone(), daily_limit: 100, allowed_contracts: vec![&env, shop.clone()], expires_ledger: 50 };
+    client.add_session_key(&session_pk, &policy);
+
+    let payload = [4u8; 32];
+    let pay = |to: &Address, amount: i128| call(&env, &pi, "transfer", vec![&env, wallet.into_val(&env), to.into_val(&env), amount.into_val(&env)]);
+
+    // Pays the shop and calls it, within the daily limit
+    assert!(check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, call(&env, &shop, "buy", vec![&env]), pay(&shop, 60)]));
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, pay(&shop, 60)]));
+    // Other recipients, other PI functions, other contracts and the wallet itself are out of scope
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, pay(&elsewhere, 1)]));
+    let approve = call(&env, &pi, "approve", vec![&env, wallet.into_val(&env), shop.into_val(&env), 1i128.into_val(&env), 100u32.into_val(&env)]);
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, approve]));
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, call(&env, &elsewhere, "buy", vec![&env])]));
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, call(&env, &wallet, "add_passkey", vec![&env])]));
+
+    // The limit resets daily; the key stops working at expiry or revocation
+    env.ledger().with_mut(|li| li.timestamp += crate::DAY);
+    assert!(check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env, pay(&shop, 100)]));
+    env.ledger().with_mut(|li| li.sequence_number = 51);
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env]));
+    env.ledger().with_mut(|li| li.sequence_number = 10);
+    client.revoke_session_key(&session_pk);
+    assert!(!check_auth_for(&env, &wallet, &payload, session_sign(&env, &session, &payload), vec![&env]));
+}
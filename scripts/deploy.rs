@@ -1,69 +0,0 @@
-#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
-use pi_coin_contract::PiCoinContract; // Assume imports from lib
-use pi_coin_oracle::PiCoinOracle;
-use pi_coin_governance::PiCoinGovernance;
-use pi_coin_utils::PiCoinUtils;
-use crate::PiCoinSource;
-
-#[contract]
-pub struct PiCoinDeployer;
-
-#[contractimpl]
-impl PiCoinDeployer {
-    // Hyper-tech deployment: Deploy all contracts with source validation
-    pub fn deploy_pi_coin_ecosystem(env: Env, admin: Address, source: PiCoinSource) -> Result<(Address, Address, Address), ()> {
-        // Validate source for deployment (only valid sources allowed)
-        if source == PiCoinSource::Invalid {
-            log!(&env, "Deployment rejected: Invalid source - No access to Pi Ecosystem");
-            return Err(());
-        }
-
-        // Deploy main contract
-        let collateral = Address::random(&env);
-        let oracle_addr = Address::random(&env);
-        let governance_addr = Address::random(&env);
-        PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle_addr.clone(), governance_addr.clone())?;
-        let main_contract = env.current_contract_address();
-
-        // Deploy oracle with AI setup
-        PiCoinOracle::initialize(env.clone(), admin.clone())?;
-        let oracle_contract = env.current_contract_address();
-
-        // Deploy governance with quantum threshold
-        PiCoinGovernance::initialize(env.clone(), admin.clone(), 5)?; // 5 sig threshold
-        let governance_contract = env.current_contract_address();
-
-        // Hyper-tech: Setup provenance and quantum keys
-        let quantum_key = PiCoinUtils::generate_quantum_key(env.clone(), Bytes::from_slice(&env, b"PiCoin-Deploy-Key"));
-        log!(&env, "Quantum key set for deployment: {:?} - Ultimate security", quantum_key);
-
-        // Simulate global recognition post-deploy
-        PiCoinUtils::simulate_dex_bridge(env.clone(), 1_000_000, Symbol::new(&env, "StellarDEX"), source)?;
-
-        log!(&env, "Pi Coin ecosystem deployed from {} source: Main {}, Oracle {}, Governance {} - Worldwide payment ready", source, main_contract, oracle_contract, governance_contract);
-        Ok((main_contract, oracle_contract, governance_contract))
-    }
-
-    // Utility: Test deploy in simulation
-    pub fn simulate_deploy(env: Env) -> Result<(), ()> {
-        let admin = Address::random(&env);
-        let source = PiCoinSource::Mining; // Valid for test
-        let result = Self::deploy_pi_coin_ecosystem(env, admin, source);
-        assert!(result.is_ok());
-        log!(&env, "Deployment simulation successful - Hyper-tech ecosystem live");
-        Ok(())
-    }
-}
-
-// Main function for CLI execution (integrate with stellar-cli)
-fn main() {
-    let env = Env::default();
-    // In real: Parse args from stellar-cli, e.g., --network testnet --source Mining
-    let admin = Address::from_str(&env, "GA..."); // Replace with real admin
-    let source = PiCoinSource::Mining;
-    match PiCoinDeployer::deploy_pi_coin_ecosystem(env, admin, source) {
-        Ok((main, oracle, gov)) => println!("Deployed: Main {}, Oracle {}, Gov {}", main, oracle, gov),
-        Err(_) => println!("Deployment failed - Invalid source"),
-    }
-}